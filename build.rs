@@ -0,0 +1,71 @@
+use std::{
+    env::var,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/*
+    Embeds information about the build, which is included in the
+    output of `rokit --version --format json`:
+
+    - The commit hash, if building from a git checkout
+    - The build date, respecting `SOURCE_DATE_EPOCH` for reproducible builds
+    - The target triple that Rokit was compiled for
+*/
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    if let Some(commit) = git_commit_hash() {
+        println!("cargo:rustc-env=ROKIT_BUILD_COMMIT={commit}");
+    }
+
+    let epoch_secs = var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|secs| secs.trim().parse::<u64>().ok())
+        .or_else(|| {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+            Some(now.as_secs())
+        });
+    if let Some(secs) = epoch_secs {
+        println!("cargo:rustc-env=ROKIT_BUILD_DATE={}", format_date(secs));
+    }
+
+    let target = var("TARGET").unwrap_or_default();
+    println!("cargo:rustc-env=ROKIT_BUILD_TARGET={target}");
+}
+
+fn git_commit_hash() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()?;
+    let hash = String::from_utf8(output.stdout).ok()?;
+    let hash = hash.trim();
+    (output.status.success() && !hash.is_empty()).then(|| hash.to_string())
+}
+
+/*
+    Formats seconds since the unix epoch as a `YYYY-MM-DD` date, in UTC.
+
+    Uses the days-to-civil algorithm by Howard Hinnant, since the build
+    script should not need any dependencies just for formatting a date.
+*/
+fn format_date(epoch_secs: u64) -> String {
+    let days = i64::try_from(epoch_secs / 86_400).unwrap_or_default() + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
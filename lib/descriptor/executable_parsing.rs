@@ -1,5 +1,7 @@
+#[cfg(feature = "runtime")]
+use std::time::Instant;
+
 use goblin::{elf::Elf, mach::Mach, pe::header::Header as PEHeader};
-use tokio::time::Instant;
 
 use super::{Arch, OS};
 
@@ -15,6 +17,7 @@ pub fn parse_executable(binary_contents: impl AsRef<[u8]>) -> Option<(OS, Arch)>
 
     // Prioritize parsing the current OS's format first since
     // it's the most likely to be correct, for most use cases.
+    #[cfg(feature = "runtime")]
     let start = Instant::now();
 
     #[cfg(target_os = "linux")]
@@ -38,6 +41,16 @@ pub fn parse_executable(binary_contents: impl AsRef<[u8]>) -> Option<(OS, Arch)>
             .or_else(|| parse_mach(binary_contents))
     };
 
+    // NOTE: Any other target, such as wasm32, is never the host of a parsed
+    // executable, so there is no preferred format and the order is arbitrary
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    let opt = {
+        parse_elf(binary_contents)
+            .or_else(|| parse_mach(binary_contents))
+            .or_else(|| parse_pe(binary_contents))
+    };
+
+    #[cfg(feature = "runtime")]
     tracing::trace!(
         elapsed = ?start.elapsed(),
         "parsed executable format"
@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use serde::Deserialize;
 
-use crate::tool::{ToolAlias, ToolSpec};
+use crate::{
+    manifests::TapsManifest,
+    tool::{ToolAlias, ToolSpec},
+};
 
 use super::Manifest;
 
@@ -35,7 +38,7 @@ impl Manifest for AftmanManifest {
         toml::from_str(contents).ok()
     }
 
-    fn into_tools(self) -> HashMap<ToolAlias, ToolSpec> {
+    fn into_tools(self, _taps: &TapsManifest) -> HashMap<ToolAlias, ToolSpec> {
         self.tools
             .into_iter()
             .map(|(alias, tool)| (alias.0, tool.0))
@@ -3,7 +3,10 @@ use std::{collections::HashMap, str::FromStr};
 use semver::Version;
 use toml_edit::{DocumentMut, InlineTable, Table};
 
-use crate::tool::{ToolAlias, ToolId, ToolSpec};
+use crate::{
+    manifests::TapsManifest,
+    tool::{ToolAlias, ToolId, ToolSpec},
+};
 
 use super::Manifest;
 
@@ -35,7 +38,7 @@ impl Manifest for ForemanManifest {
             .ok()
     }
 
-    fn into_tools(self) -> HashMap<ToolAlias, ToolSpec> {
+    fn into_tools(self, _taps: &TapsManifest) -> HashMap<ToolAlias, ToolSpec> {
         let mut tools = HashMap::new();
         if let Some(map) = self.document.get("tools").and_then(|t| t.as_table()) {
             for (alias, tool_def) in map {
@@ -4,15 +4,16 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use futures::{stream::FuturesOrdered, StreamExt};
-use tokio::fs::read_to_string;
-
 use crate::{
-    manifests::RokitManifest,
+    executor::fs::read_to_string,
+    manifests::{RokitManifest, TapsManifest, ToolOptions},
     storage::Home,
     system::current_dir,
     tool::{ToolAlias, ToolSpec},
+    warnings::{AliasCollision, RokitWarning},
 };
+use futures::{stream::FuturesOrdered, StreamExt};
+use semver::Version;
 
 use self::{aftman::AftmanManifest, foreman::ForemanManifest};
 
@@ -34,7 +35,10 @@ where
     fn home_dir() -> &'static str;
     fn manifest_file_name() -> &'static str;
     fn parse_manifest(contents: &str) -> Option<Self>;
-    fn into_tools(self) -> HashMap<ToolAlias, ToolSpec>;
+    fn into_tools(self, taps: &TapsManifest) -> HashMap<ToolAlias, ToolSpec>;
+    fn tool_options(&self) -> HashMap<ToolAlias, ToolOptions> {
+        HashMap::new()
+    }
 }
 
 /**
@@ -47,6 +51,7 @@ pub struct DiscoveredManifest {
     _kind: ManifestKind,
     pub path: PathBuf,
     pub tools: HashMap<ToolAlias, ToolSpec>,
+    pub options: HashMap<ToolAlias, ToolOptions>,
 }
 
 fn search_paths(cwd: &Path, rokit_only: bool, skip_home: bool) -> Vec<(ManifestKind, PathBuf)> {
@@ -98,15 +103,48 @@ fn search_paths(cwd: &Path, rokit_only: bool, skip_home: bool) -> Vec<(ManifestK
     ordered_paths
 }
 
+type ToolsAndOptions = (
+    HashMap<ToolAlias, ToolSpec>,
+    HashMap<ToolAlias, ToolOptions>,
+);
+
+fn parse_tools_and_options<M: Manifest>(
+    contents: &str,
+    taps: &TapsManifest,
+) -> Option<ToolsAndOptions> {
+    let manifest = M::parse_manifest(contents)?;
+    let options = manifest.tool_options();
+    Some((manifest.into_tools(taps), options))
+}
+
 /**
     Discovers all known tool manifests in the current directory and its ancestors, as well as home directories.
 
     This is a slow operation that reads many potential files - use `discover_tool_spec` if possible.
+
+    Short tool specifications in Rokit manifests are resolved using the given taps.
 */
-pub async fn discover_all_manifests(rokit_only: bool, skip_home: bool) -> Vec<DiscoveredManifest> {
+pub async fn discover_all_manifests(
+    taps: &TapsManifest,
+    rokit_only: bool,
+    skip_home: bool,
+) -> Vec<DiscoveredManifest> {
     let cwd = current_dir().await;
+    discover_all_manifests_in(&cwd, taps, rokit_only, skip_home).await
+}
 
-    let found_manifest_contents = search_paths(&cwd, rokit_only, skip_home)
+/**
+    Discovers all known tool manifests in the given directory and its ancestors, as well as home directories.
+
+    See [`discover_all_manifests`] for more information.
+*/
+pub async fn discover_all_manifests_in(
+    dir: impl AsRef<Path>,
+    taps: &TapsManifest,
+    rokit_only: bool,
+    skip_home: bool,
+) -> Vec<DiscoveredManifest> {
+    let found_manifest_contents = search_paths(dir.as_ref(), rokit_only, skip_home)
         .into_iter()
         .map(|(kind, path)| async move {
             let contents = read_to_string(&path).await.ok()?;
@@ -122,45 +160,138 @@ pub async fn discover_all_manifests(rokit_only: bool, skip_home: bool) -> Vec<Di
     found_manifest_contents
         .into_iter()
         .filter_map(|(kind, path, contents)| {
-            let tools = match kind {
-                ManifestKind::Rokit => RokitManifest::parse_manifest(&contents)?.into_tools(),
-                ManifestKind::Aftman => AftmanManifest::parse_manifest(&contents)?.into_tools(),
-                ManifestKind::Foreman => ForemanManifest::parse_manifest(&contents)?.into_tools(),
+            let (tools, options) = match kind {
+                ManifestKind::Rokit => parse_tools_and_options::<RokitManifest>(&contents, taps)?,
+                ManifestKind::Aftman => parse_tools_and_options::<AftmanManifest>(&contents, taps)?,
+                ManifestKind::Foreman => {
+                    parse_tools_and_options::<ForemanManifest>(&contents, taps)?
+                }
             };
             Some(DiscoveredManifest {
                 _kind: kind,
                 path,
                 tools,
+                options,
             })
         })
         .collect()
 }
 
+/**
+    Finds tool aliases that are defined with different specifications in several
+    of the given manifests, which must be ordered from nearest to furthest away,
+    as returned by [`discover_all_manifests`].
+
+    The nearest definition of an alias is the one that is used, and any other
+    definitions are shadowed - a warning is returned for each shadowed definition,
+    unless the nearest definition has been marked as intentional using `shadow = true`.
+*/
+#[must_use]
+pub fn find_alias_collisions(manifests: &[DiscoveredManifest]) -> Vec<RokitWarning> {
+    let mut nearest = HashMap::<&ToolAlias, (&DiscoveredManifest, &ToolSpec)>::new();
+    let mut warnings = Vec::new();
+
+    for manifest in manifests {
+        // NOTE: Sort aliases to make the order of warnings deterministic
+        let mut tools = manifest.tools.iter().collect::<Vec<_>>();
+        tools.sort();
+
+        for (alias, spec) in tools {
+            let Some((used, used_spec)) = nearest.get(alias) else {
+                nearest.insert(alias, (manifest, spec));
+                continue;
+            };
+            let is_intended = used.options.get(alias).is_some_and(|o| o.shadow);
+            if used_spec != &spec && !is_intended {
+                warnings.push(RokitWarning::AliasCollision(Box::new(AliasCollision {
+                    alias: alias.clone(),
+                    spec: (*used_spec).clone(),
+                    path: used.path.clone(),
+                    shadowed_spec: spec.clone(),
+                    shadowed_path: manifest.path.clone(),
+                })));
+            }
+        }
+    }
+
+    warnings
+}
+
 /**
     Discovers a tool spec by searching for manifests in the current directory and its ancestors.
 
     This is a fast operation that reads only the necessary files.
+
+    Short tool specifications in Rokit manifests are resolved using the given taps.
 */
 pub async fn discover_tool_spec(
+    taps: &TapsManifest,
     alias: &ToolAlias,
     rokit_only: bool,
     skip_home: bool,
 ) -> Option<ToolSpec> {
     let cwd = current_dir().await;
+    let (_, spec, _) = discover_tool_spec_in(&cwd, taps, alias, rokit_only, skip_home).await?;
+    Some(spec)
+}
+
+/**
+    Discovers a tool spec by searching for manifests in the given directory and its
+    ancestors, and returns it together with the path to the manifest it was found in,
+    and the options for the tool, which are only ever set in Rokit manifests.
 
-    for (kind, path) in search_paths(&cwd, rokit_only, skip_home) {
+    See [`discover_tool_spec`] for more information.
+*/
+async fn discover_tool_spec_in(
+    dir: &Path,
+    taps: &TapsManifest,
+    alias: &ToolAlias,
+    rokit_only: bool,
+    skip_home: bool,
+) -> Option<(PathBuf, ToolSpec, ToolOptions)> {
+    for (kind, path) in search_paths(dir, rokit_only, skip_home) {
         let Ok(contents) = read_to_string(&path).await else {
             continue;
         };
 
+        let mut options = ToolOptions::default();
         let tools = match kind {
-            ManifestKind::Rokit => RokitManifest::parse_manifest(&contents)?.into_tools(),
-            ManifestKind::Aftman => AftmanManifest::parse_manifest(&contents)?.into_tools(),
-            ManifestKind::Foreman => ForemanManifest::parse_manifest(&contents)?.into_tools(),
+            ManifestKind::Rokit => {
+                let manifest = RokitManifest::parse_manifest(&contents)?;
+                options = manifest.get_tool_options(alias);
+                manifest.into_tools(taps)
+            }
+            ManifestKind::Aftman => AftmanManifest::parse_manifest(&contents)?.into_tools(taps),
+            ManifestKind::Foreman => ForemanManifest::parse_manifest(&contents)?.into_tools(taps),
         };
 
         if let Some(spec) = tools.get(alias) {
-            return Some(spec.clone());
+            return Some((path, spec.clone(), options));
+        }
+    }
+
+    None
+}
+
+/**
+    Discovers the version of Rokit that the current project is pinned to, if any.
+
+    This searches Rokit manifests in the current directory and its ancestors,
+    but never the home directory, and returns the first pinned version found
+    together with the path to the manifest that pinned it.
+*/
+pub async fn discover_rokit_version() -> Option<(PathBuf, Version)> {
+    let cwd = current_dir().await;
+
+    for (_, path) in search_paths(&cwd, true, true) {
+        let Ok(contents) = read_to_string(&path).await else {
+            continue;
+        };
+        let Some(manifest) = RokitManifest::parse_manifest(&contents) else {
+            continue;
+        };
+        if let Some(version) = manifest.rokit_version() {
+            return Some((path, version));
         }
     }
 
@@ -177,12 +308,15 @@ pub async fn discover_tool_spec(
 */
 pub async fn discover_non_rokit_tool(home: &Home, alias: &ToolAlias) -> Option<PathBuf> {
     let cwd = current_dir().await;
+    discover_non_rokit_tool_in(home, &cwd, alias)
+}
 
+fn discover_non_rokit_tool_in(home: &Home, dir: &Path, alias: &ToolAlias) -> Option<PathBuf> {
     let binary_name = alias.name().to_string();
-    let home_path = home.path().to_owned();
+    let home_path = home.root_path().to_owned();
     let search_paths = var_os("PATH")?;
 
-    let mut found_tool_paths = which::which_in_all(binary_name, Some(search_paths), &cwd)
+    let mut found_tool_paths = which::which_in_all(binary_name, Some(search_paths), dir)
         .ok()
         .into_iter()
         .flatten()
@@ -190,3 +324,124 @@ pub async fn discover_non_rokit_tool(home: &Home, alias: &ToolAlias) -> Option<P
 
     found_tool_paths.next()
 }
+
+/**
+    A tool that would run for an alias, as resolved by [`resolve_alias_for_dir`].
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedTool {
+    /**
+        The path to the binary that would run.
+    */
+    pub path: PathBuf,
+    /**
+        The tool specification from the manifest, or `None`
+        if the tool is not managed by Rokit, and would run from
+        the system `PATH` instead.
+    */
+    pub spec: Option<ToolSpec>,
+    /**
+        The version of the tool, or `None` if the tool is not managed by Rokit.
+    */
+    pub version: Option<Version>,
+    /**
+        The path to the manifest that the tool was found in,
+        or `None` if the tool is not managed by Rokit.
+    */
+    pub manifest_source: Option<PathBuf>,
+    /**
+        If the binary has been installed. Tools that are not
+        managed by Rokit are always considered to be installed.
+    */
+    pub is_installed: bool,
+}
+
+/**
+    Resolves which tool would run for the given alias, if it were run in the
+    given directory, without running or installing anything - meant for editors
+    and other integrations that want to show which binary and version is used.
+
+    Tools are resolved the exact same way as when running a tool through a
+    link, meaning the nearest manifest with the alias is used, and otherwise
+    a tool with the same name in the system `PATH`, if one exists.
+
+    Returns `None` if no tool would run for the alias.
+*/
+pub async fn resolve_alias_for_dir(
+    home: &Home,
+    dir: impl AsRef<Path>,
+    alias: &ToolAlias,
+) -> Option<ResolvedTool> {
+    let dir = dir.as_ref();
+    let taps = home.taps().await.unwrap_or_default();
+
+    if let Some((manifest_path, spec, options)) =
+        discover_tool_spec_in(dir, &taps, alias, false, false).await
+    {
+        Some(ResolvedTool {
+            path: home
+                .tool_storage()
+                .entrypoint_path(&spec, options.entrypoint.as_deref()),
+            version: Some(spec.version().clone()),
+            manifest_source: Some(manifest_path),
+            is_installed: home.tool_cache().is_installed(&spec),
+            spec: Some(spec),
+        })
+    } else {
+        let path = discover_non_rokit_tool_in(home, dir, alias)?;
+        Some(ResolvedTool {
+            path,
+            spec: None,
+            version: None,
+            manifest_source: None,
+            is_installed: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(path: &str, tools: &[(&str, &str)], shadow: bool) -> DiscoveredManifest {
+        let tools = tools
+            .iter()
+            .map(|(alias, spec)| (alias.parse().unwrap(), spec.parse().unwrap()))
+            .collect::<HashMap<ToolAlias, ToolSpec>>();
+        let options = tools
+            .keys()
+            .map(|alias| {
+                let options = ToolOptions {
+                    shadow,
+                    ..ToolOptions::default()
+                };
+                (alias.clone(), options)
+            })
+            .collect();
+        DiscoveredManifest {
+            _kind: ManifestKind::Rokit,
+            path: PathBuf::from(path),
+            tools,
+            options,
+        }
+    }
+
+    #[test]
+    fn alias_collisions() {
+        let project = manifest("project", &[("rojo", "rojo-rbx/rojo@7.4.4")], false);
+        let same = manifest("same", &[("rojo", "rojo-rbx/rojo@7.4.4")], false);
+        let global = manifest("global", &[("rojo", "rojo-rbx/rojo@7.3.0")], false);
+
+        assert!(find_alias_collisions(&[project.clone(), same]).is_empty());
+
+        let warnings = find_alias_collisions(&[project, global.clone()]);
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            &warnings[0],
+            RokitWarning::AliasCollision(collision) if collision.shadowed_path == global.path
+        ));
+
+        let intended = manifest("project", &[("rojo", "rojo-rbx/rojo@7.4.4")], true);
+        assert!(find_alias_collisions(&[intended, global]).is_empty());
+    }
+}
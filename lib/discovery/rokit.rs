@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::{
-    manifests::RokitManifest,
+    manifests::{RokitManifest, TapsManifest, ToolOptions},
     tool::{ToolAlias, ToolSpec},
 };
 
@@ -23,7 +23,11 @@ impl Manifest for RokitManifest {
         contents.parse().ok()
     }
 
-    fn into_tools(self) -> HashMap<ToolAlias, ToolSpec> {
-        self.tool_specs().into_iter().collect()
+    fn into_tools(self, taps: &TapsManifest) -> HashMap<ToolAlias, ToolSpec> {
+        self.tool_specs_with_taps(taps).into_iter().collect()
+    }
+
+    fn tool_options(&self) -> HashMap<ToolAlias, ToolOptions> {
+        RokitManifest::tool_options(self).into_iter().collect()
     }
 }
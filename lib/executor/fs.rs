@@ -0,0 +1,135 @@
+use std::{
+    fs::{self, Metadata, Permissions},
+    io::{Error as IoError, Result as IoResult, Write},
+    path::{Path, PathBuf},
+    vec::IntoIter,
+};
+
+use super::spawn_blocking;
+
+/*
+    Async versions of the filesystem functions used by Rokit, which run the
+    standard library functions using the current executor - the same way
+    that `tokio::fs` does, but without depending on a tokio runtime.
+
+    Functions have the same names and signatures as those in `tokio::fs`.
+*/
+
+async fn asyncify<F, T>(f: F) -> IoResult<T>
+where
+    F: FnOnce() -> IoResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    spawn_blocking(f).await.map_err(IoError::other)?
+}
+
+pub(crate) async fn read(path: impl AsRef<Path>) -> IoResult<Vec<u8>> {
+    let path = path.as_ref().to_path_buf();
+    asyncify(move || fs::read(path)).await
+}
+
+pub(crate) async fn read_to_string(path: impl AsRef<Path>) -> IoResult<String> {
+    let path = path.as_ref().to_path_buf();
+    asyncify(move || fs::read_to_string(path)).await
+}
+
+pub(crate) async fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> IoResult<()> {
+    let path = path.as_ref().to_path_buf();
+    let contents = contents.as_ref().to_vec();
+    asyncify(move || fs::write(path, contents)).await
+}
+
+pub(crate) async fn create_dir_all(path: impl AsRef<Path>) -> IoResult<()> {
+    let path = path.as_ref().to_path_buf();
+    asyncify(move || fs::create_dir_all(path)).await
+}
+
+pub(crate) async fn remove_file(path: impl AsRef<Path>) -> IoResult<()> {
+    let path = path.as_ref().to_path_buf();
+    asyncify(move || fs::remove_file(path)).await
+}
+
+pub(crate) async fn remove_dir_all(path: impl AsRef<Path>) -> IoResult<()> {
+    let path = path.as_ref().to_path_buf();
+    asyncify(move || fs::remove_dir_all(path)).await
+}
+
+pub(crate) async fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> IoResult<()> {
+    let from = from.as_ref().to_path_buf();
+    let to = to.as_ref().to_path_buf();
+    asyncify(move || fs::rename(from, to)).await
+}
+
+pub(crate) async fn metadata(path: impl AsRef<Path>) -> IoResult<Metadata> {
+    let path = path.as_ref().to_path_buf();
+    asyncify(move || fs::metadata(path)).await
+}
+
+pub(crate) async fn try_exists(path: impl AsRef<Path>) -> IoResult<bool> {
+    let path = path.as_ref().to_path_buf();
+    asyncify(move || path.try_exists()).await
+}
+
+#[cfg(unix)]
+pub(crate) async fn set_permissions(path: impl AsRef<Path>, perm: Permissions) -> IoResult<()> {
+    let path = path.as_ref().to_path_buf();
+    asyncify(move || fs::set_permissions(path, perm)).await
+}
+
+/*
+    Appends the given contents to the file at the given path,
+    creating the file if it does not already exist.
+*/
+pub(crate) async fn append(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> IoResult<()> {
+    let path = path.as_ref().to_path_buf();
+    let contents = contents.as_ref().to_vec();
+    asyncify(move || {
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(&contents)
+    })
+    .await
+}
+
+/*
+    Reads all entries of the directory at the given path at once,
+    which may then be iterated using `ReadDir::next_entry`.
+*/
+pub(crate) async fn read_dir(path: impl AsRef<Path>) -> IoResult<ReadDir> {
+    let path = path.as_ref().to_path_buf();
+    let entries = asyncify(move || fs::read_dir(path)?.collect::<IoResult<Vec<_>>>()).await?;
+    Ok(ReadDir {
+        entries: entries.into_iter(),
+    })
+}
+
+#[derive(Debug)]
+pub(crate) struct ReadDir {
+    entries: IntoIter<fs::DirEntry>,
+}
+
+impl ReadDir {
+    #[allow(clippy::unused_async)]
+    pub(crate) async fn next_entry(&mut self) -> IoResult<Option<DirEntry>> {
+        Ok(self.entries.next().map(|inner| DirEntry { inner }))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DirEntry {
+    inner: fs::DirEntry,
+}
+
+impl DirEntry {
+    pub(crate) fn path(&self) -> PathBuf {
+        self.inner.path()
+    }
+
+    // NOTE: Symlinks are never followed, same as for `tokio::fs::DirEntry`
+    pub(crate) async fn metadata(&self) -> IoResult<Metadata> {
+        let path = self.inner.path();
+        asyncify(move || fs::symlink_metadata(path)).await
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    fmt,
+    thread::{sleep as sleep_thread, spawn},
+    time::Duration,
+};
+
+use futures::{channel::oneshot, future::BoxFuture, FutureExt};
+use once_cell::sync::OnceCell;
+use thiserror::Error;
+
+pub(crate) mod fs;
+pub(crate) mod process;
+
+static EXECUTOR: OnceCell<Box<dyn Executor>> = OnceCell::new();
+
+/**
+    Error returned when a blocking task panicked, or was
+    cancelled by its executor before it could complete.
+*/
+#[derive(Debug, Clone, Copy, Error)]
+#[error("blocking task panicked or was cancelled")]
+pub struct TaskError;
+
+/**
+    An executor for the blocking work and timers needed by Rokit.
+
+    Rokit does not depend on a specific async runtime, other than for the
+    `tokio` feature - blocking work, such as filesystem operations, and
+    timers are all run using the current executor, which by default is:
+
+    - [`TokioExecutor`], when the `tokio` feature is enabled, and used
+      from within a tokio runtime - this is the case for the Rokit CLI.
+    - [`ThreadExecutor`] otherwise, which works with any async runtime.
+
+    Embedders using another runtime, such as `async-std` or a custom one,
+    may use [`set_executor`] to run blocking work on their own thread pool.
+
+    Implementations must be usable from any thread, and from within any async
+    runtime - futures returned by Rokit may be polled by any runtime at all.
+*/
+pub trait Executor: fmt::Debug + Send + Sync {
+    /**
+        Runs the given function on a thread where blocking is allowed.
+
+        The function must eventually run, even if nothing waits for it to
+        complete - dropping it without running it cancels the blocking task.
+    */
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>);
+
+    /**
+        Creates a future that completes after the given duration.
+    */
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/**
+    An executor that spawns a new thread for every blocking task and timer.
+
+    This works with any async runtime, and without one, but is much less
+    efficient than using the thread pool of a runtime - see [`set_executor`].
+*/
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ThreadExecutor;
+
+impl Executor for ThreadExecutor {
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>) {
+        spawn(f);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let (tx, rx) = oneshot::channel();
+        spawn(move || {
+            sleep_thread(duration);
+            let _ = tx.send(());
+        });
+        rx.map(|_| ()).boxed()
+    }
+}
+
+/**
+    An executor that uses the blocking thread pool and timers of the current
+    tokio runtime - this must only be used from within a tokio runtime.
+*/
+#[cfg(feature = "tokio")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokioExecutor;
+
+#[cfg(feature = "tokio")]
+impl Executor for TokioExecutor {
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>) {
+        drop(tokio::task::spawn_blocking(f));
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        tokio::time::sleep(duration).boxed()
+    }
+}
+
+/**
+    Sets the executor used by Rokit for the rest of the current process.
+
+    This should be called once, before using anything else in Rokit.
+    Returns `false` if an executor was already set, and was not replaced.
+*/
+pub fn set_executor(executor: impl Executor + 'static) -> bool {
+    EXECUTOR.set(Box::new(executor)).is_ok()
+}
+
+/**
+    Gets the executor used by Rokit - see [`Executor`] for more information.
+*/
+#[must_use]
+pub fn current_executor() -> &'static dyn Executor {
+    if let Some(executor) = EXECUTOR.get() {
+        return executor.as_ref();
+    }
+    #[cfg(feature = "tokio")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return &TokioExecutor;
+    }
+    &ThreadExecutor
+}
+
+/**
+    Runs the given blocking function using the current executor,
+    and waits for it to complete, returning its result.
+*/
+pub(crate) async fn spawn_blocking<F, T>(f: F) -> Result<T, TaskError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    current_executor().spawn_blocking(Box::new(move || {
+        let _ = tx.send(f());
+    }));
+    rx.await.map_err(|_| TaskError)
+}
+
+/**
+    Waits for the given duration, using the current executor.
+*/
+pub(crate) async fn sleep(duration: Duration) {
+    current_executor().sleep(duration).await;
+}
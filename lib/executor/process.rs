@@ -0,0 +1,48 @@
+use std::{
+    io::{Error as IoError, Result as IoResult},
+    process::{Command, Output, Stdio},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use super::spawn_blocking;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/*
+    Runs the given command to completion using the current executor,
+    collecting its output, same as `Command::output` does.
+*/
+pub(crate) async fn command_output(mut command: Command) -> IoResult<Output> {
+    spawn_blocking(move || command.output())
+        .await
+        .map_err(IoError::other)?
+}
+
+/*
+    Same as `command_output`, but kills the command if it does not exit
+    before the given timeout, returning `None` instead of its output.
+*/
+#[cfg_attr(not(feature = "extract"), allow(dead_code))]
+pub(crate) async fn command_output_with_timeout(
+    mut command: Command,
+    timeout: Duration,
+) -> IoResult<Option<Output>> {
+    let task = move || {
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let deadline = Instant::now() + timeout;
+        while child.try_wait()?.is_none() {
+            if Instant::now() >= deadline {
+                child.kill()?;
+                child.wait()?;
+                return Ok(None);
+            }
+            sleep(POLL_INTERVAL);
+        }
+        child.wait_with_output().map(Some)
+    };
+    spawn_blocking(task).await.map_err(IoError::other)?
+}
@@ -1,10 +1,33 @@
 pub(crate) mod util;
 
+// NOTE: Modules that are not gated behind the `runtime` feature make up the
+// core of Rokit - they must never use tokio, the network, or the filesystem,
+// so that they can be compiled for targets such as wasm32-unknown-unknown.
+
 pub mod descriptor;
-pub mod discovery;
 pub mod manifests;
-pub mod result;
 pub mod sources;
+pub mod tool;
+pub mod warnings;
+
+#[cfg(feature = "runtime")]
+mod resolve;
+
+#[cfg(feature = "runtime")]
+pub mod discovery;
+#[cfg(feature = "runtime")]
+pub mod executor;
+#[cfg(feature = "runtime")]
+pub mod prompt;
+#[cfg(feature = "runtime")]
+pub mod result;
+#[cfg(feature = "runtime")]
 pub mod storage;
+#[cfg(feature = "runtime")]
 pub mod system;
-pub mod tool;
+
+#[cfg(feature = "extract")]
+pub mod session;
+
+#[cfg(feature = "runtime")]
+pub use self::resolve::{resolve, ResolveConstraints, ResolvedArtifact};
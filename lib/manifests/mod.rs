@@ -1,8 +1,18 @@
 mod auth;
+mod parse_error;
+mod policy;
 mod rokit;
+mod taps;
+mod tool_options;
 
 pub use self::auth::{AuthManifest, MANIFEST_FILE_NAME as AUTH_MANIFEST_FILE_NAME};
+pub use self::parse_error::ManifestParseError;
+pub use self::policy::{
+    HeadlessTrust, PolicyManifest, MANIFEST_FILE_NAME as POLICY_MANIFEST_FILE_NAME, POLICY_SETTINGS,
+};
 pub use self::rokit::{RokitManifest, MANIFEST_FILE_NAME as ROKIT_MANIFEST_FILE_NAME};
+pub use self::taps::{is_short_spec, TapsManifest, MANIFEST_FILE_NAME as TAPS_MANIFEST_FILE_NAME};
+pub use self::tool_options::{InstallTarget, ToolOptions};
 
 /**
     Helper function to make sure our authored manifest templates
@@ -24,33 +34,49 @@ mod tests {
     #[test]
     fn has_no_indentation() {
         let auth_contents = make_manifest_template(auth::MANIFEST_DEFAULT_CONTENTS);
+        let policy_contents = make_manifest_template(policy::MANIFEST_DEFAULT_CONTENTS);
         let rokit_contents = make_manifest_template(rokit::MANIFEST_DEFAULT_CONTENTS);
+        let taps_contents = make_manifest_template(taps::MANIFEST_DEFAULT_CONTENTS);
 
         assert!(!auth_contents.contains('\t'));
+        assert!(!policy_contents.contains('\t'));
         assert!(!rokit_contents.contains('\t'));
+        assert!(!taps_contents.contains('\t'));
 
         assert!(!auth_contents.contains("\n  "));
+        assert!(!policy_contents.contains("\n  "));
         assert!(!rokit_contents.contains("\n  "));
+        assert!(!taps_contents.contains("\n  "));
 
         assert!(!auth_contents.contains("    "));
+        assert!(!policy_contents.contains("    "));
         assert!(!rokit_contents.contains("    "));
+        assert!(!taps_contents.contains("    "));
     }
 
     #[test]
     fn ends_with_newline() {
         assert!(make_manifest_template(auth::MANIFEST_DEFAULT_CONTENTS).ends_with('\n'));
+        assert!(make_manifest_template(policy::MANIFEST_DEFAULT_CONTENTS).ends_with('\n'));
         assert!(make_manifest_template(rokit::MANIFEST_DEFAULT_CONTENTS).ends_with('\n'));
+        assert!(make_manifest_template(taps::MANIFEST_DEFAULT_CONTENTS).ends_with('\n'));
     }
 
     #[test]
     fn contains_repo_url() {
         let auth_contents = make_manifest_template(auth::MANIFEST_DEFAULT_CONTENTS);
+        let policy_contents = make_manifest_template(policy::MANIFEST_DEFAULT_CONTENTS);
         let rokit_contents = make_manifest_template(rokit::MANIFEST_DEFAULT_CONTENTS);
+        let taps_contents = make_manifest_template(taps::MANIFEST_DEFAULT_CONTENTS);
 
         assert!(auth_contents.contains(env!("CARGO_PKG_REPOSITORY")));
+        assert!(policy_contents.contains(env!("CARGO_PKG_REPOSITORY")));
         assert!(rokit_contents.contains(env!("CARGO_PKG_REPOSITORY")));
+        assert!(taps_contents.contains(env!("CARGO_PKG_REPOSITORY")));
 
         assert!(!auth_contents.contains("REPOSITORY_URL"));
+        assert!(!policy_contents.contains("REPOSITORY_URL"));
         assert!(!rokit_contents.contains("REPOSITORY_URL"));
+        assert!(!taps_contents.contains("REPOSITORY_URL"));
     }
 }
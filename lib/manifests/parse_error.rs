@@ -0,0 +1,228 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use toml_edit::TomlError;
+
+/**
+    Error type representing a manifest file that is not valid TOML.
+
+    Contains the location of the error, the key it occurred at (if
+    known), and a suggestion for how to fix it, in addition to the
+    original error message, which makes for much nicer CLI output.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestParseError {
+    path: Option<PathBuf>,
+    line: usize,
+    column: usize,
+    line_contents: String,
+    key: Option<String>,
+    message: String,
+    suggestion: Option<&'static str>,
+}
+
+impl ManifestParseError {
+    /**
+        Creates a new parse error from the given manifest contents
+        and the TOML error that occurred while parsing them.
+    */
+    #[must_use]
+    pub fn new(contents: &str, err: &TomlError) -> Self {
+        let offset = err.span().map_or(0, |span| span.start).min(contents.len());
+        let before = &contents[..offset];
+
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let column = before[line_start..].chars().count() + 1;
+        let line_contents = contents[line_start..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        let message = err.message().trim().to_string();
+        let key = find_key(&message, &line_contents);
+        let suggestion = find_suggestion(&message);
+
+        Self {
+            path: None,
+            line,
+            column,
+            line_contents,
+            key,
+            message,
+            suggestion,
+        }
+    }
+
+    /**
+        Sets the path of the manifest file that this error occurred in.
+    */
+    #[must_use]
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /**
+        Gets the path of the manifest file that this error occurred in, if known.
+    */
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /**
+        Gets the line that this error occurred at, starting from 1.
+    */
+    #[must_use]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /**
+        Gets the column that this error occurred at, starting from 1.
+    */
+    #[must_use]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /**
+        Gets the key that this error occurred at, if known.
+    */
+    #[must_use]
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /**
+        Gets the original error message from the TOML parser.
+    */
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /**
+        Gets a suggestion for how to fix this error, if any.
+    */
+    #[must_use]
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion
+    }
+
+    /**
+        Renders a code frame pointing at the location of this error, such as:
+
+        ```text
+         --> rokit.toml:3:6
+          |
+        3 | rojo "rojo-rbx/rojo@7.4.4"
+          |      ^
+        ```
+    */
+    #[must_use]
+    pub fn code_frame(&self) -> String {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let location = match &self.path {
+            Some(path) => format!("{}:{}:{}", path.display(), self.line, self.column),
+            None => format!("{}:{}", self.line, self.column),
+        };
+        format!(
+            "{gutter}--> {location}\n{gutter} |\n{line_number} | {}\n{gutter} | {}^",
+            self.line_contents,
+            " ".repeat(self.column - 1),
+        )
+    }
+}
+
+impl fmt::Display for ManifestParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "invalid manifest at {}", path.display())?,
+            None => write!(f, "invalid manifest")?,
+        }
+        write!(f, " (line {}, column {})", self.line, self.column)?;
+        if let Some(key) = &self.key {
+            write!(f, " for key '{key}'")?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(suggestion) = self.suggestion {
+            write!(f, "\nhelp: {suggestion}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ManifestParseError {}
+
+fn find_key(message: &str, line_contents: &str) -> Option<String> {
+    // Duplicate key errors contain the key itself, wrapped in backticks
+    if let Some(rest) = message.strip_prefix("duplicate key `") {
+        return rest.split('`').next().map(ToString::to_string);
+    }
+
+    // Otherwise, use whatever is to the left of the `=` on the line, if it looks like a key
+    let (key, _) = line_contents.split_once('=')?;
+    let key = key.trim().trim_matches('"');
+    let is_key = !key.is_empty() && !key.starts_with('[') && !key.starts_with('#');
+    is_key.then(|| key.to_string())
+}
+
+fn find_suggestion(message: &str) -> Option<&'static str> {
+    let message = message.to_ascii_lowercase();
+    if message.contains("duplicate key") {
+        Some("each key may only be listed once - remove or rename one of them")
+    } else if message.contains("duplicate") {
+        Some("each table may only be listed once - merge the duplicate tables")
+    } else if message.contains("invalid string") || message.contains("expected `\"`") {
+        Some("string values must be quoted, such as `rojo = \"rojo-rbx/rojo@7.4.4\"`")
+    } else if message.contains("expected `=`") || message.contains("invalid key") {
+        Some("keys must be followed by `=` and a value, such as `rojo = \"rojo-rbx/rojo@7.4.4\"`")
+    } else if message.contains("expected `]`") {
+        Some("make sure that all tables and arrays are closed using `]`")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use toml_edit::DocumentMut;
+
+    use super::*;
+
+    fn parse_err(contents: &str) -> ManifestParseError {
+        let err = contents.parse::<DocumentMut>().unwrap_err();
+        ManifestParseError::new(contents, &err)
+    }
+
+    #[test]
+    fn location() {
+        let err = parse_err("[tools]\nrojo = \"rojo-rbx/rojo@7.4.4\"\nlune = lune-org/lune\n");
+        assert_eq!(err.line(), 3);
+        assert_eq!(err.column(), 8);
+        assert_eq!(err.key(), Some("lune"));
+        assert!(err.suggestion().is_some());
+    }
+
+    #[test]
+    fn duplicate_key() {
+        let err = parse_err("[tools]\nrojo = \"a/b@1.0.0\"\nrojo = \"a/b@2.0.0\"\n");
+        assert_eq!(err.key(), Some("rojo"));
+        assert!(err.suggestion().is_some());
+    }
+
+    #[test]
+    fn code_frame() {
+        let err = parse_err("[tools]\nlune = lune-org/lune\n").with_path("rokit.toml");
+        assert_eq!(
+            err.code_frame(),
+            " --> rokit.toml:2:8\n  |\n2 | lune = lune-org/lune\n  |        ^"
+        );
+    }
+}
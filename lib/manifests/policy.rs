@@ -0,0 +1,1318 @@
+#![allow(clippy::to_string_trait_impl)]
+// NOTE: We don't want to implement Display here since it may
+// make library consumers think that policy manifests are meant
+// to be displayed - they are only meant to be stringified.
+
+use std::{fmt, path::PathBuf, str::FromStr, time::Duration};
+
+use semver::Version;
+use toml_edit::{value, DocumentMut, Item, Value};
+use tracing::warn;
+use url::Url;
+
+use crate::{
+    sources::{ArtifactProvider, RequestPolicy},
+    tool::{ToolId, ToolSpec},
+};
+
+use super::parse_error::ManifestParseError;
+
+#[cfg(feature = "runtime")]
+use std::path::Path;
+
+#[cfg(feature = "runtime")]
+use crate::{
+    result::{RokitError, RokitResult},
+    util::fs::{load_from_file, save_to_file},
+};
+
+pub const MANIFEST_FILE_NAME: &str = "policy.toml";
+const DEFAULT_CONFIRM_DOWNLOAD_MB: u64 = 250;
+const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 4;
+
+/**
+    Top-level settings in the policy manifest, which may be read and written
+    using [`PolicyManifest::get_setting`] and [`PolicyManifest::set_setting`].
+*/
+pub const POLICY_SETTINGS: [&str; 14] = [
+    "headless_trust",
+    "confirm_download_mb",
+    "max_concurrent_downloads",
+    "metrics",
+    "metrics_endpoint",
+    "registry",
+    "s3_bucket",
+    "s3_region",
+    "proxy",
+    "ca_bundle",
+    "timeout_secs",
+    "retries",
+    "retry_backoff_ms",
+    "temp_dir",
+];
+
+pub(super) const MANIFEST_DEFAULT_CONTENTS: &str = "
+# This file lists policies for tools managed by Rokit, a toolchain manager for Roblox projects.
+# For more information, see <|REPOSITORY_URL|>
+
+# When a tool is not trusted and Rokit can not prompt for trust, such as in CI,
+# installing it will fail. Set this to \"trust\" to trust such tools automatically,
+# or to \"skip\" to skip them. The ROKIT_HEADLESS_TRUST environment variable
+# may also be used, and takes precedence over this setting.
+
+# headless_trust = \"fail\"
+
+# When a single install would download more than this many megabytes, Rokit
+# asks for confirmation first, which is useful on metered connections.
+# Set this to 0 to never ask. Rokit never asks when it can not prompt.
+
+# confirm_download_mb = 250
+
+# The maximum number of requests that Rokit sends at once, such as when
+# installing many tools. Lower this on slow or unreliable connections.
+
+# max_concurrent_downloads = 4
+
+# Set this to true to record anonymous metrics about Rokit commands, such as
+# how often they run, how long they take, and what kinds of errors they fail
+# with. Metrics are stored locally, and are only ever sent anywhere if an
+# endpoint is also set, in which case they are posted as JSON once per day.
+
+# metrics = false
+# metrics_endpoint = \"https://metrics.example.com/rokit\"
+
+# The base URL of a custom tool registry, which publishes tools in an index.json
+# file instead of as releases. Tools from the registry use ids such as
+# \"registry:author/name\", and must be served over HTTPS.
+
+# registry = \"https://tools.example.com\"
+
+# The path-style URL of an S3-compatible bucket, such as Amazon S3, MinIO, or
+# Cloudflare R2, optionally followed by a prefix for all keys. Tools from the
+# bucket use ids such as \"s3:author/name\", and are stored as objects with keys
+# such as \"<prefix>/author/name/1.2.3/name-1.2.3-linux-x86_64.zip\". Credentials
+# are read from `rokit authenticate s3`, or from the standard AWS variables.
+
+# s3_bucket = \"https://s3.us-east-1.amazonaws.com/my-bucket/rokit\"
+# s3_region = \"us-east-1\"
+
+# Proxies set in the HTTP_PROXY, HTTPS_PROXY, and NO_PROXY environment variables
+# are always used. Set a proxy here to use it instead, for all providers. Hosts
+# in NO_PROXY still bypass it. Networks that inspect HTTPS traffic may also need
+# the path to a PEM file with extra root certificates to trust, which may be
+# relative to the directory of this file.
+
+# proxy = \"http://proxy.example.com:8080\"
+# ca_bundle = \"/etc/ssl/certs/corporate-ca.pem\"
+
+# Requests that take longer than this many seconds fail. Requests that fail
+# with a transient error, such as a connection or server error, are retried
+# this many times, and Rokit waits about twice as long before each retry,
+# starting at this many milliseconds. These may also be set per provider,
+# below. The ROKIT_TIMEOUT_SECS, ROKIT_RETRIES, and ROKIT_RETRY_BACKOFF_MS
+# environment variables may also be used, and take precedence over both.
+
+# timeout_secs = 60
+# retries = 3
+# retry_backoff_ms = 1000
+
+# The directory for temporary files, such as archives being extracted, tools
+# being tried out, and the previous binary during self-updates. By default,
+# archives are extracted inside of tool storage, and anything else goes in the
+# system temporary directory, which is a small tmpfs on some CI images. Set
+# this to \"home\" to use a directory inside of the Rokit home, on the same
+# filesystem as tool storage, or to any other directory, which may be relative
+# to the directory of this file. The ROKIT_TEMP_DIR environment variable may
+# also be used, and takes precedence over this setting.
+
+# temp_dir = \"home\"
+
+# Mirrors to download GitHub or GitLab artifacts from instead of the provider
+# itself, which is useful in CI behind a proxy. The path of each download URL
+# is kept, and only its origin is replaced with the mirror. Mirrors are tried
+# in order, and the provider itself is used if all of them fail.
+# Authentication tokens are never sent to mirrors.
+
+[mirrors]
+# github = [\"https://mirror.example.com/github\"]
+
+# Custom base URLs for the APIs of GitHub and GitLab, and for downloading
+# their artifacts, such as an internal API gateway or a local mock server.
+# Download URLs keep the path of each download, same as mirrors, but have
+# no fallback, and authentication tokens are sent to them.
+
+# [providers.github]
+# api_url = \"https://github-gateway.example.com/api\"
+# download_url = \"https://github-gateway.example.com/downloads\"
+
+# Timeouts and retries may be set for any provider, such as github, gitlab,
+# crates, npm, git, registry, s3, or url, and replace the ones set above.
+
+# [providers.npm]
+# timeout_secs = 120
+# retries = 5
+
+# Tool versions listed as yanked are known to be broken. They will be skipped
+# when Rokit looks for the latest version of a tool, and Rokit will warn about
+# any project that pins one of them exactly.
+
+[yanked]
+# \"author/name\" = [\"1.2.3\"]
+";
+
+/**
+    What to do with untrusted tools when Rokit can not prompt for trust,
+    such as when running in CI or another non-interactive environment.
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HeadlessTrust {
+    /// Fail, and tell the user how to trust the tool.
+    #[default]
+    Fail,
+    /// Trust the tool without prompting.
+    Trust,
+    /// Skip the tool without trusting or installing it.
+    Skip,
+}
+
+impl HeadlessTrust {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Fail => "fail",
+            Self::Trust => "trust",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+impl FromStr for HeadlessTrust {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "fail" => Ok(Self::Fail),
+            "trust" => Ok(Self::Trust),
+            "skip" => Ok(Self::Skip),
+            _ => Err(format!(
+                "unknown headless trust policy '{s}' - expected 'fail', 'trust', or 'skip'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for HeadlessTrust {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/**
+    Policy manifest file.
+
+    Contains policies for tools managed by Rokit, such as yanked versions.
+*/
+#[derive(Debug, Clone)]
+pub struct PolicyManifest {
+    document: DocumentMut,
+}
+
+#[cfg(feature = "runtime")]
+impl PolicyManifest {
+    /**
+        Loads the manifest from the given directory, or creates a new one if it doesn't exist.
+
+        If the manifest doesn't exist, a new one will be created with default contents and saved.
+
+        See [`PolicyManifest::load`] and [`PolicyManifest::save`] for more information.
+
+        # Errors
+
+        - If the manifest file could not be loaded or created.
+    */
+    pub async fn load_or_create(dir: impl AsRef<Path>) -> RokitResult<Self> {
+        let path = dir.as_ref().join(MANIFEST_FILE_NAME);
+        match load_from_file(path).await {
+            Ok(manifest) => Ok(manifest),
+            Err(RokitError::FileNotFound(_)) => {
+                let new = Self::default();
+                new.save(dir).await?;
+                Ok(new)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /**
+        Loads the manifest from the given directory.
+
+        This will search for a file named `policy.toml` in the given directory.
+
+        # Errors
+
+        - If the manifest file could not be loaded.
+    */
+    #[tracing::instrument(skip(dir), level = "trace")]
+    pub async fn load(dir: impl AsRef<Path>) -> RokitResult<Self> {
+        let path = dir.as_ref().join(MANIFEST_FILE_NAME);
+        tracing::trace!(?path, "Loading manifest");
+        load_from_file(path).await
+    }
+
+    /**
+        Saves the manifest to the given directory.
+
+        This will write the manifest to a file named `policy.toml` in the given directory.
+
+        # Errors
+
+        - If the manifest file could not be saved.
+    */
+    #[tracing::instrument(skip(self, dir), level = "trace")]
+    pub async fn save(&self, dir: impl AsRef<Path>) -> RokitResult<()> {
+        let path = dir.as_ref().join(MANIFEST_FILE_NAME);
+        tracing::trace!(?path, "Saving manifest");
+        save_to_file(path, self.clone()).await
+    }
+}
+
+impl PolicyManifest {
+    /**
+        Gets all versions of the given tool that have been marked as yanked.
+
+        Any versions that are not valid semver versions will be ignored.
+    */
+    #[must_use]
+    pub fn yanked_versions(&self, id: &ToolId) -> Vec<Version> {
+        let Some(yanked) = self.document.get("yanked").and_then(Item::as_table) else {
+            return Vec::new();
+        };
+        yanked
+            .iter()
+            .filter(|(key, _)| key.parse::<ToolId>().is_ok_and(|key_id| key_id == *id))
+            .filter_map(|(_, value)| value.as_array())
+            .flat_map(|versions| versions.iter().filter_map(Value::as_str))
+            .filter_map(|version| version.trim_start_matches('v').parse::<Version>().ok())
+            .collect()
+    }
+
+    /**
+        Checks if the given tool specification has been marked as yanked.
+    */
+    #[must_use]
+    pub fn is_yanked(&self, spec: &ToolSpec) -> bool {
+        self.yanked_versions(spec.id()).contains(spec.version())
+    }
+
+    /**
+        Gets what to do with untrusted tools when Rokit can not prompt for trust.
+
+        Defaults to [`HeadlessTrust::Fail`] if not set, or if the value is invalid.
+    */
+    #[must_use]
+    pub fn headless_trust(&self) -> HeadlessTrust {
+        self.document
+            .get("headless_trust")
+            .and_then(Item::as_str)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /**
+        Gets the download size, in bytes, above which Rokit should ask
+        for confirmation before downloading tools, or `None` if it should
+        never ask for confirmation.
+
+        Defaults to 250 megabytes if not set, or if the value is invalid.
+    */
+    #[must_use]
+    pub fn confirm_download_size(&self) -> Option<u64> {
+        let megabytes = self
+            .document
+            .get("confirm_download_mb")
+            .and_then(Item::as_integer)
+            .and_then(|mb| u64::try_from(mb).ok())
+            .unwrap_or(DEFAULT_CONFIRM_DOWNLOAD_MB);
+        (megabytes > 0).then(|| megabytes * 1024 * 1024)
+    }
+
+    /**
+        Gets the maximum number of requests that Rokit should send at once.
+
+        Defaults to 4 if not set, or if the value is invalid.
+    */
+    #[must_use]
+    pub fn max_concurrent_downloads(&self) -> usize {
+        self.document
+            .get("max_concurrent_downloads")
+            .and_then(Item::as_integer)
+            .and_then(|n| usize::try_from(n).ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_DOWNLOADS)
+    }
+
+    /**
+        Checks if Rokit should record metrics about the commands it runs.
+
+        Defaults to `false` if not set, or if the value is invalid.
+    */
+    #[must_use]
+    pub fn metrics_enabled(&self) -> bool {
+        self.document
+            .get("metrics")
+            .and_then(Item::as_bool)
+            .unwrap_or_default()
+    }
+
+    /**
+        Gets the endpoint that recorded metrics should be posted to, if any.
+
+        Always returns `None` if metrics are not enabled, or if the
+        endpoint is not a valid `http` or `https` URL.
+    */
+    #[must_use]
+    pub fn metrics_endpoint(&self) -> Option<Url> {
+        if !self.metrics_enabled() {
+            return None;
+        }
+        self.document
+            .get("metrics_endpoint")
+            .and_then(Item::as_str)
+            .and_then(parse_endpoint)
+    }
+
+    /**
+        Gets the base URL of the custom tool registry, if any.
+
+        Returns `None` if the registry is not set, or
+        if it is not a valid `https` URL.
+    */
+    #[must_use]
+    pub fn registry_url(&self) -> Option<Url> {
+        self.document
+            .get("registry")
+            .and_then(Item::as_str)
+            .and_then(parse_registry_url)
+    }
+
+    /**
+        Gets the path-style URL of the S3-compatible bucket, if any.
+
+        Returns `None` if the bucket is not set, or if it is
+        not a valid HTTP or HTTPS URL that includes a bucket name.
+    */
+    #[must_use]
+    pub fn s3_bucket_url(&self) -> Option<Url> {
+        self.document
+            .get("s3_bucket")
+            .and_then(Item::as_str)
+            .and_then(parse_s3_bucket_url)
+    }
+
+    /**
+        Gets the URL of the proxy to send all requests through, if any.
+
+        Returns `None` if the proxy is not set, or if
+        it is not a valid HTTP or HTTPS URL with a host.
+    */
+    #[must_use]
+    pub fn proxy_url(&self) -> Option<Url> {
+        self.document
+            .get("proxy")
+            .and_then(Item::as_str)
+            .and_then(parse_proxy_url)
+    }
+
+    /**
+        Gets the path to a PEM bundle of extra root certificates to trust, if set.
+    */
+    #[must_use]
+    pub fn ca_bundle_path(&self) -> Option<PathBuf> {
+        self.document
+            .get("ca_bundle")
+            .and_then(Item::as_str)
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /**
+        Gets the directory to use for temporary files, if set - either
+        a path, or `home` for a directory inside of the Rokit home.
+    */
+    #[must_use]
+    pub fn temp_dir(&self) -> Option<PathBuf> {
+        self.document
+            .get("temp_dir")
+            .and_then(Item::as_str)
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /**
+        Gets the region of the S3-compatible bucket, if set.
+    */
+    #[must_use]
+    pub fn s3_region(&self) -> Option<String> {
+        self.document
+            .get("s3_region")
+            .and_then(Item::as_str)
+            .map(str::trim)
+            .filter(|region| !region.is_empty())
+            .map(ToString::to_string)
+    }
+
+    /**
+        Gets the mirrors that artifacts from the given provider
+        should be downloaded from, in the order they should be tried.
+
+        Only GitHub and GitLab artifacts may be mirrored, and any
+        mirror that is not a valid `http` or `https` URL is ignored.
+    */
+    #[must_use]
+    pub fn mirrors(&self, provider: ArtifactProvider) -> Vec<Url> {
+        let Some(mirrors) = self.document.get("mirrors").and_then(Item::as_table) else {
+            return Vec::new();
+        };
+        mirrors
+            .iter()
+            .filter(|(key, _)| parse_hosted_provider(key) == Some(provider))
+            .filter_map(|(_, value)| value.as_array())
+            .flat_map(|urls| urls.iter().filter_map(Value::as_str))
+            .filter_map(parse_endpoint)
+            .collect()
+    }
+
+    /**
+        Gets the custom base URL for the API of the given provider, if any.
+
+        Only GitHub and GitLab have APIs, and any URL that
+        is not a valid `http` or `https` URL is ignored.
+    */
+    #[must_use]
+    pub fn api_url(&self, provider: ArtifactProvider) -> Option<Url> {
+        self.provider_url(provider, "api_url")
+    }
+
+    /**
+        Gets the custom base URL for downloading artifacts
+        from the given provider, if any - see [`PolicyManifest::api_url`].
+    */
+    #[must_use]
+    pub fn download_url(&self, provider: ArtifactProvider) -> Option<Url> {
+        self.provider_url(provider, "download_url")
+    }
+
+    /**
+        Gets the timeout and retry policy for requests to the given
+        provider, or for requests to all providers if `None` is given.
+
+        Settings for the provider take precedence over settings
+        for all providers, and any setting that is not set or is
+        invalid uses the default from [`RequestPolicy::default`].
+
+        Note that this does not include any environment variable overrides.
+    */
+    #[must_use]
+    pub fn request_policy(&self, provider: Option<ArtifactProvider>) -> RequestPolicy {
+        let mut policy = RequestPolicy::default();
+        apply_request_policy(&mut policy, self.document.as_item());
+        let Some(provider) = provider else {
+            return policy;
+        };
+        let providers = self.document.get("providers").and_then(Item::as_table);
+        for (name, table) in providers.into_iter().flat_map(|t| t.iter()) {
+            if parse_policy_provider(name) == Some(provider) {
+                apply_request_policy(&mut policy, table);
+            }
+        }
+        policy
+    }
+
+    fn provider_url(&self, provider: ArtifactProvider, key: &str) -> Option<Url> {
+        let providers = self.document.get("providers").and_then(Item::as_table)?;
+        providers
+            .iter()
+            .filter(|(name, _)| parse_hosted_provider(name) == Some(provider))
+            .find_map(|(_, table)| table.get(key))
+            .and_then(Item::as_str)
+            .and_then(parse_endpoint)
+    }
+
+    /**
+        Gets the value of a top-level setting, as written in the manifest,
+        or `None` if the setting is not set or its value is invalid.
+
+        See [`POLICY_SETTINGS`] for all known settings.
+    */
+    #[must_use]
+    pub fn get_setting(&self, key: &str) -> Option<String> {
+        let value = match self.document.get(key).and_then(Item::as_value)? {
+            Value::String(s) => s.value().clone(),
+            Value::Integer(i) => i.value().to_string(),
+            Value::Boolean(b) => b.value().to_string(),
+            _ => return None,
+        };
+        setting_item(key, &value).ok()?;
+        Some(value)
+    }
+
+    /**
+        Gets the value that is used for a top-level setting
+        when it is not set, or `None` if it has no default.
+    */
+    #[must_use]
+    pub fn default_setting(key: &str) -> Option<String> {
+        match key {
+            "headless_trust" => Some(HeadlessTrust::default().to_string()),
+            "confirm_download_mb" => Some(DEFAULT_CONFIRM_DOWNLOAD_MB.to_string()),
+            "max_concurrent_downloads" => Some(DEFAULT_MAX_CONCURRENT_DOWNLOADS.to_string()),
+            "metrics" => Some(false.to_string()),
+            "timeout_secs" => Some(RequestPolicy::DEFAULT_TIMEOUT.as_secs().to_string()),
+            "retries" => Some(RequestPolicy::DEFAULT_RETRIES.to_string()),
+            "retry_backoff_ms" => {
+                Some(RequestPolicy::DEFAULT_RETRY_BACKOFF.as_millis().to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /**
+        Sets a top-level setting, after checking that the value is valid for it.
+
+        Returns `true` if the setting replaced an older
+        value, `false` if an older value was not present.
+
+        # Errors
+
+        - If the setting is not one of [`POLICY_SETTINGS`].
+        - If the value is not valid for the setting.
+    */
+    pub fn set_setting(&mut self, key: &str, value: &str) -> Result<bool, String> {
+        let item = setting_item(key, value)?;
+        Ok(self.document.as_table_mut().insert(key, item).is_some())
+    }
+}
+
+fn setting_item(key: &str, new_value: &str) -> Result<Item, String> {
+    let new_value = new_value.trim();
+    let item = match key {
+        "headless_trust" => value(HeadlessTrust::from_str(new_value)?.as_str()),
+        "confirm_download_mb" => value(
+            new_value
+                .parse::<i64>()
+                .ok()
+                .filter(|mb| *mb >= 0)
+                .ok_or_else(|| format!("expected a non-negative integer, got '{new_value}'"))?,
+        ),
+        "max_concurrent_downloads" => value(
+            new_value
+                .parse::<i64>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("expected a positive integer, got '{new_value}'"))?,
+        ),
+        "metrics" => value(
+            new_value
+                .parse::<bool>()
+                .map_err(|_| format!("expected 'true' or 'false', got '{new_value}'"))?,
+        ),
+        "metrics_endpoint" => value(
+            parse_endpoint(new_value)
+                .ok_or_else(|| format!("expected an HTTP or HTTPS URL, got '{new_value}'"))?
+                .as_str(),
+        ),
+        "registry" => value(
+            parse_registry_url(new_value)
+                .ok_or_else(|| format!("expected an HTTPS URL, got '{new_value}'"))?
+                .as_str(),
+        ),
+        "s3_bucket" => value(
+            parse_s3_bucket_url(new_value)
+                .ok_or_else(|| format!("expected a bucket URL, got '{new_value}'"))?
+                .as_str(),
+        ),
+        "s3_region" | "ca_bundle" | "temp_dir" if !new_value.is_empty() => value(new_value),
+        "s3_region" => return Err(String::from("expected a region, such as 'us-east-1'")),
+        "proxy" => value(
+            parse_proxy_url(new_value)
+                .ok_or_else(|| format!("expected an HTTP or HTTPS URL, got '{new_value}'"))?
+                .as_str(),
+        ),
+        "ca_bundle" => return Err(String::from("expected a path to a PEM file")),
+        "temp_dir" => return Err(String::from("expected a path to a directory, or 'home'")),
+        "timeout_secs" | "retry_backoff_ms" => value(
+            new_value
+                .parse::<i64>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("expected a positive integer, got '{new_value}'"))?,
+        ),
+        "retries" => value(
+            new_value
+                .parse::<i64>()
+                .ok()
+                .filter(|n| u32::try_from(*n).is_ok())
+                .ok_or_else(|| format!("expected a non-negative integer, got '{new_value}'"))?,
+        ),
+        _ => {
+            return Err(format!(
+                "unknown setting '{key}' - expected one of: {}",
+                POLICY_SETTINGS.join(", ")
+            ))
+        }
+    };
+    Ok(item)
+}
+
+fn parse_hosted_provider(s: &str) -> Option<ArtifactProvider> {
+    s.parse::<ArtifactProvider>().ok().filter(|provider| {
+        matches!(
+            provider,
+            ArtifactProvider::GitHub | ArtifactProvider::GitLab
+        )
+    })
+}
+
+fn parse_policy_provider(s: &str) -> Option<ArtifactProvider> {
+    s.parse::<ArtifactProvider>()
+        .ok()
+        .filter(|provider| *provider != ArtifactProvider::Custom)
+}
+
+fn parse_timeout(item: &Item) -> Option<Duration> {
+    let secs = item.as_integer().and_then(|n| u64::try_from(n).ok())?;
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+fn parse_retries(item: &Item) -> Option<u32> {
+    item.as_integer().and_then(|n| u32::try_from(n).ok())
+}
+
+fn parse_retry_backoff(item: &Item) -> Option<Duration> {
+    let millis = item.as_integer().and_then(|n| u64::try_from(n).ok())?;
+    (millis > 0).then(|| Duration::from_millis(millis))
+}
+
+fn apply_request_policy(policy: &mut RequestPolicy, table: &Item) {
+    if let Some(timeout) = table.get("timeout_secs").and_then(parse_timeout) {
+        policy.timeout = timeout;
+    }
+    if let Some(retries) = table.get("retries").and_then(parse_retries) {
+        policy.retries = retries;
+    }
+    if let Some(backoff) = table.get("retry_backoff_ms").and_then(parse_retry_backoff) {
+        policy.retry_backoff = backoff;
+    }
+}
+
+/*
+    Warns about any invalid timeout or retry settings in the given table,
+    which is either the top-level table or the table for a single provider.
+*/
+fn warn_invalid_request_policy(table: &Item, location: &str) {
+    let checks: [(&str, bool, &str); 3] = [
+        (
+            "timeout_secs",
+            table
+                .get("timeout_secs")
+                .is_some_and(|v| parse_timeout(v).is_none()),
+            "Positive integer",
+        ),
+        (
+            "retries",
+            table
+                .get("retries")
+                .is_some_and(|v| parse_retries(v).is_none()),
+            "Non-negative integer",
+        ),
+        (
+            "retry_backoff_ms",
+            table
+                .get("retry_backoff_ms")
+                .is_some_and(|v| parse_retry_backoff(v).is_none()),
+            "Positive integer",
+        ),
+    ];
+    for (key, is_invalid, expected) in checks {
+        if is_invalid {
+            warn!(
+                "Encountered invalid '{key}' value{location} in policy manifest!\
+                \nThe value will be ignored.\
+                \nExpected: {expected}"
+            );
+        }
+    }
+}
+
+fn parse_endpoint(s: &str) -> Option<Url> {
+    Url::parse(s.trim())
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+fn parse_proxy_url(s: &str) -> Option<Url> {
+    parse_endpoint(s).filter(Url::has_host)
+}
+
+fn parse_registry_url(s: &str) -> Option<Url> {
+    Url::parse(s.trim())
+        .ok()
+        .filter(|url| url.scheme() == "https" && url.has_host())
+}
+
+fn parse_s3_bucket_url(s: &str) -> Option<Url> {
+    parse_endpoint(s).filter(|url| {
+        url.has_host()
+            && url
+                .path_segments()
+                .is_some_and(|mut segments| segments.any(|segment| !segment.is_empty()))
+    })
+}
+
+impl FromStr for PolicyManifest {
+    type Err = ManifestParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let document = s
+            .parse::<DocumentMut>()
+            .map_err(|e| ManifestParseError::new(s, &e))?;
+
+        if let Some(value) = document.get("headless_trust") {
+            if let Err(e) = value
+                .as_str()
+                .ok_or_else(|| String::from("expected a string"))
+                .and_then(HeadlessTrust::from_str)
+            {
+                warn!(
+                    "Encountered invalid 'headless_trust' value in policy manifest!\
+                    \nUntrusted tools will fail to install when Rokit can not prompt for trust.\
+                    \nError: {e}"
+                );
+            }
+        }
+
+        if let Some(value) = document.get("confirm_download_mb") {
+            if value.as_integer().is_none_or(|mb| mb < 0) {
+                warn!(
+                    "Encountered invalid 'confirm_download_mb' value in policy manifest!\
+                    \nThe default of {DEFAULT_CONFIRM_DOWNLOAD_MB} megabytes will be used.\
+                    \nExpected: Non-negative integer"
+                );
+            }
+        }
+
+        if let Some(value) = document.get("max_concurrent_downloads") {
+            if value.as_integer().is_none_or(|n| n <= 0) {
+                warn!(
+                    "Encountered invalid 'max_concurrent_downloads' value in policy manifest!\
+                    \nThe default of {DEFAULT_MAX_CONCURRENT_DOWNLOADS} will be used.\
+                    \nExpected: Positive integer"
+                );
+            }
+        }
+
+        if let Some(value) = document.get("metrics") {
+            if !value.is_bool() {
+                warn!(
+                    "Encountered invalid 'metrics' value in policy manifest!\
+                    \nMetrics will not be recorded.\
+                    \nExpected: Boolean"
+                );
+            }
+        }
+
+        if let Some(value) = document.get("metrics_endpoint") {
+            if value.as_str().and_then(parse_endpoint).is_none() {
+                warn!(
+                    "Encountered invalid 'metrics_endpoint' value in policy manifest!\
+                    \nMetrics will only be stored locally.\
+                    \nExpected: HTTP or HTTPS URL"
+                );
+            }
+        }
+
+        if let Some(value) = document.get("registry") {
+            if value.as_str().and_then(parse_registry_url).is_none() {
+                warn!(
+                    "Encountered invalid 'registry' value in policy manifest!\
+                    \nTools from the registry will not be available.\
+                    \nExpected: HTTPS URL"
+                );
+            }
+        }
+
+        if let Some(value) = document.get("proxy") {
+            if value.as_str().and_then(parse_proxy_url).is_none() {
+                warn!(
+                    "Encountered invalid 'proxy' value in policy manifest!\
+                    \nProxies from the environment will be used instead.\
+                    \nExpected: HTTP or HTTPS URL"
+                );
+            }
+        }
+
+        if let Some(value) = document.get("s3_bucket") {
+            if value.as_str().and_then(parse_s3_bucket_url).is_none() {
+                warn!(
+                    "Encountered invalid 's3_bucket' value in policy manifest!\
+                    \nTools from the bucket will not be available.\
+                    \nExpected: HTTP or HTTPS URL, such as 'https://s3.example.com/bucket'"
+                );
+            }
+        }
+
+        warn_invalid_request_policy(document.as_item(), "");
+
+        let mirror_kv_pairs = document
+            .get("mirrors")
+            .and_then(Item::as_table)
+            .map(|t| t.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        for (key, value) in mirror_kv_pairs {
+            if parse_hosted_provider(key).is_none() {
+                warn!(
+                    "Encountered invalid mirror provider '{key}' in policy manifest!\
+                    \nThe entry will be ignored.\
+                    \nExpected: 'github' or 'gitlab'"
+                );
+                continue;
+            }
+            let Some(urls) = value.as_array() else {
+                warn!(
+                    "Encountered invalid mirrors for provider '{key}' in policy manifest!\
+                    \nExpected: Array\
+                    \nActual: {}",
+                    value.type_name()
+                );
+                continue;
+            };
+            for url in urls {
+                if url.as_str().and_then(parse_endpoint).is_none() {
+                    warn!(
+                        "Encountered invalid mirror '{url}' \
+                        for provider '{key}' in policy manifest!\
+                        \nThe mirror will be ignored."
+                    );
+                }
+            }
+        }
+
+        let provider_kv_pairs = document
+            .get("providers")
+            .and_then(Item::as_table)
+            .map(|t| t.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        for (name, table) in provider_kv_pairs {
+            if parse_policy_provider(name).is_none() {
+                warn!(
+                    "Encountered invalid provider '{name}' in policy manifest!\
+                    \nThe entry will be ignored.\
+                    \nExpected: A provider such as 'github', 'gitlab', 'crates', or 'npm'"
+                );
+                continue;
+            }
+            for key in ["api_url", "download_url"] {
+                let Some(value) = table.get(key) else {
+                    continue;
+                };
+                if parse_hosted_provider(name).is_none() {
+                    warn!(
+                        "Encountered unsupported '{key}' for provider '{name}' in policy manifest!\
+                        \nThe URL will be ignored.\
+                        \nExpected: Only 'github' and 'gitlab' may use custom URLs"
+                    );
+                } else if value.as_str().and_then(parse_endpoint).is_none() {
+                    warn!(
+                        "Encountered invalid '{key}' for provider '{name}' in policy manifest!\
+                        \nThe default URL will be used.\
+                        \nExpected: HTTP or HTTPS URL"
+                    );
+                }
+            }
+            warn_invalid_request_policy(table, &format!(" for provider '{name}'"));
+        }
+
+        /*
+            Check for invalid yanked entries and warn the user about them
+            as a preprocessing step. We do this here instead of when accessed
+            in manifest methods to avoid duplicate warnings being emitted.
+        */
+        let yanked_kv_pairs = document
+            .get("yanked")
+            .and_then(Item::as_table)
+            .map(|t| t.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        for (key, value) in yanked_kv_pairs {
+            if let Err(e) = key.parse::<ToolId>() {
+                warn!(
+                    "Encountered invalid tool id '{key}' in policy manifest!\
+                    \nThe entry will be ignored.\
+                    \nError: {e}"
+                );
+                continue;
+            }
+            let Some(versions) = value.as_array() else {
+                warn!(
+                    "Encountered invalid yanked versions for tool '{key}' in policy manifest!\
+                    \nExpected: Array\
+                    \nActual: {}",
+                    value.type_name()
+                );
+                continue;
+            };
+            for version in versions {
+                let is_valid = version
+                    .as_str()
+                    .is_some_and(|v| v.trim_start_matches('v').parse::<Version>().is_ok());
+                if !is_valid {
+                    warn!(
+                        "Encountered invalid yanked version '{version}' \
+                        for tool '{key}' in policy manifest!\
+                        \nThe version will be ignored."
+                    );
+                }
+            }
+        }
+
+        Ok(Self { document })
+    }
+}
+
+impl ToString for PolicyManifest {
+    fn to_string(&self) -> String {
+        self.document.to_string()
+    }
+}
+
+impl Default for PolicyManifest {
+    fn default() -> Self {
+        let document = super::make_manifest_template(MANIFEST_DEFAULT_CONTENTS)
+            .parse::<DocumentMut>()
+            .expect("default manifest template should be valid");
+        Self { document }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_spec(s: &str) -> ToolSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn yanked_versions_basic() {
+        let manifest = "[yanked]\n\"author/name\" = [\"1.0.0\", \"v1.2.3\"]"
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert!(manifest.is_yanked(&new_spec("author/name@1.0.0")));
+        assert!(manifest.is_yanked(&new_spec("author/name@1.2.3")));
+        assert!(!manifest.is_yanked(&new_spec("author/name@1.1.0")));
+        assert!(!manifest.is_yanked(&new_spec("author/other@1.0.0")));
+    }
+
+    #[test]
+    fn yanked_versions_case_insensitive() {
+        let manifest = "[yanked]\n\"Author/Name\" = [\"1.0.0\"]"
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert!(manifest.is_yanked(&new_spec("author/name@1.0.0")));
+        assert!(manifest.is_yanked(&new_spec("AUTHOR/NAME@1.0.0")));
+    }
+
+    #[test]
+    fn yanked_versions_invalid_ignored() {
+        let manifest = "[yanked]\n\"author/name\" = [\"not-a-version\", 123, \"2.0.0\"]"
+            .parse::<PolicyManifest>()
+            .unwrap();
+        let id = "author/name".parse::<ToolId>().unwrap();
+        assert_eq!(manifest.yanked_versions(&id), vec![Version::new(2, 0, 0)]);
+    }
+
+    #[test]
+    fn default_has_no_yanked() {
+        let manifest = PolicyManifest::default();
+        assert!(!manifest.is_yanked(&new_spec("author/name@1.0.0")));
+    }
+
+    #[test]
+    fn headless_trust() {
+        assert_eq!(
+            PolicyManifest::default().headless_trust(),
+            HeadlessTrust::Fail
+        );
+        let manifest = "headless_trust = \"Skip\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(manifest.headless_trust(), HeadlessTrust::Skip);
+        let manifest = "headless_trust = \"maybe\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(manifest.headless_trust(), HeadlessTrust::Fail);
+    }
+
+    #[test]
+    fn confirm_download_size() {
+        assert_eq!(
+            PolicyManifest::default().confirm_download_size(),
+            Some(250 * 1024 * 1024)
+        );
+        let manifest = "confirm_download_mb = 10"
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(manifest.confirm_download_size(), Some(10 * 1024 * 1024));
+        let manifest = "confirm_download_mb = 0".parse::<PolicyManifest>().unwrap();
+        assert_eq!(manifest.confirm_download_size(), None);
+        let manifest = "confirm_download_mb = -5"
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(manifest.confirm_download_size(), Some(250 * 1024 * 1024));
+    }
+
+    #[test]
+    fn max_concurrent_downloads() {
+        assert_eq!(PolicyManifest::default().max_concurrent_downloads(), 4);
+        let manifest = "max_concurrent_downloads = 8"
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(manifest.max_concurrent_downloads(), 8);
+        let manifest = "max_concurrent_downloads = 0"
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(manifest.max_concurrent_downloads(), 4);
+    }
+
+    #[test]
+    fn metrics_opt_in() {
+        let manifest = PolicyManifest::default();
+        assert!(!manifest.metrics_enabled());
+        assert_eq!(manifest.metrics_endpoint(), None);
+
+        let manifest = "metrics_endpoint = \"https://example.com/rokit\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert!(!manifest.metrics_enabled());
+        assert_eq!(manifest.metrics_endpoint(), None);
+
+        let manifest = "metrics = true\nmetrics_endpoint = \"https://example.com/rokit\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert!(manifest.metrics_enabled());
+        assert_eq!(
+            manifest.metrics_endpoint().map(String::from),
+            Some(String::from("https://example.com/rokit"))
+        );
+
+        let manifest = "metrics = true\nmetrics_endpoint = \"ftp://example.com\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert!(manifest.metrics_enabled());
+        assert_eq!(manifest.metrics_endpoint(), None);
+    }
+
+    #[test]
+    fn registry_url() {
+        assert_eq!(PolicyManifest::default().registry_url(), None);
+
+        let manifest = "registry = \"https://tools.example.com/rokit/\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(
+            manifest.registry_url().map(String::from),
+            Some(String::from("https://tools.example.com/rokit/"))
+        );
+
+        let manifest = "registry = \"http://tools.example.com\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(manifest.registry_url(), None);
+    }
+
+    #[test]
+    fn s3_bucket_url() {
+        let manifest = PolicyManifest::default();
+        assert_eq!(manifest.s3_bucket_url(), None);
+        assert_eq!(manifest.s3_region(), None);
+
+        let manifest = "s3_bucket = \"http://localhost:9000/tools/rokit\"\ns3_region = \"auto\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(
+            manifest.s3_bucket_url().map(String::from),
+            Some(String::from("http://localhost:9000/tools/rokit"))
+        );
+        assert_eq!(manifest.s3_region().as_deref(), Some("auto"));
+
+        // Buckets must be given as path-style URLs, including the bucket name
+        let manifest = "s3_bucket = \"https://s3.example.com/\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(manifest.s3_bucket_url(), None);
+    }
+
+    #[test]
+    fn proxy_and_ca_bundle() {
+        let manifest = PolicyManifest::default();
+        assert_eq!(manifest.proxy_url(), None);
+        assert_eq!(manifest.ca_bundle_path(), None);
+
+        let manifest = "proxy = \"http://proxy.example.com:8080\"\nca_bundle = \"certs/ca.pem\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(
+            manifest.proxy_url().map(String::from),
+            Some(String::from("http://proxy.example.com:8080/"))
+        );
+        assert_eq!(
+            manifest.ca_bundle_path(),
+            Some(PathBuf::from("certs/ca.pem"))
+        );
+
+        let manifest = "proxy = \"socks5://proxy.example.com\"\nca_bundle = \" \""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(manifest.proxy_url(), None);
+        assert_eq!(manifest.ca_bundle_path(), None);
+    }
+
+    #[test]
+    fn temp_dir_setting() {
+        let mut manifest = PolicyManifest::default();
+        assert_eq!(manifest.temp_dir(), None);
+        assert!(manifest.set_setting("temp_dir", " ").is_err());
+
+        let _ = manifest.set_setting("temp_dir", "home").unwrap();
+        assert_eq!(manifest.temp_dir(), Some(PathBuf::from("home")));
+        let _ = manifest.set_setting("temp_dir", "/mnt/scratch").unwrap();
+        assert_eq!(manifest.temp_dir(), Some(PathBuf::from("/mnt/scratch")));
+    }
+
+    #[test]
+    fn mirrors_in_order() {
+        let manifest = PolicyManifest::default();
+        assert!(manifest.mirrors(ArtifactProvider::GitHub).is_empty());
+
+        let manifest = "[mirrors]\n\
+            GitHub = [\"https://a.example.com/gh\", \"not a url\", \"http://b.example.com\"]\n\
+            registry = [\"https://c.example.com\"]"
+            .parse::<PolicyManifest>()
+            .unwrap();
+        let mirrors = manifest
+            .mirrors(ArtifactProvider::GitHub)
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            mirrors,
+            vec!["https://a.example.com/gh", "http://b.example.com/"]
+        );
+        assert!(manifest.mirrors(ArtifactProvider::GitLab).is_empty());
+        assert!(manifest.mirrors(ArtifactProvider::Registry).is_empty());
+    }
+
+    #[test]
+    fn set_setting_validates() {
+        let mut manifest = PolicyManifest::default();
+        assert_eq!(manifest.get_setting("headless_trust"), None);
+
+        assert_eq!(manifest.set_setting("headless_trust", "Skip"), Ok(false));
+        assert_eq!(manifest.set_setting("confirm_download_mb", "10"), Ok(false));
+        assert_eq!(manifest.set_setting("metrics", "true"), Ok(false));
+        assert_eq!(manifest.set_setting("metrics", "false"), Ok(true));
+        assert_eq!(manifest.headless_trust(), HeadlessTrust::Skip);
+        assert_eq!(manifest.confirm_download_size(), Some(10 * 1024 * 1024));
+        assert_eq!(
+            manifest.get_setting("headless_trust").as_deref(),
+            Some("skip")
+        );
+        assert_eq!(manifest.get_setting("metrics").as_deref(), Some("false"));
+
+        assert!(manifest.set_setting("headless_trust", "maybe").is_err());
+        assert!(manifest.set_setting("confirm_download_mb", "-1").is_err());
+        assert!(manifest
+            .set_setting("max_concurrent_downloads", "0")
+            .is_err());
+        assert!(manifest
+            .set_setting("registry", "http://example.com")
+            .is_err());
+        assert!(manifest.set_setting("unknown", "value").is_err());
+
+        let invalid = "metrics = \"yes\"".parse::<PolicyManifest>().unwrap();
+        assert_eq!(invalid.get_setting("metrics"), None);
+
+        let reparsed = manifest.to_string().parse::<PolicyManifest>().unwrap();
+        assert_eq!(reparsed.headless_trust(), HeadlessTrust::Skip);
+        assert!(!reparsed.is_yanked(&new_spec("author/name@1.0.0")));
+    }
+
+    #[test]
+    fn request_policies() {
+        let manifest = PolicyManifest::default();
+        assert_eq!(manifest.request_policy(None), RequestPolicy::default());
+        assert_eq!(
+            manifest.request_policy(Some(ArtifactProvider::Npm)),
+            RequestPolicy::default()
+        );
+
+        let manifest = "timeout_secs = 30\n\
+            retries = 0\n\
+            retry_backoff_ms = -1\n\
+            [providers.npm]\n\
+            timeout_secs = 120\n\
+            retry_backoff_ms = 250\n\
+            [providers.custom]\n\
+            retries = 5"
+            .parse::<PolicyManifest>()
+            .unwrap();
+        let global = RequestPolicy {
+            timeout: Duration::from_secs(30),
+            retries: 0,
+            retry_backoff: RequestPolicy::DEFAULT_RETRY_BACKOFF,
+        };
+        assert_eq!(manifest.request_policy(None), global);
+        assert_eq!(
+            manifest.request_policy(Some(ArtifactProvider::GitHub)),
+            global
+        );
+        assert_eq!(
+            manifest.request_policy(Some(ArtifactProvider::Custom)),
+            global
+        );
+        assert_eq!(
+            manifest.request_policy(Some(ArtifactProvider::Npm)),
+            RequestPolicy {
+                timeout: Duration::from_mins(2),
+                retries: 0,
+                retry_backoff: Duration::from_millis(250),
+            }
+        );
+
+        let mut manifest = PolicyManifest::default();
+        assert_eq!(manifest.set_setting("retries", "0"), Ok(false));
+        assert!(manifest.set_setting("timeout_secs", "0").is_err());
+        assert!(manifest.set_setting("retries", "-1").is_err());
+        assert_eq!(manifest.get_setting("retries").as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn provider_urls() {
+        let manifest = PolicyManifest::default();
+        assert_eq!(manifest.api_url(ArtifactProvider::GitHub), None);
+
+        let manifest = "[providers.github]\n\
+            api_url = \"http://localhost:8080/api\"\n\
+            download_url = \"not a url\"\n\
+            [providers.registry]\n\
+            api_url = \"https://example.com\""
+            .parse::<PolicyManifest>()
+            .unwrap();
+        assert_eq!(
+            manifest.api_url(ArtifactProvider::GitHub).map(String::from),
+            Some(String::from("http://localhost:8080/api"))
+        );
+        assert_eq!(manifest.download_url(ArtifactProvider::GitHub), None);
+        assert_eq!(manifest.api_url(ArtifactProvider::GitLab), None);
+        assert_eq!(manifest.api_url(ArtifactProvider::Registry), None);
+    }
+}
@@ -3,17 +3,33 @@
 // make library consumers think that Rokit manifests are meant
 // to be displayed - they are only meant to be stringified.
 
-use std::{path::Path, str::FromStr};
+use std::str::FromStr;
 
-use toml_edit::{DocumentMut, Formatted, Item, Value};
+use semver::{Version, VersionReq};
+use toml_edit::{DocumentMut, Formatted, InlineTable, Item, KeyMut, Value};
 use tracing::warn;
+use url::Url;
 
+use crate::tool::{ReleaseChannel, ToolAlias, ToolSpec};
+
+#[cfg(feature = "runtime")]
+use std::path::Path;
+
+#[cfg(feature = "runtime")]
 use crate::{
     result::{RokitError, RokitResult},
-    tool::{ToolAlias, ToolSpec},
     util::fs::{load_from_file, save_to_file},
 };
 
+use super::{
+    parse_error::ManifestParseError,
+    taps::{is_short_spec, TapsManifest},
+    tool_options::{
+        is_valid_sha256, platform_spec_str, tool_spec_str, ToolOptions, ASSET_EXCLUDE_KEY,
+        INSTALL_DIR_KEY, PLATFORMS_KEY, PLATFORM_NAMES, PRE_REMOVE_KEY, SHA256_KEY,
+    },
+};
+
 pub const MANIFEST_FILE_NAME: &str = "rokit.toml";
 pub(super) const MANIFEST_DEFAULT_CONTENTS: &str = "
 # This file lists tools managed by Rokit, a toolchain manager for Roblox projects.
@@ -34,6 +50,7 @@ pub struct RokitManifest {
     document: DocumentMut,
 }
 
+#[cfg(feature = "runtime")]
 impl RokitManifest {
     /**
         Loads the manifest from the given directory, or creates a new one if it doesn't exist.
@@ -90,7 +107,9 @@ impl RokitManifest {
         tracing::trace!(?path, "Saving manifest");
         save_to_file(path, self.clone()).await
     }
+}
 
+impl RokitManifest {
     /**
         Checks if the manifest has a tool with the given alias.
     */
@@ -105,9 +124,39 @@ impl RokitManifest {
     */
     #[must_use]
     pub fn get_tool(&self, alias: &ToolAlias) -> Option<ToolSpec> {
+        self.get_tool_inner(alias, None)
+    }
+
+    /**
+        Gets a tool specification from the manifest by its alias, if it exists.
+
+        Short tool specifications, such as `rojo@7.4.4`, will be resolved using the given taps.
+    */
+    #[must_use]
+    pub fn get_tool_with_taps(&self, alias: &ToolAlias, taps: &TapsManifest) -> Option<ToolSpec> {
+        self.get_tool_inner(alias, Some(taps))
+    }
+
+    fn get_tool_inner(&self, alias: &ToolAlias, taps: Option<&TapsManifest>) -> Option<ToolSpec> {
         let tools = self.document.get("tools")?.as_table()?;
-        let tool_str = tools.get(alias.name())?.as_str()?;
-        tool_str.parse::<ToolSpec>().ok()
+        let tool_value = tools.get(alias.name())?.as_value()?;
+        parse_tool_spec(tool_spec_str(tool_value)?, taps)
+    }
+
+    /**
+        Gets the additional options for a tool in the manifest by its alias.
+
+        Returns default options if the tool does not exist, or if it was
+        specified using only a plain tool specification string.
+    */
+    #[must_use]
+    pub fn get_tool_options(&self, alias: &ToolAlias) -> ToolOptions {
+        let tools = self.document.get("tools").and_then(|v| v.as_table());
+        tools
+            .and_then(|t| t.get(alias.name()))
+            .and_then(|item| item.as_value())
+            .map(ToolOptions::from_value)
+            .unwrap_or_default()
     }
 
     /**
@@ -132,6 +181,86 @@ impl RokitManifest {
         }
     }
 
+    /**
+        Adds a tool to the manifest, which must stay within the given
+        version requirement when it is updated - see [`ToolOptions::version`].
+
+        If the tool already exists, this will return `false` and do nothing.
+    */
+    pub fn add_tool_with_version_req(
+        &mut self,
+        alias: &ToolAlias,
+        spec: &ToolSpec,
+        version_req: &VersionReq,
+    ) -> bool {
+        self.add_tool_with_options(alias, spec, &[("version", version_req.to_string())])
+    }
+
+    /**
+        Adds a tool to the manifest, which follows the given release channel
+        when it is updated, and optionally stays within the given version
+        requirement - see [`ToolOptions::channel`] and [`ToolOptions::version`].
+
+        If the tool already exists, this will return `false` and do nothing.
+    */
+    pub fn add_tool_with_channel(
+        &mut self,
+        alias: &ToolAlias,
+        spec: &ToolSpec,
+        channel: &ReleaseChannel,
+        version_req: Option<&VersionReq>,
+    ) -> bool {
+        let mut options = vec![("channel", channel.to_string())];
+        if let Some(version_req) = version_req {
+            options.push(("version", version_req.to_string()));
+        }
+        self.add_tool_with_options(alias, spec, &options)
+    }
+
+    /**
+        Adds a tool to the manifest, which is downloaded from
+        the given direct URL - see [`ToolOptions::url`].
+
+        If the tool already exists, this will return `false` and do nothing.
+    */
+    pub fn add_tool_with_url(&mut self, alias: &ToolAlias, spec: &ToolSpec, url: &Url) -> bool {
+        self.add_tool_with_options(alias, spec, &[("url", url.to_string())])
+    }
+
+    fn add_tool_with_options(
+        &mut self,
+        alias: &ToolAlias,
+        spec: &ToolSpec,
+        options: &[(&str, String)],
+    ) -> bool {
+        if !self.add_tool(alias, spec) {
+            return false;
+        }
+        let mut table = InlineTable::new();
+        table.insert("spec", Value::from(spec.to_string()));
+        for (key, value) in options {
+            table.insert(*key, Value::from(value.as_str()));
+        }
+        self.document["tools"][alias.name()] = Item::Value(Value::InlineTable(table));
+        true
+    }
+
+    /**
+        Removes a tool from the manifest, including all of its options.
+
+        If the tool doesn't exist, this will return `false` and do nothing.
+    */
+    pub fn remove_tool(&mut self, alias: &ToolAlias) -> bool {
+        let Some(tools) = self
+            .document
+            .get_mut("tools")
+            .and_then(|item| item.as_table_mut())
+        else {
+            return false;
+        };
+        tools.remove(alias.name()).is_some()
+    }
+
     /**
         Updates a tool in the manifest with a new tool specification.
 
@@ -143,15 +272,96 @@ impl RokitManifest {
             return false;
         }
         let tools = doc["tools"].as_table_mut().unwrap();
-        if tools.contains_value(alias.name()) {
-            tools.insert(
-                alias.name(),
-                Item::Value(Value::String(Formatted::new(spec.to_string()))),
+        if !tools.contains_value(alias.name()) {
+            return false;
+        }
+        // NOTE: Tools specified using a short name from a tap should
+        // keep using that short name, and only have their version updated
+        let spec_str = tools
+            .get(alias.name())
+            .and_then(|item| tool_spec_str(item.as_value()?))
+            .filter(|s| is_short_spec(s))
+            .and_then(|s| s.split_once('@'))
+            .map_or_else(
+                || spec.to_string(),
+                |(name, _)| format!("{}@{}", name.trim(), spec.version()),
             );
-            true
-        } else {
-            false
+        // NOTE: Tools with additional options are stored as inline
+        // tables, and we must take care to preserve those options here,
+        // and to update the spec for the current platform if it has one
+        let spec_value = Value::String(Formatted::new(spec_str));
+        match tools
+            .get_mut(alias.name())
+            .and_then(|item| item.as_inline_table_mut())
+        {
+            Some(table) if platform_spec_str(table).is_some() => {
+                if platform_spec_str(table) != spec_value.as_str() {
+                    table.remove(SHA256_KEY);
+                }
+                let platforms = table
+                    .get_mut(PLATFORMS_KEY)
+                    .and_then(Value::as_inline_table_mut)
+                    .expect("platform spec exists");
+                platforms.insert(std::env::consts::OS, spec_value);
+            }
+            Some(table) => {
+                // NOTE: Pinned checksums only apply to the exact version that
+                // was pinned, and would make any other version fail to install
+                if table.get("spec").and_then(Value::as_str) != spec_value.as_str() {
+                    table.remove(SHA256_KEY);
+                }
+                table.insert("spec", spec_value);
+            }
+            None => {
+                tools.insert(alias.name(), Item::Value(spec_value));
+            }
         }
+        true
+    }
+
+    /**
+        Normalizes the formatting of the manifest, while preserving any comments.
+
+        - Tools are sorted by their alias.
+        - Tool specifications are written in their canonical form, such as `rojo-rbx/rojo@7.4.4`.
+        - Keys are unquoted where possible, and all strings use double quotes.
+
+        Returns `true` if the manifest was changed.
+    */
+    pub fn normalize(&mut self) -> bool {
+        let before = self.document.to_string();
+
+        let tools = self
+            .document
+            .get_mut("tools")
+            .and_then(|item| item.as_table_mut());
+        if let Some(tools) = tools {
+            tools.sort_values();
+            for (mut key, item) in tools.iter_mut() {
+                normalize_key(&mut key);
+                match item.as_value_mut() {
+                    Some(Value::InlineTable(table)) => {
+                        for (mut key, value) in table.iter_mut() {
+                            normalize_key(&mut key);
+                            if key.get() == "spec" {
+                                normalize_spec_value(value);
+                            } else if let Value::InlineTable(platforms) = value {
+                                for (mut key, value) in platforms.iter_mut() {
+                                    normalize_key(&mut key);
+                                    normalize_spec_value(value);
+                                }
+                            } else {
+                                normalize_string_values(value);
+                            }
+                        }
+                    }
+                    Some(value) => normalize_spec_value(value),
+                    None => {}
+                }
+            }
+        }
+
+        self.document.to_string() != before
     }
 
     /**
@@ -161,23 +371,105 @@ impl RokitManifest {
     */
     #[must_use]
     pub fn tool_specs(&self) -> Vec<(ToolAlias, ToolSpec)> {
+        self.tool_specs_inner(None)
+    }
+
+    /**
+        Returns all valid tool specifications in the manifest.
+
+        Short tool specifications, such as `rojo@7.4.4`, will be resolved using
+        the given taps, and any that can not be resolved will be ignored.
+    */
+    #[must_use]
+    pub fn tool_specs_with_taps(&self, taps: &TapsManifest) -> Vec<(ToolAlias, ToolSpec)> {
+        self.tool_specs_inner(Some(taps))
+    }
+
+    fn tool_specs_inner(&self, taps: Option<&TapsManifest>) -> Vec<(ToolAlias, ToolSpec)> {
         let tools = self.document.get("tools").and_then(|v| v.as_table());
         let tool_kv_pairs = tools.map(|t| t.get_values()).unwrap_or_default();
         tool_kv_pairs
             .into_iter()
             .filter_map(|(keys, value)| {
                 let alias = keys.last()?.parse::<ToolAlias>().ok()?;
-                let spec = value.as_str()?.parse::<ToolSpec>().ok()?;
+                let spec = parse_tool_spec(tool_spec_str(value)?, taps)?;
                 Some((alias, spec))
             })
             .collect()
     }
+
+    /**
+        Gets the version of Rokit that this manifest is pinned to, if any.
+
+        When Rokit runs in a directory with a manifest that pins a different
+        version, it will download and delegate to the pinned version instead.
+    */
+    #[must_use]
+    pub fn rokit_version(&self) -> Option<Version> {
+        let version = self.document.get("rokit_version")?.as_str()?;
+        version.trim().trim_start_matches('v').parse().ok()
+    }
+
+    /**
+        Gets the setup command for this manifest, if any.
+
+        The setup command is given as an array of strings using the top-level
+        `setup` key, where the first string is the alias of a tool in the
+        manifest, and is run once after creating a project using `rokit new`:
+
+        ```toml
+        setup = ["lune", "run", "setup"]
+        ```
+    */
+    #[must_use]
+    pub fn setup_command(&self) -> Option<Vec<String>> {
+        parse_setup_command(self.document.get("setup")?)
+    }
+
+    /**
+        Pins the manifest to the given version of Rokit,
+        or removes any existing pin if `None` is given.
+
+        See [`RokitManifest::rokit_version`] for more information.
+    */
+    pub fn set_rokit_version(&mut self, version: Option<&Version>) {
+        let doc = self.document.as_table_mut();
+        match version {
+            Some(version) => {
+                doc.insert("rokit_version", toml_edit::value(version.to_string()));
+            }
+            None => {
+                doc.remove("rokit_version");
+            }
+        }
+    }
+
+    /**
+        Returns additional options for all valid tools in the manifest.
+
+        This will ignore any tools that are not valid tool specifications.
+    */
+    #[must_use]
+    pub fn tool_options(&self) -> Vec<(ToolAlias, ToolOptions)> {
+        let tools = self.document.get("tools").and_then(|v| v.as_table());
+        let tool_kv_pairs = tools.map(|t| t.get_values()).unwrap_or_default();
+        tool_kv_pairs
+            .into_iter()
+            .filter_map(|(keys, value)| {
+                let alias = keys.last()?.parse::<ToolAlias>().ok()?;
+                tool_spec_str(value)?;
+                Some((alias, ToolOptions::from_value(value)))
+            })
+            .collect()
+    }
 }
 
 impl FromStr for RokitManifest {
-    type Err = toml_edit::TomlError;
+    type Err = ManifestParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut document = s.parse::<DocumentMut>()?;
+        let mut document = s
+            .parse::<DocumentMut>()
+            .map_err(|e| ManifestParseError::new(s, &e))?;
 
         /*
             Check for invalid tool aliases and specs and warn the user about them
@@ -211,6 +503,31 @@ impl FromStr for RokitManifest {
             }
         };
 
+        // Check the pinned Rokit version, if any.
+        if let Some(version) = document.get("rokit_version") {
+            let parsed = version
+                .as_str()
+                .map(|v| v.trim().trim_start_matches('v').parse::<Version>());
+            if !matches!(parsed, Some(Ok(_))) {
+                warn!(
+                    "Encountered an invalid Rokit version in a Rokit manifest!\
+                    \nThe version will be ignored and the current version of Rokit will be used.\
+                    \nExpected: String containing a semver version, such as \"1.0.0\""
+                );
+            }
+        }
+
+        // Check the setup command, if any.
+        if let Some(setup) = document.get("setup") {
+            if parse_setup_command(setup).is_none() {
+                warn!(
+                    "Encountered an invalid setup command in a Rokit manifest!\
+                    \nThe setup command will be ignored.\
+                    \nExpected: Non-empty array of strings"
+                );
+            }
+        }
+
         // Check all of the tools.
         let tool_kv_pairs = tools.map(|t| t.get_values()).unwrap_or_default();
         for (keys, value) in tool_kv_pairs {
@@ -221,17 +538,146 @@ impl FromStr for RokitManifest {
                     \nError: {e}",
                 );
             };
-            let Some(spec_str) = value.as_str() else {
+            let Some(spec_str) = tool_spec_str(value) else {
                 warn!(
                     "A tool spec with alias '{}' could not be parsed!\
                     \nThe tool will be ignored and may not be available.\
-                    \nExpected: String\
+                    \nExpected: String, or inline table with a 'spec' key\
                     \nActual: {}",
                     keys.into_iter().last().unwrap(),
                     value.type_name()
                 );
                 continue;
             };
+            if let Some(table) = value.as_inline_table() {
+                let options = ToolOptions::from_table_like(table);
+                let platforms = table.get(PLATFORMS_KEY).and_then(Value::as_inline_table);
+                let asset_exclude = table.get(ASSET_EXCLUDE_KEY).and_then(Value::as_array);
+                let sha256 = table.get(SHA256_KEY);
+                // NOTE: Options that can not be told apart from their defaults once
+                // parsed, such as booleans and checksums, are checked as they are
+                let checked_options = [
+                    (
+                        "check",
+                        options.check.is_some(),
+                        "The check will be ignored.",
+                        "Array of strings",
+                    ),
+                    (
+                        PRE_REMOVE_KEY,
+                        options.pre_remove.is_some(),
+                        "The command will not run when the tool is removed.",
+                        "Array of strings",
+                    ),
+                    (
+                        PLATFORMS_KEY,
+                        platforms.is_some_and(|platforms| {
+                            platforms.iter().all(|(platform, spec)| {
+                                PLATFORM_NAMES.contains(&platform)
+                                    && spec.as_str().is_some_and(|spec| {
+                                        is_short_spec(spec) || spec.parse::<ToolSpec>().is_ok()
+                                    })
+                            })
+                        }),
+                        "Some platform specifications may be ignored.",
+                        "Inline table mapping 'windows', 'macos', or 'linux' to tool specs",
+                    ),
+                    (
+                        "installs",
+                        options.installs.is_some(),
+                        "The tool will be installed as an executable.",
+                        "\"executable\" or \"plugin\"",
+                    ),
+                    (
+                        ASSET_EXCLUDE_KEY,
+                        asset_exclude.is_some_and(|patterns| {
+                            patterns.iter().all(|pattern| pattern.as_str().is_some())
+                        }),
+                        "No assets will be excluded for the tool.",
+                        "Array of strings, such as [\"*.deb\", \"*.msi\"]",
+                    ),
+                    (
+                        "extras",
+                        table.get("extras").and_then(Value::as_bool).is_some(),
+                        "Man pages and completions will not be installed.",
+                        "Boolean",
+                    ),
+                    (
+                        INSTALL_DIR_KEY,
+                        table.get(INSTALL_DIR_KEY).and_then(Value::as_bool).is_some(),
+                        "Only the executable of the tool will be installed.",
+                        "Boolean",
+                    ),
+                    (
+                        "entrypoint",
+                        options.entrypoint.is_some(),
+                        "The executable will be looked for at the root of the tool directory.",
+                        "Relative path to the executable, used with 'install-dir = true'",
+                    ),
+                    (
+                        "channel",
+                        options.channel.is_some(),
+                        "The tool will follow the stable channel.",
+                        "String containing a channel name, such as \"beta\"",
+                    ),
+                    (
+                        "resolution",
+                        options.resolution.is_some(),
+                        "The tool will update to the release marked as latest.",
+                        "\"latest\" or \"newest-tag\"",
+                    ),
+                    (
+                        "url",
+                        options.url.is_some(),
+                        "The tool will be ignored and may not be available.",
+                        "String containing an HTTPS URL",
+                    ),
+                    (
+                        "mirror",
+                        options.mirror.is_some(),
+                        "The tool will only be downloaded from its provider.",
+                        "String containing an HTTPS URL",
+                    ),
+                    (
+                        "assets",
+                        options.assets.is_some(),
+                        "The default template for release assets will be used.",
+                        "String containing an HTTPS URL template",
+                    ),
+                    (
+                        SHA256_KEY,
+                        sha256.is_some_and(|sha256| match sha256.as_inline_table() {
+                            Some(checksums) => checksums.iter().all(|(platform, checksum)| {
+                                PLATFORM_NAMES.contains(&platform)
+                                    && checksum.as_str().is_some_and(is_valid_sha256)
+                            }),
+                            None => sha256.as_str().is_some_and(is_valid_sha256),
+                        }),
+                        "Installing the tool will fail until the checksum is fixed.",
+                        "String containing a SHA-256 checksum, or inline table mapping 'windows', 'macos', or 'linux' to checksums",
+                    ),
+                    (
+                        "version",
+                        options.version.is_some(),
+                        "The tool will update to the latest version.",
+                        "String containing a version requirement, such as \"^6\"",
+                    ),
+                ];
+                for (key, is_valid, consequence, expected) in checked_options {
+                    if table.contains_key(key) && !is_valid {
+                        warn!(
+                            "A tool with alias '{}' has an invalid '{key}' option!\
+                            \n{consequence}\
+                            \nExpected: {expected}",
+                            keys.last().unwrap(),
+                        );
+                    }
+                }
+            }
+            // NOTE: Short specs can only be checked once they are resolved using taps
+            if is_short_spec(spec_str) {
+                continue;
+            }
             if let Err(e) = spec_str.parse::<ToolSpec>() {
                 warn!(
                     "A tool spec with alias '{}' could not be parsed!\
@@ -246,6 +692,78 @@ impl FromStr for RokitManifest {
     }
 }
 
+fn canonical_spec_str(spec_str: &str) -> Option<String> {
+    let (name, version) = spec_str.split_once('@')?;
+    let (name, version) = (name.trim(), version.trim());
+    // NOTE: Versions are commonly written with a leading 'v', same as git
+    // tags, but tags given explicitly using "tag:" must be kept verbatim
+    let version = match version.strip_prefix('v') {
+        Some(stripped) if stripped.starts_with(|c: char| c.is_ascii_digit()) => stripped,
+        _ => version,
+    };
+    if is_short_spec(spec_str) {
+        let version = version.parse::<Version>().ok()?;
+        Some(format!("{name}@{version}"))
+    } else {
+        format!("{name}@{version}")
+            .parse::<ToolSpec>()
+            .ok()
+            .map(|s| s.to_string())
+    }
+}
+
+fn normalize_key(key: &mut KeyMut<'_>) {
+    // NOTE: Formatting a key may reset its decor, which
+    // contains any comments on the lines above the key
+    let decor = key.leaf_decor().clone();
+    key.fmt();
+    *key.leaf_decor_mut() = decor;
+}
+
+fn normalize_spec_value(value: &mut Value) {
+    if let Some(canonical) = value.as_str().and_then(canonical_spec_str) {
+        replace_string_value(value, canonical);
+    } else {
+        normalize_string_values(value);
+    }
+}
+
+fn normalize_string_values(value: &mut Value) {
+    match value {
+        Value::String(s) => {
+            let s = s.value().clone();
+            replace_string_value(value, s);
+        }
+        Value::Array(array) => array.iter_mut().for_each(normalize_string_values),
+        _ => {}
+    }
+}
+
+fn replace_string_value(value: &mut Value, new: String) {
+    // NOTE: Creating a new value resets its quoting to the default, double
+    // quotes, but we must keep the decor which may contain comments / spacing
+    let decor = value.decor().clone();
+    *value = Value::from(new);
+    *value.decor_mut() = decor;
+}
+
+fn parse_tool_spec(spec_str: &str, taps: Option<&TapsManifest>) -> Option<ToolSpec> {
+    match taps {
+        Some(taps) if is_short_spec(spec_str) => {
+            let spec = taps.resolve_spec(spec_str);
+            if spec.is_none() {
+                warn!(
+                    "The tool '{spec_str}' was not found in any tap!\
+                    \nThe tool will be ignored and may not be available.\
+                    \nAdd a tap containing the tool using `rokit tap add <url>`."
+                );
+            }
+            spec
+        }
+        _ => spec_str.parse::<ToolSpec>().ok(),
+    }
+}
+
 impl ToString for RokitManifest {
     fn to_string(&self) -> String {
         self.document.to_string()
@@ -260,3 +778,277 @@ impl Default for RokitManifest {
         Self { document }
     }
 }
+
+fn parse_setup_command(item: &Item) -> Option<Vec<String>> {
+    item.as_array()?
+        .iter()
+        .map(|arg| arg.as_str().map(ToString::to_string))
+        .collect::<Option<Vec<_>>>()
+        .filter(|args| !args.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tool::ResolutionStrategy;
+
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        let mut manifest = RokitManifest::from_str(
+            "[tools]\n\
+            \"rojo\" = { spec = 'rojo-rbx/rojo@v7.4.4', check = ['rojo', \"--version\"] }\n\
+            # Lune is used for scripts\n\
+            lune = 'lune-org/lune@0.8.9'\n",
+        )
+        .unwrap();
+
+        assert!(manifest.normalize());
+        assert_eq!(
+            manifest.to_string(),
+            "[tools]\n\
+            # Lune is used for scripts\n\
+            lune = \"lune-org/lune@0.8.9\"\n\
+            rojo = { spec = \"rojo-rbx/rojo@7.4.4\", check = [\"rojo\", \"--version\"] }\n",
+        );
+        assert!(!manifest.normalize());
+    }
+
+    #[test]
+    fn setup_command() {
+        let manifest =
+            RokitManifest::from_str("setup = [\"lune\", \"run\", \"setup\"]\n[tools]\n").unwrap();
+        assert_eq!(
+            manifest.setup_command(),
+            Some(vec![
+                String::from("lune"),
+                String::from("run"),
+                String::from("setup")
+            ])
+        );
+
+        let manifest = RokitManifest::from_str("setup = []\n[tools]\n").unwrap();
+        assert_eq!(manifest.setup_command(), None);
+
+        let manifest = RokitManifest::from_str("setup = \"lune run setup\"\n").unwrap();
+        assert_eq!(manifest.setup_command(), None);
+    }
+
+    #[test]
+    fn platform_specs() {
+        let contents = format!(
+            "[tools]\n\
+            stylua = {{ spec = \"a/stylua@1.0.0\", platforms = {{ {} = \"b/stylua@1.0.0\" }} }}\n",
+            std::env::consts::OS
+        );
+        let mut manifest = RokitManifest::from_str(&contents).unwrap();
+        let alias: ToolAlias = "stylua".parse().unwrap();
+
+        let spec = manifest.get_tool(&alias).unwrap();
+        assert_eq!(spec.to_string(), "b/stylua@1.0.0");
+
+        let updated: ToolSpec = "b/stylua@2.0.0".parse().unwrap();
+        assert!(manifest.update_tool(&alias, &updated));
+        assert_eq!(manifest.get_tool(&alias), Some(updated));
+        assert!(manifest.to_string().contains("spec = \"a/stylua@1.0.0\""));
+    }
+
+    #[test]
+    fn url_tools() {
+        let mut manifest = RokitManifest::from_str("[tools]\n").unwrap();
+        let alias: ToolAlias = "tool".parse().unwrap();
+        let spec: ToolSpec = "url:example.com/tool@1.2.3".parse().unwrap();
+        let url: Url = "https://example.com/tool-v1.2.3-linux-x64.tar.gz"
+            .parse()
+            .unwrap();
+
+        assert!(manifest.add_tool_with_url(&alias, &spec, &url));
+        assert_eq!(manifest.get_tool(&alias), Some(spec));
+        assert_eq!(manifest.get_tool_options(&alias).url, Some(url));
+
+        let insecure = RokitManifest::from_str(
+            "[tools]\ntool = { spec = \"url:example.com/tool@1.0.0\", url = \"http://example.com/tool\" }\n",
+        )
+        .unwrap();
+        assert_eq!(insecure.get_tool_options(&alias).url, None);
+    }
+
+    #[test]
+    fn install_dir_tools() {
+        let manifest = RokitManifest::from_str(
+            "[tools]\n\
+            tool = { spec = \"a/tool@1.0.0\", install-dir = true, entrypoint = \"bin/tool\" }\n\
+            escaping = { spec = \"a/escaping@1.0.0\", install-dir = true, entrypoint = \"../tool\" }\n\
+            plain = { spec = \"a/plain@1.0.0\", entrypoint = \"bin/plain\" }\n",
+        )
+        .unwrap();
+
+        let options = manifest.get_tool_options(&"tool".parse().unwrap());
+        assert!(options.install_dir);
+        assert_eq!(options.entrypoint.as_deref(), Some("bin/tool"));
+
+        let escaping = manifest.get_tool_options(&"escaping".parse().unwrap());
+        assert!(escaping.install_dir);
+        assert_eq!(escaping.entrypoint, None);
+
+        let plain = manifest.get_tool_options(&"plain".parse().unwrap());
+        assert!(!plain.install_dir);
+        assert_eq!(plain.entrypoint, None);
+    }
+
+    #[test]
+    fn tool_mirrors() {
+        let manifest = RokitManifest::from_str(
+            "[tools]\n\
+            tool = { spec = \"a/tool@1.0.0\", mirror = \"https://mirror.example.com/github\" }\n\
+            insecure = { spec = \"a/insecure@1.0.0\", mirror = \"http://mirror.example.com\" }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            manifest
+                .get_tool_options(&"tool".parse().unwrap())
+                .mirror
+                .map(String::from),
+            Some(String::from("https://mirror.example.com/github"))
+        );
+        assert_eq!(
+            manifest
+                .get_tool_options(&"insecure".parse().unwrap())
+                .mirror,
+            None
+        );
+    }
+
+    #[test]
+    fn git_asset_templates() {
+        let manifest = RokitManifest::from_str(
+            "[tools]\n\
+            tool = { spec = \"git:codeberg.org/a/tool@1.0.0\", assets = \"https://dl.example.com/{tag}/tool.zip\" }\n\
+            insecure = { spec = \"git:codeberg.org/a/insecure@1.0.0\", assets = \"http://dl.example.com/{tag}\" }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.get_tool_options(&"tool".parse().unwrap()).assets,
+            Some(String::from("https://dl.example.com/{tag}/tool.zip"))
+        );
+        assert_eq!(
+            manifest
+                .get_tool_options(&"insecure".parse().unwrap())
+                .assets,
+            None
+        );
+    }
+
+    #[test]
+    fn side_by_side_versions() {
+        let mut manifest = RokitManifest::from_str("[tools]\ntool = \"a/tool@7.1.0\"\n").unwrap();
+        let alias: ToolAlias = "tool6".parse().unwrap();
+        let spec: ToolSpec = "a/tool@6.4.2".parse().unwrap();
+        let req: VersionReq = "6".parse().unwrap();
+
+        assert!(manifest.add_tool_with_version_req(&alias, &spec, &req));
+        assert!(!manifest.add_tool_with_version_req(&alias, &spec, &req));
+        assert_eq!(manifest.get_tool(&alias), Some(spec));
+        assert_eq!(manifest.get_tool_options(&alias).version, Some(req));
+        assert_eq!(manifest.tool_specs().len(), 2);
+
+        let updated: ToolSpec = "a/tool@6.5.0".parse().unwrap();
+        assert!(manifest.update_tool(&alias, &updated));
+        assert_eq!(
+            manifest.to_string(),
+            "[tools]\n\
+            tool = \"a/tool@7.1.0\"\n\
+            tool6 = { spec = \"a/tool@6.5.0\", version = \"^6\" }\n",
+        );
+    }
+
+    #[test]
+    fn resolution_strategies() {
+        let manifest = RokitManifest::from_str(
+            "[tools]\n\
+            tool = { spec = \"a/tool@8.1.0\", resolution = \"newest-tag\" }\n\
+            invalid = { spec = \"a/invalid@1.0.0\", resolution = \"oldest\" }\n\
+            plain = \"a/plain@1.0.0\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            manifest
+                .get_tool_options(&"tool".parse().unwrap())
+                .resolution,
+            Some(ResolutionStrategy::NewestTag)
+        );
+        assert_eq!(
+            manifest
+                .get_tool_options(&"invalid".parse().unwrap())
+                .resolution,
+            None
+        );
+        assert_eq!(
+            manifest
+                .get_tool_options(&"plain".parse().unwrap())
+                .resolution,
+            None
+        );
+    }
+
+    #[test]
+    fn pinned_checksums() {
+        let sha256 = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+        let mut manifest = RokitManifest::from_str(&format!(
+            "[tools]\n\
+            tool = {{ spec = \"a/tool@1.0.0\", sha256 = \"{sha256}\" }}\n\
+            other = {{ spec = \"a/other@1.0.0\", sha256 = {{ {} = \"{sha256}\" }} }}\n\
+            missing = {{ spec = \"a/missing@1.0.0\", sha256 = {{ {} = \"{sha256}\" }} }}\n",
+            std::env::consts::OS,
+            if std::env::consts::OS == "linux" {
+                "windows"
+            } else {
+                "linux"
+            },
+        ))
+        .unwrap();
+        let alias: ToolAlias = "tool".parse().unwrap();
+        let lowercase = Some(sha256.to_ascii_lowercase());
+
+        assert_eq!(manifest.get_tool_options(&alias).sha256, lowercase);
+        assert_eq!(
+            manifest.get_tool_options(&"other".parse().unwrap()).sha256,
+            lowercase
+        );
+        assert_eq!(
+            manifest
+                .get_tool_options(&"missing".parse().unwrap())
+                .sha256,
+            None
+        );
+
+        // Checksums only apply to the version they were pinned for
+        assert!(manifest.update_tool(&alias, &"a/tool@1.0.0".parse().unwrap()));
+        assert_eq!(manifest.get_tool_options(&alias).sha256, lowercase);
+        assert!(manifest.update_tool(&alias, &"a/tool@2.0.0".parse().unwrap()));
+        assert_eq!(manifest.get_tool_options(&alias).sha256, None);
+    }
+
+    #[test]
+    fn remove_tools() {
+        let mut manifest = RokitManifest::from_str(
+            "[tools]\n\
+            rojo = \"rojo-rbx/rojo@7.4.4\"\n\
+            tool = { spec = \"a/tool@1.0.0\", pre-remove = [\"tool\", \"unregister\"] }\n",
+        )
+        .unwrap();
+        let alias: ToolAlias = "tool".parse().unwrap();
+
+        assert_eq!(
+            manifest.get_tool_options(&alias).pre_remove,
+            Some(vec![String::from("tool"), String::from("unregister")])
+        );
+        assert!(manifest.remove_tool(&alias));
+        assert!(!manifest.remove_tool(&alias));
+        assert!(!manifest.has_tool(&alias));
+        assert_eq!(
+            manifest.to_string(),
+            "[tools]\nrojo = \"rojo-rbx/rojo@7.4.4\"\n"
+        );
+    }
+}
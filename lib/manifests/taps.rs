@@ -0,0 +1,345 @@
+#![allow(clippy::to_string_trait_impl)]
+// NOTE: We don't want to implement Display here since it may
+// make library consumers think that taps manifests are meant
+// to be displayed - they are only meant to be stringified.
+
+use std::str::FromStr;
+
+use semver::Version;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table};
+use tracing::warn;
+
+use crate::{
+    sources::TapIndex,
+    tool::{ToolId, ToolSpec},
+};
+
+use super::parse_error::ManifestParseError;
+
+#[cfg(feature = "runtime")]
+use std::path::Path;
+
+#[cfg(feature = "runtime")]
+use crate::{
+    result::{RokitError, RokitResult},
+    util::fs::{load_from_file, save_to_file},
+};
+
+pub const MANIFEST_FILE_NAME: &str = "taps.toml";
+pub(super) const MANIFEST_DEFAULT_CONTENTS: &str = "
+# This file lists taps used by Rokit, a toolchain manager for Roblox projects.
+# For more information, see <|REPOSITORY_URL|>
+
+# Taps are registries of short tool names, which make it possible to write
+# `rojo@7.4.4` instead of `rojo-rbx/rojo@7.4.4` in Rokit manifests.
+# New taps can be added by running `rokit tap add <url>` in a terminal.
+# Taps are searched in the order they are listed in this file.
+";
+
+/**
+    Taps manifest file.
+
+    Lists taps - registries of short tool names - and
+    the tools they contained when they were last fetched.
+*/
+#[derive(Debug, Clone)]
+pub struct TapsManifest {
+    document: DocumentMut,
+}
+
+#[cfg(feature = "runtime")]
+impl TapsManifest {
+    /**
+        Loads the manifest from the given directory, or creates a new one if it doesn't exist.
+
+        If the manifest doesn't exist, a new one will be created with default contents and saved.
+
+        See [`TapsManifest::load`] and [`TapsManifest::save`] for more information.
+
+        # Errors
+
+        - If the manifest file could not be loaded or created.
+    */
+    pub async fn load_or_create(dir: impl AsRef<Path>) -> RokitResult<Self> {
+        let path = dir.as_ref().join(MANIFEST_FILE_NAME);
+        match load_from_file(path).await {
+            Ok(manifest) => Ok(manifest),
+            Err(RokitError::FileNotFound(_)) => {
+                let new = Self::default();
+                new.save(dir).await?;
+                Ok(new)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /**
+        Loads the manifest from the given directory.
+
+        This will search for a file named `taps.toml` in the given directory.
+
+        # Errors
+
+        - If the manifest file could not be loaded.
+    */
+    #[tracing::instrument(skip(dir), level = "trace")]
+    pub async fn load(dir: impl AsRef<Path>) -> RokitResult<Self> {
+        let path = dir.as_ref().join(MANIFEST_FILE_NAME);
+        tracing::trace!(?path, "Loading manifest");
+        load_from_file(path).await
+    }
+
+    /**
+        Saves the manifest to the given directory.
+
+        This will write the manifest to a file named `taps.toml` in the given directory.
+
+        # Errors
+
+        - If the manifest file could not be saved.
+    */
+    #[tracing::instrument(skip(self, dir), level = "trace")]
+    pub async fn save(&self, dir: impl AsRef<Path>) -> RokitResult<()> {
+        let path = dir.as_ref().join(MANIFEST_FILE_NAME);
+        tracing::trace!(?path, "Saving manifest");
+        save_to_file(path, self.clone()).await
+    }
+}
+
+impl TapsManifest {
+    fn taps(&self) -> impl Iterator<Item = &Table> {
+        self.document
+            .get("taps")
+            .and_then(Item::as_array_of_tables)
+            .into_iter()
+            .flat_map(ArrayOfTables::iter)
+    }
+
+    /**
+        Gets the URLs of all taps in the manifest, in resolution order.
+    */
+    #[must_use]
+    pub fn tap_urls(&self) -> Vec<String> {
+        self.taps()
+            .filter_map(|tap| tap.get("url")?.as_str())
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /**
+        Adds a tap to the manifest, together with the contents of its index.
+
+        If the tap already exists, its tools will be replaced
+        with the ones in the given index, and its position in the
+        resolution order will be kept. Returns `true` if the tap is new.
+    */
+    pub fn add_tap(&mut self, url: &str, index: &TapIndex) -> bool {
+        let mut tools = Table::new();
+        for (name, id) in index.tools() {
+            tools.insert(name, toml_edit::value(id.to_string()));
+        }
+
+        let doc = self.document.as_table_mut();
+        if !doc.get("taps").is_some_and(Item::is_array_of_tables) {
+            doc.insert("taps", Item::ArrayOfTables(ArrayOfTables::new()));
+        }
+        let taps = doc["taps"].as_array_of_tables_mut().unwrap();
+
+        let existing = taps
+            .iter_mut()
+            .find(|tap| tap.get("url").and_then(Item::as_str) == Some(url));
+        if let Some(tap) = existing {
+            tap.insert("tools", Item::Table(tools));
+            false
+        } else {
+            let mut tap = Table::new();
+            tap.insert("url", toml_edit::value(url));
+            tap.insert("tools", Item::Table(tools));
+            taps.push(tap);
+            true
+        }
+    }
+
+    /**
+        Removes a tap from the manifest.
+
+        Returns `true` if the tap was found and removed.
+    */
+    pub fn remove_tap(&mut self, url: &str) -> bool {
+        let Some(taps) = self
+            .document
+            .get_mut("taps")
+            .and_then(Item::as_array_of_tables_mut)
+        else {
+            return false;
+        };
+        let len_before = taps.len();
+        taps.retain(|tap| tap.get("url").and_then(Item::as_str) != Some(url));
+        taps.len() != len_before
+    }
+
+    /**
+        Resolves a short tool name into a full tool id, using
+        the first tap in the manifest that contains the name.
+
+        Short names are not case sensitive.
+    */
+    #[must_use]
+    pub fn resolve(&self, name: &str) -> Option<ToolId> {
+        self.taps().find_map(|tap| {
+            let tools = tap.get("tools")?.as_table()?;
+            tools
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .and_then(|(_, value)| value.as_str()?.parse().ok())
+        })
+    }
+
+    /**
+        Resolves a short tool specification, such as `rojo@7.4.4`,
+        into a full tool specification using [`TapsManifest::resolve`].
+
+        Returns `None` if the string is not a short tool specification,
+        or if no tap contains the short name of the tool.
+    */
+    #[must_use]
+    pub fn resolve_spec(&self, spec: &str) -> Option<ToolSpec> {
+        let (name, version) = spec.trim().split_once('@')?;
+        if name.contains('/') || name.contains(':') {
+            return None;
+        }
+        let version = version.trim_start_matches('v').parse::<Version>().ok()?;
+        Some((self.resolve(name)?, version).into())
+    }
+}
+
+/**
+    Checks if the given string is a short tool specification, such as `rojo@7.4.4`,
+    which must be resolved through taps - see [`TapsManifest::resolve_spec`].
+*/
+#[must_use]
+pub fn is_short_spec(spec: &str) -> bool {
+    spec.trim()
+        .split_once('@')
+        .is_some_and(|(name, _)| !name.is_empty() && !name.contains('/') && !name.contains(':'))
+}
+
+impl FromStr for TapsManifest {
+    type Err = ManifestParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let document = s
+            .parse::<DocumentMut>()
+            .map_err(|e| ManifestParseError::new(s, &e))?;
+
+        /*
+            Check for invalid taps and warn the user about them as a
+            preprocessing step. We do this here instead of when accessed
+            in manifest methods to avoid duplicate warnings being emitted.
+        */
+        let manifest = Self { document };
+        for tap in manifest.taps() {
+            let Some(url) = tap.get("url").and_then(Item::as_str) else {
+                warn!(
+                    "Encountered a tap without a 'url' in taps manifest!\
+                    \nThe tap will be ignored."
+                );
+                continue;
+            };
+            let tools = tap.get("tools").and_then(Item::as_table);
+            for (name, value) in tools.into_iter().flat_map(Table::iter) {
+                if value
+                    .as_str()
+                    .and_then(|s| s.parse::<ToolId>().ok())
+                    .is_none()
+                {
+                    warn!(
+                        "Encountered invalid tool id for '{name}' in tap '{url}'!\
+                        \nThe tool will be ignored.\
+                        \nRun `rokit tap add {url}` to fetch the tap again."
+                    );
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+}
+
+impl ToString for TapsManifest {
+    fn to_string(&self) -> String {
+        self.document.to_string()
+    }
+}
+
+impl Default for TapsManifest {
+    fn default() -> Self {
+        let document = super::make_manifest_template(MANIFEST_DEFAULT_CONTENTS)
+            .parse::<DocumentMut>()
+            .expect("default manifest template should be valid");
+        Self { document }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_index(s: &str) -> TapIndex {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn resolve_in_order() {
+        let mut manifest = TapsManifest::default();
+        manifest.add_tap("https://a", &new_index("[tools]\nrojo = \"rojo-rbx/rojo\""));
+        manifest.add_tap("https://b", &new_index("[tools]\nrojo = \"other/rojo\""));
+        assert_eq!(
+            manifest.resolve("rojo"),
+            Some("rojo-rbx/rojo".parse().unwrap())
+        );
+        assert_eq!(
+            manifest.resolve("ROJO"),
+            Some("rojo-rbx/rojo".parse().unwrap())
+        );
+        assert_eq!(manifest.resolve("lune"), None);
+    }
+
+    #[test]
+    fn resolve_spec() {
+        let mut manifest = TapsManifest::default();
+        manifest.add_tap("https://a", &new_index("[tools]\nrojo = \"rojo-rbx/rojo\""));
+        assert_eq!(
+            manifest.resolve_spec("rojo@7.4.4"),
+            Some("rojo-rbx/rojo@7.4.4".parse().unwrap())
+        );
+        assert_eq!(manifest.resolve_spec("rojo-rbx/rojo@7.4.4"), None);
+        assert_eq!(manifest.resolve_spec("rojo"), None);
+        assert_eq!(manifest.resolve_spec("lune@0.8.9"), None);
+    }
+
+    #[test]
+    fn add_existing_replaces_tools() {
+        let mut manifest = TapsManifest::default();
+        assert!(manifest.add_tap("https://a", &new_index("[tools]\nrojo = \"a/rojo\"")));
+        assert!(!manifest.add_tap("https://a", &new_index("[tools]\nrojo = \"b/rojo\"")));
+        assert_eq!(manifest.tap_urls(), vec!["https://a".to_string()]);
+        assert_eq!(manifest.resolve("rojo"), Some("b/rojo".parse().unwrap()));
+    }
+
+    #[test]
+    fn remove_tap() {
+        let mut manifest = TapsManifest::default();
+        manifest.add_tap("https://a", &new_index("[tools]\nrojo = \"a/rojo\""));
+        assert!(manifest.remove_tap("https://a"));
+        assert!(!manifest.remove_tap("https://a"));
+        assert_eq!(manifest.resolve("rojo"), None);
+    }
+
+    #[test]
+    fn short_specs() {
+        assert!(is_short_spec("rojo@7.4.4"));
+        assert!(!is_short_spec("rojo-rbx/rojo@7.4.4"));
+        assert!(!is_short_spec("rojo"));
+        assert!(!is_short_spec("@7.4.4"));
+    }
+}
@@ -0,0 +1,413 @@
+use std::{
+    env::consts::OS as CURRENT_OS,
+    path::{Component, Path},
+};
+
+use semver::VersionReq;
+use toml_edit::{InlineTable, Item, TableLike, Value};
+use url::Url;
+
+use crate::tool::{ReleaseChannel, ResolutionStrategy};
+
+pub(super) const PLATFORMS_KEY: &str = "platforms";
+pub(super) const PLATFORM_NAMES: [&str; 3] = ["windows", "macos", "linux"];
+pub(super) const ASSET_EXCLUDE_KEY: &str = "asset-exclude";
+pub(super) const PRE_REMOVE_KEY: &str = "pre-remove";
+pub(super) const INSTALL_DIR_KEY: &str = "install-dir";
+pub(super) const SHA256_KEY: &str = "sha256";
+
+/**
+    Additional options for a tool in a Rokit manifest.
+
+    Tools may be specified either as a plain string, or as an inline
+    table with a `spec` key followed by any of these options:
+
+    ```toml
+    [tools]
+    rojo = "rojo-rbx/rojo@7.4.4"
+    lune = { spec = "lune-org/lune@0.8.9", check = ["lune", "--version"] }
+    wally = { spec = "upliftgames/wally@0.3.2", channel = "beta" }
+    selene = { spec = "kampfkarren/selene@0.27.1", shadow = true }
+    stylua = { spec = "johnnymorganz/stylua@0.20.0", platforms = { windows = "someone/stylua-win@0.20.0" } }
+    ```
+
+    Tools that register integrations with the system, such as file associations,
+    may set a `pre-remove` command to clean those up before the tool is removed
+    using `rokit remove` - like `check`, it must run the tool itself, and only
+    runs if the tool is trusted and installed:
+
+    ```toml
+    [tools]
+    tool = { spec = "owner/repo@1.0.0", pre-remove = ["tool", "unregister"] }
+    ```
+
+    Tools that are Roblox Studio plugins, published as `.rbxm` or `.rbxmx`
+    release assets, may set `installs = "plugin"` to be installed into the
+    Roblox plugins directory instead of being linked as an executable.
+
+    Release assets with names matching any of the wildcard patterns in the
+    `asset-exclude` option, such as `asset-exclude = ["*.deb", "*.msi"]`,
+    are never chosen when finding the most compatible asset for a tool.
+
+    Tools may set a `version` requirement, such as `version = "^6"`, to stay
+    within those versions when updated. This is what allows several major
+    versions of the same tool to be added side by side, using different
+    aliases, which `rokit add owner/repo@6 tool6` does automatically:
+
+    ```toml
+    [tools]
+    tool = "owner/repo@7.1.0"
+    tool6 = { spec = "owner/repo@6.4.2", version = "^6" }
+    ```
+
+    Some repositories on GitHub mark a long-term support release as their latest
+    release, even though releases with newer versions exist. Tools may set
+    `resolution = "newest-tag"` to always update to the release with the highest
+    version instead - see [`ResolutionStrategy`] for more information:
+
+    ```toml
+    [tools]
+    tool = { spec = "owner/repo@8.1.0", resolution = "newest-tag" }
+    ```
+
+    Tools may also be downloaded from a direct HTTPS `url`, pointing to an
+    archive or a raw binary, instead of from releases. These tools are never
+    updated, and their `spec` uses the `url` provider - which is what
+    `rokit add https://example.com/tool-v1.2.3-linux-x64.tar.gz` writes:
+
+    ```toml
+    [tools]
+    tool = { spec = "url:example.com/tool@1.2.3", url = "https://example.com/tool-v1.2.3-linux-x64.tar.gz" }
+    ```
+
+    Tools may set a `mirror`, an HTTPS URL that the artifacts of the tool are
+    downloaded from first, before falling back to its provider - such as an
+    internal mirror for a single tool, while all others come from GitHub.
+    The path of each download is kept, the same as for mirrors in the policy
+    manifest, and a mirror for a tool takes precedence over those:
+
+    ```toml
+    [tools]
+    tool = { spec = "owner/repo@1.0.0", mirror = "https://mirror.example.com/github" }
+    ```
+
+    Tools from plain git repositories, such as `git:codeberg.org/owner/repo`,
+    may set an `assets` template for the HTTPS URLs of their release assets,
+    with placeholders such as `{tag}` and `{platform}` - see `AssetTemplate`
+    for all placeholders, and `GitProvider` for the template used by default:
+
+    ```toml
+    [tools]
+    tool = { spec = "git:codeberg.org/owner/tool@1.0.0", assets = "https://dl.example.com/{tag}/tool-{platform}.tar.gz" }
+    ```
+
+    Tools may set `extras = true` to also install any man pages and shell
+    completion scripts shipped in their release archive, into the share
+    directory next to the Rokit binary directory - see [`ToolStorage::share_dir`].
+
+    [`ToolStorage::share_dir`]: crate::storage::ToolStorage::share_dir
+
+    Tools that need files shipped alongside their executable, such as templates
+    or other assets, may set `install-dir = true` to have their entire release
+    archive unpacked into storage, keeping its layout. Only the executable is
+    linked, and it is found at the path given by `entrypoint`, relative to
+    the root of the archive - or at the root, using the alias of the tool:
+
+    ```toml
+    [tools]
+    tool = { spec = "owner/repo@1.0.0", install-dir = true, entrypoint = "bin/tool" }
+    ```
+
+    Teams that do not use a lockfile may pin the SHA-256 checksum of the artifact
+    that is downloaded for a tool using the `sha256` option - either as a single
+    checksum, or as an inline table mapping operating systems to checksums, since
+    each platform downloads a different artifact. Installing the tool fails if the
+    downloaded artifact does not match, even if hash checking is disabled by policy:
+
+    ```toml
+    [tools]
+    tool = { spec = "owner/repo@1.0.0", sha256 = { windows = "9f86d0...", linux = "2c26b4..." } }
+    ```
+
+    Checksums only apply to the exact version in the `spec`, and are removed
+    when a tool is updated to another version using [`RokitManifest::update_tool`].
+
+    [`RokitManifest::update_tool`]: crate::manifests::RokitManifest::update_tool
+
+    The `platforms` option maps operating systems (`windows`, `macos`, or `linux`)
+    to a different tool specification, for tools that publish builds for some
+    platforms from a separate repository - the `spec` is used for all others.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ToolOptions {
+    /**
+        A command to run after the tool has been installed, to verify that it
+        can actually execute on the current system. The first item must be the
+        alias of the tool, and the remaining items are passed as arguments.
+    */
+    pub check: Option<Vec<String>>,
+    /**
+        A command to run before the tool is removed from the manifest, to
+        clean up any integrations it registered with the system. The first
+        item must be the alias of the tool, same as for [`ToolOptions::check`].
+    */
+    pub pre_remove: Option<Vec<String>>,
+    /**
+        The release channel that the tool follows when it is updated,
+        such as `beta`, or `prerelease` to opt into all prereleases.
+        Tools follow the stable channel by default.
+        See [`ReleaseChannel`] for more information.
+    */
+    pub channel: Option<ReleaseChannel>,
+    /**
+        A version requirement that the tool must stay within when
+        it is updated, such as `^6`. Tools update to the latest
+        version, within their release channel, by default.
+    */
+    pub version: Option<VersionReq>,
+    /**
+        How the latest version of the tool is chosen when it is updated.
+        Tools use the latest release marker of their provider by default.
+        See [`ResolutionStrategy`] for more information.
+    */
+    pub resolution: Option<ResolutionStrategy>,
+    /**
+        A direct HTTPS URL that the tool is downloaded from, instead of
+        from releases. See [`UrlSource`] for more information.
+
+        [`UrlSource`]: crate::sources::UrlSource
+    */
+    pub url: Option<Url>,
+    /**
+        An HTTPS URL of a mirror that artifacts of the tool are downloaded
+        from first, before falling back to its provider. See
+        [`ArtifactSource::with_tool_mirror`] for more information.
+
+        [`ArtifactSource::with_tool_mirror`]: crate::sources::ArtifactSource::with_tool_mirror
+    */
+    pub mirror: Option<Url>,
+    /**
+        An HTTPS URL template for the release assets of a tool from a plain
+        git repository, with placeholders such as `{tag}` and `{platform}`.
+    */
+    pub assets: Option<String>,
+    /**
+        If the tool is expected to shadow a tool with the same alias in
+        another manifest further up the directory tree, or in the global
+        manifest - otherwise, Rokit will warn when this happens.
+    */
+    pub shadow: bool,
+    /**
+        What the tool installs as, see [`InstallTarget`].
+    */
+    pub installs: Option<InstallTarget>,
+    /**
+        If man pages and shell completion scripts shipped
+        in the release archive of the tool should be installed.
+    */
+    pub extras: bool,
+    /**
+        Wildcard patterns for names of release assets that must never
+        be chosen for the tool, such as installers or system packages.
+    */
+    pub asset_exclude: Vec<String>,
+    /**
+        If the entire release archive of the tool should be
+        unpacked into storage, instead of only its executable.
+    */
+    pub install_dir: bool,
+    /**
+        The path to the executable of the tool, relative to the root of its
+        unpacked archive. Only set for tools using [`ToolOptions::install_dir`],
+        and never set to paths that would lead outside of the archive.
+    */
+    pub entrypoint: Option<String>,
+    /**
+        The expected SHA-256 checksum of the artifact for the tool on the
+        current platform, in lowercase. Checksums that are not valid are
+        kept as they are, so that they never match, and fail the install.
+    */
+    pub sha256: Option<String>,
+}
+
+/**
+    What a tool installs as, given using the `installs` option for a tool.
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InstallTarget {
+    /// An executable, linked into the Rokit binary directory.
+    #[default]
+    Executable,
+    /// A Roblox Studio plugin, placed into the Roblox plugins directory.
+    Plugin,
+}
+
+impl InstallTarget {
+    /**
+        Gets the name of this install target, as used in manifests.
+    */
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Executable => "executable",
+            Self::Plugin => "plugin",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "executable" => Some(Self::Executable),
+            "plugin" => Some(Self::Plugin),
+            _ => None,
+        }
+    }
+}
+
+impl ToolOptions {
+    pub(super) fn from_value(value: &Value) -> Self {
+        value
+            .as_inline_table()
+            .map(Self::from_table_like)
+            .unwrap_or_default()
+    }
+
+    pub(super) fn from_table_like<T: TableLike + ?Sized>(table: &T) -> Self {
+        let check = table.get("check").and_then(|item| {
+            item.as_array()?
+                .iter()
+                .map(|arg| arg.as_str().map(ToString::to_string))
+                .collect::<Option<Vec<_>>>()
+        });
+        let pre_remove = table.get(PRE_REMOVE_KEY).and_then(|item| {
+            item.as_array()?
+                .iter()
+                .map(|arg| arg.as_str().map(ToString::to_string))
+                .collect::<Option<Vec<_>>>()
+        });
+        let channel = table
+            .get("channel")
+            .and_then(|item| item.as_str()?.parse().ok());
+        let version = table
+            .get("version")
+            .and_then(|item| item.as_str()?.parse().ok());
+        let resolution = table
+            .get("resolution")
+            .and_then(|item| item.as_str()?.parse().ok());
+        let url = table
+            .get("url")
+            .and_then(|item| item.as_str()?.parse::<Url>().ok())
+            .filter(|url| url.scheme() == "https");
+        let mirror = table
+            .get("mirror")
+            .and_then(|item| item.as_str()?.trim().parse::<Url>().ok())
+            .filter(|url| url.scheme() == "https" && url.has_host());
+        let assets = table
+            .get("assets")
+            .and_then(|item| item.as_str())
+            .map(str::trim)
+            .filter(|template| {
+                template.starts_with("https://") && !template.contains(char::is_whitespace)
+            })
+            .map(ToString::to_string);
+        let shadow = table
+            .get("shadow")
+            .and_then(Item::as_bool)
+            .unwrap_or_default();
+        let installs = table
+            .get("installs")
+            .and_then(|item| InstallTarget::from_name(item.as_str()?));
+        let extras = table
+            .get("extras")
+            .and_then(Item::as_bool)
+            .unwrap_or_default();
+        let asset_exclude = table
+            .get(ASSET_EXCLUDE_KEY)
+            .and_then(|item| {
+                item.as_array()?
+                    .iter()
+                    .map(|pattern| pattern.as_str().map(ToString::to_string))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .unwrap_or_default();
+        let install_dir = table
+            .get(INSTALL_DIR_KEY)
+            .and_then(Item::as_bool)
+            .unwrap_or_default();
+        let entrypoint = table
+            .get("entrypoint")
+            .and_then(|item| item.as_str())
+            .filter(|path| install_dir && is_relative_entrypoint(path))
+            .map(ToString::to_string);
+        let sha256 = table
+            .get(SHA256_KEY)
+            .and_then(|item| match item.as_inline_table() {
+                Some(platforms) => platforms.get(CURRENT_OS).map(|c| c.as_str()),
+                None => Some(item.as_str()),
+            })
+            .map(Option::unwrap_or_default)
+            .map(|checksum| checksum.trim().to_ascii_lowercase());
+        Self {
+            check,
+            pre_remove,
+            channel,
+            version,
+            resolution,
+            url,
+            mirror,
+            assets,
+            shadow,
+            installs,
+            extras,
+            asset_exclude,
+            install_dir,
+            entrypoint,
+            sha256,
+        }
+    }
+}
+
+/**
+    Checks if a SHA-256 checksum given in a manifest
+    is valid, meaning 64 hexadecimal characters.
+*/
+pub(super) fn is_valid_sha256(checksum: &str) -> bool {
+    let checksum = checksum.trim();
+    checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/*
+    Checks if an entrypoint is a relative path that stays
+    inside of the directory that the tool is unpacked into.
+*/
+fn is_relative_entrypoint(path: &str) -> bool {
+    let path = Path::new(path.trim());
+    path.components().any(|c| matches!(c, Component::Normal(_)))
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/**
+    Gets the tool specification string for a tool in a Rokit manifest,
+    preferring any specification given for the current platform.
+
+    See [`ToolOptions`] for the supported formats.
+*/
+pub(super) fn tool_spec_str(value: &Value) -> Option<&str> {
+    value.as_str().or_else(|| {
+        let table = value.as_inline_table()?;
+        platform_spec_str(table).or_else(|| table.get("spec")?.as_str())
+    })
+}
+
+/**
+    Gets the tool specification string for the current platform,
+    if one has been given using the `platforms` option.
+*/
+pub(super) fn platform_spec_str(table: &InlineTable) -> Option<&str> {
+    table
+        .get(PLATFORMS_KEY)?
+        .as_inline_table()?
+        .get(CURRENT_OS)?
+        .as_str()
+}
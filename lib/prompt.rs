@@ -0,0 +1,417 @@
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    error::Error,
+    fmt,
+    sync::Mutex,
+};
+
+use futures::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
+use semver::Version;
+use tracing::debug;
+
+use crate::{
+    resolve::ResolveConstraints,
+    result::{RokitError, RokitResult},
+    sources::{ArtifactSource, ToolProvenance},
+    storage::ToolCache,
+    tool::ToolId,
+};
+
+/**
+    Error type returned by a [`Prompter`].
+
+    Prompters may return any error, such as when the user closed
+    a dialog without answering, or when no answer could be given.
+*/
+pub type PromptError = Box<dyn Error + Send + Sync>;
+
+/**
+    A request to trust a tool, given to [`Prompter::confirm_trust`].
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustPrompt {
+    /// The tool that needs to be trusted.
+    pub id: ToolId,
+    /// What is known about where the tool comes from, if anything.
+    pub provenance: Option<ToolProvenance>,
+    /// Trusted tools with the same name as this tool, but from a different owner.
+    pub lookalikes: Vec<ToolId>,
+}
+
+/**
+    A request to pick a version of a tool, given to [`Prompter::pick_version`].
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionPrompt {
+    /// The tool to pick a version for.
+    pub id: ToolId,
+    /// The versions that may be picked, newest first.
+    pub versions: Vec<Version>,
+}
+
+/**
+    A request to confirm a large download, given to [`Prompter::confirm_download`].
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadPrompt {
+    /// The total number of bytes that would be downloaded.
+    pub size: u64,
+}
+
+/**
+    Asks the user questions on behalf of Rokit, such as whether to trust a tool.
+
+    Embedders may implement this trait to show their own dialogs, for example
+    in a GUI or in another language, and tests may implement it to script
+    answers - see [`ScriptedPrompter`].
+
+    The Rokit CLI implements this trait using terminal prompts.
+*/
+pub trait Prompter: fmt::Debug + Send + Sync {
+    /**
+        Asks the user if the given tool should be trusted.
+
+        Returns `true` if the tool should be trusted.
+    */
+    fn confirm_trust(&self, prompt: TrustPrompt) -> BoxFuture<'_, Result<bool, PromptError>>;
+
+    /**
+        Asks the user which of the given tools should be trusted, all at once,
+        such as using a single dialog where each tool can be selected.
+
+        Returns whether each tool should be trusted, in the same order as the
+        given prompts. By default, this asks about each tool individually using
+        [`Prompter::confirm_trust`], one after another.
+    */
+    fn confirm_trust_all(
+        &self,
+        prompts: Vec<TrustPrompt>,
+    ) -> BoxFuture<'_, Result<Vec<bool>, PromptError>> {
+        Box::pin(async move {
+            let mut answers = Vec::with_capacity(prompts.len());
+            for prompt in prompts {
+                answers.push(self.confirm_trust(prompt).await?);
+            }
+            Ok(answers)
+        })
+    }
+
+    /**
+        Asks the user to pick one of the given versions of a tool.
+
+        Returns `None` if the user did not pick any version.
+    */
+    fn pick_version(
+        &self,
+        prompt: VersionPrompt,
+    ) -> BoxFuture<'_, Result<Option<Version>, PromptError>>;
+
+    /**
+        Checks if this prompter shows the provenance of tools to the user.
+
+        Fetching provenance needs extra requests for each tool, which
+        prompters that never show it, such as ones that always give
+        the same answer, may opt out of by returning `false`.
+    */
+    fn wants_provenance(&self) -> bool {
+        true
+    }
+
+    /**
+        Asks the user to confirm downloading more than the size
+        set in the policy manifest, before anything is downloaded.
+
+        Returns `true` if the download should continue. Prompters
+        that can not ask the user always continue by default.
+    */
+    fn confirm_download(
+        &self,
+        _prompt: DownloadPrompt,
+    ) -> BoxFuture<'_, Result<bool, PromptError>> {
+        Box::pin(async { Ok(true) })
+    }
+}
+
+/**
+    A prompter that gives answers from a script, in order.
+
+    Useful for tests, or for embedders that decide on answers
+    ahead of time. Running out of answers is an error.
+*/
+#[derive(Debug, Default)]
+pub struct ScriptedPrompter {
+    trust: Mutex<VecDeque<bool>>,
+    versions: Mutex<VecDeque<Option<Version>>>,
+    asked: Mutex<Vec<ToolId>>,
+}
+
+impl ScriptedPrompter {
+    /**
+        Creates a new prompter without any scripted answers.
+    */
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+        Adds answers for trust prompts to this prompter.
+    */
+    #[must_use]
+    pub fn with_trust_answers(self, answers: impl IntoIterator<Item = bool>) -> Self {
+        self.trust
+            .lock()
+            .expect("lock was poisoned")
+            .extend(answers);
+        self
+    }
+
+    /**
+        Adds answers for version prompts to this prompter.
+    */
+    #[must_use]
+    pub fn with_version_answers(self, answers: impl IntoIterator<Item = Option<Version>>) -> Self {
+        self.versions
+            .lock()
+            .expect("lock was poisoned")
+            .extend(answers);
+        self
+    }
+
+    /**
+        Gets the tools that this prompter has been asked about, in order.
+    */
+    #[must_use]
+    pub fn asked(&self) -> Vec<ToolId> {
+        self.asked.lock().expect("lock was poisoned").clone()
+    }
+
+    fn next_answer<T>(&self, answers: &Mutex<VecDeque<T>>, id: ToolId) -> Result<T, PromptError> {
+        let answer = answers.lock().expect("lock was poisoned").pop_front();
+        let answer = answer.ok_or_else(|| format!("no scripted answer left for {id}"))?;
+        self.asked.lock().expect("lock was poisoned").push(id);
+        Ok(answer)
+    }
+}
+
+impl Prompter for ScriptedPrompter {
+    fn confirm_trust(&self, prompt: TrustPrompt) -> BoxFuture<'_, Result<bool, PromptError>> {
+        Box::pin(async move { self.next_answer(&self.trust, prompt.id) })
+    }
+
+    fn pick_version(
+        &self,
+        prompt: VersionPrompt,
+    ) -> BoxFuture<'_, Result<Option<Version>, PromptError>> {
+        Box::pin(async move {
+            let version = self.next_answer(&self.versions, prompt.id.clone())?;
+            match version {
+                Some(v) if !prompt.versions.contains(&v) => {
+                    Err(format!("scripted version {v} is not available for {}", prompt.id).into())
+                }
+                version => Ok(version),
+            }
+        })
+    }
+
+    fn wants_provenance(&self) -> bool {
+        false
+    }
+}
+
+/**
+    Asks the user to trust each of the given tools that are not yet
+    trusted, along with their provenance and any trusted lookalikes.
+
+    When there are several tools to trust, the user is asked about
+    all of them at once, using [`Prompter::confirm_trust_all`].
+
+    Returns the tools that the user chose to trust, sorted. Note that
+    these are not added to the given tool cache, since trusting a tool
+    may only be wanted for a single operation, such as `rokit try`.
+
+    # Errors
+
+    - If the prompter returned an error.
+*/
+pub async fn prompt_for_trust(
+    prompter: &dyn Prompter,
+    source: &ArtifactSource,
+    tool_cache: &ToolCache,
+    tool_ids: impl IntoIterator<Item = ToolId>,
+) -> RokitResult<Vec<ToolId>> {
+    let untrusted = tool_ids
+        .into_iter()
+        .filter(|id| !tool_cache.is_trusted(id))
+        .collect::<BTreeSet<_>>();
+
+    let mut provenances = if prompter.wants_provenance() {
+        fetch_provenances(source, &untrusted).await
+    } else {
+        HashMap::new()
+    };
+
+    let prompts = untrusted
+        .into_iter()
+        .map(|id| TrustPrompt {
+            provenance: provenances.remove(&id),
+            lookalikes: tool_cache.trusted_lookalikes(&id),
+            id,
+        })
+        .collect::<Vec<_>>();
+    let ids = prompts
+        .iter()
+        .map(|prompt| prompt.id.clone())
+        .collect::<Vec<_>>();
+
+    let answers = match prompts.len() {
+        0 => Vec::new(),
+        1 => {
+            let prompt = prompts.into_iter().next().expect("one prompt");
+            vec![prompter
+                .confirm_trust(prompt)
+                .await
+                .map_err(RokitError::Prompt)?]
+        }
+        _ => prompter
+            .confirm_trust_all(prompts)
+            .await
+            .map_err(RokitError::Prompt)?,
+    };
+    if answers.len() != ids.len() {
+        let message = format!(
+            "prompter gave {} answers for {} tools",
+            answers.len(),
+            ids.len()
+        );
+        return Err(RokitError::Prompt(message.into()));
+    }
+
+    Ok(ids
+        .into_iter()
+        .zip(answers)
+        .filter_map(|(id, trusted)| trusted.then_some(id))
+        .collect())
+}
+
+/**
+    Asks the user to pick a version of the given tool, out
+    of its most recent versions matching the given constraints.
+
+    Returns `None` if the user did not pick any version.
+
+    # Errors
+
+    - If releases for the tool could not be fetched.
+    - If no release matching the given constraints exists.
+    - If the prompter returned an error.
+*/
+pub async fn prompt_for_version(
+    prompter: &dyn Prompter,
+    source: &ArtifactSource,
+    tool_id: &ToolId,
+    constraints: &ResolveConstraints,
+) -> RokitResult<Option<Version>> {
+    let versions = source
+        .get_recent_releases_in_channel(tool_id, &constraints.channel)
+        .await?
+        .into_iter()
+        .map(|release| release.version)
+        .filter(|version| constraints.allows(version))
+        .collect::<Vec<_>>();
+    if versions.is_empty() {
+        return Err(RokitError::NoEligibleRelease(tool_id.clone().into()));
+    }
+
+    let prompt = VersionPrompt {
+        id: tool_id.clone(),
+        versions,
+    };
+    prompter
+        .pick_version(prompt)
+        .await
+        .map_err(RokitError::Prompt)
+}
+
+/**
+    Fetches provenance information for all of the given tools.
+
+    This is best-effort - tools that provenance could not be fetched for are skipped.
+*/
+async fn fetch_provenances(
+    source: &ArtifactSource,
+    tool_ids: &BTreeSet<ToolId>,
+) -> HashMap<ToolId, ToolProvenance> {
+    tool_ids
+        .iter()
+        .map(|id| async move {
+            match source.get_provenance(id).await {
+                Ok(provenance) => Some((id.clone(), provenance)),
+                Err(e) => {
+                    debug!(%id, error = %e, "failed to fetch provenance for tool");
+                    None
+                }
+            }
+        })
+        .collect::<FuturesUnordered<_>>()
+        .filter_map(|res| async move { res })
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    fn trust_prompt(id: &str) -> TrustPrompt {
+        TrustPrompt {
+            id: id.parse().unwrap(),
+            provenance: None,
+            lookalikes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn scripted_answers_in_order() {
+        let prompter = ScriptedPrompter::new().with_trust_answers([true, false]);
+        assert!(block_on(prompter.confirm_trust(trust_prompt("rojo-rbx/rojo"))).unwrap());
+        assert!(!block_on(prompter.confirm_trust(trust_prompt("evil/rojo"))).unwrap());
+        assert!(block_on(prompter.confirm_trust(trust_prompt("lune-org/lune"))).is_err());
+        assert_eq!(
+            prompter.asked(),
+            vec![
+                "rojo-rbx/rojo".parse().unwrap(),
+                "evil/rojo".parse().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn trust_all_asks_in_order() {
+        let prompter = ScriptedPrompter::new().with_trust_answers([false, true]);
+        let prompts = vec![trust_prompt("evil/rojo"), trust_prompt("rojo-rbx/rojo")];
+        assert_eq!(
+            block_on(prompter.confirm_trust_all(prompts.clone())).unwrap(),
+            vec![false, true]
+        );
+        assert!(block_on(prompter.confirm_trust_all(prompts)).is_err());
+    }
+
+    #[test]
+    fn scripted_version_must_be_available() {
+        let v1 = Version::new(1, 0, 0);
+        let v2 = Version::new(2, 0, 0);
+        let prompter = ScriptedPrompter::new().with_version_answers([Some(v1.clone()), Some(v2)]);
+        let prompt = VersionPrompt {
+            id: "rojo-rbx/rojo".parse().unwrap(),
+            versions: vec![v1.clone()],
+        };
+        assert_eq!(
+            block_on(prompter.pick_version(prompt.clone())).unwrap(),
+            Some(v1)
+        );
+        assert!(block_on(prompter.pick_version(prompt)).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    descriptor::Descriptor,
+    result::{RokitError, RokitResult},
+    sources::{Artifact, ArtifactSource},
+    tool::{ReleaseChannel, ResolutionStrategy, ToolId, ToolSpec},
+    warnings::{RokitWarning, RokitWarnings},
+};
+use semver::{Comparator, Op, Version, VersionReq};
+
+/**
+    Constraints for which versions of a tool may be chosen by [`resolve`].
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolveConstraints {
+    /// The versions that may be chosen, or `None` to choose the latest version.
+    pub version: Option<VersionReq>,
+    /// Versions that must never be chosen, such as versions that have been yanked.
+    pub excluded: Vec<Version>,
+    /// The release channel that chosen versions must belong to.
+    pub channel: ReleaseChannel,
+    /// How the latest version is chosen, when more than one version is allowed.
+    pub resolution: ResolutionStrategy,
+}
+
+impl ResolveConstraints {
+    /**
+        Creates constraints that allow only the exact given version.
+
+        The channel of the constraints is the channel that the version belongs
+        to, meaning exact prerelease versions such as `1.0.0-beta.1` are allowed.
+    */
+    #[must_use]
+    pub fn exact(version: &Version) -> Self {
+        let comparator = Comparator {
+            op: Op::Exact,
+            major: version.major,
+            minor: Some(version.minor),
+            patch: Some(version.patch),
+            pre: version.pre.clone(),
+        };
+        Self {
+            version: Some(VersionReq {
+                comparators: vec![comparator],
+            }),
+            excluded: Vec::new(),
+            channel: ReleaseChannel::of(version),
+            resolution: ResolutionStrategy::default(),
+        }
+    }
+
+    /**
+        Creates constraints that allow only versions matching the given requirement.
+    */
+    #[must_use]
+    pub fn matching(req: VersionReq) -> Self {
+        Self {
+            version: Some(req),
+            excluded: Vec::new(),
+            channel: ReleaseChannel::Stable,
+            resolution: ResolutionStrategy::default(),
+        }
+    }
+
+    /**
+        Allows only versions in the given release channel to be chosen.
+    */
+    #[must_use]
+    pub fn in_channel(mut self, channel: ReleaseChannel) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /**
+        Chooses the latest version using the given strategy.
+    */
+    #[must_use]
+    pub fn with_resolution(mut self, resolution: ResolutionStrategy) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /**
+        Excludes the given versions from being chosen.
+    */
+    #[must_use]
+    pub fn excluding(mut self, versions: impl IntoIterator<Item = Version>) -> Self {
+        self.excluded.extend(versions);
+        self
+    }
+
+    /**
+        Checks if the given version satisfies these constraints.
+    */
+    #[must_use]
+    pub fn allows(&self, version: &Version) -> bool {
+        let is_matching = match &self.version {
+            Some(req) => req.matches(version),
+            None => true,
+        };
+        is_matching && self.channel.contains(version) && !self.excluded.contains(version)
+    }
+
+    pub(crate) fn exact_version(&self) -> Option<Version> {
+        let req = self.version.as_ref()?;
+        match req.comparators.as_slice() {
+            [c] if c.op == Op::Exact => Some(Version {
+                major: c.major,
+                minor: c.minor?,
+                patch: c.patch?,
+                pre: c.pre.clone(),
+                build: semver::BuildMetadata::EMPTY,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/**
+    A tool version and artifact chosen by [`resolve`].
+*/
+#[derive(Debug, Clone)]
+pub struct ResolvedArtifact {
+    /// The specification of the tool version that was chosen.
+    pub spec: ToolSpec,
+    /// The artifact that was chosen for the target system.
+    pub artifact: Artifact,
+    /// The changelog for the chosen release, if any.
+    pub changelog: Option<String>,
+    /// Any warnings encountered while choosing the artifact.
+    pub warnings: Vec<RokitWarning>,
+}
+
+/**
+    Resolves the version of a tool matching the given constraints, and
+    the most compatible artifact of that version for the given system.
+
+    This does not need a Rokit [`Home`](crate::storage::Home), and does
+    not download or install anything, which makes it useful for other
+    tools that want to reuse the release querying and artifact selection
+    of Rokit - use [`ArtifactSource::new`] for an unauthenticated source.
+
+    # Errors
+
+    - If releases for the tool could not be fetched.
+    - If no release matching the given constraints exists.
+    - If no artifact compatible with the given system exists.
+*/
+pub async fn resolve(
+    source: &ArtifactSource,
+    tool_id: &ToolId,
+    constraints: &ResolveConstraints,
+    system: &Descriptor,
+) -> RokitResult<ResolvedArtifact> {
+    let release = source
+        .get_latest_release_matching(tool_id, constraints)
+        .await?;
+    let spec = ToolSpec::from((tool_id.clone(), release.version.clone()));
+
+    let warnings = RokitWarnings::new();
+    // NOTE: Installers are only ever chosen when nothing else is compatible,
+    // and since they can not be installed, that means nothing is compatible
+    let artifact = Artifact::find_most_compatible_with(&release.artifacts, system, &warnings)
+        .filter(|artifact| !artifact.is_installer())
+        .ok_or_else(|| RokitError::NoCompatibleArtifact(spec.clone().into()))?;
+
+    Ok(ResolvedArtifact {
+        spec,
+        artifact,
+        changelog: release.changelog,
+        warnings: warnings.all(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_constraints() {
+        let version = Version::new(1, 2, 3);
+        let constraints = ResolveConstraints::exact(&version);
+        assert_eq!(constraints.exact_version(), Some(version.clone()));
+        assert!(constraints.allows(&version));
+        assert!(!constraints.allows(&Version::new(1, 2, 4)));
+    }
+
+    #[test]
+    fn matching_constraints() {
+        let constraints = ResolveConstraints::matching("^1.2".parse().unwrap())
+            .excluding([Version::new(1, 3, 0)]);
+        assert_eq!(constraints.exact_version(), None);
+        assert!(constraints.allows(&Version::new(1, 2, 0)));
+        assert!(constraints.allows(&Version::new(1, 4, 0)));
+        assert!(!constraints.allows(&Version::new(1, 3, 0)));
+        assert!(!constraints.allows(&Version::new(2, 0, 0)));
+    }
+
+    #[test]
+    fn exact_prerelease_constraints() {
+        let version: Version = "1.2.3-beta.1".parse().unwrap();
+        let constraints = ResolveConstraints::exact(&version);
+        assert_eq!(constraints.channel, "beta".parse().unwrap());
+        assert!(constraints.allows(&version));
+    }
+
+    #[test]
+    fn channel_constraints() {
+        let constraints = ResolveConstraints::default().in_channel("beta".parse().unwrap());
+        assert!(constraints.allows(&"2.0.0-beta.1".parse().unwrap()));
+        assert!(constraints.allows(&Version::new(2, 0, 0)));
+        assert!(!constraints.allows(&"2.0.0-alpha.1".parse().unwrap()));
+        assert!(!ResolveConstraints::default().allows(&"2.0.0-beta.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn default_allows_all_but_excluded() {
+        let constraints = ResolveConstraints::default().excluding([Version::new(1, 0, 0)]);
+        assert!(constraints.allows(&Version::new(2, 0, 0)));
+        assert!(!constraints.allows(&Version::new(1, 0, 0)));
+    }
+}
@@ -1,54 +1,273 @@
 use std::io::Error as IoError;
 use std::path::PathBuf;
 
+use chrono::{DateTime, Utc};
 use postcard::Error as PostcardError;
 use serde_json::Error as JsonError;
 use thiserror::Error;
-use tokio::task::JoinError;
 use toml_edit::TomlError;
+
+use crate::{
+    executor::TaskError,
+    manifests::ManifestParseError,
+    prompt::PromptError,
+    sources::{
+        crates::CratesError, git::GitError, npm::NpmError, registry::RegistryError, s3::S3Error,
+        ArtifactProvider, CustomProviderError, HttpError, UrlSourceError,
+    },
+    storage::PreflightError,
+    tool::{ToolId, ToolSpec},
+};
+
+#[cfg(feature = "extract")]
 use zip::result::ZipError;
 
-use crate::sources::{github::GithubError, ExtractError};
+#[cfg(feature = "extract")]
+use crate::sources::ExtractError;
+
+#[cfg(feature = "extract")]
+use crate::session::HookError;
+
+#[cfg(feature = "github")]
+use crate::sources::github::GithubError;
+
+#[cfg(feature = "gitlab")]
+use crate::sources::gitlab::GitlabError;
 
 #[derive(Debug, Error)]
 pub enum RokitError {
     #[error("home directory not found")]
     HomeNotFound,
+    #[error("invalid profile name '{0}' - names may only contain letters, digits, dashes and underscores")]
+    InvalidProfileName(String),
+    #[error("no eligible release was found for tool '{0}'")]
+    NoEligibleRelease(Box<ToolId>),
+    #[error("no compatible artifact was found for tool '{0}'")]
+    NoCompatibleArtifact(Box<ToolSpec>),
     #[error("file not found: {0}")]
     FileNotFound(PathBuf),
     #[error("unexpected invalid UTF-8")]
     InvalidUtf8,
+    #[error("the Roblox Studio plugins directory could not be found - set the ROKIT_ROBLOX_PLUGINS_DIR environment variable to use a custom directory")]
+    PluginsDirNotFound,
+    #[error("Rokit can not use its home directory - {0}")]
+    Preflight(Box<PreflightError>),
+    #[error("'{}' was written by a newer version of Rokit, using version {version} of its format - this version of Rokit only supports up to version {supported}", .path.display())]
+    UnsupportedDataVersion {
+        path: PathBuf,
+        version: u64,
+        supported: u64,
+    },
+    #[error("artifact provider '{0}' is not enabled in this build of Rokit")]
+    ProviderNotEnabled(ArtifactProvider),
+    #[error("the artifact for tool '{spec}' has changed since it was first installed - expected SHA-256 {expected}, got {actual}")]
+    ArtifactHashChanged {
+        spec: Box<ToolSpec>,
+        expected: String,
+        actual: String,
+    },
+    #[error("the artifact for tool '{spec}' does not match the checksum pinned in its manifest - expected SHA-256 {expected}, got {actual}")]
+    ArtifactChecksumMismatch {
+        spec: Box<ToolSpec>,
+        expected: String,
+        actual: String,
+    },
+    #[error("the artifact for tool '{spec}' does not match the digest published with its release - expected SHA-256 {expected}, got {actual}")]
+    ArtifactDigestMismatch {
+        spec: Box<ToolSpec>,
+        expected: String,
+        actual: String,
+    },
+    #[error("the artifact for tool '{spec}' was not fully downloaded - expected {expected} bytes, got {actual}")]
+    ArtifactSizeMismatch {
+        spec: Box<ToolSpec>,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("{} API rate limit exceeded{} - authenticate using `rokit authenticate {provider}` for a higher limit", .provider.display_name(), format_reset_at(.reset_at.as_ref()))]
+    RateLimited {
+        provider: ArtifactProvider,
+        reset_at: Option<DateTime<Utc>>,
+    },
+    #[error("no custom provider is registered for host '{0}'")]
+    CustomProviderNotFound(String),
+    #[error("custom provider '{host}' does not support {operation}")]
+    CustomProviderNotSupported {
+        host: String,
+        operation: &'static str,
+    },
+    #[error("the executable for tool '{spec}' was not found at '{}' in its archive - set the 'entrypoint' option to its path within the archive", .path.display())]
+    EntrypointNotFound { spec: Box<ToolSpec>, path: PathBuf },
+    #[error("no plugin file was found for tool '{0}' - the release must contain a '.rbxm' or '.rbxmx' file to be installed as a plugin")]
+    NoPluginFile(Box<ToolSpec>),
+    #[error("the check for tool '{spec}' failed - {reason}")]
+    ToolCheckFailed { spec: Box<ToolSpec>, reason: String },
+    #[error(
+        "tools are not available locally, and can not be downloaded while offline: {}",
+        format_specs(.0)
+    )]
+    Offline(Vec<ToolSpec>),
+    #[error("tool '{0}' has never been installed before, and can not be installed while locked")]
+    NotLocked(Box<ToolSpec>),
+    #[error(
+        "installing would download {size} bytes, which is more than the maximum of {max} bytes"
+    )]
+    DownloadTooLarge { size: u64, max: u64 },
+    #[error("installation was cancelled")]
+    InstallCancelled,
+    #[error("{operation} '{subject}' was vetoed - {reason}")]
+    Vetoed {
+        operation: &'static str,
+        subject: String,
+        reason: String,
+    },
+    #[cfg(feature = "extract")]
+    #[error("install hook error: {0}")]
+    Hook(HookError),
+    #[error("failed to install tool '{spec}' - {error}")]
+    Install {
+        spec: Box<ToolSpec>,
+        error: Box<RokitError>,
+    },
+    #[error("tool '{0}' was added from a direct URL and has no releases - add it again using a new URL to change its version")]
+    NoReleasesForUrlTool(Box<ToolId>),
+    #[error("invalid tool URL: {0}")]
+    UrlSource(Box<UrlSourceError>),
+    #[cfg(feature = "extract")]
     #[error("failed to extract artifact: {0}")]
     Extract(Box<ExtractError>),
     #[error("task join error: {0}")]
-    TaskJoinError(Box<JoinError>),
+    TaskJoinError(TaskError),
     #[error("TOML parse error: {0}")]
     TomlParseError(Box<TomlError>),
+    #[error("{0}")]
+    ManifestParse(Box<ManifestParseError>),
     #[error("I/O error: {0}")]
     Io(Box<IoError>),
     #[error("JSON error: {0}")]
     Json(Box<JsonError>),
     #[error("Postcard error: {0}")]
     Postcard(Box<PostcardError>),
+    #[cfg(feature = "extract")]
     #[error("Zip file error: {0}")]
     Zip(Box<ZipError>),
+    #[cfg(feature = "github")]
     #[error("GitHub error: {0}")]
     GitHub(Box<GithubError>),
+    #[cfg(feature = "gitlab")]
+    #[error("GitLab error: {0}")]
+    GitLab(Box<GitlabError>),
+    #[error("Registry error: {0}")]
+    Registry(Box<RegistryError>),
+    #[error("S3 error: {0}")]
+    S3(Box<S3Error>),
+    #[error("crates.io error: {0}")]
+    Crates(Box<CratesError>),
+    #[error("npm error: {0}")]
+    Npm(Box<NpmError>),
+    #[error("git error: {0}")]
+    Git(Box<GitError>),
+    #[error("Custom provider error: {0}")]
+    CustomProvider(CustomProviderError),
+    #[error("HTTP error: {0}")]
+    Http(HttpError),
+    #[error("{0}")]
+    Prompt(PromptError),
+}
+
+impl RokitError {
+    /**
+        Gets a short, stable name for the kind of this error, such as `http`.
+
+        This never contains any details about the error itself, making
+        it suitable for anonymous metrics - see [`crate::storage::Metrics`].
+    */
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::HomeNotFound | Self::InvalidProfileName(_) => "home",
+            Self::NoEligibleRelease(_) => "no-eligible-release",
+            Self::NoCompatibleArtifact(_) | Self::NoPluginFile(_) => "no-compatible-artifact",
+            Self::ToolCheckFailed { .. } => "tool-check",
+            Self::Offline(_) => "offline",
+            Self::NotLocked(_) => "not-locked",
+            Self::DownloadTooLarge { .. } | Self::InstallCancelled => "install-cancelled",
+            Self::Install { error, .. } => error.kind(),
+            Self::Vetoed { .. } => "vetoed",
+            #[cfg(feature = "extract")]
+            Self::Hook(_) => "hook",
+            Self::FileNotFound(_) => "file-not-found",
+            Self::EntrypointNotFound { .. } => "entrypoint-not-found",
+            Self::InvalidUtf8 => "invalid-utf8",
+            Self::PluginsDirNotFound => "plugins-dir-not-found",
+            Self::Preflight(_) => "preflight",
+            Self::UnsupportedDataVersion { .. } => "unsupported-data-version",
+            Self::ProviderNotEnabled(_) => "provider-not-enabled",
+            Self::ArtifactHashChanged { .. } => "artifact-hash-changed",
+            Self::ArtifactChecksumMismatch { .. } => "artifact-checksum-mismatch",
+            Self::ArtifactDigestMismatch { .. } => "artifact-digest-mismatch",
+            Self::ArtifactSizeMismatch { .. } => "artifact-size-mismatch",
+            Self::RateLimited { .. } => "rate-limited",
+            Self::NoReleasesForUrlTool(_) | Self::UrlSource(_) => "url-tool",
+            #[cfg(feature = "extract")]
+            Self::Extract(_) | Self::Zip(_) => "extract",
+            Self::TaskJoinError(_) => "task",
+            Self::TomlParseError(_) | Self::ManifestParse(_) => "manifest",
+            Self::Io(_) => "io",
+            Self::Json(_) | Self::Postcard(_) => "serialization",
+            #[cfg(feature = "github")]
+            Self::GitHub(_) => "github",
+            #[cfg(feature = "gitlab")]
+            Self::GitLab(_) => "gitlab",
+            Self::Registry(_) => "registry",
+            Self::S3(_) => "s3",
+            Self::Crates(_) => "crates",
+            Self::Npm(_) => "npm",
+            Self::Git(_) => "git",
+            Self::CustomProviderNotFound(_)
+            | Self::CustomProviderNotSupported { .. }
+            | Self::CustomProvider(_) => "custom",
+            Self::Http(_) => "http",
+            Self::Prompt(_) => "prompt",
+        }
+    }
 }
 
 pub type RokitResult<T> = Result<T, RokitError>;
 
+pub(crate) fn format_specs(specs: &[ToolSpec]) -> String {
+    specs
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(crate) fn format_reset_at(reset_at: Option<&DateTime<Utc>>) -> String {
+    match reset_at {
+        Some(reset_at) => format!(" - the limit resets at {}", reset_at.format("%H:%M:%S UTC")),
+        None => String::new(),
+    }
+}
+
 // FUTURE: Figure out some way to reduce this boxing boilerplate
 
+#[cfg(feature = "extract")]
 impl From<ExtractError> for RokitError {
     fn from(err: ExtractError) -> Self {
         RokitError::Extract(err.into())
     }
 }
 
-impl From<JoinError> for RokitError {
-    fn from(err: JoinError) -> Self {
-        RokitError::TaskJoinError(err.into())
+impl From<PreflightError> for RokitError {
+    fn from(err: PreflightError) -> Self {
+        RokitError::Preflight(err.into())
+    }
+}
+
+impl From<TaskError> for RokitError {
+    fn from(err: TaskError) -> Self {
+        RokitError::TaskJoinError(err)
     }
 }
 
@@ -58,6 +277,12 @@ impl From<TomlError> for RokitError {
     }
 }
 
+impl From<ManifestParseError> for RokitError {
+    fn from(err: ManifestParseError) -> Self {
+        RokitError::ManifestParse(err.into())
+    }
+}
+
 impl From<IoError> for RokitError {
     fn from(err: IoError) -> Self {
         RokitError::Io(err.into())
@@ -76,14 +301,71 @@ impl From<PostcardError> for RokitError {
     }
 }
 
+#[cfg(feature = "extract")]
 impl From<ZipError> for RokitError {
     fn from(err: ZipError) -> Self {
         RokitError::Zip(err.into())
     }
 }
 
+#[cfg(feature = "github")]
 impl From<GithubError> for RokitError {
     fn from(err: GithubError) -> Self {
-        RokitError::GitHub(err.into())
+        match err {
+            GithubError::RateLimited { reset_at } => RokitError::RateLimited {
+                provider: ArtifactProvider::GitHub,
+                reset_at,
+            },
+            err => RokitError::GitHub(err.into()),
+        }
+    }
+}
+
+#[cfg(feature = "gitlab")]
+impl From<GitlabError> for RokitError {
+    fn from(err: GitlabError) -> Self {
+        RokitError::GitLab(err.into())
+    }
+}
+
+impl From<RegistryError> for RokitError {
+    fn from(err: RegistryError) -> Self {
+        RokitError::Registry(err.into())
+    }
+}
+
+impl From<S3Error> for RokitError {
+    fn from(err: S3Error) -> Self {
+        RokitError::S3(err.into())
+    }
+}
+
+impl From<CratesError> for RokitError {
+    fn from(err: CratesError) -> Self {
+        RokitError::Crates(err.into())
+    }
+}
+
+impl From<NpmError> for RokitError {
+    fn from(err: NpmError) -> Self {
+        RokitError::Npm(err.into())
+    }
+}
+
+impl From<GitError> for RokitError {
+    fn from(err: GitError) -> Self {
+        RokitError::Git(err.into())
+    }
+}
+
+impl From<UrlSourceError> for RokitError {
+    fn from(err: UrlSourceError) -> Self {
+        RokitError::UrlSource(err.into())
+    }
+}
+
+impl From<HttpError> for RokitError {
+    fn from(err: HttpError) -> Self {
+        RokitError::Http(err)
     }
 }
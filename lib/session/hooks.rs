@@ -0,0 +1,75 @@
+use std::{error::Error, fmt};
+
+use futures::future::BoxFuture;
+
+use crate::{
+    sources::Artifact,
+    tool::{ToolAlias, ToolSpec},
+};
+
+/**
+    Error type returned by [`InstallHooks`].
+
+    Hooks may return any error, such as when a policy
+    engine could not be reached to make a decision.
+*/
+pub type HookError = Box<dyn Error + Send + Sync>;
+
+/**
+    The decision of a hook in [`InstallHooks`] about an operation.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookAction<T> {
+    /// Continue with the operation, using the given, possibly modified, value.
+    Continue(T),
+    /// Stop the operation, failing the install with the given reason.
+    Veto(String),
+}
+
+/**
+    Intercepts operations of [`Session::install_all`](super::Session::install_all)
+    before they happen, and may veto or modify them.
+
+    Embedders may implement this trait to enforce their own policies without
+    forking Rokit, for example to only allow tools from an approved list,
+    or to download artifacts from an internal mirror - all hooks
+    continue with the unmodified operation by default.
+
+    Unlike an [`InstallObserver`](super::InstallObserver), which is only
+    told about progress, hooks are awaited before each operation continues.
+*/
+pub trait InstallHooks: fmt::Debug + Send + Sync {
+    /**
+        Called before resolving the release of a tool that needs to be installed.
+    */
+    fn pre_resolve<'a>(
+        &'a self,
+        _spec: &'a ToolSpec,
+    ) -> BoxFuture<'a, Result<HookAction<()>, HookError>> {
+        Box::pin(async { Ok(HookAction::Continue(())) })
+    }
+
+    /**
+        Called before downloading an artifact, which may be modified,
+        such as by changing its URL to download it from a mirror.
+
+        Note that the artifact may also be a delta, in which case
+        vetoing it falls back to downloading the full artifact.
+    */
+    fn pre_download(
+        &self,
+        artifact: Artifact,
+    ) -> BoxFuture<'_, Result<HookAction<Artifact>, HookError>> {
+        Box::pin(async move { Ok(HookAction::Continue(artifact)) })
+    }
+
+    /**
+        Called before creating the link for a tool alias.
+    */
+    fn pre_link<'a>(
+        &'a self,
+        _alias: &'a ToolAlias,
+    ) -> BoxFuture<'a, Result<HookAction<()>, HookError>> {
+        Box::pin(async { Ok(HookAction::Continue(())) })
+    }
+}
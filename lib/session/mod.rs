@@ -0,0 +1,1360 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    path::Path,
+    process::{Command, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use futures::{
+    stream::{self, FuturesUnordered},
+    StreamExt, TryStreamExt,
+};
+use tracing::{debug, instrument, warn};
+use url::Url;
+
+use crate::{
+    discovery::{discover_all_manifests_in, find_alias_collisions, DiscoveredManifest},
+    executor::{fs::read, process::command_output_with_timeout},
+    manifests::{InstallTarget, PolicyManifest},
+    prompt::{prompt_for_trust, DownloadPrompt, Prompter},
+    result::{RokitError, RokitResult},
+    sources::{git::AssetTemplate, Artifact, ArtifactSource, Release, UrlSource},
+    storage::{Home, RecordedArtifact},
+    tool::{ToolAlias, ToolId, ToolSpec},
+    warnings::{RokitWarning, RokitWarnings},
+};
+
+mod hooks;
+
+pub use self::hooks::{HookAction, HookError, InstallHooks};
+
+/**
+    The number of steps that installing a single tool is made up of,
+    reported to an [`InstallObserver`] - resolving its release, picking
+    an artifact, downloading it, extracting it, and verifying it.
+*/
+pub const STEPS_PER_TOOL: usize = 5;
+
+const TOOL_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/**
+    How thoroughly tools are verified when installed by [`Session::install_all`].
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationLevel {
+    /// Tools are installed without any verification.
+    None,
+    /// Artifacts are checked against the hash recorded when the same
+    /// tool version was first installed, which is recorded if missing.
+    Hashes,
+    /// Artifact hashes are checked, and any checks from
+    /// manifests are run for tools once they are installed.
+    #[default]
+    Full,
+}
+
+impl VerificationLevel {
+    fn checks_hashes(self) -> bool {
+        matches!(self, Self::Hashes | Self::Full)
+    }
+
+    fn runs_checks(self) -> bool {
+        matches!(self, Self::Full)
+    }
+}
+
+/**
+    Restrictions on which artifacts [`Session::install_all`] may install.
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InstallRestrictions {
+    /// Never download anything, and only install tools from local caches - the
+    /// install journal and the shared cache - failing for any other tools.
+    ///
+    /// Note that this does not stop the artifact source from fetching release
+    /// metadata, use [`Home::offline_artifact_source`] to also prevent that.
+    pub offline: bool,
+    /// Only install tool versions that have been installed before, and
+    /// have a recorded artifact hash, failing for any other tool versions.
+    pub locked: bool,
+}
+
+/**
+    Policies for installing tools using [`Session::install_all`].
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InstallPolicy {
+    /// Install all tools, even if they are already installed.
+    pub force: bool,
+    /// Install tools without checking if they have been trusted before.
+    pub skip_trust_check: bool,
+    /// Restrictions on where tools may be installed from.
+    pub restrictions: InstallRestrictions,
+    /// The maximum number of tools to install at once, or `None` for no limit.
+    pub concurrency: Option<usize>,
+    /// How thoroughly tools are verified once downloaded.
+    pub verification: VerificationLevel,
+    /// Fail instead of downloading more than this many bytes in total.
+    pub max_download_size: Option<u64>,
+}
+
+/**
+    A phase of [`Session::install_all`], reported to an [`InstallObserver`].
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallPhase {
+    /// Releases and artifacts are being resolved for all tools.
+    Resolving,
+    /// Tools are being downloaded, extracted, and verified.
+    Installing,
+    /// Links are being created for all tool aliases.
+    Linking,
+}
+
+/**
+    Observes the progress of [`Session::install_all`].
+
+    Embedders may implement this trait to display progress, for
+    example as a progress bar - all methods do nothing by default.
+    Every tool completes [`STEPS_PER_TOOL`] steps, or is completed
+    all at once when it does not need to be installed.
+*/
+pub trait InstallObserver: fmt::Debug + Send + Sync {
+    /**
+        Called before asking the [`Prompter`] to trust the given tools.
+    */
+    fn untrusted_tools(&self, _tool_ids: &[ToolId]) {}
+
+    /**
+        Called when a new phase starts, with the number of tools to install.
+    */
+    fn phase_started(&self, _phase: InstallPhase, _num_tools: usize) {}
+
+    /**
+        Called when a single step of installing the given tool completed.
+    */
+    fn step_completed(&self, _spec: &ToolSpec) {}
+
+    /**
+        Called when the given tool completed all of its remaining steps at once.
+    */
+    fn tool_completed(&self, _spec: &ToolSpec) {}
+}
+
+/**
+    Time spent in each phase of installing a single tool.
+
+    Phases that were skipped, such as downloading and extracting
+    a tool that was already installed, will have a zero duration.
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InstallTimings {
+    pub resolve: Duration,
+    pub download: Duration,
+    pub extract: Duration,
+    pub link: Duration,
+}
+
+impl InstallTimings {
+    /**
+        Gets the total time spent installing the tool.
+    */
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.resolve + self.download + self.extract + self.link
+    }
+}
+
+/**
+    A single tool handled by [`Session::install_all`].
+*/
+#[derive(Debug, Clone)]
+pub struct InstalledTool {
+    /// The tool version that was handled.
+    pub spec: ToolSpec,
+    /// All aliases that link to the tool, sorted.
+    pub aliases: Vec<ToolAlias>,
+    /// The artifact that was downloaded, or `None` if the tool was already installed.
+    pub artifact: Option<Artifact>,
+    /// The SHA-256 hash of the downloaded artifact, if any.
+    pub sha256: Option<String>,
+    /// Time spent in each phase of installing the tool.
+    pub timings: InstallTimings,
+}
+
+impl InstalledTool {
+    fn already_installed(spec: &ToolSpec) -> Self {
+        Self {
+            spec: spec.clone(),
+            aliases: Vec::new(),
+            artifact: None,
+            sha256: None,
+            timings: InstallTimings::default(),
+        }
+    }
+
+    fn installed(
+        spec: &ToolSpec,
+        artifact: Artifact,
+        sha256: String,
+        timings: InstallTimings,
+    ) -> Self {
+        Self {
+            spec: spec.clone(),
+            aliases: Vec::new(),
+            artifact: Some(artifact),
+            sha256: Some(sha256),
+            timings,
+        }
+    }
+
+    /**
+        Checks if the tool was downloaded and installed, instead of already being installed.
+    */
+    #[must_use]
+    pub fn was_installed(&self) -> bool {
+        self.artifact.is_some()
+    }
+}
+
+/**
+    The outcome of [`Session::install_all`].
+*/
+#[derive(Debug, Clone)]
+pub struct InstallSummary {
+    /// All tools that were handled, sorted by their specification.
+    pub tools: Vec<InstalledTool>,
+    /// The total size of all artifacts that were resolved, in bytes, if known.
+    pub download_size: u64,
+    /// Whether any man pages or completions were installed.
+    pub installed_extras: bool,
+    /// Any warnings encountered while installing tools.
+    pub warnings: RokitWarnings,
+}
+
+/**
+    A session for installing tools into a Rokit [`Home`], using
+    the given artifact source and prompter for any questions.
+
+    This is the same pipeline that the `rokit install` command
+    uses, for embedders that want to install tools in one call.
+*/
+#[derive(Debug)]
+pub struct Session<'a> {
+    home: &'a Home,
+    source: ArtifactSource,
+    prompter: &'a dyn Prompter,
+    observer: Option<&'a dyn InstallObserver>,
+    hooks: Option<&'a dyn InstallHooks>,
+}
+
+impl<'a> Session<'a> {
+    /**
+        Creates a new session for the given home, artifact source, and prompter.
+    */
+    #[must_use]
+    pub fn new(home: &'a Home, source: ArtifactSource, prompter: &'a dyn Prompter) -> Self {
+        Self {
+            home,
+            source,
+            prompter,
+            observer: None,
+            hooks: None,
+        }
+    }
+
+    /**
+        Adds an observer for the progress of installing tools to this session.
+    */
+    #[must_use]
+    pub fn with_observer(mut self, observer: &'a dyn InstallObserver) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /**
+        Adds hooks that may veto or modify operations to this session.
+    */
+    #[must_use]
+    pub fn with_hooks(mut self, hooks: &'a dyn InstallHooks) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /**
+        Gets the artifact source used by this session.
+    */
+    #[must_use]
+    pub fn source(&self) -> &ArtifactSource {
+        &self.source
+    }
+
+    fn observe(&self, f: impl FnOnce(&dyn InstallObserver)) {
+        if let Some(observer) = self.observer {
+            f(observer);
+        }
+    }
+
+    async fn hook_pre_resolve(&self, spec: &ToolSpec) -> RokitResult<()> {
+        let Some(hooks) = self.hooks else {
+            return Ok(());
+        };
+        let action = hooks.pre_resolve(spec).await.map_err(RokitError::Hook)?;
+        vetoed("resolving", spec.to_string(), action)
+    }
+
+    async fn hook_pre_download(&self, artifact: Artifact) -> RokitResult<Artifact> {
+        let Some(hooks) = self.hooks else {
+            return Ok(artifact);
+        };
+        let spec = artifact.tool_spec.to_string();
+        let action = hooks
+            .pre_download(artifact)
+            .await
+            .map_err(RokitError::Hook)?;
+        vetoed("downloading", spec, action)
+    }
+
+    async fn hook_pre_link(&self, alias: &ToolAlias) -> RokitResult<()> {
+        let Some(hooks) = self.hooks else {
+            return Ok(());
+        };
+        let action = hooks.pre_link(alias).await.map_err(RokitError::Hook)?;
+        vetoed("linking", alias.to_string(), action)
+    }
+
+    /**
+        Installs all tools for the manifests found in the given directory.
+
+        See [`Session::install_all`] for more information.
+
+        # Errors
+
+        - If the taps manifest could not be loaded.
+        - If any tool could not be installed.
+    */
+    pub async fn install_all_in(
+        &self,
+        dir: impl AsRef<Path>,
+        policy: &InstallPolicy,
+    ) -> RokitResult<InstallSummary> {
+        let taps = self.home.taps().await?;
+        let manifests = discover_all_manifests_in(dir, &taps, false, false).await;
+        self.install_all(&manifests, policy).await
+    }
+
+    /**
+        Installs all tools in the given manifests, and links all of their aliases.
+
+        This will, in order:
+
+        1. Ask the prompter to trust any tools that are not yet trusted,
+           skipping any tools that were not trusted.
+        2. Resolve the most compatible artifact for each tool that is not yet
+           installed, and ask the prompter to confirm the download if it is
+           larger than the size set in the policy manifest.
+        3. Download, extract, and verify all tools, resuming any downloads
+           from an interrupted install, and using the shared cache and
+           deltas from previously installed versions where possible.
+        4. Link all tool aliases, even for tools that were already installed,
+           in case the links have been corrupted.
+
+        Any [`InstallHooks`] of this session are awaited before resolving,
+        downloading, and linking, and may veto or modify those operations.
+
+        # Errors
+
+        - If the policy manifest could not be loaded.
+        - If the prompter returned an error.
+        - If the download is larger than the maximum, or was not confirmed.
+        - If any tool could not be installed, or violates the given policy.
+        - If any hook vetoed an operation, or returned an error.
+        - If any link could not be created.
+    */
+    #[instrument(skip_all, level = "debug")]
+    pub async fn install_all(
+        &self,
+        manifests: &[DiscoveredManifest],
+        policy: &InstallPolicy,
+    ) -> RokitResult<InstallSummary> {
+        let policy_manifest = PolicyManifest::load_or_create(self.home.path()).await?;
+        let tool_cache = self.home.tool_cache();
+        let tool_storage = self.home.tool_storage();
+        let journal = self.home.install_journal();
+        let warnings = RokitWarnings::new();
+        let plan = InstallPlan::new(manifests);
+
+        // NOTE: Tools may be downloaded from their own mirror, which takes
+        // precedence over any mirrors for their provider in the policy manifest
+        let source = plan
+            .mirrors
+            .iter()
+            .fold(self.source.clone(), |source, (id, mirror)| {
+                source.with_tool_mirror(id.clone(), mirror.clone())
+            });
+        let source = plan
+            .git_templates
+            .iter()
+            .fold(source, |source, (id, template)| {
+                source.with_git_asset_template(id.clone(), template.clone())
+            });
+
+        // 1. Gather tool specifications from all manifests, and check for trust
+
+        for warning in find_alias_collisions(manifests) {
+            warnings.push(warning);
+        }
+
+        let tools = manifests
+            .iter()
+            .flat_map(|manifest| manifest.tools.clone().into_iter())
+            .collect::<Vec<_>>();
+        let aliases_by_spec =
+            tools
+                .iter()
+                .fold(HashMap::<_, Vec<_>>::new(), |mut map, (alias, spec)| {
+                    map.entry(spec.clone()).or_default().push(alias.clone());
+                    map
+                });
+
+        // NOTE: Deduplicate tool aliases and specs since they may appear in several manifests,
+        // and also include any aliases that a previous, interrupted run did not finish linking
+        let tool_aliases = tools
+            .iter()
+            .filter(|(_, spec)| !plan.plugins.contains_key(spec))
+            .map(|(alias, _)| alias.clone())
+            .chain(journal.all_unlinked().await)
+            .collect::<BTreeSet<_>>();
+        let tool_specs = tools
+            .into_iter()
+            .map(|(_, spec)| spec)
+            .collect::<BTreeSet<_>>();
+
+        // NOTE: Yanked versions are still installed if pinned exactly, since
+        // the user may depend on them, but we should make it very obvious
+        for spec in tool_specs.iter().filter(|s| policy_manifest.is_yanked(s)) {
+            warnings.push(RokitWarning::YankedVersion { spec: spec.clone() });
+        }
+
+        let tool_specs = if policy.skip_trust_check {
+            tool_specs
+        } else {
+            self.trusted_specs(&source, tool_specs).await?
+        };
+
+        // 2. Find artifacts for all tools that need to be installed, so
+        // that we know how much to download before downloading anything
+
+        self.observe(|o| o.phase_started(InstallPhase::Resolving, tool_specs.len()));
+        let (source, plan, warnings_ref) = (&source, &plan, &warnings);
+        let needs_install = tool_specs
+            .iter()
+            .filter(|spec| !plan.plugins.contains_key(*spec))
+            .filter(|spec| policy.force || !tool_cache.is_installed(spec))
+            .collect::<Vec<_>>();
+        let resolved = if policy.restrictions.offline {
+            self.resolve_offline(source, plan, policy, &tool_specs, warnings_ref)
+                .await?
+        } else {
+            // NOTE: Releases for many tools can be fetched using a single request
+            // from some providers, which is much faster for large manifests
+            let batched = needs_install
+                .iter()
+                .filter(|spec| !plan.urls.contains_key(**spec))
+                .map(|spec| (*spec).clone())
+                .collect::<Vec<_>>();
+            let prefetched = &source.get_specific_releases(&batched).await;
+            stream::iter(needs_install)
+                .map(|spec| async move {
+                    self.resolve_tool(source, plan, policy, spec, prefetched, warnings_ref)
+                        .await
+                        .map(|resolved| (spec.clone(), resolved))
+                        .map_err(|e| install_error(spec, e))
+                })
+                .buffer_unordered(concurrency(policy))
+                .try_collect::<HashMap<_, _>>()
+                .await?
+        };
+
+        // NOTE: Sizes not known from the provider are checked using
+        // cached HEAD requests, so repeated installs stay fast
+        let download_size = resolved
+            .values()
+            .map(|resolved| source.get_artifact_size(&resolved.artifact))
+            .collect::<FuturesUnordered<_>>()
+            .filter_map(|size| async move { size })
+            .fold(0, |total, size| async move { total + size })
+            .await;
+        if let Some(max) = policy.max_download_size {
+            if download_size > max {
+                return Err(RokitError::DownloadTooLarge {
+                    size: download_size,
+                    max,
+                });
+            }
+        }
+        if policy_manifest
+            .confirm_download_size()
+            .is_some_and(|confirm| download_size > confirm)
+        {
+            let prompt = DownloadPrompt {
+                size: download_size,
+            };
+            let confirmed = self
+                .prompter
+                .confirm_download(prompt)
+                .await
+                .map_err(RokitError::Prompt)?;
+            if !confirmed {
+                return Err(RokitError::InstallCancelled);
+            }
+        }
+
+        // 3. Download and install all of the tools
+
+        self.observe(|o| o.phase_started(InstallPhase::Installing, tool_specs.len()));
+        let installed_extras = AtomicBool::new(false);
+        let mut installed_tools = stream::iter(tool_specs)
+            .map(|spec| async {
+                // HACK: Force the async closure to take ownership of spec
+                let spec = spec;
+                let result = if let Some(alias) = plan.plugins.get(&spec) {
+                    self.install_plugin(source, plan, policy, alias, &spec)
+                        .await
+                } else if let Some(resolved) = resolved.get(&spec) {
+                    self.install_tool(source, plan, policy, resolved, &installed_extras)
+                        .await
+                } else {
+                    self.observe(|o| o.tool_completed(&spec));
+                    Ok(InstalledTool::already_installed(&spec))
+                };
+                result.map_err(|e| install_error(&spec, e))
+            })
+            .buffer_unordered(concurrency(policy))
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        // 4. Link all of the (possibly new) aliases, we do this even if the
+        // tool is already installed in case the link(s) have been corrupted
+        // and the user tries to re-install tools to fix it.
+
+        self.observe(|o| o.phase_started(InstallPhase::Linking, tool_aliases.len()));
+        journal
+            .record_unlinked(tool_aliases.iter().cloned())
+            .await?;
+        let link_timings = stream::iter(&tool_aliases)
+            .map(|alias| async move {
+                self.hook_pre_link(alias).await?;
+                let link_start = Instant::now();
+                tool_storage.create_tool_link(alias).await?;
+                Ok::<_, RokitError>((alias, link_start.elapsed()))
+            })
+            .buffer_unordered(concurrency(policy))
+            .try_collect::<HashMap<_, _>>()
+            .await?;
+        journal.record_linked(&tool_aliases).await?;
+
+        for tool in &mut installed_tools {
+            let mut aliases = aliases_by_spec.get(&tool.spec).cloned().unwrap_or_default();
+            aliases.sort();
+            aliases.dedup();
+            tool.timings.link = aliases
+                .iter()
+                .filter_map(|alias| link_timings.get(alias))
+                .sum();
+            tool.aliases = aliases;
+        }
+        // NOTE: Tools are installed concurrently and finish in
+        // any order, sort them to keep summaries stable between runs
+        installed_tools.sort_by(|a, b| a.spec.cmp(&b.spec));
+
+        Ok(InstallSummary {
+            tools: installed_tools,
+            download_size,
+            installed_extras: installed_extras.load(Ordering::Relaxed),
+            warnings,
+        })
+    }
+
+    /*
+        Asks the prompter to trust all tools that are not yet trusted, and
+        returns the given specs without any tools that were not trusted.
+    */
+    async fn trusted_specs(
+        &self,
+        source: &ArtifactSource,
+        tool_specs: BTreeSet<ToolSpec>,
+    ) -> RokitResult<BTreeSet<ToolSpec>> {
+        let tool_cache = self.home.tool_cache();
+        let untrusted_ids = tool_specs
+            .iter()
+            .map(|spec| spec.id().clone())
+            .filter(|id| !tool_cache.is_trusted(id))
+            .collect::<BTreeSet<_>>();
+        if untrusted_ids.is_empty() {
+            return Ok(tool_specs);
+        }
+
+        let untrusted_ids = untrusted_ids.into_iter().collect::<Vec<_>>();
+        self.observe(|o| o.untrusted_tools(&untrusted_ids));
+        let trusted_ids =
+            prompt_for_trust(self.prompter, source, tool_cache, untrusted_ids).await?;
+        for id in &trusted_ids {
+            let _ = tool_cache.add_trust(id.clone());
+        }
+
+        Ok(tool_specs
+            .into_iter()
+            .filter(|spec| tool_cache.is_trusted(spec.id()))
+            .collect())
+    }
+
+    /*
+        Resolves all tools that need to be installed while offline, which is only
+        possible for tools whose artifact is in the install journal or shared cache.
+
+        Instead of failing for the first tool that is missing, all of them
+        are gathered, so that they can be listed together in a single error.
+    */
+    async fn resolve_offline(
+        &self,
+        source: &ArtifactSource,
+        plan: &InstallPlan,
+        policy: &InstallPolicy,
+        tool_specs: &BTreeSet<ToolSpec>,
+        warnings: &RokitWarnings,
+    ) -> RokitResult<HashMap<ToolSpec, ResolvedTool>> {
+        let tool_cache = self.home.tool_cache();
+        let results = stream::iter(tool_specs)
+            .map(|spec| async move {
+                // NOTE: Plugins are never journaled or cached,
+                // so they are only available once installed
+                if plan.plugins.contains_key(spec) {
+                    let installed = tool_cache.is_plugin_installed(spec) && !policy.force;
+                    return Ok((spec, None, !installed));
+                }
+                if tool_cache.is_installed(spec) && !policy.force {
+                    return Ok((spec, None, false));
+                }
+                match self
+                    .resolve_tool(source, plan, policy, spec, &HashMap::new(), warnings)
+                    .await
+                {
+                    Ok(resolved) if self.is_available_locally(&resolved).await => {
+                        Ok((spec, Some(resolved), false))
+                    }
+                    Ok(_) => Ok((spec, None, true)),
+                    Err(e @ (RokitError::Vetoed { .. } | RokitError::Hook(_))) => {
+                        Err(install_error(spec, e))
+                    }
+                    Err(e) => {
+                        debug!(%spec, error = %e, "failed to resolve tool while offline");
+                        Ok((spec, None, true))
+                    }
+                }
+            })
+            .buffer_unordered(concurrency(policy))
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut missing = results
+            .iter()
+            .filter(|(_, _, missing)| *missing)
+            .map(|(spec, _, _)| (*spec).clone())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            missing.sort();
+            return Err(RokitError::Offline(missing));
+        }
+
+        Ok(results
+            .into_iter()
+            .filter_map(|(spec, resolved, _)| Some((spec.clone(), resolved?)))
+            .collect())
+    }
+
+    /*
+        Checks if the artifact for the given tool can be installed without
+        downloading it, meaning it is in the install journal or shared cache.
+    */
+    async fn is_available_locally(&self, resolved: &ResolvedTool) -> bool {
+        if self
+            .home
+            .install_journal()
+            .has_download(&resolved.spec)
+            .await
+        {
+            return true;
+        }
+        self.home.shared_cache().contains(&resolved.artifact).await
+    }
+
+    /*
+        Checks that the given tool may be downloaded under the given policy.
+    */
+    fn check_download_allowed(&self, policy: &InstallPolicy, spec: &ToolSpec) -> RokitResult<()> {
+        if policy.restrictions.locked && self.home.tool_cache().artifact_hash(spec).is_none() {
+            return Err(RokitError::NotLocked(spec.clone().into()));
+        }
+        Ok(())
+    }
+
+    async fn resolve_tool(
+        &self,
+        source: &ArtifactSource,
+        plan: &InstallPlan,
+        policy: &InstallPolicy,
+        spec: &ToolSpec,
+        prefetched: &HashMap<ToolSpec, Release>,
+        warnings: &RokitWarnings,
+    ) -> RokitResult<ResolvedTool> {
+        self.check_download_allowed(policy, spec)?;
+        self.hook_pre_resolve(spec).await?;
+        let phase_start = Instant::now();
+
+        if let Some(url) = plan.urls.get(spec) {
+            // NOTE: Tools from direct URLs only ever have the single artifact
+            // that the user chose, so there is nothing to pick or exclude
+            let release = UrlSource::new(url.clone())?.release_for(spec);
+            let artifact = release.artifacts[0].clone();
+            self.observe(|o| o.step_completed(spec));
+            self.observe(|o| o.step_completed(spec));
+            return Ok(ResolvedTool {
+                spec: spec.clone(),
+                release,
+                artifact,
+                recorded: None,
+                elapsed: phase_start.elapsed(),
+            });
+        }
+
+        // NOTE: If release metadata can not be fetched, such as when the provider
+        // is down, we may still install the artifact recorded for this exact
+        // version when it was last installed, as long as its hash still matches
+        let mut recorded = None;
+        let release = match prefetched.get(spec) {
+            Some(release) => release.clone(),
+            None => match source.get_specific_release(spec).await {
+                Ok(release) => release,
+                Err(e) => {
+                    let Some(artifact) = self.home.tool_cache().recorded_artifact(spec) else {
+                        return Err(e);
+                    };
+                    warnings.push(RokitWarning::RecordedArtifact {
+                        spec: spec.clone(),
+                        reason: e.to_string(),
+                    });
+                    recorded.insert(artifact).to_release(spec)
+                }
+            },
+        };
+        self.observe(|o| o.step_completed(spec));
+
+        let candidates = match plan.excludes.get(spec) {
+            Some(patterns) => Artifact::filter_excluded(&release.artifacts, patterns),
+            None => release.artifacts.clone(),
+        };
+        // NOTE: Installers are only ever chosen when nothing else is compatible,
+        // and since they can not be installed, that means nothing is compatible
+        let artifact = Artifact::find_most_compatible(&candidates, warnings)
+            .filter(|artifact| !artifact.is_installer())
+            .ok_or_else(|| RokitError::NoCompatibleArtifact(spec.clone().into()))?;
+        self.observe(|o| o.step_completed(spec));
+
+        Ok(ResolvedTool {
+            spec: spec.clone(),
+            release,
+            artifact,
+            recorded,
+            elapsed: phase_start.elapsed(),
+        })
+    }
+
+    #[allow(clippy::too_many_lines)]
+    async fn install_tool(
+        &self,
+        source: &ArtifactSource,
+        plan: &InstallPlan,
+        policy: &InstallPolicy,
+        resolved: &ResolvedTool,
+        installed_extras: &AtomicBool,
+    ) -> RokitResult<InstalledTool> {
+        let tool_cache = self.home.tool_cache();
+        let tool_storage = self.home.tool_storage();
+        let journal = self.home.install_journal();
+        let shared_cache = self.home.shared_cache();
+        // NOTE: Tools can only be installed from local caches while offline,
+        // so we must use those even when forced, unlike when online
+        let force = policy.force && !policy.restrictions.offline;
+        let spec = &resolved.spec;
+        let artifact = &resolved.artifact;
+
+        let mut timings = InstallTimings {
+            resolve: resolved.elapsed,
+            ..InstallTimings::default()
+        };
+
+        // If a previous run was interrupted after downloading
+        // this tool, we can skip downloading it all over again
+        let phase_start = Instant::now();
+        let journaled = if force {
+            None
+        } else {
+            journal.read_download(spec).await
+        };
+
+        // This exact artifact may have been downloaded before, by another project
+        // or by other jobs using the same shared cache, which beats downloading a delta
+        let cached = if force || journaled.is_some() {
+            None
+        } else {
+            shared_cache.read(artifact).await
+        };
+
+        // If an older version of this tool is already installed, and the release
+        // contains a delta for it, we only need to download that delta - note that
+        // we never use deltas when forced, since the old binary may be what's broken,
+        // or for tools installed as directory trees, since deltas only contain the binary
+        // - the same goes for tools with a pinned checksum for their full artifact, or
+        // with a recorded artifact hash but no binary hash to verify the delta against
+        let entrypoints = plan.entrypoints(spec);
+        let has_unverifiable_hash = policy.verification.checks_hashes()
+            && tool_cache.artifact_hash(spec).is_some()
+            && tool_cache.binary_hash(spec).is_none();
+        let delta = if !cfg!(feature = "zstd")
+            || force
+            || journaled.is_some()
+            || cached.is_some()
+            || !entrypoints.is_empty()
+            || plan.checksums.contains_key(spec)
+            || has_unverifiable_hash
+        {
+            None
+        } else {
+            self.download_delta(source, &resolved.release.artifacts, artifact)
+                .await
+        };
+
+        let mut downloaded_artifact = artifact.clone();
+        let (contents, delta) = if let Some(contents) = journaled {
+            (contents, None)
+        } else if let Some((delta_artifact, contents, base)) = delta {
+            (contents, Some((delta_artifact, base)))
+        } else if let Some(contents) = cached {
+            (contents, None)
+        } else {
+            downloaded_artifact = self.hook_pre_download(artifact.clone()).await?;
+            let contents = source
+                .download_artifact_contents(&downloaded_artifact)
+                .await?;
+            // NOTE: The shared cache is only an optimization, and failing to
+            // write to it should never fail the install - the download is then
+            // not journaled either, since the journal only refers to the cache
+            match shared_cache.write(artifact, &contents).await {
+                Ok(path) => journal.record_download(spec, path).await?,
+                Err(e) => warn!(%spec, error = %e, "failed to write to shared cache"),
+            }
+            (contents, None)
+        };
+        timings.download = phase_start.elapsed();
+        self.observe(|o| o.step_completed(spec));
+
+        let sha256 = Artifact::compute_sha256(&contents);
+        plan.check_checksum(spec, &sha256)?;
+        if let Some(recorded) = &resolved.recorded {
+            if recorded.sha256 != sha256 {
+                return Err(RokitError::ArtifactHashChanged {
+                    spec: spec.clone().into(),
+                    expected: recorded.sha256.clone(),
+                    actual: sha256,
+                });
+            }
+        }
+        // NOTE: Deltas can not be compared with the hash of the full artifact, but
+        // must match the digest published for the delta itself, and the binary
+        // reconstructed from it is checked against the recorded binary hash below
+        if policy.verification.checks_hashes() {
+            if let Some((delta_artifact, _)) = &delta {
+                check_published_digest(source, delta_artifact, &sha256).await?;
+            } else {
+                check_published_digest(source, artifact, &sha256).await?;
+                tool_cache.check_artifact_hash(spec, &sha256)?;
+            }
+        }
+        if delta.is_none() && tool_cache.record_artifact(spec, artifact, &sha256) {
+            debug!(%spec, "replaced recorded artifact for tool");
+        }
+        let downloaded_artifact = delta
+            .as_ref()
+            .map_or(downloaded_artifact, |(a, _)| a.clone());
+        let phase_start = Instant::now();
+
+        // NOTE: Deltas only ever contain the binary, so any extra
+        // files from the previous version are left as they are
+        let extras = if plan.extras.contains(spec) && delta.is_none() {
+            artifact
+                .extract_extra_files(&contents)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!(%spec, error = %e, "failed to extract extra files");
+                    Vec::new()
+                })
+        } else {
+            Vec::new()
+        };
+
+        if entrypoints.is_empty() {
+            let extracted = match delta {
+                Some((delta_artifact, base_contents)) => {
+                    apply_delta(&delta_artifact, contents, base_contents).await?
+                }
+                None => artifact.extract_contents(contents).await?,
+            };
+            if policy.verification.checks_hashes() {
+                let binary_sha256 = Artifact::compute_sha256(&extracted);
+                tool_cache.check_binary_hash(spec, &binary_sha256)?;
+            }
+            self.observe(|o| o.step_completed(spec));
+
+            journal.record_installing(spec).await?;
+            tool_storage.replace_tool_contents(spec, extracted).await?;
+        } else {
+            let staged = tool_storage.create_tool_staging_dir(spec).await?;
+            artifact.unpack_contents(contents, staged.path()).await?;
+            self.observe(|o| o.step_completed(spec));
+
+            journal.record_installing(spec).await?;
+            tool_storage
+                .replace_tool_dir(spec, staged, &entrypoints)
+                .await?;
+        }
+        if !extras.is_empty() {
+            tool_storage.replace_extra_files(&extras).await?;
+            installed_extras.store(true, Ordering::Relaxed);
+        }
+        timings.extract = phase_start.elapsed();
+
+        // Checks run the tool binary, so they must only ever run for trusted tools
+        if let Some((alias, check)) = plan.checks.get(spec) {
+            if !policy.verification.runs_checks() {
+                debug!(%spec, "skipping check for tool");
+            } else if tool_cache.is_trusted(spec.id()) {
+                let path = tool_storage.entrypoint_path(spec, plan.entrypoint_for(spec, alias));
+                run_tool_check(&path, spec, alias, check).await?;
+            } else {
+                debug!(%spec, "skipping check for untrusted tool");
+            }
+        }
+        self.observe(|o| o.step_completed(spec));
+
+        let _ = tool_cache.add_installed(spec.clone());
+        journal.record_installed(spec).await?;
+        Ok(InstalledTool::installed(
+            spec,
+            downloaded_artifact,
+            sha256,
+            timings,
+        ))
+    }
+
+    /*
+        Downloads a delta for the given artifact, if the release contains one
+        for a version of the tool that is already installed, together with the
+        contents of the installed binary that the delta should be applied to.
+
+        Any failure here is not fatal, and simply means the full artifact is used.
+    */
+    async fn download_delta(
+        &self,
+        source: &ArtifactSource,
+        artifacts: &[Artifact],
+        artifact: &Artifact,
+    ) -> Option<(Artifact, Vec<u8>, Vec<u8>)> {
+        let id = artifact.tool_spec.id();
+        let installed_versions = self.home.tool_cache().all_installed_versions_for_id(id);
+        let delta = Artifact::find_delta(artifacts, artifact, &installed_versions)?;
+        // NOTE: Deltas that are vetoed simply fall back to the full artifact,
+        // which is then given to the same hook before it is downloaded
+        let delta = self.hook_pre_download(delta).await.ok()?;
+
+        let base_spec = ToolSpec::from((id.clone(), delta.delta_base_version()?));
+        let base_path = self.home.tool_storage().tool_path(&base_spec);
+        let base_contents = read(base_path).await.ok()?;
+
+        match source.download_artifact_contents(&delta).await {
+            Ok(contents) => {
+                debug!(%base_spec, "downloaded delta for tool");
+                Some((delta, contents, base_contents))
+            }
+            Err(e) => {
+                debug!(%base_spec, error = %e, "failed to download delta for tool");
+                None
+            }
+        }
+    }
+
+    /*
+        Installs a tool that is a Roblox Studio plugin into the Roblox plugins directory.
+
+        Plugins are not extracted, checked, or linked - the
+        plugin file from the release is written as-is.
+    */
+    async fn install_plugin(
+        &self,
+        source: &ArtifactSource,
+        plan: &InstallPlan,
+        policy: &InstallPolicy,
+        alias: &ToolAlias,
+        spec: &ToolSpec,
+    ) -> RokitResult<InstalledTool> {
+        let tool_cache = self.home.tool_cache();
+        if tool_cache.is_plugin_installed(spec) && !policy.force {
+            self.observe(|o| o.tool_completed(spec));
+            return Ok(InstalledTool::already_installed(spec));
+        }
+        self.check_download_allowed(policy, spec)?;
+        self.hook_pre_resolve(spec).await?;
+
+        let mut timings = InstallTimings::default();
+        let phase_start = Instant::now();
+        let release = source.get_specific_release(spec).await?;
+        let artifact = Artifact::find_plugin(&release.artifacts)
+            .ok_or_else(|| RokitError::NoPluginFile(spec.clone().into()))?;
+        let extension = artifact.plugin_extension().unwrap_or("rbxm");
+        timings.resolve = phase_start.elapsed();
+
+        let phase_start = Instant::now();
+        let artifact = self.hook_pre_download(artifact).await?;
+        let contents = source.download_artifact_contents(&artifact).await?;
+        timings.download = phase_start.elapsed();
+
+        let sha256 = Artifact::compute_sha256(&contents);
+        plan.check_checksum(spec, &sha256)?;
+        if policy.verification.checks_hashes() {
+            check_published_digest(source, &artifact, &sha256).await?;
+            tool_cache.check_artifact_hash(spec, &sha256)?;
+        }
+        let phase_start = Instant::now();
+        let path = self
+            .home
+            .tool_storage()
+            .replace_plugin_contents(alias, extension, contents)
+            .await?;
+        timings.extract = phase_start.elapsed();
+        debug!(%spec, ?path, "installed plugin");
+
+        let _ = tool_cache.add_installed_plugin(spec.clone());
+        self.observe(|o| o.tool_completed(spec));
+        Ok(InstalledTool::installed(spec, artifact, sha256, timings))
+    }
+}
+
+/*
+    A release and artifact resolved for a tool, before downloading anything.
+*/
+#[derive(Debug, Clone)]
+struct ResolvedTool {
+    spec: ToolSpec,
+    release: Release,
+    artifact: Artifact,
+    recorded: Option<RecordedArtifact>,
+    elapsed: Duration,
+}
+
+/*
+    Options for tools from all manifests, keyed by their specification,
+    since options are given per alias but tools are installed per spec.
+*/
+#[derive(Debug, Default)]
+struct InstallPlan {
+    checks: HashMap<ToolSpec, (ToolAlias, Vec<String>)>,
+    plugins: HashMap<ToolSpec, ToolAlias>,
+    extras: HashSet<ToolSpec>,
+    entrypoints: Vec<(ToolSpec, ToolAlias, Option<String>)>,
+    urls: HashMap<ToolSpec, Url>,
+    excludes: HashMap<ToolSpec, Vec<String>>,
+    checksums: HashMap<ToolSpec, String>,
+    mirrors: Vec<(ToolId, Url)>,
+    git_templates: Vec<(ToolId, AssetTemplate)>,
+}
+
+impl InstallPlan {
+    fn new(manifests: &[DiscoveredManifest]) -> Self {
+        let mut plan = Self::default();
+        for manifest in manifests {
+            for (alias, spec) in &manifest.tools {
+                let Some(options) = manifest.options.get(alias) else {
+                    continue;
+                };
+                if let Some(check) = &options.check {
+                    plan.checks
+                        .insert(spec.clone(), (alias.clone(), check.clone()));
+                }
+                // NOTE: Plugins are installed into the Roblox plugins directory
+                // as `<alias>.<ext>` instead of into tool storage, and never linked
+                if options.installs == Some(InstallTarget::Plugin) {
+                    plan.plugins.insert(spec.clone(), alias.clone());
+                }
+                if options.extras {
+                    plan.extras.insert(spec.clone());
+                }
+                // NOTE: Tools installed as a whole directory tree may be used by several
+                // aliases, and each alias may link to a different entrypoint in the tree
+                if options.install_dir {
+                    let entrypoint = options.entrypoint.clone();
+                    plan.entrypoints
+                        .push((spec.clone(), alias.clone(), entrypoint));
+                }
+                if let Some(url) = &options.url {
+                    plan.urls.insert(spec.clone(), url.clone());
+                }
+                if !options.asset_exclude.is_empty() {
+                    let patterns = options.asset_exclude.clone();
+                    plan.excludes.insert(spec.clone(), patterns);
+                }
+                if let Some(sha256) = &options.sha256 {
+                    plan.checksums.insert(spec.clone(), sha256.clone());
+                }
+                if let Some(mirror) = &options.mirror {
+                    plan.mirrors.push((spec.id().clone(), mirror.clone()));
+                }
+                if let Some(template) = options.assets.clone().and_then(AssetTemplate::new) {
+                    plan.git_templates.push((spec.id().clone(), template));
+                }
+            }
+        }
+        plan
+    }
+
+    /*
+        Checks the hash of a downloaded artifact against the checksum pinned
+        in a manifest, if any - unlike hashes recorded in the tool cache,
+        pinned checksums are always checked, regardless of the policy.
+    */
+    fn check_checksum(&self, spec: &ToolSpec, sha256: &str) -> RokitResult<()> {
+        match self.checksums.get(spec) {
+            Some(expected) if expected != sha256 => Err(RokitError::ArtifactChecksumMismatch {
+                spec: spec.clone().into(),
+                expected: expected.clone(),
+                actual: sha256.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn entrypoints(&self, spec: &ToolSpec) -> Vec<Option<&str>> {
+        self.entrypoints
+            .iter()
+            .filter(|(s, _, _)| s == spec)
+            .map(|(_, _, entrypoint)| entrypoint.as_deref())
+            .collect()
+    }
+
+    fn entrypoint_for(&self, spec: &ToolSpec, alias: &ToolAlias) -> Option<&str> {
+        self.entrypoints
+            .iter()
+            .find(|(s, a, _)| s == spec && a == alias)
+            .and_then(|(_, _, entrypoint)| entrypoint.as_deref())
+    }
+}
+
+fn vetoed<T>(operation: &'static str, subject: String, action: HookAction<T>) -> RokitResult<T> {
+    match action {
+        HookAction::Continue(value) => Ok(value),
+        HookAction::Veto(reason) => Err(RokitError::Vetoed {
+            operation,
+            subject,
+            reason,
+        }),
+    }
+}
+
+fn concurrency(policy: &InstallPolicy) -> usize {
+    policy.concurrency.unwrap_or(usize::MAX).max(1)
+}
+
+/*
+    Checks the hash of downloaded artifact contents against the digest
+    published alongside its release, such as in a `SHA256SUMS` file, if any.
+*/
+async fn check_published_digest(
+    source: &ArtifactSource,
+    artifact: &Artifact,
+    sha256: &str,
+) -> RokitResult<()> {
+    match source.get_artifact_sha256(artifact).await {
+        Some(expected) if expected != sha256 => Err(RokitError::ArtifactDigestMismatch {
+            spec: artifact.tool_spec.clone().into(),
+            expected,
+            actual: sha256.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn install_error(spec: &ToolSpec, error: RokitError) -> RokitError {
+    // NOTE: Errors that already name the tool, or that are not about any
+    // single tool, are more useful to callers without being wrapped
+    match error {
+        RokitError::Install { .. }
+        | RokitError::ArtifactHashChanged { .. }
+        | RokitError::ArtifactChecksumMismatch { .. }
+        | RokitError::ArtifactDigestMismatch { .. }
+        | RokitError::Offline(_)
+        | RokitError::NotLocked(_)
+        | RokitError::Vetoed { .. }
+        | RokitError::Prompt(_) => error,
+        error => RokitError::Install {
+            spec: spec.clone().into(),
+            error: error.into(),
+        },
+    }
+}
+
+#[cfg(feature = "zstd")]
+async fn apply_delta(
+    delta_artifact: &Artifact,
+    contents: Vec<u8>,
+    base_contents: Vec<u8>,
+) -> RokitResult<Vec<u8>> {
+    delta_artifact
+        .apply_delta_contents(contents, base_contents)
+        .await
+}
+
+// NOTE: Deltas are compressed using zstd, and are never downloaded without it
+#[cfg(not(feature = "zstd"))]
+#[allow(clippy::unused_async)]
+async fn apply_delta(
+    _delta_artifact: &Artifact,
+    _contents: Vec<u8>,
+    _base_contents: Vec<u8>,
+) -> RokitResult<Vec<u8>> {
+    unreachable!("deltas are never downloaded without zstd")
+}
+
+async fn run_tool_check(
+    path: &Path,
+    spec: &ToolSpec,
+    alias: &ToolAlias,
+    check: &[String],
+) -> RokitResult<()> {
+    let failed = |reason: String| RokitError::ToolCheckFailed {
+        spec: spec.clone().into(),
+        reason,
+    };
+
+    let Some((program, args)) = check.split_first() else {
+        return Err(failed(format!("the check for alias '{alias}' is empty")));
+    };
+    if !program.eq_ignore_ascii_case(alias.name()) {
+        return Err(failed(format!(
+            "the check must run '{alias}' itself, but tried to run '{program}'"
+        )));
+    }
+
+    let command = check.join(" ");
+    let mut check_command = Command::new(path);
+    check_command.args(args).stdin(Stdio::null());
+    let output = command_output_with_timeout(check_command, TOOL_CHECK_TIMEOUT)
+        .await
+        .map_err(|e| failed(format!("`{command}` could not be run - {e}")))?
+        .ok_or_else(|| failed(format!("`{command}` timed out")))?;
+
+    if !output.status.success() {
+        return Err(failed(format!(
+            "`{command}` failed ({}), the tool may not be compatible with the current system\n\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_spec(s: &str) -> ToolSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn verification_levels() {
+        assert!(!VerificationLevel::None.checks_hashes());
+        assert!(VerificationLevel::Hashes.checks_hashes());
+        assert!(!VerificationLevel::Hashes.runs_checks());
+        assert!(VerificationLevel::default().checks_hashes());
+        assert!(VerificationLevel::default().runs_checks());
+    }
+
+    #[test]
+    fn concurrency_is_never_zero() {
+        let mut policy = InstallPolicy::default();
+        assert_eq!(concurrency(&policy), usize::MAX);
+        policy.concurrency = Some(0);
+        assert_eq!(concurrency(&policy), 1);
+        policy.concurrency = Some(4);
+        assert_eq!(concurrency(&policy), 4);
+    }
+
+    #[test]
+    fn vetoed_actions_fail() {
+        assert_eq!(
+            vetoed("linking", String::from("rojo"), HookAction::Continue(1)).unwrap(),
+            1
+        );
+        let err = vetoed::<()>(
+            "resolving",
+            String::from("rojo-rbx/rojo@7.4.1"),
+            HookAction::Veto(String::from("not approved")),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), "vetoed");
+        assert_eq!(
+            err.to_string(),
+            "resolving 'rojo-rbx/rojo@7.4.1' was vetoed - not approved"
+        );
+        assert!(matches!(
+            install_error(&new_spec("rojo-rbx/rojo@7.4.1"), err),
+            RokitError::Vetoed { .. }
+        ));
+    }
+
+    #[test]
+    fn install_errors_name_the_tool() {
+        let spec = new_spec("rojo-rbx/rojo@7.4.1");
+        let err = install_error(&spec, RokitError::InvalidUtf8);
+        assert!(matches!(err, RokitError::Install { .. }));
+        assert_eq!(err.kind(), "invalid-utf8");
+        assert!(err.to_string().contains("rojo-rbx/rojo@7.4.1"));
+
+        // Errors that already name the tool are not wrapped again
+        let err = install_error(&spec, RokitError::NotLocked(spec.clone().into()));
+        assert!(matches!(err, RokitError::NotLocked(_)));
+        let err = install_error(&spec, err);
+        assert!(matches!(err, RokitError::NotLocked(_)));
+    }
+
+    #[test]
+    fn pinned_checksums_must_match() {
+        let spec = new_spec("rojo-rbx/rojo@7.4.1");
+        let sha256 = Artifact::compute_sha256("contents");
+        let mut plan = InstallPlan::default();
+        assert!(plan.check_checksum(&spec, &sha256).is_ok());
+
+        plan.checksums.insert(spec.clone(), sha256.clone());
+        assert!(plan.check_checksum(&spec, &sha256).is_ok());
+
+        let other = Artifact::compute_sha256("other contents");
+        let err = plan.check_checksum(&spec, &other).unwrap_err();
+        assert_eq!(err.kind(), "artifact-checksum-mismatch");
+        assert!(matches!(
+            install_error(&spec, err),
+            RokitError::ArtifactChecksumMismatch { .. }
+        ));
+    }
+}
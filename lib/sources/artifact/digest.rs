@@ -0,0 +1,236 @@
+use super::Artifact;
+
+const COMPANION_SUFFIXES: [&str; 2] = [".sha256", ".sha256sum"];
+
+/**
+    The expected SHA-256 digest of an artifact, published alongside its release.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArtifactDigest {
+    /// The digest is known, such as when computed by the provider itself.
+    Sha256(String),
+    /**
+        The digest is listed in another artifact of the same release, such
+        as a `SHA256SUMS` file or a `<name>.sha256` companion file, under
+        the given file name - it must be downloaded to get the digest.
+
+        Companion files are published for a single artifact, and
+        may contain only the digest itself, without any file name.
+    */
+    Published {
+        file: Box<Artifact>,
+        file_name: String,
+        companion: bool,
+    },
+}
+
+/**
+    Checks if the given release asset name is a file containing
+    SHA-256 digests for other assets, such as `SHA256SUMS`,
+    `checksums.txt`, or a companion file like `tool.zip.sha256`.
+*/
+#[must_use]
+pub fn is_digest_file_name(name: &str) -> bool {
+    let name = name.trim().to_ascii_lowercase();
+    COMPANION_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+        || name.contains("sha256sums")
+        || name.ends_with("checksums.txt")
+}
+
+/*
+    Links each artifact to the digest file that lists it, if the release
+    has one - preferring companion files for the artifact itself, such as
+    `tool.zip.sha256`, over files listing digests for all artifacts.
+
+    The file names must be the full names of the release assets, in the same
+    order as the artifacts. Artifacts that already have a digest are skipped.
+*/
+#[cfg(any(feature = "github", feature = "gitlab"))]
+pub(crate) fn link_published_digests(artifacts: &mut [Artifact], file_names: &[&str]) {
+    let digest_files = artifacts
+        .iter()
+        .zip(file_names)
+        .filter(|(_, name)| is_digest_file_name(name))
+        .map(|(artifact, name)| (artifact.clone(), name.to_ascii_lowercase()))
+        .collect::<Vec<_>>();
+    if digest_files.is_empty() {
+        return;
+    }
+
+    for (artifact, file_name) in artifacts.iter_mut().zip(file_names) {
+        if artifact.digest.is_some() || is_digest_file_name(file_name) {
+            continue;
+        }
+        let lowercase = file_name.to_ascii_lowercase();
+        let companion = digest_files
+            .iter()
+            .find(|(_, name)| companion_of(name) == Some(lowercase.as_str()));
+        let listing = digest_files
+            .iter()
+            .find(|(_, name)| companion_of(name).is_none());
+        if let Some((file, _)) = companion.or(listing) {
+            artifact.digest = Some(ArtifactDigest::Published {
+                file: Box::new(file.clone()),
+                file_name: (*file_name).to_string(),
+                companion: companion.is_some(),
+            });
+        }
+    }
+}
+
+/*
+    Gets the name of the asset that a lowercase digest file name is
+    a companion file for, such as `tool.zip` for `tool.zip.sha256`.
+*/
+#[cfg(any(feature = "github", feature = "gitlab"))]
+fn companion_of(digest_file_name: &str) -> Option<&str> {
+    COMPANION_SUFFIXES
+        .iter()
+        .find_map(|suffix| digest_file_name.strip_suffix(suffix))
+}
+
+/*
+    Finds the SHA-256 digest for the given file name in the contents of a
+    digest file, returning it as a lowercase hexadecimal string. Supports:
+
+    - `<digest>  <name>` and `<digest> *<name>`, as written by `sha256sum`
+    - `SHA256 (<name>) = <digest>`, as written by `shasum --tag` and BSD tools
+    - A single `<digest>`, but only in companion files for a single asset,
+      since a bare digest in a listing could belong to any of its assets
+*/
+#[cfg(feature = "runtime")]
+pub(crate) fn parse_digest_file(
+    contents: &str,
+    file_name: &str,
+    companion: bool,
+) -> Option<String> {
+    let matches_name = |name: &str| {
+        let name = name.trim().trim_start_matches('*');
+        let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+        name.eq_ignore_ascii_case(file_name)
+    };
+
+    for line in contents.lines().map(str::trim) {
+        let digest = if let Some(rest) = line.strip_prefix("SHA256 (") {
+            let Some((name, digest)) = rest.rsplit_once(") = ") else {
+                continue;
+            };
+            matches_name(name).then_some(digest)
+        } else if let Some((digest, name)) = line.split_once(char::is_whitespace) {
+            matches_name(name).then_some(digest)
+        } else {
+            companion.then_some(line)
+        };
+        if let Some(digest) = digest.map(str::trim).filter(|d| is_sha256_hex(d)) {
+            return Some(digest.to_ascii_lowercase());
+        }
+    }
+
+    None
+}
+
+#[cfg(feature = "runtime")]
+fn is_sha256_hex(digest: &str) -> bool {
+    digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "runtime")]
+    const DIGEST_A: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+    #[cfg(feature = "runtime")]
+    const DIGEST_B: &str = "2C26B46B68FFC68FF99B453C1D30413413422D706483BFA0F98A5E886266E7AE";
+
+    #[test]
+    fn digest_file_names() {
+        assert!(is_digest_file_name("SHA256SUMS"));
+        assert!(is_digest_file_name("tool-1.0.0-sha256sums.txt"));
+        assert!(is_digest_file_name("checksums.txt"));
+        assert!(is_digest_file_name("tool_1.0.0_checksums.txt"));
+        assert!(is_digest_file_name("tool-linux-x86_64.zip.sha256"));
+        assert!(!is_digest_file_name("tool-linux-x86_64.zip"));
+        assert!(!is_digest_file_name("tool-linux-x86_64.zip.sig"));
+    }
+
+    #[test]
+    #[cfg(feature = "runtime")]
+    fn parse_formats() {
+        let gnu = format!("{DIGEST_A}  tool-linux.zip\n{DIGEST_B} *./dist/tool-windows.zip\n");
+        assert_eq!(
+            parse_digest_file(&gnu, "tool-linux.zip", false).as_deref(),
+            Some(DIGEST_A)
+        );
+        assert_eq!(
+            parse_digest_file(&gnu, "tool-windows.zip", false),
+            Some(DIGEST_B.to_ascii_lowercase())
+        );
+        assert_eq!(parse_digest_file(&gnu, "tool-macos.zip", false), None);
+
+        let bsd = format!("SHA256 (tool-linux.zip) = {DIGEST_A}\n");
+        assert_eq!(
+            parse_digest_file(&bsd, "tool-linux.zip", false).as_deref(),
+            Some(DIGEST_A)
+        );
+
+        let single = format!("{DIGEST_A}\n");
+        assert_eq!(
+            parse_digest_file(&single, "tool-linux.zip", true).as_deref(),
+            Some(DIGEST_A)
+        );
+        assert_eq!(parse_digest_file("not a digest", "not", true), None);
+    }
+
+    #[test]
+    #[cfg(feature = "runtime")]
+    fn bare_digests_only_in_companion_files() {
+        // A listing without a line for the asset must never match
+        // some other line, even one that looks like a bare digest
+        let listing = format!("{DIGEST_A}  tool-linux.zip\n{DIGEST_B}\n");
+        assert_eq!(parse_digest_file(&listing, "tool-macos.zip", false), None);
+
+        let single = format!("{DIGEST_A}\n");
+        assert_eq!(parse_digest_file(&single, "tool-linux.zip", false), None);
+    }
+
+    #[test]
+    #[cfg(any(feature = "github", feature = "gitlab"))]
+    fn link_prefers_companion_files() {
+        let names = [
+            "tool-linux.zip",
+            "tool-linux.zip.sha256",
+            "tool-windows.zip",
+            "tool-macos.zip",
+            "SHA256SUMS",
+        ];
+        let mut artifacts = names.map(Artifact::for_test).to_vec();
+        artifacts[2].digest = Some(ArtifactDigest::Sha256(String::from(DIGEST_A)));
+        link_published_digests(&mut artifacts, &names);
+
+        assert_eq!(
+            artifacts[0].digest,
+            Some(ArtifactDigest::Published {
+                file: Box::new(Artifact::for_test("tool-linux.zip.sha256")),
+                file_name: String::from("tool-linux.zip"),
+                companion: true,
+            })
+        );
+        assert_eq!(artifacts[1].digest, None);
+        assert_eq!(
+            artifacts[2].digest,
+            Some(ArtifactDigest::Sha256(String::from(DIGEST_A)))
+        );
+        assert_eq!(
+            artifacts[3].digest,
+            Some(ArtifactDigest::Published {
+                file: Box::new(Artifact::for_test("SHA256SUMS")),
+                file_name: String::from("tool-macos.zip"),
+                companion: false,
+            })
+        );
+        assert_eq!(artifacts[4].digest, None);
+    }
+}
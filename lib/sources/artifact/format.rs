@@ -40,6 +40,28 @@ impl ArtifactFormat {
         }
     }
 
+    /**
+        Detects the format of an artifact from the first bytes of its contents.
+
+        Gzip-compressed contents are assumed to be tarballs, since
+        artifacts that are a single compressed binary are rare.
+
+        Returns `None` if the contents are not in any known archive format.
+    */
+    #[must_use]
+    pub fn from_contents(contents: impl AsRef<[u8]>) -> Option<Self> {
+        let contents = contents.as_ref();
+        if contents.starts_with(b"PK\x03\x04") {
+            Some(Self::Zip)
+        } else if contents.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::TarGz)
+        } else if contents.get(257..262) == Some(b"ustar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
     #[must_use]
     pub fn from_path_or_url(path_or_url: impl AsRef<str>) -> Option<Self> {
         let path_or_url = path_or_url.as_ref();
@@ -56,6 +78,7 @@ impl FromStr for ArtifactFormat {
             "zip" => Ok(Self::Zip),
             "tar" => Ok(Self::Tar),
             "tar.gz" | "tgz" => Ok(Self::TarGz),
+            "gz" => Ok(Self::Gz),
             _ => Err(format!("unknown artifact format '{l}'")),
         }
     }
@@ -128,6 +151,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn format_from_contents() {
+        let mut tar = vec![0u8; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(
+            ArtifactFormat::from_contents(b"PK\x03\x04rest"),
+            Some(ArtifactFormat::Zip)
+        );
+        assert_eq!(
+            ArtifactFormat::from_contents([0x1f, 0x8b, 0x08]),
+            Some(ArtifactFormat::TarGz)
+        );
+        assert_eq!(
+            ArtifactFormat::from_contents(&tar),
+            Some(ArtifactFormat::Tar)
+        );
+        assert_eq!(ArtifactFormat::from_contents(b"\x7fELF"), None);
+        assert_eq!(ArtifactFormat::from_contents(b""), None);
+    }
+
     #[test]
     fn format_case_sensitivity() {
         assert_eq!(format_from_str("file.ZIP"), Some(ArtifactFormat::Zip));
@@ -1,41 +1,119 @@
-use tracing::instrument;
+use semver::Version;
 use url::Url;
 
 use crate::{
-    descriptor::{Descriptor, OS},
-    result::RokitResult,
+    descriptor::Descriptor,
     tool::ToolSpec,
+    util::{hash::sha256_hex, str::matches_wildcard},
+    warnings::{RokitWarning, RokitWarnings},
 };
 
+#[cfg(feature = "extract")]
+use tracing::instrument;
+
+#[cfg(feature = "extract")]
+use std::{env::consts::EXE_SUFFIX, path::Path};
+
+#[cfg(feature = "extract")]
+use crate::{descriptor::OS, result::RokitResult};
+
+#[cfg(feature = "extract")]
 use super::{
     decompression::decompress_gzip,
-    extraction::{extract_tar_file, extract_zip_file},
-    github::models::GithubAsset,
-    ExtractError,
+    extraction::{
+        extract_tar_extra_files, extract_tar_file, extract_zip_extra_files, extract_zip_file,
+        unpack_tar_tree, unpack_zip_tree,
+    },
+    ExtraFile, ExtractError,
 };
 
+#[cfg(feature = "zstd")]
+use super::decompression::decompress_zstd_patch;
+
+#[cfg(feature = "github")]
+use super::github::models::GithubAsset;
+
+#[cfg(feature = "gitlab")]
+use super::gitlab::models::GitlabAssetLink;
+
+mod digest;
 mod format;
 mod provider;
 mod sorting;
 mod util;
 
+use self::sorting::sort_installers_last;
 use self::sorting::sort_preferred_artifact;
 use self::sorting::sort_preferred_formats;
-use self::util::split_filename_and_extensions;
 
+pub(crate) use self::util::split_filename_and_extensions;
+
+#[cfg(any(feature = "github", feature = "gitlab"))]
+pub(crate) use self::digest::link_published_digests;
+#[cfg(feature = "runtime")]
+pub(crate) use self::digest::parse_digest_file;
+
+pub use self::digest::{is_digest_file_name, ArtifactDigest};
 pub use self::format::ArtifactFormat;
 pub use self::provider::ArtifactProvider;
 
+const DELTA_INFIX: &str = ".patch-from-";
+const DELTA_SUFFIX: &str = ".zst";
+const PLUGIN_EXTENSIONS: [&str; 2] = ["rbxm", "rbxmx"];
+const INSTALLER_EXTENSIONS: [&str; 7] = ["msi", "msix", "appx", "pkg", "dmg", "deb", "rpm"];
+
 /**
-    A release found by Rokit, containing a list
-    of artifacts, and optionally a changelog.
+    A release found by Rokit, containing its version,
+    a list of artifacts, and optionally a changelog.
 */
 #[derive(Debug, Clone)]
 pub struct Release {
+    pub version: Version,
     pub changelog: Option<String>,
     pub artifacts: Vec<Artifact>,
 }
 
+/**
+    The release notes for a single release of a tool.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseNotes {
+    pub version: Version,
+    pub changelog: String,
+}
+
+impl ReleaseNotes {
+    /**
+        Gets the release notes for the given release,
+        if it has a changelog that is not empty.
+    */
+    #[must_use]
+    pub fn from_release(release: &Release) -> Option<Self> {
+        let changelog = release.changelog.as_deref()?.trim();
+        if changelog.is_empty() {
+            None
+        } else {
+            Some(Self {
+                version: release.version.clone(),
+                changelog: changelog.to_string(),
+            })
+        }
+    }
+
+    /**
+        Gets a short, one-line summary of the release notes, which
+        is the first line that is not empty, without heading markers.
+    */
+    #[must_use]
+    pub fn summary(&self) -> &str {
+        self.changelog
+            .lines()
+            .map(|line| line.trim().trim_start_matches('#').trim())
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+    }
+}
+
 /**
     An artifact found by Rokit, to be downloaded and installed.
 */
@@ -46,11 +124,25 @@ pub struct Artifact {
     pub id: Option<String>,
     pub url: Option<Url>,
     pub name: Option<String>,
+    /// The size of the artifact in bytes, if known before downloading it.
+    pub size: Option<u64>,
+    /// The expected digest of the artifact, if published alongside its release.
+    pub digest: Option<ArtifactDigest>,
     pub tool_spec: ToolSpec,
 }
 
 impl Artifact {
-    pub(crate) fn from_github_release_asset(asset: &GithubAsset, spec: &ToolSpec) -> Self {
+    /**
+        Creates an artifact from a GitHub release asset, for the given tool spec.
+
+        This is useful together with [`GithubProvider::get_latest_github_release`]
+        and similar methods, when implementing custom artifact selection.
+
+        [`GithubProvider::get_latest_github_release`]: crate::sources::github::GithubProvider::get_latest_github_release
+    */
+    #[cfg(feature = "github")]
+    #[must_use]
+    pub fn from_github_release_asset(asset: &GithubAsset, spec: &ToolSpec) -> Self {
         let (name, extensions) = split_filename_and_extensions(&asset.name);
         let format = ArtifactFormat::from_extensions(extensions);
         Self {
@@ -59,6 +151,58 @@ impl Artifact {
             id: Some(asset.id.to_string()),
             url: Some(asset.url.clone()),
             name: Some(name.to_string()),
+            size: Some(asset.size),
+            digest: asset.sha256().map(ArtifactDigest::Sha256),
+            tool_spec: spec.clone(),
+        }
+    }
+
+    /**
+        Create an artifact from a GitLab release asset link.
+
+        GitLab does not know the size of linked assets, so artifacts
+        created using this method never have a size, and are always
+        downloaded using the direct URL of the link, if it has one.
+    */
+    #[cfg(feature = "gitlab")]
+    #[must_use]
+    pub fn from_gitlab_release_link(link: &GitlabAssetLink, spec: &ToolSpec) -> Self {
+        let (name, extensions) = split_filename_and_extensions(&link.name);
+        let format = ArtifactFormat::from_extensions(extensions);
+        Self {
+            provider: ArtifactProvider::GitLab,
+            format,
+            id: Some(link.id.to_string()),
+            url: Some(link.download_url().clone()),
+            name: Some(name.to_string()),
+            size: None,
+            digest: None,
+            tool_spec: spec.clone(),
+        }
+    }
+
+    /**
+        Creates an artifact from a direct URL to an archive or a raw binary.
+
+        The name and format of the artifact are taken from the last path segment of the
+        URL, and artifacts without a known archive format are treated as raw binaries.
+    */
+    #[must_use]
+    pub fn from_url(url: &Url, spec: &ToolSpec) -> Self {
+        let file_name = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default();
+        let (name, extensions) = split_filename_and_extensions(file_name);
+        let format = ArtifactFormat::from_extensions(extensions);
+        Self {
+            provider: ArtifactProvider::Url,
+            format,
+            id: None,
+            url: Some(url.clone()),
+            name: Some(name.to_string()),
+            size: None,
+            digest: None,
             tool_spec: spec.clone(),
         }
     }
@@ -73,9 +217,27 @@ impl Artifact {
         is used to both create and download the artifact, the format
         should be known and the contents should be in the correct format.
     */
+    #[cfg(feature = "extract")]
     #[instrument(skip(self, contents), level = "debug")]
     pub async fn extract_contents(&self, contents: Vec<u8>) -> RokitResult<Vec<u8>> {
-        let format = self.format.ok_or(ExtractError::UnknownFormat)?;
+        // NOTE: Artifacts from a direct URL, a registry, a bucket, a custom provider, crates.io, or a
+        // git repository that are not in any known archive format are expected to be the raw binary
+        let format = match self.format_of(&contents) {
+            Some(format) => format,
+            None if matches!(
+                self.provider,
+                ArtifactProvider::Url
+                    | ArtifactProvider::Registry
+                    | ArtifactProvider::S3
+                    | ArtifactProvider::Custom
+                    | ArtifactProvider::Crates
+                    | ArtifactProvider::Git
+            ) =>
+            {
+                return self.ensure_runnable(contents);
+            }
+            None => return Err(ExtractError::UnknownFormat.into()),
+        };
 
         let file_name = self.tool_spec.name().to_string();
         let file_res = match format {
@@ -111,6 +273,234 @@ impl Artifact {
         // ... and parse the OS from the executable binary, or error,
         // to ensure that the user will actually be able to run it
 
+        self.ensure_runnable(file_bytes)
+    }
+
+    /*
+        Gets the format of this artifact - prebuilt binaries of crates
+        may be in any format, which is only known once they are downloaded.
+    */
+    #[cfg(feature = "extract")]
+    fn format_of(&self, contents: &[u8]) -> Option<ArtifactFormat> {
+        match self.format {
+            None if self.provider == ArtifactProvider::Crates => {
+                ArtifactFormat::from_contents(contents)
+            }
+            format => format,
+        }
+    }
+
+    #[cfg(feature = "extract")]
+    fn ensure_runnable(&self, file_bytes: Vec<u8>) -> RokitResult<Vec<u8>> {
+        let os_current = OS::current_system();
+        let os_file = OS::detect_from_executable(&file_bytes);
+        if os_file.is_some_and(|os| os != os_current) {
+            Err(ExtractError::OSMismatch {
+                current_os: os_current,
+                file_os: os_file.unwrap(),
+                file_name: self.tool_spec.name().to_string(),
+                archive_name: self.name.clone().unwrap_or_default(),
+            })?;
+        }
+
+        Ok(file_bytes)
+    }
+
+    /**
+        Extract any extra files, such as man pages and completion
+        scripts, that are shipped alongside the binary in the artifact.
+
+        The given contents must be the raw bytes of the artifact, same as for
+        [`Artifact::extract_contents`]. Artifacts that are not archives, and
+        so can only ever contain a single binary, never have any extra files.
+    */
+    #[cfg(feature = "extract")]
+    #[instrument(skip(self, contents), level = "debug")]
+    pub async fn extract_extra_files(&self, contents: &[u8]) -> RokitResult<Vec<ExtraFile>> {
+        match self
+            .format_of(contents)
+            .ok_or(ExtractError::UnknownFormat)?
+        {
+            ArtifactFormat::Zip => extract_zip_extra_files(contents).await,
+            ArtifactFormat::Tar => extract_tar_extra_files(contents).await,
+            ArtifactFormat::TarGz => {
+                let tar = decompress_gzip(contents).await?;
+                extract_tar_extra_files(&tar).await
+            }
+            ArtifactFormat::Gz => Ok(Vec::new()),
+        }
+    }
+
+    /**
+        Unpack all files in the artifact into the given directory, keeping
+        the layout of its archive, for tools installed using `install-dir = true`.
+
+        The given contents must be the raw bytes of the artifact, same as for
+        [`Artifact::extract_contents`]. Artifacts that are not archives only
+        contain a single binary, which is written into the directory, named
+        after the tool, the same as when the tool is installed normally.
+
+        Returns the number of files that were unpacked.
+    */
+    #[cfg(feature = "extract")]
+    #[instrument(skip(self, contents), level = "debug")]
+    pub async fn unpack_contents(&self, contents: Vec<u8>, dir: &Path) -> RokitResult<usize> {
+        match self.format_of(&contents) {
+            Some(ArtifactFormat::Zip) => unpack_zip_tree(&contents, dir).await,
+            Some(ArtifactFormat::Tar) => unpack_tar_tree(&contents, dir).await,
+            Some(ArtifactFormat::TarGz) => {
+                let tar = decompress_gzip(&contents).await?;
+                unpack_tar_tree(&tar, dir).await
+            }
+            Some(ArtifactFormat::Gz) | None => {
+                let binary = self.extract_contents(contents).await?;
+                let path = dir.join(format!("{}{EXE_SUFFIX}", self.tool_spec.name()));
+                crate::executor::fs::write(path, binary).await?;
+                Ok(1)
+            }
+        }
+    }
+
+    /**
+        Gets the file extension of this artifact if it is a Roblox
+        plugin, meaning a `.rbxm` or `.rbxmx` model file, or `None` if
+        this artifact is not a Roblox plugin.
+    */
+    #[must_use]
+    pub fn plugin_extension(&self) -> Option<&'static str> {
+        let (_, ext) = self.name.as_deref()?.rsplit_once('.')?;
+        PLUGIN_EXTENSIONS
+            .into_iter()
+            .find(|plugin_ext| plugin_ext.eq_ignore_ascii_case(ext))
+    }
+
+    /**
+        Finds the Roblox plugin artifact among the given artifacts, if any.
+
+        Binary `.rbxm` plugins are preferred over XML `.rbxmx` plugins,
+        since they are smaller and faster for Roblox Studio to load.
+    */
+    #[must_use]
+    pub fn find_plugin(artifacts: impl AsRef<[Self]>) -> Option<Self> {
+        artifacts
+            .as_ref()
+            .iter()
+            .filter_map(|artifact| {
+                let ext = artifact.plugin_extension()?;
+                let preference = PLUGIN_EXTENSIONS.iter().position(|e| *e == ext)?;
+                Some((preference, artifact))
+            })
+            .min_by_key(|(preference, _)| *preference)
+            .map(|(_, artifact)| artifact.clone())
+    }
+
+    /**
+        Checks if this artifact is an installer or system package, such
+        as an `.msi`, `.pkg`, or `.deb` file, which Rokit can not extract.
+
+        Installers are only ever chosen when finding the most compatible
+        artifact if no other compatible artifacts exist.
+    */
+    #[must_use]
+    pub fn is_installer(&self) -> bool {
+        self.name
+            .as_deref()
+            .and_then(|name| name.rsplit_once('.'))
+            .is_some_and(|(_, ext)| {
+                INSTALLER_EXTENSIONS
+                    .iter()
+                    .any(|installer_ext| installer_ext.eq_ignore_ascii_case(ext))
+            })
+    }
+
+    /**
+        Checks if this artifact is a file containing digests for other
+        artifacts, such as `SHA256SUMS` - see [`is_digest_file_name`].
+
+        Digest files are never chosen when finding the most compatible artifact.
+    */
+    #[must_use]
+    pub fn is_digest_file(&self) -> bool {
+        self.name.as_deref().is_some_and(is_digest_file_name)
+    }
+
+    /**
+        Checks if this artifact is a delta - a binary patch that can be
+        applied to a previously installed version of the same tool.
+
+        Deltas are named `<artifact>.patch-from-<version>.zst`, where
+        `<artifact>` is the name of the full artifact without extensions,
+        and are created using `zstd --patch-from=<old binary> <new binary>`.
+    */
+    #[must_use]
+    pub fn is_delta(&self) -> bool {
+        self.delta_base_version().is_some()
+    }
+
+    /**
+        Gets the version of the tool that this delta artifact must be
+        applied to, or `None` if this artifact is not a delta.
+    */
+    #[must_use]
+    pub fn delta_base_version(&self) -> Option<Version> {
+        let name = self.name.as_deref()?;
+        let name = name.strip_suffix(DELTA_SUFFIX)?;
+        let (_, version) = name.rsplit_once(DELTA_INFIX)?;
+        version.trim_start_matches('v').parse().ok()
+    }
+
+    /**
+        Finds a delta artifact that can be used instead of downloading the
+        given full artifact, if one exists for any of the given base versions.
+
+        If deltas exist for several of the base versions, the delta
+        for the most recent base version will be preferred.
+    */
+    #[must_use]
+    pub fn find_delta(
+        artifacts: impl AsRef<[Self]>,
+        full: &Self,
+        base_versions: &[Version],
+    ) -> Option<Self> {
+        let full_name = full.name.as_deref()?;
+        artifacts
+            .as_ref()
+            .iter()
+            .filter(|artifact| artifact.provider == full.provider)
+            .filter_map(|artifact| {
+                let version = artifact.delta_base_version()?;
+                let name = artifact.name.as_deref()?;
+                let target = name.strip_suffix(DELTA_SUFFIX)?.rsplit_once(DELTA_INFIX)?.0;
+                let is_match = target == full_name && base_versions.contains(&version);
+                is_match.then_some((version, artifact))
+            })
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, artifact)| artifact.clone())
+    }
+
+    /**
+        Applies the contents of this delta artifact to the given base binary
+        contents, reconstructing the binary for the tool of this artifact.
+
+        # Errors
+
+        - If this artifact is not a delta.
+        - If the delta could not be applied to the given base contents.
+        - If the reconstructed binary is not for the current OS.
+    */
+    #[cfg(feature = "zstd")]
+    #[instrument(skip(self, contents, base_contents), level = "debug")]
+    pub async fn apply_delta_contents(
+        &self,
+        contents: Vec<u8>,
+        base_contents: Vec<u8>,
+    ) -> RokitResult<Vec<u8>> {
+        if !self.is_delta() {
+            return Err(ExtractError::UnknownFormat.into());
+        }
+
+        let file_bytes = decompress_zstd_patch(&contents, &base_contents).await?;
+
         let os_current = OS::current_system();
         let os_file = OS::detect_from_executable(&file_bytes);
         if os_file.is_some_and(|os| os != os_current) {
@@ -125,6 +515,18 @@ impl Artifact {
         Ok(file_bytes)
     }
 
+    /**
+        Computes the SHA-256 digest of the given artifact contents,
+        formatted as a lowercase hexadecimal string.
+
+        The given contents should be the raw bytes of the artifact,
+        as downloaded from the download URL, and not extracted.
+    */
+    #[must_use]
+    pub fn compute_sha256(contents: impl AsRef<[u8]>) -> String {
+        sha256_hex(contents)
+    }
+
     /**
         Sorts the given artifacts by their compatibility with the current system.
 
@@ -135,7 +537,20 @@ impl Artifact {
         - [`Descriptor::sort_by_preferred_compat`]
     */
     pub fn sort_by_system_compatibility(artifacts: impl AsRef<[Self]>) -> Vec<Self> {
-        Self::sort_by_system_compatibility_inner(artifacts, false)
+        Self::sort_by_compatibility_inner(artifacts, Descriptor::current_system(), false)
+    }
+
+    /**
+        Sorts the given artifacts by their compatibility with the given system,
+        which does not need to be the current system.
+
+        See [`Artifact::sort_by_system_compatibility`] for more information.
+    */
+    pub fn sort_by_compatibility_with(
+        artifacts: impl AsRef<[Self]>,
+        system: &Descriptor,
+    ) -> Vec<Self> {
+        Self::sort_by_compatibility_inner(artifacts, *system, false)
     }
 
     /**
@@ -147,20 +562,108 @@ impl Artifact {
         system, the contents of the artifact should be checked before use.
     */
     pub fn find_partially_compatible_fallback(artifacts: impl AsRef<[Self]>) -> Option<Self> {
-        Self::sort_by_system_compatibility_inner(artifacts, true)
+        Self::sort_by_compatibility_inner(artifacts, Descriptor::current_system(), true)
+            .into_iter()
+            .next()
+    }
+
+    /**
+        Removes all artifacts with names matching any of the given wildcard
+        patterns, such as `*.deb`, so that they are never chosen when
+        finding the most compatible artifact. Matching ignores case.
+
+        Artifacts without a name are never removed.
+    */
+    #[must_use]
+    pub fn filter_excluded(artifacts: impl AsRef<[Self]>, patterns: &[String]) -> Vec<Self> {
+        artifacts
+            .as_ref()
+            .iter()
+            .filter(|artifact| {
+                let name = artifact.name.as_deref().unwrap_or_default();
+                name.is_empty()
+                    || !patterns
+                        .iter()
+                        .any(|pattern| matches_wildcard(pattern, name))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /**
+        Finds the most compatible artifact for the current system, if any.
+
+        This prefers artifacts found using [`Artifact::sort_by_system_compatibility`],
+        and falls back to [`Artifact::find_partially_compatible_fallback`] otherwise.
+
+        Any artifact that is expected to run using emulation, or that was
+        only found as a fallback, will be added to the given warnings.
+    */
+    #[must_use]
+    pub fn find_most_compatible(
+        artifacts: impl AsRef<[Self]>,
+        warnings: &RokitWarnings,
+    ) -> Option<Self> {
+        Self::find_most_compatible_with(artifacts, &Descriptor::current_system(), warnings)
+    }
+
+    /**
+        Finds the most compatible artifact for the given system, which
+        does not need to be the current system, if any.
+
+        See [`Artifact::find_most_compatible`] for more information.
+    */
+    #[must_use]
+    pub fn find_most_compatible_with(
+        artifacts: impl AsRef<[Self]>,
+        system: &Descriptor,
+        warnings: &RokitWarnings,
+    ) -> Option<Self> {
+        let artifacts = artifacts.as_ref();
+
+        if let Some(artifact) = Self::sort_by_compatibility_with(artifacts, system)
             .into_iter()
             .next()
+        {
+            // NOTE: Systems without a known architecture can't tell if an
+            // artifact will be emulated, we don't fall back to the host
+            // architecture here since the system may not be the host
+            let artifact_arch = artifact
+                .name
+                .as_deref()
+                .and_then(Descriptor::detect)
+                .and_then(|desc| desc.arch())
+                .filter(|arch| Some(*arch) != system.arch());
+            if let (Some(current_arch), Some(artifact_arch)) = (system.arch(), artifact_arch) {
+                warnings.push(RokitWarning::EmulatedArtifact {
+                    spec: artifact.tool_spec.clone(),
+                    artifact_name: artifact.name.clone().unwrap_or_default(),
+                    current_arch,
+                    artifact_arch,
+                });
+            }
+            return Some(artifact);
+        }
+
+        let artifact = Self::sort_by_compatibility_inner(artifacts, *system, true)
+            .into_iter()
+            .next()?;
+        warnings.push(RokitWarning::PartiallyCompatibleArtifact {
+            spec: artifact.tool_spec.clone(),
+            artifact_name: artifact.name.clone().unwrap_or_default(),
+        });
+        Some(artifact)
     }
 
-    fn sort_by_system_compatibility_inner(
+    fn sort_by_compatibility_inner(
         artifacts: impl AsRef<[Self]>,
+        current_desc: Descriptor,
         allow_partial_compatibility: bool,
     ) -> Vec<Self> {
-        let current_desc = Descriptor::current_system();
-
         let mut compatible_artifacts = artifacts
             .as_ref()
             .iter()
+            .filter(|artifact| !artifact.is_delta() && !artifact.is_digest_file())
             .filter_map(|artifact| {
                 let name = artifact.name.as_deref()?;
                 if let Some(asset_desc) = Descriptor::detect(name) {
@@ -178,8 +681,8 @@ impl Artifact {
             .collect::<Vec<_>>();
 
         compatible_artifacts.sort_by(|(desc_a, artifact_a), (desc_b, artifact_b)| {
-            current_desc
-                .sort_by_preferred_compat(desc_a, desc_b)
+            sort_installers_last(artifact_a, artifact_b)
+                .then_with(|| current_desc.sort_by_preferred_compat(desc_a, desc_b))
                 .then_with(|| sort_preferred_artifact(artifact_a, artifact_b))
                 .then_with(|| sort_preferred_formats(artifact_a, artifact_b))
         });
@@ -190,3 +693,128 @@ impl Artifact {
             .collect()
     }
 }
+
+#[cfg(test)]
+impl Artifact {
+    /**
+        Creates an artifact for `author/tool@2.0.0` that has only the given name, for tests.
+    */
+    pub(crate) fn for_test(name: &str) -> Self {
+        Self {
+            provider: ArtifactProvider::GitHub,
+            format: None,
+            id: None,
+            url: None,
+            name: Some(name.to_string()),
+            size: None,
+            digest: None,
+            tool_spec: "author/tool@2.0.0".parse().unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::descriptor::{Arch, OS};
+
+    use super::*;
+
+    #[test]
+    fn find_plugin_prefers_binary() {
+        let artifacts = vec![
+            Artifact::for_test("tool-2.0.0-windows-x86_64"),
+            Artifact::for_test("Plugin.rbxmx"),
+            Artifact::for_test("Plugin.RBXM"),
+        ];
+        assert_eq!(artifacts[1].plugin_extension(), Some("rbxmx"));
+        assert_eq!(artifacts[2].plugin_extension(), Some("rbxm"));
+        assert_eq!(artifacts[0].plugin_extension(), None);
+        assert_eq!(
+            Artifact::find_plugin(&artifacts),
+            Some(artifacts[2].clone())
+        );
+    }
+
+    #[test]
+    fn delta_base_version() {
+        let full = Artifact::for_test("tool-2.0.0-linux-x86_64");
+        let delta = Artifact::for_test("tool-2.0.0-linux-x86_64.patch-from-1.5.0.zst");
+        assert!(!full.is_delta());
+        assert!(delta.is_delta());
+        assert_eq!(delta.delta_base_version(), Some(Version::new(1, 5, 0)));
+    }
+
+    #[test]
+    fn find_delta_prefers_latest_base() {
+        let full = Artifact::for_test("tool-2.0.0-linux-x86_64");
+        let artifacts = vec![
+            full.clone(),
+            Artifact::for_test("tool-2.0.0-linux-x86_64.patch-from-1.0.0.zst"),
+            Artifact::for_test("tool-2.0.0-linux-x86_64.patch-from-1.5.0.zst"),
+            Artifact::for_test("tool-2.0.0-windows-x86_64.patch-from-1.9.0.zst"),
+        ];
+        let found = Artifact::find_delta(
+            &artifacts,
+            &full,
+            &[
+                Version::new(1, 0, 0),
+                Version::new(1, 5, 0),
+                Version::new(1, 9, 0),
+            ],
+        );
+        assert_eq!(found, Some(artifacts[2].clone()));
+    }
+
+    #[test]
+    fn find_delta_requires_installed_base() {
+        let full = Artifact::for_test("tool-2.0.0-linux-x86_64");
+        let artifacts = vec![
+            full.clone(),
+            Artifact::for_test("tool-2.0.0-linux-x86_64.patch-from-1.0.0.zst"),
+        ];
+        let found = Artifact::find_delta(&artifacts, &full, &[Version::new(1, 1, 0)]);
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn deltas_are_never_most_compatible() {
+        let artifacts = vec![Artifact::for_test(&format!(
+            "tool-2.0.0-{}-{}.patch-from-1.0.0.zst",
+            OS::current_system().as_str(),
+            Arch::current_system().as_str(),
+        ))];
+        assert!(Artifact::sort_by_system_compatibility(&artifacts).is_empty());
+    }
+
+    #[test]
+    fn installers_are_least_compatible() {
+        let system = Descriptor::detect("linux-x86_64").unwrap();
+        let installer = Artifact::for_test("tool-2.0.0-linux-x86_64.MSI");
+        let archive = Artifact::for_test("tool-2.0.0-linux-x86_64.zip");
+        assert!(installer.is_installer());
+        assert!(!archive.is_installer());
+
+        let sorted =
+            Artifact::sort_by_compatibility_with([installer.clone(), archive.clone()], &system);
+        assert_eq!(sorted, vec![archive, installer.clone()]);
+
+        let sorted = Artifact::sort_by_compatibility_with([installer.clone()], &system);
+        assert_eq!(sorted, vec![installer]);
+    }
+
+    #[test]
+    fn release_notes_skip_empty_changelogs() {
+        let mut release = Release {
+            version: Version::new(1, 2, 3),
+            changelog: Some(String::from("  \n\n  ")),
+            artifacts: Vec::new(),
+        };
+        assert_eq!(ReleaseNotes::from_release(&release), None);
+
+        release.changelog = Some(String::from("\n## Fixes\n\n- Fixed a crash\n"));
+        let notes = ReleaseNotes::from_release(&release).unwrap();
+        assert_eq!(notes.version, Version::new(1, 2, 3));
+        assert_eq!(notes.changelog, "## Fixes\n\n- Fixed a crash");
+        assert_eq!(notes.summary(), "Fixes");
+    }
+}
@@ -4,11 +4,44 @@ use std::{fmt, str::FromStr};
     An artifact provider supported by Rokit.
 
     The default provider is [`ArtifactProvider::GitHub`].
+
+    Tools downloaded from a direct URL use [`ArtifactProvider::Url`],
+    which has no releases - see [`UrlSource`](crate::sources::UrlSource).
+
+    Tools published in a custom registry, configured in the policy manifest,
+    use [`ArtifactProvider::Registry`] - see `RegistryProvider` for more information.
+
+    Tools stored in an S3-compatible bucket, configured in the policy manifest,
+    use [`ArtifactProvider::S3`] - see `S3Provider` for more information.
+
+    Tools published as crates on crates.io use [`ArtifactProvider::Crates`],
+    with prebuilt binaries found using the conventions of cargo-binstall -
+    see `CratesProvider` for more information.
+
+    Tools published as packages on the npm registry use [`ArtifactProvider::Npm`],
+    with binaries found in the platform-specific optional dependencies of
+    the package - see `NpmProvider` for more information.
+
+    Tools from plain git repositories, on forges without a releases API, use
+    [`ArtifactProvider::Git`] together with the host of the repository, with
+    releases found from its tags - see `GitProvider` for more information.
+
+    Tools from third-party providers, implemented outside of Rokit, use
+    [`ArtifactProvider::Custom`] together with the host that the provider
+    was registered for - see `CustomProvider` for more information.
 */
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ArtifactProvider {
     #[default]
     GitHub,
+    GitLab,
+    Url,
+    Registry,
+    S3,
+    Custom,
+    Crates,
+    Npm,
+    Git,
 }
 
 impl ArtifactProvider {
@@ -16,6 +49,14 @@ impl ArtifactProvider {
     pub fn as_str(self) -> &'static str {
         match self {
             Self::GitHub => "github",
+            Self::GitLab => "gitlab",
+            Self::Url => "url",
+            Self::Registry => "registry",
+            Self::S3 => "s3",
+            Self::Custom => "custom",
+            Self::Crates => "crates",
+            Self::Npm => "npm",
+            Self::Git => "git",
         }
     }
 
@@ -23,6 +64,14 @@ impl ArtifactProvider {
     pub fn display_name(self) -> &'static str {
         match self {
             Self::GitHub => "GitHub",
+            Self::GitLab => "GitLab",
+            Self::Url => "URL",
+            Self::Registry => "Registry",
+            Self::S3 => "S3",
+            Self::Custom => "Custom",
+            Self::Crates => "crates.io",
+            Self::Npm => "npm",
+            Self::Git => "Git",
         }
     }
 }
@@ -33,6 +82,14 @@ impl FromStr for ArtifactProvider {
         let l = s.trim().to_lowercase();
         match l.as_str() {
             "github" => Ok(Self::GitHub),
+            "gitlab" => Ok(Self::GitLab),
+            "url" => Ok(Self::Url),
+            "registry" => Ok(Self::Registry),
+            "s3" => Ok(Self::S3),
+            "custom" => Ok(Self::Custom),
+            "crates" => Ok(Self::Crates),
+            "npm" => Ok(Self::Npm),
+            "git" => Ok(Self::Git),
             _ => Err(format!("unknown artifact provider '{l}'")),
         }
     }
@@ -75,6 +75,15 @@ fn word_is_not_arch_or_os_or_version_or_numeric(word: impl AsRef<str>) -> bool {
         && !word.chars().all(char::is_numeric)
 }
 
+/**
+    Helper function to sort installers, such as `.msi` or `.pkg` files,
+    after all other artifacts - installers can not be extracted, so they
+    must only ever be chosen if there is nothing else to choose from.
+*/
+pub(super) fn sort_installers_last(artifact_a: &Artifact, artifact_b: &Artifact) -> Ordering {
+    artifact_a.is_installer().cmp(&artifact_b.is_installer())
+}
+
 pub(super) fn sort_preferred_formats(artifact_a: &Artifact, artifact_b: &Artifact) -> Ordering {
     match (artifact_a.format, artifact_b.format) {
         (None, None) => std::cmp::Ordering::Equal,
@@ -194,6 +203,8 @@ mod tests {
                 id: Some("id".to_string()),
                 url: Some("https://github.com".parse().unwrap()),
                 name: Some(name.to_string()),
+                size: None,
+                digest: None,
                 tool_spec: new_id("author", name).into_spec(Version::parse("1.0.0").unwrap()),
             })
             .collect::<Vec<_>>();
@@ -3,7 +3,7 @@ use std::path::Path;
 const ALLOWED_EXTENSION_NAMES: [&str; 4] = ["zip", "tar", "gz", "tgz"];
 const ALLOWED_EXTENSION_COUNT: usize = 2;
 
-pub(super) fn split_filename_and_extensions(name: &str) -> (&str, Vec<&str>) {
+pub(crate) fn split_filename_and_extensions(name: &str) -> (&str, Vec<&str>) {
     let mut path = Path::new(name);
     let mut exts = Vec::new();
 
@@ -0,0 +1,46 @@
+use std::{num::NonZeroUsize, sync::Arc, thread::available_parallelism};
+
+use once_cell::sync::Lazy;
+use tokio::sync::Semaphore;
+
+use crate::{executor::spawn_blocking, result::RokitResult};
+
+/*
+    Limits how many CPU-bound tasks, such as decompression and
+    extraction, may run at once - one per available core.
+
+    The blocking pool of the executor is much larger than this, and is also
+    used for plain filesystem operations, which should never have to wait
+    behind many large archives being decompressed at the same time.
+*/
+static PERMITS: Lazy<Arc<Semaphore>> = Lazy::new(|| {
+    let cores = available_parallelism().map_or(1, NonZeroUsize::get);
+    Arc::new(Semaphore::new(cores))
+});
+
+/**
+    Runs the given CPU-bound function using the current executor,
+    waiting until one of the available cores is free before starting it.
+
+    Multiple calls may be awaited concurrently, such as when installing
+    many tools at once, and will then use all of the available cores.
+*/
+pub(super) async fn spawn_cpu_bound<F, T>(f: F) -> RokitResult<T>
+where
+    F: FnOnce() -> RokitResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let permit = Arc::clone(&PERMITS)
+        .acquire_owned()
+        .await
+        .expect("cpu-bound task semaphore is never closed");
+
+    // NOTE: The permit is moved into the task, so that it is held until
+    // the task finishes, even if the returned future is dropped early
+    spawn_blocking(move || {
+        let result = f();
+        drop(permit);
+        result
+    })
+    .await?
+}
@@ -1,56 +1,138 @@
 use std::time::Duration;
 
-use reqwest::{
-    header::{HeaderMap, USER_AGENT},
-    Client, Error,
-};
+use futures::{future::BoxFuture, FutureExt};
+use reqwest::{Certificate, Client, NoProxy, Proxy};
 
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use reqwest_tracing::TracingMiddleware;
 
+use super::{
+    http::{
+        HttpClient, HttpClientOptions, HttpError, HttpMethod, HttpPartialBodyError, HttpRequest,
+        HttpResponse,
+    },
+    RequestPolicy,
+};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_mins(1);
+
 /*
     Adds middleware for:
 
-    - Retrying failed requests with exponential backoff
+    - Retrying failed requests with exponential backoff, using the request policy
     - Tracing of HTTP requests
 */
-fn add_client_middleware(client: Client) -> ClientWithMiddleware {
+fn add_client_middleware(client: Client, policy: &RequestPolicy) -> ClientWithMiddleware {
+    // NOTE: Retrying right away is never useful, so always wait for a little while
+    let min_backoff = policy.retry_backoff.max(Duration::from_millis(1));
+    let max_backoff = min_backoff.max(MAX_RETRY_BACKOFF);
     ClientBuilder::new(client)
         .with(RetryTransientMiddleware::new_with_policy(
-            ExponentialBackoff::builder().build_with_max_retries(3),
+            ExponentialBackoff::builder()
+                .retry_bounds(min_backoff, max_backoff)
+                .build_with_max_retries(policy.retries),
         ))
         .with(TracingMiddleware::default())
         .build()
 }
 
 /**
-    Creates a client with:
+    The default HTTP client, using `reqwest`, with:
 
     - HTTPS only
     - Timeouts for connection and response
     - All common compression algorithms enabled
-    - User agent set to `<crate_name>/<crate_version> (<repository_url>)`
+    - Retries for transient errors, and tracing of requests
+    - Proxies from the environment, and optional extra root certificates
 */
-pub fn create_client(mut default_headers: HeaderMap) -> Result<ClientWithMiddleware, Error> {
-    let user_agent = format!(
-        "{}/{} ({})",
-        env!("CARGO_PKG_NAME"),
-        env!("CARGO_PKG_VERSION"),
-        env!("CARGO_PKG_REPOSITORY"),
-    );
-
-    default_headers.insert(USER_AGENT, user_agent.parse().unwrap());
-
-    let client = Client::builder()
-        .default_headers(default_headers)
-        .https_only(true)
-        .connect_timeout(Duration::from_secs(15))
-        .timeout(Duration::from_secs(60))
-        .gzip(true)
-        .brotli(true)
-        .deflate(true)
-        .build()?;
-
-    Ok(add_client_middleware(client))
+#[derive(Debug, Clone)]
+pub struct ReqwestClient {
+    client: ClientWithMiddleware,
+}
+
+impl ReqwestClient {
+    pub fn new_with_options(options: &HttpClientOptions) -> Result<Self, HttpError> {
+        // NOTE: Proxies from HTTP_PROXY, HTTPS_PROXY and NO_PROXY are
+        // picked up by reqwest itself, unless an explicit proxy is set
+        let mut builder = Client::builder()
+            .https_only(true)
+            .connect_timeout(CONNECT_TIMEOUT.min(options.request_policy.timeout))
+            .timeout(options.request_policy.timeout)
+            .gzip(true)
+            .brotli(true)
+            .deflate(true);
+
+        if let Some(proxy) = &options.proxy {
+            builder = builder.proxy(Proxy::all(proxy.as_str())?.no_proxy(NoProxy::from_env()));
+        }
+
+        if let Some(path) = &options.ca_bundle {
+            let pem = std::fs::read(path)
+                .map_err(|e| format!("failed to read CA bundle at '{}' - {e}", path.display()))?;
+            for certificate in Certificate::from_pem_bundle(&pem)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        let client = builder.build()?;
+
+        Ok(Self {
+            client: add_client_middleware(client, &options.request_policy),
+        })
+    }
+
+    async fn send_inner(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let mut builder = match request.method {
+            HttpMethod::Get => self.client.get(&request.url),
+            HttpMethod::Head => self.client.head(&request.url),
+            HttpMethod::Post => self.client.post(&request.url),
+        };
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if !request.body.is_empty() {
+            builder = builder.body(request.body);
+        }
+
+        let mut response = builder.send().await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.to_str().ok()?;
+                Some((name.as_str().to_string(), value.to_string()))
+            })
+            .collect();
+        let mut received = HttpResponse {
+            url: request.url,
+            status,
+            headers,
+            body: Vec::new(),
+        };
+
+        // NOTE: The body is read in chunks, so that anything that was
+        // received before a connection failure can be used to resume it
+        loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => received.body.extend_from_slice(&chunk),
+                Ok(None) => return Ok(received),
+                Err(e) if received.body.is_empty() => return Err(e.into()),
+                Err(e) => {
+                    return Err(Box::new(HttpPartialBodyError {
+                        response: received,
+                        source: e.into(),
+                    }))
+                }
+            }
+        }
+    }
+}
+
+impl HttpClient for ReqwestClient {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>> {
+        self.send_inner(request).boxed()
+    }
 }
@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/**
+    Targets that prebuilt binaries are looked for, in the same
+    format as the target triples used by Rust and cargo-binstall.
+
+    Releases of crates contain an artifact for each of these targets,
+    and the one most compatible with the current system is picked,
+    the same as for artifacts of tools from any other provider.
+*/
+pub const TARGETS: [&str; 9] = [
+    "x86_64-unknown-linux-gnu",
+    "x86_64-unknown-linux-musl",
+    "aarch64-unknown-linux-gnu",
+    "aarch64-unknown-linux-musl",
+    "x86_64-apple-darwin",
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "aarch64-pc-windows-msvc",
+    "i686-pc-windows-msvc",
+];
+
+/*
+    The package URLs that cargo-binstall tries for crates
+    without any `pkg-url` in their metadata, in order.
+*/
+const DEFAULT_PKG_URLS: [&str; 8] = [
+    "{ repo }/releases/download/{ version }/{ name }-{ target }-v{ version }{ archive-suffix }",
+    "{ repo }/releases/download/v{ version }/{ name }-{ target }-v{ version }{ archive-suffix }",
+    "{ repo }/releases/download/{ version }/{ name }-{ target }-{ version }{ archive-suffix }",
+    "{ repo }/releases/download/v{ version }/{ name }-{ target }-{ version }{ archive-suffix }",
+    "{ repo }/releases/download/{ version }/{ name }-{ version }-{ target }{ archive-suffix }",
+    "{ repo }/releases/download/v{ version }/{ name }-{ version }-{ target }{ archive-suffix }",
+    "{ repo }/releases/download/{ version }/{ name }-{ target }{ archive-suffix }",
+    "{ repo }/releases/download/v{ version }/{ name }-{ target }{ archive-suffix }",
+];
+
+/**
+    The `[package.metadata.binstall]` table in the manifest of a crate,
+    which describes where prebuilt binaries of the crate can be found.
+
+    Only the package URL and format are used by Rokit - binaries are
+    always found in archives by name, same as for any other tool.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BinstallMetadata {
+    pub pkg_url: Option<String>,
+    pub pkg_fmt: Option<String>,
+    #[serde(default)]
+    pub overrides: HashMap<String, BinstallOverride>,
+}
+
+/**
+    Overrides of [`BinstallMetadata`] for a single target.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BinstallOverride {
+    pub pkg_url: Option<String>,
+    pub pkg_fmt: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Option<ManifestPackage>,
+}
+
+#[derive(Deserialize)]
+struct ManifestPackage {
+    metadata: Option<ManifestMetadata>,
+}
+
+#[derive(Deserialize)]
+struct ManifestMetadata {
+    binstall: Option<BinstallMetadata>,
+}
+
+impl BinstallMetadata {
+    /**
+        Reads the binstall metadata from the contents of a `Cargo.toml` manifest.
+
+        Returns `None` if the manifest is invalid, or has no binstall metadata.
+    */
+    #[must_use]
+    pub fn from_manifest(manifest: &str) -> Option<Self> {
+        let manifest = toml::from_str::<Manifest>(manifest).ok()?;
+        manifest.package?.metadata?.binstall
+    }
+
+    /**
+        Gets the package URL and format to use for the given target,
+        taking any overrides for the target into account.
+    */
+    #[must_use]
+    pub fn for_target(&self, target: &str) -> (Option<&str>, Option<&str>) {
+        let overrides = self.overrides.get(target);
+        let pkg_url = overrides
+            .and_then(|o| o.pkg_url.as_deref())
+            .or(self.pkg_url.as_deref());
+        let pkg_fmt = overrides
+            .and_then(|o| o.pkg_fmt.as_deref())
+            .or(self.pkg_fmt.as_deref());
+        (pkg_url, pkg_fmt)
+    }
+}
+
+/*
+    A package format supported by cargo-binstall, which Rokit can also install.
+
+    The formats `tbz2`, `txz`, and `tzstd` are not supported.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageFormat {
+    Tgz,
+    Zip,
+    Tar,
+    Bin,
+}
+
+impl PackageFormat {
+    const ALL: [Self; 4] = [Self::Tgz, Self::Zip, Self::Tar, Self::Bin];
+
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "tgz" | "tar.gz" => Some(Self::Tgz),
+            "zip" => Some(Self::Zip),
+            "tar" => Some(Self::Tar),
+            "bin" => Some(Self::Bin),
+            _ => None,
+        }
+    }
+
+    fn archive_suffixes(self, is_windows: bool) -> &'static [&'static str] {
+        match self {
+            Self::Tgz => &[".tgz", ".tar.gz"],
+            Self::Zip => &[".zip"],
+            Self::Tar => &[".tar"],
+            Self::Bin if is_windows => &[".exe", ""],
+            Self::Bin => &[""],
+        }
+    }
+}
+
+/**
+    Renders a cargo-binstall template, such as `{ repo }/{ name }-{ target }`,
+    replacing each variable with its value in the given list of variables.
+
+    Variables without a value are kept as they are.
+*/
+#[must_use]
+pub fn render_template(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let var = &rest[start..=start + len];
+        let key = var[1..var.len() - 1].trim();
+        match vars.iter().find(|(k, _)| *k == key) {
+            Some((_, value)) => rendered.push_str(value),
+            None => rendered.push_str(var),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/**
+    Gets all URLs where a prebuilt binary of the given crate, version,
+    and target may be found, in the order that they should be tried.
+
+    Crates without a package URL in their metadata use the same default
+    URLs as cargo-binstall, which point at the releases of their repository.
+
+    # Errors
+
+    - If the metadata has a package format that is not supported,
+      in which case the unsupported format is returned.
+*/
+pub fn candidate_urls(
+    metadata: &BinstallMetadata,
+    name: &str,
+    version: &str,
+    repo: Option<&str>,
+    target: &str,
+) -> Result<Vec<String>, String> {
+    let (pkg_url, pkg_fmt) = metadata.for_target(target);
+    let formats = match pkg_fmt {
+        Some(fmt) => vec![PackageFormat::parse(fmt).ok_or_else(|| fmt.to_string())?],
+        None => PackageFormat::ALL.to_vec(),
+    };
+    let templates = match pkg_url {
+        Some(url) => vec![url],
+        None => DEFAULT_PKG_URLS.to_vec(),
+    };
+
+    let mut parts = target.split('-');
+    let target_arch = parts.next().unwrap_or_default();
+    let target_vendor = parts.next().unwrap_or_default();
+    let target_libc = parts.nth(1).unwrap_or_default();
+    let is_windows = target.contains("windows");
+    let (target_family, binary_ext) = if is_windows {
+        ("windows", ".exe")
+    } else {
+        ("unix", "")
+    };
+
+    let mut urls = Vec::new();
+    for template in templates {
+        for format in &formats {
+            for &suffix in format.archive_suffixes(is_windows) {
+                let archive_format = match format {
+                    PackageFormat::Bin => "bin",
+                    _ => suffix.trim_start_matches('.'),
+                };
+                let mut vars = vec![
+                    ("name", name),
+                    ("version", version),
+                    ("target", target),
+                    ("archive-format", archive_format),
+                    ("format", archive_format),
+                    ("archive-suffix", suffix),
+                    ("binary-ext", binary_ext),
+                    ("target-family", target_family),
+                    ("target-arch", target_arch),
+                    ("target-vendor", target_vendor),
+                    ("target-libc", target_libc),
+                ];
+                if let Some(repo) = repo {
+                    vars.push(("repo", repo));
+                }
+                // NOTE: Any variable left in the URL is one we do not know,
+                // most commonly a missing repository, so the URL is unusable
+                let url = render_template(template, &vars);
+                if !url.contains(['{', '}']) && !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+        }
+    }
+    Ok(urls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_template_vars() {
+        let vars = [("name", "stylua"), ("version", "0.20.0")];
+        assert_eq!(
+            render_template("{ name }-v{version}{ unknown }", &vars),
+            "stylua-v0.20.0{ unknown }"
+        );
+        assert_eq!(render_template("no vars", &vars), "no vars");
+        assert_eq!(render_template("unclosed { name", &vars), "unclosed { name");
+    }
+
+    #[test]
+    fn metadata_from_manifest() {
+        let metadata = BinstallMetadata::from_manifest(
+            r#"
+            [package]
+            name = "tool"
+
+            [package.metadata.binstall]
+            pkg-url = "{ repo }/releases/download/v{ version }/tool-{ target }.zip"
+            pkg-fmt = "zip"
+
+            [package.metadata.binstall.overrides.x86_64-pc-windows-msvc]
+            pkg-fmt = "bin"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            metadata.for_target("x86_64-unknown-linux-gnu"),
+            (
+                Some("{ repo }/releases/download/v{ version }/tool-{ target }.zip"),
+                Some("zip")
+            )
+        );
+        assert_eq!(metadata.for_target("x86_64-pc-windows-msvc").1, Some("bin"));
+        assert_eq!(
+            BinstallMetadata::from_manifest("[package]\nname = \"tool\""),
+            None
+        );
+    }
+
+    #[test]
+    fn candidate_urls_explicit() {
+        let metadata = BinstallMetadata {
+            pkg_url: Some(String::from(
+                "{ repo }/releases/download/v{ version }/{ name }-{ target }{ archive-suffix }",
+            )),
+            pkg_fmt: Some(String::from("tgz")),
+            ..BinstallMetadata::default()
+        };
+        assert_eq!(
+            candidate_urls(
+                &metadata,
+                "tool",
+                "1.0.0",
+                Some("https://github.com/author/tool"),
+                "x86_64-unknown-linux-gnu",
+            )
+            .unwrap(),
+            vec![
+                "https://github.com/author/tool/releases/download/v1.0.0/tool-x86_64-unknown-linux-gnu.tgz",
+                "https://github.com/author/tool/releases/download/v1.0.0/tool-x86_64-unknown-linux-gnu.tar.gz",
+            ]
+        );
+    }
+
+    #[test]
+    fn candidate_urls_default() {
+        let metadata = BinstallMetadata::default();
+        let urls = candidate_urls(
+            &metadata,
+            "tool",
+            "1.0.0",
+            Some("https://github.com/author/tool"),
+            "x86_64-pc-windows-msvc",
+        )
+        .unwrap();
+        assert_eq!(
+            urls.first().map(String::as_str),
+            Some("https://github.com/author/tool/releases/download/1.0.0/tool-x86_64-pc-windows-msvc-v1.0.0.tgz")
+        );
+        assert!(urls.contains(&String::from(
+            "https://github.com/author/tool/releases/download/v1.0.0/tool-x86_64-pc-windows-msvc.exe"
+        )));
+        // Default URLs all need a repository
+        assert!(
+            candidate_urls(&metadata, "tool", "1.0.0", None, "x86_64-apple-darwin")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn candidate_urls_unsupported_format() {
+        let metadata = BinstallMetadata {
+            pkg_fmt: Some(String::from("txz")),
+            ..BinstallMetadata::default()
+        };
+        assert_eq!(
+            candidate_urls(&metadata, "tool", "1.0.0", None, "x86_64-apple-darwin"),
+            Err(String::from("txz"))
+        );
+    }
+}
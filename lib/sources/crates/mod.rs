@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use futures::future::join_all;
+use tracing::{debug, instrument};
+
+use crate::tool::{ReleaseChannel, ToolId, ToolSpec};
+
+use super::{
+    http::{error_status, user_agent, HttpClient, HttpRequest, HttpResponse},
+    Artifact, ArtifactProvider, Release,
+};
+
+#[cfg(feature = "extract")]
+use super::extraction::extract_crate_manifest;
+
+#[cfg(feature = "reqwest-client")]
+use super::http::default_http_client;
+
+const API_URL: &str = "https://crates.io/api/v1";
+#[cfg(feature = "extract")]
+const DOWNLOAD_URL: &str = "https://static.crates.io/crates";
+
+pub mod binstall;
+pub mod models;
+mod result;
+
+pub use self::binstall::{BinstallMetadata, TARGETS};
+pub use self::models::{CrateInfo, CrateResponse, CrateVersion};
+pub use self::result::{CratesError, CratesResult};
+
+use self::binstall::candidate_urls;
+
+/**
+    A provider for tools published as crates on crates.io, such as `crates:stylua`.
+
+    Versions of a tool are the versions of its crate on crates.io, but crates
+    are never built from source - instead, prebuilt binaries are found using
+    the same conventions as cargo-binstall, from the package URL in the
+    `[package.metadata.binstall]` table of the crate manifest, or from the
+    default URLs of cargo-binstall, which point at the releases of its repository.
+
+    Finding a prebuilt binary means probing many URLs, so every release
+    contains one artifact for each of the [`TARGETS`], and the binary
+    for a target is only looked for once its artifact is downloaded.
+*/
+#[derive(Debug, Clone)]
+pub struct CratesProvider {
+    client: Arc<dyn HttpClient>,
+}
+
+impl CratesProvider {
+    /**
+        Creates a new crates.io provider, using the default HTTP client.
+
+        # Errors
+
+        - If the HTTP client could not be created.
+    */
+    #[cfg(feature = "reqwest-client")]
+    pub fn new() -> CratesResult<Self> {
+        Ok(Self::new_with_client(default_http_client()?))
+    }
+
+    /**
+        Creates a new crates.io provider, using the given HTTP client.
+    */
+    #[must_use]
+    pub fn new_with_client(client: Arc<dyn HttpClient>) -> Self {
+        Self { client }
+    }
+
+    /**
+        Uses the given HTTP client for all requests made by this provider.
+    */
+    #[must_use]
+    pub(crate) fn with_client(mut self, client: Arc<dyn HttpClient>) -> Self {
+        self.client = client;
+        self
+    }
+
+    async fn get(&self, request: HttpRequest) -> CratesResult<HttpResponse> {
+        // NOTE: crates.io rejects any requests without a user agent
+        let request = request.header("user-agent", user_agent());
+        Ok(self.client.send(request).await?.error_for_status()?)
+    }
+
+    /**
+        Fetches information about the crate of the given tool, and all of its versions.
+
+        # Errors
+
+        - If the crate does not exist.
+        - If the request to the crates.io API failed.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn get_crate(&self, tool_id: &ToolId) -> CratesResult<CrateResponse> {
+        let url = format!("{API_URL}/crates/{}", tool_id.name());
+        let request = HttpRequest::get(url).header("accept", "application/json");
+        match self.get(request).await {
+            Ok(response) => Ok(serde_json::from_slice(&response.body)?),
+            Err(e) if status(&e) == Some(404) => {
+                Err(CratesError::CrateNotFound(tool_id.name().to_string()))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /*
+        Reads the binstall metadata of a specific version of a crate, from the
+        manifest in its published package. This is best-effort, and crates
+        without metadata, or with an unreadable package, use the defaults.
+    */
+    #[cfg_attr(
+        not(feature = "extract"),
+        allow(clippy::unused_async, unused_variables)
+    )]
+    async fn get_binstall_metadata(&self, name: &str, version: &str) -> BinstallMetadata {
+        #[cfg(feature = "extract")]
+        {
+            let url = format!("{DOWNLOAD_URL}/{name}/{name}-{version}.crate");
+            let manifest = match self.get(HttpRequest::get(url)).await {
+                Ok(response) => extract_crate_manifest(response.body).await,
+                Err(e) => Err(e.into()),
+            };
+            match manifest {
+                Ok(Some(manifest)) => {
+                    BinstallMetadata::from_manifest(&String::from_utf8_lossy(&manifest))
+                        .unwrap_or_default()
+                }
+                Ok(None) => BinstallMetadata::default(),
+                Err(e) => {
+                    debug!(%name, %version, error = %e, "failed to read crate manifest");
+                    BinstallMetadata::default()
+                }
+            }
+        }
+        #[cfg(not(feature = "extract"))]
+        BinstallMetadata::default()
+    }
+
+    fn release_for(spec: &ToolSpec) -> Release {
+        Release {
+            version: spec.version().clone(),
+            changelog: None,
+            artifacts: TARGETS
+                .iter()
+                .map(|target| Artifact {
+                    provider: ArtifactProvider::Crates,
+                    format: None,
+                    id: Some((*target).to_string()),
+                    url: None,
+                    name: Some(format!("{}-{target}", spec.name())),
+                    size: None,
+                    digest: None,
+                    tool_spec: spec.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /**
+        Fetches the latest stable release for a given tool.
+
+        # Errors
+
+        - If the crate has no stable versions that have not been yanked.
+        - If the request to the crates.io API failed.
+    */
+    pub async fn get_latest_release(&self, tool_id: &ToolId) -> CratesResult<Release> {
+        self.get_recent_releases(tool_id)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| CratesError::LatestReleaseNotFound(tool_id.clone().into()))
+    }
+
+    /**
+        Fetches all stable releases for a given tool, newest first.
+
+        # Errors
+
+        - If the request to the crates.io API failed.
+    */
+    pub async fn get_recent_releases(&self, tool_id: &ToolId) -> CratesResult<Vec<Release>> {
+        self.get_recent_releases_in_channel(tool_id, &ReleaseChannel::Stable)
+            .await
+    }
+
+    /**
+        Fetches all releases in the given channel for a given tool, newest first.
+
+        Versions that have been yanked from crates.io are never included.
+
+        # Errors
+
+        - If the request to the crates.io API failed.
+    */
+    pub async fn get_recent_releases_in_channel(
+        &self,
+        tool_id: &ToolId,
+        channel: &ReleaseChannel,
+    ) -> CratesResult<Vec<Release>> {
+        let krate = self.get_crate(tool_id).await?;
+        Ok(krate
+            .available_versions()
+            .into_iter()
+            .filter(|version| channel.contains(version))
+            .map(|version| Self::release_for(&ToolSpec::from((tool_id.clone(), version))))
+            .collect())
+    }
+
+    /**
+        Fetches a specific release for a given tool.
+
+        # Errors
+
+        - If the version does not exist, or has been yanked.
+        - If the request to the crates.io API failed.
+    */
+    pub async fn get_specific_release(&self, tool_spec: &ToolSpec) -> CratesResult<Release> {
+        let krate = self.get_crate(tool_spec.id()).await?;
+        if krate.available_versions().contains(tool_spec.version()) {
+            Ok(Self::release_for(tool_spec))
+        } else {
+            Err(CratesError::ReleaseNotFound(tool_spec.clone().into()))
+        }
+    }
+
+    /**
+        Finds and downloads the prebuilt binary for the target of the given artifact.
+
+        # Errors
+
+        - If no prebuilt binary exists for the target.
+        - If the crate uses a package format that is not supported.
+        - If the binary could not be downloaded.
+    */
+    #[instrument(skip(self, artifact), level = "debug")]
+    pub async fn download_artifact_contents(&self, artifact: &Artifact) -> CratesResult<Vec<u8>> {
+        assert_eq!(
+            artifact.provider,
+            ArtifactProvider::Crates,
+            "artifact must be from crates.io"
+        );
+
+        let spec = &artifact.tool_spec;
+        let target = artifact
+            .id
+            .as_deref()
+            .expect("crate artifacts have targets");
+        let krate = self.get_crate(spec.id()).await?;
+        let name = krate.krate.name.as_str();
+        let version = spec.version().to_string();
+
+        let metadata = self.get_binstall_metadata(name, &version).await;
+        let urls = candidate_urls(&metadata, name, &version, krate.repository_url(), target)
+            .map_err(|format| CratesError::UnsupportedFormat(format, name.to_string()))?;
+
+        // NOTE: Probe all candidates at once, same as cargo-binstall,
+        // and then download the most preferred one that exists
+        let probes = join_all(
+            urls.iter()
+                .map(|url| self.get(HttpRequest::head(url.as_str()))),
+        )
+        .await;
+        let found = urls
+            .iter()
+            .zip(probes)
+            .find_map(|(url, probe)| probe.is_ok().then_some(url));
+        let Some(url) = found else {
+            return Err(CratesError::NoPrebuiltBinary {
+                spec: spec.clone().into(),
+                target: target.to_string(),
+            });
+        };
+
+        debug!(%url, "downloading prebuilt binary");
+        let response = self.get(HttpRequest::get(url.as_str())).await?;
+        Ok(response.body)
+    }
+}
+
+fn status(err: &CratesError) -> Option<u16> {
+    if let CratesError::Http(http_err) = err {
+        error_status(http_err)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,97 @@
+use semver::Version;
+use serde::Deserialize;
+
+/**
+    A crate as returned by the crates.io API, together with all of its versions.
+
+    Only the parts of the response that Rokit uses are kept.
+*/
+#[derive(Debug, Clone, Deserialize)]
+pub struct CrateResponse {
+    #[serde(rename = "crate")]
+    pub krate: CrateInfo,
+    pub versions: Vec<CrateVersion>,
+}
+
+/**
+    Information about a crate, as returned by the crates.io API.
+*/
+#[derive(Debug, Clone, Deserialize)]
+pub struct CrateInfo {
+    pub name: String,
+    #[serde(default)]
+    pub repository: Option<String>,
+}
+
+/**
+    A single published version of a crate, as returned by the crates.io API.
+*/
+#[derive(Debug, Clone, Deserialize)]
+pub struct CrateVersion {
+    pub num: String,
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+impl CrateResponse {
+    /**
+        Gets all versions of the crate that have not been
+        yanked and that are valid semver versions, newest first.
+    */
+    #[must_use]
+    pub fn available_versions(&self) -> Vec<Version> {
+        let mut versions = self
+            .versions
+            .iter()
+            .filter(|version| !version.yanked)
+            .filter_map(|version| Version::parse(&version.num).ok())
+            .collect::<Vec<_>>();
+        versions.sort_unstable_by(|a, b| b.cmp(a));
+        versions
+    }
+
+    /**
+        Gets the URL of the repository of the crate, without
+        any trailing slash or `.git` suffix, if it has one.
+    */
+    #[must_use]
+    pub fn repository_url(&self) -> Option<&str> {
+        let repository = self.krate.repository.as_deref()?.trim();
+        let repository = repository.trim_end_matches('/');
+        let repository = repository.strip_suffix(".git").unwrap_or(repository);
+        Some(repository).filter(|r| !r.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_crate_response() {
+        let response: CrateResponse = serde_json::from_str(
+            r#"{
+                "crate": { "name": "stylua", "repository": "https://github.com/JohnnyMorganz/StyLua.git" },
+                "versions": [
+                    { "num": "0.20.0", "yanked": false },
+                    { "num": "0.19.1", "yanked": true },
+                    { "num": "0.20.1-beta.1", "yanked": false },
+                    { "num": "0.18.0", "yanked": false }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response.repository_url(),
+            Some("https://github.com/JohnnyMorganz/StyLua")
+        );
+        assert_eq!(
+            response.available_versions(),
+            vec![
+                Version::parse("0.20.1-beta.1").unwrap(),
+                Version::parse("0.20.0").unwrap(),
+                Version::parse("0.18.0").unwrap(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use serde_json::Error as JsonError;
+use thiserror::Error;
+
+use crate::{
+    sources::http::HttpError,
+    tool::{ToolId, ToolSpec},
+};
+
+#[derive(Debug, Error)]
+pub enum CratesError {
+    #[error("crate '{0}' was not found on crates.io")]
+    CrateNotFound(String),
+    #[error("no latest release was found for tool '{0}'")]
+    LatestReleaseNotFound(Box<ToolId>),
+    #[error("no release was found for tool '{0}'")]
+    ReleaseNotFound(Box<ToolSpec>),
+    #[error(
+        "no prebuilt binary of '{spec}' was found for target '{target}'\
+        \nThe crate may not publish prebuilt binaries, and must be installed using `cargo install` instead."
+    )]
+    NoPrebuiltBinary { spec: Box<ToolSpec>, target: String },
+    #[error("package format '{0}' of crate '{1}' is not supported")]
+    UnsupportedFormat(String, String),
+    #[error("crates only contain tool artifacts, not {0}")]
+    NotSupported(&'static str),
+    #[error("HTTP error: {0}")]
+    Http(HttpError),
+    #[error("JSON error: {0}")]
+    Json(Box<JsonError>),
+}
+
+pub type CratesResult<T> = Result<T, CratesError>;
+
+impl From<HttpError> for CratesError {
+    fn from(err: HttpError) -> Self {
+        CratesError::Http(err)
+    }
+}
+
+impl From<JsonError> for CratesError {
+    fn from(err: JsonError) -> Self {
+        CratesError::Json(err.into())
+    }
+}
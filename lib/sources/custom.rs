@@ -0,0 +1,59 @@
+use std::{error::Error, fmt};
+
+use futures::future::BoxFuture;
+
+use crate::tool::{ToolId, ToolSpec};
+
+use super::{Artifact, Release};
+
+/**
+    An error returned by a [`CustomProvider`].
+*/
+pub type CustomProviderError = Box<dyn Error + Send + Sync>;
+
+/**
+    A third-party provider of tools, such as an Artifactory
+    instance or an S3 bucket, implemented outside of Rokit.
+
+    Embedders may implement this trait and register it with
+    [`ArtifactSource::with_custom_provider`] for a given host, after which tools
+    with ids such as `custom:artifacts.mycorp.com/author/name` use it to list
+    releases and download artifacts, the same as any built-in provider.
+
+    Artifacts returned by implementations should use [`ArtifactProvider::Custom`]
+    as their provider, and will be passed back to the same implementation when
+    downloaded - artifacts without a known format are expected to be the raw
+    binary of the tool, the same as for tools from direct URLs and registries.
+
+    [`ArtifactSource::with_custom_provider`]: super::ArtifactSource::with_custom_provider
+    [`ArtifactProvider::Custom`]: super::ArtifactProvider::Custom
+*/
+pub trait CustomProvider: fmt::Debug + Send + Sync {
+    /**
+        Lists all releases for the given tool, newest first.
+
+        This should include prereleases, since Rokit filters
+        releases by the channel that a tool is following.
+    */
+    fn get_releases<'a>(
+        &'a self,
+        id: &'a ToolId,
+    ) -> BoxFuture<'a, Result<Vec<Release>, CustomProviderError>>;
+
+    /**
+        Resolves the release for the exact version of the given tool.
+    */
+    fn get_release<'a>(
+        &'a self,
+        spec: &'a ToolSpec,
+    ) -> BoxFuture<'a, Result<Release, CustomProviderError>>;
+
+    /**
+        Downloads the contents of an artifact, previously
+        returned in a release by this same provider.
+    */
+    fn download_artifact<'a>(
+        &'a self,
+        artifact: &'a Artifact,
+    ) -> BoxFuture<'a, Result<Vec<u8>, CustomProviderError>>;
+}
@@ -1,18 +1,22 @@
-use std::io::Read;
+use std::{io::Read, time::Instant};
 
 use flate2::read::GzDecoder;
-use tokio::{task::spawn_blocking, time::Instant};
 
 use crate::result::RokitResult;
 
+use super::blocking::spawn_cpu_bound;
+
+#[cfg(feature = "zstd")]
+use zstd::stream::read::Decoder as ZstdDecoder;
+
 pub async fn decompress_gzip(gz_contents: impl AsRef<[u8]>) -> RokitResult<Vec<u8>> {
     let gz_contents = gz_contents.as_ref().to_vec();
     let num_kilobytes = gz_contents.len() / 1024;
     let start = Instant::now();
 
     // Decompressing gzip is a potentially expensive operation, so
-    // spawn it as a blocking task and use the tokio thread pool.
-    spawn_blocking(move || {
+    // spawn it as a blocking task, limited to the available cores.
+    spawn_cpu_bound(move || {
         let mut decoder = GzDecoder::new(gz_contents.as_slice());
         let mut contents = Vec::new();
         decoder.read_to_end(&mut contents)?;
@@ -24,5 +28,34 @@ pub async fn decompress_gzip(gz_contents: impl AsRef<[u8]>) -> RokitResult<Vec<u
         );
         Ok(contents)
     })
-    .await?
+    .await
+}
+
+#[cfg(feature = "zstd")]
+pub async fn decompress_zstd_patch(
+    patch_contents: impl AsRef<[u8]>,
+    base_contents: impl AsRef<[u8]>,
+) -> RokitResult<Vec<u8>> {
+    let patch_contents = patch_contents.as_ref().to_vec();
+    let base_contents = base_contents.as_ref().to_vec();
+    let num_kilobytes = patch_contents.len() / 1024;
+    let start = Instant::now();
+
+    // Same as for gzip, this is potentially expensive. Patches created
+    // using `zstd --patch-from` use the base file as a raw dictionary,
+    // and may need a larger window than what zstd allows by default.
+    spawn_cpu_bound(move || {
+        let mut decoder = ZstdDecoder::with_dictionary(patch_contents.as_slice(), &base_contents)?;
+        decoder.window_log_max(31)?;
+        let mut contents = Vec::new();
+        decoder.read_to_end(&mut contents)?;
+
+        tracing::trace!(
+            num_kilobytes,
+            elapsed = ?start.elapsed(),
+            "decompressed zstd patch"
+        );
+        Ok(contents)
+    })
+    .await
 }
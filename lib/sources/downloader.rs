@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use futures::{future::BoxFuture, FutureExt};
+use tokio::sync::Semaphore;
+use tracing::trace;
+
+use super::http::{HttpClient, HttpError, HttpRequest, HttpResponse};
+
+/**
+    A HTTP client shared by all providers of an [`ArtifactSource`], which
+    limits how many requests may be in flight at once, across all providers.
+
+    Many tools may be resolved and downloaded concurrently, such as when
+    installing all tools in a manifest, and without a limit this would open
+    a connection for each one of them, at the same time - which is slower on
+    most connections, and quickly runs into secondary rate limits on GitHub.
+
+    Can be cheaply cloned while still sharing the same limit.
+
+    [`ArtifactSource`]: crate::sources::ArtifactSource
+*/
+#[derive(Debug, Clone)]
+pub struct Downloader {
+    client: Arc<dyn HttpClient>,
+    permits: Arc<Semaphore>,
+    concurrency: usize,
+}
+
+impl Downloader {
+    /**
+        The default number of requests that may be in flight at once.
+    */
+    pub const DEFAULT_CONCURRENCY: usize = 4;
+
+    /**
+        Creates a new downloader using the given client, with the default concurrency.
+    */
+    #[must_use]
+    pub fn new(client: Arc<dyn HttpClient>) -> Self {
+        Self::with_concurrency(client, Self::DEFAULT_CONCURRENCY)
+    }
+
+    /**
+        Creates a new downloader using the given client, which allows at most
+        `concurrency` requests to be in flight at once - at least one is always allowed.
+    */
+    #[must_use]
+    pub fn with_concurrency(client: Arc<dyn HttpClient>, concurrency: usize) -> Self {
+        let concurrency = concurrency.max(1);
+        Self {
+            client,
+            permits: Arc::new(Semaphore::new(concurrency)),
+            concurrency,
+        }
+    }
+
+    /**
+        Creates a new downloader using the given client, which shares its
+        limit of requests in flight at once with this downloader.
+    */
+    #[must_use]
+    pub fn with_client(&self, client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            client,
+            permits: Arc::clone(&self.permits),
+            concurrency: self.concurrency,
+        }
+    }
+
+    /**
+        Gets the number of requests that may be in flight at once.
+    */
+    #[must_use]
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /**
+        Sends the given request, waiting for another request to finish first
+        if there are already as many requests in flight as are allowed.
+
+        Note that the request is in flight until its full body has been received.
+
+        # Errors
+
+        - If the underlying client failed to send the request.
+    */
+    pub async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        // NOTE: The semaphore is never closed, so acquiring always succeeds
+        let _permit = self.permits.acquire().await.ok();
+        trace!(url = %request.url, "sending request");
+        self.client.send(request).await
+    }
+}
+
+impl HttpClient for Downloader {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>> {
+        Downloader::send(self, request).boxed()
+    }
+}
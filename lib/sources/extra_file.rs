@@ -0,0 +1,209 @@
+use std::path::{Component, Path};
+
+/**
+    A shell that completion scripts may be installed for.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+}
+
+impl CompletionShell {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+            Self::PowerShell => "powershell",
+        }
+    }
+
+    fn from_dir_name(name: &str) -> Option<Self> {
+        match name {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            "powershell" | "pwsh" => Some(Self::PowerShell),
+            _ => None,
+        }
+    }
+
+    fn from_file_name(name: &str) -> Option<Self> {
+        let Some(extension) = Path::new(name).extension().and_then(|e| e.to_str()) else {
+            return name.starts_with('_').then_some(Self::Zsh);
+        };
+        if extension.eq_ignore_ascii_case("bash")
+            || extension.eq_ignore_ascii_case("bash-completion")
+        {
+            Some(Self::Bash)
+        } else if extension.eq_ignore_ascii_case("fish") {
+            Some(Self::Fish)
+        } else if extension.eq_ignore_ascii_case("ps1") {
+            Some(Self::PowerShell)
+        } else if extension.eq_ignore_ascii_case("zsh") {
+            Some(Self::Zsh)
+        } else {
+            None
+        }
+    }
+}
+
+/**
+    The kind of an extra file, shipped alongside the binary in a tool archive.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExtraFileKind {
+    /// A man page, in the given section (`1` through `9`).
+    ManPage(u8),
+    /// A completion script for the given shell.
+    Completion(CompletionShell),
+}
+
+impl ExtraFileKind {
+    /**
+        Figures out what kind of extra file the given path in an archive is, if any.
+
+        Man pages must be named `<name>.<section>`, optionally gzipped, and be
+        in a `man`, `doc`, or `docs` directory, or a section directory such as
+        `man1`. Completion scripts must be in a `complete` or `completions`
+        directory, and either be named after their shell (`rg.bash`, `_rg`
+        for zsh, `rg.fish`, `_rg.ps1`) or be in a directory for their shell.
+    */
+    #[cfg_attr(not(feature = "extract"), allow(dead_code))]
+    pub(crate) fn from_archive_path(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        let dir_names = path
+            .parent()?
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => name.to_str().map(str::to_ascii_lowercase),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let is_gzipped = Path::new(file_name)
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("gz"));
+        let page_name = if is_gzipped {
+            &file_name[..file_name.len() - 3]
+        } else {
+            file_name
+        };
+        if let Some((stem, section)) = page_name.rsplit_once('.') {
+            if let Ok(number @ 1..=9) = section.parse::<u8>() {
+                let in_man_dir = dir_names.iter().any(|dir| {
+                    MAN_DIR_NAMES.contains(&dir.as_str())
+                        || dir.strip_prefix("man") == Some(section)
+                });
+                if !stem.is_empty() && in_man_dir {
+                    return Some(Self::ManPage(number));
+                }
+            }
+        }
+
+        let in_completion_dir = dir_names
+            .iter()
+            .any(|dir| COMPLETION_DIR_NAMES.contains(&dir.as_str()));
+        if !in_completion_dir {
+            return None;
+        }
+        dir_names
+            .iter()
+            .rev()
+            .find_map(|dir| CompletionShell::from_dir_name(dir))
+            .or_else(|| CompletionShell::from_file_name(file_name))
+            .map(Self::Completion)
+    }
+}
+
+const MAN_DIR_NAMES: [&str; 3] = ["man", "doc", "docs"];
+const COMPLETION_DIR_NAMES: [&str; 5] = [
+    "complete",
+    "completion",
+    "completions",
+    "autocomplete",
+    "shell-completions",
+];
+
+/**
+    An extra file, such as a man page or completion script,
+    shipped alongside the binary in a tool archive.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtraFile {
+    pub kind: ExtraFileKind,
+    pub file_name: String,
+    pub contents: Vec<u8>,
+}
+
+impl ExtraFile {
+    #[cfg_attr(not(feature = "extract"), allow(dead_code))]
+    pub(crate) fn from_archive_entry(path: &Path, contents: Vec<u8>) -> Option<Self> {
+        let kind = ExtraFileKind::from_archive_path(path)?;
+        let file_name = path.file_name()?.to_str()?.to_string();
+        Some(Self {
+            kind,
+            file_name,
+            contents,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extra_kind(path: &str) -> Option<ExtraFileKind> {
+        ExtraFileKind::from_archive_path(Path::new(path))
+    }
+
+    #[test]
+    fn extra_file_kind_man_pages() {
+        assert_eq!(
+            extra_kind("ripgrep-14.1.0/doc/rg.1"),
+            Some(ExtraFileKind::ManPage(1))
+        );
+        assert_eq!(
+            extra_kind("share/man/man5/tool.toml.5.gz"),
+            Some(ExtraFileKind::ManPage(5))
+        );
+        assert_eq!(extra_kind("man1/tool.1"), Some(ExtraFileKind::ManPage(1)));
+        assert_eq!(extra_kind("man/tool.1.GZ"), Some(ExtraFileKind::ManPage(1)));
+        assert_eq!(extra_kind("man1/tool.2"), None);
+        assert_eq!(extra_kind("lib/libtool.so.1"), None);
+        assert_eq!(extra_kind("doc/README.md"), None);
+        assert_eq!(extra_kind("rg.1"), None);
+    }
+
+    #[test]
+    fn extra_file_kind_completions() {
+        let completion = |shell| Some(ExtraFileKind::Completion(shell));
+        assert_eq!(
+            extra_kind("complete/rg.bash"),
+            completion(CompletionShell::Bash)
+        );
+        assert_eq!(extra_kind("complete/_rg"), completion(CompletionShell::Zsh));
+        assert_eq!(
+            extra_kind("completions/rg.fish"),
+            completion(CompletionShell::Fish)
+        );
+        assert_eq!(
+            extra_kind("completions/_rg.ps1"),
+            completion(CompletionShell::PowerShell)
+        );
+        assert_eq!(
+            extra_kind("completions/zsh/tool"),
+            completion(CompletionShell::Zsh)
+        );
+        assert_eq!(
+            extra_kind("completions/TOOL.PS1"),
+            completion(CompletionShell::PowerShell)
+        );
+        assert_eq!(extra_kind("completions/README.md"), None);
+        assert_eq!(extra_kind("scripts/rg.bash"), None);
+    }
+}
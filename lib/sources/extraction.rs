@@ -1,18 +1,25 @@
 #![allow(clippy::struct_excessive_bools)]
 
 use std::{
+    collections::HashMap,
     env::consts::{EXE_EXTENSION, EXE_SUFFIX},
     io::{self, Read},
-    path::{Path, PathBuf, MAIN_SEPARATOR_STR},
+    path::{Component, Components, Path, PathBuf, MAIN_SEPARATOR_STR},
+    time::Instant,
 };
 
 use tar::Archive as TarArchive;
 use thiserror::Error;
-use tokio::{task::spawn_blocking, time::Instant};
 use zip::ZipArchive;
 
 use crate::{descriptor::OS, result::RokitResult, sources::ArtifactFormat};
 
+use super::{
+    blocking::spawn_cpu_bound,
+    decompression::decompress_gzip,
+    extra_file::{ExtraFile, ExtraFileKind},
+};
+
 #[derive(Debug, Error)]
 pub enum ExtractError {
     #[error("unknown format")]
@@ -138,8 +145,8 @@ pub async fn extract_zip_file(
     let start = Instant::now();
 
     // Reading a zip file is a potentially expensive operation, so
-    // spawn it as a blocking task and use the tokio thread pool.
-    spawn_blocking(move || {
+    // spawn it as a blocking task, limited to the available cores.
+    spawn_cpu_bound(move || {
         let mut found = None;
         let mut reader = io::Cursor::new(&zip_contents);
         let mut zip = ZipArchive::new(&mut reader)?;
@@ -181,7 +188,7 @@ pub async fn extract_zip_file(
         );
         Ok(found)
     })
-    .await?
+    .await
 }
 
 /**
@@ -201,8 +208,8 @@ pub async fn extract_tar_file(
     let start = Instant::now();
 
     // Reading a tar file is a potentially expensive operation, so
-    // spawn it as a blocking task and use the tokio thread pool.
-    spawn_blocking(move || {
+    // spawn it as a blocking task, limited to the available cores.
+    spawn_cpu_bound(move || {
         let mut found = None;
 
         /*
@@ -259,5 +266,604 @@ pub async fn extract_tar_file(
         );
         Ok(found)
     })
-    .await?
+    .await
+}
+
+/**
+    Extracts all extra files, such as man pages and completion scripts, from a zip archive.
+*/
+pub async fn extract_zip_extra_files(
+    zip_contents: impl AsRef<[u8]>,
+) -> RokitResult<Vec<ExtraFile>> {
+    let zip_contents = zip_contents.as_ref().to_vec();
+
+    spawn_cpu_bound(move || {
+        let mut reader = io::Cursor::new(&zip_contents);
+        let mut zip = ZipArchive::new(&mut reader)?;
+
+        let mut extras = Vec::new();
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index)?;
+            // NOTE: Only file names are ever used from these paths, so
+            // they are safe to use without sanitizing them any further
+            let path = PathBuf::from(entry.name());
+            if entry.is_dir() || ExtraFileKind::from_archive_path(&path).is_none() {
+                continue;
+            }
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            extras.extend(ExtraFile::from_archive_entry(&path, bytes));
+        }
+
+        tracing::debug!(count = extras.len(), "extracted extra files from zip file");
+        Ok(extras)
+    })
+    .await
+}
+
+/**
+    Extracts all extra files, such as man pages and completion scripts, from a tar archive.
+*/
+pub async fn extract_tar_extra_files(
+    tar_contents: impl AsRef<[u8]>,
+) -> RokitResult<Vec<ExtraFile>> {
+    let tar_contents = tar_contents.as_ref().to_vec();
+
+    spawn_cpu_bound(move || {
+        let mut reader = TarArchive::new(io::Cursor::new(&tar_contents));
+
+        let mut extras = Vec::new();
+        for entry in reader.entries_with_seek()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path()?.to_path_buf();
+            if ExtraFileKind::from_archive_path(&path).is_none() {
+                continue;
+            }
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            extras.extend(ExtraFile::from_archive_entry(&path, bytes));
+        }
+
+        tracing::debug!(count = extras.len(), "extracted extra files from tar file");
+        Ok(extras)
+    })
+    .await
+}
+
+/**
+    Unpacks a gzipped tarball of a repository, such as one downloaded using
+    [`ArtifactSource::download_repository_archive`], into the given directory.
+
+    See [`unpack_repository_tar`] for details on how files are unpacked.
+
+    [`ArtifactSource::download_repository_archive`]: crate::sources::ArtifactSource::download_repository_archive
+
+    # Errors
+
+    - If the archive is not a valid gzipped tarball.
+    - If any file could not be written.
+*/
+pub async fn unpack_repository_archive(
+    archive_contents: impl AsRef<[u8]>,
+    dir: impl Into<PathBuf>,
+) -> RokitResult<usize> {
+    let tar_contents = decompress_gzip(archive_contents).await?;
+    unpack_repository_tar(tar_contents, dir).await
+}
+
+/**
+    Unpacks all files in a tar archive of a repository into the given directory.
+
+    Repository archives contain a single top-level directory, usually named after
+    the repository and commit, which is stripped away. Entries with paths that
+    would escape the given directory, as well as links, are skipped.
+
+    Returns the number of files that were unpacked.
+*/
+async fn unpack_repository_tar(
+    tar_contents: impl AsRef<[u8]>,
+    dir: impl Into<PathBuf>,
+) -> RokitResult<usize> {
+    let tar_contents = tar_contents.as_ref().to_vec();
+    let dir = dir.into();
+    let start = Instant::now();
+
+    // Unpacking a tar file writes many files to disk, so
+    // spawn it as a blocking task, limited to the available cores.
+    spawn_cpu_bound(move || {
+        let mut num_files = 0;
+        let mut archive = TarArchive::new(io::Cursor::new(&tar_contents));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_type = entry.header().entry_type();
+            if !entry_type.is_file() && !entry_type.is_dir() {
+                continue;
+            }
+
+            let entry_path = entry.path()?.to_path_buf();
+            let Some(relative_path) = strip_repository_prefix(&entry_path) else {
+                tracing::warn!(path = ?entry_path, "skipping unsafe path in repository archive");
+                continue;
+            };
+            if relative_path.as_os_str().is_empty() {
+                continue;
+            }
+
+            let path = dir.join(relative_path);
+            if entry_type.is_dir() {
+                std::fs::create_dir_all(&path)?;
+            } else {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                entry.unpack(&path)?;
+                num_files += 1;
+            }
+        }
+
+        tracing::debug!(
+            num_files,
+            elapsed = ?start.elapsed(),
+            "unpacked repository tar file"
+        );
+        Ok(num_files)
+    })
+    .await
+}
+
+/**
+    Unpacks all files in a zip archive into the given directory, keeping
+    the layout of the archive, for tools installed using `install-dir = true`.
+
+    See [`archive_tree_paths`] for details on how files are unpacked.
+
+    Returns the number of files that were unpacked.
+*/
+pub async fn unpack_zip_tree(
+    zip_contents: impl AsRef<[u8]>,
+    dir: impl Into<PathBuf>,
+) -> RokitResult<usize> {
+    let zip_contents = zip_contents.as_ref().to_vec();
+    let dir = dir.into();
+    let start = Instant::now();
+
+    // Unpacking a zip file writes many files to disk, so
+    // spawn it as a blocking task, limited to the available cores.
+    spawn_cpu_bound(move || {
+        let mut reader = io::Cursor::new(&zip_contents);
+        let mut zip = ZipArchive::new(&mut reader)?;
+
+        let entry_paths = zip.file_names().map(PathBuf::from).collect::<Vec<_>>();
+        let mut num_files = 0;
+        for (entry_path, relative_path) in archive_tree_paths(&entry_paths) {
+            let Some(relative_path) = relative_path else {
+                tracing::warn!(path = ?entry_path, "skipping unsafe path in zip file");
+                continue;
+            };
+            let Some(name) = entry_path.to_str() else {
+                continue;
+            };
+            let mut entry = zip.by_name(name)?;
+            let path = dir.join(relative_path);
+            if entry.is_dir() {
+                std::fs::create_dir_all(&path)?;
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            write_unpacked_file(&path, &contents, entry.unix_mode())?;
+            num_files += 1;
+        }
+
+        tracing::debug!(
+            num_files,
+            elapsed = ?start.elapsed(),
+            "unpacked zip file tree"
+        );
+        Ok(num_files)
+    })
+    .await
+}
+
+/**
+    Unpacks all files in a tar archive into the given directory, keeping
+    the layout of the archive, for tools installed using `install-dir = true`.
+
+    See [`archive_tree_paths`] for details on how files are unpacked.
+
+    Returns the number of files that were unpacked.
+*/
+pub async fn unpack_tar_tree(
+    tar_contents: impl AsRef<[u8]>,
+    dir: impl Into<PathBuf>,
+) -> RokitResult<usize> {
+    let tar_contents = tar_contents.as_ref().to_vec();
+    let dir = dir.into();
+    let start = Instant::now();
+
+    // Unpacking a tar file writes many files to disk, so
+    // spawn it as a blocking task, limited to the available cores.
+    spawn_cpu_bound(move || {
+        // NOTE: Tar archives can only be read once, so gather all of
+        // the paths first, to know if there is a directory to strip
+        let mut entry_paths = Vec::new();
+        let mut archive = TarArchive::new(io::Cursor::new(&tar_contents));
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let entry_type = entry.header().entry_type();
+            if entry_type.is_file() || entry_type.is_dir() {
+                entry_paths.push(entry.path()?.to_path_buf());
+            }
+        }
+        let relative_paths = archive_tree_paths(&entry_paths)
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        let mut num_files = 0;
+        let mut archive = TarArchive::new(io::Cursor::new(&tar_contents));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_type = entry.header().entry_type();
+            if !entry_type.is_file() && !entry_type.is_dir() {
+                continue;
+            }
+
+            let entry_path = entry.path()?.to_path_buf();
+            let Some(relative_path) = relative_paths.get(&entry_path) else {
+                continue;
+            };
+            let Some(relative_path) = relative_path else {
+                tracing::warn!(path = ?entry_path, "skipping unsafe path in tar file");
+                continue;
+            };
+
+            let path = dir.join(relative_path);
+            if entry_type.is_dir() {
+                std::fs::create_dir_all(&path)?;
+            } else {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let mode = entry.header().mode().ok();
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents)?;
+                write_unpacked_file(&path, &contents, mode)?;
+                num_files += 1;
+            }
+        }
+
+        tracing::debug!(
+            num_files,
+            elapsed = ?start.elapsed(),
+            "unpacked tar file tree"
+        );
+        Ok(num_files)
+    })
+    .await
+}
+
+/**
+    Gets the paths that entries of an archive should be unpacked to,
+    relative to the directory that the archive is unpacked into.
+
+    Archives where every entry is inside of the same top-level directory,
+    such as `tool-v1.0.0-linux/`, have that directory stripped away. Entries
+    with paths that would escape the directory get a relative path of `None`,
+    and entries that are only the stripped directory itself are left out.
+*/
+fn archive_tree_paths(entry_paths: &[PathBuf]) -> Vec<(PathBuf, Option<PathBuf>)> {
+    let top_level = |path: &Path| match path.components().next() {
+        Some(Component::Normal(part)) => Some(part.to_os_string()),
+        _ => None,
+    };
+    let common_dir = entry_paths
+        .first()
+        .and_then(|path| top_level(path))
+        .filter(|dir| {
+            entry_paths
+                .iter()
+                .all(|path| top_level(path).as_ref() == Some(dir))
+                && entry_paths
+                    .iter()
+                    .any(|path| path.components().nth(1).is_some())
+        });
+
+    entry_paths
+        .iter()
+        .filter_map(|path| {
+            let mut components = path.components();
+            if common_dir.is_some() {
+                components.next();
+            }
+            let relative_path = relative_components(components);
+            match relative_path {
+                Some(relative) if relative.as_os_str().is_empty() => None,
+                relative => Some((path.clone(), relative)),
+            }
+        })
+        .collect()
+}
+
+/**
+    Extracts the `Cargo.toml` manifest from a crate package, as published
+    on crates.io - these are gzipped tarballs with a single top-level
+    directory named after the crate and its version, same as repository
+    archives, and the manifest is always found directly inside of it.
+
+    May return `None` if the package has no manifest.
+*/
+pub async fn extract_crate_manifest(
+    crate_contents: impl AsRef<[u8]>,
+) -> RokitResult<Option<Vec<u8>>> {
+    let tar_contents = decompress_gzip(crate_contents).await?;
+    spawn_cpu_bound(move || {
+        let mut archive = TarArchive::new(io::Cursor::new(&tar_contents));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let is_manifest = strip_repository_prefix(&entry.path()?)
+                .is_some_and(|path| path == Path::new("Cargo.toml"));
+            if is_manifest {
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes)?;
+                return Ok(Some(bytes));
+            }
+        }
+        Ok(None)
+    })
+    .await
+}
+
+/**
+    Strips the top-level directory from a path in a repository archive.
+
+    Returns `None` if the path is not a plain relative path.
+*/
+fn strip_repository_prefix(path: &Path) -> Option<PathBuf> {
+    let mut components = path.components();
+    match components.next()? {
+        Component::Normal(_) => {}
+        _ => return None,
+    }
+    relative_components(components)
+}
+
+/*
+    Joins the given path components into a plain relative path,
+    or returns `None` if any of them could escape the directory.
+*/
+fn relative_components(components: Components<'_>) -> Option<PathBuf> {
+    let mut stripped = PathBuf::new();
+    for component in components {
+        match component {
+            Component::Normal(part) => stripped.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(stripped)
+}
+
+/*
+    Writes a file unpacked from an archive tree, giving it the permissions
+    from its archive entry, and making sure that executables stay executable.
+*/
+fn write_unpacked_file(path: &Path, contents: &[u8], mode: Option<u32>) -> io::Result<()> {
+    std::fs::write(path, contents)?;
+    let mode = unpacked_file_mode(mode, contents);
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    Ok(())
+}
+
+/**
+    Gets the permissions that a file unpacked from an archive should have,
+    given the mode stored in its archive entry, if any, and its contents.
+
+    Archives, and zip files in particular, are often created without any
+    Unix permissions, so native executables and scripts are detected using
+    their contents and made executable by anyone that may read them.
+
+    Returns `None` if the permissions of the file should be left as-is.
+*/
+fn unpacked_file_mode(mode: Option<u32>, contents: &[u8]) -> Option<u32> {
+    let mode = mode.map(|mode| mode & 0o777).filter(|mode| *mode != 0);
+    if is_executable_contents(contents) {
+        let mode = mode.unwrap_or(0o644);
+        Some(mode | ((mode & 0o444) >> 2))
+    } else {
+        mode
+    }
+}
+
+/**
+    Checks if the given file contents look like something that should
+    be executable - an ELF or Mach-O binary, or a script with a shebang.
+*/
+fn is_executable_contents(contents: &[u8]) -> bool {
+    match contents {
+        [b'#', b'!', ..]
+        | [0x7F, b'E', b'L', b'F', ..]
+        | [0xFE, 0xED, 0xFA, 0xCE | 0xCF, ..]
+        | [0xCE | 0xCF, 0xFA, 0xED, 0xFE, ..] => true,
+        // NOTE: Universal Mach-O binaries share their magic with Java class
+        // files, which store a version in place of the number of architectures
+        [0xCA, 0xFE, 0xBA, 0xBE | 0xBF, a, b, c, d, ..] => {
+            (1..=20).contains(&u32::from_be_bytes([*a, *b, *c, *d]))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_tree_paths_strip_common_dir() {
+        let paths = [
+            PathBuf::from("tool-v1.0.0/"),
+            PathBuf::from("tool-v1.0.0/bin/tool"),
+            PathBuf::from("tool-v1.0.0/templates/default.txt"),
+        ];
+        assert_eq!(
+            archive_tree_paths(&paths),
+            vec![
+                (paths[1].clone(), Some(PathBuf::from("bin/tool"))),
+                (
+                    paths[2].clone(),
+                    Some(PathBuf::from("templates/default.txt"))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn archive_tree_paths_keep_layout() {
+        let paths = [
+            PathBuf::from("tool"),
+            PathBuf::from("templates/default.txt"),
+            PathBuf::from("../escaping.txt"),
+        ];
+        assert_eq!(
+            archive_tree_paths(&paths),
+            vec![
+                (paths[0].clone(), Some(PathBuf::from("tool"))),
+                (
+                    paths[1].clone(),
+                    Some(PathBuf::from("templates/default.txt"))
+                ),
+                (paths[2].clone(), None),
+            ]
+        );
+        // A single file at the root is never stripped away
+        assert_eq!(
+            archive_tree_paths(&[PathBuf::from("tool")]),
+            vec![(PathBuf::from("tool"), Some(PathBuf::from("tool")))]
+        );
+    }
+
+    #[test]
+    fn strip_repository_prefix_valid() {
+        assert_eq!(
+            strip_repository_prefix(Path::new("owner-repo-abc123/rokit.toml")),
+            Some(PathBuf::from("rokit.toml"))
+        );
+        assert_eq!(
+            strip_repository_prefix(Path::new("owner-repo-abc123/src/./main.luau")),
+            Some(PathBuf::from("src/main.luau"))
+        );
+        assert_eq!(
+            strip_repository_prefix(Path::new("owner-repo-abc123/")),
+            Some(PathBuf::new())
+        );
+    }
+
+    #[test]
+    fn strip_repository_prefix_unsafe() {
+        assert_eq!(
+            strip_repository_prefix(Path::new("owner-repo-abc123/../escaped")),
+            None
+        );
+        assert_eq!(strip_repository_prefix(Path::new("/etc/passwd")), None);
+        assert_eq!(strip_repository_prefix(Path::new("../escaped")), None);
+    }
+
+    #[test]
+    fn executable_contents_detection() {
+        assert!(is_executable_contents(b"\x7fELF\x02\x01\x01\x00"));
+        assert!(is_executable_contents(b"\xcf\xfa\xed\xfe\x07\x00\x00\x01"));
+        assert!(is_executable_contents(b"\xca\xfe\xba\xbe\x00\x00\x00\x02"));
+        assert!(is_executable_contents(b"#!/bin/sh\necho hello\n"));
+        assert!(!is_executable_contents(b"\xca\xfe\xba\xbe\x00\x00\x00\x34"));
+        assert!(!is_executable_contents(b"MZ\x90\x00"));
+        assert!(!is_executable_contents(b"# Tool\n"));
+        assert!(!is_executable_contents(b""));
+    }
+
+    #[test]
+    fn unpacked_file_mode_detects_executables() {
+        let elf = b"\x7fELF\x02\x01\x01\x00";
+        assert_eq!(unpacked_file_mode(None, elf), Some(0o755));
+        assert_eq!(unpacked_file_mode(Some(0), elf), Some(0o755));
+        assert_eq!(unpacked_file_mode(Some(0o100_600), elf), Some(0o700));
+        assert_eq!(unpacked_file_mode(Some(0o750), elf), Some(0o750));
+        assert_eq!(unpacked_file_mode(None, b"hello"), None);
+        assert_eq!(unpacked_file_mode(Some(0o644), b"hello"), Some(0o644));
+        assert_eq!(unpacked_file_mode(Some(0o755), b"hello"), Some(0o755));
+    }
+
+    const ARCHIVE_FILES: [(&str, u32, &[u8]); 4] = [
+        ("tool/bin/tool", 0o644, b"\x7fELF\x02\x01\x01\x00"),
+        ("tool/scripts/setup.sh", 0o600, b"#!/bin/sh\necho hello\n"),
+        ("tool/scripts/run.sh", 0o755, b"echo hello\n"),
+        ("tool/README.md", 0o644, b"# Tool\n"),
+    ];
+
+    const ARCHIVE_MODES: [Option<u32>; 4] = [Some(0o755), Some(0o700), Some(0o755), Some(0o644)];
+
+    #[test]
+    fn unpacked_file_mode_zip() {
+        use std::io::Write;
+        use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+        let mut writer = ZipWriter::new(io::Cursor::new(Vec::new()));
+        for (path, mode, contents) in ARCHIVE_FILES {
+            let options = SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Stored)
+                .unix_permissions(mode);
+            writer.start_file(path, options).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        let zip_contents = writer.finish().unwrap().into_inner();
+
+        let mut zip = ZipArchive::new(io::Cursor::new(zip_contents)).unwrap();
+        let modes = ARCHIVE_FILES
+            .iter()
+            .map(|(path, _, _)| {
+                let mut entry = zip.by_name(path).unwrap();
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents).unwrap();
+                unpacked_file_mode(entry.unix_mode(), &contents)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(modes, ARCHIVE_MODES);
+    }
+
+    #[test]
+    fn unpacked_file_mode_tar() {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, mode, contents) in ARCHIVE_FILES {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(mode);
+            header.set_cksum();
+            builder.append_data(&mut header, path, contents).unwrap();
+        }
+        let tar_contents = builder.into_inner().unwrap();
+
+        let mut archive = TarArchive::new(io::Cursor::new(tar_contents));
+        let modes = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let mode = entry.header().mode().ok();
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents).unwrap();
+                unpacked_file_mode(mode, &contents)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(modes, ARCHIVE_MODES);
+    }
 }
@@ -0,0 +1,291 @@
+use std::{collections::BTreeMap, process::Command, sync::Arc};
+
+use semver::Version;
+use tracing::{debug, instrument};
+
+use crate::{
+    executor::process::command_output,
+    tool::{ReleaseChannel, ToolId, ToolSpec},
+};
+
+use super::{
+    http::{user_agent, HttpClient, HttpRequest},
+    Artifact, ArtifactFormat, ArtifactProvider, Release,
+};
+
+#[cfg(feature = "reqwest-client")]
+use super::http::default_http_client;
+
+mod result;
+mod tags;
+mod template;
+
+pub use self::result::{GitError, GitResult};
+pub use self::template::{AssetTemplate, DEFAULT_ASSET_TEMPLATE};
+
+use self::tags::{parse_ls_remote_tags, version_from_tag};
+
+/**
+    A provider for tools from plain git repositories, on forges that do
+    not have a releases API, such as `git:codeberg.org/owner/repo`.
+
+    Versions of a tool are the tags of its repository that are valid
+    versions, which may or may not be prefixed with `v` - tags are
+    listed using `git ls-remote`, so `git` must be installed.
+
+    Since there is no API to list the assets of a release, their URLs
+    are created from an [`AssetTemplate`] for each tag instead, which
+    may be set for each tool - see [`DEFAULT_ASSET_TEMPLATE`] for the
+    template that is used for tools without their own.
+*/
+#[derive(Debug, Clone)]
+pub struct GitProvider {
+    client: Arc<dyn HttpClient>,
+    offline: bool,
+}
+
+impl GitProvider {
+    /**
+        Creates a new git provider, using the default HTTP client.
+
+        # Errors
+
+        - If the HTTP client could not be created.
+    */
+    #[cfg(feature = "reqwest-client")]
+    pub fn new() -> GitResult<Self> {
+        Ok(Self::new_with_client(default_http_client()?))
+    }
+
+    /**
+        Creates a new git provider, using the given HTTP client.
+    */
+    #[must_use]
+    pub fn new_with_client(client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            client,
+            offline: false,
+        }
+    }
+
+    /**
+        Uses the given HTTP client for all requests made by this provider.
+    */
+    #[must_use]
+    pub(crate) fn with_client(mut self, client: Arc<dyn HttpClient>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /**
+        Never runs `git` to list the tags of a repository, since it can not
+        use the HTTP client or its cache, and fails to find releases instead.
+
+        Releases for specifications with an exact tag are still found.
+    */
+    #[must_use]
+    pub(crate) fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    /**
+        Lists all tags of the repository for the given tool that are valid
+        versions, together with their versions, sorted newest first.
+
+        # Errors
+
+        - If `git` could not be run.
+        - If the tags of the repository could not be listed.
+        - If this provider is offline.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn get_tags(&self, tool_id: &ToolId) -> GitResult<Vec<(String, Version)>> {
+        let url = repository_url(tool_id);
+        if self.offline {
+            return Err(GitError::Offline(url));
+        }
+        let mut command = Command::new("git");
+        command
+            .args(["ls-remote", "--tags", "--refs", url.as_str()])
+            // NOTE: Never let git prompt for credentials, which
+            // would block forever while the progress bar is shown
+            .env("GIT_TERMINAL_PROMPT", "0");
+        let output = command_output(command).await.map_err(GitError::Spawn)?;
+        if !output.status.success() {
+            return Err(GitError::ListTags {
+                url,
+                message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+
+        // NOTE: Tags such as `v1.0.0` and `1.0.0` may both exist for the
+        // same version, in which case we keep whichever is listed first
+        let mut tags = BTreeMap::new();
+        for tag in parse_ls_remote_tags(&String::from_utf8_lossy(&output.stdout)) {
+            if let Some(version) = version_from_tag(&tag) {
+                tags.entry(version).or_insert(tag);
+            }
+        }
+        debug!(count = tags.len(), "found tags for repository");
+        Ok(tags
+            .into_iter()
+            .rev()
+            .map(|(version, tag)| (tag, version))
+            .collect())
+    }
+
+    fn release_for(spec: &ToolSpec, tag: &str, template: &AssetTemplate) -> Release {
+        let artifacts = template
+            .expand(spec.id(), tag, spec.version())
+            .into_iter()
+            .map(|url| Artifact {
+                provider: ArtifactProvider::Git,
+                format: ArtifactFormat::from_path_or_url(url.as_str()),
+                id: None,
+                name: url
+                    .path_segments()
+                    .and_then(Iterator::last)
+                    .map(ToString::to_string),
+                url: Some(url),
+                size: None,
+                digest: None,
+                tool_spec: spec.clone(),
+            })
+            .collect();
+        Release {
+            version: spec.version().clone(),
+            changelog: None,
+            artifacts,
+        }
+    }
+
+    /**
+        Fetches the latest stable release for a given tool.
+
+        # Errors
+
+        - If the repository has no tags that are stable versions.
+        - If the tags of the repository could not be listed.
+    */
+    pub async fn get_latest_release(
+        &self,
+        tool_id: &ToolId,
+        template: &AssetTemplate,
+    ) -> GitResult<Release> {
+        self.get_recent_releases(tool_id, template)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| GitError::LatestReleaseNotFound(tool_id.clone().into()))
+    }
+
+    /**
+        Fetches all stable releases for a given tool, newest first.
+
+        # Errors
+
+        - If the tags of the repository could not be listed.
+    */
+    pub async fn get_recent_releases(
+        &self,
+        tool_id: &ToolId,
+        template: &AssetTemplate,
+    ) -> GitResult<Vec<Release>> {
+        self.get_recent_releases_in_channel(tool_id, &ReleaseChannel::Stable, template)
+            .await
+    }
+
+    /**
+        Fetches all releases in the given channel for a given tool, newest first.
+
+        # Errors
+
+        - If the tags of the repository could not be listed.
+    */
+    pub async fn get_recent_releases_in_channel(
+        &self,
+        tool_id: &ToolId,
+        channel: &ReleaseChannel,
+        template: &AssetTemplate,
+    ) -> GitResult<Vec<Release>> {
+        Ok(self
+            .get_tags(tool_id)
+            .await?
+            .into_iter()
+            .filter(|(_, version)| channel.contains(version))
+            .map(|(tag, version)| {
+                let spec = ToolSpec::from((tool_id.clone(), version));
+                Self::release_for(&spec, &tag, template)
+            })
+            .collect())
+    }
+
+    /**
+        Fetches a specific release for a given tool.
+
+        Specifications with an exact tag, see [`ToolSpec::with_tag`],
+        use that tag as-is, without listing the tags of the repository.
+
+        # Errors
+
+        - If no tag exists for the version.
+        - If the tags of the repository could not be listed.
+    */
+    pub async fn get_specific_release(
+        &self,
+        tool_spec: &ToolSpec,
+        template: &AssetTemplate,
+    ) -> GitResult<Release> {
+        if let Some(tag) = tool_spec.tag() {
+            return Ok(Self::release_for(tool_spec, tag, template));
+        }
+        let tag = self
+            .get_tags(tool_spec.id())
+            .await?
+            .into_iter()
+            .find(|(_, version)| version == tool_spec.version())
+            .map(|(tag, _)| tag)
+            .ok_or_else(|| GitError::ReleaseNotFound(tool_spec.clone().into()))?;
+        Ok(Self::release_for(tool_spec, &tag, template))
+    }
+
+    /**
+        Downloads the contents of the given artifact, from the URL it was created with.
+
+        # Errors
+
+        - If the artifact could not be downloaded.
+    */
+    #[instrument(skip(self, artifact), level = "debug")]
+    pub async fn download_artifact_contents(&self, artifact: &Artifact) -> GitResult<Vec<u8>> {
+        assert_eq!(
+            artifact.provider,
+            ArtifactProvider::Git,
+            "artifact must be from a git repository"
+        );
+
+        let url = artifact
+            .url
+            .as_ref()
+            .expect("git artifacts have asset urls");
+        debug!(%url, "downloading release asset");
+        let request = HttpRequest::get(url.as_str()).header("user-agent", user_agent());
+        let response = self.client.send(request).await?.error_for_status()?;
+        Ok(response.body)
+    }
+}
+
+/**
+    Gets the URL of the repository for the given tool,
+    such as `https://codeberg.org/owner/repo.git`.
+*/
+#[must_use]
+pub fn repository_url(tool_id: &ToolId) -> String {
+    format!(
+        "https://{}/{}/{}.git",
+        tool_id.host().unwrap_or_default(),
+        tool_id.author(),
+        tool_id.name()
+    )
+}
@@ -0,0 +1,34 @@
+use std::io::Error as IoError;
+
+use thiserror::Error;
+
+use crate::{
+    sources::http::HttpError,
+    tool::{ToolId, ToolSpec},
+};
+
+#[derive(Debug, Error)]
+pub enum GitError {
+    #[error("failed to run git, make sure that it is installed: {0}")]
+    Spawn(IoError),
+    #[error("failed to list tags of repository '{url}': {message}")]
+    ListTags { url: String, message: String },
+    #[error("tags of repository '{0}' can not be listed while offline")]
+    Offline(String),
+    #[error("no latest release was found for tool '{0}'")]
+    LatestReleaseNotFound(Box<ToolId>),
+    #[error("no release was found for tool '{0}'")]
+    ReleaseNotFound(Box<ToolSpec>),
+    #[error("git repositories only contain tool artifacts, not {0}")]
+    NotSupported(&'static str),
+    #[error("HTTP error: {0}")]
+    Http(HttpError),
+}
+
+pub type GitResult<T> = Result<T, GitError>;
+
+impl From<HttpError> for GitError {
+    fn from(err: HttpError) -> Self {
+        GitError::Http(err)
+    }
+}
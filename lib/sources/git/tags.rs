@@ -0,0 +1,63 @@
+use semver::Version;
+
+/**
+    Parses the names of all tags from the output of `git ls-remote --tags`.
+
+    Peeled tags, ending with `^{}`, point to the same
+    commit as the tag itself, and are skipped.
+*/
+pub(super) fn parse_ls_remote_tags(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (_, reference) = line.split_once('\t')?;
+            let tag = reference.trim().strip_prefix("refs/tags/")?;
+            if tag.is_empty() || tag.ends_with("^{}") {
+                None
+            } else {
+                Some(tag.to_string())
+            }
+        })
+        .collect()
+}
+
+/**
+    Parses the version of a release from its tag, which
+    may or may not be prefixed with `v`, such as `v1.2.3`.
+*/
+pub(super) fn version_from_tag(tag: &str) -> Option<Version> {
+    let tag = tag.trim();
+    let tag = tag
+        .strip_prefix('v')
+        .or_else(|| tag.strip_prefix('V'))
+        .unwrap_or(tag);
+    tag.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tags() {
+        let output = "\
+            1111111111111111111111111111111111111111\trefs/tags/v1.0.0\n\
+            2222222222222222222222222222222222222222\trefs/tags/v1.0.0^{}\n\
+            3333333333333333333333333333333333333333\trefs/tags/1.1.0-beta.1\n\
+            4444444444444444444444444444444444444444\trefs/heads/main\n";
+        assert_eq!(parse_ls_remote_tags(output), vec!["v1.0.0", "1.1.0-beta.1"]);
+        assert!(parse_ls_remote_tags("").is_empty());
+    }
+
+    #[test]
+    fn versions_from_tags() {
+        assert_eq!(version_from_tag("v1.2.3"), Some(Version::new(1, 2, 3)));
+        assert_eq!(version_from_tag("1.2.3"), Some(Version::new(1, 2, 3)));
+        assert_eq!(
+            version_from_tag("v1.2.3-rc.1"),
+            Some("1.2.3-rc.1".parse().unwrap())
+        );
+        assert_eq!(version_from_tag("nightly"), None);
+        assert_eq!(version_from_tag("release-1.2.3"), None);
+    }
+}
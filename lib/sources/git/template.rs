@@ -0,0 +1,136 @@
+use semver::Version;
+use url::Url;
+
+use crate::tool::ToolId;
+
+/**
+    The asset URL template used for tools that do not set their own,
+    which matches the release downloads of Gitea and Forgejo instances.
+*/
+pub const DEFAULT_ASSET_TEMPLATE: &str =
+    "https://{host}/{owner}/{repo}/releases/download/{tag}/{repo}-{version}-{platform}.zip";
+
+/*
+    Platforms that `{platform}` expands to - one URL is created for each, and
+    the most compatible of those is picked the same as for any other provider.
+*/
+const PLATFORMS: [&str; 6] = [
+    "windows-x86_64",
+    "windows-aarch64",
+    "macos-x86_64",
+    "macos-aarch64",
+    "linux-x86_64",
+    "linux-aarch64",
+];
+
+/**
+    A URL template for the release assets of a tool from a git repository.
+
+    Templates may contain any of the following placeholders:
+
+    - `{host}`, `{owner}` and `{repo}` - the parts of the tool id
+    - `{tag}` - the tag of the release, such as `v1.2.3`
+    - `{version}` - the version of the release, such as `1.2.3`
+    - `{platform}` - a platform such as `linux-x86_64`, see below
+
+    Templates containing `{platform}` are expanded into one URL for each
+    supported platform, and templates without it into a single URL.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetTemplate(String);
+
+impl AssetTemplate {
+    /**
+        Creates a new template, if the given string is a valid template,
+        which means that it must be an `https` URL once expanded.
+    */
+    #[must_use]
+    pub fn new(template: impl Into<String>) -> Option<Self> {
+        let template = template.into().trim().to_string();
+        if template.starts_with("https://") && !template.contains(char::is_whitespace) {
+            Some(Self(template))
+        } else {
+            None
+        }
+    }
+
+    /**
+        Gets the template as a string, with its placeholders.
+    */
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /**
+        Expands this template into the URLs of all assets
+        for the given tool, tag, and version of the tool.
+
+        Any expanded URL that is not a valid `https` URL is skipped.
+    */
+    #[must_use]
+    pub fn expand(&self, id: &ToolId, tag: &str, version: &Version) -> Vec<Url> {
+        let expanded = self
+            .0
+            .replace("{host}", id.host().unwrap_or_default())
+            .replace("{owner}", id.author())
+            .replace("{repo}", id.name())
+            .replace("{tag}", tag)
+            .replace("{version}", &version.to_string());
+        let urls = if expanded.contains("{platform}") {
+            PLATFORMS
+                .iter()
+                .map(|platform| expanded.replace("{platform}", platform))
+                .collect()
+        } else {
+            vec![expanded]
+        };
+        urls.into_iter()
+            .filter_map(|url| url.parse::<Url>().ok())
+            .filter(|url| url.scheme() == "https" && url.has_host())
+            .collect()
+    }
+}
+
+impl Default for AssetTemplate {
+    fn default() -> Self {
+        Self(DEFAULT_ASSET_TEMPLATE.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_id() -> ToolId {
+        "git:codeberg.org/owner/tool".parse().unwrap()
+    }
+
+    #[test]
+    fn templates_must_be_https() {
+        assert!(AssetTemplate::new("https://example.com/{tag}/tool.zip").is_some());
+        assert!(AssetTemplate::new("http://example.com/{tag}/tool.zip").is_none());
+        assert!(AssetTemplate::new("https://example.com/{tag} tool.zip").is_none());
+        assert!(AssetTemplate::new("").is_none());
+    }
+
+    #[test]
+    fn expand_default_template() {
+        let urls = AssetTemplate::default().expand(&new_id(), "v1.2.3", &Version::new(1, 2, 3));
+        assert_eq!(urls.len(), PLATFORMS.len());
+        assert_eq!(
+            urls[0].as_str(),
+            "https://codeberg.org/owner/tool/releases/download/v1.2.3/tool-1.2.3-windows-x86_64.zip"
+        );
+    }
+
+    #[test]
+    fn expand_without_platform() {
+        let template = AssetTemplate::new("https://{host}/dl/{repo}/{tag}/{repo}").unwrap();
+        let urls = template.expand(&new_id(), "1.0.0", &Version::new(1, 0, 0));
+        assert_eq!(
+            urls.iter().map(Url::as_str).collect::<Vec<_>>(),
+            vec!["https://codeberg.org/dl/tool/1.0.0/tool"]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+
+use super::models::GithubRelease;
+
+/**
+    Controls which releases on GitHub are considered
+    when resolving the latest release for a tool.
+
+    By default, only published releases that are not marked as
+    prereleases are considered, the same as the GitHub API does for
+    the latest release of a repository - changing any of these options
+    instead resolves the latest release from a list of recent releases.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReleaseFilter {
+    /// If drafts should be considered - these are only visible with authentication.
+    pub include_drafts: bool,
+    /// If prereleases should be considered, regardless of the
+    /// release channel that the tool is following.
+    pub include_prereleases: bool,
+    /// If set, releases published before this date are not considered.
+    pub published_after: Option<DateTime<Utc>>,
+}
+
+impl ReleaseFilter {
+    /**
+        Creates a new release filter with the default options.
+    */
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+        Allows draft releases to be considered.
+    */
+    #[must_use]
+    pub fn with_drafts(mut self) -> Self {
+        self.include_drafts = true;
+        self
+    }
+
+    /**
+        Allows prereleases to be considered.
+    */
+    #[must_use]
+    pub fn with_prereleases(mut self) -> Self {
+        self.include_prereleases = true;
+        self
+    }
+
+    /**
+        Skips releases published before the given date.
+    */
+    #[must_use]
+    pub fn published_after(mut self, date: DateTime<Utc>) -> Self {
+        self.published_after = Some(date);
+        self
+    }
+
+    /**
+        Checks if these are the default options, meaning that the latest
+        release as returned by the GitHub API can be used directly.
+    */
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /**
+        Checks if the given release is allowed by this filter.
+
+        Note that this does not check if the release is a prerelease, since
+        that also depends on the channel that a tool is following.
+    */
+    #[must_use]
+    pub fn allows(&self, release: &GithubRelease) -> bool {
+        if release.draft && !self.include_drafts {
+            return false;
+        }
+        match (self.published_after, release.published_at) {
+            (None, _) => true,
+            (Some(after), Some(published_at)) => published_at >= after,
+            // NOTE: Drafts are never published, and have no date to check
+            (Some(_), None) => release.draft,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(draft: bool, published_at: Option<&str>) -> GithubRelease {
+        GithubRelease {
+            id: 0,
+            tag_name: String::from("v1.0.0"),
+            name: None,
+            prerelease: false,
+            draft,
+            changelog: None,
+            published_at: published_at.map(|s| s.parse().unwrap()),
+            assets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn default_skips_drafts() {
+        let filter = ReleaseFilter::new();
+        assert!(filter.is_default());
+        assert!(filter.allows(&release(false, Some("2024-05-01T00:00:00Z"))));
+        assert!(!filter.allows(&release(true, None)));
+        assert!(filter.with_drafts().allows(&release(true, None)));
+    }
+
+    #[test]
+    fn published_after() {
+        let filter = ReleaseFilter::new().published_after("2024-05-01T00:00:00Z".parse().unwrap());
+        assert!(!filter.is_default());
+        assert!(filter.allows(&release(false, Some("2024-05-01T00:00:00Z"))));
+        assert!(filter.allows(&release(false, Some("2024-06-01T00:00:00Z"))));
+        assert!(!filter.allows(&release(false, Some("2024-04-30T23:59:59Z"))));
+        assert!(!filter.allows(&release(false, None)));
+    }
+}
@@ -0,0 +1,309 @@
+use std::{collections::HashMap, fmt::Write};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::debug;
+use url::Url;
+
+use crate::tool::ToolSpec;
+
+use super::{
+    models::{GithubAsset, GithubRelease},
+    result::{GithubError, GithubResult},
+};
+
+const RELEASE_FIELDS: &str = "
+fragment ReleaseFields on Release {
+  databaseId
+  tagName
+  name
+  isPrerelease
+  isDraft
+  description
+  publishedAt
+  releaseAssets(first: 100) {
+    totalCount
+    nodes { databaseId name size contentType downloadUrl }
+  }
+}";
+
+#[derive(Debug, Deserialize)]
+struct GraphqlResponse {
+    #[serde(default)]
+    data: Option<HashMap<String, Option<GraphqlRepository>>>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlRepository {
+    prefixed: Option<GraphqlRelease>,
+    exact: Option<GraphqlRelease>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlRelease {
+    #[serde(default)]
+    database_id: u64,
+    tag_name: String,
+    name: Option<String>,
+    is_prerelease: bool,
+    is_draft: bool,
+    description: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+    release_assets: GraphqlAssets,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlAssets {
+    total_count: usize,
+    nodes: Vec<GraphqlAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlAsset {
+    database_id: u64,
+    name: String,
+    size: u64,
+    content_type: Option<String>,
+    download_url: Option<Url>,
+}
+
+/*
+    Gets the URL of the GraphQL API, given the URL of the REST API.
+
+    GitHub Enterprise Servers serve the REST API at `/api/v3`, and the
+    GraphQL API at `/api/graphql` - everything else uses `/graphql`.
+*/
+pub(super) fn graphql_url(base_url: &str) -> String {
+    match base_url.strip_suffix("/api/v3") {
+        Some(host_url) => format!("{host_url}/api/graphql"),
+        None => format!("{base_url}/graphql"),
+    }
+}
+
+/*
+    Builds the body of a GraphQL request that fetches releases for all
+    of the given tool specifications at once, using one alias per tool.
+
+    Releases are looked up by tag the same way as when using the REST API,
+    with and without a `v` prefix, unless the specification has an exact tag.
+*/
+pub(super) fn releases_query_body(specs: &[ToolSpec]) -> Vec<u8> {
+    let mut query = String::from("query {");
+    for (index, spec) in specs.iter().enumerate() {
+        let (owner, name) = (json!(spec.author()), json!(spec.name()));
+        let _ = write!(
+            query,
+            "\n  t{index}: repository(owner: {owner}, name: {name}) {{"
+        );
+        if let Some(tag) = spec.tag() {
+            let _ = write!(query, " exact: {}", release_field(tag));
+        } else {
+            let version = spec.version().to_string();
+            let _ = write!(
+                query,
+                " prefixed: {}",
+                release_field(&format!("v{version}"))
+            );
+            let _ = write!(query, " exact: {}", release_field(&version));
+        }
+        query.push_str(" }");
+    }
+    query.push_str("\n}");
+    query.push_str(RELEASE_FIELDS);
+
+    json!({ "query": query }).to_string().into_bytes()
+}
+
+fn release_field(tag: &str) -> String {
+    format!("release(tagName: {}) {{ ...ReleaseFields }}", json!(tag))
+}
+
+/*
+    Parses the response to a query built using `releases_query_body`,
+    returning the release found for each of the given specifications.
+
+    Releases that were not found, and releases with more assets than
+    could be fetched in a single query, are returned as `None`.
+*/
+pub(super) fn parse_releases_response(
+    body: &[u8],
+    specs: &[ToolSpec],
+    base_url: &str,
+) -> GithubResult<Vec<Option<GithubRelease>>> {
+    let response: GraphqlResponse = serde_json::from_slice(body)?;
+
+    // NOTE: Repositories that do not exist are returned as errors, but with
+    // partial data for everything else - only missing data is a real error
+    let Some(mut data) = response.data else {
+        let messages = response
+            .errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>();
+        return Err(GithubError::Other(format!(
+            "GraphQL query failed - {}",
+            messages.join(", ")
+        )));
+    };
+    for error in &response.errors {
+        debug!(message = %error.message, "GraphQL query returned an error");
+    }
+
+    Ok(specs
+        .iter()
+        .enumerate()
+        .map(|(index, spec)| {
+            let repository = data.remove(&format!("t{index}")).flatten()?;
+            let release = repository.prefixed.or(repository.exact)?;
+            into_github_release(release, spec, base_url)
+        })
+        .collect())
+}
+
+fn into_github_release(
+    release: GraphqlRelease,
+    spec: &ToolSpec,
+    base_url: &str,
+) -> Option<GithubRelease> {
+    // NOTE: Only the first page of assets is fetched, so releases with
+    // more assets than that must be fetched using the REST API instead
+    let assets = release.release_assets;
+    if assets.total_count > assets.nodes.len() {
+        return None;
+    }
+
+    let assets = assets
+        .nodes
+        .into_iter()
+        .map(|asset| {
+            // NOTE: Assets are downloaded using the REST API,
+            // the same way as for releases fetched from it
+            let url = Url::parse(&format!(
+                "{base_url}/repos/{owner}/{repo}/releases/assets/{id}",
+                owner = spec.author(),
+                repo = spec.name(),
+                id = asset.database_id,
+            ))
+            .ok()?;
+            Some(GithubAsset {
+                id: asset.database_id,
+                url,
+                browser_download_url: asset.download_url,
+                name: asset.name,
+                size: asset.size,
+                content_type: asset.content_type,
+                digest: None,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(GithubRelease {
+        id: release.database_id,
+        tag_name: release.tag_name,
+        name: release.name,
+        prerelease: release.is_prerelease,
+        draft: release.is_draft,
+        changelog: release.description,
+        published_at: release.published_at,
+        assets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_URL: &str = "https://api.github.com";
+
+    fn specs() -> Vec<ToolSpec> {
+        vec![
+            "rojo-rbx/rojo@7.4.4".parse().unwrap(),
+            "missing/tool@1.0.0".parse().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn graphql_urls() {
+        assert_eq!(graphql_url(BASE_URL), "https://api.github.com/graphql");
+        assert_eq!(
+            graphql_url("https://github.mycorp.com/api/v3"),
+            "https://github.mycorp.com/api/graphql"
+        );
+    }
+
+    #[test]
+    fn query_aliases_every_tool() {
+        let body: serde_json::Value =
+            serde_json::from_slice(&releases_query_body(&specs())).unwrap();
+        let query = body["query"].as_str().unwrap();
+        assert!(query.contains(r#"t0: repository(owner: "rojo-rbx", name: "rojo")"#));
+        assert!(query.contains(r#"prefixed: release(tagName: "v7.4.4")"#));
+        assert!(query.contains(r#"exact: release(tagName: "7.4.4")"#));
+        assert!(query.contains(r#"t1: repository(owner: "missing", name: "tool")"#));
+        assert!(query.contains("fragment ReleaseFields on Release"));
+    }
+
+    #[test]
+    fn parse_partial_response() {
+        let body = r#"{
+            "data": {
+                "t0": {
+                    "prefixed": {
+                        "databaseId": 1,
+                        "tagName": "v7.4.4",
+                        "name": "Rojo 7.4.4",
+                        "isPrerelease": false,
+                        "isDraft": false,
+                        "description": "Changelog",
+                        "publishedAt": "2024-08-01T12:00:00Z",
+                        "releaseAssets": {
+                            "totalCount": 1,
+                            "nodes": [{
+                                "databaseId": 2,
+                                "name": "rojo-7.4.4-linux-x86_64.zip",
+                                "size": 1234,
+                                "contentType": "application/zip",
+                                "downloadUrl": "https://github.com/rojo-rbx/rojo/releases/download/v7.4.4/rojo-7.4.4-linux-x86_64.zip"
+                            }]
+                        }
+                    },
+                    "exact": null
+                },
+                "t1": null
+            },
+            "errors": [{ "message": "Could not resolve to a Repository" }]
+        }"#;
+
+        let releases = parse_releases_response(body.as_bytes(), &specs(), BASE_URL).unwrap();
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[1], None);
+
+        let release = releases[0].as_ref().unwrap();
+        assert_eq!(release.tag_name, "v7.4.4");
+        assert_eq!(release.changelog.as_deref(), Some("Changelog"));
+        assert_eq!(release.assets.len(), 1);
+        assert_eq!(release.assets[0].size, 1234);
+        assert_eq!(
+            release.assets[0].url.as_str(),
+            "https://api.github.com/repos/rojo-rbx/rojo/releases/assets/2"
+        );
+    }
+
+    #[test]
+    fn parse_failed_response() {
+        let body = r#"{ "errors": [{ "message": "Bad credentials" }] }"#;
+        let err = parse_releases_response(body.as_bytes(), &specs(), BASE_URL).unwrap_err();
+        assert!(err.to_string().contains("Bad credentials"));
+    }
+}
@@ -1,89 +1,179 @@
-use reqwest_middleware::ClientWithMiddleware;
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+use chrono::{DateTime, Utc};
 use semver::Version;
 use serde::de::DeserializeOwned;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
+use url::Url;
 
-use reqwest::{
-    header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, AUTHORIZATION},
-    StatusCode,
+use crate::{
+    executor::sleep,
+    storage::HttpCache,
+    tool::{ReleaseChannel, ToolId, ToolSpec},
 };
 
-use crate::tool::{ToolId, ToolSpec};
+use super::{
+    artifact::link_published_digests,
+    http::{error_status, user_agent, HttpClient, HttpRequest, HttpResponse},
+    is_signature_asset_name, Artifact, ArtifactProvider, ProviderAuthStatus, ProviderRateLimit,
+    ProviderStatus, Release, ToolProvenance,
+};
 
-use super::{client::create_client, Artifact, ArtifactProvider, Release};
+#[cfg(feature = "reqwest-client")]
+use super::http::default_http_client;
 
-const BASE_URL: &str = "https://api.github.com";
+const DEFAULT_BASE_URL: &str = "https://api.github.com";
+const JSON_ACCEPT: &str = "application/vnd.github.v3+json";
+const RELEASES_PER_PAGE: usize = 100;
+const MAX_RELEASE_PAGES: usize = 10;
+const RELEASES_PER_QUERY: usize = 50;
 
+mod filter;
+mod graphql;
 pub mod models;
+mod rate_limit;
 mod result;
 
-use self::models::GithubRelease;
-
+pub use self::filter::ReleaseFilter;
+pub use self::models::{
+    GithubAsset, GithubRateLimit, GithubRateLimitResource, GithubRelease, GithubRepository,
+};
 pub use self::result::{GithubError, GithubResult};
 
+use self::rate_limit::{jitter, rate_limit_action, RateLimitAction};
+
+/**
+    A provider for tools hosted on GitHub.
+
+    Uses github.com by default, but can use a GitHub Enterprise Server
+    instead, by using [`GithubProvider::with_host`] - this is done
+    automatically for tools with a custom host in their tool id.
+*/
 #[derive(Debug, Clone)]
 pub struct GithubProvider {
-    client: ClientWithMiddleware,
+    client: Arc<dyn HttpClient>,
+    base_url: String,
+    download_url: Option<String>,
+    headers: Vec<(String, String)>,
     has_auth: bool,
+    http_cache: Option<HttpCache>,
+    release_filter: ReleaseFilter,
 }
 
 impl GithubProvider {
-    fn new_inner(pat: Option<String>) -> GithubResult<Self> {
+    fn new_inner(client: Arc<dyn HttpClient>, pat: Option<String>) -> GithubResult<Self> {
         let has_auth = pat.is_some();
-        let headers = {
-            let mut headers = HeaderMap::new();
-            headers.insert(
-                HeaderName::from_static("x-github-api-version"),
-                HeaderValue::from_static("2022-11-28"),
-            );
-            if let Some(pat) = pat {
-                let token = format!("Bearer {pat}");
-                headers.insert(AUTHORIZATION, HeaderValue::from_str(&token)?);
+        let mut headers = vec![
+            (String::from("user-agent"), user_agent()),
+            (
+                String::from("x-github-api-version"),
+                String::from("2022-11-28"),
+            ),
+        ];
+        if let Some(pat) = pat {
+            // NOTE: Tokens are sent as headers, which must be visible ASCII
+            if pat.is_empty() || !pat.chars().all(|c| c.is_ascii_graphic()) {
+                return Err(GithubError::InvalidAccessToken);
             }
-            headers
-        };
+            headers.push((String::from("authorization"), format!("Bearer {pat}")));
+        }
 
-        let client = create_client(headers)?;
+        Ok(Self {
+            client,
+            base_url: String::from(DEFAULT_BASE_URL),
+            download_url: None,
+            headers,
+            has_auth,
+            http_cache: None,
+            release_filter: ReleaseFilter::default(),
+        })
+    }
 
-        Ok(Self { client, has_auth })
+    /*
+        Sends the given request, using the given HTTP cache if any, and
+        waits and retries when rate limited, if the limit resets soon.
+    */
+    async fn send(
+        &self,
+        request: HttpRequest,
+        cache: Option<&HttpCache>,
+    ) -> GithubResult<HttpResponse> {
+        let mut attempt = 0;
+        loop {
+            let response = match cache {
+                Some(cache) => cache.send(self.client.as_ref(), request.clone()).await?,
+                None => self.client.send(request.clone()).await?,
+            };
+            match rate_limit_action(&response, attempt, Utc::now()) {
+                RateLimitAction::Continue => return Ok(response),
+                RateLimitAction::Retry(wait) => {
+                    let wait = wait + jitter();
+                    warn!(url = %request.url, ?wait, "rate limited by GitHub, retrying");
+                    sleep(wait).await;
+                    attempt += 1;
+                }
+                RateLimitAction::Fail(reset_at) => {
+                    return Err(GithubError::RateLimited { reset_at });
+                }
+            }
+        }
+    }
+
+    async fn get(&self, url: &str, accept: &str) -> GithubResult<Vec<u8>> {
+        let mut request = HttpRequest::get(url).header("accept", accept);
+        request.headers.extend(self.headers.iter().cloned());
+        let response = self.send(request, None).await?.error_for_status()?;
+        Ok(response.body)
     }
 
     async fn get_json<T: DeserializeOwned>(&self, url: &str) -> GithubResult<T> {
-        let response = self
-            .client
-            .get(url)
-            .header(ACCEPT, "application/vnd.github.v3+json")
-            .send()
-            .await?
-            .error_for_status()?;
-        Ok(response.json().await?)
+        let body = self.get(url, JSON_ACCEPT).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /*
+        Same as `get_json`, but sends a conditional request if there is an HTTP
+        cache - only used for release metadata, which rarely changes, but is
+        fetched very often and would otherwise use up the rate limit quickly.
+    */
+    async fn get_json_cached<T: DeserializeOwned>(&self, url: &str) -> GithubResult<T> {
+        let Some(cache) = &self.http_cache else {
+            return self.get_json(url).await;
+        };
+        let mut request = HttpRequest::get(url).header("accept", JSON_ACCEPT);
+        request.headers.extend(self.headers.iter().cloned());
+        let response = self.send(request, Some(cache)).await?.error_for_status()?;
+        Ok(serde_json::from_slice(&response.body)?)
     }
 
     async fn get_bytes(&self, url: &str) -> GithubResult<Vec<u8>> {
-        let response = self
-            .client
-            .get(url)
-            .header(ACCEPT, HeaderValue::from_static("application/octet-stream"))
-            .send()
-            .await?
-            .error_for_status()?;
-        let bytes = response.bytes().await.map(|bytes| bytes.to_vec());
-        Ok(bytes?)
+        self.get(url, "application/octet-stream").await
+    }
+
+    async fn post_graphql(&self, body: Vec<u8>) -> GithubResult<Vec<u8>> {
+        let mut request = HttpRequest::post(graphql::graphql_url(&self.base_url), body)
+            .header("accept", "application/json")
+            .header("content-type", "application/json");
+        request.headers.extend(self.headers.iter().cloned());
+        let response = self.send(request, None).await?.error_for_status()?;
+        Ok(response.body)
     }
 
     /**
-        Creates a new GitHub source instance.
+        Creates a new GitHub source instance, using the default HTTP client.
 
         # Errors
 
         - If the GitHub API client could not be created.
     */
+    #[cfg(feature = "reqwest-client")]
     pub fn new() -> GithubResult<Self> {
-        Self::new_inner(None)
+        Self::new_with_client(default_http_client()?)
     }
 
     /**
-        Creates a new authenticated GitHub source instance with a token.
+        Creates a new authenticated GitHub source instance with a token,
+        using the default HTTP client.
 
         Note that this does not verify the formatting or validity of the token,
         use the `verify_authentication` method for checking with the GitHub API.
@@ -92,9 +182,132 @@ impl GithubProvider {
 
         - If the GitHub API client could not be created.
     */
+    #[cfg(feature = "reqwest-client")]
     pub fn new_authenticated(pat: impl AsRef<str>) -> GithubResult<Self> {
+        Self::new_authenticated_with_client(default_http_client()?, pat)
+    }
+
+    /**
+        Creates a new GitHub source instance, using the given HTTP client.
+
+        # Errors
+
+        - If the GitHub API client could not be created.
+    */
+    pub fn new_with_client(client: Arc<dyn HttpClient>) -> GithubResult<Self> {
+        Self::new_inner(client, None)
+    }
+
+    /**
+        Creates a new authenticated GitHub source instance with a token,
+        using the given HTTP client.
+
+        See [`GithubProvider::new_authenticated`] for more information.
+
+        # Errors
+
+        - If the token can not be sent as a header.
+    */
+    pub fn new_authenticated_with_client(
+        client: Arc<dyn HttpClient>,
+        pat: impl AsRef<str>,
+    ) -> GithubResult<Self> {
         let pat: String = pat.as_ref().trim().to_string();
-        Self::new_inner(Some(pat))
+        Self::new_inner(client, Some(pat))
+    }
+
+    /**
+        Uses the given HTTP client for all requests made by this provider.
+    */
+    #[must_use]
+    pub(crate) fn with_client(mut self, client: Arc<dyn HttpClient>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /**
+        Uses the GitHub Enterprise Server at the given host,
+        such as `github.mycorp.com`, instead of github.com.
+
+        # Errors
+
+        - If the given host is not a valid host name.
+    */
+    pub fn with_host(mut self, host: &str) -> GithubResult<Self> {
+        let url = Url::parse(&format!("https://{host}/api/v3"))
+            .ok()
+            .filter(|url| {
+                url.port().is_none() && url.host_str().is_some_and(|h| h.eq_ignore_ascii_case(host))
+            })
+            .ok_or_else(|| GithubError::InvalidHost(host.to_string()))?;
+        self.base_url = url.as_str().trim_end_matches('/').to_string();
+        Ok(self)
+    }
+
+    /**
+        Uses the GitHub API at the given URL, such as an internal API
+        gateway, or a local mock server for tests, instead of the default.
+
+        # Errors
+
+        - If the given URL is not a valid `http` or `https` URL.
+    */
+    pub fn with_api_url(mut self, url: &str) -> GithubResult<Self> {
+        self.base_url = parse_base_url(url)?;
+        Ok(self)
+    }
+
+    /**
+        Downloads release assets from the given URL instead of from the
+        GitHub API, using the same paths as the GitHub API does, such as
+        `<url>/repos/<owner>/<repo>/releases/assets/<id>`.
+
+        Note that authentication is sent to this URL, same as to the API.
+
+        # Errors
+
+        - If the given URL is not a valid `http` or `https` URL.
+    */
+    pub fn with_download_url(mut self, url: &str) -> GithubResult<Self> {
+        self.download_url = Some(parse_base_url(url)?);
+        Ok(self)
+    }
+
+    /**
+        Uses the given HTTP cache for release metadata, sending conditional
+        requests and using cached responses for releases that have not changed.
+    */
+    #[must_use]
+    pub fn with_http_cache(mut self, cache: HttpCache) -> Self {
+        self.http_cache = Some(cache);
+        self
+    }
+
+    /**
+        Uses the given filter to decide which releases are considered when
+        resolving the latest release for a tool - see [`ReleaseFilter`].
+    */
+    #[must_use]
+    pub fn with_release_filter(mut self, filter: ReleaseFilter) -> Self {
+        self.release_filter = filter;
+        self
+    }
+
+    /**
+        Gets the filter used to decide which releases are considered
+        when resolving the latest release for a tool.
+    */
+    #[must_use]
+    pub fn release_filter(&self) -> &ReleaseFilter {
+        &self.release_filter
+    }
+
+    /**
+        Gets the URL of the GitHub API used by this provider.
+    */
+    #[must_use]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
     }
 
     /**
@@ -113,7 +326,7 @@ impl GithubProvider {
             return Ok(false);
         }
 
-        let url = format!("{BASE_URL}/rate_limit");
+        let url = format!("{}/rate_limit", self.base_url);
         let res = self.get_json::<serde_json::Value>(&url).await;
 
         match res {
@@ -124,72 +337,503 @@ impl GithubProvider {
     }
 
     /**
-        Fetches the latest release for a given tool.
+        Probes the GitHub API to check its reachability, if the current
+        authentication token is valid, and the remaining rate limit.
+
+        Checking the rate limit does not count against the rate limit itself.
+    */
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_status(&self) -> ProviderStatus {
+        let url = format!("{}/rate_limit", self.base_url);
+        let start = Instant::now();
+        let res = self.get_json::<GithubRateLimit>(&url).await;
+        let elapsed = start.elapsed();
+
+        let mut provider_status = ProviderStatus {
+            provider: ArtifactProvider::GitHub,
+            latency: None,
+            auth: None,
+            rate_limit: None,
+            error: None,
+        };
+        match res {
+            Ok(limits) => {
+                provider_status.latency = Some(elapsed);
+                provider_status.auth = Some(if self.has_auth {
+                    ProviderAuthStatus::Valid
+                } else {
+                    ProviderAuthStatus::Anonymous
+                });
+                provider_status.rate_limit = Some(ProviderRateLimit {
+                    limit: limits.rate.limit,
+                    remaining: limits.rate.remaining,
+                    resets_at: DateTime::from_timestamp(limits.rate.reset, 0),
+                });
+            }
+            Err(e) if self.has_auth && is_unauthenticated(&e) => {
+                provider_status.latency = Some(elapsed);
+                provider_status.auth = Some(ProviderAuthStatus::Invalid);
+                provider_status.error = Some(e.to_string());
+            }
+            Err(e) => {
+                // NOTE: Any response at all means that GitHub is reachable,
+                // even if it is an error such as a server-side outage
+                if status(&e).is_some() {
+                    provider_status.latency = Some(elapsed);
+                }
+                provider_status.error = Some(e.to_string());
+            }
+        }
+        provider_status
+    }
+
+    /**
+        Fetches the latest release for a given tool, as returned by the GitHub API.
+
+        This is useful for implementing custom artifact selection, see
+        [`GithubProvider::get_latest_release`] for the release used by Rokit.
+
+        # Errors
+
+        - If the tool has no latest release.
+        - If the request to the GitHub API failed.
     */
     #[instrument(skip(self), fields(%tool_id), level = "debug")]
-    pub async fn get_latest_release(&self, tool_id: &ToolId) -> GithubResult<Release> {
+    pub async fn get_latest_github_release(&self, tool_id: &ToolId) -> GithubResult<GithubRelease> {
         debug!(id = %tool_id, "fetching latest release for tool");
 
         let url = format!(
-            "{BASE_URL}/repos/{owner}/{repo}/releases/latest",
+            "{}/repos/{owner}/{repo}/releases/latest",
+            self.base_url,
             owner = tool_id.author(),
             repo = tool_id.name(),
         );
 
-        let release: GithubRelease = match self.get_json(&url).await {
-            Err(e) if is_404(&e) => {
-                return Err(GithubError::LatestReleaseNotFound(tool_id.clone().into()));
-            }
-            Err(e) => return Err(e),
-            Ok(r) => r,
-        };
+        match self.get_json_cached(&url).await {
+            Err(e) if is_404(&e) => Err(GithubError::LatestReleaseNotFound(tool_id.clone().into())),
+            res => res,
+        }
+    }
 
-        let version = release
-            .tag_name
-            .trim_start_matches('v')
-            .parse::<Version>()
-            .map_err(|e| GithubError::Other(e.to_string()))?;
+    /**
+        Fetches the most recent releases for a given tool, as returned by the
+        GitHub API - this includes drafts, prereleases, and releases without
+        a valid version, which are all skipped by [`GithubProvider::get_recent_releases`].
 
-        let tool_spec: ToolSpec = (tool_id.clone(), version).into();
-        Ok(Release {
-            changelog: release.changelog.clone(),
-            artifacts: artifacts_from_release(&release, &tool_spec),
-        })
+        Only the first page of releases is fetched, which contains the 100 most
+        recently created releases - see [`GithubProvider::get_github_releases_until`]
+        to fetch older releases.
+
+        # Errors
+
+        - If the tool has no releases.
+        - If the request to the GitHub API failed.
+    */
+    pub async fn get_recent_github_releases(
+        &self,
+        tool_id: &ToolId,
+    ) -> GithubResult<Vec<GithubRelease>> {
+        self.get_github_releases_until(tool_id, |_| true).await
     }
 
     /**
-        Fetches a specific release for a given tool.
+        Fetches releases for a given tool, as returned by the GitHub API, one
+        page at a time, newest first, until a page contains a release that
+        `is_found` returns `true` for, or there are no more releases.
+
+        To bound the number of requests, at most 10 pages of 100 releases
+        each are fetched - any releases older than that are not returned.
+
+        # Errors
+
+        - If the tool has no releases.
+        - If any request to the GitHub API failed.
+    */
+    #[instrument(skip(self, is_found), fields(%tool_id), level = "debug")]
+    pub async fn get_github_releases_until(
+        &self,
+        tool_id: &ToolId,
+        is_found: impl Fn(&GithubRelease) -> bool,
+    ) -> GithubResult<Vec<GithubRelease>> {
+        let mut releases = Vec::new();
+        for page in 1..=MAX_RELEASE_PAGES {
+            debug!(id = %tool_id, page, "fetching releases for tool");
+
+            let url = format!(
+                "{}/repos/{owner}/{repo}/releases?per_page={RELEASES_PER_PAGE}&page={page}",
+                self.base_url,
+                owner = tool_id.author(),
+                repo = tool_id.name(),
+            );
+
+            let page_releases: Vec<GithubRelease> = match self.get_json_cached(&url).await {
+                Err(e) if is_404(&e) => {
+                    return Err(GithubError::LatestReleaseNotFound(tool_id.clone().into()))
+                }
+                res => res?,
+            };
+
+            let is_last_page = page_releases.len() < RELEASES_PER_PAGE;
+            let is_done = is_last_page || page_releases.iter().any(&is_found);
+            releases.extend(page_releases);
+            if is_done {
+                return Ok(releases);
+            }
+        }
+
+        debug!(
+            id = %tool_id,
+            count = releases.len(),
+            "stopped fetching releases after reaching the page limit"
+        );
+        Ok(releases)
+    }
+
+    /**
+        Fetches a specific release for a given tool, as returned by the GitHub API.
+
+        Releases are looked up by their tag, which may or may not be prefixed with `v`,
+        unless the specification has an exact tag - see [`ToolSpec::with_tag`].
+
+        # Errors
+
+        - If the release was not found.
+        - If the request to the GitHub API failed.
     */
     #[instrument(skip(self), fields(%tool_spec), level = "debug")]
-    pub async fn get_specific_release(&self, tool_spec: &ToolSpec) -> GithubResult<Release> {
+    pub async fn get_specific_github_release(
+        &self,
+        tool_spec: &ToolSpec,
+    ) -> GithubResult<GithubRelease> {
         debug!(spec = %tool_spec, "fetching release for tool");
 
+        // NOTE: Exact tags are resolved as-is, and never with a `v` prefix
+        if let Some(tag) = tool_spec.tag() {
+            let url = format!(
+                "{}/repos/{owner}/{repo}/releases/tags/{tag}",
+                self.base_url,
+                owner = tool_spec.author(),
+                repo = tool_spec.name(),
+            );
+            return match self.get_json_cached(&url).await {
+                Err(e) if is_404(&e) => Err(GithubError::ReleaseNotFound(tool_spec.clone().into())),
+                res => res,
+            };
+        }
+
         let url_with_prefix = format!(
-            "{BASE_URL}/repos/{owner}/{repo}/releases/tags/v{tag}",
+            "{}/repos/{owner}/{repo}/releases/tags/v{tag}",
+            self.base_url,
             owner = tool_spec.author(),
             repo = tool_spec.name(),
             tag = tool_spec.version(),
         );
         let url_without_prefix = format!(
-            "{BASE_URL}/repos/{owner}/{repo}/releases/tags/{tag}",
+            "{}/repos/{owner}/{repo}/releases/tags/{tag}",
+            self.base_url,
             owner = tool_spec.author(),
             repo = tool_spec.name(),
             tag = tool_spec.version(),
         );
 
-        let release: GithubRelease = match self.get_json(&url_with_prefix).await {
-            Err(e) if is_404(&e) => match self.get_json(&url_without_prefix).await {
-                Err(e) if is_404(&e) => {
-                    return Err(GithubError::ReleaseNotFound(tool_spec.clone().into()));
-                }
-                Err(e) => return Err(e),
-                Ok(r) => r,
+        match self.get_json_cached(&url_with_prefix).await {
+            Err(e) if is_404(&e) => match self.get_json_cached(&url_without_prefix).await {
+                Err(e) if is_404(&e) => Err(GithubError::ReleaseNotFound(tool_spec.clone().into())),
+                res => res,
             },
+            res => res,
+        }
+    }
+
+    /**
+        Fetches specific releases for many tools at once, using the GitHub
+        GraphQL API, which takes a single request for up to 50 tools - instead
+        of at least one request per tool, as when using the REST API.
+
+        The GraphQL API requires authentication, so nothing is fetched if this
+        provider is not authenticated. Releases that were not found, or that
+        could not be fully fetched, are not returned - those should be fetched
+        using [`GithubProvider::get_specific_release`] instead.
+
+        # Errors
+
+        - If any request to the GitHub GraphQL API failed.
+    */
+    #[instrument(skip(self, tool_specs), fields(count = tool_specs.len()), level = "debug")]
+    pub async fn get_specific_releases(
+        &self,
+        tool_specs: &[ToolSpec],
+    ) -> GithubResult<HashMap<ToolSpec, Release>> {
+        let mut releases = HashMap::new();
+        if !self.has_auth {
+            return Ok(releases);
+        }
+
+        for chunk in tool_specs.chunks(RELEASES_PER_QUERY) {
+            debug!(
+                count = chunk.len(),
+                "fetching releases for tools using GraphQL"
+            );
+
+            let body = self
+                .post_graphql(graphql::releases_query_body(chunk))
+                .await?;
+            let found = graphql::parse_releases_response(&body, chunk, &self.base_url)?;
+            for (tool_spec, release) in chunk.iter().zip(found) {
+                let Some(release) = release else {
+                    continue;
+                };
+                let release = Release {
+                    version: tool_spec.version().clone(),
+                    changelog: release.changelog.clone(),
+                    artifacts: artifacts_from_release(&release, tool_spec),
+                };
+                releases.insert(tool_spec.clone(), release);
+            }
+        }
+
+        Ok(releases)
+    }
+
+    /**
+        Fetches the latest release for a given tool.
+
+        If a non-default [`ReleaseFilter`] is used, the latest release
+        is the newest version among recent releases that it allows.
+
+        # Errors
+
+        - If no latest release was found.
+        - If the version of the latest release could not be parsed.
+        - If the request to the GitHub API failed.
+    */
+    pub async fn get_latest_release(&self, tool_id: &ToolId) -> GithubResult<Release> {
+        if !self.release_filter.is_default() {
+            return self
+                .get_recent_releases(tool_id)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| GithubError::LatestReleaseNotFound(tool_id.clone().into()));
+        }
+
+        let release = self.get_latest_github_release(tool_id).await?;
+        let version = parse_release_version(&release)?;
+
+        let tool_spec: ToolSpec = (tool_id.clone(), version).into();
+        Ok(Release {
+            version: tool_spec.version().clone(),
+            changelog: release.changelog.clone(),
+            artifacts: artifacts_from_release(&release, &tool_spec),
+        })
+    }
+
+    /**
+        Fetches provenance information for a given tool, such as the
+        age and star count of its repository, and its latest release.
+
+        A tool without any releases is not an error, and will
+        simply be missing information about its latest release.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn get_provenance(&self, tool_id: &ToolId) -> GithubResult<ToolProvenance> {
+        debug!(id = %tool_id, "fetching provenance for tool");
+
+        let repo_url = format!(
+            "{}/repos/{owner}/{repo}",
+            self.base_url,
+            owner = tool_id.author(),
+            repo = tool_id.name(),
+        );
+        let release_url = format!("{repo_url}/releases/latest");
+
+        let (repo, release) = futures::join!(
+            self.get_json::<GithubRepository>(&repo_url),
+            self.get_json::<GithubRelease>(&release_url),
+        );
+        let repo = repo?;
+        let release = match release {
+            Err(e) if is_404(&e) => None,
             Err(e) => return Err(e),
-            Ok(r) => r,
+            Ok(r) => Some(r),
+        };
+
+        Ok(ToolProvenance {
+            created_at: Some(repo.created_at),
+            stars: Some(repo.stargazers_count),
+            archived: repo.archived,
+            fork: repo.fork,
+            latest_release_at: release.as_ref().and_then(|r| r.published_at),
+            signed: release.as_ref().map(|r| {
+                r.assets
+                    .iter()
+                    .any(|asset| is_signature_asset_name(&asset.name))
+            }),
+        })
+    }
+
+    /**
+        Gets the canonical id for a given tool, using the casing of the
+        owner and name of its repository, such as `rojo-rbx/rojo` for
+        a tool that was given as `Rojo-RBX/Rojo`.
+
+        Tools whose repository has moved keep the given id - see
+        [`GithubProvider::get_moved_id`] for detecting those instead.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn get_canonical_id(&self, tool_id: &ToolId) -> GithubResult<ToolId> {
+        let repo = self.get_repository(tool_id).await?;
+        Ok(tool_id
+            .with_full_name(&repo.full_name)
+            .filter(|canonical_id| canonical_id == tool_id)
+            .unwrap_or_else(|| tool_id.clone()))
+    }
+
+    /**
+        Checks if the repository for a given tool has been renamed or
+        transferred to a new owner, returning the new id of the tool if so.
+
+        GitHub redirects requests for a moved repository to its new location,
+        so tools that have moved keep working, but only until another
+        repository is created using the old name.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn get_moved_id(&self, tool_id: &ToolId) -> GithubResult<Option<ToolId>> {
+        debug!(id = %tool_id, "checking if tool has moved");
+
+        let repo = self.get_repository(tool_id).await?;
+
+        let moved_id = tool_id
+            .with_full_name(&repo.full_name)
+            .filter(|moved_id| moved_id != tool_id);
+        if let Some(moved_id) = &moved_id {
+            debug!(id = %tool_id, moved = %moved_id, "tool has moved");
+        }
+        Ok(moved_id)
+    }
+
+    async fn get_repository(&self, tool_id: &ToolId) -> GithubResult<GithubRepository> {
+        let repo_url = format!(
+            "{}/repos/{owner}/{repo}",
+            self.base_url,
+            owner = tool_id.author(),
+            repo = tool_id.name(),
+        );
+        self.get_json_cached::<GithubRepository>(&repo_url).await
+    }
+
+    /**
+        Fetches the most recent releases for a given tool, newest first.
+
+        Drafts, prereleases, and releases without a valid version are skipped.
+
+        # Errors
+
+        - If the request to the GitHub API failed.
+    */
+    pub async fn get_recent_releases(&self, tool_id: &ToolId) -> GithubResult<Vec<Release>> {
+        self.get_recent_releases_in_channel(tool_id, &ReleaseChannel::Stable)
+            .await
+    }
+
+    /**
+        Fetches the most recent releases in the given channel for a given tool, newest first.
+
+        Drafts, releases outside of the channel, and releases without a valid version are
+        skipped - for the stable channel, releases marked as prereleases are also skipped.
+        The [`ReleaseFilter`] of this provider may allow drafts and prereleases, and
+        may additionally skip releases that were published before a given date.
+
+        # Errors
+
+        - If the request to the GitHub API failed.
+    */
+    pub async fn get_recent_releases_in_channel(
+        &self,
+        tool_id: &ToolId,
+        channel: &ReleaseChannel,
+    ) -> GithubResult<Vec<Release>> {
+        self.get_releases_in_channel_until(tool_id, channel, |_| true)
+            .await
+    }
+
+    /**
+        Fetches releases in the given channel for a given tool, newest first,
+        until a release with a version that `is_found` returns `true` for was
+        found, or there are no more releases.
+
+        Releases are skipped the same way as in [`GithubProvider::get_recent_releases_in_channel`],
+        and fetched page by page - see [`GithubProvider::get_github_releases_until`].
+
+        # Errors
+
+        - If the request for any page of releases to the GitHub API failed.
+    */
+    pub async fn get_releases_in_channel_until(
+        &self,
+        tool_id: &ToolId,
+        channel: &ReleaseChannel,
+        is_found: impl Fn(&Version) -> bool,
+    ) -> GithubResult<Vec<Release>> {
+        let releases = self
+            .get_github_releases_until(tool_id, |release| {
+                self.version_in_channel(channel, release)
+                    .is_some_and(|version| is_found(&version))
+            })
+            .await?;
+
+        let mut releases = releases
+            .into_iter()
+            .filter_map(|release| {
+                let version = self.version_in_channel(channel, &release)?;
+                let tool_spec: ToolSpec = (tool_id.clone(), version).into();
+                Some(Release {
+                    version: tool_spec.version().clone(),
+                    changelog: release.changelog.clone(),
+                    artifacts: artifacts_from_release(&release, &tool_spec),
+                })
+            })
+            .collect::<Vec<_>>();
+        releases.sort_by(|a, b| b.version.cmp(&a.version));
+
+        Ok(releases)
+    }
+
+    /*
+        Gets the version of the given release, if it is allowed by the
+        release filter of this provider, and is in the given channel.
+    */
+    fn version_in_channel(
+        &self,
+        channel: &ReleaseChannel,
+        release: &GithubRelease,
+    ) -> Option<Version> {
+        let filter = &self.release_filter;
+        let channel = if filter.include_prereleases {
+            &ReleaseChannel::AnyPrerelease
+        } else {
+            channel
         };
+        if !filter.allows(release) || (release.prerelease && channel.is_stable()) {
+            return None;
+        }
+        let version = release.version().ok()?;
+        channel.contains(&version).then_some(version)
+    }
+
+    /**
+        Fetches a specific release for a given tool.
+
+        # Errors
+
+        - If the release was not found.
+        - If the request to the GitHub API failed.
+    */
+    pub async fn get_specific_release(&self, tool_spec: &ToolSpec) -> GithubResult<Release> {
+        let release = self.get_specific_github_release(tool_spec).await?;
 
         Ok(Release {
+            version: tool_spec.version().clone(),
             changelog: release.changelog.clone(),
             artifacts: artifacts_from_release(&release, tool_spec),
         })
@@ -210,38 +854,111 @@ impl GithubProvider {
         let name = artifact.name.as_ref().expect("GitHub artifacts have names");
         debug!(id, name, "downloading artifact contents");
 
+        self.download_asset_contents(artifact.tool_spec.id(), id)
+            .await
+    }
+
+    /**
+        Downloads the contents of a release asset, given the id of the tool
+        it belongs to and the id of the asset itself, without needing a full
+        artifact - this is mostly useful for proxying and caching downloads.
+
+        # Errors
+
+        - If the asset could not be downloaded.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn download_asset_contents(
+        &self,
+        tool_id: &ToolId,
+        asset_id: &str,
+    ) -> GithubResult<Vec<u8>> {
+        assert_eq!(
+            tool_id.provider(),
+            ArtifactProvider::GitHub,
+            "tool must be from GitHub"
+        );
+
         let url = format!(
-            "{BASE_URL}/repos/{owner}/{repo}/releases/assets/{id}",
-            owner = artifact.tool_spec.author(),
-            repo = artifact.tool_spec.name(),
+            "{}/repos/{owner}/{repo}/releases/assets/{asset_id}",
+            self.download_url.as_deref().unwrap_or(&self.base_url),
+            owner = tool_id.author(),
+            repo = tool_id.name(),
         );
 
         self.get_bytes(&url).await
     }
+
+    /**
+        Downloads a gzipped tarball of the repository for the given tool id,
+        at the given git reference - or the default branch, if not given.
+
+        # Errors
+
+        - If the repository or reference does not exist.
+        - If the archive could not be downloaded.
+    */
+    #[instrument(skip(self), fields(%repo_id), level = "debug")]
+    pub async fn download_repository_archive(
+        &self,
+        repo_id: &ToolId,
+        reference: Option<&str>,
+    ) -> GithubResult<Vec<u8>> {
+        debug!(id = %repo_id, ?reference, "downloading repository archive");
+
+        let url = format!(
+            "{}/repos/{owner}/{repo}/tarball/{reference}",
+            self.base_url,
+            owner = repo_id.author(),
+            repo = repo_id.name(),
+            reference = reference.unwrap_or_default(),
+        );
+
+        self.get_bytes(url.trim_end_matches('/')).await
+    }
 }
 
-fn is_404(err: &GithubError) -> bool {
-    if let GithubError::Reqwest(reqwest_err) = err {
-        if let Some(status) = reqwest_err.status() {
-            return status == StatusCode::NOT_FOUND;
-        }
+fn parse_base_url(url: &str) -> GithubResult<String> {
+    Url::parse(url.trim())
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https") && u.has_host())
+        .map(|u| u.as_str().trim_end_matches('/').to_string())
+        .ok_or_else(|| GithubError::InvalidBaseUrl(url.to_string()))
+}
+
+fn status(err: &GithubError) -> Option<u16> {
+    if let GithubError::Http(http_err) = err {
+        error_status(http_err)
+    } else {
+        None
     }
-    false
+}
+
+fn is_404(err: &GithubError) -> bool {
+    status(err) == Some(404)
 }
 
 fn is_unauthenticated(err: &GithubError) -> bool {
-    if let GithubError::Reqwest(reqwest_err) = err {
-        if let Some(status) = reqwest_err.status() {
-            return matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN);
-        }
-    }
-    false
+    matches!(status(err), Some(401 | 403))
 }
 
-fn artifacts_from_release(release: &GithubRelease, spec: &ToolSpec) -> Vec<Artifact> {
+fn parse_release_version(release: &GithubRelease) -> GithubResult<Version> {
     release
+        .version()
+        .map_err(|e| GithubError::Other(e.to_string()))
+}
+
+fn artifacts_from_release(release: &GithubRelease, spec: &ToolSpec) -> Vec<Artifact> {
+    let mut artifacts = release
         .assets
         .iter()
         .map(|asset| Artifact::from_github_release_asset(asset, spec))
-        .collect::<Vec<_>>()
+        .collect::<Vec<_>>();
+    let names = release
+        .assets
+        .iter()
+        .map(|asset| asset.name.as_str())
+        .collect::<Vec<_>>();
+    link_published_digests(&mut artifacts, &names);
+    artifacts
 }
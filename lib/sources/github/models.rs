@@ -1,18 +1,193 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use semver::Version;
+use serde::{Deserialize, Serialize};
 use url::Url;
 
-#[derive(Debug, Clone, Deserialize)]
+/**
+    A release, as returned by the GitHub API.
+
+    Only contains the fields used by Rokit, or that are generally useful when
+    implementing custom artifact selection - new fields may be added over time.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct GithubRelease {
-    pub assets: Vec<GithubAsset>,
+    /// The unique id of the release.
+    #[serde(default)]
+    pub id: u64,
+    /// The tag of the release, such as `v1.2.3`.
     pub tag_name: String,
+    /// The display name of the release, if any.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// If the release is marked as a prerelease.
     pub prerelease: bool,
+    /// If the release is a draft - only visible with authentication.
+    #[serde(default)]
+    pub draft: bool,
+    /// The body of the release, typically containing a changelog.
     #[serde(rename = "body")]
     pub changelog: Option<String>,
+    /// When the release was published, if it has been published.
+    #[serde(default)]
+    pub published_at: Option<DateTime<Utc>>,
+    /// All assets uploaded to the release.
+    pub assets: Vec<GithubAsset>,
+}
+
+impl GithubRelease {
+    /**
+        Parses the version of this release from its tag, ignoring any `v` prefix.
+
+        # Errors
+
+        - If the tag is not a valid semver version.
+    */
+    pub fn version(&self) -> Result<Version, semver::Error> {
+        self.tag_name.trim_start_matches('v').parse()
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/**
+    A release asset, as returned by the GitHub API.
+
+    Only contains the fields used by Rokit, or that are generally useful when
+    implementing custom artifact selection - new fields may be added over time.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct GithubAsset {
+    /// The unique id of the asset.
     pub id: u64,
+    /// The API URL of the asset, which requires an `Accept`
+    /// header of `application/octet-stream` to download.
     pub url: Url,
+    /// The public download URL of the asset.
+    #[serde(default)]
+    pub browser_download_url: Option<Url>,
+    /// The file name of the asset.
     pub name: String,
+    /// The size of the asset, in bytes.
+    #[serde(default)]
+    pub size: u64,
+    /// The content type of the asset, such as `application/zip`.
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// The digest of the asset, such as `sha256:<hex>`.
+    /// Only present for assets uploaded after GitHub began computing digests.
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+impl GithubAsset {
+    /**
+        Gets the SHA-256 digest of this asset, as a lowercase hexadecimal
+        string, if GitHub has computed a SHA-256 digest for the asset.
+    */
+    #[must_use]
+    pub fn sha256(&self) -> Option<String> {
+        let digest = self.digest.as_deref()?;
+        let (algorithm, hex) = digest.split_once(':')?;
+        if algorithm.eq_ignore_ascii_case("sha256") {
+            Some(hex.to_ascii_lowercase())
+        } else {
+            None
+        }
+    }
+}
+
+/**
+    A repository, as returned by the GitHub API.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct GithubRepository {
+    /// The full name of the repository, such as `owner/repo`.
+    ///
+    /// This may differ from the name used to fetch the repository,
+    /// if the repository was renamed or transferred to a new owner.
+    #[serde(default)]
+    pub full_name: String,
+    /// When the repository was created.
+    pub created_at: DateTime<Utc>,
+    /// The number of stars the repository has.
+    pub stargazers_count: u64,
+    /// If the repository has been archived.
+    #[serde(default)]
+    pub archived: bool,
+    /// If the repository is a fork of another repository.
+    #[serde(default)]
+    pub fork: bool,
+}
+
+/**
+    Rate limit information, as returned by the GitHub API.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct GithubRateLimit {
+    /// The rate limit for the core API, which is used for releases.
+    pub rate: GithubRateLimitResource,
+}
+
+/**
+    Rate limit information for a single GitHub API resource.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct GithubRateLimitResource {
+    /// The maximum number of requests allowed per hour.
+    pub limit: u64,
+    /// The number of requests remaining in the current hour.
+    pub remaining: u64,
+    /// When the current window resets, in seconds since the Unix epoch.
+    pub reset: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE_JSON: &str = r#"{
+        "id": 1,
+        "tag_name": "v7.4.4",
+        "name": "Rojo 7.4.4",
+        "prerelease": false,
+        "body": "Changelog",
+        "published_at": "2024-08-01T12:00:00Z",
+        "assets": [
+            {
+                "id": 2,
+                "url": "https://api.github.com/repos/rojo-rbx/rojo/releases/assets/2",
+                "browser_download_url": "https://github.com/rojo-rbx/rojo/releases/download/v7.4.4/rojo-7.4.4-linux-x86_64.zip",
+                "name": "rojo-7.4.4-linux-x86_64.zip",
+                "size": 1234,
+                "content_type": "application/zip",
+                "digest": "sha256:ABCDEF"
+            },
+            {
+                "id": 3,
+                "url": "https://api.github.com/repos/rojo-rbx/rojo/releases/assets/3",
+                "name": "rojo-7.4.4-windows-x86_64.zip"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn deserialize_release() {
+        let release: GithubRelease = serde_json::from_str(RELEASE_JSON).unwrap();
+        assert_eq!(release.version().unwrap(), Version::new(7, 4, 4));
+        assert!(!release.draft);
+        assert_eq!(release.assets.len(), 2);
+
+        let first = &release.assets[0];
+        assert_eq!(first.size, 1234);
+        assert_eq!(first.content_type.as_deref(), Some("application/zip"));
+        assert_eq!(first.sha256().as_deref(), Some("abcdef"));
+
+        let second = &release.assets[1];
+        assert_eq!(second.size, 0);
+        assert_eq!(second.browser_download_url, None);
+        assert_eq!(second.sha256(), None);
+    }
 }
@@ -0,0 +1,178 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+
+use crate::sources::http::HttpResponse;
+
+const MAX_RETRIES: u32 = 3;
+const MAX_WAIT: Duration = Duration::from_mins(1);
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_JITTER_MILLIS: u32 = 1000;
+
+/**
+    What to do about a response from the GitHub API
+    that may have been rejected due to rate limiting.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RateLimitAction {
+    /// The response was not rate limited.
+    Continue,
+    /// Wait for the given duration, and then retry the request.
+    Retry(Duration),
+    /// Give up, since the rate limit will not reset soon
+    /// enough, or the request was already retried too many times.
+    Fail(Option<DateTime<Utc>>),
+}
+
+/**
+    Decides what to do about the given response, using the rate
+    limit headers sent by GitHub, on the given retry attempt.
+
+    GitHub rejects rate limited requests with either `403` or `429`:
+
+    - For the primary rate limit, `x-ratelimit-remaining` is `0`,
+      and `x-ratelimit-reset` is the time when the limit resets.
+    - For secondary rate limits, `retry-after` is the number of
+      seconds to wait - if it is missing, we back off exponentially.
+
+    Other `403` responses are permission errors, and are never retried.
+*/
+pub(super) fn rate_limit_action(
+    response: &HttpResponse,
+    attempt: u32,
+    now: DateTime<Utc>,
+) -> RateLimitAction {
+    let reset_at = response
+        .header("x-ratelimit-reset")
+        .and_then(|secs| secs.trim().parse::<i64>().ok())
+        .and_then(|secs| DateTime::from_timestamp(secs, 0));
+    let exhausted = response
+        .header("x-ratelimit-remaining")
+        .is_some_and(|remaining| remaining.trim() == "0");
+    let retry_after = response
+        .header("retry-after")
+        .and_then(|secs| secs.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+
+    let is_rate_limited = match response.status {
+        429 => true,
+        403 => exhausted || retry_after.is_some(),
+        _ => false,
+    };
+    if !is_rate_limited {
+        return RateLimitAction::Continue;
+    }
+    if attempt >= MAX_RETRIES {
+        return RateLimitAction::Fail(reset_at);
+    }
+
+    let wait = match (retry_after, reset_at) {
+        (Some(retry_after), _) => retry_after,
+        (None, Some(reset_at)) if exhausted => (reset_at - now).to_std().unwrap_or_default(),
+        _ => BASE_BACKOFF * 2u32.pow(attempt),
+    };
+    if wait > MAX_WAIT {
+        let reset_at =
+            reset_at.or_else(|| chrono::Duration::from_std(wait).ok().map(|wait| now + wait));
+        RateLimitAction::Fail(reset_at)
+    } else {
+        RateLimitAction::Retry(wait)
+    }
+}
+
+/**
+    Gets a random-ish duration of up to one second, to add to retries
+    so that many concurrent requests do not all retry at the same time.
+*/
+pub(super) fn jitter() -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    Duration::from_millis(u64::from(nanos % MAX_JITTER_MILLIS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn not_rate_limited() {
+        let now = at(1000);
+        assert_eq!(
+            rate_limit_action(&HttpResponse::for_test(200, &[], &[]), 0, now),
+            RateLimitAction::Continue
+        );
+        assert_eq!(
+            rate_limit_action(
+                &HttpResponse::for_test(403, &[("x-ratelimit-remaining", "10")], &[]),
+                0,
+                now
+            ),
+            RateLimitAction::Continue
+        );
+    }
+
+    #[test]
+    fn primary_rate_limit() {
+        let now = at(1000);
+        let soon = HttpResponse::for_test(
+            403,
+            &[
+                ("X-RateLimit-Remaining", "0"),
+                ("X-RateLimit-Reset", "1030"),
+            ],
+            &[],
+        );
+        assert_eq!(
+            rate_limit_action(&soon, 0, now),
+            RateLimitAction::Retry(Duration::from_secs(30))
+        );
+        assert_eq!(
+            rate_limit_action(&soon, MAX_RETRIES, now),
+            RateLimitAction::Fail(Some(at(1030)))
+        );
+
+        let later = HttpResponse::for_test(
+            403,
+            &[
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", "4600"),
+            ],
+            &[],
+        );
+        assert_eq!(
+            rate_limit_action(&later, 0, now),
+            RateLimitAction::Fail(Some(at(4600)))
+        );
+    }
+
+    #[test]
+    fn secondary_rate_limit() {
+        let now = at(1000);
+        assert_eq!(
+            rate_limit_action(
+                &HttpResponse::for_test(403, &[("retry-after", "5")], &[]),
+                0,
+                now
+            ),
+            RateLimitAction::Retry(Duration::from_secs(5))
+        );
+        assert_eq!(
+            rate_limit_action(&HttpResponse::for_test(429, &[], &[]), 2, now),
+            RateLimitAction::Retry(Duration::from_secs(4))
+        );
+        assert_eq!(
+            rate_limit_action(
+                &HttpResponse::for_test(429, &[("retry-after", "120")], &[]),
+                0,
+                now
+            ),
+            RateLimitAction::Fail(Some(at(1120)))
+        );
+    }
+}
@@ -1,22 +1,33 @@
-use reqwest::{header::InvalidHeaderValue, Error as ReqwestError};
+use chrono::{DateTime, Utc};
+use serde_json::Error as JsonError;
 use thiserror::Error;
 
-use crate::tool::{ToolId, ToolSpec};
+use crate::{
+    result::format_reset_at,
+    sources::http::HttpError,
+    tool::{ToolId, ToolSpec},
+};
 
 #[derive(Debug, Error)]
 pub enum GithubError {
     #[error("unrecognized access token format - must begin with `ghp_` or `gho_`.")]
     UnrecognizedAccessToken,
+    #[error("invalid access token - must only contain visible ASCII characters")]
+    InvalidAccessToken,
+    #[error("invalid GitHub Enterprise Server host '{0}'")]
+    InvalidHost(String),
+    #[error("invalid GitHub base URL '{0}' - must be an http or https URL")]
+    InvalidBaseUrl(String),
+    #[error("GitHub API rate limit exceeded{}", format_reset_at(.reset_at.as_ref()))]
+    RateLimited { reset_at: Option<DateTime<Utc>> },
     #[error("no latest release was found for tool '{0}'")]
     LatestReleaseNotFound(Box<ToolId>),
     #[error("no release was found for tool '{0}'")]
     ReleaseNotFound(Box<ToolSpec>),
-    #[error("failed to build client - invalid header value: {0}")]
-    ReqwestHeader(Box<InvalidHeaderValue>),
-    #[error("reqwest middleware error: {0}")]
-    ReqwestMiddleware(Box<reqwest_middleware::Error>),
-    #[error("reqwest error: {0}")]
-    Reqwest(Box<reqwest::Error>),
+    #[error("HTTP error: {0}")]
+    Http(HttpError),
+    #[error("JSON error: {0}")]
+    Json(Box<JsonError>),
     #[error("other error: {0}")]
     Other(String),
 }
@@ -25,20 +36,14 @@ pub type GithubResult<T> = Result<T, GithubError>;
 
 // FUTURE: Figure out some way to reduce this boxing boilerplate
 
-impl From<InvalidHeaderValue> for GithubError {
-    fn from(err: InvalidHeaderValue) -> Self {
-        GithubError::ReqwestHeader(err.into())
+impl From<HttpError> for GithubError {
+    fn from(err: HttpError) -> Self {
+        GithubError::Http(err)
     }
 }
 
-impl From<reqwest_middleware::Error> for GithubError {
-    fn from(err: reqwest_middleware::Error) -> Self {
-        GithubError::ReqwestMiddleware(err.into())
-    }
-}
-
-impl From<ReqwestError> for GithubError {
-    fn from(err: ReqwestError) -> Self {
-        GithubError::Reqwest(err.into())
+impl From<JsonError> for GithubError {
+    fn from(err: JsonError) -> Self {
+        GithubError::Json(err.into())
     }
 }
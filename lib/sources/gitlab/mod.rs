@@ -0,0 +1,622 @@
+use std::{env::var, sync::Arc, time::Instant};
+
+use semver::Version;
+use serde::de::DeserializeOwned;
+use tracing::{debug, instrument};
+use url::{form_urlencoded::byte_serialize, Url};
+
+use crate::tool::{ReleaseChannel, ToolId, ToolSpec};
+
+use super::{
+    artifact::link_published_digests,
+    http::{error_status, user_agent, HttpClient, HttpRequest},
+    is_signature_asset_name,
+    mirror::mirror_url,
+    Artifact, ArtifactProvider, ProviderAuthStatus, ProviderStatus, Release, ToolProvenance,
+};
+
+#[cfg(feature = "reqwest-client")]
+use super::http::default_http_client;
+
+const DEFAULT_BASE_URL: &str = "https://gitlab.com";
+pub(crate) const BASE_URL_ENV_VAR: &str = "ROKIT_GITLAB_URL";
+
+pub mod models;
+mod result;
+
+pub use self::models::{GitlabAssetLink, GitlabProject, GitlabRelease, GitlabReleaseAssets};
+pub use self::result::{GitlabError, GitlabResult};
+
+/**
+    A provider for tools hosted on GitLab.
+
+    Uses gitlab.com by default, but can use a self-hosted GitLab instance
+    instead, either by setting the `ROKIT_GITLAB_URL` environment variable
+    to the URL of the instance, or by using [`GitlabProvider::with_base_url`].
+
+    Note that GitLab projects in subgroups, such as `group/subgroup/project`,
+    can not be used, since tool ids only have a single author and name.
+*/
+#[derive(Debug, Clone)]
+pub struct GitlabProvider {
+    client: Arc<dyn HttpClient>,
+    base_url: String,
+    download_url: Option<Url>,
+    headers: Vec<(String, String)>,
+    has_auth: bool,
+}
+
+impl GitlabProvider {
+    fn new_inner(client: Arc<dyn HttpClient>, token: Option<String>) -> GitlabResult<Self> {
+        let has_auth = token.is_some();
+        let mut headers = vec![(String::from("user-agent"), user_agent())];
+        if let Some(token) = token {
+            // NOTE: Tokens are sent as headers, which must be visible ASCII
+            if token.is_empty() || !token.chars().all(|c| c.is_ascii_graphic()) {
+                return Err(GitlabError::InvalidAccessToken);
+            }
+            headers.push((String::from("authorization"), format!("Bearer {token}")));
+        }
+
+        let provider = Self {
+            client,
+            base_url: String::new(),
+            download_url: None,
+            headers,
+            has_auth,
+        };
+        match var(BASE_URL_ENV_VAR) {
+            Ok(url) if !url.trim().is_empty() => provider.with_base_url(url.trim()),
+            _ => provider.with_base_url(DEFAULT_BASE_URL),
+        }
+    }
+
+    async fn get(&self, url: &str, accept: &str) -> GitlabResult<Vec<u8>> {
+        let mut request = HttpRequest::get(url).header("accept", accept);
+        request.headers.extend(self.headers.iter().cloned());
+        let response = self.client.send(request).await?.error_for_status()?;
+        Ok(response.body)
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> GitlabResult<T> {
+        let body = self.get(url, "application/json").await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    async fn get_bytes(&self, url: &str) -> GitlabResult<Vec<u8>> {
+        self.get(url, "application/octet-stream").await
+    }
+
+    fn api_url(&self) -> String {
+        format!("{}/api/v4", self.base_url)
+    }
+
+    fn project_url(&self, tool_id: &ToolId) -> String {
+        format!(
+            "{}/projects/{}",
+            self.api_url(),
+            encode(&format!("{}/{}", tool_id.author(), tool_id.name()))
+        )
+    }
+
+    /**
+        Creates a new GitLab source instance, using the default HTTP client.
+
+        # Errors
+
+        - If the GitLab API client could not be created.
+    */
+    #[cfg(feature = "reqwest-client")]
+    pub fn new() -> GitlabResult<Self> {
+        Self::new_with_client(default_http_client()?)
+    }
+
+    /**
+        Creates a new authenticated GitLab source instance with a token,
+        using the default HTTP client.
+
+        Note that this does not verify the formatting or validity of the token,
+        use the `verify_authentication` method for checking with the GitLab API.
+
+        # Errors
+
+        - If the GitLab API client could not be created.
+    */
+    #[cfg(feature = "reqwest-client")]
+    pub fn new_authenticated(token: impl AsRef<str>) -> GitlabResult<Self> {
+        Self::new_authenticated_with_client(default_http_client()?, token)
+    }
+
+    /**
+        Creates a new GitLab source instance, using the given HTTP client.
+
+        # Errors
+
+        - If the GitLab API client could not be created.
+    */
+    pub fn new_with_client(client: Arc<dyn HttpClient>) -> GitlabResult<Self> {
+        Self::new_inner(client, None)
+    }
+
+    /**
+        Creates a new authenticated GitLab source instance with a token,
+        using the given HTTP client.
+
+        See [`GitlabProvider::new_authenticated`] for more information.
+
+        # Errors
+
+        - If the token can not be sent as a header.
+    */
+    pub fn new_authenticated_with_client(
+        client: Arc<dyn HttpClient>,
+        token: impl AsRef<str>,
+    ) -> GitlabResult<Self> {
+        let token: String = token.as_ref().trim().to_string();
+        Self::new_inner(client, Some(token))
+    }
+
+    /**
+        Uses the given HTTP client for all requests made by this provider.
+    */
+    #[must_use]
+    pub(crate) fn with_client(mut self, client: Arc<dyn HttpClient>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /**
+        Uses the GitLab instance at the given URL, such as
+        `https://gitlab.example.com`, instead of gitlab.com.
+
+        # Errors
+
+        - If the given URL is not a valid `http` or `https` URL.
+    */
+    pub fn with_base_url(mut self, base_url: &str) -> GitlabResult<Self> {
+        let url = Url::parse(base_url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+            .ok_or_else(|| GitlabError::InvalidBaseUrl(base_url.to_string()))?;
+        self.base_url = url.as_str().trim_end_matches('/').to_string();
+        Ok(self)
+    }
+
+    /**
+        Downloads artifacts hosted on the GitLab instance from the given URL
+        instead, such as an internal gateway, keeping the path of each download.
+
+        Note that authentication is sent to this URL, same as to the instance.
+
+        # Errors
+
+        - If the given URL is not a valid `http` or `https` URL.
+    */
+    pub fn with_download_url(mut self, download_url: &str) -> GitlabResult<Self> {
+        let url = Url::parse(download_url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+            .ok_or_else(|| GitlabError::InvalidBaseUrl(download_url.to_string()))?;
+        self.download_url = Some(url);
+        Ok(self)
+    }
+
+    /**
+        Gets the URL of the GitLab instance used by this provider.
+    */
+    #[must_use]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /**
+        Verifies that the current authentication token is valid.
+
+        Returns `true` if the token is valid, `false` if it is not.
+
+        Always returns `false` if the source is not authenticated.
+
+        # Errors
+
+        - If the request to the GitLab API failed.
+    */
+    pub async fn verify_authentication(&self) -> GitlabResult<bool> {
+        if !self.has_auth {
+            return Ok(false);
+        }
+
+        let url = format!("{}/user", self.api_url());
+        let res = self.get_json::<serde_json::Value>(&url).await;
+
+        match res {
+            Ok(_) => Ok(true),
+            Err(e) if is_unauthenticated(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /**
+        Probes the GitLab API to check its reachability,
+        and if the current authentication token is valid.
+
+        GitLab does not have an endpoint for checking rate limits,
+        so the returned status never contains a rate limit.
+    */
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_status(&self) -> ProviderStatus {
+        let url = if self.has_auth {
+            format!("{}/user", self.api_url())
+        } else {
+            format!("{}/projects?per_page=1&simple=true", self.api_url())
+        };
+        let start = Instant::now();
+        let res = self.get_json::<serde_json::Value>(&url).await;
+        let elapsed = start.elapsed();
+
+        let mut provider_status = ProviderStatus {
+            provider: ArtifactProvider::GitLab,
+            latency: None,
+            auth: None,
+            rate_limit: None,
+            error: None,
+        };
+        match res {
+            Ok(_) => {
+                provider_status.latency = Some(elapsed);
+                provider_status.auth = Some(if self.has_auth {
+                    ProviderAuthStatus::Valid
+                } else {
+                    ProviderAuthStatus::Anonymous
+                });
+            }
+            Err(e) if self.has_auth && is_unauthenticated(&e) => {
+                provider_status.latency = Some(elapsed);
+                provider_status.auth = Some(ProviderAuthStatus::Invalid);
+                provider_status.error = Some(e.to_string());
+            }
+            Err(e) => {
+                // NOTE: Any response at all means that GitLab is reachable,
+                // even if it is an error such as a server-side outage
+                if status(&e).is_some() {
+                    provider_status.latency = Some(elapsed);
+                }
+                provider_status.error = Some(e.to_string());
+            }
+        }
+        provider_status
+    }
+
+    /**
+        Fetches the latest release for a given tool, as returned by the GitLab API.
+
+        # Errors
+
+        - If the tool has no latest release.
+        - If the request to the GitLab API failed.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn get_latest_gitlab_release(&self, tool_id: &ToolId) -> GitlabResult<GitlabRelease> {
+        debug!(id = %tool_id, "fetching latest release for tool");
+
+        let url = format!("{}/releases/permalink/latest", self.project_url(tool_id));
+
+        match self.get_json(&url).await {
+            Err(e) if is_404(&e) => Err(GitlabError::LatestReleaseNotFound(tool_id.clone().into())),
+            res => res,
+        }
+    }
+
+    /**
+        Fetches the most recent releases for a given tool, as returned by the
+        GitLab API - this includes upcoming releases, and releases without a
+        valid version, which are both skipped by [`GitlabProvider::get_recent_releases`].
+
+        # Errors
+
+        - If the tool has no releases.
+        - If the request to the GitLab API failed.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn get_recent_gitlab_releases(
+        &self,
+        tool_id: &ToolId,
+    ) -> GitlabResult<Vec<GitlabRelease>> {
+        debug!(id = %tool_id, "fetching recent releases for tool");
+
+        let url = format!("{}/releases?per_page=100", self.project_url(tool_id));
+
+        match self.get_json(&url).await {
+            Err(e) if is_404(&e) => Err(GitlabError::LatestReleaseNotFound(tool_id.clone().into())),
+            res => res,
+        }
+    }
+
+    /**
+        Fetches a specific release for a given tool, as returned by the GitLab API.
+
+        Releases are looked up by their tag, which may or may not be prefixed with `v`.
+
+        # Errors
+
+        - If the release was not found.
+        - If the request to the GitLab API failed.
+    */
+    #[instrument(skip(self), fields(%tool_spec), level = "debug")]
+    pub async fn get_specific_gitlab_release(
+        &self,
+        tool_spec: &ToolSpec,
+    ) -> GitlabResult<GitlabRelease> {
+        debug!(spec = %tool_spec, "fetching release for tool");
+
+        let project_url = self.project_url(tool_spec.id());
+        let tag = tool_spec.version().to_string();
+        let url_with_prefix = format!("{project_url}/releases/{}", encode(&format!("v{tag}")));
+        let url_without_prefix = format!("{project_url}/releases/{}", encode(&tag));
+
+        match self.get_json(&url_with_prefix).await {
+            Err(e) if is_404(&e) => match self.get_json(&url_without_prefix).await {
+                Err(e) if is_404(&e) => Err(GitlabError::ReleaseNotFound(tool_spec.clone().into())),
+                res => res,
+            },
+            res => res,
+        }
+    }
+
+    /**
+        Fetches the latest release for a given tool.
+
+        # Errors
+
+        - If no latest release was found.
+        - If the version of the latest release could not be parsed.
+        - If the request to the GitLab API failed.
+    */
+    pub async fn get_latest_release(&self, tool_id: &ToolId) -> GitlabResult<Release> {
+        let release = self.get_latest_gitlab_release(tool_id).await?;
+        let version = parse_release_version(&release)?;
+
+        let tool_spec: ToolSpec = (tool_id.clone(), version).into();
+        Ok(Release {
+            version: tool_spec.version().clone(),
+            changelog: release.changelog.clone(),
+            artifacts: artifacts_from_release(&release, &tool_spec),
+        })
+    }
+
+    /**
+        Fetches provenance information for a given tool, such as the
+        age and star count of its project, and its latest release.
+
+        A tool without any releases is not an error, and will
+        simply be missing information about its latest release.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn get_provenance(&self, tool_id: &ToolId) -> GitlabResult<ToolProvenance> {
+        debug!(id = %tool_id, "fetching provenance for tool");
+
+        let project_url = self.project_url(tool_id);
+        let release_url = format!("{project_url}/releases/permalink/latest");
+
+        let (project, release) = futures::join!(
+            self.get_json::<GitlabProject>(&project_url),
+            self.get_json::<GitlabRelease>(&release_url),
+        );
+        let project = project?;
+        let release = match release {
+            Err(e) if is_404(&e) => None,
+            Err(e) => return Err(e),
+            Ok(r) => Some(r),
+        };
+
+        Ok(ToolProvenance {
+            created_at: Some(project.created_at),
+            stars: Some(project.star_count),
+            archived: project.archived,
+            fork: project.is_fork(),
+            latest_release_at: release.as_ref().and_then(|r| r.released_at),
+            signed: release.as_ref().map(|r| {
+                r.assets
+                    .links
+                    .iter()
+                    .any(|link| is_signature_asset_name(&link.name))
+            }),
+        })
+    }
+
+    /**
+        Fetches the most recent releases for a given tool, newest first.
+
+        Upcoming releases, prereleases, and releases without a valid version are skipped.
+
+        # Errors
+
+        - If the request to the GitLab API failed.
+    */
+    pub async fn get_recent_releases(&self, tool_id: &ToolId) -> GitlabResult<Vec<Release>> {
+        self.get_recent_releases_in_channel(tool_id, &ReleaseChannel::Stable)
+            .await
+    }
+
+    /**
+        Fetches the most recent releases in the given channel for a given tool, newest first.
+
+        Upcoming releases, releases outside of the channel, and releases without a valid
+        version are skipped - GitLab has no prerelease flag, so only versions are checked.
+
+        # Errors
+
+        - If the request to the GitLab API failed.
+    */
+    pub async fn get_recent_releases_in_channel(
+        &self,
+        tool_id: &ToolId,
+        channel: &ReleaseChannel,
+    ) -> GitlabResult<Vec<Release>> {
+        let releases = self.get_recent_gitlab_releases(tool_id).await?;
+
+        let mut releases = releases
+            .into_iter()
+            .filter(|release| !release.upcoming_release)
+            .filter_map(|release| {
+                let version = release.version().ok()?;
+                if !channel.contains(&version) {
+                    return None;
+                }
+                let tool_spec: ToolSpec = (tool_id.clone(), version).into();
+                Some(Release {
+                    version: tool_spec.version().clone(),
+                    changelog: release.changelog.clone(),
+                    artifacts: artifacts_from_release(&release, &tool_spec),
+                })
+            })
+            .collect::<Vec<_>>();
+        releases.sort_by(|a, b| b.version.cmp(&a.version));
+
+        Ok(releases)
+    }
+
+    /**
+        Fetches a specific release for a given tool.
+
+        # Errors
+
+        - If the release was not found.
+        - If the request to the GitLab API failed.
+    */
+    pub async fn get_specific_release(&self, tool_spec: &ToolSpec) -> GitlabResult<Release> {
+        let release = self.get_specific_gitlab_release(tool_spec).await?;
+
+        Ok(Release {
+            version: tool_spec.version().clone(),
+            changelog: release.changelog.clone(),
+            artifacts: artifacts_from_release(&release, tool_spec),
+        })
+    }
+
+    /**
+        Downloads the contents of the given artifact.
+
+        Linked assets may be hosted on the GitLab instance itself, or
+        elsewhere - authentication is sent along to the GitLab instance
+        only, and never to any other host that an asset may link to.
+    */
+    #[instrument(skip(self, artifact), level = "debug")]
+    pub async fn download_artifact_contents(&self, artifact: &Artifact) -> GitlabResult<Vec<u8>> {
+        assert_eq!(
+            artifact.provider,
+            ArtifactProvider::GitLab,
+            "artifact must be from GitLab"
+        );
+
+        let url = artifact.url.as_ref().expect("GitLab artifacts have urls");
+        let name = artifact.name.as_ref().expect("GitLab artifacts have names");
+        debug!(%url, name, "downloading artifact contents");
+
+        if self.is_same_instance(url) {
+            // NOTE: Download URLs replace the origin of links the same way
+            // that mirrors do, but are authenticated and have no fallback
+            match &self.download_url {
+                Some(download_url) => self.get_bytes(mirror_url(download_url, url).as_str()).await,
+                None => self.get_bytes(url.as_str()).await,
+            }
+        } else {
+            let request = HttpRequest::get(url.as_str())
+                .header("accept", "application/octet-stream")
+                .header("user-agent", user_agent());
+            let response = self.client.send(request).await?.error_for_status()?;
+            Ok(response.body)
+        }
+    }
+
+    /**
+        Downloads the contents of a release asset, given the id of the tool
+        it belongs to and the id of the asset itself.
+
+        GitLab can only look up asset links by id together with the tag of
+        their release, so this always fails - download the full artifact
+        using [`GitlabProvider::download_artifact_contents`] instead.
+
+        # Errors
+
+        - Always, see above.
+    */
+    #[allow(clippy::unused_async)]
+    pub async fn download_asset_contents(
+        &self,
+        tool_id: &ToolId,
+        asset_id: &str,
+    ) -> GitlabResult<Vec<u8>> {
+        debug!(id = %tool_id, asset_id, "can not download GitLab asset by id");
+        Err(GitlabError::AssetIdNotSupported)
+    }
+
+    /**
+        Downloads a gzipped tarball of the project for the given tool id,
+        at the given git reference - or the default branch, if not given.
+
+        # Errors
+
+        - If the project or reference does not exist.
+        - If the archive could not be downloaded.
+    */
+    #[instrument(skip(self), fields(%repo_id), level = "debug")]
+    pub async fn download_repository_archive(
+        &self,
+        repo_id: &ToolId,
+        reference: Option<&str>,
+    ) -> GitlabResult<Vec<u8>> {
+        debug!(id = %repo_id, ?reference, "downloading repository archive");
+
+        let mut url = format!("{}/repository/archive.tar.gz", self.project_url(repo_id));
+        if let Some(reference) = reference {
+            url.push_str("?sha=");
+            url.push_str(&encode(reference));
+        }
+
+        self.get_bytes(&url).await
+    }
+
+    fn is_same_instance(&self, url: &Url) -> bool {
+        Url::parse(&self.base_url).is_ok_and(|base| base.origin() == url.origin())
+    }
+}
+
+fn encode(s: &str) -> String {
+    byte_serialize(s.as_bytes()).collect()
+}
+
+fn status(err: &GitlabError) -> Option<u16> {
+    if let GitlabError::Http(http_err) = err {
+        error_status(http_err)
+    } else {
+        None
+    }
+}
+
+fn is_404(err: &GitlabError) -> bool {
+    status(err) == Some(404)
+}
+
+fn is_unauthenticated(err: &GitlabError) -> bool {
+    matches!(status(err), Some(401 | 403))
+}
+
+fn parse_release_version(release: &GitlabRelease) -> GitlabResult<Version> {
+    release
+        .version()
+        .map_err(|e| GitlabError::Other(e.to_string()))
+}
+
+fn artifacts_from_release(release: &GitlabRelease, spec: &ToolSpec) -> Vec<Artifact> {
+    let links = &release.assets.links;
+    let mut artifacts = links
+        .iter()
+        .map(|link| Artifact::from_gitlab_release_link(link, spec))
+        .collect::<Vec<_>>();
+    let names = links
+        .iter()
+        .map(|link| link.name.as_str())
+        .collect::<Vec<_>>();
+    link_published_digests(&mut artifacts, &names);
+    artifacts
+}
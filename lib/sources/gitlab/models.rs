@@ -0,0 +1,198 @@
+use chrono::{DateTime, Utc};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/**
+    A release, as returned by the GitLab API.
+
+    Only contains the fields used by Rokit, or that are generally useful when
+    implementing custom artifact selection - new fields may be added over time.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct GitlabRelease {
+    /// The tag of the release, such as `v1.2.3`.
+    pub tag_name: String,
+    /// The display name of the release, if any.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The description of the release, typically containing a changelog.
+    #[serde(rename = "description", default)]
+    pub changelog: Option<String>,
+    /// When the release was, or will be, published.
+    #[serde(default)]
+    pub released_at: Option<DateTime<Utc>>,
+    /// If the release is scheduled to be published in the future.
+    #[serde(default)]
+    pub upcoming_release: bool,
+    /// All assets attached to the release.
+    #[serde(default)]
+    pub assets: GitlabReleaseAssets,
+}
+
+impl GitlabRelease {
+    /**
+        Parses the version of this release from its tag, ignoring any `v` prefix.
+
+        # Errors
+
+        - If the tag is not a valid semver version.
+    */
+    pub fn version(&self) -> Result<Version, semver::Error> {
+        self.tag_name.trim_start_matches('v').parse()
+    }
+}
+
+/**
+    The assets of a release, as returned by the GitLab API.
+
+    Source code archives that GitLab generates for every
+    release are not included, only explicitly linked assets.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct GitlabReleaseAssets {
+    /// Links to files attached to the release.
+    #[serde(default)]
+    pub links: Vec<GitlabAssetLink>,
+}
+
+/**
+    A link to a release asset, as returned by the GitLab API.
+
+    Only contains the fields used by Rokit, or that are generally useful when
+    implementing custom artifact selection - new fields may be added over time.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct GitlabAssetLink {
+    /// The unique id of the link.
+    pub id: u64,
+    /// The file name of the asset.
+    pub name: String,
+    /// The URL that the link points to.
+    pub url: Url,
+    /// The permanent URL of the link, which redirects to the asset.
+    #[serde(default)]
+    pub direct_asset_url: Option<Url>,
+    /// The type of the link, such as `package` or `other`.
+    #[serde(default)]
+    pub link_type: Option<String>,
+}
+
+impl GitlabAssetLink {
+    /**
+        Gets the URL to download this asset from, preferring the
+        permanent direct asset URL over the URL of the link itself.
+    */
+    #[must_use]
+    pub fn download_url(&self) -> &Url {
+        self.direct_asset_url.as_ref().unwrap_or(&self.url)
+    }
+}
+
+/**
+    A project, as returned by the GitLab API.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct GitlabProject {
+    /// When the project was created.
+    pub created_at: DateTime<Utc>,
+    /// The number of stars the project has.
+    #[serde(default)]
+    pub star_count: u64,
+    /// If the project has been archived.
+    #[serde(default)]
+    pub archived: bool,
+    /// The project that this project is a fork of, if any.
+    #[serde(default)]
+    pub forked_from_project: Option<serde_json::Value>,
+}
+
+impl GitlabProject {
+    /**
+        Checks if this project is a fork of another project.
+    */
+    #[must_use]
+    pub fn is_fork(&self) -> bool {
+        self.forked_from_project.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE_JSON: &str = r#"{
+        "tag_name": "v1.2.3",
+        "name": "Tool 1.2.3",
+        "description": "Changelog",
+        "released_at": "2024-08-01T12:00:00Z",
+        "upcoming_release": false,
+        "assets": {
+            "count": 3,
+            "sources": [
+                {
+                    "format": "zip",
+                    "url": "https://gitlab.com/owner/tool/-/archive/v1.2.3/tool-v1.2.3.zip"
+                }
+            ],
+            "links": [
+                {
+                    "id": 2,
+                    "name": "tool-1.2.3-linux-x86_64.zip",
+                    "url": "https://gitlab.com/owner/tool/-/package_files/2/download",
+                    "direct_asset_url": "https://gitlab.com/owner/tool/-/releases/v1.2.3/downloads/tool-1.2.3-linux-x86_64.zip",
+                    "link_type": "package"
+                },
+                {
+                    "id": 3,
+                    "name": "tool-1.2.3-windows-x86_64.zip",
+                    "url": "https://example.com/tool-1.2.3-windows-x86_64.zip"
+                }
+            ]
+        }
+    }"#;
+
+    const PROJECT_JSON: &str = r#"{
+        "id": 1,
+        "created_at": "2020-01-01T00:00:00Z",
+        "star_count": 42,
+        "archived": false,
+        "forked_from_project": null
+    }"#;
+
+    #[test]
+    fn deserialize_release() {
+        let release: GitlabRelease = serde_json::from_str(RELEASE_JSON).unwrap();
+        assert_eq!(release.version().unwrap(), Version::new(1, 2, 3));
+        assert!(!release.upcoming_release);
+        assert_eq!(release.changelog.as_deref(), Some("Changelog"));
+        assert_eq!(release.assets.links.len(), 2);
+
+        let first = &release.assets.links[0];
+        assert_eq!(
+            first.download_url().as_str(),
+            "https://gitlab.com/owner/tool/-/releases/v1.2.3/downloads/tool-1.2.3-linux-x86_64.zip"
+        );
+
+        let second = &release.assets.links[1];
+        assert_eq!(second.direct_asset_url, None);
+        assert_eq!(second.download_url(), &second.url);
+    }
+
+    #[test]
+    fn deserialize_project() {
+        let project: GitlabProject = serde_json::from_str(PROJECT_JSON).unwrap();
+        assert_eq!(project.star_count, 42);
+        assert!(!project.is_fork());
+
+        let fork: GitlabProject = serde_json::from_str(
+            r#"{ "created_at": "2020-01-01T00:00:00Z", "forked_from_project": { "id": 2 } }"#,
+        )
+        .unwrap();
+        assert!(fork.is_fork());
+    }
+}
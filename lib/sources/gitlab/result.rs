@@ -0,0 +1,43 @@
+use serde_json::Error as JsonError;
+use thiserror::Error;
+
+use crate::{
+    sources::http::HttpError,
+    tool::{ToolId, ToolSpec},
+};
+
+#[derive(Debug, Error)]
+pub enum GitlabError {
+    #[error("invalid access token - must only contain visible ASCII characters")]
+    InvalidAccessToken,
+    #[error("invalid GitLab instance URL '{0}'")]
+    InvalidBaseUrl(String),
+    #[error("no latest release was found for tool '{0}'")]
+    LatestReleaseNotFound(Box<ToolId>),
+    #[error("no release was found for tool '{0}'")]
+    ReleaseNotFound(Box<ToolSpec>),
+    #[error("GitLab release assets can not be downloaded by id alone - use the full artifact")]
+    AssetIdNotSupported,
+    #[error("HTTP error: {0}")]
+    Http(HttpError),
+    #[error("JSON error: {0}")]
+    Json(Box<JsonError>),
+    #[error("other error: {0}")]
+    Other(String),
+}
+
+pub type GitlabResult<T> = Result<T, GitlabError>;
+
+// FUTURE: Figure out some way to reduce this boxing boilerplate
+
+impl From<HttpError> for GitlabError {
+    fn from(err: HttpError) -> Self {
+        GitlabError::Http(err)
+    }
+}
+
+impl From<JsonError> for GitlabError {
+    fn from(err: JsonError) -> Self {
+        GitlabError::Json(err.into())
+    }
+}
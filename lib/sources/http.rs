@@ -0,0 +1,353 @@
+use std::{error::Error, fmt, path::PathBuf};
+
+use futures::future::BoxFuture;
+use thiserror::Error;
+use url::Url;
+
+use super::RequestPolicy;
+
+#[cfg(feature = "reqwest-client")]
+use std::sync::Arc;
+
+/**
+    Error type returned by an [`HttpClient`].
+
+    Clients may return any error, such as a connection or TLS error.
+    Responses with an unsuccessful status code are not errors by
+    themselves, see [`HttpResponse::error_for_status`].
+*/
+pub type HttpError = Box<dyn Error + Send + Sync>;
+
+/**
+    Error representing an unsuccessful HTTP status code for a response.
+*/
+#[derive(Debug, Error)]
+#[error("HTTP status {status} for url '{url}'")]
+pub struct HttpStatusError {
+    pub status: u16,
+    pub url: String,
+}
+
+/**
+    Error returned by an [`HttpClient`] when the connection failed after
+    a response was received, but before its full body could be read.
+
+    Contains the response, with the part of the body that was received,
+    so that the download may be resumed later using a `Range` request.
+*/
+#[derive(Debug, Error)]
+#[error(
+    "connection failed after receiving {} bytes from url '{}': {source}",
+    .response.body.len(),
+    .response.url
+)]
+pub struct HttpPartialBodyError {
+    pub response: HttpResponse,
+    pub source: HttpError,
+}
+
+/**
+    Error representing a downloaded body that does not have the
+    length that the server said it would, or that was expected.
+*/
+#[derive(Debug, Error)]
+#[error("incomplete download from url '{url}' - expected {expected} bytes, got {actual}")]
+pub struct HttpLengthError {
+    pub url: String,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+/**
+    Error returned for requests that would need network access while offline,
+    meaning that there was no cached response for the URL that was requested.
+*/
+#[derive(Debug, Error)]
+#[error("url '{url}' is not cached, and can not be fetched while offline")]
+pub struct HttpOfflineError {
+    pub url: String,
+}
+
+/**
+    The method of a [`HttpRequest`].
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HttpMethod {
+    /// Fetches a resource.
+    #[default]
+    Get,
+    /// Fetches only the headers of a resource, such as its size.
+    Head,
+    /// Sends a body to a resource, such as a query to a GraphQL API.
+    Post,
+}
+
+/**
+    A HTTP request made by Rokit.
+
+    Rokit almost only ever needs to fetch resources, or their metadata, so
+    the body is empty for everything except `POST` requests - which are
+    only used for queries that fetch many resources at once.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /**
+        Creates a new `GET` request for the given URL, without any headers.
+    */
+    #[must_use]
+    pub fn get(url: impl Into<String>) -> Self {
+        Self {
+            method: HttpMethod::Get,
+            url: url.into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /**
+        Creates a new `HEAD` request for the given URL, without any headers.
+    */
+    #[must_use]
+    pub fn head(url: impl Into<String>) -> Self {
+        Self {
+            method: HttpMethod::Head,
+            url: url.into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /**
+        Creates a new `POST` request for the given URL, with the given body,
+        and without any headers.
+    */
+    #[must_use]
+    pub fn post(url: impl Into<String>, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            method: HttpMethod::Post,
+            url: url.into(),
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /**
+        Adds a header to this request.
+    */
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/**
+    A HTTP response returned by an [`HttpClient`].
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub url: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /**
+        Gets the value of the given header in this response, if any.
+
+        Header names are compared case-insensitively.
+    */
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /**
+        Checks if the status code of this response is in the `2xx` range.
+    */
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /**
+        Turns an unsuccessful response into an [`HttpStatusError`].
+
+        # Errors
+
+        - If the status code of this response is not in the `2xx` range.
+    */
+    pub fn error_for_status(self) -> Result<Self, HttpError> {
+        if self.is_success() {
+            Ok(self)
+        } else {
+            Err(Box::new(HttpStatusError {
+                status: self.status,
+                url: self.url,
+            }))
+        }
+    }
+}
+
+/**
+    A HTTP client used by Rokit to fetch releases, artifacts and taps.
+
+    Embedders may implement this trait to use their own client, for example
+    one with a custom TLS stack or instrumentation, or a test double.
+
+    The default implementation is available using [`default_http_client`]
+    when the `reqwest-client` feature is enabled, and will follow redirects,
+    decompress responses, and retry transient errors.
+
+    Note that implementations must not add authentication headers by
+    themselves, since Rokit adds these only for requests that need them.
+*/
+pub trait HttpClient: fmt::Debug + Send + Sync {
+    /**
+        Sends the given request, returning the response.
+
+        Any response that was received should be returned, even if its
+        status code is unsuccessful - only return an error if no response
+        could be received at all, such as for connection or timeout errors.
+
+        If the connection fails while reading the body of a response,
+        implementations may return an [`HttpPartialBodyError`] with the
+        part of the body that was received, to let Rokit resume it.
+    */
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>>;
+}
+
+/**
+    Gets the status code of an [`HttpStatusError`], if the given error is one.
+*/
+#[must_use]
+pub fn error_status(err: &HttpError) -> Option<u16> {
+    err.downcast_ref::<HttpStatusError>().map(|e| e.status)
+}
+
+/**
+    Gets the user agent used by Rokit for all requests, which is
+    `<crate_name>/<crate_version> (<repository_url>)`.
+*/
+#[must_use]
+pub fn user_agent() -> String {
+    format!(
+        "{}/{} ({})",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_REPOSITORY"),
+    )
+}
+
+/**
+    Network options for the default HTTP client.
+
+    Proxies set in the `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY`
+    environment variables are always honored - an explicit proxy
+    takes precedence over those, but still respects `NO_PROXY`.
+
+    Timeouts and retries for all requests are set by the request policy.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HttpClientOptions {
+    /// Proxy to send all requests through.
+    pub proxy: Option<Url>,
+    /// Path to a PEM bundle of extra root certificates to trust.
+    pub ca_bundle: Option<PathBuf>,
+    /// Timeout and retry policy for all requests.
+    pub request_policy: RequestPolicy,
+}
+
+/**
+    Creates the default HTTP client, using `reqwest`.
+
+    # Errors
+
+    - If the client could not be created.
+*/
+#[cfg(feature = "reqwest-client")]
+pub fn default_http_client() -> Result<Arc<dyn HttpClient>, HttpError> {
+    http_client_with_options(&HttpClientOptions::default())
+}
+
+/**
+    Creates the default HTTP client, using `reqwest`, with the given network options.
+
+    # Errors
+
+    - If the proxy URL is invalid.
+    - If the CA bundle could not be read, or contains invalid certificates.
+    - If the client could not be created.
+*/
+#[cfg(feature = "reqwest-client")]
+pub fn http_client_with_options(
+    options: &HttpClientOptions,
+) -> Result<Arc<dyn HttpClient>, HttpError> {
+    Ok(Arc::new(super::client::ReqwestClient::new_with_options(
+        options,
+    )?))
+}
+
+#[cfg(test)]
+impl HttpResponse {
+    /**
+        Creates a response for `https://example.com/tool.zip`
+        with the given status, headers, and body, for tests.
+    */
+    pub(crate) fn for_test(status: u16, headers: &[(&str, &str)], body: &[u8]) -> Self {
+        Self {
+            url: String::from("https://example.com/tool.zip"),
+            status,
+            headers: headers
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+            body: body.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16) -> HttpResponse {
+        HttpResponse {
+            url: String::from("https://example.com"),
+            status,
+            headers: vec![(String::from("ETag"), String::from("\"abc\""))],
+            body: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn error_for_status() {
+        assert!(response(200).error_for_status().is_ok());
+        assert!(response(204).error_for_status().is_ok());
+
+        let err = response(404).error_for_status().unwrap_err();
+        assert_eq!(error_status(&err), Some(404));
+
+        let other: HttpError = "connection reset".into();
+        assert_eq!(error_status(&other), None);
+    }
+
+    #[test]
+    fn header_case_insensitive() {
+        let response = response(200);
+        assert_eq!(response.header("etag"), Some("\"abc\""));
+        assert_eq!(response.header("ETAG"), Some("\"abc\""));
+        assert_eq!(response.header("last-modified"), None);
+    }
+}
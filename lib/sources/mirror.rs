@@ -0,0 +1,92 @@
+use url::Url;
+
+#[cfg(feature = "runtime")]
+use tracing::{debug, instrument, warn};
+
+#[cfg(feature = "runtime")]
+use super::http::{user_agent, HttpClient, HttpRequest, HttpResponse};
+
+/**
+    Rewrites the given download URL to point at the given mirror.
+
+    The origin of the URL is replaced with the mirror, and its path
+    and query are kept, appended to any path the mirror already has:
+
+    - `https://api.github.com/repos/a/b/releases/assets/1`
+    - with mirror `https://mirror.example.com/github`
+    - becomes `https://mirror.example.com/github/repos/a/b/releases/assets/1`
+*/
+#[must_use]
+#[cfg_attr(not(feature = "runtime"), allow(dead_code))]
+pub(crate) fn mirror_url(mirror: &Url, url: &Url) -> Url {
+    let mut mirrored = mirror.clone();
+    let path = format!(
+        "{}/{}",
+        mirror.path().trim_end_matches('/'),
+        url.path().trim_start_matches('/')
+    );
+    mirrored.set_path(&path);
+    mirrored.set_query(url.query());
+    mirrored.set_fragment(None);
+    mirrored
+}
+
+/**
+    Tries to download the given URL from each of the given mirrors, in order.
+
+    Returns the contents from the first mirror that succeeds, or `None` if all
+    of them failed, in which case the caller should download from the original.
+
+    Mirrors are never sent authentication tokens for the original provider.
+*/
+#[cfg(feature = "runtime")]
+#[instrument(skip(client, mirrors), level = "debug")]
+pub(super) async fn download_from_mirrors(
+    client: &dyn HttpClient,
+    mirrors: &[Url],
+    url: &Url,
+) -> Option<Vec<u8>> {
+    for mirror in mirrors {
+        let mirrored = mirror_url(mirror, url);
+        debug!(url = %mirrored, "downloading artifact from mirror");
+        let request = HttpRequest::get(mirrored.as_str())
+            .header("accept", "application/octet-stream")
+            .header("user-agent", user_agent());
+        match client
+            .send(request)
+            .await
+            .and_then(HttpResponse::error_for_status)
+        {
+            Ok(response) => return Some(response.body),
+            Err(e) => warn!(%mirror, error = %e, "failed to download from mirror"),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirrored(mirror: &str, url: &str) -> String {
+        mirror_url(&Url::parse(mirror).unwrap(), &Url::parse(url).unwrap()).into()
+    }
+
+    #[test]
+    fn mirror_url_keeps_path_and_query() {
+        assert_eq!(
+            mirrored(
+                "https://mirror.example.com/github",
+                "https://api.github.com/repos/a/b/releases/assets/1?x=y"
+            ),
+            "https://mirror.example.com/github/repos/a/b/releases/assets/1?x=y"
+        );
+        assert_eq!(
+            mirrored(
+                "http://mirror.example.com/",
+                "https://gitlab.com/a/b/-/releases/v1/downloads/tool.zip"
+            ),
+            "http://mirror.example.com/a/b/-/releases/v1/downloads/tool.zip"
+        );
+    }
+}
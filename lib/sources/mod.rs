@@ -1,11 +1,71 @@
 mod artifact;
+mod extra_file;
+mod mirror;
+mod request_policy;
+mod tap;
+mod url_source;
+
+#[cfg(feature = "extract")]
+mod blocking;
+#[cfg(feature = "reqwest-client")]
 mod client;
+#[cfg(feature = "runtime")]
+pub mod crates;
+#[cfg(feature = "runtime")]
+mod custom;
+#[cfg(feature = "extract")]
 mod decompression;
+#[cfg(feature = "runtime")]
+mod downloader;
+#[cfg(feature = "extract")]
 mod extraction;
+#[cfg(feature = "runtime")]
+pub mod git;
+#[cfg(feature = "runtime")]
+mod http;
+#[cfg(feature = "runtime")]
+pub mod npm;
+#[cfg(feature = "runtime")]
+mod provenance;
+#[cfg(feature = "runtime")]
+pub mod registry;
+#[cfg(feature = "runtime")]
+pub mod s3;
+#[cfg(feature = "runtime")]
 mod source;
+#[cfg(feature = "runtime")]
+mod status;
 
+#[cfg(feature = "github")]
 pub mod github;
+#[cfg(feature = "gitlab")]
+pub mod gitlab;
+
+pub use self::artifact::{
+    is_digest_file_name, Artifact, ArtifactDigest, ArtifactFormat, ArtifactProvider, Release,
+    ReleaseNotes,
+};
+pub use self::extra_file::{CompletionShell, ExtraFile, ExtraFileKind};
+pub use self::request_policy::RequestPolicy;
+pub use self::tap::TapIndex;
+pub use self::url_source::{UrlSource, UrlSourceError};
 
-pub use self::artifact::{Artifact, ArtifactFormat, ArtifactProvider, Release};
-pub use self::extraction::ExtractError;
+#[cfg(feature = "runtime")]
+pub use self::custom::{CustomProvider, CustomProviderError};
+#[cfg(feature = "runtime")]
+pub use self::downloader::Downloader;
+#[cfg(feature = "extract")]
+pub use self::extraction::{unpack_repository_archive, ExtractError};
+#[cfg(feature = "reqwest-client")]
+pub use self::http::{default_http_client, http_client_with_options};
+#[cfg(feature = "runtime")]
+pub use self::http::{
+    error_status, user_agent, HttpClient, HttpClientOptions, HttpError, HttpLengthError,
+    HttpMethod, HttpOfflineError, HttpPartialBodyError, HttpRequest, HttpResponse, HttpStatusError,
+};
+#[cfg(feature = "runtime")]
+pub use self::provenance::{is_signature_asset_name, ToolProvenance};
+#[cfg(feature = "runtime")]
 pub use self::source::ArtifactSource;
+#[cfg(feature = "runtime")]
+pub use self::status::{ProviderAuthStatus, ProviderRateLimit, ProviderStatus};
@@ -0,0 +1,270 @@
+use std::sync::Arc;
+
+use semver::Version;
+use tracing::{debug, instrument};
+
+use crate::tool::{ReleaseChannel, ToolId, ToolSpec, NPM_AUTHOR};
+
+use super::{
+    http::{error_status, user_agent, HttpClient, HttpRequest, HttpResponse},
+    Artifact, ArtifactFormat, ArtifactProvider, Release,
+};
+
+#[cfg(feature = "reqwest-client")]
+use super::http::default_http_client;
+
+const REGISTRY_URL: &str = "https://registry.npmjs.org";
+// NOTE: The abbreviated metadata is much smaller than the full metadata of
+// a package, and still contains everything needed to install the package
+const ABBREVIATED_ACCEPT: &str =
+    "application/vnd.npm.install-v1+json; q=1.0, application/json; q=0.8";
+
+pub mod models;
+mod result;
+
+pub use self::models::{NpmDist, NpmPackage, NpmPlatformPackage, NpmVersion};
+pub use self::result::{NpmError, NpmResult};
+
+/**
+    A provider for tools published as packages on the npm registry,
+    such as `npm:esbuild`, or `npm:biomejs/biome` for scoped packages.
+
+    Versions of a tool are the versions of its package on the registry.
+    Many tools distributed through npm publish their binaries in a separate
+    package for each platform, such as `@esbuild/linux-x64`, which are
+    optional dependencies of the main package - every release contains
+    one artifact for each of those, named after their platform, so
+    that the most compatible one is picked the same as for any
+    other provider. Packages without any platform-specific
+    dependencies have a single artifact, for their own tarball.
+
+    Note that tools are never run using Node - the binary of the tool
+    is extracted from the tarball of the package and installed directly.
+*/
+#[derive(Debug, Clone)]
+pub struct NpmProvider {
+    client: Arc<dyn HttpClient>,
+}
+
+impl NpmProvider {
+    /**
+        Creates a new npm provider, using the default HTTP client.
+
+        # Errors
+
+        - If the HTTP client could not be created.
+    */
+    #[cfg(feature = "reqwest-client")]
+    pub fn new() -> NpmResult<Self> {
+        Ok(Self::new_with_client(default_http_client()?))
+    }
+
+    /**
+        Creates a new npm provider, using the given HTTP client.
+    */
+    #[must_use]
+    pub fn new_with_client(client: Arc<dyn HttpClient>) -> Self {
+        Self { client }
+    }
+
+    /**
+        Uses the given HTTP client for all requests made by this provider.
+    */
+    #[must_use]
+    pub(crate) fn with_client(mut self, client: Arc<dyn HttpClient>) -> Self {
+        self.client = client;
+        self
+    }
+
+    async fn get(&self, request: HttpRequest) -> NpmResult<HttpResponse> {
+        let request = request.header("user-agent", user_agent());
+        Ok(self.client.send(request).await?.error_for_status()?)
+    }
+
+    /**
+        Fetches information about the package of the given tool, and all of its versions.
+
+        # Errors
+
+        - If the package does not exist.
+        - If the request to the npm registry failed.
+    */
+    #[instrument(skip(self), fields(%tool_id), level = "debug")]
+    pub async fn get_package(&self, tool_id: &ToolId) -> NpmResult<NpmPackage> {
+        let name = package_name(tool_id);
+        // NOTE: The registry expects the slash in scoped package names to be encoded
+        let url = format!("{REGISTRY_URL}/{}", name.replace('/', "%2F"));
+        let request = HttpRequest::get(url).header("accept", ABBREVIATED_ACCEPT);
+        match self.get(request).await {
+            Ok(response) => Ok(serde_json::from_slice(&response.body)?),
+            Err(e) if status(&e) == Some(404) => Err(NpmError::PackageNotFound(name)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn release_for(package: &NpmPackage, spec: &ToolSpec) -> Option<Release> {
+        let version = package.version(spec.version())?;
+        let platform_packages = version.platform_packages();
+        let artifacts = if platform_packages.is_empty() {
+            version
+                .dist
+                .tarball
+                .parse()
+                .ok()
+                .map(|url| Artifact {
+                    provider: ArtifactProvider::Npm,
+                    format: Some(ArtifactFormat::TarGz),
+                    id: Some(package.name.clone()),
+                    url: Some(url),
+                    name: Some(spec.name().to_string()),
+                    size: None,
+                    digest: None,
+                    tool_spec: spec.clone(),
+                })
+                .into_iter()
+                .collect()
+        } else {
+            platform_packages
+                .into_iter()
+                .filter_map(|platform_package| {
+                    let url = tarball_url(&platform_package.name, &platform_package.version);
+                    Some(Artifact {
+                        provider: ArtifactProvider::Npm,
+                        format: Some(ArtifactFormat::TarGz),
+                        id: Some(platform_package.name),
+                        url: Some(url.parse().ok()?),
+                        name: Some(format!("{}-{}", spec.name(), platform_package.platform)),
+                        size: None,
+                        digest: None,
+                        tool_spec: spec.clone(),
+                    })
+                })
+                .collect()
+        };
+        Some(Release {
+            version: spec.version().clone(),
+            changelog: None,
+            artifacts,
+        })
+    }
+
+    /**
+        Fetches the latest stable release for a given tool.
+
+        # Errors
+
+        - If the package has no stable versions.
+        - If the request to the npm registry failed.
+    */
+    pub async fn get_latest_release(&self, tool_id: &ToolId) -> NpmResult<Release> {
+        self.get_recent_releases(tool_id)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| NpmError::LatestReleaseNotFound(tool_id.clone().into()))
+    }
+
+    /**
+        Fetches all stable releases for a given tool, newest first.
+
+        # Errors
+
+        - If the request to the npm registry failed.
+    */
+    pub async fn get_recent_releases(&self, tool_id: &ToolId) -> NpmResult<Vec<Release>> {
+        self.get_recent_releases_in_channel(tool_id, &ReleaseChannel::Stable)
+            .await
+    }
+
+    /**
+        Fetches all releases in the given channel for a given tool, newest first.
+
+        # Errors
+
+        - If the request to the npm registry failed.
+    */
+    pub async fn get_recent_releases_in_channel(
+        &self,
+        tool_id: &ToolId,
+        channel: &ReleaseChannel,
+    ) -> NpmResult<Vec<Release>> {
+        let package = self.get_package(tool_id).await?;
+        Ok(package
+            .available_versions()
+            .into_iter()
+            .filter(|version| channel.contains(version))
+            .filter_map(|version| {
+                Self::release_for(&package, &ToolSpec::from((tool_id.clone(), version)))
+            })
+            .collect())
+    }
+
+    /**
+        Fetches a specific release for a given tool.
+
+        # Errors
+
+        - If the version does not exist.
+        - If the request to the npm registry failed.
+    */
+    pub async fn get_specific_release(&self, tool_spec: &ToolSpec) -> NpmResult<Release> {
+        let package = self.get_package(tool_spec.id()).await?;
+        Self::release_for(&package, tool_spec)
+            .ok_or_else(|| NpmError::ReleaseNotFound(tool_spec.clone().into()))
+    }
+
+    /**
+        Downloads the tarball of the package for the given artifact.
+
+        # Errors
+
+        - If the tarball could not be downloaded.
+    */
+    #[instrument(skip(self, artifact), level = "debug")]
+    pub async fn download_artifact_contents(&self, artifact: &Artifact) -> NpmResult<Vec<u8>> {
+        assert_eq!(
+            artifact.provider,
+            ArtifactProvider::Npm,
+            "artifact must be from the npm registry"
+        );
+
+        let url = artifact
+            .url
+            .as_ref()
+            .expect("npm artifacts have tarball urls");
+        debug!(%url, "downloading package tarball");
+        let response = self.get(HttpRequest::get(url.as_str())).await?;
+        Ok(response.body)
+    }
+}
+
+/**
+    Gets the name of the package on the npm registry for the given tool,
+    such as `esbuild` for `npm:esbuild`, or `@biomejs/biome` for `npm:biomejs/biome`.
+*/
+#[must_use]
+pub fn package_name(tool_id: &ToolId) -> String {
+    if tool_id.author() == NPM_AUTHOR {
+        tool_id.name().to_string()
+    } else {
+        format!("@{}/{}", tool_id.author(), tool_id.name())
+    }
+}
+
+/*
+    Gets the URL of the tarball for the given package and version, which
+    always follows the same pattern on the registry, for scoped packages
+    such as `@esbuild/linux-x64/-/linux-x64-0.20.0.tgz` too.
+*/
+fn tarball_url(package_name: &str, version: &Version) -> String {
+    let unscoped = package_name.rsplit('/').next().unwrap_or(package_name);
+    format!("{REGISTRY_URL}/{package_name}/-/{unscoped}-{version}.tgz")
+}
+
+fn status(err: &NpmError) -> Option<u16> {
+    if let NpmError::Http(http_err) = err {
+        error_status(http_err)
+    } else {
+        None
+    }
+}
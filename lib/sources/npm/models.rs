@@ -0,0 +1,194 @@
+use std::collections::{BTreeMap, HashMap};
+
+use semver::Version;
+use serde::Deserialize;
+
+/**
+    A package on the npm registry, together with all of its versions,
+    as returned by the abbreviated metadata endpoint of the registry.
+
+    Only the parts of the response that Rokit uses are kept.
+*/
+#[derive(Debug, Clone, Deserialize)]
+pub struct NpmPackage {
+    pub name: String,
+    #[serde(default)]
+    pub versions: HashMap<String, NpmVersion>,
+}
+
+/**
+    A single published version of a package on the npm registry.
+*/
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NpmVersion {
+    #[serde(default)]
+    pub optional_dependencies: BTreeMap<String, String>,
+    pub dist: NpmDist,
+}
+
+/**
+    Information about the tarball of a version of a package on the npm registry.
+*/
+#[derive(Debug, Clone, Deserialize)]
+pub struct NpmDist {
+    pub tarball: String,
+}
+
+/**
+    A platform-specific package, such as `@esbuild/linux-x64`, which
+    contains the binary of a tool for a single platform - these are
+    optional dependencies of the main package of the tool.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpmPlatformPackage {
+    pub name: String,
+    pub version: Version,
+    /**
+        The platform of the package, using the same names for operating systems
+        and architectures as release assets usually do, such as `linux-x86_64`.
+    */
+    pub platform: String,
+}
+
+impl NpmPackage {
+    /**
+        Gets all versions of the package that are valid semver versions, newest first.
+    */
+    #[must_use]
+    pub fn available_versions(&self) -> Vec<Version> {
+        let mut versions = self
+            .versions
+            .keys()
+            .filter_map(|version| Version::parse(version).ok())
+            .collect::<Vec<_>>();
+        versions.sort_unstable_by(|a, b| b.cmp(a));
+        versions
+    }
+
+    /**
+        Gets the given version of the package, if it exists.
+    */
+    #[must_use]
+    pub fn version(&self, version: &Version) -> Option<&NpmVersion> {
+        self.versions.get(&version.to_string())
+    }
+}
+
+impl NpmVersion {
+    /**
+        Gets all optional dependencies of this version that are platform-specific
+        packages, meaning packages with both an operating system and architecture
+        in their name, such as `@biomejs/cli-darwin-arm64`, and an exact version.
+    */
+    #[must_use]
+    pub fn platform_packages(&self) -> Vec<NpmPlatformPackage> {
+        self.optional_dependencies
+            .iter()
+            .filter_map(|(name, version)| {
+                Some(NpmPlatformPackage {
+                    name: name.clone(),
+                    version: Version::parse(version.trim().trim_start_matches('=')).ok()?,
+                    platform: package_platform(name)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/*
+    Parses the platform from the name of a platform-specific package,
+    translating the operating systems and architectures used by Node,
+    such as `win32` and `x64`, into names that are not ambiguous.
+*/
+fn package_platform(package_name: &str) -> Option<String> {
+    let name = package_name.rsplit('/').next()?;
+    let mut os = None;
+    let mut arch = None;
+    let mut toolchain = None;
+    for part in name.split('-') {
+        match part.to_ascii_lowercase().as_str() {
+            "win32" | "windows" => os = Some("windows"),
+            "darwin" | "macos" => os = Some("macos"),
+            "linux" => os = Some("linux"),
+            "x64" => arch = Some("x86_64"),
+            "arm64" => arch = Some("aarch64"),
+            "ia32" => arch = Some("i686"),
+            "gnu" => toolchain = Some("gnu"),
+            "musl" => toolchain = Some("musl"),
+            "msvc" => toolchain = Some("msvc"),
+            _ => {}
+        }
+    }
+    let platform = format!("{}-{}", os?, arch?);
+    Some(match toolchain {
+        Some(toolchain) => format!("{platform}-{toolchain}"),
+        None => platform,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platform_from_package_name() {
+        assert_eq!(
+            package_platform("@esbuild/linux-x64").as_deref(),
+            Some("linux-x86_64")
+        );
+        assert_eq!(
+            package_platform("@biomejs/cli-win32-arm64").as_deref(),
+            Some("windows-aarch64")
+        );
+        assert_eq!(
+            package_platform("@rollup/rollup-linux-x64-musl").as_deref(),
+            Some("linux-x86_64-musl")
+        );
+        assert_eq!(package_platform("@esbuild/android-arm64"), None);
+        assert_eq!(package_platform("fsevents"), None);
+    }
+
+    #[test]
+    fn parse_package() {
+        let package: NpmPackage = serde_json::from_str(
+            r#"{
+                "name": "tool",
+                "versions": {
+                    "1.0.0": {
+                        "dist": { "tarball": "https://registry.npmjs.org/tool/-/tool-1.0.0.tgz" }
+                    },
+                    "1.1.0": {
+                        "optionalDependencies": {
+                            "@tool/darwin-arm64": "1.1.0",
+                            "@tool/linux-x64": "1.1.0",
+                            "@tool/linux-arm64": "^1.1.0",
+                            "fsevents": "2.3.3"
+                        },
+                        "dist": { "tarball": "https://registry.npmjs.org/tool/-/tool-1.1.0.tgz" }
+                    },
+                    "not-a-version": {
+                        "dist": { "tarball": "https://registry.npmjs.org/tool/-/tool.tgz" }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let versions = package.available_versions();
+        assert_eq!(versions, vec![Version::new(1, 1, 0), Version::new(1, 0, 0)]);
+
+        let latest = package.version(&versions[0]).unwrap();
+        let platforms = latest
+            .platform_packages()
+            .into_iter()
+            .map(|package| package.platform)
+            .collect::<Vec<_>>();
+        assert_eq!(platforms, vec!["macos-aarch64", "linux-x86_64"]);
+        assert!(package
+            .version(&versions[1])
+            .unwrap()
+            .platform_packages()
+            .is_empty());
+    }
+}
@@ -0,0 +1,37 @@
+use serde_json::Error as JsonError;
+use thiserror::Error;
+
+use crate::{
+    sources::http::HttpError,
+    tool::{ToolId, ToolSpec},
+};
+
+#[derive(Debug, Error)]
+pub enum NpmError {
+    #[error("package '{0}' was not found on the npm registry")]
+    PackageNotFound(String),
+    #[error("no latest release was found for tool '{0}'")]
+    LatestReleaseNotFound(Box<ToolId>),
+    #[error("no release was found for tool '{0}'")]
+    ReleaseNotFound(Box<ToolSpec>),
+    #[error("npm packages only contain tool artifacts, not {0}")]
+    NotSupported(&'static str),
+    #[error("HTTP error: {0}")]
+    Http(HttpError),
+    #[error("JSON error: {0}")]
+    Json(Box<JsonError>),
+}
+
+pub type NpmResult<T> = Result<T, NpmError>;
+
+impl From<HttpError> for NpmError {
+    fn from(err: HttpError) -> Self {
+        NpmError::Http(err)
+    }
+}
+
+impl From<JsonError> for NpmError {
+    fn from(err: JsonError) -> Self {
+        NpmError::Json(err.into())
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+
+const SIGNATURE_SUFFIXES: [&str; 6] = [
+    ".sig",
+    ".asc",
+    ".minisig",
+    ".sigstore",
+    ".sigstore.json",
+    ".intoto.jsonl",
+];
+
+/**
+    Information about where a tool comes from, such as the age and
+    popularity of its repository, and whether its releases are signed.
+
+    Meant to help users make an informed decision when deciding to trust a tool.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolProvenance {
+    /// When the repository of the tool was created.
+    pub created_at: Option<DateTime<Utc>>,
+    /// The number of stars the repository of the tool has.
+    pub stars: Option<u64>,
+    /// If the repository of the tool is archived and no longer maintained.
+    pub archived: bool,
+    /// If the repository of the tool is a fork of another repository.
+    pub fork: bool,
+    /// When the latest release of the tool was published.
+    pub latest_release_at: Option<DateTime<Utc>>,
+    /// If the latest release of the tool contains signatures for its artifacts.
+    /// This is `None` if the tool has no releases.
+    pub signed: Option<bool>,
+}
+
+/**
+    Checks if the given release asset name is a signature, or other attestation,
+    for another release asset - for example `rojo-7.4.4-linux-x86_64.zip.sig`.
+*/
+#[must_use]
+pub fn is_signature_asset_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SIGNATURE_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_asset_names() {
+        assert!(is_signature_asset_name("rojo-7.4.4-linux-x86_64.zip.sig"));
+        assert!(is_signature_asset_name("rojo-7.4.4-linux-x86_64.zip.ASC"));
+        assert!(is_signature_asset_name("multiple.intoto.jsonl"));
+        assert!(is_signature_asset_name("rojo.zip.sigstore.json"));
+        assert!(!is_signature_asset_name("rojo-7.4.4-linux-x86_64.zip"));
+        assert!(!is_signature_asset_name("signature-tool.zip"));
+    }
+}
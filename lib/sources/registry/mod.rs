@@ -0,0 +1,317 @@
+use std::{sync::Arc, time::Instant};
+
+use tokio::sync::OnceCell;
+use tracing::{debug, instrument};
+use url::Url;
+
+use crate::{
+    tool::{ReleaseChannel, ToolId, ToolSpec},
+    util::hash::sha256_hex,
+};
+
+use super::{
+    http::{error_status, user_agent, HttpClient, HttpRequest},
+    Artifact, ArtifactDigest, ArtifactProvider, ProviderAuthStatus, ProviderStatus, Release,
+};
+
+#[cfg(feature = "reqwest-client")]
+use super::http::default_http_client;
+
+const INDEX_FILE_NAME: &str = "index.json";
+
+pub mod models;
+mod result;
+
+pub use self::models::{RegistryArtifact, RegistryIndex};
+pub use self::result::{RegistryError, RegistryResult};
+
+/**
+    A provider for tools published in a custom registry.
+
+    Registries let organizations publish tools without putting them on
+    GitHub, using a single JSON index served at `<registry>/index.json` -
+    see [`RegistryIndex`] for the format of the index. Every artifact in
+    the index has a checksum, which downloaded artifacts must match.
+
+    The index is fetched at most once for each provider, and
+    requests to the registry are never authenticated.
+*/
+#[derive(Debug, Clone)]
+pub struct RegistryProvider {
+    client: Arc<dyn HttpClient>,
+    base_url: Url,
+    index: Arc<OnceCell<RegistryIndex>>,
+}
+
+impl RegistryProvider {
+    /**
+        Creates a new registry provider for the registry at the given
+        base URL, such as `https://tools.example.com`, using the default HTTP client.
+
+        # Errors
+
+        - If the given URL is not a valid `https` URL.
+        - If the HTTP client could not be created.
+    */
+    #[cfg(feature = "reqwest-client")]
+    pub fn new(base_url: &Url) -> RegistryResult<Self> {
+        Self::new_with_client(default_http_client()?, base_url)
+    }
+
+    /**
+        Creates a new registry provider for the registry at
+        the given base URL, using the given HTTP client.
+
+        # Errors
+
+        - If the given URL is not a valid `https` URL.
+    */
+    pub fn new_with_client(client: Arc<dyn HttpClient>, base_url: &Url) -> RegistryResult<Self> {
+        if base_url.scheme() != "https" || !base_url.has_host() {
+            return Err(RegistryError::InvalidBaseUrl(base_url.to_string()));
+        }
+
+        // NOTE: Joining paths onto a URL without a trailing slash
+        // replaces its last segment, which is never what we want
+        let mut base_url = base_url.clone();
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+
+        Ok(Self {
+            client,
+            base_url,
+            index: Arc::new(OnceCell::new()),
+        })
+    }
+
+    /**
+        Uses the given HTTP client for all requests made by this provider.
+    */
+    #[must_use]
+    pub(crate) fn with_client(mut self, client: Arc<dyn HttpClient>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /**
+        Gets the base URL of the registry used by this provider.
+    */
+    #[must_use]
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    fn index_url(&self) -> Url {
+        self.base_url
+            .join(INDEX_FILE_NAME)
+            .expect("index file name is a valid relative URL")
+    }
+
+    async fn get(&self, url: &Url, accept: &str) -> RegistryResult<Vec<u8>> {
+        let request = HttpRequest::get(url.as_str())
+            .header("accept", accept)
+            .header("user-agent", user_agent());
+        let response = self.client.send(request).await?.error_for_status()?;
+        Ok(response.body)
+    }
+
+    async fn fetch_index(&self) -> RegistryResult<RegistryIndex> {
+        let body = self.get(&self.index_url(), "application/json").await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /**
+        Fetches the index of the registry, or returns the
+        index that was already fetched by this provider.
+
+        # Errors
+
+        - If the index could not be fetched or parsed.
+    */
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_index(&self) -> RegistryResult<&RegistryIndex> {
+        self.index
+            .get_or_try_init(|| async {
+                debug!(url = %self.index_url(), "fetching registry index");
+                self.fetch_index().await
+            })
+            .await
+    }
+
+    /**
+        Probes the registry to check its reachability, by fetching its index.
+
+        Registries are never authenticated, and have no rate limits.
+    */
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_status(&self) -> ProviderStatus {
+        let start = Instant::now();
+        let res = self.fetch_index().await;
+        let elapsed = start.elapsed();
+
+        let mut provider_status = ProviderStatus {
+            provider: ArtifactProvider::Registry,
+            latency: None,
+            auth: None,
+            rate_limit: None,
+            error: None,
+        };
+        match res {
+            Ok(_) => {
+                provider_status.latency = Some(elapsed);
+                provider_status.auth = Some(ProviderAuthStatus::Anonymous);
+            }
+            Err(e) => {
+                // NOTE: Any response at all means that the registry is reachable,
+                // even if it is an error such as a missing or invalid index
+                if status(&e).is_some() || matches!(e, RegistryError::Json(_)) {
+                    provider_status.latency = Some(elapsed);
+                }
+                provider_status.error = Some(e.to_string());
+            }
+        }
+        provider_status
+    }
+
+    /**
+        Fetches the latest stable release for a given tool.
+
+        # Errors
+
+        - If the tool has no stable releases in the registry.
+        - If the index could not be fetched.
+    */
+    pub async fn get_latest_release(&self, tool_id: &ToolId) -> RegistryResult<Release> {
+        self.get_recent_releases(tool_id)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| RegistryError::LatestReleaseNotFound(tool_id.clone().into()))
+    }
+
+    /**
+        Fetches all stable releases for a given tool, newest first.
+
+        # Errors
+
+        - If the index could not be fetched.
+    */
+    pub async fn get_recent_releases(&self, tool_id: &ToolId) -> RegistryResult<Vec<Release>> {
+        self.get_recent_releases_in_channel(tool_id, &ReleaseChannel::Stable)
+            .await
+    }
+
+    /**
+        Fetches all releases in the given channel for a given tool, newest first.
+
+        # Errors
+
+        - If the index could not be fetched.
+    */
+    pub async fn get_recent_releases_in_channel(
+        &self,
+        tool_id: &ToolId,
+        channel: &ReleaseChannel,
+    ) -> RegistryResult<Vec<Release>> {
+        let index = self.get_index().await?;
+        let releases = index
+            .versions(tool_id)
+            .into_iter()
+            .filter(|(version, _)| channel.contains(version))
+            .map(|(version, artifacts)| {
+                let tool_spec: ToolSpec = (tool_id.clone(), version).into();
+                release_from_artifacts(&tool_spec, artifacts)
+            })
+            .collect();
+        Ok(releases)
+    }
+
+    /**
+        Fetches a specific release for a given tool.
+
+        # Errors
+
+        - If the release is not in the registry.
+        - If the index could not be fetched.
+    */
+    pub async fn get_specific_release(&self, tool_spec: &ToolSpec) -> RegistryResult<Release> {
+        let index = self.get_index().await?;
+        index
+            .versions(tool_spec.id())
+            .into_iter()
+            .find(|(version, _)| version == tool_spec.version())
+            .map(|(_, artifacts)| release_from_artifacts(tool_spec, artifacts))
+            .ok_or_else(|| RegistryError::ReleaseNotFound(tool_spec.clone().into()))
+    }
+
+    /**
+        Downloads the contents of the given artifact, and
+        verifies them against the checksum in the registry index.
+
+        # Errors
+
+        - If the artifact is not listed in the registry index.
+        - If the artifact could not be downloaded.
+        - If the downloaded contents do not match the checksum.
+    */
+    #[instrument(skip(self, artifact), level = "debug")]
+    pub async fn download_artifact_contents(&self, artifact: &Artifact) -> RegistryResult<Vec<u8>> {
+        assert_eq!(
+            artifact.provider,
+            ArtifactProvider::Registry,
+            "artifact must be from a registry"
+        );
+
+        let url = artifact.url.as_ref().expect("registry artifacts have urls");
+        let index = self.get_index().await?;
+        let expected = index
+            .versions(artifact.tool_spec.id())
+            .into_iter()
+            .filter(|(version, _)| version == artifact.tool_spec.version())
+            .flat_map(|(_, artifacts)| artifacts)
+            .find(|a| &a.url == url)
+            .map(|a| a.sha256.trim().to_ascii_lowercase())
+            .ok_or_else(|| RegistryError::ArtifactNotFound(url.clone().into()))?;
+
+        debug!(%url, "downloading artifact contents");
+        let contents = self.get(url, "application/octet-stream").await?;
+
+        let actual = sha256_hex(&contents);
+        if actual != expected {
+            return Err(RegistryError::ChecksumMismatch {
+                url: url.clone().into(),
+                expected,
+                actual,
+            });
+        }
+
+        Ok(contents)
+    }
+}
+
+fn status(err: &RegistryError) -> Option<u16> {
+    if let RegistryError::Http(http_err) = err {
+        error_status(http_err)
+    } else {
+        None
+    }
+}
+
+fn release_from_artifacts(spec: &ToolSpec, artifacts: &[RegistryArtifact]) -> Release {
+    Release {
+        version: spec.version().clone(),
+        changelog: None,
+        artifacts: artifacts
+            .iter()
+            .map(|artifact| Artifact {
+                provider: ArtifactProvider::Registry,
+                digest: Some(ArtifactDigest::Sha256(
+                    artifact.sha256.trim().to_ascii_lowercase(),
+                )),
+                ..Artifact::from_url(&artifact.url, spec)
+            })
+            .collect(),
+    }
+}
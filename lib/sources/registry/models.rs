@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::tool::ToolId;
+
+/**
+    The index of a custom registry, as served at `<registry>/index.json`.
+
+    Lists every tool in the registry by its id, such as `author/name`, then
+    every version of the tool, and then every artifact for that version:
+
+    ```json
+    {
+        "tools": {
+            "author/name": {
+                "1.2.3": [
+                    {
+                        "url": "https://tools.example.com/name-1.2.3-linux-x86_64.zip",
+                        "sha256": "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                    }
+                ]
+            }
+        }
+    }
+    ```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct RegistryIndex {
+    /// All tools in the registry, by id, then version.
+    #[serde(default)]
+    pub tools: BTreeMap<String, BTreeMap<String, Vec<RegistryArtifact>>>,
+}
+
+impl RegistryIndex {
+    /**
+        Gets all versions of the given tool, and their artifacts, newest first.
+
+        Tool ids are not case sensitive, and versions that are
+        not valid semver versions are skipped.
+    */
+    #[must_use]
+    pub fn versions(&self, id: &ToolId) -> Vec<(Version, &[RegistryArtifact])> {
+        let key = format!("{}/{}", id.author(), id.name());
+        let Some(versions) = self
+            .tools
+            .iter()
+            .find(|(tool, _)| tool.eq_ignore_ascii_case(&key))
+            .map(|(_, versions)| versions)
+        else {
+            return Vec::new();
+        };
+
+        let mut versions = versions
+            .iter()
+            .filter_map(|(version, artifacts)| {
+                let version = version.trim_start_matches('v').parse::<Version>().ok()?;
+                Some((version, artifacts.as_slice()))
+            })
+            .collect::<Vec<_>>();
+        versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+        versions
+    }
+}
+
+/**
+    A single downloadable artifact in a [`RegistryIndex`].
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct RegistryArtifact {
+    /// The URL to download the artifact from, such as `https://tools.example.com/tool.zip`.
+    pub url: Url,
+    /// The SHA-256 checksum of the artifact, as a hexadecimal string.
+    pub sha256: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX: &str = r#"{
+        "tools": {
+            "Author/Name": {
+                "1.0.0": [{ "url": "https://example.com/name-1.0.0.zip", "sha256": "aa" }],
+                "v1.2.0": [{ "url": "https://example.com/name-1.2.0.zip", "sha256": "bb" }],
+                "1.3.0-beta.1": [],
+                "latest": []
+            }
+        }
+    }"#;
+
+    #[test]
+    fn versions_newest_first() {
+        let index = serde_json::from_str::<RegistryIndex>(INDEX).unwrap();
+        let id = "registry:author/name".parse::<ToolId>().unwrap();
+
+        let versions = index
+            .versions(&id)
+            .into_iter()
+            .map(|(version, _)| version.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec!["1.3.0-beta.1", "1.2.0", "1.0.0"]);
+
+        let (_, artifacts) = &index.versions(&id)[1];
+        assert_eq!(artifacts[0].sha256, "bb");
+
+        let other = "registry:author/other".parse::<ToolId>().unwrap();
+        assert!(index.versions(&other).is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+use serde_json::Error as JsonError;
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+    sources::http::HttpError,
+    tool::{ToolId, ToolSpec},
+};
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("invalid registry URL '{0}' - registries must use HTTPS")]
+    InvalidBaseUrl(String),
+    #[error("no custom registry is configured - set 'registry' in the policy manifest to use tool '{0}'")]
+    NotConfigured(Box<ToolId>),
+    #[error("no latest release was found for tool '{0}'")]
+    LatestReleaseNotFound(Box<ToolId>),
+    #[error("no release was found for tool '{0}'")]
+    ReleaseNotFound(Box<ToolSpec>),
+    #[error("the artifact at '{0}' is not listed in the registry index")]
+    ArtifactNotFound(Box<Url>),
+    #[error("checksum mismatch for '{url}' - expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        url: Box<Url>,
+        expected: String,
+        actual: String,
+    },
+    #[error("registries only contain tool artifacts, not {0}")]
+    NotSupported(&'static str),
+    #[error("HTTP error: {0}")]
+    Http(HttpError),
+    #[error("JSON error: {0}")]
+    Json(Box<JsonError>),
+}
+
+pub type RegistryResult<T> = Result<T, RegistryError>;
+
+// FUTURE: Figure out some way to reduce this boxing boilerplate
+
+impl From<HttpError> for RegistryError {
+    fn from(err: HttpError) -> Self {
+        RegistryError::Http(err)
+    }
+}
+
+impl From<JsonError> for RegistryError {
+    fn from(err: JsonError) -> Self {
+        RegistryError::Json(err.into())
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+/**
+    Timeout and retry policy for requests sent to a provider.
+
+    Only transient errors are retried, such as connection errors and server
+    errors. The first retry waits for `retry_backoff`, and every retry after
+    that waits about twice as long as the one before it.
+
+    Policies may be set for all providers, or for specific providers, in the
+    policy manifest - see [`PolicyManifest::request_policy`] for more information.
+
+    [`PolicyManifest::request_policy`]: crate::manifests::PolicyManifest::request_policy
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestPolicy {
+    /// The maximum time a single request may take, including reading its body.
+    pub timeout: Duration,
+    /// The number of times a request is retried after a transient error.
+    pub retries: u32,
+    /// The time to wait before the first retry.
+    pub retry_backoff: Duration,
+}
+
+impl RequestPolicy {
+    /**
+        The default timeout for a single request, which is 60 seconds.
+    */
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_mins(1);
+
+    /**
+        The default number of retries after a transient error, which is 3.
+    */
+    pub const DEFAULT_RETRIES: u32 = 3;
+
+    /**
+        The default time to wait before the first retry, which is 1 second.
+    */
+    pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Self::DEFAULT_TIMEOUT,
+            retries: Self::DEFAULT_RETRIES,
+            retry_backoff: Self::DEFAULT_RETRY_BACKOFF,
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use std::{collections::BTreeMap, sync::Arc, time::Instant};
+
+use chrono::Utc;
+use semver::Version;
+use tracing::{debug, instrument};
+use url::Url;
+
+use crate::tool::{ReleaseChannel, ToolId, ToolSpec};
+
+use super::{
+    http::{error_status, user_agent, HttpClient, HttpRequest, HttpResponse},
+    Artifact, ArtifactProvider, ProviderAuthStatus, ProviderStatus, Release,
+};
+
+#[cfg(feature = "reqwest-client")]
+use super::http::default_http_client;
+
+pub mod models;
+mod result;
+mod signing;
+
+pub use self::models::{S3ListPage, S3Object};
+pub use self::result::{S3Error, S3Result};
+pub use self::signing::S3Credentials;
+
+use self::signing::{sign_request, uri_encode};
+
+/**
+    The region used for S3 buckets when none is configured.
+
+    Most S3-compatible services, such as `MinIO`, accept any region,
+    and Cloudflare R2 also accepts `auto` in place of a region.
+*/
+pub const DEFAULT_REGION: &str = "us-east-1";
+
+/**
+    A provider for tools stored in an S3-compatible bucket,
+    such as Amazon S3, `MinIO`, or Cloudflare R2.
+
+    Buckets are given as path-style URLs, such as `https://s3.example.com/bucket`,
+    optionally followed by a prefix that all keys for tools start with.
+
+    Tools use ids such as `s3:author/name`, and their artifacts are stored
+    with keys following the convention `<prefix>/<author>/<name>/<version>/<file>`,
+    where the version may optionally start with a `v`. The name and format
+    of each artifact are taken from its file name, the same as for tools
+    from direct URLs, and files without a known format are raw binaries.
+
+    Requests are signed using AWS Signature Version 4 when the provider has
+    credentials, and are sent without authentication for public buckets otherwise.
+*/
+#[derive(Debug, Clone)]
+pub struct S3Provider {
+    client: Arc<dyn HttpClient>,
+    endpoint: String,
+    bucket: String,
+    prefix: String,
+    region: String,
+    credentials: Option<S3Credentials>,
+}
+
+impl S3Provider {
+    /**
+        Creates a new provider for the bucket at the given
+        path-style URL, in the given region, using the default HTTP client.
+
+        # Errors
+
+        - If the given URL is not a valid bucket URL.
+        - If the HTTP client could not be created.
+    */
+    #[cfg(feature = "reqwest-client")]
+    pub fn new(bucket_url: &Url, region: &str) -> S3Result<Self> {
+        Self::new_with_client(default_http_client()?, bucket_url, region)
+    }
+
+    /**
+        Creates a new provider for the bucket at the given
+        path-style URL, in the given region, using the given HTTP client.
+
+        # Errors
+
+        - If the given URL is not a valid bucket URL.
+    */
+    pub fn new_with_client(
+        client: Arc<dyn HttpClient>,
+        bucket_url: &Url,
+        region: &str,
+    ) -> S3Result<Self> {
+        let invalid = || S3Error::InvalidBucketUrl(bucket_url.to_string());
+        if !matches!(bucket_url.scheme(), "http" | "https") || !bucket_url.has_host() {
+            return Err(invalid());
+        }
+
+        let mut segments = bucket_url
+            .path_segments()
+            .ok_or_else(invalid)?
+            .filter(|segment| !segment.is_empty());
+        let bucket = segments.next().ok_or_else(invalid)?.to_string();
+        let prefix = segments.fold(String::new(), |mut prefix, segment| {
+            prefix.push_str(segment);
+            prefix.push('/');
+            prefix
+        });
+
+        let region = region.trim();
+        Ok(Self {
+            client,
+            endpoint: bucket_url.origin().ascii_serialization(),
+            bucket,
+            prefix,
+            region: if region.is_empty() {
+                DEFAULT_REGION.to_string()
+            } else {
+                region.to_string()
+            },
+            credentials: None,
+        })
+    }
+
+    /**
+        Uses the given HTTP client for all requests made by this provider.
+    */
+    #[must_use]
+    pub(crate) fn with_client(mut self, client: Arc<dyn HttpClient>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /**
+        Signs all requests to the bucket using the given credentials.
+    */
+    #[must_use]
+    pub fn with_credentials(mut self, credentials: S3Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /**
+        Gets the name of the bucket used by this provider.
+    */
+    #[must_use]
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /**
+        Gets the region of the bucket used by this provider.
+    */
+    #[must_use]
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    fn bucket_url(&self) -> String {
+        format!("{}/{}", self.endpoint, uri_encode(&self.bucket, true))
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        format!("{}/{}", self.bucket_url(), uri_encode(key, false))
+    }
+
+    fn list_url(&self, prefix: &str, continuation_token: Option<&str>, max_keys: usize) -> String {
+        let mut url = format!(
+            "{}?list-type=2&max-keys={max_keys}&prefix={}",
+            self.bucket_url(),
+            uri_encode(prefix, true)
+        );
+        if let Some(token) = continuation_token {
+            url.push_str("&continuation-token=");
+            url.push_str(&uri_encode(token, true));
+        }
+        url
+    }
+
+    fn tool_prefix(&self, id: &ToolId) -> String {
+        format!("{}{}/{}/", self.prefix, id.author(), id.name())
+    }
+
+    async fn get(&self, url: String) -> S3Result<HttpResponse> {
+        let mut request = HttpRequest::get(url).header("user-agent", user_agent());
+        if let Some(credentials) = &self.credentials {
+            request = sign_request(request, credentials, &self.region, Utc::now());
+        }
+        Ok(self.client.send(request).await?.error_for_status()?)
+    }
+
+    async fn list_page(
+        &self,
+        prefix: &str,
+        continuation_token: Option<&str>,
+        max_keys: usize,
+    ) -> S3Result<S3ListPage> {
+        let response = self
+            .get(self.list_url(prefix, continuation_token, max_keys))
+            .await?;
+        let body = String::from_utf8(response.body).map_err(|_| S3Error::InvalidListing)?;
+        S3ListPage::from_xml(&body).ok_or(S3Error::InvalidListing)
+    }
+
+    /**
+        Lists all objects in the bucket with keys starting with the given prefix.
+
+        # Errors
+
+        - If the objects could not be listed.
+    */
+    #[instrument(skip(self), level = "debug")]
+    pub async fn list_objects(&self, prefix: &str) -> S3Result<Vec<S3Object>> {
+        let mut objects = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let page = self
+                .list_page(prefix, continuation_token.as_deref(), 1000)
+                .await?;
+            objects.extend(page.objects);
+            match page.next_continuation_token {
+                Some(token) if page.is_truncated => continuation_token = Some(token),
+                _ => break,
+            }
+        }
+        Ok(objects)
+    }
+
+    /*
+        Lists all releases of the given tool in the bucket, newest first,
+        grouping objects by the version in their key - keys that do not
+        follow the convention, or that have an invalid version, are ignored.
+    */
+    async fn list_releases(&self, tool_id: &ToolId) -> S3Result<Vec<Release>> {
+        let tool_prefix = self.tool_prefix(tool_id);
+        let objects = self.list_objects(&tool_prefix).await?;
+
+        let mut versions = BTreeMap::<Version, Vec<S3Object>>::new();
+        for object in objects {
+            let Some(rest) = object.key.strip_prefix(&tool_prefix) else {
+                continue;
+            };
+            let Some((version, file_name)) = rest.split_once('/') else {
+                continue;
+            };
+            if file_name.is_empty() || file_name.contains('/') {
+                continue;
+            }
+            let version = version.strip_prefix('v').unwrap_or(version);
+            if let Ok(version) = Version::parse(version) {
+                versions.entry(version).or_default().push(object);
+            }
+        }
+
+        let releases = versions
+            .into_iter()
+            .rev()
+            .map(|(version, objects)| {
+                let tool_spec: ToolSpec = (tool_id.clone(), version).into();
+                self.release_from_objects(&tool_spec, &objects)
+            })
+            .collect();
+        Ok(releases)
+    }
+
+    fn release_from_objects(&self, spec: &ToolSpec, objects: &[S3Object]) -> Release {
+        Release {
+            version: spec.version().clone(),
+            changelog: None,
+            artifacts: objects
+                .iter()
+                .filter_map(|object| {
+                    let url = Url::parse(&self.object_url(&object.key)).ok()?;
+                    Some(Artifact {
+                        provider: ArtifactProvider::S3,
+                        size: object.size,
+                        ..Artifact::from_url(&url, spec)
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /**
+        Verifies that the current credentials are valid, by listing the bucket.
+
+        Returns `true` if the credentials are valid, `false` if they are not.
+
+        Always returns `false` if the provider has no credentials.
+
+        # Errors
+
+        - If the request to the bucket failed.
+    */
+    pub async fn verify_authentication(&self) -> S3Result<bool> {
+        if self.credentials.is_none() {
+            return Ok(false);
+        }
+        match self.list_page(&self.prefix, None, 1).await {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(status(&e), Some(401 | 403)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /**
+        Probes the bucket to check its reachability, and if the
+        current credentials are valid, by listing a single object.
+
+        S3-compatible services do not report rate limits, so the
+        returned status never contains a rate limit.
+    */
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_status(&self) -> ProviderStatus {
+        let start = Instant::now();
+        let res = self.list_page(&self.prefix, None, 1).await;
+        let elapsed = start.elapsed();
+
+        let mut provider_status = ProviderStatus {
+            provider: ArtifactProvider::S3,
+            latency: None,
+            auth: None,
+            rate_limit: None,
+            error: None,
+        };
+        match res {
+            Ok(_) => {
+                provider_status.latency = Some(elapsed);
+                provider_status.auth = Some(if self.credentials.is_some() {
+                    ProviderAuthStatus::Valid
+                } else {
+                    ProviderAuthStatus::Anonymous
+                });
+            }
+            Err(e) => {
+                let status = status(&e);
+                if status.is_some() || matches!(e, S3Error::InvalidListing) {
+                    provider_status.latency = Some(elapsed);
+                }
+                if self.credentials.is_some() && matches!(status, Some(401 | 403)) {
+                    provider_status.auth = Some(ProviderAuthStatus::Invalid);
+                }
+                provider_status.error = Some(e.to_string());
+            }
+        }
+        provider_status
+    }
+
+    /**
+        Fetches the latest stable release for a given tool.
+
+        # Errors
+
+        - If the tool has no stable releases in the bucket.
+        - If the objects in the bucket could not be listed.
+    */
+    pub async fn get_latest_release(&self, tool_id: &ToolId) -> S3Result<Release> {
+        self.get_recent_releases(tool_id)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| S3Error::LatestReleaseNotFound(tool_id.clone().into()))
+    }
+
+    /**
+        Fetches all stable releases for a given tool, newest first.
+
+        # Errors
+
+        - If the objects in the bucket could not be listed.
+    */
+    pub async fn get_recent_releases(&self, tool_id: &ToolId) -> S3Result<Vec<Release>> {
+        self.get_recent_releases_in_channel(tool_id, &ReleaseChannel::Stable)
+            .await
+    }
+
+    /**
+        Fetches all releases in the given channel for a given tool, newest first.
+
+        # Errors
+
+        - If the objects in the bucket could not be listed.
+    */
+    pub async fn get_recent_releases_in_channel(
+        &self,
+        tool_id: &ToolId,
+        channel: &ReleaseChannel,
+    ) -> S3Result<Vec<Release>> {
+        let releases = self.list_releases(tool_id).await?;
+        Ok(releases
+            .into_iter()
+            .filter(|release| channel.contains(&release.version))
+            .collect())
+    }
+
+    /**
+        Fetches a specific release for a given tool.
+
+        # Errors
+
+        - If the release is not in the bucket.
+        - If the objects in the bucket could not be listed.
+    */
+    pub async fn get_specific_release(&self, tool_spec: &ToolSpec) -> S3Result<Release> {
+        self.list_releases(tool_spec.id())
+            .await?
+            .into_iter()
+            .find(|release| &release.version == tool_spec.version())
+            .ok_or_else(|| S3Error::ReleaseNotFound(tool_spec.clone().into()))
+    }
+
+    /**
+        Downloads the contents of the given artifact.
+
+        # Errors
+
+        - If the artifact could not be downloaded.
+    */
+    #[instrument(skip(self, artifact), level = "debug")]
+    pub async fn download_artifact_contents(&self, artifact: &Artifact) -> S3Result<Vec<u8>> {
+        assert_eq!(
+            artifact.provider,
+            ArtifactProvider::S3,
+            "artifact must be from an S3 bucket"
+        );
+
+        let url = artifact.url.as_ref().expect("S3 artifacts have urls");
+        debug!(%url, "downloading artifact contents");
+        let response = self.get(url.to_string()).await?;
+        Ok(response.body)
+    }
+}
+
+fn status(err: &S3Error) -> Option<u16> {
+    if let S3Error::Http(http_err) = err {
+        error_status(http_err)
+    } else {
+        None
+    }
+}
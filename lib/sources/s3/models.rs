@@ -0,0 +1,158 @@
+/**
+    An object in an S3-compatible bucket, as listed by `ListObjectsV2`.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Object {
+    pub key: String,
+    pub size: Option<u64>,
+}
+
+/**
+    A single page of objects in an S3-compatible bucket, as returned by `ListObjectsV2`.
+
+    Only the parts of the response that Rokit uses are kept.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct S3ListPage {
+    pub objects: Vec<S3Object>,
+    pub is_truncated: bool,
+    pub next_continuation_token: Option<String>,
+}
+
+impl S3ListPage {
+    /**
+        Parses a page from the XML body of a `ListObjectsV2` response.
+
+        Returns `None` if the body is not a `ListBucketResult` document.
+    */
+    #[must_use]
+    pub fn from_xml(xml: &str) -> Option<Self> {
+        let result = elements(xml, "ListBucketResult").next()?;
+        let objects = elements(result, "Contents")
+            .filter_map(|contents| {
+                let key = elements(contents, "Key").next()?;
+                let size = elements(contents, "Size")
+                    .next()
+                    .and_then(|size| size.trim().parse().ok());
+                Some(S3Object {
+                    key: unescape(key),
+                    size,
+                })
+            })
+            .collect();
+        let is_truncated = elements(result, "IsTruncated")
+            .next()
+            .is_some_and(|s| s.trim().eq_ignore_ascii_case("true"));
+        let next_continuation_token = elements(result, "NextContinuationToken")
+            .next()
+            .map(unescape)
+            .filter(|token| !token.is_empty());
+        Some(Self {
+            objects,
+            is_truncated,
+            next_continuation_token,
+        })
+    }
+}
+
+/*
+    Finds the contents of all elements with the given tag name, in order.
+
+    This is not a general XML parser - it does not handle nested elements
+    with the same name, comments, or CDATA, none of which are ever present
+    in the listings returned by S3-compatible services.
+*/
+fn elements<'a>(xml: &'a str, tag: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let open = format!("<{tag}");
+    let close = format!("</{tag}>");
+    let mut rest = xml;
+    std::iter::from_fn(move || loop {
+        let start = rest.find(&open)?;
+        let after_name = &rest[start + open.len()..];
+        // NOTE: Make sure this is not a different tag starting with the same
+        // name, such as <Contents> when looking for <Content>
+        let Some(end_of_tag) = after_name.find('>') else {
+            rest = "";
+            return None;
+        };
+        if !after_name.starts_with(['>', ' ', '\t', '\r', '\n', '/']) {
+            rest = after_name;
+            continue;
+        }
+        if after_name[..end_of_tag].ends_with('/') {
+            rest = &after_name[end_of_tag + 1..];
+            return Some("");
+        }
+        let inner = &after_name[end_of_tag + 1..];
+        let Some(inner_end) = inner.find(&close) else {
+            rest = "";
+            return None;
+        };
+        rest = &inner[inner_end + close.len()..];
+        return Some(&inner[..inner_end]);
+    })
+}
+
+fn unescape(s: &str) -> String {
+    s.trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+    <Name>tools</Name>
+    <Prefix>team/tool/</Prefix>
+    <KeyCount>2</KeyCount>
+    <IsTruncated>true</IsTruncated>
+    <NextContinuationToken>abc&amp;123</NextContinuationToken>
+    <Contents>
+        <Key>team/tool/1.0.0/tool-1.0.0-linux-x86_64.zip</Key>
+        <Size>1024</Size>
+    </Contents>
+    <Contents>
+        <Key>team/tool/1.1.0/tool &amp; friends.zip</Key>
+        <Size>2048</Size>
+    </Contents>
+</ListBucketResult>"#;
+
+    #[test]
+    fn parse_listing() {
+        let page = S3ListPage::from_xml(LISTING).unwrap();
+        assert!(page.is_truncated);
+        assert_eq!(page.next_continuation_token.as_deref(), Some("abc&123"));
+        assert_eq!(
+            page.objects,
+            vec![
+                S3Object {
+                    key: String::from("team/tool/1.0.0/tool-1.0.0-linux-x86_64.zip"),
+                    size: Some(1024),
+                },
+                S3Object {
+                    key: String::from("team/tool/1.1.0/tool & friends.zip"),
+                    size: Some(2048),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_empty_listing() {
+        let page = S3ListPage::from_xml(
+            "<ListBucketResult><IsTruncated>false</IsTruncated><KeyCount>0</KeyCount></ListBucketResult>",
+        )
+        .unwrap();
+        assert_eq!(page, S3ListPage::default());
+        assert_eq!(
+            S3ListPage::from_xml("<Error><Code>NoSuchBucket</Code></Error>"),
+            None
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use thiserror::Error;
+
+use crate::{
+    sources::http::HttpError,
+    tool::{ToolId, ToolSpec},
+};
+
+#[derive(Debug, Error)]
+pub enum S3Error {
+    #[error(
+        "invalid S3 bucket URL '{0}' - expected a URL such as 'https://s3.example.com/bucket'"
+    )]
+    InvalidBucketUrl(String),
+    #[error(
+        "no S3 bucket is configured - set 's3_bucket' in the policy manifest to use tool '{0}'"
+    )]
+    NotConfigured(Box<ToolId>),
+    #[error("no latest release was found for tool '{0}'")]
+    LatestReleaseNotFound(Box<ToolId>),
+    #[error("no release was found for tool '{0}'")]
+    ReleaseNotFound(Box<ToolSpec>),
+    #[error("the S3 bucket returned an invalid object listing")]
+    InvalidListing,
+    #[error("S3 buckets only contain tool artifacts, not {0}")]
+    NotSupported(&'static str),
+    #[error("HTTP error: {0}")]
+    Http(HttpError),
+}
+
+pub type S3Result<T> = Result<T, S3Error>;
+
+impl From<HttpError> for S3Error {
+    fn from(err: HttpError) -> Self {
+        S3Error::Http(err)
+    }
+}
@@ -1,25 +1,82 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, slice, sync::Arc};
+
+#[cfg(feature = "github")]
+use std::borrow::Cow;
+
+use semver::Version;
+use tracing::debug;
+use url::Url;
 
 use crate::{
-    result::RokitResult,
-    tool::{ToolId, ToolSpec},
+    resolve::ResolveConstraints,
+    result::{RokitError, RokitResult},
+    storage::{AssetMetadataCache, HttpCache},
+    tool::{ReleaseChannel, ToolId, ToolSpec},
 };
 
-use super::{github::GithubProvider, Artifact, ArtifactProvider, Release};
+use super::{
+    artifact::parse_digest_file,
+    crates::{CratesError, CratesProvider},
+    custom::CustomProvider,
+    git::{AssetTemplate, GitError, GitProvider},
+    http::{HttpClient, HttpRequest},
+    mirror::download_from_mirrors,
+    npm::{NpmError, NpmProvider},
+    registry::{RegistryError, RegistryProvider},
+    s3::{S3Credentials, S3Error, S3Provider},
+    url_source::download_url_artifact,
+    Artifact, ArtifactDigest, ArtifactProvider, Downloader, ProviderStatus, Release, ReleaseNotes,
+    ToolProvenance,
+};
+
+#[cfg(feature = "github")]
+use super::github::{GithubProvider, ReleaseFilter};
+
+#[cfg(feature = "gitlab")]
+use std::env::var;
+
+#[cfg(feature = "gitlab")]
+use super::gitlab::{GitlabProvider, BASE_URL_ENV_VAR as GITLAB_URL_ENV_VAR};
+
+#[cfg(feature = "reqwest-client")]
+use super::http::default_http_client;
 
 /**
     A source for artifacts.
 
     Provides high-level access abstracting over individual providers such as GitHub, ...
+
+    All providers share a single [`Downloader`], which limits how many
+    requests may be in flight at once, across all of the providers.
 */
 #[derive(Debug, Clone)]
 pub struct ArtifactSource {
+    client: Arc<dyn HttpClient>,
+    downloader: Downloader,
+    provider_clients: HashMap<ArtifactProvider, Arc<dyn HttpClient>>,
+    #[cfg(feature = "github")]
     github: GithubProvider,
+    #[cfg(feature = "github")]
+    github_hosts: HashMap<String, GithubProvider>,
+    #[cfg(feature = "gitlab")]
+    gitlab: GitlabProvider,
+    crates: CratesProvider,
+    npm: NpmProvider,
+    git: GitProvider,
+    git_templates: HashMap<ToolId, AssetTemplate>,
+    registry: Option<RegistryProvider>,
+    s3: Option<S3Provider>,
+    s3_credentials: Option<S3Credentials>,
+    mirrors: HashMap<ArtifactProvider, Vec<Url>>,
+    tool_mirrors: HashMap<ToolId, Url>,
+    http_cache: Option<HttpCache>,
+    asset_metadata: Option<AssetMetadataCache>,
+    custom_providers: HashMap<String, Arc<dyn CustomProvider>>,
 }
 
 impl ArtifactSource {
     /**
-        Creates a new artifact source.
+        Creates a new artifact source, using the default HTTP client.
 
         This source is unauthenticated and may be rate limited and/or unable to access
         private resources. To authenticate using auth tokens, use `new_authenticated`.
@@ -28,13 +85,13 @@ impl ArtifactSource {
 
         - If the artifact source could not be created.
     */
+    #[cfg(feature = "reqwest-client")]
     pub fn new() -> RokitResult<Self> {
-        let github = GithubProvider::new()?;
-        Ok(Self { github })
+        Self::new_with_client(default_http_client()?)
     }
 
     /**
-        Creates a new authenticated artifact source.
+        Creates a new authenticated artifact source, using the default HTTP client.
 
         This source is authenticated and can access private resources.
 
@@ -42,12 +99,480 @@ impl ArtifactSource {
 
         - If the artifact source could not be created.
     */
+    #[cfg(feature = "reqwest-client")]
     pub fn new_authenticated(auth: &HashMap<ArtifactProvider, String>) -> RokitResult<Self> {
+        Self::new_authenticated_with_client(default_http_client()?, auth)
+    }
+
+    /**
+        Creates a new artifact source, using the given HTTP client.
+
+        See [`ArtifactSource::new`] for more information.
+
+        # Errors
+
+        - If the artifact source could not be created.
+    */
+    pub fn new_with_client(client: Arc<dyn HttpClient>) -> RokitResult<Self> {
+        Self::new_authenticated_with_client(client, &HashMap::new())
+    }
+
+    /**
+        Creates a new authenticated artifact source, using the given HTTP client.
+
+        The client is wrapped in a [`Downloader`] with the default concurrency,
+        see [`ArtifactSource::new_authenticated_with_downloader`] to change it.
+
+        See [`ArtifactSource::new_authenticated`] for more information.
+
+        # Errors
+
+        - If the artifact source could not be created.
+    */
+    pub fn new_authenticated_with_client(
+        client: Arc<dyn HttpClient>,
+        auth: &HashMap<ArtifactProvider, String>,
+    ) -> RokitResult<Self> {
+        Self::new_authenticated_with_downloader(Downloader::new(client), auth)
+    }
+
+    /**
+        Creates a new authenticated artifact source, using the given downloader.
+
+        See [`ArtifactSource::new_authenticated`] for more information.
+
+        # Errors
+
+        - If the artifact source could not be created.
+    */
+    pub fn new_authenticated_with_downloader(
+        downloader: Downloader,
+        auth: &HashMap<ArtifactProvider, String>,
+    ) -> RokitResult<Self> {
+        let client: Arc<dyn HttpClient> = Arc::new(downloader.clone());
+        #[cfg(feature = "github")]
         let github = match auth.get(&ArtifactProvider::GitHub) {
-            Some(token) => GithubProvider::new_authenticated(token)?,
-            None => GithubProvider::new()?,
+            Some(token) => GithubProvider::new_authenticated_with_client(client.clone(), token)?,
+            None => GithubProvider::new_with_client(client.clone())?,
+        };
+        #[cfg(feature = "gitlab")]
+        let gitlab = match auth.get(&ArtifactProvider::GitLab) {
+            Some(token) => GitlabProvider::new_authenticated_with_client(client.clone(), token)?,
+            None => GitlabProvider::new_with_client(client.clone())?,
+        };
+        let crates = CratesProvider::new_with_client(client.clone());
+        let npm = NpmProvider::new_with_client(client.clone());
+        let git = GitProvider::new_with_client(client.clone());
+        // NOTE: Credentials are only used once a bucket is configured, and
+        // may also come from the standard AWS environment variables
+        let s3_credentials = auth
+            .get(&ArtifactProvider::S3)
+            .and_then(|token| S3Credentials::from_token(token))
+            .or_else(S3Credentials::from_env);
+        Ok(Self {
+            client,
+            downloader,
+            provider_clients: HashMap::new(),
+            #[cfg(feature = "github")]
+            github,
+            #[cfg(feature = "github")]
+            github_hosts: HashMap::new(),
+            #[cfg(feature = "gitlab")]
+            gitlab,
+            crates,
+            npm,
+            git,
+            git_templates: HashMap::new(),
+            registry: None,
+            s3: None,
+            s3_credentials,
+            mirrors: HashMap::new(),
+            tool_mirrors: HashMap::new(),
+            http_cache: None,
+            asset_metadata: None,
+            custom_providers: HashMap::new(),
+        })
+    }
+
+    /**
+        Uses the custom registry at the given base URL for tools
+        from the registry, such as `registry:author/name`.
+
+        Without a registry, tools from the registry can not be used.
+
+        # Errors
+
+        - If the given URL is not a valid `https` URL.
+    */
+    pub fn with_registry(mut self, base_url: &Url) -> RokitResult<Self> {
+        self.registry = Some(RegistryProvider::new_with_client(
+            self.client_for(ArtifactProvider::Registry).clone(),
+            base_url,
+        )?);
+        Ok(self)
+    }
+
+    /**
+        Uses the S3-compatible bucket at the given path-style URL, in
+        the given region, for tools from buckets, such as `s3:author/name`.
+
+        Requests to the bucket are signed using the S3 credentials from the
+        auth tokens of this source, or from the standard AWS environment
+        variables, if any - see [`S3Credentials`] for more information.
+
+        Without a bucket, tools from buckets can not be used.
+
+        # Errors
+
+        - If the given URL is not a valid bucket URL.
+    */
+    pub fn with_s3_bucket(mut self, bucket_url: &Url, region: &str) -> RokitResult<Self> {
+        let client = self.client_for(ArtifactProvider::S3).clone();
+        let mut provider = S3Provider::new_with_client(client, bucket_url, region)?;
+        if let Some(credentials) = &self.s3_credentials {
+            provider = provider.with_credentials(credentials.clone());
+        }
+        self.s3 = Some(provider);
+        Ok(self)
+    }
+
+    /**
+        Uses the given HTTP client for all requests to the given provider,
+        instead of the client of this source - such as a client with
+        other timeouts or retries.
+
+        The given client still shares the limit of requests in flight at once
+        with all other providers. Custom providers use their own clients,
+        and are not affected - see [`ArtifactSource::with_custom_provider`].
+    */
+    #[must_use]
+    pub fn with_provider_client(
+        mut self,
+        provider: ArtifactProvider,
+        client: Arc<dyn HttpClient>,
+    ) -> Self {
+        let client: Arc<dyn HttpClient> = Arc::new(self.downloader.with_client(client));
+        match provider {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => {
+                self.github = self.github.with_client(client.clone());
+                self.github_hosts = self
+                    .github_hosts
+                    .into_iter()
+                    .map(|(host, provider)| (host, provider.with_client(client.clone())))
+                    .collect();
+            }
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => self.gitlab = self.gitlab.with_client(client.clone()),
+            ArtifactProvider::Registry => {
+                self.registry = self.registry.map(|r| r.with_client(client.clone()));
+            }
+            ArtifactProvider::S3 => self.s3 = self.s3.map(|s| s.with_client(client.clone())),
+            ArtifactProvider::Crates => self.crates = self.crates.with_client(client.clone()),
+            ArtifactProvider::Npm => self.npm = self.npm.with_client(client.clone()),
+            ArtifactProvider::Git => self.git = self.git.with_client(client.clone()),
+            _ => {}
+        }
+        self.provider_clients.insert(provider, client);
+        self
+    }
+
+    /**
+        Makes providers that access the network without using an HTTP
+        client, such as the git provider, fail instead of doing so.
+
+        Used together with a client that never accesses the
+        network, see [`Home::offline_artifact_source`].
+
+        [`Home::offline_artifact_source`]: crate::storage::Home::offline_artifact_source
+    */
+    #[must_use]
+    pub fn offline(mut self) -> Self {
+        self.git = self.git.offline();
+        self
+    }
+
+    /**
+        Gets the HTTP client to use for requests to the given provider, which
+        is the client of this source, unless another one was given for it.
+    */
+    fn client_for(&self, provider: ArtifactProvider) -> &Arc<dyn HttpClient> {
+        self.provider_clients.get(&provider).unwrap_or(&self.client)
+    }
+
+    /**
+        Downloads artifacts from the given provider from the given mirrors
+        first, trying them in order, before falling back to the provider itself.
+
+        Only GitHub and GitLab artifacts are downloaded from mirrors - registry
+        artifacts are verified against their index, and URL artifacts have no
+        single origin to mirror. Mirrors for other providers are ignored.
+    */
+    #[must_use]
+    pub fn with_mirrors(mut self, provider: ArtifactProvider, mirrors: Vec<Url>) -> Self {
+        if matches!(
+            provider,
+            ArtifactProvider::GitHub | ArtifactProvider::GitLab
+        ) {
+            self.mirrors.insert(provider, mirrors);
+        }
+        self
+    }
+
+    /**
+        Downloads artifacts of the given tool from the given mirror first,
+        before falling back to the provider of the tool itself - any mirrors
+        for the provider, added using [`ArtifactSource::with_mirrors`], are
+        not tried for the tool. Adding a mirror for a tool that already
+        has one replaces it.
+
+        Unlike mirrors for providers, these are chosen for a single tool, and so
+        are used for any artifact with a download URL, from any provider or host.
+    */
+    #[must_use]
+    pub fn with_tool_mirror(mut self, id: ToolId, mirror: Url) -> Self {
+        self.tool_mirrors.insert(id, mirror);
+        self
+    }
+
+    /**
+        Creates the URLs of release assets for the given tool, which must be
+        from a git repository, using the given template instead of the default
+        template - see [`GitProvider`] for more information. Adding a template
+        for a tool that already has one replaces it.
+    */
+    #[must_use]
+    pub fn with_git_asset_template(mut self, id: ToolId, template: AssetTemplate) -> Self {
+        self.git_templates.insert(id, template);
+        self
+    }
+
+    fn git_template_for(&self, id: &ToolId) -> AssetTemplate {
+        self.git_templates.get(id).cloned().unwrap_or_default()
+    }
+
+    /**
+        Uses the API at the given URL for the given provider, instead of its
+        public instance - such as an internal API gateway, or a mock server.
+
+        Only GitHub and GitLab have APIs, other providers are ignored. For GitLab,
+        the `ROKIT_GITLAB_URL` environment variable takes precedence, if set.
+
+        # Errors
+
+        - If the given URL is not a valid `http` or `https` URL.
+    */
+    #[cfg_attr(
+        not(any(feature = "github", feature = "gitlab")),
+        allow(unused_variables, unused_mut)
+    )]
+    pub fn with_api_url(mut self, provider: ArtifactProvider, url: &Url) -> RokitResult<Self> {
+        match provider {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => self.github = self.github.with_api_url(url.as_str())?,
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab
+                if !var(GITLAB_URL_ENV_VAR).is_ok_and(|v| !v.trim().is_empty()) =>
+            {
+                self.gitlab = self.gitlab.with_base_url(url.as_str())?;
+            }
+            _ => {}
+        }
+        Ok(self)
+    }
+
+    /**
+        Downloads artifacts for the given provider from the given URL,
+        instead of from its public instance, keeping the path of each
+        download - unlike mirrors, this has no fallback, and is authenticated.
+
+        Only GitHub and GitLab artifacts may be downloaded from another URL.
+
+        # Errors
+
+        - If the given URL is not a valid `http` or `https` URL.
+    */
+    #[cfg_attr(
+        not(any(feature = "github", feature = "gitlab")),
+        allow(unused_variables, unused_mut)
+    )]
+    pub fn with_download_url(mut self, provider: ArtifactProvider, url: &Url) -> RokitResult<Self> {
+        match provider {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => {
+                self.github = self.github.with_download_url(url.as_str())?;
+            }
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => {
+                self.gitlab = self.gitlab.with_download_url(url.as_str())?;
+            }
+            _ => {}
+        }
+        Ok(self)
+    }
+
+    /**
+        Uses the given HTTP cache for release metadata from providers that
+        support it, which currently is GitHub, including any custom hosts.
+
+        See [`HttpCache`] for more information.
+    */
+    #[must_use]
+    pub fn with_http_cache(mut self, cache: HttpCache) -> Self {
+        #[cfg(feature = "github")]
+        {
+            self.github = self.github.with_http_cache(cache.clone());
+            for provider in self.github_hosts.values_mut() {
+                *provider = provider.clone().with_http_cache(cache.clone());
+            }
+        }
+        self.http_cache = Some(cache);
+        self
+    }
+
+    /**
+        Uses the given cache for metadata about artifacts, such as their
+        sizes, when those are not already known from the provider.
+
+        See [`AssetMetadataCache`] for more information.
+    */
+    #[must_use]
+    pub fn with_asset_metadata_cache(mut self, cache: AssetMetadataCache) -> Self {
+        self.asset_metadata = Some(cache);
+        self
+    }
+
+    /**
+        Uses the given filter to decide which GitHub releases are considered
+        when resolving the latest release for a tool, for all GitHub hosts.
+
+        See [`ReleaseFilter`] for more information.
+    */
+    #[cfg(feature = "github")]
+    #[must_use]
+    pub fn with_github_release_filter(mut self, filter: ReleaseFilter) -> Self {
+        for provider in self.github_hosts.values_mut() {
+            *provider = provider.clone().with_release_filter(filter.clone());
+        }
+        self.github = self.github.with_release_filter(filter);
+        self
+    }
+
+    fn registry_for(&self, id: &ToolId) -> RokitResult<&RegistryProvider> {
+        self.registry
+            .as_ref()
+            .ok_or_else(|| RegistryError::NotConfigured(id.clone().into()).into())
+    }
+
+    fn s3_for(&self, id: &ToolId) -> RokitResult<&S3Provider> {
+        self.s3
+            .as_ref()
+            .ok_or_else(|| S3Error::NotConfigured(id.clone().into()).into())
+    }
+
+    /**
+        Registers a third-party provider for the given host, which is then
+        used for all tools with ids such as `custom:<host>/author/name`.
+
+        Registering a provider for a host that already has one replaces it.
+
+        See [`CustomProvider`] for more information.
+    */
+    #[must_use]
+    pub fn with_custom_provider(
+        mut self,
+        host: impl AsRef<str>,
+        provider: Arc<dyn CustomProvider>,
+    ) -> Self {
+        let host = host.as_ref().trim().to_ascii_lowercase();
+        self.custom_providers.insert(host, provider);
+        self
+    }
+
+    fn custom_for(&self, id: &ToolId) -> RokitResult<&dyn CustomProvider> {
+        let host = id.host().unwrap_or_default();
+        self.custom_providers
+            .get(&host.to_ascii_lowercase())
+            .map(AsRef::as_ref)
+            .ok_or_else(|| RokitError::CustomProviderNotFound(host.to_string()))
+    }
+
+    /*
+        Lists releases from the custom provider for the given tool, newest
+        first, keeping only those in the given channel - custom providers
+        list all of their releases, and are not aware of channels.
+    */
+    async fn get_custom_releases_in_channel(
+        &self,
+        id: &ToolId,
+        channel: &ReleaseChannel,
+    ) -> RokitResult<Vec<Release>> {
+        let releases = self
+            .custom_for(id)?
+            .get_releases(id)
+            .await
+            .map_err(RokitError::CustomProvider)?;
+        Ok(releases
+            .into_iter()
+            .filter(|release| channel.contains(&release.version))
+            .collect())
+    }
+
+    /**
+        Adds authentication tokens for custom hosts, such as GitHub Enterprise
+        Servers, which are used for tools with a matching host in their id.
+
+        Tools from hosts without a token are still accessible, but unauthenticated.
+
+        # Errors
+
+        - If any of the hosts or tokens are invalid.
+    */
+    #[cfg_attr(not(feature = "github"), allow(unused_variables, unused_mut))]
+    pub fn with_host_tokens(mut self, hosts: &HashMap<String, String>) -> RokitResult<Self> {
+        #[cfg(feature = "github")]
+        for (host, token) in hosts {
+            let client = self.client_for(ArtifactProvider::GitHub).clone();
+            let provider = GithubProvider::new_authenticated_with_client(client, token)?
+                .with_host(host)?
+                .with_release_filter(self.github.release_filter().clone());
+            self.github_hosts
+                .insert(host.to_ascii_lowercase(), provider);
+        }
+        Ok(self)
+    }
+
+    /**
+        Gets the GitHub provider to use for the given tool, which
+        depends on the host of the tool, if it has a custom one.
+    */
+    #[cfg(feature = "github")]
+    fn github_for(&self, id: &ToolId) -> RokitResult<Cow<'_, GithubProvider>> {
+        let Some(host) = id.host() else {
+            return Ok(Cow::Borrowed(&self.github));
         };
-        Ok(Self { github })
+        if let Some(provider) = self.github_hosts.get(&host.to_ascii_lowercase()) {
+            return Ok(Cow::Borrowed(provider));
+        }
+        let mut provider =
+            GithubProvider::new_with_client(self.client_for(ArtifactProvider::GitHub).clone())?
+                .with_host(host)?
+                .with_release_filter(self.github.release_filter().clone());
+        if let Some(cache) = &self.http_cache {
+            provider = provider.with_http_cache(cache.clone());
+        }
+        Ok(Cow::Owned(provider))
+    }
+
+    /**
+        Gets the HTTP client used by this artifact source.
+
+        Note that this client is never authenticated, authentication
+        is only added to requests made to specific providers.
+    */
+    #[must_use]
+    pub fn http_client(&self) -> &Arc<dyn HttpClient> {
+        &self.client
     }
 
     /**
@@ -58,9 +583,282 @@ impl ArtifactSource {
         - If the latest release could not be fetched.
     */
     pub async fn get_latest_release(&self, id: &ToolId) -> RokitResult<Release> {
-        Ok(match id.provider() {
-            ArtifactProvider::GitHub => self.github.get_latest_release(id).await?,
+        match id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self.github_for(id)?.get_latest_release(id).await?),
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => Ok(self.gitlab.get_latest_release(id).await?),
+            ArtifactProvider::Url => Err(RokitError::NoReleasesForUrlTool(id.clone().into())),
+            ArtifactProvider::Registry => Ok(self.registry_for(id)?.get_latest_release(id).await?),
+            ArtifactProvider::S3 => Ok(self.s3_for(id)?.get_latest_release(id).await?),
+            ArtifactProvider::Crates => Ok(self.crates.get_latest_release(id).await?),
+            ArtifactProvider::Npm => Ok(self.npm.get_latest_release(id).await?),
+            ArtifactProvider::Git => Ok(self
+                .git
+                .get_latest_release(id, &self.git_template_for(id))
+                .await?),
+            ArtifactProvider::Custom => self
+                .get_custom_releases_in_channel(id, &ReleaseChannel::Stable)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| RokitError::NoEligibleRelease(id.clone().into())),
+            #[cfg(not(all(feature = "github", feature = "gitlab")))]
+            provider => Err(RokitError::ProviderNotEnabled(provider)),
+        }
+    }
+
+    /**
+        Gets the most recent releases for a tool, newest first.
+
+        # Errors
+
+        - If the releases could not be fetched.
+    */
+    pub async fn get_recent_releases(&self, id: &ToolId) -> RokitResult<Vec<Release>> {
+        match id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self.github_for(id)?.get_recent_releases(id).await?),
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => Ok(self.gitlab.get_recent_releases(id).await?),
+            ArtifactProvider::Url => Err(RokitError::NoReleasesForUrlTool(id.clone().into())),
+            ArtifactProvider::Registry => {
+                Ok(self.registry_for(id)?.get_recent_releases(id).await?)
+            }
+            ArtifactProvider::S3 => Ok(self.s3_for(id)?.get_recent_releases(id).await?),
+            ArtifactProvider::Crates => Ok(self.crates.get_recent_releases(id).await?),
+            ArtifactProvider::Npm => Ok(self.npm.get_recent_releases(id).await?),
+            ArtifactProvider::Git => Ok(self
+                .git
+                .get_recent_releases(id, &self.git_template_for(id))
+                .await?),
+            ArtifactProvider::Custom => {
+                self.get_custom_releases_in_channel(id, &ReleaseChannel::Stable)
+                    .await
+            }
+            #[cfg(not(all(feature = "github", feature = "gitlab")))]
+            provider => Err(RokitError::ProviderNotEnabled(provider)),
+        }
+    }
+
+    /**
+        Gets the most recent releases in the given channel for a tool, newest first.
+
+        # Errors
+
+        - If the releases could not be fetched.
+    */
+    #[cfg_attr(
+        not(any(feature = "github", feature = "gitlab")),
+        allow(unused_variables)
+    )]
+    pub async fn get_recent_releases_in_channel(
+        &self,
+        id: &ToolId,
+        channel: &ReleaseChannel,
+    ) -> RokitResult<Vec<Release>> {
+        match id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self
+                .github_for(id)?
+                .get_recent_releases_in_channel(id, channel)
+                .await?),
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => Ok(self
+                .gitlab
+                .get_recent_releases_in_channel(id, channel)
+                .await?),
+            ArtifactProvider::Url => Err(RokitError::NoReleasesForUrlTool(id.clone().into())),
+            ArtifactProvider::Registry => Ok(self
+                .registry_for(id)?
+                .get_recent_releases_in_channel(id, channel)
+                .await?),
+            ArtifactProvider::S3 => Ok(self
+                .s3_for(id)?
+                .get_recent_releases_in_channel(id, channel)
+                .await?),
+            ArtifactProvider::Crates => Ok(self
+                .crates
+                .get_recent_releases_in_channel(id, channel)
+                .await?),
+            ArtifactProvider::Npm => {
+                Ok(self.npm.get_recent_releases_in_channel(id, channel).await?)
+            }
+            ArtifactProvider::Git => Ok(self
+                .git
+                .get_recent_releases_in_channel(id, channel, &self.git_template_for(id))
+                .await?),
+            ArtifactProvider::Custom => self.get_custom_releases_in_channel(id, channel).await,
+            #[cfg(not(all(feature = "github", feature = "gitlab")))]
+            provider => Err(RokitError::ProviderNotEnabled(provider)),
+        }
+    }
+    /**
+        Gets the latest release for a tool, skipping any of the given excluded versions.
+
+        This is typically used to skip over versions that have been yanked, and
+        will only fetch additional releases if the latest release is excluded.
+
+        # Errors
+
+        - If the latest release could not be fetched.
+        - If no release that is not excluded could be found.
+    */
+    pub async fn get_latest_release_excluding(
+        &self,
+        id: &ToolId,
+        excluded: &[Version],
+    ) -> RokitResult<Release> {
+        let latest = self.get_latest_release(id).await?;
+        if !excluded.contains(&latest.version) {
+            return Ok(latest);
+        }
+
+        debug!(%id, version = %latest.version, "latest release is excluded, searching for older");
+        self.find_release_in_channel(id, &ReleaseChannel::Stable, |version| {
+            !excluded.contains(version)
         })
+        .await
+    }
+
+    /**
+        Gets the latest release in the given channel for a tool,
+        skipping any of the given excluded versions.
+
+        For the stable channel, this is the same as [`ArtifactSource::get_latest_release_excluding`].
+
+        # Errors
+
+        - If the releases could not be fetched.
+        - If no release in the channel that is not excluded could be found.
+    */
+    pub async fn get_latest_release_in_channel(
+        &self,
+        id: &ToolId,
+        channel: &ReleaseChannel,
+        excluded: &[Version],
+    ) -> RokitResult<Release> {
+        if channel.is_stable() {
+            return self.get_latest_release_excluding(id, excluded).await;
+        }
+
+        self.find_release_in_channel(id, channel, |version| !excluded.contains(version))
+            .await
+    }
+
+    /**
+        Gets the latest release for a tool that satisfies the given constraints.
+
+        Exact versions are fetched directly, and other constraints will only
+        fetch additional releases if the latest release is not allowed, or if
+        the constraints use the [`ResolutionStrategy::NewestTag`] strategy.
+
+        [`ResolutionStrategy::NewestTag`]: crate::tool::ResolutionStrategy::NewestTag
+
+        # Errors
+
+        - If the releases could not be fetched.
+        - If no release satisfying the constraints could be found.
+    */
+    pub async fn get_latest_release_matching(
+        &self,
+        id: &ToolId,
+        constraints: &ResolveConstraints,
+    ) -> RokitResult<Release> {
+        // Exact versions can be fetched directly, without listing releases
+        if let Some(version) = constraints.exact_version() {
+            if constraints.excluded.contains(&version) {
+                return Err(RokitError::NoEligibleRelease(id.clone().into()));
+            }
+            let spec = ToolSpec::from((id.clone(), version));
+            return self.get_specific_release(&spec).await;
+        }
+
+        // The latest release is always stable, so for other channels we
+        // can skip fetching it, and go straight to listing recent releases -
+        // the same goes for tools that ignore the latest release marker
+        if !constraints.channel.is_stable() || !constraints.resolution.is_latest_release() {
+            return self
+                .find_release_in_channel(id, &constraints.channel, |version| {
+                    constraints.allows(version)
+                })
+                .await;
+        }
+
+        let latest = self.get_latest_release(id).await?;
+        if constraints.allows(&latest.version) {
+            return Ok(latest);
+        }
+
+        debug!(%id, version = %latest.version, "latest release not allowed, searching for older");
+        self.find_release_in_channel(id, &ReleaseChannel::Stable, |version| {
+            constraints.allows(version)
+        })
+        .await
+    }
+
+    /*
+        Finds the newest release in the given channel for a tool that is allowed.
+
+        Providers that list releases page by page, which currently is GitHub,
+        keep fetching older pages until an allowed release is found - other
+        providers only look through their recent releases.
+    */
+    async fn find_release_in_channel(
+        &self,
+        id: &ToolId,
+        channel: &ReleaseChannel,
+        is_allowed: impl Fn(&Version) -> bool,
+    ) -> RokitResult<Release> {
+        let releases = match id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => {
+                self.github_for(id)?
+                    .get_releases_in_channel_until(id, channel, &is_allowed)
+                    .await?
+            }
+            _ => self.get_recent_releases_in_channel(id, channel).await?,
+        };
+        releases
+            .into_iter()
+            .find(|release| is_allowed(&release.version))
+            .ok_or_else(|| RokitError::NoEligibleRelease(id.clone().into()))
+    }
+
+    /**
+        Gets specific releases for many tools at once, from providers that
+        support it - currently only authenticated GitHub providers, which
+        use the GraphQL API to fetch releases for up to 50 tools at a time.
+
+        This is only an optimization, and never fails - releases that could not
+        be fetched this way are not returned, and should be fetched one at a
+        time using [`ArtifactSource::get_specific_release`] instead.
+    */
+    #[cfg_attr(not(feature = "github"), allow(unused_variables, unused_mut))]
+    pub async fn get_specific_releases(&self, specs: &[ToolSpec]) -> HashMap<ToolSpec, Release> {
+        let mut releases = HashMap::new();
+
+        #[cfg(feature = "github")]
+        {
+            let mut specs_by_host = HashMap::<_, Vec<_>>::new();
+            for spec in specs {
+                if spec.provider() == ArtifactProvider::GitHub {
+                    let host = spec.id().host().map(str::to_ascii_lowercase);
+                    specs_by_host.entry(host).or_default().push(spec.clone());
+                }
+            }
+            for specs in specs_by_host.values() {
+                let Ok(github) = self.github_for(specs[0].id()) else {
+                    continue;
+                };
+                match github.get_specific_releases(specs).await {
+                    Ok(found) => releases.extend(found),
+                    Err(e) => debug!(error = %e, "failed to fetch releases using GraphQL"),
+                }
+            }
+        }
+
+        releases
     }
 
     /**
@@ -71,21 +869,424 @@ impl ArtifactSource {
         - If the specific release could not be fetched.
     */
     pub async fn get_specific_release(&self, spec: &ToolSpec) -> RokitResult<Release> {
-        Ok(match spec.provider() {
-            ArtifactProvider::GitHub => self.github.get_specific_release(spec).await?,
-        })
+        match spec.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self
+                .github_for(spec.id())?
+                .get_specific_release(spec)
+                .await?),
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => Ok(self.gitlab.get_specific_release(spec).await?),
+            ArtifactProvider::Url => {
+                Err(RokitError::NoReleasesForUrlTool(spec.id().clone().into()))
+            }
+            ArtifactProvider::Registry => Ok(self
+                .registry_for(spec.id())?
+                .get_specific_release(spec)
+                .await?),
+            ArtifactProvider::S3 => Ok(self.s3_for(spec.id())?.get_specific_release(spec).await?),
+            ArtifactProvider::Crates => Ok(self.crates.get_specific_release(spec).await?),
+            ArtifactProvider::Npm => Ok(self.npm.get_specific_release(spec).await?),
+            ArtifactProvider::Git => Ok(self
+                .git
+                .get_specific_release(spec, &self.git_template_for(spec.id()))
+                .await?),
+            ArtifactProvider::Custom => self
+                .custom_for(spec.id())?
+                .get_release(spec)
+                .await
+                .map_err(RokitError::CustomProvider),
+            #[cfg(not(all(feature = "github", feature = "gitlab")))]
+            provider => Err(RokitError::ProviderNotEnabled(provider)),
+        }
+    }
+
+    /**
+        Gets the release notes for a specific release of a tool.
+
+        Returns `None` if the release has no release notes,
+        which is always the case for some providers.
+
+        # Errors
+
+        - If the specific release could not be fetched.
+    */
+    pub async fn get_release_notes(&self, spec: &ToolSpec) -> RokitResult<Option<ReleaseNotes>> {
+        let release = self.get_specific_release(spec).await?;
+        Ok(ReleaseNotes::from_release(&release))
+    }
+
+    /**
+        Gets the release notes for all releases of a tool that are newer than
+        `from`, up to and including `to`, newest first - such as between the
+        installed version of a tool and its latest version.
+
+        Releases without release notes are skipped. Prereleases
+        are only included if `to` is a prerelease itself.
+
+        # Errors
+
+        - If the releases could not be fetched.
+    */
+    pub async fn get_release_notes_between(
+        &self,
+        id: &ToolId,
+        from: &Version,
+        to: &Version,
+    ) -> RokitResult<Vec<ReleaseNotes>> {
+        let channel = if to.pre.is_empty() {
+            ReleaseChannel::Stable
+        } else {
+            ReleaseChannel::AnyPrerelease
+        };
+
+        // NOTE: GitHub lists releases page by page, so we keep fetching
+        // older pages until we reach a release that is not newer than `from`
+        let releases = match id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => {
+                self.github_for(id)?
+                    .get_releases_in_channel_until(id, &channel, |version| version <= from)
+                    .await?
+            }
+            _ => self.get_recent_releases_in_channel(id, &channel).await?,
+        };
+
+        Ok(releases
+            .iter()
+            .filter(|release| release.version > *from && release.version <= *to)
+            .filter_map(ReleaseNotes::from_release)
+            .collect())
+    }
+
+    /**
+        Gets provenance information for a tool, such as the age and
+        popularity of its repository, and whether its releases are signed.
+
+        # Errors
+
+        - If the provenance information could not be fetched.
+    */
+    pub async fn get_provenance(&self, id: &ToolId) -> RokitResult<ToolProvenance> {
+        match id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self.github_for(id)?.get_provenance(id).await?),
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => Ok(self.gitlab.get_provenance(id).await?),
+            ArtifactProvider::Url => Err(RokitError::NoReleasesForUrlTool(id.clone().into())),
+            ArtifactProvider::Registry
+            | ArtifactProvider::S3
+            | ArtifactProvider::Custom
+            | ArtifactProvider::Crates
+            | ArtifactProvider::Npm
+            | ArtifactProvider::Git => Ok(ToolProvenance::default()),
+            #[cfg(not(all(feature = "github", feature = "gitlab")))]
+            provider => Err(RokitError::ProviderNotEnabled(provider)),
+        }
+    }
+
+    /**
+        Gets the canonical id for a tool, with the casing of its author
+        and name as reported by its provider, such as `rojo-rbx/rojo`
+        for a tool that was given as `Rojo-RBX/Rojo`.
+
+        Only tools from GitHub can currently be canonicalized,
+        tools from other providers always keep the given id.
+
+        # Errors
+
+        - If the canonical id of the tool could not be fetched.
+    */
+    pub async fn get_canonical_id(&self, id: &ToolId) -> RokitResult<ToolId> {
+        match id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self.github_for(id)?.get_canonical_id(id).await?),
+            _ => Ok(id.clone()),
+        }
+    }
+
+    /**
+        Checks if a tool has moved to a new location, such as a GitHub
+        repository that was renamed or transferred to a new owner,
+        returning the new id of the tool if so.
+
+        Only tools from GitHub can currently be detected as moved,
+        tools from other providers are never considered to have moved.
+
+        # Errors
+
+        - If the current location of the tool could not be fetched.
+    */
+    pub async fn get_moved_id(&self, id: &ToolId) -> RokitResult<Option<ToolId>> {
+        match id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self.github_for(id)?.get_moved_id(id).await?),
+            _ => Ok(None),
+        }
+    }
+
+    /**
+        Gets the size of an artifact in bytes, without downloading it.
+
+        If the size is not already known from the provider, and an asset
+        metadata cache was added using [`ArtifactSource::with_asset_metadata_cache`],
+        the size is fetched using a `HEAD` request to the URL of the artifact.
+
+        This is best-effort, and returns `None` if the size could not be determined.
+    */
+    pub async fn get_artifact_size(&self, artifact: &Artifact) -> Option<u64> {
+        if artifact.size.is_some() {
+            return artifact.size;
+        }
+        let cache = self.asset_metadata.as_ref()?;
+        let url = artifact.url.as_ref()?;
+        match cache
+            .head(
+                self.client_for(artifact.provider).as_ref(),
+                HttpRequest::get(url.as_str()),
+            )
+            .await
+        {
+            Ok(metadata) => metadata.size,
+            Err(e) => {
+                debug!(%url, error = %e, "failed to get artifact size");
+                None
+            }
+        }
+    }
+
+    /**
+        Gets the expected SHA-256 digest of an artifact, as a lowercase
+        hexadecimal string, if one was published alongside its release.
+
+        Digests listed in another artifact of the release, such as a
+        `SHA256SUMS` file, are found by downloading and parsing that file.
+
+        This is best-effort, and returns `None` if the digest could not be determined.
+    */
+    pub async fn get_artifact_sha256(&self, artifact: &Artifact) -> Option<String> {
+        match artifact.digest.as_ref()? {
+            ArtifactDigest::Sha256(digest) => Some(digest.clone()),
+            ArtifactDigest::Published {
+                file,
+                file_name,
+                companion,
+            } => {
+                let contents = match self.download_artifact_contents(file).await {
+                    Ok(contents) => contents,
+                    Err(e) => {
+                        debug!(file = ?file.name, error = %e, "failed to download digest file");
+                        return None;
+                    }
+                };
+                let digest =
+                    parse_digest_file(&String::from_utf8_lossy(&contents), file_name, *companion);
+                if digest.is_none() {
+                    debug!(file = ?file.name, %file_name, "digest file does not list artifact");
+                }
+                digest
+            }
+        }
     }
 
     /**
         Downloads the contents of an artifact.
 
+        If a mirror was added for the tool of the artifact, using
+        [`ArtifactSource::with_tool_mirror`], or mirrors were added for its
+        provider, using [`ArtifactSource::with_mirrors`], those are tried first.
+
+        If the size of the artifact is known, the downloaded
+        contents are checked to have that exact size.
+
         # Errors
 
         - If the artifact contents could not be downloaded.
+        - If the downloaded contents do not have the size of the artifact.
     */
     pub async fn download_artifact_contents(&self, artifact: &Artifact) -> RokitResult<Vec<u8>> {
-        Ok(match &artifact.provider {
-            ArtifactProvider::GitHub => self.github.download_artifact_contents(artifact).await?,
-        })
+        let contents = self.download_artifact_contents_unchecked(artifact).await?;
+        match artifact.size {
+            Some(expected) if expected != contents.len() as u64 => {
+                Err(RokitError::ArtifactSizeMismatch {
+                    spec: artifact.tool_spec.clone().into(),
+                    expected,
+                    actual: contents.len() as u64,
+                })
+            }
+            _ => Ok(contents),
+        }
+    }
+
+    async fn download_artifact_contents_unchecked(
+        &self,
+        artifact: &Artifact,
+    ) -> RokitResult<Vec<u8>> {
+        // NOTE: Mirrors are for the public instance of each provider,
+        // and never for custom hosts such as GitHub Enterprise Server,
+        // unless the mirror was chosen for this specific tool
+        let mirrors = match self.tool_mirrors.get(artifact.tool_spec.id()) {
+            Some(mirror) => Some(slice::from_ref(mirror)),
+            None => self
+                .mirrors
+                .get(&artifact.provider)
+                .filter(|_| artifact.tool_spec.id().host().is_none())
+                .map(Vec::as_slice),
+        };
+        if let (Some(mirrors), Some(url)) = (mirrors, artifact.url.as_ref()) {
+            let client = self.client_for(artifact.provider).as_ref();
+            if let Some(contents) = download_from_mirrors(client, mirrors, url).await {
+                return Ok(contents);
+            }
+            debug!(%url, "all mirrors failed, downloading from provider");
+        }
+
+        match artifact.provider {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self
+                .github_for(artifact.tool_spec.id())?
+                .download_artifact_contents(artifact)
+                .await?),
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => {
+                Ok(self.gitlab.download_artifact_contents(artifact).await?)
+            }
+            ArtifactProvider::Url => {
+                download_url_artifact(self.client_for(ArtifactProvider::Url).as_ref(), artifact)
+                    .await
+            }
+            ArtifactProvider::Registry => Ok(self
+                .registry_for(artifact.tool_spec.id())?
+                .download_artifact_contents(artifact)
+                .await?),
+            ArtifactProvider::S3 => Ok(self
+                .s3_for(artifact.tool_spec.id())?
+                .download_artifact_contents(artifact)
+                .await?),
+            ArtifactProvider::Crates => {
+                Ok(self.crates.download_artifact_contents(artifact).await?)
+            }
+            ArtifactProvider::Npm => Ok(self.npm.download_artifact_contents(artifact).await?),
+            ArtifactProvider::Git => Ok(self.git.download_artifact_contents(artifact).await?),
+            ArtifactProvider::Custom => self
+                .custom_for(artifact.tool_spec.id())?
+                .download_artifact(artifact)
+                .await
+                .map_err(RokitError::CustomProvider),
+            #[cfg(not(all(feature = "github", feature = "gitlab")))]
+            provider => Err(RokitError::ProviderNotEnabled(provider)),
+        }
+    }
+
+    /**
+        Downloads the contents of a release asset for the given tool, by asset id.
+
+        Unlike [`ArtifactSource::download_artifact_contents`], this does not
+        require a full artifact, and is mostly useful for proxying downloads.
+
+        # Errors
+
+        - If the asset could not be downloaded.
+    */
+    #[cfg_attr(
+        not(any(feature = "github", feature = "gitlab")),
+        allow(unused_variables)
+    )]
+    pub async fn download_asset_contents(
+        &self,
+        tool_id: &ToolId,
+        asset_id: &str,
+    ) -> RokitResult<Vec<u8>> {
+        match tool_id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self
+                .github_for(tool_id)?
+                .download_asset_contents(tool_id, asset_id)
+                .await?),
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => Ok(self
+                .gitlab
+                .download_asset_contents(tool_id, asset_id)
+                .await?),
+            ArtifactProvider::Url => Err(RokitError::NoReleasesForUrlTool(tool_id.clone().into())),
+            ArtifactProvider::Registry => Err(RegistryError::NotSupported("asset ids").into()),
+            ArtifactProvider::S3 => Err(S3Error::NotSupported("asset ids").into()),
+            ArtifactProvider::Crates => Err(CratesError::NotSupported("asset ids").into()),
+            ArtifactProvider::Npm => Err(NpmError::NotSupported("asset ids").into()),
+            ArtifactProvider::Git => Err(GitError::NotSupported("asset ids").into()),
+            ArtifactProvider::Custom => Err(RokitError::CustomProviderNotSupported {
+                host: tool_id.host().unwrap_or_default().to_string(),
+                operation: "asset ids",
+            }),
+            #[cfg(not(all(feature = "github", feature = "gitlab")))]
+            provider => Err(RokitError::ProviderNotEnabled(provider)),
+        }
+    }
+
+    /**
+        Downloads a gzipped tarball of a repository, at the given
+        git reference - or the default branch, if not given.
+
+        This is used for project templates, and the repository is
+        identified using a tool id, such as `rojo-rbx/rojo`.
+
+        # Errors
+
+        - If the repository archive could not be downloaded.
+    */
+    #[cfg_attr(
+        not(any(feature = "github", feature = "gitlab")),
+        allow(unused_variables)
+    )]
+    pub async fn download_repository_archive(
+        &self,
+        repo_id: &ToolId,
+        reference: Option<&str>,
+    ) -> RokitResult<Vec<u8>> {
+        match repo_id.provider() {
+            #[cfg(feature = "github")]
+            ArtifactProvider::GitHub => Ok(self
+                .github_for(repo_id)?
+                .download_repository_archive(repo_id, reference)
+                .await?),
+            #[cfg(feature = "gitlab")]
+            ArtifactProvider::GitLab => Ok(self
+                .gitlab
+                .download_repository_archive(repo_id, reference)
+                .await?),
+            ArtifactProvider::Url => Err(RokitError::NoReleasesForUrlTool(repo_id.clone().into())),
+            ArtifactProvider::Registry => Err(RegistryError::NotSupported("repositories").into()),
+            ArtifactProvider::S3 => Err(S3Error::NotSupported("repositories").into()),
+            ArtifactProvider::Crates => Err(CratesError::NotSupported("repositories").into()),
+            ArtifactProvider::Npm => Err(NpmError::NotSupported("repositories").into()),
+            ArtifactProvider::Git => Err(GitError::NotSupported("repositories").into()),
+            ArtifactProvider::Custom => Err(RokitError::CustomProviderNotSupported {
+                host: repo_id.host().unwrap_or_default().to_string(),
+                operation: "repositories",
+            }),
+            #[cfg(not(all(feature = "github", feature = "gitlab")))]
+            provider => Err(RokitError::ProviderNotEnabled(provider)),
+        }
+    }
+
+    /**
+        Probes all enabled providers, checking their reachability,
+        authentication, and remaining rate limits.
+
+        This never fails - any errors are instead reported in the returned statuses.
+    */
+    pub async fn get_provider_statuses(&self) -> Vec<ProviderStatus> {
+        let mut statuses = Vec::new();
+        #[cfg(feature = "github")]
+        statuses.push(self.github.get_status().await);
+        #[cfg(feature = "gitlab")]
+        statuses.push(self.gitlab.get_status().await);
+        if let Some(registry) = &self.registry {
+            statuses.push(registry.get_status().await);
+        }
+        if let Some(s3) = &self.s3 {
+            statuses.push(s3.get_status().await);
+        }
+        statuses
     }
 }
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use super::ArtifactProvider;
+
+/**
+    The authentication status of a provider, as reported by the provider itself.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderAuthStatus {
+    /// No authentication token is configured for the provider.
+    Anonymous,
+    /// The configured authentication token was accepted by the provider.
+    Valid,
+    /// The configured authentication token was rejected by the provider.
+    Invalid,
+}
+
+/**
+    The rate limit of a provider, for the current authentication.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProviderRateLimit {
+    /// The maximum number of requests allowed in the current window.
+    pub limit: u64,
+    /// The number of requests remaining in the current window.
+    pub remaining: u64,
+    /// When the current window ends, and the remaining requests reset.
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl ProviderRateLimit {
+    /**
+        Checks if the rate limit has been exhausted, meaning that
+        no more requests can be made until the limit resets.
+    */
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+/**
+    The status of a provider, as seen from the current machine.
+
+    Meant to quickly answer if problems are caused by the provider,
+    such as an outage or an exhausted rate limit, or by the user.
+*/
+#[derive(Debug, Clone)]
+pub struct ProviderStatus {
+    /// The provider that this status is for.
+    pub provider: ArtifactProvider,
+    /// How long the provider took to respond, if it responded at all.
+    pub latency: Option<Duration>,
+    /// The authentication status, if the provider responded.
+    pub auth: Option<ProviderAuthStatus>,
+    /// The current rate limit, if the provider responded with one.
+    pub rate_limit: Option<ProviderRateLimit>,
+    /// The error that occurred when probing the provider, if any.
+    pub error: Option<String>,
+}
+
+impl ProviderStatus {
+    /**
+        Checks if the provider responded to the probe.
+    */
+    #[must_use]
+    pub fn is_reachable(&self) -> bool {
+        self.latency.is_some()
+    }
+
+    /**
+        Checks if the provider is fully usable, meaning that it responded without
+        errors, any configured authentication is valid, and it is not rate limited.
+    */
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.is_reachable()
+            && self.error.is_none()
+            && self.auth != Some(ProviderAuthStatus::Invalid)
+            && !self.rate_limit.is_some_and(|r| r.is_exhausted())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_status() -> ProviderStatus {
+        ProviderStatus {
+            provider: ArtifactProvider::GitHub,
+            latency: Some(Duration::from_millis(100)),
+            auth: Some(ProviderAuthStatus::Anonymous),
+            rate_limit: Some(ProviderRateLimit {
+                limit: 60,
+                remaining: 59,
+                resets_at: None,
+            }),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn healthy() {
+        assert!(new_status().is_healthy());
+    }
+
+    #[test]
+    fn unhealthy() {
+        let mut unreachable = new_status();
+        unreachable.latency = None;
+        assert!(!unreachable.is_reachable());
+        assert!(!unreachable.is_healthy());
+
+        let mut invalid_auth = new_status();
+        invalid_auth.auth = Some(ProviderAuthStatus::Invalid);
+        assert!(invalid_auth.is_reachable());
+        assert!(!invalid_auth.is_healthy());
+
+        let mut rate_limited = new_status();
+        rate_limited.rate_limit.as_mut().unwrap().remaining = 0;
+        assert!(!rate_limited.is_healthy());
+
+        let mut outage = new_status();
+        outage.error = Some(String::from("HTTP error: 503"));
+        assert!(outage.is_reachable());
+        assert!(!outage.is_healthy());
+    }
+}
@@ -0,0 +1,110 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use toml_edit::{DocumentMut, Item};
+use tracing::warn;
+
+use crate::tool::ToolId;
+
+#[cfg(feature = "runtime")]
+use tracing::{debug, instrument};
+#[cfg(feature = "runtime")]
+use url::Url;
+
+#[cfg(feature = "runtime")]
+use crate::result::{RokitError, RokitResult};
+
+#[cfg(feature = "runtime")]
+use super::http::{user_agent, HttpClient, HttpRequest};
+
+/**
+    The index of a tap - a registry of short tool names.
+
+    Tap indexes are TOML files, listing short names and the full tool ids they refer to:
+
+    ```toml
+    [tools]
+    rojo = "rojo-rbx/rojo"
+    lune = "lune-org/lune"
+    ```
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TapIndex {
+    tools: BTreeMap<String, ToolId>,
+}
+
+impl TapIndex {
+    /**
+        Fetches the tap index at the given URL, using the given HTTP client.
+
+        Note that, unlike other requests made by Rokit, this request
+        is never authenticated, since taps may be hosted anywhere.
+
+        # Errors
+
+        - If the index could not be fetched.
+        - If the index is not valid TOML.
+    */
+    #[cfg(feature = "runtime")]
+    #[instrument(skip(client), level = "debug")]
+    pub async fn fetch(client: &dyn HttpClient, url: &Url) -> RokitResult<Self> {
+        debug!(%url, "fetching tap index");
+        let request = HttpRequest::get(url.as_str()).header("user-agent", user_agent());
+        let response = client.send(request).await?.error_for_status()?;
+        let contents = String::from_utf8(response.body).map_err(|_| RokitError::InvalidUtf8)?;
+        Ok(contents.parse()?)
+    }
+
+    /**
+        Gets all short names and the tool ids they refer to, sorted by name.
+    */
+    pub fn tools(&self) -> impl Iterator<Item = (&str, &ToolId)> {
+        self.tools.iter().map(|(name, id)| (name.as_str(), id))
+    }
+
+    /**
+        Gets the number of tools in this index.
+    */
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    /**
+        Checks if this index contains no tools.
+    */
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+}
+
+impl FromStr for TapIndex {
+    type Err = toml_edit::TomlError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let document = s.parse::<DocumentMut>()?;
+
+        let mut tools = BTreeMap::new();
+        let entries = document.get("tools").and_then(Item::as_table);
+        for (name, value) in entries.into_iter().flat_map(|t| t.iter()) {
+            match value.as_str().map(str::parse::<ToolId>) {
+                Some(Ok(id)) => {
+                    tools.insert(name.to_ascii_lowercase(), id);
+                }
+                Some(Err(e)) => warn!(
+                    "Encountered invalid tool id for '{name}' in tap index!\
+                    \nThe tool will be ignored.\
+                    \nError: {e}"
+                ),
+                None => warn!(
+                    "Encountered invalid tool id for '{name}' in tap index!\
+                    \nThe tool will be ignored.\
+                    \nExpected: String\
+                    \nActual: {}",
+                    value.type_name()
+                ),
+            }
+        }
+
+        Ok(Self { tools })
+    }
+}
@@ -0,0 +1,235 @@
+use semver::Version;
+use thiserror::Error;
+use url::Url;
+
+use crate::tool::{ToolId, ToolSpec};
+use crate::util::str::CaseInsensitiveString;
+
+use super::{artifact::split_filename_and_extensions, Artifact, ArtifactProvider, Release};
+
+#[cfg(feature = "runtime")]
+use tracing::{debug, instrument};
+
+#[cfg(feature = "runtime")]
+use crate::result::{RokitError, RokitResult};
+
+#[cfg(feature = "runtime")]
+use super::http::{user_agent, HttpClient, HttpRequest};
+
+const EXE_EXTENSION: &str = ".exe";
+
+/**
+    Error type representing the possible errors that can occur when creating a `UrlSource`.
+*/
+#[derive(Debug, Error)]
+pub enum UrlSourceError {
+    #[error("tools can only be downloaded using HTTPS, but the URL uses '{0}'")]
+    InsecureScheme(String),
+    #[error("the URL has no valid host name")]
+    InvalidHost,
+    #[error("the URL does not point to a file")]
+    MissingFileName,
+    #[error("no valid tool name could be found in the file name '{0}'")]
+    InvalidToolName(String),
+}
+
+/**
+    A source for a tool downloaded from a direct URL, instead of from releases.
+
+    The URL must use HTTPS, and point to either an archive or a raw binary, such as
+    `https://example.com/tool-v1.2.3-linux-x64.tar.gz`. Tools from direct URLs use
+    [`ArtifactProvider::Url`], with the host of the URL as the author, and the
+    name of the tool taken from the file name - such as `url:example.com/tool`.
+
+    The version of the tool is also taken from the file name, if it contains one,
+    and is `0.0.0` otherwise. Since there are no releases to look for, tools from
+    direct URLs are never updated - add the tool again using a different URL
+    to change its version.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UrlSource {
+    url: Url,
+    tool_id: ToolId,
+    version: Version,
+}
+
+impl UrlSource {
+    /**
+        Creates a new source for the tool at the given URL.
+
+        # Errors
+
+        - If the URL does not use HTTPS.
+        - If the URL has no valid host name, or does not point to a file.
+        - If no valid tool name could be found in the file name.
+    */
+    pub fn new(url: Url) -> Result<Self, UrlSourceError> {
+        if url.scheme() != "https" {
+            return Err(UrlSourceError::InsecureScheme(url.scheme().to_string()));
+        }
+        let host = url
+            .host_str()
+            .filter(|host| !host.is_empty() && !host.contains(':'))
+            .ok_or(UrlSourceError::InvalidHost)?;
+        let file_name = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|name| !name.is_empty())
+            .ok_or(UrlSourceError::MissingFileName)?;
+
+        let (tool_name, version) = parse_file_name(file_name)
+            .ok_or_else(|| UrlSourceError::InvalidToolName(file_name.to_string()))?;
+        let tool_id = ToolId {
+            provider: ArtifactProvider::Url,
+            host: None,
+            author: CaseInsensitiveString::new(host),
+            name: CaseInsensitiveString::new(tool_name),
+        };
+
+        Ok(Self {
+            url,
+            tool_id,
+            version: version.unwrap_or_else(|| Version::new(0, 0, 0)),
+        })
+    }
+
+    /**
+        Gets the URL that the tool is downloaded from.
+    */
+    #[must_use]
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /**
+        Gets the identifier of the tool, such as `url:example.com/tool`.
+    */
+    #[must_use]
+    pub fn tool_id(&self) -> &ToolId {
+        &self.tool_id
+    }
+
+    /**
+        Gets the specification of the tool, such as `url:example.com/tool@1.2.3`.
+    */
+    #[must_use]
+    pub fn tool_spec(&self) -> ToolSpec {
+        ToolSpec::from((self.tool_id.clone(), self.version.clone()))
+    }
+
+    /**
+        Gets the release for the given tool specification, which
+        contains a single artifact for the URL of this source.
+
+        The specification is typically the one returned by [`UrlSource::tool_spec`],
+        but may also be one that was previously stored in a manifest.
+    */
+    #[must_use]
+    pub fn release_for(&self, spec: &ToolSpec) -> Release {
+        Release {
+            version: spec.version().clone(),
+            changelog: None,
+            artifacts: vec![Artifact::from_url(&self.url, spec)],
+        }
+    }
+}
+
+/**
+    Downloads the contents of an artifact from a direct URL.
+
+    Note that, same as for taps, this request is never
+    authenticated, since direct URLs may point anywhere.
+*/
+#[cfg(feature = "runtime")]
+#[instrument(skip(client, artifact), level = "debug")]
+pub(super) async fn download_url_artifact(
+    client: &dyn HttpClient,
+    artifact: &Artifact,
+) -> RokitResult<Vec<u8>> {
+    let url = artifact
+        .url
+        .as_ref()
+        .ok_or_else(|| RokitError::NoCompatibleArtifact(artifact.tool_spec.clone().into()))?;
+    debug!(%url, "downloading artifact from direct url");
+    let request = HttpRequest::get(url.as_str()).header("user-agent", user_agent());
+    let response = client.send(request).await?.error_for_status()?;
+    Ok(response.body)
+}
+
+/**
+    Parses the name of a tool, and its version if any, from a file name
+    such as `tool-v1.2.3-linux-x64.tar.gz`, `tool_1.2.3.zip` or `tool.exe`.
+*/
+fn parse_file_name(file_name: &str) -> Option<(&str, Option<Version>)> {
+    let (stem, _) = split_filename_and_extensions(file_name);
+    let stem = stem
+        .strip_suffix(EXE_EXTENSION)
+        .or_else(|| stem.strip_suffix(&EXE_EXTENSION.to_ascii_uppercase()))
+        .unwrap_or(stem);
+
+    let mut tokens = stem.split(['-', '_']);
+    let name = tokens.next().filter(|name| {
+        !name.is_empty()
+            && !name.contains([':', '/', '@', '%'])
+            && !name.contains(char::is_whitespace)
+    })?;
+    let version = tokens.find_map(|token| {
+        let token = token.strip_prefix(['v', 'V']).unwrap_or(token);
+        token.parse::<Version>().ok()
+    });
+
+    Some((name, version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_source(url: &str) -> Result<UrlSource, UrlSourceError> {
+        UrlSource::new(url.parse().unwrap())
+    }
+
+    #[test]
+    fn parse_file_names() {
+        assert_eq!(
+            parse_file_name("tool-v1.2.3-linux-x64.tar.gz"),
+            Some(("tool", Some(Version::new(1, 2, 3))))
+        );
+        assert_eq!(
+            parse_file_name("tool_1.2.3.zip"),
+            Some(("tool", Some(Version::new(1, 2, 3))))
+        );
+        assert_eq!(parse_file_name("tool.exe"), Some(("tool", None)));
+        assert_eq!(parse_file_name("tool"), Some(("tool", None)));
+        assert_eq!(parse_file_name("-1.2.3.zip"), None);
+    }
+
+    #[test]
+    fn source_from_url() {
+        let source = new_source("https://example.com/dl/tool-v1.2.3-linux-x64.tar.gz").unwrap();
+        assert_eq!(source.tool_id().to_string(), "url:example.com/tool");
+        assert_eq!(source.tool_spec().to_string(), "url:example.com/tool@1.2.3");
+
+        let release = source.release_for(&source.tool_spec());
+        assert_eq!(release.version, Version::new(1, 2, 3));
+        assert_eq!(release.artifacts.len(), 1);
+        assert_eq!(release.artifacts[0].provider, ArtifactProvider::Url);
+        assert!(release.artifacts[0].format.is_some());
+
+        let raw = new_source("https://example.com/tool").unwrap();
+        assert_eq!(raw.tool_spec().to_string(), "url:example.com/tool@0.0.0");
+        assert_eq!(raw.release_for(&raw.tool_spec()).artifacts[0].format, None);
+    }
+
+    #[test]
+    fn source_requires_https_and_file() {
+        assert!(matches!(
+            new_source("http://example.com/tool.zip"),
+            Err(UrlSourceError::InsecureScheme(_))
+        ));
+        assert!(matches!(
+            new_source("https://example.com/"),
+            Err(UrlSourceError::MissingFileName)
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    result::RokitResult,
+    sources::{HttpClient, HttpError, HttpMethod, HttpRequest, HttpResponse},
+};
+
+use super::url_entries::{UrlEntries, UrlEntry};
+
+/**
+    How long asset metadata is considered up to date after it was checked.
+
+    Release assets are practically never replaced once published,
+    so this can be fairly long without returning stale sizes.
+*/
+const MAX_AGE_HOURS: i64 = 24;
+
+/**
+    Metadata about a downloadable asset, as returned by a `HEAD` request.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetMetadata {
+    pub url: String,
+    pub size: Option<u64>,
+    pub digest: Option<String>,
+    pub etag: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl AssetMetadata {
+    /**
+        Creates new asset metadata from the headers of the given response.
+
+        The size is read from the `Content-Length` header, and the digest
+        from the `Repr-Digest` or legacy `Digest` header, if present.
+    */
+    #[must_use]
+    pub fn from_response(response: &HttpResponse, checked_at: DateTime<Utc>) -> Self {
+        let size = response
+            .header("content-length")
+            .and_then(|len| len.trim().parse().ok());
+        let digest = response
+            .header("repr-digest")
+            .or_else(|| response.header("digest"))
+            .map(ToString::to_string);
+        let etag = response.header("etag").map(ToString::to_string);
+        Self {
+            url: response.url.clone(),
+            size,
+            digest,
+            etag,
+            checked_at,
+        }
+    }
+
+    /**
+        Checks if this metadata is still considered up to date at the given time.
+    */
+    #[must_use]
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        let age = now.signed_duration_since(self.checked_at);
+        age >= Duration::zero() && age < Duration::hours(MAX_AGE_HOURS)
+    }
+}
+
+impl UrlEntry for AssetMetadata {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/**
+    A cache for metadata about downloadable assets, such as their sizes,
+    stored in the `cache/assets` directory of the Rokit home directory.
+
+    Assets are checked using `HEAD` requests, and the resulting metadata
+    is reused for a while, so that repeated dry runs, size checks before
+    downloading, and checks for outdated tools do not query the same URLs
+    over and over again.
+
+    Unlike the [`HttpCache`], entries are never revalidated with the
+    server - they are used as they are until they expire, after which
+    the asset is checked again using a new `HEAD` request.
+
+    Can be cheaply cloned while still referring to the same underlying data.
+
+    [`HttpCache`]: super::HttpCache
+*/
+#[derive(Debug, Clone)]
+pub struct AssetMetadataCache {
+    entries: UrlEntries,
+}
+
+impl AssetMetadataCache {
+    /**
+        Creates a new `AssetMetadataCache` stored in the given directory.
+    */
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            entries: UrlEntries::new(root.into()),
+        }
+    }
+
+    /**
+        Gets the path to the directory that this cache is stored in.
+    */
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.entries.path()
+    }
+
+    /**
+        Reads the cached metadata for the given URL, if any.
+
+        Note that this may return metadata that is no longer
+        fresh - use [`AssetMetadata::is_fresh`] to check.
+    */
+    #[instrument(skip(self), level = "trace")]
+    pub async fn read(&self, url: &str) -> Option<AssetMetadata> {
+        self.entries.read(url).await
+    }
+
+    /**
+        Writes the given metadata to the cache, replacing any older metadata for the same URL.
+
+        # Errors
+
+        - If the metadata could not be written to the cache.
+    */
+    #[instrument(skip(self, entry), fields(url = %entry.url), level = "trace")]
+    pub async fn write(&self, entry: &AssetMetadata) -> RokitResult<()> {
+        self.entries.write(entry).await?;
+        trace!("wrote asset metadata cache entry");
+        Ok(())
+    }
+
+    /**
+        Gets metadata for the asset at the URL of the given request.
+
+        Fresh cached metadata is returned directly, otherwise the request
+        is sent as a `HEAD` request using the given client, and the
+        resulting metadata is stored in the cache.
+
+        # Errors
+
+        - If the client failed to send the request.
+        - If the server responded with an unsuccessful status.
+    */
+    pub async fn head(
+        &self,
+        client: &dyn HttpClient,
+        mut request: HttpRequest,
+    ) -> Result<AssetMetadata, HttpError> {
+        let now = Utc::now();
+        if let Some(entry) = self.read(&request.url).await {
+            if entry.is_fresh(now) {
+                debug!(url = %entry.url, "using cached asset metadata");
+                return Ok(entry);
+            }
+        }
+
+        request.method = HttpMethod::Head;
+        let response = client.send(request).await?.error_for_status()?;
+        let entry = AssetMetadata::from_response(&response, now);
+
+        // NOTE: The metadata was fetched successfully, and not being able
+        // to store it only means that the next check sends another request
+        if let Err(e) = self.write(&entry).await {
+            debug!(url = %entry.url, error = %e, "failed to write asset metadata cache entry");
+        }
+
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_from_headers() {
+        let now = Utc::now();
+        let meta = AssetMetadata::from_response(
+            &HttpResponse::for_test(
+                200,
+                &[
+                    ("Content-Length", "1024"),
+                    ("Repr-Digest", "sha-256=:abc=:"),
+                    ("ETag", "\"a\""),
+                ],
+                &[],
+            ),
+            now,
+        );
+        assert_eq!(meta.size, Some(1024));
+        assert_eq!(meta.digest.as_deref(), Some("sha-256=:abc=:"));
+        assert_eq!(meta.etag.as_deref(), Some("\"a\""));
+
+        let meta = AssetMetadata::from_response(
+            &HttpResponse::for_test(200, &[("Content-Length", "?")], &[]),
+            now,
+        );
+        assert_eq!(meta.size, None);
+        assert_eq!(meta.digest, None);
+    }
+
+    #[test]
+    fn metadata_expires() {
+        let now = Utc::now();
+        let meta = AssetMetadata::from_response(&HttpResponse::for_test(200, &[], &[]), now);
+        assert!(meta.is_fresh(now));
+        assert!(meta.is_fresh(now + Duration::hours(MAX_AGE_HOURS - 1)));
+        assert!(!meta.is_fresh(now + Duration::hours(MAX_AGE_HOURS)));
+        assert!(!meta.is_fresh(now - Duration::hours(1)));
+    }
+}
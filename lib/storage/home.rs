@@ -1,14 +1,121 @@
+use std::collections::HashMap;
 use std::env::var;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use tokio::fs::create_dir_all;
-
-use crate::manifests::AuthManifest;
+use crate::executor::fs::create_dir_all;
+use crate::manifests::{AuthManifest, PolicyManifest, TapsManifest};
 use crate::result::{RokitError, RokitResult};
-use crate::sources::ArtifactSource;
+use crate::sources::{
+    s3::DEFAULT_REGION as DEFAULT_S3_REGION, ArtifactProvider, ArtifactSource, Downloader,
+    HttpClient,
+};
+use crate::tool::ToolSpec;
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "reqwest-client")]
+use std::{str::FromStr, time::Duration};
+
+#[cfg(feature = "reqwest-client")]
+use tracing::warn;
+
+#[cfg(feature = "reqwest-client")]
+use crate::sources::{http_client_with_options, HttpClientOptions, RequestPolicy};
+
+use super::{
+    home_lock::HomeLock, preflight::check_dir, AssetMetadataCache, HttpCache, InstallJournal,
+    LatestVersionCache, Metrics, MetricsRecord, PartialDownloads, SharedCache, ToolCache,
+    ToolStorage, UsageStats,
+};
+
+const PROFILES_DIR_NAME: &str = "profiles";
+const DEFAULT_PROFILE_NAME: &str = "default";
+const OFFLINE_ENV_VAR: &str = "ROKIT_OFFLINE";
+
+/**
+    Environment variable that overrides the timeout for requests, in seconds.
+*/
+pub const TIMEOUT_ENV_VAR: &str = "ROKIT_TIMEOUT_SECS";
+
+/**
+    Environment variable that overrides the number of retries for requests.
+*/
+pub const RETRIES_ENV_VAR: &str = "ROKIT_RETRIES";
+
+/**
+    Environment variable that overrides the time to wait
+    before the first retry of a request, in milliseconds.
+*/
+pub const RETRY_BACKOFF_ENV_VAR: &str = "ROKIT_RETRY_BACKOFF_MS";
+
+/**
+    Providers that may have their own timeout and retry policy.
+*/
+#[cfg(feature = "reqwest-client")]
+const REQUEST_POLICY_PROVIDERS: [ArtifactProvider; 8] = [
+    ArtifactProvider::GitHub,
+    ArtifactProvider::GitLab,
+    ArtifactProvider::Url,
+    ArtifactProvider::Registry,
+    ArtifactProvider::S3,
+    ArtifactProvider::Crates,
+    ArtifactProvider::Npm,
+    ArtifactProvider::Git,
+];
+
+/**
+    Checks if Rokit should run offline, which is the case
+    when the `ROKIT_OFFLINE` environment variable is set to `1`.
+
+    See [`Home::offline_artifact_source`] for more information.
+*/
+#[must_use]
+pub fn offline_from_env() -> bool {
+    var(OFFLINE_ENV_VAR).is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes" | "on"))
+}
+
+/*
+    Overrides for the request policy of all providers, from the
+    `ROKIT_TIMEOUT_SECS`, `ROKIT_RETRIES`, and `ROKIT_RETRY_BACKOFF_MS`
+    environment variables, which take precedence over the policy manifest.
+*/
+#[cfg(feature = "reqwest-client")]
+#[derive(Debug, Default, Clone, Copy)]
+struct RequestPolicyOverrides {
+    timeout: Option<Duration>,
+    retries: Option<u32>,
+    retry_backoff: Option<Duration>,
+}
+
+#[cfg(feature = "reqwest-client")]
+impl RequestPolicyOverrides {
+    fn from_env() -> Self {
+        Self {
+            timeout: env_number(TIMEOUT_ENV_VAR, |secs: &u64| *secs > 0).map(Duration::from_secs),
+            retries: env_number(RETRIES_ENV_VAR, |_: &u32| true),
+            retry_backoff: env_number(RETRY_BACKOFF_ENV_VAR, |ms: &u64| *ms > 0)
+                .map(Duration::from_millis),
+        }
+    }
 
-use super::{ToolCache, ToolStorage};
+    fn apply(self, policy: RequestPolicy) -> RequestPolicy {
+        RequestPolicy {
+            timeout: self.timeout.unwrap_or(policy.timeout),
+            retries: self.retries.unwrap_or(policy.retries),
+            retry_backoff: self.retry_backoff.unwrap_or(policy.retry_backoff),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest-client")]
+fn env_number<T: FromStr>(name: &str, is_valid: impl Fn(&T) -> bool) -> Option<T> {
+    let value = var(name).ok()?;
+    let parsed = value.trim().parse::<T>().ok().filter(is_valid);
+    if parsed.is_none() {
+        warn!(%value, "ignoring invalid value for {name}");
+    }
+    parsed
+}
 
 /**
     Rokit's home directory - this is where Rokit stores its
@@ -17,28 +124,98 @@ use super::{ToolCache, ToolStorage};
 
     By default, this is `$HOME/.rokit`, but can be overridden
     by setting the `ROKIT_ROOT` environment variable.
+
+    A home may also belong to a named profile, in which case it is
+    stored in `profiles/<name>` inside of the root home directory,
+    and has its own independent storage, trust, and authentication.
+    Tool links are always shared between all profiles.
 */
 #[derive(Debug, Clone)]
 pub struct Home {
     path: Arc<Path>,
+    root_path: Arc<Path>,
+    profile: Option<Arc<str>>,
     tool_storage: ToolStorage,
     tool_cache: ToolCache,
+    install_journal: InstallJournal,
+    latest_versions: LatestVersionCache,
+    shared_cache: SharedCache,
 }
 
 impl Home {
     /**
-        Creates a new `Home` from the given path.
+        Creates a new `Home` from the given root path and optional profile.
     */
-    async fn load_from_path(path: impl Into<PathBuf>) -> RokitResult<Self> {
-        let path: Arc<Path> = path.into().into();
+    async fn load_from_path(
+        root_path: impl Into<PathBuf>,
+        profile: Option<&str>,
+    ) -> RokitResult<Self> {
+        let root_path: Arc<Path> = root_path.into().into();
+        let profile: Option<Arc<str>> = match profile.map(str::trim) {
+            None | Some("" | DEFAULT_PROFILE_NAME) => None,
+            Some(name) if is_valid_profile_name(name) => Some(name.into()),
+            Some(name) => return Err(RokitError::InvalidProfileName(name.to_string())),
+        };
+
+        let path: Arc<Path> = match profile.as_deref() {
+            Some(name) => root_path.join(PROFILES_DIR_NAME).join(name).into(),
+            // NOTE: Don't share the Arc with root_path here, we use
+            // the strong count of path to detect the last Home dropped
+            None => root_path.to_path_buf().into(),
+        };
+        create_dir_all(&path).await?;
+
+        let (tool_storage, tool_cache, install_journal, latest_versions) = futures::try_join!(
+            ToolStorage::load(&path, &root_path),
+            ToolCache::load(&path),
+            InstallJournal::load(&path),
+            LatestVersionCache::load(&path)
+        )?;
+        let shared_cache = SharedCache::from_env()
+            .unwrap_or_else(|| SharedCache::new(path.join("cache").join("artifacts")));
 
-        let (tool_storage, tool_cache) =
-            tokio::try_join!(ToolStorage::load(&path), ToolCache::load(&path))?;
+        // Tools that were installed by an interrupted run of Rokit
+        // never made it into the tool cache, so we add them here
+        for spec in install_journal.all_installed().await {
+            let _ = tool_cache.add_installed(spec);
+        }
+
+        // Tools that an interrupted run of Rokit was in the middle of writing
+        // may be only partially written, so we remove them to start over, and
+        // also clean up any downloads stored in the journal by older versions
+        // NOTE: Once a tool is no longer in the tool cache, it will be installed
+        // again even if its binary remains, so cleanup is only ever best-effort
+        for spec in install_journal.take_interrupted().await? {
+            tracing::warn!(%spec, "removing tool from an interrupted installation");
+            let _ = tool_cache.remove_installed(&spec);
+            if let Err(e) = tool_storage.remove_tool_contents(&spec).await {
+                tracing::debug!(%spec, error = %e, "failed to remove interrupted tool");
+            }
+        }
+        // Tools from providers other than GitHub used to share their directories
+        // with GitHub tools, and any that can not be moved must be installed again
+        for spec in tool_storage
+            .migrate_provider_dirs(&tool_cache.all_installed())
+            .await
+        {
+            tracing::warn!(%spec, "tool directory could not be moved, it will be installed again");
+            let _ = tool_cache.remove_installed(&spec);
+        }
+        match install_journal.remove_legacy_downloads().await {
+            Ok(false) => {}
+            Ok(true) => tracing::debug!("removed legacy journal downloads"),
+            Err(e) => tracing::debug!(error = %e, "failed to remove legacy journal downloads"),
+        }
 
         Ok(Self {
             path,
+            root_path,
+            profile,
             tool_storage,
             tool_cache,
+            install_journal,
+            latest_versions,
+            shared_cache,
         })
     }
 
@@ -51,30 +228,93 @@ impl Home {
         If the `ROKIT_ROOT` environment variable is set, this will use
         that as the home directory. Otherwise, it will use `$HOME/.rokit`.
 
+        If the `ROKIT_PROFILE` environment variable is set, this will
+        use the home for that profile, see [`Home::load_from_env_with_profile`].
+
         # Errors
 
         - If the home directory could not be read or created.
+        - If the profile name is invalid.
     */
     pub async fn load_from_env() -> RokitResult<Self> {
+        let profile = var("ROKIT_PROFILE").ok();
+        Self::load_from_env_with_profile(profile.as_deref()).await
+    }
+
+    /**
+        Creates a new `Home` from the environment, for the given profile.
+
+        Works the same as [`Home::load_from_env`], but ignores the `ROKIT_PROFILE`
+        environment variable. Passing `None`, an empty string, or `default` as
+        the profile will use the default profile, which is the root home directory.
+
+        Profile names may only contain ASCII letters, digits, dashes and underscores.
+
+        # Errors
+
+        - If the home directory could not be read or created.
+        - If the profile name is invalid.
+    */
+    pub async fn load_from_env_with_profile(profile: Option<&str>) -> RokitResult<Self> {
         if let Ok(root_str) = var("ROKIT_ROOT") {
-            Self::load_from_path(root_str).await
+            Self::load_from_path(root_str, profile).await
         } else {
             let path = dirs::home_dir()
                 .ok_or(RokitError::HomeNotFound)?
                 .join(".rokit");
-            create_dir_all(&path).await?;
-            Self::load_from_path(path).await
+            Self::load_from_path(path, profile).await
         }
     }
 
     /**
         Gets a reference to the path for this `Home`.
+
+        If this `Home` belongs to a named profile, this is the profile directory.
     */
     #[must_use]
     pub fn path(&self) -> &Path {
         &self.path
     }
 
+    /**
+        Gets a reference to the root path for this `Home`.
+
+        This is shared between all profiles, and contains the `bin` directory.
+    */
+    #[must_use]
+    pub fn root_path(&self) -> &Path {
+        &self.root_path
+    }
+
+    /**
+        Gets the name of the profile for this `Home`, if
+        it belongs to a named profile and not the default one.
+    */
+    #[must_use]
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /**
+        Checks that the directories used by this `Home` are usable, meaning
+        that they are writable, and that tools and links stored in them
+        may be executed - this can be used to fail early with a helpful
+        error message, instead of failing in the middle of an installation.
+
+        # Errors
+
+        - If any of the directories are not writable.
+        - If any of the directories are on a filesystem without support for executables.
+    */
+    pub async fn preflight(&self) -> RokitResult<()> {
+        futures::try_join!(
+            check_dir(&self.path, false),
+            check_dir(&self.tool_storage.tools_dir, true),
+            check_dir(&self.tool_storage.aliases_dir, true),
+        )?;
+        Ok(())
+    }
+
     /**
         Returns a reference to the `ToolStorage` for this `Home`.
     */
@@ -83,6 +323,91 @@ impl Home {
         &self.tool_storage
     }
 
+    /**
+        Returns a reference to the `SharedCache` for this `Home`, which is stored in
+        its `cache/artifacts` directory, unless `ROKIT_SHARED_CACHE` is set.
+
+        See [`SharedCache`] for more information.
+    */
+    #[must_use]
+    pub fn shared_cache(&self) -> &SharedCache {
+        &self.shared_cache
+    }
+
+    /**
+        Records a single run of the given tool, for usage statistics.
+
+        This is cheap, and safe to call from many processes at once.
+
+        # Errors
+
+        - If the usage could not be written to disk.
+    */
+    pub async fn record_tool_usage(&self, spec: &ToolSpec) -> RokitResult<()> {
+        UsageStats::record_usage(&self.path, spec).await
+    }
+
+    /**
+        Loads usage statistics for all tools run using this `Home`.
+
+        See [`UsageStats`] for more information.
+
+        # Errors
+
+        - If the usage statistics could not be compacted.
+    */
+    pub async fn usage_stats(&self) -> RokitResult<UsageStats> {
+        {
+            let _lock = HomeLock::acquire(&self.path).await?;
+            UsageStats::compact_if_needed(&self.path).await?;
+        }
+        UsageStats::load(&self.path).await
+    }
+
+    /**
+        Records a single run of a Rokit command, for anonymous metrics.
+
+        This should only be called if metrics are enabled in the policy manifest.
+
+        # Errors
+
+        - If the record could not be written to disk.
+    */
+    pub async fn record_metrics(&self, record: &MetricsRecord) -> RokitResult<()> {
+        Metrics::record(&self.path, record).await
+    }
+
+    /**
+        Loads metrics for all Rokit commands run using this `Home`.
+
+        See [`Metrics`] for more information.
+
+        # Errors
+
+        - If the metrics could not be loaded.
+    */
+    pub async fn metrics(&self) -> RokitResult<Metrics> {
+        Metrics::load(&self.path).await
+    }
+
+    /**
+        Gets when metrics for this `Home` were last posted to an endpoint, if ever.
+    */
+    pub async fn metrics_last_posted(&self) -> Option<DateTime<Utc>> {
+        Metrics::last_posted(&self.path).await
+    }
+
+    /**
+        Marks metrics for this `Home` as having been posted to an endpoint, right now.
+
+        # Errors
+
+        - If the timestamp could not be written to disk.
+    */
+    pub async fn set_metrics_last_posted(&self) -> RokitResult<()> {
+        Metrics::set_last_posted(&self.path).await
+    }
+
     /**
         Returns a reference to the `ToolCache` for this `Home`.
     */
@@ -91,33 +416,306 @@ impl Home {
         &self.tool_cache
     }
 
+    /**
+        Returns a reference to the `InstallJournal` for this `Home`.
+    */
+    #[must_use]
+    pub fn install_journal(&self) -> &InstallJournal {
+        &self.install_journal
+    }
+
+    /**
+        Returns a reference to the `LatestVersionCache` for this `Home`.
+    */
+    #[must_use]
+    pub fn latest_versions(&self) -> &LatestVersionCache {
+        &self.latest_versions
+    }
+
+    /**
+        Returns the `HttpCache` for this `Home`, which caches release
+        metadata in its `cache/http` directory, see [`HttpCache`].
+    */
+    #[must_use]
+    pub fn http_cache(&self) -> HttpCache {
+        HttpCache::new(self.path.join("cache").join("http"))
+    }
+
+    /**
+        Returns the `AssetMetadataCache` for this `Home`, which caches asset
+        sizes in its `cache/assets` directory, see [`AssetMetadataCache`].
+    */
+    #[must_use]
+    pub fn asset_metadata(&self) -> AssetMetadataCache {
+        AssetMetadataCache::new(self.path.join("cache").join("assets"))
+    }
+
+    /**
+        Gets the storage for interrupted downloads of this `Home`,
+        which is stored in the `cache/downloads` directory.
+    */
+    #[must_use]
+    pub fn partial_downloads(&self) -> PartialDownloads {
+        PartialDownloads::new(self.path.join("cache").join("downloads"))
+    }
+
     /**
         Creates a new `ArtifactSource` for this `Home`.
 
         This will load any stored authentication from disk and use
-        it to authenticate with the artifact source and various providers.
+        it to authenticate with the artifact source and various providers,
+        as well as any custom registry, S3 bucket, provider URLs, mirrors, proxy,
+        CA bundle, timeouts and retries, and the maximum number of concurrent
+        downloads from the policy manifest, and the HTTP cache for release metadata.
+
+        Timeouts and retries may be overridden for all providers using the
+        `ROKIT_TIMEOUT_SECS`, `ROKIT_RETRIES`, and `ROKIT_RETRY_BACKOFF_MS`
+        environment variables - see [`PolicyManifest::request_policy`].
+
+        Interrupted artifact downloads are stored in [`Home::partial_downloads`],
+        and resumed from where they were interrupted by later downloads.
+
+        If the `ROKIT_OFFLINE` environment variable is set, this
+        returns [`Home::offline_artifact_source`] instead.
 
         # Errors
 
-        - If the auth manifest could not be loaded or created.
+        - If the auth or policy manifest could not be loaded or created.
+        - If the CA bundle could not be read.
         - If the artifact source could not be created.
     */
+    #[cfg(feature = "reqwest-client")]
     pub async fn artifact_source(&self) -> RokitResult<ArtifactSource> {
-        let auth = AuthManifest::load_or_create(&self.path).await?;
-        ArtifactSource::new_authenticated(&auth.get_all_tokens())
+        self.artifact_source_impl(true).await
+    }
+
+    /**
+        Creates a new `ArtifactSource` for this `Home`, same as
+        [`Home::artifact_source`], but without any stored authentication.
+
+        This should be used whenever requests are made on behalf of someone
+        else, such as clients of a proxy, who must never be able to use the
+        stored credentials of the user running Rokit.
+
+        # Errors
+
+        - If the policy manifest could not be loaded or created.
+        - If the CA bundle could not be read.
+        - If the artifact source could not be created.
+    */
+    #[cfg(feature = "reqwest-client")]
+    pub async fn anonymous_artifact_source(&self) -> RokitResult<ArtifactSource> {
+        self.artifact_source_impl(false).await
+    }
+
+    #[cfg(feature = "reqwest-client")]
+    async fn artifact_source_impl(&self, authenticated: bool) -> RokitResult<ArtifactSource> {
+        if offline_from_env() {
+            let client = self.http_cache().offline_client();
+            let source = self.source_with_client(client, authenticated).await?;
+            return Ok(source.offline());
+        }
+        let policy = PolicyManifest::load_or_create(&self.path).await?;
+        let overrides = RequestPolicyOverrides::from_env();
+        let options = HttpClientOptions {
+            proxy: policy.proxy_url(),
+            // NOTE: Relative paths are relative to the Rokit home directory
+            ca_bundle: policy.ca_bundle_path().map(|path| self.path.join(path)),
+            request_policy: overrides.apply(policy.request_policy(None)),
+        };
+        let mut source = self
+            .source_with_client(http_client_with_options(&options)?, authenticated)
+            .await?;
+
+        // NOTE: Timeouts and retries are set when creating a client,
+        // so providers with their own policy also need their own client
+        for provider in REQUEST_POLICY_PROVIDERS {
+            let request_policy = overrides.apply(policy.request_policy(Some(provider)));
+            if request_policy == options.request_policy {
+                continue;
+            }
+            let options = HttpClientOptions {
+                request_policy,
+                ..options.clone()
+            };
+            let client = http_client_with_options(&options)?;
+            source =
+                source.with_provider_client(provider, self.partial_downloads().wrap_client(client));
+        }
+
+        Ok(source)
+    }
+
+    /**
+        Creates a new `ArtifactSource` for this `Home` that never accesses the network.
+
+        Releases are only resolved from the HTTP cache, and any request
+        for something that is not cached - such as any artifact download -
+        fails, see [`HttpCache::offline_client`] for more information.
+        Tools from git repositories are only resolved if they have an
+        exact tag, since listing tags needs to run `git`.
+
+        # Errors
+
+        - If the auth or policy manifest could not be loaded or created.
+        - If the artifact source could not be created.
+    */
+    pub async fn offline_artifact_source(&self) -> RokitResult<ArtifactSource> {
+        let source = self
+            .artifact_source_with_client(self.http_cache().offline_client())
+            .await?;
+        Ok(source.offline())
+    }
+
+    /**
+        Creates a new `ArtifactSource` for this `Home`, using the given HTTP client.
+
+        See [`Home::artifact_source`] for more information.
+
+        # Errors
+
+        - If the auth or policy manifest could not be loaded or created.
+        - If the artifact source could not be created.
+    */
+    pub async fn artifact_source_with_client(
+        &self,
+        client: Arc<dyn HttpClient>,
+    ) -> RokitResult<ArtifactSource> {
+        self.source_with_client(client, true).await
+    }
+
+    async fn source_with_client(
+        &self,
+        client: Arc<dyn HttpClient>,
+        authenticated: bool,
+    ) -> RokitResult<ArtifactSource> {
+        let policy = PolicyManifest::load_or_create(&self.path).await?;
+        let downloader = Downloader::with_concurrency(
+            self.partial_downloads().wrap_client(client),
+            policy.max_concurrent_downloads(),
+        );
+        let source = if authenticated {
+            let auth = AuthManifest::load_or_create(&self.path).await?;
+            ArtifactSource::new_authenticated_with_downloader(downloader, &auth.get_all_tokens())?
+                .with_host_tokens(&auth.get_all_host_tokens())?
+        } else {
+            ArtifactSource::new_authenticated_with_downloader(downloader, &HashMap::new())?
+        };
+        self.with_policy_sources(source, &policy)
+    }
+
+    fn with_policy_sources(
+        &self,
+        source: ArtifactSource,
+        policy: &PolicyManifest,
+    ) -> RokitResult<ArtifactSource> {
+        let mut source = source
+            .with_http_cache(self.http_cache())
+            .with_asset_metadata_cache(self.asset_metadata());
+        for provider in [ArtifactProvider::GitHub, ArtifactProvider::GitLab] {
+            if let Some(url) = policy.api_url(provider) {
+                source = source.with_api_url(provider, &url)?;
+            }
+            if let Some(url) = policy.download_url(provider) {
+                source = source.with_download_url(provider, &url)?;
+            }
+            let mirrors = policy.mirrors(provider);
+            if !mirrors.is_empty() {
+                source = source.with_mirrors(provider, mirrors);
+            }
+        }
+        if let Some(url) = policy.s3_bucket_url() {
+            let region = policy
+                .s3_region()
+                .unwrap_or_else(|| DEFAULT_S3_REGION.to_string());
+            source = source.with_s3_bucket(&url, &region)?;
+        }
+        match policy.registry_url() {
+            Some(url) => source.with_registry(&url),
+            None => Ok(source),
+        }
+    }
+
+    /**
+        Loads the taps manifest for this `Home`, which is used
+        to resolve short tool names such as `rojo@7.4.4`.
+
+        # Errors
+
+        - If the taps manifest could not be loaded or created.
+    */
+    pub async fn taps(&self) -> RokitResult<TapsManifest> {
+        TapsManifest::load_or_create(&self.path).await
+    }
+
+    /**
+        Checks if the contents of this `Home` have changed since
+        it was loaded or last saved, and need to be saved to disk.
+    */
+    pub async fn needs_saving(&self) -> bool {
+        self.tool_cache.needs_saving()
+            || self.tool_storage.needs_saving()
+            || self.latest_versions.needs_saving()
+            || self.install_journal.needs_saving().await
     }
 
     /**
         Saves the contents of this `Home` to disk.
 
+        This will always rewrite all of the contents, even if they
+        have not changed - prefer [`Home::save_if_dirty`] when possible.
+
+        Changes made by other Rokit processes while this `Home` was
+        loaded are merged in while saving, and are never overwritten.
+
         # Errors
 
         - If the contents could not be saved to disk.
     */
     pub async fn save(&self) -> RokitResult<()> {
+        let _lock = HomeLock::acquire(&self.path).await?;
         self.tool_cache.save(&self.path).await?;
+        self.latest_versions.save(&self.path).await?;
+        self.install_journal.clear_installed().await?;
         Ok(())
     }
+
+    /**
+        Saves the contents of this `Home` to disk, but only the
+        components that have changed since it was loaded or last saved.
+
+        Returns `true` if anything was written to disk.
+
+        # Errors
+
+        - If the contents could not be saved to disk.
+    */
+    pub async fn save_if_dirty(&self) -> RokitResult<bool> {
+        let mut saved = false;
+        if self.tool_cache.needs_saving() {
+            let _lock = HomeLock::acquire(&self.path).await?;
+            self.tool_cache.save(&self.path).await?;
+            saved = true;
+        }
+        if self.latest_versions.needs_saving() {
+            self.latest_versions.save(&self.path).await?;
+            saved = true;
+        }
+        // NOTE: The journal must only be cleared after the tool cache has
+        // been saved, which is either now or during some previous run
+        if self.install_journal.needs_saving().await {
+            self.install_journal.clear_installed().await?;
+            saved = true;
+        }
+        Ok(saved)
+    }
+}
+
+fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
 }
 
 /*
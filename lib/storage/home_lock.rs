@@ -0,0 +1,86 @@
+use std::{
+    fs::OpenOptions,
+    io::{Error as IoError, ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use tracing::{debug, trace};
+
+use crate::{
+    executor::{
+        fs::{metadata, remove_file},
+        sleep, spawn_blocking,
+    },
+    result::RokitResult,
+};
+
+const LOCK_FILE_NAME: &str = ".lock";
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(25);
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(10);
+
+/**
+    Advisory lock for a Rokit home directory, held while writing
+    shared state such as the tool cache, so that multiple concurrent
+    Rokit processes do not overwrite each other's changes.
+
+    The lock is a file that is created exclusively, and removed when
+    the lock is dropped. A lock file that is older than a few seconds
+    was most likely left behind by a crashed process, and is ignored.
+*/
+#[derive(Debug)]
+pub(crate) struct HomeLock {
+    path: PathBuf,
+}
+
+impl HomeLock {
+    pub(crate) async fn acquire(home_path: impl AsRef<Path>) -> RokitResult<Self> {
+        let path = home_path.as_ref().join(LOCK_FILE_NAME);
+        let start = Instant::now();
+
+        loop {
+            let lock_path = path.clone();
+            let result = spawn_blocking(move || {
+                let mut file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(lock_path)?;
+                // NOTE: The process id is only written to help with debugging,
+                // it is never read - failing to write it is not an error
+                let _ = file.write_all(std::process::id().to_string().as_bytes());
+                Ok::<_, IoError>(())
+            })
+            .await?;
+            match result {
+                Ok(()) => {
+                    trace!(?path, elapsed = ?start.elapsed(), "Acquired home lock");
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if is_stale(&path).await {
+                        debug!(?path, "Removing stale home lock");
+                        let _ = remove_file(&path).await;
+                    } else {
+                        sleep(LOCK_RETRY_INTERVAL).await;
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for HomeLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+async fn is_stale(path: &Path) -> bool {
+    let Ok(modified) = metadata(path).await.and_then(|m| m.modified()) else {
+        return false;
+    };
+    SystemTime::now()
+        .duration_since(modified)
+        .is_ok_and(|age| age > LOCK_STALE_AFTER)
+}
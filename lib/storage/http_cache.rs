@@ -0,0 +1,276 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    result::RokitResult,
+    sources::{HttpClient, HttpError, HttpMethod, HttpOfflineError, HttpRequest, HttpResponse},
+};
+
+use super::url_entries::{UrlEntries, UrlEntry};
+
+const STATUS_NOT_MODIFIED: u16 = 304;
+
+/**
+    A cached HTTP response, together with the validators
+    that can be used to check if it is still up to date.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpCacheEntry {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+impl HttpCacheEntry {
+    /**
+        Creates a new cache entry from the given response.
+
+        Returns `None` if the response was not successful, if it has
+        neither an `ETag` nor a `Last-Modified` header, or if its body
+        is not text - such responses can not be revalidated, or are
+        not metadata, and are never cached.
+    */
+    #[must_use]
+    pub fn from_response(response: &HttpResponse) -> Option<Self> {
+        if !response.is_success() {
+            return None;
+        }
+        let etag = response.header("etag").map(ToString::to_string);
+        let last_modified = response.header("last-modified").map(ToString::to_string);
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+        let body = String::from_utf8(response.body.clone()).ok()?;
+        Some(Self {
+            url: response.url.clone(),
+            etag,
+            last_modified,
+            body,
+        })
+    }
+
+    /**
+        Gets the headers to send to check if this entry is still up to date.
+    */
+    #[must_use]
+    pub fn conditional_headers(&self) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        if let Some(etag) = &self.etag {
+            headers.push((String::from("if-none-match"), etag.clone()));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.push((String::from("if-modified-since"), last_modified.clone()));
+        }
+        headers
+    }
+}
+
+impl UrlEntry for HttpCacheEntry {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/**
+    A cache for HTTP metadata, such as release listings, stored in
+    the `cache/http` directory of the Rokit home directory.
+
+    Responses are stored together with their `ETag` and `Last-Modified`
+    headers, which are sent with later requests to the same URL. When
+    the server responds with `304 Not Modified`, the cached response is
+    used instead - such requests are much faster, and are not counted
+    towards the rate limit of providers such as GitHub.
+
+    Entries are stored as `<sha256 of url>.json`, and a missing or
+    corrupted entry simply means that the full response is fetched again.
+
+    Can be cheaply cloned while still referring to the same underlying data.
+*/
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    entries: UrlEntries,
+}
+
+impl HttpCache {
+    /**
+        Creates a new `HttpCache` stored in the given directory.
+    */
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            entries: UrlEntries::new(root.into()),
+        }
+    }
+
+    /**
+        Gets the path to the directory that this cache is stored in.
+    */
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.entries.path()
+    }
+
+    /**
+        Reads the cached entry for the given URL, if any.
+    */
+    #[instrument(skip(self), level = "trace")]
+    pub async fn read(&self, url: &str) -> Option<HttpCacheEntry> {
+        self.entries.read(url).await
+    }
+
+    /**
+        Writes the given entry to the cache, replacing any older entry for the same URL.
+
+        # Errors
+
+        - If the entry could not be written to the cache.
+    */
+    #[instrument(skip(self, entry), fields(url = %entry.url), level = "trace")]
+    pub async fn write(&self, entry: &HttpCacheEntry) -> RokitResult<()> {
+        self.entries.write(entry).await?;
+        trace!("wrote http cache entry");
+        Ok(())
+    }
+
+    /**
+        Sends the given request using the given client, as a conditional
+        request if there is a cached entry for its URL.
+
+        If the server responds with `304 Not Modified`, the cached response
+        is returned instead. Successful responses that can be revalidated
+        are stored in the cache, replacing any older entry.
+
+        Only `GET` requests are cached - any other request is sent as-is.
+
+        # Errors
+
+        - If the client failed to send the request.
+    */
+    pub async fn send(
+        &self,
+        client: &dyn HttpClient,
+        mut request: HttpRequest,
+    ) -> Result<HttpResponse, HttpError> {
+        if request.method != HttpMethod::Get {
+            return client.send(request).await;
+        }
+
+        let cached = self.read(&request.url).await;
+        if let Some(entry) = &cached {
+            request.headers.extend(entry.conditional_headers());
+        }
+
+        let response = client.send(request).await?;
+        if response.status == STATUS_NOT_MODIFIED {
+            if let Some(entry) = cached {
+                debug!(url = %entry.url, "using cached response, not modified");
+                return Ok(HttpResponse {
+                    url: entry.url,
+                    status: 200,
+                    headers: Vec::new(),
+                    body: entry.body.into_bytes(),
+                });
+            }
+        }
+
+        // NOTE: The cache is only an optimization, and failing
+        // to write to it must never fail the request itself
+        if let Some(entry) = HttpCacheEntry::from_response(&response) {
+            if let Err(e) = self.write(&entry).await {
+                debug!(url = %entry.url, error = %e, "failed to write http cache entry");
+            }
+        }
+
+        Ok(response)
+    }
+
+    /**
+        Creates a HTTP client that never accesses the network, and
+        instead responds to requests using entries in this cache.
+
+        Requests for any URL that is not cached, or that are not `GET`
+        requests, fail with an [`HttpOfflineError`] - this includes
+        all artifact downloads, since those are never cached here.
+    */
+    #[must_use]
+    pub fn offline_client(&self) -> Arc<dyn HttpClient> {
+        Arc::new(OfflineClient {
+            cache: self.clone(),
+        })
+    }
+}
+
+/*
+    A client that serves requests from a HTTP cache, see [`HttpCache::offline_client`].
+*/
+#[derive(Debug)]
+struct OfflineClient {
+    cache: HttpCache,
+}
+
+impl HttpClient for OfflineClient {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>> {
+        Box::pin(async move {
+            let cached = match request.method {
+                HttpMethod::Get => self.cache.read(&request.url).await,
+                HttpMethod::Head | HttpMethod::Post => None,
+            };
+            let Some(entry) = cached else {
+                return Err(HttpOfflineError { url: request.url }.into());
+            };
+            debug!(url = %entry.url, "using cached response while offline");
+            Ok(HttpResponse {
+                url: entry.url,
+                status: 200,
+                headers: Vec::new(),
+                body: entry.body.into_bytes(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_need_validators() {
+        assert_eq!(
+            HttpCacheEntry::from_response(&HttpResponse::for_test(200, &[], b"[]")),
+            None
+        );
+        assert_eq!(
+            HttpCacheEntry::from_response(&HttpResponse::for_test(
+                404,
+                &[("ETag", "\"a\"")],
+                b"[]"
+            )),
+            None
+        );
+
+        let entry = HttpCacheEntry::from_response(&HttpResponse::for_test(
+            200,
+            &[("ETag", "\"a\""), ("Last-Modified", "Tue, 01 Oct 2024")],
+            b"[]",
+        ))
+        .unwrap();
+        assert_eq!(entry.body, "[]");
+        assert_eq!(
+            entry.conditional_headers(),
+            vec![
+                (String::from("if-none-match"), String::from("\"a\"")),
+                (
+                    String::from("if-modified-since"),
+                    String::from("Tue, 01 Oct 2024")
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tempfile::NamedTempFile;
+use tokio::sync::Mutex as AsyncMutex;
+use tracing::{debug, trace, warn};
+
+use crate::{
+    executor::{
+        fs::{create_dir_all, read, read_to_string, remove_dir_all, try_exists},
+        spawn_blocking,
+    },
+    result::RokitResult,
+    tool::{ToolAlias, ToolSpec},
+    util::fs::{durable_writes, sync_parent_dir},
+};
+
+use super::schema::Schema;
+
+const JOURNAL_DIR_NAME: &str = ".journal";
+const JOURNAL_FILE_NAME: &str = "journal.json";
+const LEGACY_DOWNLOADS_DIR_NAME: &str = "downloads";
+
+/*
+    Versioned format of the journal file - add a migration
+    here whenever the format changes, see `Schema` for details.
+*/
+const SCHEMA: Schema = Schema::new(&[migrate_downloads_to_paths]);
+
+/*
+    Other Rokit processes may be installing tools right now, using the
+    same journal directory - anything younger than this is left alone,
+    since it can not be told apart from an installation in progress.
+*/
+const STALE_AFTER: Duration = Duration::from_mins(10);
+
+/**
+    Journal for tracking the progress of tool installations.
+
+    Unlike the [`ToolCache`](super::ToolCache), the journal is written to disk
+    as soon as it changes, so that an interrupted installation can be resumed
+    by the next invocation of Rokit instead of starting over from scratch.
+
+    Can be cheaply cloned while still referring to the same underlying data.
+*/
+#[derive(Debug, Clone)]
+pub struct InstallJournal {
+    dir: Arc<Path>,
+    state: Arc<AsyncMutex<JournalState>>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct JournalState {
+    #[serde(default)]
+    downloaded: BTreeMap<ToolSpec, PathBuf>,
+    #[serde(default)]
+    installed: BTreeSet<ToolSpec>,
+    #[serde(default)]
+    unlinked: BTreeSet<ToolAlias>,
+    #[serde(default)]
+    installing: BTreeMap<ToolSpec, DateTime<Utc>>,
+}
+
+impl InstallJournal {
+    fn journal_path(&self) -> PathBuf {
+        self.dir.join(JOURNAL_FILE_NAME)
+    }
+
+    async fn write_state(&self, state: &JournalState) -> RokitResult<()> {
+        let json = SCHEMA.with_version(serde_json::to_value(state)?);
+        let contents = serde_json::to_string(&json)?;
+
+        // NOTE: The journal is replaced atomically, so that Rokit exiting
+        // while writing it never leaves a partially written journal behind
+        let dir = self.dir.to_path_buf();
+        let path = self.journal_path();
+        let durable = durable_writes();
+        spawn_blocking(move || {
+            let mut file = NamedTempFile::new_in(dir)?;
+            file.write_all(contents.as_bytes())?;
+            if durable {
+                file.as_file().sync_all()?;
+            }
+            file.persist(path).map_err(|e| e.error)?;
+            RokitResult::Ok(())
+        })
+        .await??;
+
+        if durable {
+            sync_parent_dir(self.journal_path()).await?;
+        }
+        Ok(())
+    }
+
+    /**
+        Reads the contents of a previously downloaded artifact for the given tool.
+
+        Returns `None` if no download was recorded, or if the recorded
+        download could no longer be read and has been discarded.
+    */
+    pub async fn read_download(&self, spec: &ToolSpec) -> Option<Vec<u8>> {
+        let mut state = self.state.lock().await;
+        let path = state.downloaded.get(spec)?.clone();
+        if let Ok(contents) = read(&path).await {
+            debug!(%spec, ?path, "resuming from journaled download");
+            Some(contents)
+        } else {
+            warn!(%spec, ?path, "journaled download is missing, discarding it");
+            state.downloaded.remove(spec);
+            self.write_state(&state).await.ok();
+            None
+        }
+    }
+
+    /**
+        Checks if a previously downloaded artifact for the given tool has been
+        recorded, and still exists, without reading its contents.
+    */
+    pub async fn has_download(&self, spec: &ToolSpec) -> bool {
+        let state = self.state.lock().await;
+        match state.downloaded.get(spec) {
+            Some(path) => try_exists(path).await.unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /**
+        Records the path to the downloaded artifact for the given tool.
+
+        The journal never stores artifacts itself, only the path to where
+        they were already stored once downloaded, such as in the shared
+        cache - the file must be fully written before it is recorded.
+
+        # Errors
+
+        - If the journal could not be written to disk.
+    */
+    pub async fn record_download(
+        &self,
+        spec: &ToolSpec,
+        path: impl Into<PathBuf>,
+    ) -> RokitResult<()> {
+        let mut state = self.state.lock().await;
+        state.downloaded.insert(spec.clone(), path.into());
+        self.write_state(&state).await
+    }
+
+    /**
+        Records that the given tool is about to be written into tool storage.
+
+        If Rokit exits before [`InstallJournal::record_installed`] is called
+        for the tool, its binary may only be partially written, and it will
+        be removed by the next invocation of Rokit, to be installed again.
+
+        # Errors
+
+        - If the journal could not be written to disk.
+    */
+    pub async fn record_installing(&self, spec: &ToolSpec) -> RokitResult<()> {
+        let mut state = self.state.lock().await;
+        state.installing.insert(spec.clone(), Utc::now());
+        self.write_state(&state).await
+    }
+
+    /**
+        Records that the given tool has been fully installed into tool storage.
+
+        This also forgets any download recorded for the tool, since it is no longer needed.
+
+        # Errors
+
+        - If the journal could not be written to disk.
+    */
+    pub async fn record_installed(&self, spec: &ToolSpec) -> RokitResult<()> {
+        let mut state = self.state.lock().await;
+
+        state.downloaded.remove(spec);
+        state.installing.remove(spec);
+
+        state.installed.insert(spec.clone());
+        self.write_state(&state).await
+    }
+
+    /**
+        Records that links for the given aliases are about to be created.
+
+        # Errors
+
+        - If the journal could not be written to disk.
+    */
+    pub async fn record_unlinked(
+        &self,
+        aliases: impl IntoIterator<Item = ToolAlias>,
+    ) -> RokitResult<()> {
+        let mut state = self.state.lock().await;
+        state.unlinked.extend(aliases);
+        self.write_state(&state).await
+    }
+
+    /**
+        Records that links for the given aliases have been created.
+
+        # Errors
+
+        - If the journal could not be written to disk.
+    */
+    pub async fn record_linked<'a>(
+        &self,
+        aliases: impl IntoIterator<Item = &'a ToolAlias>,
+    ) -> RokitResult<()> {
+        let mut state = self.state.lock().await;
+        for alias in aliases {
+            state.unlinked.remove(alias);
+        }
+        self.write_state(&state).await
+    }
+
+    /**
+        Gets a sorted list of all tools that were installed,
+        but not yet saved to the tool cache, by a previous run.
+    */
+    pub async fn all_installed(&self) -> Vec<ToolSpec> {
+        let state = self.state.lock().await;
+        state.installed.iter().cloned().collect()
+    }
+
+    /**
+        Gets a sorted list of all aliases that a previous run
+        was going to create links for, but may not have finished.
+    */
+    pub async fn all_unlinked(&self) -> Vec<ToolAlias> {
+        let state = self.state.lock().await;
+        state.unlinked.iter().cloned().collect()
+    }
+
+    /**
+        Takes all tools that a previous run started writing into tool
+        storage, but never finished - most likely because it crashed.
+
+        The binaries of these tools may be only partially written, and
+        must be removed, so that they are installed again from scratch.
+
+        # Errors
+
+        - If the journal could not be written to disk.
+    */
+    pub(crate) async fn take_interrupted(&self) -> RokitResult<Vec<ToolSpec>> {
+        let mut state = self.state.lock().await;
+        let now = Utc::now();
+        let interrupted = state
+            .installing
+            .iter()
+            .filter(|(_, started)| {
+                (now - **started)
+                    .to_std()
+                    .is_ok_and(|elapsed| elapsed >= STALE_AFTER)
+            })
+            .map(|(spec, _)| spec.clone())
+            .collect::<Vec<_>>();
+        if interrupted.is_empty() {
+            return Ok(interrupted);
+        }
+
+        for spec in &interrupted {
+            state.installing.remove(spec);
+        }
+        self.write_state(&state).await?;
+        Ok(interrupted)
+    }
+
+    /**
+        Removes the downloads directory of the journal, which older versions
+        of Rokit stored a copy of every downloaded artifact in - downloads
+        are now only referred to by path, and never stored in the journal.
+
+        Returns `true` if the directory existed and was removed.
+
+        # Errors
+
+        - If the directory exists, but could not be removed.
+    */
+    pub(crate) async fn remove_legacy_downloads(&self) -> RokitResult<bool> {
+        match remove_dir_all(self.dir.join(LEGACY_DOWNLOADS_DIR_NAME)).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub(crate) async fn needs_saving(&self) -> bool {
+        // Installed tools are only kept in the journal until
+        // the tool cache has been saved, after which we clear them
+        let state = self.state.lock().await;
+        !state.installed.is_empty()
+    }
+
+    pub(crate) async fn clear_installed(&self) -> RokitResult<()> {
+        let mut state = self.state.lock().await;
+        if state.installed.is_empty() {
+            return Ok(());
+        }
+        state.installed.clear();
+        self.write_state(&state).await
+    }
+
+    pub(crate) async fn load(home_path: impl AsRef<Path>) -> RokitResult<Self> {
+        let dir: Arc<Path> = home_path
+            .as_ref()
+            .join("tool-storage")
+            .join(JOURNAL_DIR_NAME)
+            .into();
+        create_dir_all(&dir).await?;
+
+        // A missing or corrupted journal should never prevent Rokit from
+        // running, in the worst case we just start installation over again
+        let path = dir.join(JOURNAL_FILE_NAME);
+        let state = match read_to_string(&path).await {
+            Ok(contents) => parse_state(&path, &contents),
+            Err(_) => JournalState::default(),
+        };
+        trace!(?path, ?state, "Loaded install journal");
+
+        Ok(Self {
+            dir,
+            state: Arc::new(AsyncMutex::new(state)),
+        })
+    }
+}
+
+/*
+    Parses the contents of the journal file, migrating them from older
+    formats. Journals written by newer versions of Rokit are ignored,
+    same as corrupted ones, and any installation in them starts over.
+*/
+fn parse_state(path: &Path, contents: &str) -> JournalState {
+    let Ok(mut contents) = serde_json::from_str::<serde_json::Value>(contents) else {
+        return JournalState::default();
+    };
+    match SCHEMA.migrate(path, &mut contents) {
+        Ok(_) => serde_json::from_value(contents).unwrap_or_default(),
+        Err(e) => {
+            warn!("ignoring install journal - {e}");
+            JournalState::default()
+        }
+    }
+}
+
+/*
+    Downloads used to be stored in the journal itself, and were recorded
+    using only the tool spec - they are now recorded along with their path,
+    and the old downloads are removed, see `remove_legacy_downloads`.
+*/
+fn migrate_downloads_to_paths(object: &mut Map<String, Value>) {
+    object.insert(String::from("downloaded"), Value::Object(Map::new()));
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace};
+
+use crate::{
+    executor::fs::{create_dir_all, read_to_string, write},
+    result::RokitResult,
+    tool::ToolId,
+};
+
+/**
+    The latest known version of a tool, and when it was last checked.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatestVersion {
+    pub version: Version,
+    pub checked_at: DateTime<Utc>,
+}
+
+/**
+    Cache for the latest known version of each tool.
+
+    This is used to display possibly stale, but still useful, information
+    about available updates without having to access the network, such
+    as when listing tools or when checking for updates while offline.
+
+    Can be cheaply cloned while still referring to the same underlying data.
+*/
+#[derive(Debug, Default, Clone)]
+pub struct LatestVersionCache {
+    versions: Arc<DashMap<ToolId, LatestVersion>>,
+    needs_saving: Arc<AtomicBool>,
+}
+
+impl LatestVersionCache {
+    /**
+        Gets the latest known version of a tool, if it has ever been checked.
+    */
+    #[must_use]
+    pub fn get(&self, id: &ToolId) -> Option<LatestVersion> {
+        self.versions.get(id).map(|entry| entry.clone())
+    }
+
+    /**
+        Records the given version as the latest version of a tool, checked right now.
+    */
+    pub fn record(&self, id: &ToolId, version: &Version) {
+        let latest = LatestVersion {
+            version: version.clone(),
+            checked_at: Utc::now(),
+        };
+        self.versions.insert(id.clone(), latest);
+        self.needs_saving.store(true, Ordering::SeqCst);
+    }
+
+    fn path(home_path: impl AsRef<Path>) -> PathBuf {
+        home_path
+            .as_ref()
+            .join("tool-storage")
+            .join("latest-versions.json")
+    }
+
+    #[instrument(skip(home_path), level = "trace")]
+    pub(crate) async fn load(home_path: impl AsRef<Path>) -> RokitResult<Self> {
+        let path = Self::path(home_path);
+
+        // NOTE: This cache is purely informational, so a missing
+        // or corrupted file should never prevent Rokit from running
+        let versions = match read_to_string(&path).await {
+            Ok(contents) => serde_json::from_str::<BTreeMap<ToolId, LatestVersion>>(&contents)
+                .unwrap_or_default(),
+            Err(_) => BTreeMap::new(),
+        };
+        trace!(?path, count = versions.len(), "Loaded latest version cache");
+
+        Ok(Self {
+            versions: Arc::new(versions.into_iter().collect()),
+            needs_saving: Arc::default(),
+        })
+    }
+
+    #[instrument(skip(self, home_path), level = "trace")]
+    pub(crate) async fn save(&self, home_path: impl AsRef<Path>) -> RokitResult<()> {
+        self.needs_saving.store(false, Ordering::SeqCst);
+        let path = Self::path(home_path);
+
+        // NOTE: Sorting keeps the file stable and easy to read for humans
+        let sorted = self
+            .versions
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect::<BTreeMap<_, _>>();
+
+        create_dir_all(path.parent().unwrap()).await?;
+        write(&path, serde_json::to_string(&sorted)?).await?;
+        trace!(?path, "Saved latest version cache");
+        Ok(())
+    }
+
+    pub(crate) fn needs_saving(&self) -> bool {
+        self.needs_saving.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace};
+
+use crate::{
+    executor::fs::{append, read_to_string, write},
+    result::RokitResult,
+};
+
+const METRICS_LOG_FILE_NAME: &str = "metrics.log";
+const METRICS_POSTED_FILE_NAME: &str = "metrics-posted";
+const METRICS_OK: &str = "ok";
+
+/**
+    A single run of a Rokit command, to be recorded in [`Metrics`].
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsRecord {
+    /// The name of the command that was run, such as `install`.
+    pub command: String,
+    /// The kind of error that the command failed with, if it failed.
+    pub error_kind: Option<String>,
+    /// How long the command took to run.
+    pub duration: Duration,
+}
+
+/**
+    Counters for a single Rokit command.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandMetrics {
+    /// How many times the command has been run.
+    pub runs: u64,
+    /// How many times the command has failed, by kind of error.
+    pub failures: BTreeMap<String, u64>,
+    /// The combined duration of all runs of the command, in milliseconds.
+    pub total_duration_ms: u64,
+}
+
+impl CommandMetrics {
+    /**
+        Gets how many times the command has failed, for any kind of error.
+    */
+    #[must_use]
+    pub fn total_failures(&self) -> u64 {
+        self.failures.values().sum()
+    }
+}
+
+/**
+    Anonymous metrics about the Rokit commands that have been run, such
+    as how often they run, how long they take, and how often they fail.
+
+    Metrics are opt-in, and are only recorded if enabled in the policy
+    manifest. They contain no tool names, paths, or other identifying
+    information, and are stored locally - see [`Metrics::to_json`].
+
+    Every run of a command appends a single line to a log file, which
+    is safe to do from many concurrent processes without any locking.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metrics {
+    commands: BTreeMap<String, CommandMetrics>,
+}
+
+impl Metrics {
+    /**
+        Gets the metrics for the given command, if it has ever been run.
+    */
+    #[must_use]
+    pub fn get(&self, command: &str) -> Option<&CommandMetrics> {
+        self.commands.get(command)
+    }
+
+    /**
+        Gets the metrics for all commands that have ever been run, sorted by name.
+    */
+    pub fn commands(&self) -> impl Iterator<Item = (&str, &CommandMetrics)> {
+        self.commands.iter().map(|(name, m)| (name.as_str(), m))
+    }
+
+    /**
+        Serializes these metrics as JSON, which is also
+        the format used when posting them to an endpoint.
+
+        # Errors
+
+        - If the metrics could not be serialized.
+    */
+    pub fn to_json(&self) -> RokitResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    fn merge_log(&mut self, log: &str) {
+        for line in log.lines() {
+            let mut parts = line.split('\t');
+            let (Some(_), Some(command), Some(outcome), Some(duration_ms), None) = (
+                parts.next(),
+                parts.next(),
+                parts.next(),
+                parts.next(),
+                parts.next(),
+            ) else {
+                continue;
+            };
+            let Ok(duration_ms) = duration_ms.trim().parse::<u64>() else {
+                continue;
+            };
+
+            let metrics = self.commands.entry(command.to_string()).or_default();
+            metrics.runs += 1;
+            metrics.total_duration_ms = metrics.total_duration_ms.saturating_add(duration_ms);
+            if outcome != METRICS_OK {
+                *metrics.failures.entry(outcome.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    fn log_path(home_path: impl AsRef<Path>) -> PathBuf {
+        home_path.as_ref().join(METRICS_LOG_FILE_NAME)
+    }
+
+    fn posted_path(home_path: impl AsRef<Path>) -> PathBuf {
+        home_path.as_ref().join(METRICS_POSTED_FILE_NAME)
+    }
+
+    /**
+        Records a single run of a command, right now.
+    */
+    #[instrument(skip(home_path), level = "trace")]
+    pub(crate) async fn record(
+        home_path: impl AsRef<Path>,
+        record: &MetricsRecord,
+    ) -> RokitResult<()> {
+        // NOTE: Appending a single short line is atomic, meaning
+        // that concurrent runs of Rokit will never corrupt the log
+        let line = format_log_line(Utc::now(), record);
+        append(Self::log_path(home_path), line).await?;
+
+        trace!(command = %record.command, "recorded metrics");
+        Ok(())
+    }
+
+    #[instrument(skip(home_path), level = "trace")]
+    pub(crate) async fn load(home_path: impl AsRef<Path>) -> RokitResult<Self> {
+        // NOTE: Metrics are purely informational, so a missing or
+        // corrupted file should never prevent Rokit from running
+        let mut metrics = Self::default();
+        if let Ok(log) = read_to_string(Self::log_path(home_path)).await {
+            metrics.merge_log(&log);
+        }
+        Ok(metrics)
+    }
+
+    /**
+        Gets when metrics were last posted to an endpoint, if ever.
+    */
+    pub(crate) async fn last_posted(home_path: impl AsRef<Path>) -> Option<DateTime<Utc>> {
+        let contents = read_to_string(Self::posted_path(home_path)).await.ok()?;
+        DateTime::from_timestamp(contents.trim().parse().ok()?, 0)
+    }
+
+    /**
+        Marks metrics as having been posted to an endpoint, right now.
+    */
+    pub(crate) async fn set_last_posted(home_path: impl AsRef<Path>) -> RokitResult<()> {
+        let timestamp = Utc::now().timestamp().to_string();
+        write(Self::posted_path(home_path), timestamp).await?;
+        Ok(())
+    }
+}
+
+fn format_log_line(at: DateTime<Utc>, record: &MetricsRecord) -> String {
+    let sanitize = |s: &str| s.replace(|c: char| c.is_whitespace(), "_");
+    format!(
+        "{}\t{}\t{}\t{}\n",
+        at.timestamp(),
+        sanitize(&record.command),
+        sanitize(record.error_kind.as_deref().unwrap_or(METRICS_OK)),
+        record.duration.as_millis(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_record(command: &str, error_kind: Option<&str>, millis: u64) -> MetricsRecord {
+        MetricsRecord {
+            command: command.to_string(),
+            error_kind: error_kind.map(ToString::to_string),
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn merge_log() {
+        let at = DateTime::from_timestamp(100, 0).unwrap();
+        let mut log = String::new();
+        log.push_str(&format_log_line(at, &new_record("install", None, 100)));
+        log.push_str(&format_log_line(
+            at,
+            &new_record("install", Some("http"), 50),
+        ));
+        log.push_str(&format_log_line(
+            at,
+            &new_record("install", Some("http"), 25),
+        ));
+        log.push_str("not a valid line\n");
+        log.push_str(&format_log_line(at, &new_record("add", Some("other"), 10)));
+
+        let mut metrics = Metrics::default();
+        metrics.merge_log(&log);
+
+        let install = metrics.get("install").unwrap();
+        assert_eq!(install.runs, 3);
+        assert_eq!(install.total_duration_ms, 175);
+        assert_eq!(install.failures.get("http"), Some(&2));
+        assert_eq!(install.total_failures(), 2);
+
+        let add = metrics.get("add").unwrap();
+        assert_eq!(add.runs, 1);
+        assert_eq!(add.total_failures(), 1);
+
+        assert_eq!(metrics.get("update"), None);
+        assert_eq!(metrics.commands().count(), 2);
+    }
+
+    #[test]
+    fn log_lines_are_sanitized() {
+        let at = DateTime::from_timestamp(100, 0).unwrap();
+        let line = format_log_line(at, &new_record("self\tupdate", Some("bad\nkind"), 1));
+        assert_eq!(line, "100\tself_update\tbad_kind\t1\n");
+    }
+}
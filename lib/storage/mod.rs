@@ -1,8 +1,35 @@
+mod asset_metadata;
 mod home;
+mod home_lock;
+mod http_cache;
+mod install_journal;
+mod latest_versions;
 mod metadata;
+mod metrics;
+mod partial_downloads;
+mod preflight;
+mod schema;
+mod shared_cache;
 mod tool_cache;
 mod tool_storage;
+mod trust_list;
+mod url_entries;
+mod usage_stats;
 
-pub use self::home::Home;
-pub use self::tool_cache::ToolCache;
-pub use self::tool_storage::ToolStorage;
+pub use self::asset_metadata::{AssetMetadata, AssetMetadataCache};
+pub use self::home::{
+    offline_from_env, Home, RETRIES_ENV_VAR, RETRY_BACKOFF_ENV_VAR, TIMEOUT_ENV_VAR,
+};
+pub use self::http_cache::{HttpCache, HttpCacheEntry};
+pub use self::install_journal::InstallJournal;
+pub use self::latest_versions::{LatestVersion, LatestVersionCache};
+pub use self::metrics::{CommandMetrics, Metrics, MetricsRecord};
+pub use self::partial_downloads::PartialDownloads;
+pub use self::preflight::PreflightError;
+pub use self::shared_cache::{SharedCache, SharedCacheUsage};
+pub use self::tool_cache::{RecordedArtifact, ToolCache};
+pub use self::tool_storage::{LinkStatus, ToolStorage, TEMP_DIR_ENV_VAR};
+pub use self::trust_list::{TrustList, TrustListParseError};
+pub use self::usage_stats::{ToolUsage, UsageStats};
+
+pub use crate::util::fs::{durable_writes, set_durable_writes};
@@ -0,0 +1,396 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn};
+
+use crate::{
+    executor::fs::{create_dir_all, read, remove_file, write},
+    result::RokitResult,
+    sources::{
+        HttpClient, HttpError, HttpLengthError, HttpMethod, HttpPartialBodyError, HttpRequest,
+        HttpResponse,
+    },
+    util::hash::sha256_hex,
+};
+
+const MAX_RESUMES: u32 = 3;
+const STATUS_OK: u16 = 200;
+const STATUS_PARTIAL_CONTENT: u16 = 206;
+const STATUS_RANGE_NOT_SATISFIABLE: u16 = 416;
+
+/**
+    A download that was interrupted before it finished,
+    together with the validators needed to resume it.
+
+    The body is stored next to the metadata, in a separate file.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PartialDownload {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    total: Option<u64>,
+    #[serde(skip)]
+    body: Vec<u8>,
+}
+
+impl PartialDownload {
+    /*
+        Creates the partial download to store after the given response was
+        interrupted, continuing from the previous partial download, if any.
+
+        Returns `None` if the download can not be resumed - either because
+        nothing was received, or because there is no `ETag` or `Last-Modified`
+        header to make sure that the rest of the body is from the same file.
+    */
+    fn after_interrupted(previous: Option<Self>, response: HttpResponse) -> Option<Self> {
+        if response.body.is_empty() {
+            return previous;
+        }
+        match response.status {
+            STATUS_OK => {
+                let etag = response.header("etag").map(ToString::to_string);
+                let last_modified = response.header("last-modified").map(ToString::to_string);
+                if etag.is_none() && last_modified.is_none() {
+                    return None;
+                }
+                let total = response
+                    .header("content-length")
+                    .and_then(|len| len.trim().parse().ok());
+                Some(Self {
+                    url: response.url,
+                    etag,
+                    last_modified,
+                    total,
+                    body: response.body,
+                })
+            }
+            STATUS_PARTIAL_CONTENT => {
+                let mut previous = previous?;
+                let (start, total) = parse_content_range(response.header("content-range")?)?;
+                if start != previous.body.len() as u64 {
+                    return None;
+                }
+                previous.total = total.or(previous.total);
+                previous.body.extend_from_slice(&response.body);
+                Some(previous)
+            }
+            _ => None,
+        }
+    }
+
+    /*
+        Gets the headers to send to resume this download - the `If-Range`
+        header makes the server send the full file if it has changed.
+    */
+    fn range_headers(&self) -> Vec<(String, String)> {
+        let validator = self.etag.as_ref().or(self.last_modified.as_ref());
+        let mut headers = vec![(String::from("range"), format!("bytes={}-", self.body.len()))];
+        if let Some(validator) = validator {
+            headers.push((String::from("if-range"), validator.clone()));
+        }
+        headers
+    }
+}
+
+/*
+    Completes a download using the given response, continuing from the
+    previous partial download if the server responded with partial content.
+
+    The length of the full body is checked against the length that the server
+    sent, so that an incomplete download is never returned as a successful one.
+*/
+fn complete_download(
+    previous: Option<PartialDownload>,
+    mut response: HttpResponse,
+) -> Result<HttpResponse, HttpError> {
+    let expected = match response.status {
+        STATUS_OK => response
+            .header("content-length")
+            .and_then(|len| len.trim().parse::<u64>().ok()),
+        STATUS_PARTIAL_CONTENT => {
+            let previous_len = previous.as_ref().map_or(0, |p| p.body.len() as u64);
+            let previous_total = previous.as_ref().and_then(|p| p.total);
+            let (start, total) = response
+                .header("content-range")
+                .and_then(parse_content_range)
+                .unwrap_or_default();
+            if start != previous_len {
+                return Err(Box::new(HttpLengthError {
+                    url: response.url,
+                    expected: previous_len,
+                    actual: start,
+                }));
+            }
+            if let Some(previous) = previous {
+                let mut body = previous.body;
+                body.append(&mut response.body);
+                response.body = body;
+            }
+            response.status = STATUS_OK;
+            total.or(previous_total)
+        }
+        _ => return Ok(response),
+    };
+
+    let actual = response.body.len() as u64;
+    match expected {
+        Some(expected) if expected != actual => Err(Box::new(HttpLengthError {
+            url: response.url,
+            expected,
+            actual,
+        })),
+        _ => Ok(response),
+    }
+}
+
+/*
+    Parses a `Content-Range` header such as `bytes 100-999/1000`,
+    returning the start of the range and the total length, if known.
+*/
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    let (range, total) = range.split_once('/')?;
+    let (start, _) = range.split_once('-')?;
+    let start = start.trim().parse().ok()?;
+    let total = match total.trim() {
+        "*" => None,
+        total => Some(total.parse().ok()?),
+    };
+    Some((start, total))
+}
+
+/**
+    Storage for downloads that were interrupted, stored in
+    the `cache/downloads` directory of the Rokit home directory.
+
+    Artifacts downloaded using a client returned by [`PartialDownloads::wrap_client`]
+    are resumed from where they were interrupted, using `Range` requests, both
+    within a single download and across separate runs of Rokit. Partial
+    downloads are removed once the download has completed.
+
+    Can be cheaply cloned while still referring to the same underlying data.
+*/
+#[derive(Debug, Clone)]
+pub struct PartialDownloads {
+    root: Arc<Path>,
+}
+
+impl PartialDownloads {
+    /**
+        Creates a new `PartialDownloads` stored in the given directory.
+    */
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into().into(),
+        }
+    }
+
+    /**
+        Gets the path to the directory that partial downloads are stored in.
+    */
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /**
+        Wraps the given client, so that artifact downloads made using
+        it are stored here when interrupted, and resumed later on.
+
+        Only requests that accept `application/octet-stream` are resumed,
+        other requests are sent using the given client as they are.
+    */
+    #[must_use]
+    pub fn wrap_client(&self, client: Arc<dyn HttpClient>) -> Arc<dyn HttpClient> {
+        Arc::new(ResumableClient {
+            inner: client,
+            downloads: self.clone(),
+        })
+    }
+
+    fn meta_path(&self, url: &str) -> PathBuf {
+        self.root.join(format!("{}.json", sha256_hex(url)))
+    }
+
+    fn body_path(&self, url: &str) -> PathBuf {
+        self.root.join(format!("{}.part", sha256_hex(url)))
+    }
+
+    async fn read(&self, url: &str) -> Option<PartialDownload> {
+        let meta = read(self.meta_path(url)).await.ok()?;
+        let mut partial = serde_json::from_slice::<PartialDownload>(&meta)
+            .ok()
+            .filter(|partial| partial.url == url)?;
+        partial.body = read(self.body_path(url)).await.ok()?;
+        Some(partial)
+    }
+
+    async fn write(&self, partial: &PartialDownload) -> RokitResult<()> {
+        create_dir_all(&self.root).await?;
+        // NOTE: The body is written first, so that metadata
+        // never refers to a body that does not exist yet
+        write(self.body_path(&partial.url), &partial.body).await?;
+        write(self.meta_path(&partial.url), serde_json::to_vec(partial)?).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, url: &str) {
+        remove_file(self.meta_path(url)).await.ok();
+        remove_file(self.body_path(url)).await.ok();
+    }
+}
+
+#[derive(Debug)]
+struct ResumableClient {
+    inner: Arc<dyn HttpClient>,
+    downloads: PartialDownloads,
+}
+
+impl ResumableClient {
+    #[instrument(skip(self, request), fields(url = %request.url), level = "debug")]
+    async fn download(&self, mut request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        // NOTE: Ranges refer to the encoded body, so the body must
+        // not be compressed for it to be possible to resume it
+        request
+            .headers
+            .push((String::from("accept-encoding"), String::from("identity")));
+
+        let mut resumes = 0;
+        loop {
+            let previous = self.downloads.read(&request.url).await;
+            let mut attempt = request.clone();
+            if let Some(previous) = &previous {
+                debug!(
+                    received = previous.body.len(),
+                    "resuming interrupted download"
+                );
+                attempt.headers.extend(previous.range_headers());
+            }
+
+            let err = match self.inner.send(attempt).await {
+                Ok(response) => {
+                    // NOTE: Partial downloads are kept after other unsuccessful
+                    // responses, which may be temporary, such as rate limits
+                    let range_failed = response.status == STATUS_RANGE_NOT_SATISFIABLE;
+                    if response.is_success() || range_failed {
+                        self.downloads.remove(&request.url).await;
+                    }
+                    if range_failed && previous.is_some() && resumes < MAX_RESUMES {
+                        resumes += 1;
+                        continue;
+                    }
+                    return complete_download(previous, response);
+                }
+                Err(err) => err,
+            };
+
+            let interrupted = match err.downcast::<HttpPartialBodyError>() {
+                Ok(interrupted) => *interrupted,
+                Err(err) => return Err(err),
+            };
+            let HttpPartialBodyError { response, source } = interrupted;
+            let Some(partial) = PartialDownload::after_interrupted(previous, response) else {
+                self.downloads.remove(&request.url).await;
+                return Err(source);
+            };
+
+            // NOTE: Storing the partial download is best-effort, failing
+            // to do so only means that it has to start over from zero
+            if let Err(e) = self.downloads.write(&partial).await {
+                warn!(error = %e, "failed to store partial download");
+                return Err(source);
+            }
+            if resumes >= MAX_RESUMES {
+                return Err(source);
+            }
+            resumes += 1;
+            warn!(
+                received = partial.body.len(),
+                total = ?partial.total,
+                error = %source,
+                "download was interrupted, resuming"
+            );
+        }
+    }
+}
+
+impl HttpClient for ResumableClient {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>> {
+        let is_download = request.method == HttpMethod::Get
+            && request.headers.iter().any(|(name, value)| {
+                name.eq_ignore_ascii_case("accept") && value == "application/octet-stream"
+            });
+        if is_download {
+            self.download(request).boxed()
+        } else {
+            self.inner.send(request)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_content_ranges() {
+        assert_eq!(parse_content_range("bytes 0-99/100"), Some((0, Some(100))));
+        assert_eq!(parse_content_range("bytes 50-99/*"), Some((50, None)));
+        assert_eq!(parse_content_range("bytes */100"), None);
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+
+    #[test]
+    fn interrupted_downloads_need_validators() {
+        let response = HttpResponse::for_test(200, &[("content-length", "6")], b"abc");
+        assert_eq!(PartialDownload::after_interrupted(None, response), None);
+
+        let response =
+            HttpResponse::for_test(200, &[("ETag", "\"a\""), ("content-length", "6")], b"abc");
+        let partial = PartialDownload::after_interrupted(None, response).unwrap();
+        assert_eq!(partial.body, b"abc");
+        assert_eq!(partial.total, Some(6));
+        assert_eq!(
+            partial.range_headers(),
+            vec![
+                (String::from("range"), String::from("bytes=3-")),
+                (String::from("if-range"), String::from("\"a\"")),
+            ]
+        );
+
+        let response = HttpResponse::for_test(206, &[("content-range", "bytes 3-5/6")], b"d");
+        let partial = PartialDownload::after_interrupted(Some(partial), response).unwrap();
+        assert_eq!(partial.body, b"abcd");
+    }
+
+    #[test]
+    fn completed_downloads_are_checked() {
+        let partial = PartialDownload {
+            url: String::from("https://example.com/tool.zip"),
+            etag: Some(String::from("\"a\"")),
+            last_modified: None,
+            total: Some(6),
+            body: b"abc".to_vec(),
+        };
+
+        let response = HttpResponse::for_test(206, &[("content-range", "bytes 3-5/6")], b"def");
+        let response = complete_download(Some(partial.clone()), response).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"abcdef");
+
+        let response = HttpResponse::for_test(206, &[("content-range", "bytes 3-6/7")], b"def");
+        assert!(complete_download(Some(partial.clone()), response).is_err());
+
+        let response = HttpResponse::for_test(206, &[("content-range", "bytes 0-5/6")], b"abcdef");
+        assert!(complete_download(Some(partial), response).is_err());
+
+        let response = HttpResponse::for_test(200, &[("content-length", "6")], b"abc");
+        assert!(complete_download(None, response).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    io::Error as IoError,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+use tracing::trace;
+
+use crate::executor::fs::{remove_file, write};
+
+/**
+    Error type representing a directory that Rokit can not use for its storage.
+
+    Each error contains a hint for how the user may resolve it, since these
+    errors are almost always caused by the environment and not by Rokit.
+*/
+#[derive(Debug, Error)]
+pub enum PreflightError {
+    #[error(
+        "the directory at '{}' is not writable: {source}\
+        \nMake sure that your user owns this directory, or set the \
+        `ROKIT_ROOT` environment variable to use a different directory.",
+        path.display()
+    )]
+    NotWritable { path: PathBuf, source: IoError },
+    #[error(
+        "the directory at '{}' does not support executable files\
+        \nThis usually means that it is on a filesystem such as exFAT or FAT32, \
+        or a drive mounted without exec permissions. Set the `ROKIT_ROOT` \
+        environment variable to use a directory on a different filesystem.",
+        path.display()
+    )]
+    NotExecutable { path: PathBuf },
+}
+
+/**
+    Checks that the given directory exists and is writable, and if
+    `executable` is true, that files written to it may be executable.
+*/
+pub(crate) async fn check_dir(
+    dir: impl AsRef<Path>,
+    executable: bool,
+) -> Result<(), PreflightError> {
+    let dir = dir.as_ref();
+    let probe = dir.join(format!(".rokit-preflight-{}", std::process::id()));
+
+    let not_writable = |source| PreflightError::NotWritable {
+        path: dir.to_path_buf(),
+        source,
+    };
+
+    write(&probe, b"").await.map_err(not_writable)?;
+    let exec_result = if executable {
+        check_executable(&probe).await
+    } else {
+        Ok(true)
+    };
+    let remove_result = remove_file(&probe).await;
+
+    if !exec_result.map_err(not_writable)? {
+        return Err(PreflightError::NotExecutable {
+            path: dir.to_path_buf(),
+        });
+    }
+    remove_result.map_err(not_writable)?;
+
+    trace!(?dir, executable, "Preflight check passed");
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn check_executable(path: &Path) -> Result<bool, IoError> {
+    use std::fs::Permissions;
+    use std::os::unix::fs::PermissionsExt;
+
+    use crate::executor::fs::{metadata, set_permissions};
+
+    // NOTE: Filesystems such as exFAT silently ignore permission
+    // changes, so we need to read the permissions back to be sure
+    set_permissions(path, Permissions::from_mode(0o755)).await?;
+    let mode = metadata(path).await?.permissions().mode();
+    Ok(mode & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unused_async)]
+async fn check_executable(_path: &Path) -> Result<bool, IoError> {
+    Ok(true)
+}
@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::result::{RokitError, RokitResult};
+
+const VERSION_KEY: &str = "version";
+
+/**
+    A migration that upgrades the contents of a JSON file
+    in the home directory from one version to the next.
+*/
+pub(crate) type Migration = fn(&mut Map<String, Value>);
+
+/**
+    The versioned format of a JSON file in the home directory.
+
+    Files are written with a top-level `version` field, and files without
+    one use the original format, which is version `1`. Each migration
+    upgrades a file by exactly one version, in order, meaning that the
+    current version is always one more than the number of migrations.
+
+    Files written by a newer version of Rokit, using a version of the
+    format that is not known, are never loaded - since saving them
+    again would silently discard anything that was added to them.
+*/
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Schema {
+    migrations: &'static [Migration],
+}
+
+impl Schema {
+    pub(crate) const fn new(migrations: &'static [Migration]) -> Self {
+        Self { migrations }
+    }
+
+    /**
+        Gets the current version of the format, which is written to new files.
+    */
+    pub(crate) fn current_version(self) -> u64 {
+        u64::try_from(self.migrations.len()).map_or(u64::MAX, |len| len + 1)
+    }
+
+    /**
+        Upgrades the given contents of the file at the given path to the
+        current version of the format, running any migrations in order.
+
+        Returns `true` if any migrations ran. Contents that are not
+        a JSON object are left as they are, and never migrated.
+
+        # Errors
+
+        - If the contents were written using a newer version of the format.
+    */
+    pub(crate) fn migrate(self, path: &Path, contents: &mut Value) -> RokitResult<bool> {
+        let Some(object) = contents.as_object_mut() else {
+            return Ok(false);
+        };
+
+        let version = object
+            .get(VERSION_KEY)
+            .and_then(Value::as_u64)
+            .unwrap_or(1)
+            .max(1);
+        let current = self.current_version();
+        if version > current {
+            return Err(RokitError::UnsupportedDataVersion {
+                path: path.to_path_buf(),
+                version,
+                supported: current,
+            });
+        }
+
+        let skip = usize::try_from(version - 1).unwrap_or(usize::MAX);
+        for migration in self.migrations.iter().skip(skip) {
+            migration(object);
+        }
+        object.insert(VERSION_KEY.to_string(), Value::from(current));
+
+        Ok(version < current)
+    }
+
+    /**
+        Adds the current version of the format to the given
+        contents, which should be written to disk right after.
+    */
+    pub(crate) fn with_version(self, mut contents: Value) -> Value {
+        if let Some(object) = contents.as_object_mut() {
+            object.insert(VERSION_KEY.to_string(), Value::from(self.current_version()));
+        }
+        contents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn rename_tools(object: &mut Map<String, Value>) {
+        if let Some(tools) = object.remove("tools") {
+            object.insert(String::from("installed"), tools);
+        }
+    }
+
+    fn add_plugins(object: &mut Map<String, Value>) {
+        object.insert(String::from("plugins"), json!([]));
+    }
+
+    const SCHEMA: Schema = Schema::new(&[rename_tools, add_plugins]);
+
+    #[test]
+    fn migrates_from_any_older_version() {
+        let path = Path::new("cache.json");
+        assert_eq!(SCHEMA.current_version(), 3);
+
+        let mut contents = json!({ "tools": ["a/b@1.0.0"] });
+        assert!(SCHEMA.migrate(path, &mut contents).unwrap());
+        assert_eq!(
+            contents,
+            json!({ "installed": ["a/b@1.0.0"], "plugins": [], "version": 3 })
+        );
+
+        let mut contents = json!({ "installed": [], "version": 2 });
+        assert!(SCHEMA.migrate(path, &mut contents).unwrap());
+        assert_eq!(
+            contents,
+            json!({ "installed": [], "plugins": [], "version": 3 })
+        );
+
+        let mut current = contents.clone();
+        assert!(!SCHEMA.migrate(path, &mut current).unwrap());
+        assert_eq!(current, contents);
+    }
+
+    #[test]
+    fn refuses_newer_versions() {
+        let path = Path::new("cache.json");
+        let mut contents = json!({ "installed": [], "version": 4 });
+        assert!(matches!(
+            SCHEMA.migrate(path, &mut contents),
+            Err(RokitError::UnsupportedDataVersion {
+                version: 4,
+                supported: 3,
+                ..
+            })
+        ));
+
+        let mut contents = json!(["not", "an", "object"]);
+        assert!(!SCHEMA.migrate(path, &mut contents).unwrap());
+    }
+
+    #[test]
+    fn adds_version_when_saving() {
+        let contents = Schema::new(&[]).with_version(json!({ "trusted": [] }));
+        assert_eq!(contents, json!({ "trusted": [], "version": 1 }));
+    }
+}
@@ -0,0 +1,285 @@
+use std::{
+    env::var_os,
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tempfile::NamedTempFile;
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    executor::{
+        fs::{create_dir_all, read, read_dir, read_to_string, remove_dir_all, try_exists},
+        spawn_blocking,
+    },
+    result::RokitResult,
+    sources::Artifact,
+    util::hash::sha256_hex,
+};
+
+const SHARED_CACHE_ENV_VAR: &str = "ROKIT_SHARED_CACHE";
+const BLOBS_DIR_NAME: &str = "blobs";
+const INDEX_DIR_NAME: &str = "index";
+
+/**
+    The number of artifacts stored in a [`SharedCache`], and their total size.
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SharedCacheUsage {
+    pub artifacts: usize,
+    pub size: u64,
+}
+
+/**
+    A content-addressed cache for downloaded artifacts, so that installing the
+    same tool version again - in another project, or after it was removed -
+    never downloads it again.
+
+    By default, the cache is stored in the `cache/artifacts` directory of
+    the Rokit home directory. It may also be stored in a directory that is
+    shared between many Rokit processes at once - such as a CI runner fleet
+    where every job would otherwise download the same tools - by setting
+    the `ROKIT_SHARED_CACHE` environment variable to that directory.
+
+    The cache is laid out as follows:
+
+    - `blobs/<sha256>` - artifact contents, addressed by their own hash
+    - `index/<sha256>` - the hash of the contents for an artifact,
+      addressed by the hash of a key that uniquely identifies the artifact
+
+    Entries are written to a temporary file and then moved into place, and are
+    never modified once they exist, so no locking is needed. Since blobs are
+    verified against their hash whenever they are read, a corrupted or
+    cleared entry simply means that the artifact is downloaded again.
+
+    Can be cheaply cloned while still referring to the same underlying data.
+*/
+#[derive(Debug, Clone)]
+pub struct SharedCache {
+    root: Arc<Path>,
+}
+
+impl SharedCache {
+    /**
+        Creates a new `SharedCache` stored in the given directory.
+    */
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into().into(),
+        }
+    }
+
+    /**
+        Creates a new `SharedCache` from the `ROKIT_SHARED_CACHE`
+        environment variable, or returns `None` if it is not set.
+    */
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let root = var_os(SHARED_CACHE_ENV_VAR).filter(|root| !root.is_empty())?;
+        Some(Self::new(root))
+    }
+
+    /**
+        Gets the path to the directory that this cache is stored in.
+    */
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    fn blob_path(&self, hash: &str) -> PathBuf {
+        self.root.join(BLOBS_DIR_NAME).join(hash)
+    }
+
+    fn index_path(&self, artifact: &Artifact) -> PathBuf {
+        self.root
+            .join(INDEX_DIR_NAME)
+            .join(sha256_hex(artifact_key(artifact)))
+    }
+
+    /**
+        Reads the cached contents for the given artifact, if any.
+
+        Returns `None` if the artifact is not cached, or if
+        the cached contents do not match their expected hash.
+    */
+    #[instrument(skip(self, artifact), level = "trace")]
+    pub async fn read(&self, artifact: &Artifact) -> Option<Vec<u8>> {
+        let index_path = self.index_path(artifact);
+        let hash = read_to_string(&index_path).await.ok()?;
+        let hash = hash.trim();
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            debug!(spec = %artifact.tool_spec, "ignoring invalid shared cache entry");
+            return None;
+        }
+
+        let contents = read(self.blob_path(hash)).await.ok()?;
+        if sha256_hex(&contents) == hash {
+            debug!(spec = %artifact.tool_spec, hash, "found artifact in shared cache");
+            Some(contents)
+        } else {
+            debug!(spec = %artifact.tool_spec, hash, "ignoring corrupted shared cache entry");
+            None
+        }
+    }
+
+    /**
+        Checks if the given artifact is cached, without reading its contents.
+
+        Note that this does not verify the cached contents, so a later
+        [`SharedCache::read`] may still return `None` for corrupted entries.
+    */
+    pub async fn contains(&self, artifact: &Artifact) -> bool {
+        let Ok(hash) = read_to_string(self.index_path(artifact)).await else {
+            return false;
+        };
+        let hash = hash.trim();
+        hash.len() == 64
+            && hash.chars().all(|c| c.is_ascii_hexdigit())
+            && try_exists(self.blob_path(hash)).await.unwrap_or(false)
+    }
+
+    /**
+        Writes the given contents for an artifact to the cache.
+
+        If the artifact is already cached, this does nothing.
+
+        Returns the path to the file that the contents are stored in.
+
+        # Errors
+
+        - If the contents could not be written to the cache.
+    */
+    #[instrument(skip(self, artifact, contents), level = "trace")]
+    pub async fn write(
+        &self,
+        artifact: &Artifact,
+        contents: impl AsRef<[u8]>,
+    ) -> RokitResult<PathBuf> {
+        let hash = sha256_hex(contents.as_ref());
+        let blob_path = self.blob_path(&hash);
+
+        // NOTE: The blob must be in place before the index entry,
+        // otherwise a concurrent reader may find a dangling entry
+        write_new(blob_path.clone(), contents.as_ref().to_vec()).await?;
+        write_new(self.index_path(artifact), hash.clone().into_bytes()).await?;
+
+        trace!(spec = %artifact.tool_spec, hash, "wrote artifact to shared cache");
+        Ok(blob_path)
+    }
+
+    /**
+        Gets the number of artifacts stored in the cache, and their total size.
+
+        # Errors
+
+        - If the cache directory could not be read.
+    */
+    pub async fn usage(&self) -> RokitResult<SharedCacheUsage> {
+        let mut usage = SharedCacheUsage::default();
+        let mut entries = match read_dir(self.root.join(BLOBS_DIR_NAME)).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(usage),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() {
+                usage.artifacts += 1;
+                usage.size += metadata.len();
+            }
+        }
+        Ok(usage)
+    }
+
+    /**
+        Removes all artifacts from the cache, returning what was removed.
+
+        # Errors
+
+        - If the cache directory could not be read or removed.
+    */
+    pub async fn clear(&self) -> RokitResult<SharedCacheUsage> {
+        let usage = self.usage().await?;
+
+        // NOTE: The index must be removed before the blobs, for the
+        // same reason that blobs are written before the index entries
+        for dir_name in [INDEX_DIR_NAME, BLOBS_DIR_NAME] {
+            match remove_dir_all(self.root.join(dir_name)).await {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+
+        debug!(
+            artifacts = usage.artifacts,
+            size = usage.size,
+            "cleared shared cache"
+        );
+        Ok(usage)
+    }
+}
+
+/**
+    Creates a key that uniquely identifies an artifact, across all tools and providers.
+*/
+fn artifact_key(artifact: &Artifact) -> String {
+    format!(
+        "{}\n{}\n{}\n{}",
+        artifact.provider.as_str(),
+        artifact.tool_spec,
+        artifact.id.as_deref().unwrap_or_default(),
+        artifact.name.as_deref().unwrap_or_default(),
+    )
+}
+
+/**
+    Writes a file atomically, by writing to a temporary file in the
+    same directory and then moving it into place - unless the file
+    already exists, in which case it is left untouched.
+*/
+async fn write_new(path: PathBuf, contents: Vec<u8>) -> RokitResult<()> {
+    let dir = path
+        .parent()
+        .expect("cache paths have a parent")
+        .to_path_buf();
+    create_dir_all(&dir).await?;
+    if path.exists() {
+        return Ok(());
+    }
+
+    spawn_blocking(move || {
+        let mut file = NamedTempFile::new_in(&dir)?;
+        file.write_all(&contents)?;
+        file.as_file().sync_all()?;
+        match file.persist_noclobber(&path) {
+            Ok(_) => Ok(()),
+            // Another process wrote the same entry first, which is fine,
+            // since entries for the same path always have the same contents
+            Err(e) if e.error.kind() == ErrorKind::AlreadyExists => Ok(()),
+            Err(e) => Err(e.error.into()),
+        }
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifact_keys_are_unique() {
+        let linux = Artifact::for_test("tool-linux-x86_64.zip");
+        let windows = Artifact::for_test("tool-windows-x86_64.zip");
+        let newer = Artifact {
+            tool_spec: "author/tool@2.0.1".parse().unwrap(),
+            ..linux.clone()
+        };
+
+        assert_eq!(artifact_key(&linux), artifact_key(&linux.clone()));
+        assert_ne!(artifact_key(&linux), artifact_key(&windows));
+        assert_ne!(artifact_key(&linux), artifact_key(&newer));
+    }
+}
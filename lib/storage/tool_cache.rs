@@ -2,34 +2,135 @@
 #![allow(clippy::inherent_to_string)]
 
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
+    time::Instant,
 };
 
-use dashmap::DashSet;
+use dashmap::{DashMap, DashSet};
 use semver::Version;
-use serde::Deserialize;
-use tokio::{fs::create_dir_all, task::spawn_blocking, time::Instant};
-use tracing::{instrument, trace};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, trace};
+use url::Url;
 
 use crate::{
-    result::RokitResult,
+    executor::{fs::create_dir_all, spawn_blocking},
+    result::{RokitError, RokitResult},
+    sources::{Artifact, Release},
     tool::{ToolId, ToolSpec},
+    util::fs::{durable_writes, sync_parent_dir},
 };
 
+use super::schema::Schema;
+
+/*
+    Versioned format of the tool cache file - add a migration
+    here whenever the format changes, see `Schema` for details.
+*/
+const SCHEMA: Schema = Schema::new(&[]);
+
+/**
+    The artifact that was downloaded when a tool version was installed,
+    together with the hash of its contents.
+
+    Lets the tool version be installed again when its release metadata
+    can not be fetched, such as when its provider is down, as long as
+    the downloaded contents still match the recorded hash.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedArtifact {
+    pub provider: String,
+    pub url: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    pub sha256: String,
+}
+
+impl RecordedArtifact {
+    /**
+        Creates a recorded artifact from the given artifact and the hash of its contents.
+
+        Returns `None` if the artifact has no download URL.
+    */
+    #[must_use]
+    pub fn from_artifact(artifact: &Artifact, sha256: impl Into<String>) -> Option<Self> {
+        Some(Self {
+            provider: artifact.provider.as_str().to_string(),
+            url: artifact.url.clone()?,
+            id: artifact.id.clone(),
+            name: artifact.name.clone(),
+            format: artifact.format.map(|format| format.as_str().to_string()),
+            sha256: sha256.into(),
+        })
+    }
+
+    /**
+        Creates a release for the given tool version, containing only this recorded artifact.
+
+        Note that the downloaded contents of the artifact must
+        still be verified against the recorded hash before use.
+    */
+    #[must_use]
+    pub fn to_release(&self, spec: &ToolSpec) -> Release {
+        let artifact = Artifact {
+            provider: self.provider.parse().unwrap_or(spec.provider()),
+            format: self.format.as_deref().and_then(|f| f.parse().ok()),
+            id: self.id.clone(),
+            url: Some(self.url.clone()),
+            name: self.name.clone(),
+            size: None,
+            digest: None,
+            tool_spec: spec.clone(),
+        };
+        Release {
+            version: spec.version().clone(),
+            changelog: None,
+            artifacts: vec![artifact],
+        }
+    }
+}
+
 /**
     Cache for trusted tool identifiers and installed tool specifications.
 
+    Also records the hashes of the artifact downloaded and the binary extracted
+    when each tool version was first installed, so that a release that was
+    changed after the fact, such as by being re-tagged or tampered with, is
+    never silently installed - not even when rebuilt from a delta.
+
     Can be cheaply cloned while still referring to the same underlying data.
+
+    When saved, any changes made by other Rokit processes since this cache
+    was loaded are merged in, so that concurrent changes are never lost.
 */
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct ToolCache {
     trusted: Arc<DashSet<ToolId>>,
     installed: Arc<DashSet<ToolSpec>>,
+    #[serde(default)]
+    plugins: Arc<DashSet<ToolSpec>>,
+    #[serde(default)]
+    artifact_hashes: Arc<DashMap<ToolSpec, String>>,
+    #[serde(default)]
+    binary_hashes: Arc<DashMap<ToolSpec, String>>,
+    #[serde(default)]
+    artifacts: Arc<DashMap<ToolSpec, RecordedArtifact>>,
+    #[serde(default, skip)]
+    removed_trusted: Arc<DashSet<ToolId>>,
+    #[serde(default, skip)]
+    removed_installed: Arc<DashSet<ToolSpec>>,
+    #[serde(default, skip)]
+    removed_plugins: Arc<DashSet<ToolSpec>>,
+    #[serde(default, skip)]
+    removed_hashes: Arc<DashSet<ToolId>>,
     #[serde(default, skip)]
     needs_saving: Arc<AtomicBool>,
 }
@@ -50,19 +151,27 @@ impl ToolCache {
     */
     #[must_use]
     pub fn add_trust(&self, tool: ToolId) -> bool {
-        self.needs_saving.store(true, Ordering::SeqCst);
-        self.trusted.insert(tool)
+        self.removed_trusted.remove(&tool);
+        let added = self.trusted.insert(tool);
+        self.mark_dirty_if(added)
     }
 
     /**
         Remove trust for a tool from this `ToolCache`.
 
+        This also forgets any hashes recorded for the tool,
+        see [`ToolCache::forget_artifact_hashes`] for more information.
+
         Returns `true` if the tool was previously trusted and has now been removed.
     */
     #[must_use]
     pub fn remove_trust(&self, tool: &ToolId) -> bool {
-        self.needs_saving.store(true, Ordering::SeqCst);
-        self.trusted.remove(tool).is_some()
+        let removed = self.trusted.remove(tool).is_some();
+        if removed {
+            self.removed_trusted.insert(tool.clone());
+            let _ = self.forget_artifact_hashes(tool);
+        }
+        self.mark_dirty_if(removed)
     }
 
     /**
@@ -73,6 +182,25 @@ impl ToolCache {
         self.trusted.contains(tool)
     }
 
+    /**
+        Get a sorted list of trusted tools that have the same name as the
+        given tool, but come from a different author or provider.
+
+        A tool that is not trusted itself but shares its name with a trusted
+        tool may be an attempt at impersonating it, and is worth warning about.
+    */
+    #[must_use]
+    pub fn trusted_lookalikes(&self, tool: &ToolId) -> Vec<ToolId> {
+        let mut lookalikes = self
+            .trusted
+            .iter()
+            .filter(|id| id.name == tool.name && id.key() != tool)
+            .map(|id| id.clone())
+            .collect::<Vec<_>>();
+        lookalikes.sort();
+        lookalikes
+    }
+
     /**
         Get a sorted copy of the trusted tools in this `ToolCache`.
     */
@@ -83,6 +211,117 @@ impl ToolCache {
         sorted_tools
     }
 
+    /**
+        Get the hash of the artifact that was downloaded when
+        the given tool version was first installed, if any.
+    */
+    #[must_use]
+    pub fn artifact_hash(&self, tool: &ToolSpec) -> Option<String> {
+        self.artifact_hashes.get(tool).map(|hash| hash.clone())
+    }
+
+    /**
+        Check the hash of a downloaded artifact against the hash that was
+        recorded when the same tool version was first installed, or record
+        it if this is the first time the tool version is installed.
+
+        Returns `true` if the hash was recorded, and `false` if it matched.
+
+        # Errors
+
+        - If a different hash was recorded for the tool version, meaning that
+          its release was changed since it was first installed. The tool must
+          then be trusted again before it can be installed, which forgets all
+          recorded hashes - see [`ToolCache::forget_artifact_hashes`].
+    */
+    pub fn check_artifact_hash(&self, tool: &ToolSpec, hash: &str) -> RokitResult<bool> {
+        let recorded = check_hash(&self.artifact_hashes, tool, hash)?;
+        Ok(self.mark_dirty_if(recorded))
+    }
+
+    /**
+        Get the hash of the binary that was extracted when
+        the given tool version was first installed, if any.
+    */
+    #[must_use]
+    pub fn binary_hash(&self, tool: &ToolSpec) -> Option<String> {
+        self.binary_hashes.get(tool).map(|hash| hash.clone())
+    }
+
+    /**
+        Check the hash of an extracted or reconstructed tool binary against
+        the hash that was recorded when the same tool version was first
+        installed, or record it if this is the first time it is installed.
+
+        Unlike artifact hashes, this can also verify binaries that were
+        reconstructed from a delta, since they must be identical to the
+        binary that was extracted from the full artifact.
+
+        Returns `true` if the hash was recorded, and `false` if it matched.
+
+        # Errors
+
+        - If a different hash was recorded for the tool version,
+          same as for [`ToolCache::check_artifact_hash`].
+    */
+    pub fn check_binary_hash(&self, tool: &ToolSpec, hash: &str) -> RokitResult<bool> {
+        let recorded = check_hash(&self.binary_hashes, tool, hash)?;
+        Ok(self.mark_dirty_if(recorded))
+    }
+
+    /**
+        Forget all artifact and binary hashes recorded for the given tool, along
+        with its recorded artifacts, so that any version of it may be installed
+        again, even if it has changed.
+
+        Hashes for the tool that were recorded on disk by other Rokit processes
+        are also forgotten when this `ToolCache` is saved, unless the tool was
+        installed again and had new hashes recorded by this process since.
+
+        Returns the number of tool versions that had a recorded hash.
+    */
+    #[must_use]
+    pub fn forget_artifact_hashes(&self, tool: &ToolId) -> usize {
+        let specs = self
+            .artifact_hashes
+            .iter()
+            .map(|entry| entry.key().clone())
+            .chain(self.binary_hashes.iter().map(|entry| entry.key().clone()))
+            .chain(self.artifacts.iter().map(|entry| entry.key().clone()))
+            .filter(|spec| spec.matches_id(tool))
+            .collect::<BTreeSet<_>>();
+        for spec in &specs {
+            self.artifact_hashes.remove(spec);
+            self.binary_hashes.remove(spec);
+            self.artifacts.remove(spec);
+        }
+        self.removed_hashes.insert(tool.clone());
+        self.mark_dirty_if(true);
+        specs.len()
+    }
+
+    /**
+        Get a sorted copy of all recorded artifact hashes in this `ToolCache`.
+    */
+    #[must_use]
+    pub fn all_artifact_hashes(&self) -> BTreeMap<ToolSpec, String> {
+        self.artifact_hashes
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    /**
+        Get a sorted copy of all recorded binary hashes in this `ToolCache`.
+    */
+    #[must_use]
+    pub fn all_binary_hashes(&self) -> BTreeMap<ToolSpec, String> {
+        self.binary_hashes
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
     /**
         Add a tool to this `ToolCache`.
 
@@ -90,8 +329,9 @@ impl ToolCache {
     */
     #[must_use]
     pub fn add_installed(&self, tool: ToolSpec) -> bool {
-        self.needs_saving.store(true, Ordering::SeqCst);
-        self.installed.insert(tool)
+        self.removed_installed.remove(&tool);
+        let added = self.installed.insert(tool);
+        self.mark_dirty_if(added)
     }
 
     /**
@@ -101,8 +341,11 @@ impl ToolCache {
     */
     #[must_use]
     pub fn remove_installed(&self, tool: &ToolSpec) -> bool {
-        self.needs_saving.store(true, Ordering::SeqCst);
-        self.installed.remove(tool).is_some()
+        let removed = self.installed.remove(tool).is_some();
+        if removed {
+            self.removed_installed.insert(tool.clone());
+        }
+        self.mark_dirty_if(removed)
     }
 
     /**
@@ -127,6 +370,93 @@ impl ToolCache {
         sorted_tools
     }
 
+    /**
+        Add a Roblox Studio plugin to this `ToolCache`.
+
+        Plugins are tracked separately from other installed tools,
+        since they are not stored in tool storage and have no links.
+
+        Returns `true` if the plugin was added and not already cached.
+    */
+    #[must_use]
+    pub fn add_installed_plugin(&self, tool: ToolSpec) -> bool {
+        self.removed_plugins.remove(&tool);
+        let added = self.plugins.insert(tool);
+        self.mark_dirty_if(added)
+    }
+
+    /**
+        Remove a Roblox Studio plugin from this `ToolCache`.
+
+        Returns `true` if the plugin was previously cached and has now been removed.
+    */
+    #[must_use]
+    pub fn remove_installed_plugin(&self, tool: &ToolSpec) -> bool {
+        let removed = self.plugins.remove(tool).is_some();
+        if removed {
+            self.removed_plugins.insert(tool.clone());
+        }
+        self.mark_dirty_if(removed)
+    }
+
+    /**
+        Check if a Roblox Studio plugin is cached in this `ToolCache`.
+    */
+    #[must_use]
+    pub fn is_plugin_installed(&self, tool: &ToolSpec) -> bool {
+        self.plugins.contains(tool)
+    }
+
+    /**
+        Get a sorted copy of the cached Roblox Studio plugins in this `ToolCache`.
+    */
+    #[must_use]
+    pub fn all_installed_plugins(&self) -> Vec<ToolSpec> {
+        let mut sorted_tools = self.plugins.iter().map(|id| id.clone()).collect::<Vec<_>>();
+        sorted_tools.sort();
+        sorted_tools
+    }
+
+    /**
+        Get the artifact that was recorded when the given tool version was installed, if any.
+    */
+    #[must_use]
+    pub fn recorded_artifact(&self, tool: &ToolSpec) -> Option<RecordedArtifact> {
+        self.artifacts.get(tool).map(|artifact| artifact.clone())
+    }
+
+    /**
+        Record the artifact that was downloaded for the given tool
+        version, together with the hash of its downloaded contents.
+
+        Returns `true` if a different artifact had already been
+        recorded for the tool version, and has now been replaced.
+    */
+    #[must_use]
+    pub fn record_artifact(&self, tool: &ToolSpec, artifact: &Artifact, sha256: &str) -> bool {
+        let Some(recorded) = RecordedArtifact::from_artifact(artifact, sha256) else {
+            return false;
+        };
+        match self.artifacts.insert(tool.clone(), recorded.clone()) {
+            Some(previous) if previous == recorded => false,
+            previous => {
+                self.mark_dirty_if(true);
+                previous.is_some()
+            }
+        }
+    }
+
+    /**
+        Get a sorted copy of all recorded artifacts in this `ToolCache`.
+    */
+    #[must_use]
+    pub fn all_recorded_artifacts(&self) -> BTreeMap<ToolSpec, RecordedArtifact> {
+        self.artifacts
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
     /**
         Get a sorted list of all unique tool identifiers in this `ToolCache`.
 
@@ -162,6 +492,61 @@ impl ToolCache {
         sorted_set.into_iter().collect()
     }
 
+    fn mark_dirty_if(&self, changed: bool) -> bool {
+        // NOTE: Only mark the cache as dirty if something actually changed,
+        // re-trusting or re-installing a tool should not cause a write
+        if changed {
+            self.needs_saving.store(true, Ordering::SeqCst);
+        }
+        changed
+    }
+
+    fn merge_from(&self, other: &ToolCache) {
+        // NOTE: Entries that were removed by this process must not be
+        // merged back in, but anything else added on disk should be kept
+        for tool in other.trusted.iter() {
+            if !self.removed_trusted.contains(tool.key()) {
+                self.trusted.insert(tool.clone());
+            }
+        }
+        for tool in other.installed.iter() {
+            if !self.removed_installed.contains(tool.key()) {
+                self.installed.insert(tool.clone());
+            }
+        }
+        for tool in other.plugins.iter() {
+            if !self.removed_plugins.contains(tool.key()) {
+                self.plugins.insert(tool.clone());
+            }
+        }
+        // NOTE: Hashes recorded by this process always take precedence,
+        // since they come from the artifact that was actually installed,
+        // and hashes for tools that were forgotten must never come back
+        let is_removed = |spec: &ToolSpec| {
+            self.removed_hashes
+                .iter()
+                .any(|id| spec.matches_id(id.key()))
+        };
+        for (own, theirs) in [
+            (&self.artifact_hashes, &other.artifact_hashes),
+            (&self.binary_hashes, &other.binary_hashes),
+        ] {
+            for entry in theirs.iter() {
+                if !is_removed(entry.key()) {
+                    own.entry(entry.key().clone())
+                        .or_insert_with(|| entry.value().clone());
+                }
+            }
+        }
+        for entry in other.artifacts.iter() {
+            if !is_removed(entry.key()) {
+                self.artifacts
+                    .entry(entry.key().clone())
+                    .or_insert_with(|| entry.value().clone());
+            }
+        }
+    }
+
     fn path(home_path: impl AsRef<Path>) -> PathBuf {
         home_path.as_ref().join("tool-storage").join("cache.json")
     }
@@ -175,21 +560,55 @@ impl ToolCache {
         Ok(this)
     }
 
+    /*
+        NOTE: The caller must hold the home lock while saving, otherwise
+        another process may write to the cache between our read and write.
+    */
     #[instrument(skip(self, home_path), level = "trace")]
     pub(crate) async fn save(&self, home_path: impl AsRef<Path>) -> RokitResult<()> {
         self.needs_saving.store(false, Ordering::SeqCst);
         let start = Instant::now();
         let path = Self::path(home_path);
+        let on_disk = load_impl(path.clone()).await?;
+        self.merge_from(&on_disk);
         save_impl(path.clone(), self).await?;
+        self.removed_trusted.clear();
+        self.removed_installed.clear();
+        self.removed_plugins.clear();
+        self.removed_hashes.clear();
         trace!(?path, elapsed = ?start.elapsed(), "Saved tool cache");
         Ok(())
     }
 
-    pub(crate) fn needs_saving(&self) -> bool {
+    /**
+        Check if this `ToolCache` has been changed since it was last loaded or saved.
+    */
+    #[must_use]
+    pub fn needs_saving(&self) -> bool {
         self.needs_saving.load(Ordering::SeqCst)
     }
 }
 
+fn check_hash(
+    hashes: &DashMap<ToolSpec, String>,
+    tool: &ToolSpec,
+    hash: &str,
+) -> RokitResult<bool> {
+    let hash = hash.trim().to_ascii_lowercase();
+    if let Some(expected) = hashes.get(tool).map(|hash| hash.clone()) {
+        if expected != hash {
+            return Err(RokitError::ArtifactHashChanged {
+                spec: tool.clone().into(),
+                expected,
+                actual: hash,
+            });
+        }
+        return Ok(false);
+    }
+    hashes.insert(tool.clone(), hash);
+    Ok(true)
+}
+
 async fn load_impl(path: PathBuf) -> RokitResult<ToolCache> {
     // Make sure we have created the directory for the cache file, since
     // OpenOptions::create will only create the file and not the directory.
@@ -211,40 +630,238 @@ async fn load_impl(path: PathBuf) -> RokitResult<ToolCache> {
             .write(true)
             .create(true)
             .truncate(false)
-            .open(path)?;
+            .open(&path)?;
         let reader = BufReader::new(file);
-        let this: ToolCache = serde_json::from_reader(reader)?;
+        let contents: serde_json::Value = serde_json::from_reader(reader)?;
 
-        Ok::<_, Error>(this)
+        Ok::<_, Error>((path, contents))
     });
 
     let read_result = result
         .await
         .expect("blocking reader task panicked unexpectedly");
-    Ok(read_result.unwrap_or_default())
+    let Ok((path, mut contents)) = read_result else {
+        return Ok(ToolCache::default());
+    };
+
+    // NOTE: Files from newer versions of Rokit must never be
+    // loaded, since saving would discard anything that is new
+    if SCHEMA.migrate(&path, &mut contents)? {
+        debug!(?path, "migrated tool cache to the current format");
+    }
+    Ok(serde_json::from_value(contents).unwrap_or_default())
 }
 
 async fn save_impl(path: PathBuf, cache: &ToolCache) -> RokitResult<()> {
     // NOTE: We save using sorted json arrays here, which is
     // compatible with the deserialize implementation for DashSet,
     // while also being easier to read for any human inspectors.
-    let json = serde_json::json!({
+    let json = SCHEMA.with_version(serde_json::json!({
         "trusted": cache.all_trusted(),
         "installed": cache.all_installed(),
-    });
+        "plugins": cache.all_installed_plugins(),
+        "artifact_hashes": cache.all_artifact_hashes(),
+        "binary_hashes": cache.all_binary_hashes(),
+        "artifacts": cache.all_recorded_artifacts(),
+    }));
 
     // Same as in our load implementation, see notes there.
+    let durable = durable_writes();
     let result = spawn_blocking(move || {
         use std::{
             fs::{create_dir_all, File},
-            io::{BufWriter, Error},
+            io::{BufWriter, Error, Write},
         };
         create_dir_all(path.parent().unwrap())?;
-        let writer = BufWriter::new(File::create(path)?);
-        serde_json::to_writer(writer, &json)?;
-        Ok::<_, Error>(())
+        let mut writer = BufWriter::new(File::create(&path)?);
+        serde_json::to_writer(&mut writer, &json)?;
+        writer.flush()?;
+        if durable {
+            writer.get_ref().sync_all()?;
+        }
+        Ok::<_, Error>(path)
     });
 
-    result.await??;
+    let path = result.await??;
+    if durable {
+        sync_parent_dir(path).await?;
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::sources::ArtifactProvider;
+
+    use super::*;
+
+    #[test]
+    fn dirty_only_on_change() {
+        let cache = ToolCache::new();
+        let id: ToolId = "rojo-rbx/rojo".parse().unwrap();
+        assert!(!cache.needs_saving());
+
+        assert!(!cache.remove_trust(&id));
+        assert!(!cache.needs_saving());
+
+        assert!(cache.add_trust(id.clone()));
+        assert!(cache.needs_saving());
+
+        cache.needs_saving.store(false, Ordering::SeqCst);
+        assert!(!cache.add_trust(id));
+        assert!(!cache.needs_saving());
+    }
+
+    #[test]
+    fn merge_keeps_concurrent_changes() {
+        let rojo: ToolId = "rojo-rbx/rojo".parse().unwrap();
+        let lune: ToolId = "lune-org/lune".parse().unwrap();
+        let selene: ToolId = "kampfkarren/selene".parse().unwrap();
+
+        let on_disk = ToolCache::new();
+        let _ = on_disk.add_trust(rojo.clone());
+        let _ = on_disk.add_trust(lune.clone());
+
+        let cache = ToolCache::new();
+        let _ = cache.add_trust(rojo.clone());
+        let _ = cache.remove_trust(&rojo);
+        let _ = cache.add_trust(selene.clone());
+
+        cache.merge_from(&on_disk);
+        assert_eq!(cache.all_trusted(), vec![selene, lune]);
+    }
+
+    #[test]
+    fn recorded_artifacts_round_trip() {
+        let spec: ToolSpec = "rojo-rbx/rojo@7.4.4".parse().unwrap();
+        let artifact = Artifact {
+            provider: ArtifactProvider::GitHub,
+            format: "zip".parse().ok(),
+            id: Some("123".to_string()),
+            url: "https://example.com/rojo.zip".parse().ok(),
+            name: Some("rojo".to_string()),
+            size: None,
+            digest: None,
+            tool_spec: spec.clone(),
+        };
+
+        let cache = ToolCache::new();
+        assert_eq!(cache.recorded_artifact(&spec), None);
+        assert!(!cache.record_artifact(&spec, &artifact, "aa"));
+        assert!(cache.needs_saving());
+        assert!(!cache.record_artifact(&spec, &artifact, "aa"));
+        assert!(cache.record_artifact(&spec, &artifact, "bb"));
+
+        let recorded = cache.recorded_artifact(&spec).unwrap();
+        assert_eq!(recorded.sha256, "bb");
+        let release = recorded.to_release(&spec);
+        assert_eq!(&release.version, spec.version());
+        assert_eq!(release.artifacts, vec![artifact]);
+
+        assert_eq!(cache.forget_artifact_hashes(spec.id()), 1);
+        assert_eq!(cache.recorded_artifact(&spec), None);
+    }
+
+    #[test]
+    fn trust_requires_exact_id() {
+        let cache = ToolCache::new();
+        let _ = cache.add_trust("rojo-rbx/rojo".parse().unwrap());
+
+        let is_trusted = |s: &str| cache.is_trusted(&s.parse().unwrap());
+        assert!(is_trusted("rojo-rbx/rojo"));
+        assert!(is_trusted("Rojo-Rbx/Rojo"));
+        assert!(is_trusted("github:rojo-rbx/rojo"));
+        assert!(!is_trusted("evil/rojo"));
+        assert!(!is_trusted("github:evil/rojo"));
+        assert!(!is_trusted("rojo-rbx/rojo2"));
+        assert!(!is_trusted("rojo-rbx-/rojo"));
+        assert!(!is_trusted("rojo/rojo-rbx"));
+    }
+
+    #[test]
+    fn lookalikes_have_same_name_and_different_owner() {
+        let rojo: ToolId = "rojo-rbx/rojo".parse().unwrap();
+        let cache = ToolCache::new();
+        let _ = cache.add_trust(rojo.clone());
+        let _ = cache.add_trust("lune-org/lune".parse().unwrap());
+
+        let lookalikes = |s: &str| cache.trusted_lookalikes(&s.parse().unwrap());
+        assert_eq!(lookalikes("evil/rojo"), vec![rojo.clone()]);
+        assert_eq!(lookalikes("github:evil/Rojo"), vec![rojo]);
+        assert!(lookalikes("rojo-rbx/rojo").is_empty());
+        assert!(lookalikes("Rojo-Rbx/Rojo").is_empty());
+        assert!(lookalikes("evil/rojo2").is_empty());
+    }
+
+    #[test]
+    fn artifact_hashes_trust_on_first_use() {
+        let cache = ToolCache::new();
+        let id: ToolId = "rojo-rbx/rojo".parse().unwrap();
+        let spec: ToolSpec = "rojo-rbx/rojo@7.4.4".parse().unwrap();
+
+        // The first hash is recorded, and the same hash is accepted later
+        assert!(cache.check_artifact_hash(&spec, "AABB").unwrap());
+        assert_eq!(cache.artifact_hash(&spec).as_deref(), Some("aabb"));
+        assert!(!cache.check_artifact_hash(&spec, "aabb").unwrap());
+
+        // A different hash for the same version is refused
+        assert!(matches!(
+            cache.check_artifact_hash(&spec, "ccdd"),
+            Err(RokitError::ArtifactHashChanged { .. })
+        ));
+
+        // Other versions are not affected
+        let other: ToolSpec = "rojo-rbx/rojo@7.5.0".parse().unwrap();
+        assert!(cache.check_artifact_hash(&other, "ccdd").unwrap());
+
+        // Re-trusting the tool forgets its hashes, allowing the new hash
+        let _ = cache.add_trust(id.clone());
+        assert!(cache.remove_trust(&id));
+        assert_eq!(cache.artifact_hash(&spec), None);
+        assert!(cache.check_artifact_hash(&spec, "ccdd").unwrap());
+    }
+
+    #[test]
+    fn merge_keeps_own_artifact_hashes() {
+        let rojo: ToolSpec = "rojo-rbx/rojo@7.4.4".parse().unwrap();
+        let lune: ToolSpec = "lune-org/lune@0.8.0".parse().unwrap();
+
+        let on_disk = ToolCache::new();
+        let _ = on_disk.check_artifact_hash(&rojo, "aa");
+        let _ = on_disk.check_artifact_hash(&lune, "bb");
+
+        let cache = ToolCache::new();
+        let _ = cache.check_artifact_hash(&rojo, "cc");
+        assert_eq!(
+            cache.forget_artifact_hashes(&"lune-org/lune".parse().unwrap()),
+            0
+        );
+
+        cache.merge_from(&on_disk);
+        assert_eq!(cache.artifact_hash(&rojo).as_deref(), Some("cc"));
+        assert_eq!(cache.artifact_hash(&lune), None);
+    }
+
+    #[test]
+    fn untrusting_forgets_all_hashes() {
+        let id: ToolId = "rojo-rbx/rojo".parse().unwrap();
+        let spec: ToolSpec = "rojo-rbx/rojo@7.4.4".parse().unwrap();
+
+        let on_disk = ToolCache::new();
+        let _ = on_disk.check_artifact_hash(&spec, "aa");
+        let _ = on_disk.check_binary_hash(&spec, "bb");
+
+        let cache = ToolCache::new();
+        let _ = cache.add_trust(id.clone());
+        let _ = cache.check_binary_hash(&spec, "bb");
+        assert!(matches!(
+            cache.check_binary_hash(&spec, "cc"),
+            Err(RokitError::ArtifactHashChanged { .. })
+        ));
+
+        assert!(cache.remove_trust(&id));
+        cache.merge_from(&on_disk);
+        assert_eq!(cache.artifact_hash(&spec), None);
+        assert_eq!(cache.binary_hash(&spec), None);
+    }
+}
@@ -1,26 +1,57 @@
 use std::{
-    env::consts::{EXE_EXTENSION, EXE_SUFFIX},
+    env::{
+        consts::{EXE_EXTENSION, EXE_SUFFIX},
+        temp_dir, var_os,
+    },
+    io::{ErrorKind, Write},
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use filepath::FilePath;
 use futures::{stream::FuturesUnordered, TryStreamExt};
-use tokio::{
-    fs::{create_dir_all, read, read_dir, remove_file, rename},
-    sync::Mutex as AsyncMutex,
-};
+use tempfile::{NamedTempFile, TempDir};
+use tokio::sync::Mutex as AsyncMutex;
 use tracing::{debug, trace};
 
 use crate::{
-    manifests::{AuthManifest, RokitManifest},
-    result::RokitResult,
+    executor::{
+        fs::{create_dir_all, read, read_dir, remove_dir_all, remove_file, rename, write},
+        spawn_blocking,
+    },
+    manifests::{AuthManifest, PolicyManifest, RokitManifest},
+    result::{RokitError, RokitResult},
+    sources::{ArtifactProvider, ExtraFile, ExtraFileKind},
     storage::metadata::RokitLinkMetadata,
-    system::current_exe_contents,
+    system::{current_exe_contents, roblox_plugins_dir},
     tool::{ToolAlias, ToolSpec},
-    util::fs::{path_exists, write_executable_file},
+    util::fs::{
+        add_executable_permissions, copy_executable_file, path_exists, write_executable_file,
+    },
 };
 
+/**
+    Environment variable that overrides the directory used for temporary files.
+*/
+pub const TEMP_DIR_ENV_VAR: &str = "ROKIT_TEMP_DIR";
+const TEMP_DIR_HOME_VALUE: &str = "home";
+const TEMP_DIR_HOME_NAME: &str = "tmp";
+
+/**
+    The status of a single link in the binary directory.
+
+    See [`ToolStorage::check_link`] for more information.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkStatus {
+    /// The link was written by the current version of Rokit.
+    Current,
+    /// The link was written by a different version of Rokit.
+    Outdated(String),
+    /// The link is not a valid Rokit link, and may be corrupted.
+    Invalid,
+}
+
 /**
     Storage for tool binaries and aliases.
 
@@ -31,18 +62,15 @@ use crate::{
 pub struct ToolStorage {
     pub(super) tools_dir: Arc<Path>,
     pub(super) aliases_dir: Arc<Path>,
+    pub(super) share_dir: Arc<Path>,
+    temp_dir: Option<Arc<Path>>,
     current_rokit_contents: Arc<AsyncMutex<Option<Vec<u8>>>>,
+    current_link_template: Arc<AsyncMutex<Option<PathBuf>>>,
 }
 
 impl ToolStorage {
     fn tool_paths(&self, spec: &ToolSpec) -> (PathBuf, PathBuf) {
-        // NOTE: We use uncased strings for the tool author and name
-        // to ensure that the tool paths are always case-insensitive
-        let tool_dir = self
-            .tools_dir
-            .join(spec.id.author.uncased_str())
-            .join(spec.id.name.uncased_str())
-            .join(spec.version.to_string());
+        let tool_dir = self.tools_dir.join(tool_relative_dir(spec));
 
         let tool_file_name = format!("{}{EXE_SUFFIX}", spec.id.name.uncased_str());
         let tool_file = tool_dir.join(tool_file_name);
@@ -69,6 +97,42 @@ impl ToolStorage {
         Ok(contents)
     }
 
+    /**
+        Gets the path to a template for links, meaning the Rokit
+        binary with link metadata appended, writing it if necessary.
+
+        All links have the exact same contents, so they are copied from this
+        template, which lets filesystems that support copy-on-write clones
+        share the same data between all links, instead of storing it once
+        for every single link - see [`copy_executable_file`].
+    */
+    async fn link_template(&self) -> RokitResult<PathBuf> {
+        let mut guard = self.current_link_template.lock().await;
+        if let Some(path) = &*guard {
+            return Ok(path.clone());
+        }
+
+        let path = self.tools_dir.join(format!(".link{EXE_SUFFIX}"));
+        let contents = RokitLinkMetadata::current().append_to(self.rokit_contents().await?)?;
+        if read(&path).await.ok().as_deref() != Some(contents.as_slice()) {
+            trace!(?path, "writing link template");
+            // NOTE: Other Rokit processes may be copying from the template
+            // right now, so it must be replaced atomically, never in place
+            let dir = self.tools_dir.to_path_buf();
+            let target = path.clone();
+            spawn_blocking(move || {
+                let mut file = NamedTempFile::new_in(dir)?;
+                file.write_all(&contents)?;
+                file.persist(target).map_err(|e| e.error)?;
+                RokitResult::Ok(())
+            })
+            .await??;
+        }
+
+        *guard = Some(path.clone());
+        Ok(path)
+    }
+
     /**
         Returns the path to the binary for the given tool.
 
@@ -79,6 +143,113 @@ impl ToolStorage {
         self.tool_paths(spec).1
     }
 
+    /**
+        Returns the path to the directory for the given tool, which contains
+        its binary, and may also be used to store any other tool data.
+
+        Note that this does not check if the directory actually exists.
+    */
+    #[must_use]
+    pub fn tool_dir(&self, spec: &ToolSpec) -> PathBuf {
+        self.tool_paths(spec).0
+    }
+
+    /**
+        Returns the path to the executable for the given tool, when it is
+        installed as a whole directory tree using `install-dir = true`.
+
+        The entrypoint is relative to the tool directory, and gets the
+        executable extension of the current system if it has none.
+        Without an entrypoint, this is the same as [`ToolStorage::tool_path`].
+
+        Note that this does not check if the executable actually exists.
+    */
+    #[must_use]
+    pub fn entrypoint_path(&self, spec: &ToolSpec, entrypoint: Option<&str>) -> PathBuf {
+        let (dir_path, file_path) = self.tool_paths(spec);
+        match entrypoint {
+            Some(entrypoint) => {
+                let mut path = dir_path.join(entrypoint.trim()).into_os_string();
+                if !has_exe_extension(&path) {
+                    path.push(EXE_SUFFIX);
+                }
+                PathBuf::from(path)
+            }
+            None => file_path,
+        }
+    }
+
+    /**
+        Creates an empty directory to unpack the entire archive of a tool into,
+        next to its tool directory, or in the configured temporary directory,
+        so that it can be moved into place using [`ToolStorage::replace_tool_dir`].
+
+        The directory is removed once the returned [`TempDir`] is dropped, unless
+        it was moved. See [`ToolStorage::temp_dir`] for more information.
+
+        # Errors
+
+        - If the directory could not be created.
+    */
+    pub async fn create_tool_staging_dir(&self, spec: &ToolSpec) -> RokitResult<TempDir> {
+        let parent = if self.temp_dir.is_some() {
+            self.temp_dir().await?
+        } else {
+            let dir_path = self.tool_dir(spec);
+            dir_path.parent().unwrap_or(&self.tools_dir).to_path_buf()
+        };
+        create_dir_all(&parent).await?;
+        let dir = tempfile::Builder::new()
+            .prefix(".staging-")
+            .tempdir_in(parent)?;
+        Ok(dir)
+    }
+
+    /**
+        Replaces the entire directory for the given tool with the staged
+        directory, for tools installed using `install-dir = true`.
+
+        Every one of the given entrypoints, as used for [`ToolStorage::entrypoint_path`],
+        must exist in the staged directory, and gets executable permissions.
+
+        # Errors
+
+        - If any of the entrypoints does not exist.
+        - If the existing directory could not be removed, or replaced.
+    */
+    pub async fn replace_tool_dir(
+        &self,
+        spec: &ToolSpec,
+        staged: TempDir,
+        entrypoints: &[Option<&str>],
+    ) -> RokitResult<()> {
+        let dir_path = self.tool_dir(spec);
+        for entrypoint in entrypoints {
+            let path = self.entrypoint_path(spec, *entrypoint);
+            let relative = path.strip_prefix(&dir_path).unwrap_or(&path);
+            let staged_path = staged.path().join(relative);
+            if !path_exists(&staged_path).await {
+                return Err(RokitError::EntrypointNotFound {
+                    spec: spec.clone().into(),
+                    path: relative.to_path_buf(),
+                });
+            }
+            add_executable_permissions(&staged_path).await?;
+        }
+
+        match remove_dir_all(&dir_path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        // NOTE: The configured temporary directory may be on another
+        // filesystem, in which case the staged directory must be copied
+        if rename(staged.path(), &dir_path).await.is_err() {
+            copy_dir_all(staged.path().to_path_buf(), dir_path.clone()).await?;
+        }
+        debug!(path = ?dir_path, "replaced tool directory");
+        Ok(())
+    }
+
     /**
         Replaces the binary contents for the given tool.
 
@@ -97,6 +268,138 @@ impl ToolStorage {
         Ok(())
     }
 
+    /**
+        Removes the binary for the given tool, if it exists.
+
+        Returns `true` if the binary was removed, `false` if it did not exist.
+
+        # Errors
+
+        - If the binary exists, but could not be removed.
+    */
+    pub async fn remove_tool_contents(&self, spec: &ToolSpec) -> RokitResult<bool> {
+        match remove_file(self.tool_path(spec)).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /**
+        Replaces the contents of a Roblox Studio plugin, writing it into
+        the Roblox plugins directory as `<alias>.<extension>`.
+
+        Returns the path that the plugin was written to.
+
+        # Errors
+
+        - If the Roblox plugins directory could not be found.
+        - If the plugin could not be written.
+    */
+    pub async fn replace_plugin_contents(
+        &self,
+        alias: &ToolAlias,
+        extension: &str,
+        contents: impl AsRef<[u8]>,
+    ) -> RokitResult<PathBuf> {
+        let dir = roblox_plugins_dir().ok_or(RokitError::PluginsDirNotFound)?;
+        create_dir_all(&dir).await?;
+        let path = dir.join(format!("{}.{extension}", alias.name()));
+        write(&path, contents).await?;
+        Ok(path)
+    }
+
+    /**
+        Returns the path to the directory that extra files shipped
+        with tools, such as man pages and completion scripts, are
+        installed into. Man pages are placed in `man/man<section>`,
+        and completion scripts in `completions/<shell>`.
+
+        Note that this does not check if the directory actually exists.
+    */
+    #[must_use]
+    pub fn share_dir(&self) -> &Path {
+        &self.share_dir
+    }
+
+    /**
+        Writes extra files shipped with a tool, such as man pages and
+        completion scripts, into the share directory - replacing any
+        existing files with the same names.
+
+        Returns the paths of all files that were written.
+
+        # Errors
+
+        - If any of the files could not be written.
+    */
+    pub async fn replace_extra_files(&self, extras: &[ExtraFile]) -> RokitResult<Vec<PathBuf>> {
+        let mut paths = Vec::with_capacity(extras.len());
+        for extra in extras {
+            let dir = match extra.kind {
+                ExtraFileKind::ManPage(section) => {
+                    self.share_dir.join("man").join(format!("man{section}"))
+                }
+                ExtraFileKind::Completion(shell) => {
+                    self.share_dir.join("completions").join(shell.as_str())
+                }
+            };
+            create_dir_all(&dir).await?;
+            let path = dir.join(&extra.file_name);
+            write(&path, &extra.contents).await?;
+            trace!(?path, "wrote extra file");
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /**
+        Creates a temporary directory containing only the binary for a tool,
+        named after the given alias, for running the tool without installing
+        it - the directory and binary are removed once the returned
+        [`TempDir`] is dropped.
+
+        # Errors
+
+        - If the directory or binary could not be written.
+    */
+    pub async fn create_sandbox(
+        &self,
+        alias: &ToolAlias,
+        contents: impl AsRef<[u8]>,
+    ) -> RokitResult<TempDir> {
+        let dir = tempfile::Builder::new()
+            .prefix("rokit-sandbox-")
+            .tempdir_in(self.temp_dir().await?)?;
+        let path = dir.path().join(format!("{}{EXE_SUFFIX}", alias.name()));
+        write_executable_file(&path, contents).await?;
+        debug!(?path, "created sandbox");
+        Ok(dir)
+    }
+
+    /**
+        Gets the directory to use for temporary files, creating it if necessary.
+
+        By default, this is the system temporary directory. It may be changed
+        using the `temp_dir` setting in the policy manifest, or by setting the
+        `ROKIT_TEMP_DIR` environment variable, which takes precedence - either
+        to a path, or to `home` to use a directory inside of the Rokit home,
+        keeping temporary files on the same filesystem as tool storage, so that
+        they can be renamed instead of copied, and avoiding small `tmpfs` mounts
+        on some CI images. Relative paths are relative to the Rokit home.
+
+        # Errors
+
+        - If the temporary directory could not be created.
+    */
+    pub async fn temp_dir(&self) -> RokitResult<PathBuf> {
+        let Some(dir) = &self.temp_dir else {
+            return Ok(temp_dir());
+        };
+        create_dir_all(dir).await?;
+        Ok(dir.to_path_buf())
+    }
+
     /**
         Replaces the contents of the stored Rokit binary in memory.
 
@@ -106,6 +409,7 @@ impl ToolStorage {
     */
     pub async fn replace_rokit_contents(&self, contents: Vec<u8>) {
         self.current_rokit_contents.lock().await.replace(contents);
+        self.current_link_template.lock().await.take();
     }
 
     /**
@@ -130,13 +434,63 @@ impl ToolStorage {
         }
 
         // Create the new link
-        let rokit_contents = self.rokit_contents().await?;
-        let rokit_metadata = RokitLinkMetadata::current();
-        skip_or_write_link_with_meta(path, &rokit_contents, &rokit_metadata).await?;
+        let link_template = self.link_template().await?;
+        skip_or_write_link_with_meta(path, &link_template).await?;
 
         Ok(())
     }
 
+    /**
+        Checks the status of a link at the given path, such as
+        one returned by [`ToolStorage::all_link_paths`].
+
+        # Errors
+
+        - If the link could not be read.
+    */
+    pub async fn check_link(&self, path: impl AsRef<Path>) -> RokitResult<LinkStatus> {
+        let contents = read(path.as_ref()).await?;
+        Ok(match RokitLinkMetadata::parse_from(&contents) {
+            Some(meta) if meta.is_current() => LinkStatus::Current,
+            Some(meta) => LinkStatus::Outdated(meta.version),
+            None => LinkStatus::Invalid,
+        })
+    }
+
+    /**
+        Rewrites the link at the given path using the current version of Rokit,
+        unless it is already up-to-date - see [`ToolStorage::check_link`].
+
+        # Errors
+
+        - If the link could not be written.
+    */
+    pub async fn repair_link(&self, path: impl AsRef<Path>) -> RokitResult<()> {
+        let link_template = self.link_template().await?;
+        skip_or_write_link_with_meta(path, &link_template).await
+    }
+
+    /**
+        Removes the link at the given path, if it exists in the binary directory.
+
+        Returns `true` if the link was removed, `false` if it did not exist.
+
+        # Errors
+
+        - If the link could not be removed.
+    */
+    pub async fn remove_link(&self, path: impl AsRef<Path>) -> RokitResult<bool> {
+        let path = path.as_ref();
+        if path == self.rokit_path() || !path.starts_with(&self.aliases_dir) {
+            return Ok(false);
+        }
+        if !path_exists(path).await {
+            return Ok(false);
+        }
+        remove_file(path).await?;
+        Ok(true)
+    }
+
     /**
         Reads all currently known link paths for tool aliases in the binary directory.
 
@@ -213,7 +567,7 @@ impl ToolStorage {
                 // NOTE: If the currently running Rokit binary is being updated,
                 // we need to move it to a temporary location first to avoid issues
                 // with the OS killing the current executable when its overwritten.
-                let temp_file = tempfile::tempfile()?;
+                let temp_file = tempfile::tempfile_in(self.temp_dir().await?)?;
                 #[allow(unused_mut)]
                 let mut temp_path = temp_file.path()?;
                 #[cfg(windows)]
@@ -236,10 +590,10 @@ impl ToolStorage {
         // If any link already has the correct Rokit contents, we
         // can skip creating it, to avoid OS permission errors if the
         // link is currently being used to run some Rokit-managed program.
-        let rokit_metadata = RokitLinkMetadata::current();
+        let link_template = self.link_template().await?;
         link_paths
             .into_iter()
-            .map(|path| skip_or_write_link_with_meta(path, &rokit_contents, &rokit_metadata))
+            .map(|path| skip_or_write_link_with_meta(path, &link_template))
             .collect::<FuturesUnordered<_>>()
             .try_collect::<Vec<_>>()
             .await?;
@@ -247,25 +601,97 @@ impl ToolStorage {
         Ok((rokit_link_existed, was_rokit_updated))
     }
 
-    pub(crate) async fn load(home_path: impl AsRef<Path>) -> RokitResult<Self> {
+    /**
+        Moves tools from providers other than GitHub out of the directories
+        that older versions of Rokit stored them in, which did not include
+        the provider, and were shared with tools from all other providers.
+
+        A legacy directory is only moved if exactly one of the given installed
+        tools could have been stored in it, since it is otherwise impossible to
+        tell which provider the tool binary inside of it actually came from.
+
+        Returns the tools that could not be moved, and must be installed again.
+    */
+    pub(crate) async fn migrate_provider_dirs(&self, installed: &[ToolSpec]) -> Vec<ToolSpec> {
+        let mut not_migrated = Vec::new();
+        for spec in installed {
+            if spec.id.provider == ArtifactProvider::GitHub {
+                continue;
+            }
+            let tool_dir = self.tool_dir(spec);
+            if path_exists(&tool_dir).await {
+                continue;
+            }
+
+            let legacy_dir = legacy_tool_relative_dir(spec);
+            let sharing = installed
+                .iter()
+                .filter(|other| legacy_tool_relative_dir(other) == legacy_dir)
+                .count();
+            let legacy_dir = self.tools_dir.join(legacy_dir);
+            if sharing > 1 || !path_exists(&legacy_dir).await {
+                not_migrated.push(spec.clone());
+                continue;
+            }
+
+            let parent = tool_dir.parent().unwrap_or(&self.tools_dir);
+            let result = async {
+                create_dir_all(parent).await?;
+                rename(&legacy_dir, &tool_dir).await
+            };
+            match result.await {
+                Ok(()) => debug!(%spec, "moved tool into its provider directory"),
+                Err(e) => {
+                    debug!(%spec, error = %e, "failed to move tool into its provider directory");
+                    not_migrated.push(spec.clone());
+                }
+            }
+        }
+        not_migrated
+    }
+
+    pub(crate) async fn load(
+        home_path: impl AsRef<Path>,
+        root_path: impl AsRef<Path>,
+    ) -> RokitResult<Self> {
         let home_path = home_path.as_ref();
 
+        // NOTE: Links are shared between all profiles, they only
+        // run Rokit which then uses the currently active profile
         let tools_dir = home_path.join("tool-storage").into();
-        let aliases_dir = home_path.join("bin").into();
+        let aliases_dir = root_path.as_ref().join("bin").into();
+        let share_dir = root_path.as_ref().join("share").into();
 
-        tokio::try_join!(
+        let (_, _, policy, (), ()) = futures::try_join!(
             RokitManifest::load_or_create(&home_path),
             AuthManifest::load_or_create(&home_path),
+            PolicyManifest::load_or_create(&home_path),
             async { Ok(create_dir_all(&tools_dir).await?) },
             async { Ok(create_dir_all(&aliases_dir).await?) },
         )?;
 
+        let temp_dir = match var_os(TEMP_DIR_ENV_VAR).filter(|value| !value.is_empty()) {
+            Some(value) => Some(PathBuf::from(value)),
+            None => policy.temp_dir(),
+        };
+        let temp_dir = temp_dir.map(|dir| {
+            if dir == Path::new(TEMP_DIR_HOME_VALUE) {
+                home_path.join(TEMP_DIR_HOME_NAME).into()
+            } else {
+                home_path.join(dir).into()
+            }
+        });
+
         let current_rokit_contents = Arc::new(AsyncMutex::new(None));
+        let current_link_template = Arc::new(AsyncMutex::new(None));
 
         Ok(Self {
             tools_dir,
             aliases_dir,
+            share_dir,
+            temp_dir,
             current_rokit_contents,
+            current_link_template,
         })
     }
 
@@ -277,6 +703,60 @@ impl ToolStorage {
     }
 }
 
+/*
+    Tools from GitHub are stored as `author/name/version`, with a leading
+    directory for the host if it is not the default one, and tools from any
+    other provider get an additional leading directory for their provider.
+
+    The provider directory starts with an '@', which is never valid in hosts
+    or GitHub usernames, and can therefore never be shared with a GitHub tool.
+
+    NOTE: We use uncased strings for the tool author and name
+    to ensure that the tool paths are always case-insensitive.
+*/
+pub(super) fn tool_relative_dir(spec: &ToolSpec) -> PathBuf {
+    let legacy_dir = legacy_tool_relative_dir(spec);
+    match spec.id.provider {
+        ArtifactProvider::GitHub => legacy_dir,
+        provider => Path::new(&format!("@{}", provider.as_str())).join(legacy_dir),
+    }
+}
+
+fn legacy_tool_relative_dir(spec: &ToolSpec) -> PathBuf {
+    let mut dir = PathBuf::new();
+    if let Some(host) = &spec.id.host {
+        dir.push(host.uncased_str());
+    }
+    dir.push(spec.id.author.uncased_str());
+    dir.push(spec.id.name.uncased_str());
+    dir.push(spec.version.to_string());
+    dir
+}
+
+/*
+    Recursively copies the contents of a directory, for when it can not be
+    renamed because the source and destination are on different filesystems.
+*/
+async fn copy_dir_all(from: PathBuf, to: PathBuf) -> RokitResult<()> {
+    spawn_blocking(move || {
+        let mut dirs = vec![(from, to)];
+        while let Some((from, to)) = dirs.pop() {
+            std::fs::create_dir_all(&to)?;
+            for entry in std::fs::read_dir(&from)? {
+                let entry = entry?;
+                let target = to.join(entry.file_name());
+                if entry.file_type()?.is_dir() {
+                    dirs.push((entry.path(), target));
+                } else {
+                    std::fs::copy(entry.path(), target)?;
+                }
+            }
+        }
+        RokitResult::Ok(())
+    })
+    .await?
+}
+
 // Utility functions for migrating missing exe extensions from old Rokit versions
 
 fn should_check_exe_extensions() -> bool {
@@ -311,8 +791,7 @@ fn append_exe_extension(path: impl Into<PathBuf>) -> PathBuf {
 
 async fn skip_or_write_link_with_meta(
     path: impl AsRef<Path>,
-    rokit_contents: &[u8],
-    rokit_metadata: &RokitLinkMetadata,
+    link_template: &Path,
 ) -> RokitResult<()> {
     let link_path = path.as_ref();
 
@@ -326,8 +805,51 @@ async fn skip_or_write_link_with_meta(
         trace!(?link_path, ?meta, "link is outdated");
     }
 
-    let link_contents = rokit_metadata.append_to(rokit_contents)?;
-    write_executable_file(link_path, link_contents).await?;
+    copy_executable_file(link_template, link_path).await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn relative_dir(spec: &str) -> PathBuf {
+        tool_relative_dir(&spec.parse().unwrap())
+    }
+
+    #[test]
+    fn providers_never_share_tool_dirs() {
+        let specs = [
+            "author/name@1.0.0",
+            "github:ghe.example.com/author/name@1.0.0",
+            "gitlab:author/name@1.0.0",
+            "registry:author/name@1.0.0",
+            "s3:author/name@1.0.0",
+            "git:ghe.example.com/author/name@1.0.0",
+            "custom:ghe.example.com/author/name@1.0.0",
+        ];
+        let dirs = specs
+            .iter()
+            .map(|s| relative_dir(s))
+            .collect::<BTreeSet<_>>();
+        assert_eq!(dirs.len(), specs.len());
+    }
+
+    #[test]
+    fn tool_dir_layout() {
+        assert_eq!(
+            relative_dir("Rojo-Rbx/Rojo@7.4.4"),
+            Path::new("rojo-rbx").join("rojo").join("7.4.4")
+        );
+        assert_eq!(
+            relative_dir("gitlab:Rojo-Rbx/Rojo@7.4.4"),
+            Path::new("@gitlab")
+                .join("rojo-rbx")
+                .join("rojo")
+                .join("7.4.4")
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+use thiserror::Error;
+use toml_edit::{Array, DocumentMut};
+
+use crate::tool::{ToolId, ToolIdParseError};
+
+const TRUSTED_KEY: &str = "trusted";
+
+/**
+    Error type representing the possible errors that can occur when parsing a `TrustList`.
+*/
+#[derive(Debug, Error)]
+pub enum TrustListParseError {
+    #[error("the '{TRUSTED_KEY}' key must be an array of strings")]
+    InvalidArray,
+    #[error("invalid tool id '{id}': {source}")]
+    InvalidToolId {
+        id: String,
+        source: ToolIdParseError,
+    },
+}
+
+/**
+    A list of trusted tool identifiers, used to import and export trust in bulk.
+
+    May be parsed from either of these formats:
+
+    - A TOML file with a `trusted` array of tool identifiers
+    - A plain text file with one tool identifier per line, where
+      empty lines and lines starting with `#` are ignored
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustList {
+    tools: BTreeSet<ToolId>,
+}
+
+impl TrustList {
+    /**
+        Creates a new trust list from the given tool identifiers.
+    */
+    #[must_use]
+    pub fn new(tools: impl IntoIterator<Item = ToolId>) -> Self {
+        Self {
+            tools: tools.into_iter().collect(),
+        }
+    }
+
+    /**
+        Returns all tool identifiers in the list, sorted and deduplicated.
+    */
+    #[must_use]
+    pub fn tools(&self) -> Vec<ToolId> {
+        self.tools.iter().cloned().collect()
+    }
+
+    /**
+        Returns the number of tool identifiers in the list.
+    */
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    /**
+        Returns `true` if the list contains no tool identifiers.
+    */
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /**
+        Formats the list as a TOML file with a `trusted` array.
+
+        Use the `Display` implementation for the plain text format.
+    */
+    #[must_use]
+    pub fn to_toml_string(&self) -> String {
+        let mut array = self
+            .tools
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Array>();
+        for value in array.iter_mut() {
+            value.decor_mut().set_prefix("\n    ");
+        }
+        array.set_trailing("\n");
+        array.set_trailing_comma(true);
+
+        let mut document = DocumentMut::new();
+        document.insert(TRUSTED_KEY, toml_edit::value(array));
+        document.to_string()
+    }
+}
+
+impl FromStr for TrustList {
+    type Err = TrustListParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // NOTE: A plain list of tool ids is never valid TOML,
+        // so we can safely try to parse it as TOML first
+        if let Ok(document) = s.parse::<DocumentMut>() {
+            if let Some(item) = document.get(TRUSTED_KEY) {
+                let array = item.as_array().ok_or(TrustListParseError::InvalidArray)?;
+                let tools = array
+                    .iter()
+                    .map(|value| {
+                        let id = value.as_str().ok_or(TrustListParseError::InvalidArray)?;
+                        parse_tool_id(id)
+                    })
+                    .collect::<Result<_, _>>()?;
+                return Ok(Self { tools });
+            }
+        }
+
+        let tools = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(parse_tool_id)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { tools })
+    }
+}
+
+impl fmt::Display for TrustList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for tool in &self.tools {
+            writeln!(f, "{tool}")?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_tool_id(id: &str) -> Result<ToolId, TrustListParseError> {
+    id.parse()
+        .map_err(|source| TrustListParseError::InvalidToolId {
+            id: id.to_string(),
+            source,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_id(s: &str) -> ToolId {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_plain() {
+        let list = "# Comment\nrojo-rbx/rojo\n\n  lune-org/lune  \nrojo-rbx/rojo\n"
+            .parse::<TrustList>()
+            .unwrap();
+        assert_eq!(
+            list.tools(),
+            vec![new_id("lune-org/lune"), new_id("rojo-rbx/rojo")]
+        );
+    }
+
+    #[test]
+    fn parse_toml() {
+        let list = "trusted = [\"rojo-rbx/rojo\", \"lune-org/lune\"]"
+            .parse::<TrustList>()
+            .unwrap();
+        assert_eq!(
+            list.tools(),
+            vec![new_id("lune-org/lune"), new_id("rojo-rbx/rojo")]
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let err = "rojo-rbx/rojo\nnot-an-id\n"
+            .parse::<TrustList>()
+            .unwrap_err();
+        assert!(matches!(err, TrustListParseError::InvalidToolId { .. }));
+        assert!(matches!(
+            "trusted = \"rojo-rbx/rojo\"".parse::<TrustList>(),
+            Err(TrustListParseError::InvalidArray)
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let list = TrustList::new([new_id("rojo-rbx/rojo"), new_id("lune-org/lune")]);
+        assert_eq!(list.to_string().parse::<TrustList>().unwrap(), list);
+        assert_eq!(list.to_toml_string().parse::<TrustList>().unwrap(), list);
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tempfile::NamedTempFile;
+
+use crate::{
+    executor::{
+        fs::{create_dir_all, read},
+        spawn_blocking,
+    },
+    result::RokitResult,
+    util::hash::sha256_hex,
+};
+
+/**
+    An entry that can be stored in [`UrlEntries`], for the URL it describes.
+*/
+pub(super) trait UrlEntry: Serialize + DeserializeOwned {
+    fn url(&self) -> &str;
+}
+
+/**
+    A directory of small JSON entries, one for each URL,
+    stored as `<sha256 of url>.json` in the directory.
+
+    A missing or corrupted entry is read as no entry at all, so anything
+    stored here must be possible to fetch again from its URL.
+*/
+#[derive(Debug, Clone)]
+pub(super) struct UrlEntries {
+    root: Arc<Path>,
+}
+
+impl UrlEntries {
+    pub(super) fn new(root: PathBuf) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub(super) fn path(&self) -> &Path {
+        &self.root
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.root.join(format!("{}.json", sha256_hex(url)))
+    }
+
+    /**
+        Reads the entry for the given URL, if any.
+    */
+    pub(super) async fn read<T: UrlEntry>(&self, url: &str) -> Option<T> {
+        let contents = read(self.entry_path(url)).await.ok()?;
+        serde_json::from_slice::<T>(&contents)
+            .ok()
+            .filter(|entry| entry.url() == url)
+    }
+
+    /**
+        Writes the given entry, replacing any older entry for the same URL.
+
+        # Errors
+
+        - If the entry could not be written.
+    */
+    pub(super) async fn write<T: UrlEntry>(&self, entry: &T) -> RokitResult<()> {
+        let path = self.entry_path(entry.url());
+        let contents = serde_json::to_vec(entry)?;
+
+        create_dir_all(&self.root).await?;
+        let dir = self.root.to_path_buf();
+        spawn_blocking(move || {
+            // NOTE: Entries are moved into place, so that concurrent
+            // readers never see a partially written entry
+            let mut file = NamedTempFile::new_in(&dir)?;
+            file.write_all(&contents)?;
+            file.persist(&path).map_err(|e| e.error)?;
+            RokitResult::Ok(())
+        })
+        .await??;
+
+        Ok(())
+    }
+}
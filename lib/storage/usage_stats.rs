@@ -0,0 +1,217 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    executor::fs::{append, create_dir_all, metadata, read_to_string, remove_file, rename, write},
+    result::RokitResult,
+    tool::{ToolId, ToolSpec},
+};
+
+const USAGE_LOG_FILE_NAME: &str = "usage.log";
+const USAGE_SUMMARY_FILE_NAME: &str = "usage.json";
+const USAGE_LOG_COMPACT_SIZE: u64 = 256 * 1024;
+
+/**
+    How many times a tool has been run, and when it was last run.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolUsage {
+    pub invocations: u64,
+    pub last_used: DateTime<Utc>,
+}
+
+impl ToolUsage {
+    fn merge(&mut self, other: ToolUsage) {
+        self.invocations += other.invocations;
+        self.last_used = self.last_used.max(other.last_used);
+    }
+}
+
+/**
+    Statistics about how often each tool managed by Rokit is run.
+
+    Every run of a tool appends a single line to a log file, which is safe
+    to do from many concurrent processes without any locking. The log is
+    occasionally compacted into a summary when statistics are loaded.
+
+    Statistics are only ever stored locally, and are never sent anywhere.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UsageStats {
+    tools: BTreeMap<ToolSpec, ToolUsage>,
+}
+
+impl UsageStats {
+    /**
+        Gets the usage of a specific version of a tool, if it has ever been run.
+    */
+    #[must_use]
+    pub fn get(&self, spec: &ToolSpec) -> Option<ToolUsage> {
+        self.tools.get(spec).copied()
+    }
+
+    /**
+        Gets the combined usage of all versions of a tool, if it has ever been run.
+    */
+    #[must_use]
+    pub fn get_for_id(&self, id: &ToolId) -> Option<ToolUsage> {
+        self.tools
+            .iter()
+            .filter(|(spec, _)| spec.id() == id)
+            .map(|(_, usage)| *usage)
+            .reduce(|mut total, usage| {
+                total.merge(usage);
+                total
+            })
+    }
+
+    /**
+        Gets the usage of all tools that have ever been run, sorted by tool spec.
+    */
+    pub fn tools(&self) -> impl Iterator<Item = (&ToolSpec, &ToolUsage)> {
+        self.tools.iter()
+    }
+
+    fn record(&mut self, spec: ToolSpec, usage: ToolUsage) {
+        match self.tools.get_mut(&spec) {
+            Some(existing) => existing.merge(usage),
+            None => {
+                self.tools.insert(spec, usage);
+            }
+        }
+    }
+
+    fn merge_log(&mut self, log: &str) {
+        for (spec, used_at) in log.lines().filter_map(parse_log_line) {
+            let usage = ToolUsage {
+                invocations: 1,
+                last_used: used_at,
+            };
+            self.record(spec, usage);
+        }
+    }
+
+    fn dir(home_path: impl AsRef<Path>) -> PathBuf {
+        home_path.as_ref().join("tool-storage")
+    }
+
+    /**
+        Records a single run of the given tool, right now.
+    */
+    #[instrument(skip(home_path), level = "trace")]
+    pub(crate) async fn record_usage(
+        home_path: impl AsRef<Path>,
+        spec: &ToolSpec,
+    ) -> RokitResult<()> {
+        let dir = Self::dir(home_path);
+        create_dir_all(&dir).await?;
+
+        // NOTE: Appending a single short line is atomic, meaning
+        // that concurrent runs of tools will never corrupt the log
+        let line = format!("{}\t{spec}\n", Utc::now().timestamp());
+        append(dir.join(USAGE_LOG_FILE_NAME), line).await?;
+
+        trace!(%spec, "recorded tool usage");
+        Ok(())
+    }
+
+    #[instrument(skip(home_path), level = "trace")]
+    pub(crate) async fn load(home_path: impl AsRef<Path>) -> RokitResult<Self> {
+        let dir = Self::dir(home_path);
+
+        // NOTE: Statistics are purely informational, so a missing
+        // or corrupted file should never prevent Rokit from running
+        let mut stats = Self::default();
+        if let Ok(summary) = read_to_string(dir.join(USAGE_SUMMARY_FILE_NAME)).await {
+            let tools = serde_json::from_str::<BTreeMap<ToolSpec, ToolUsage>>(&summary);
+            stats.tools = tools.unwrap_or_default();
+        }
+        if let Ok(log) = read_to_string(dir.join(USAGE_LOG_FILE_NAME)).await {
+            stats.merge_log(&log);
+        }
+
+        trace!(count = stats.tools.len(), "loaded usage stats");
+        Ok(stats)
+    }
+
+    /**
+        Compacts the usage log into the summary, if the log has grown large.
+
+        Must only be called while holding the home lock.
+    */
+    #[instrument(skip(home_path), level = "trace")]
+    pub(crate) async fn compact_if_needed(home_path: impl AsRef<Path>) -> RokitResult<()> {
+        let dir = Self::dir(home_path);
+        let log_path = dir.join(USAGE_LOG_FILE_NAME);
+        let log_size = metadata(&log_path).await.map_or(0, |m| m.len());
+        if log_size < USAGE_LOG_COMPACT_SIZE {
+            return Ok(());
+        }
+
+        // NOTE: Moving the log out of the way first means that any tool
+        // run while compacting starts a new log instead of being lost
+        let compacting_path = dir.join(format!("{USAGE_LOG_FILE_NAME}.compacting"));
+        rename(&log_path, &compacting_path).await?;
+
+        let summary_path = dir.join(USAGE_SUMMARY_FILE_NAME);
+        let mut stats = Self::default();
+        if let Ok(summary) = read_to_string(&summary_path).await {
+            let tools = serde_json::from_str::<BTreeMap<ToolSpec, ToolUsage>>(&summary);
+            stats.tools = tools.unwrap_or_default();
+        }
+        stats.merge_log(&read_to_string(&compacting_path).await?);
+
+        write(&summary_path, serde_json::to_string(&stats.tools)?).await?;
+        remove_file(&compacting_path).await?;
+
+        debug!(log_size, "compacted usage log");
+        Ok(())
+    }
+}
+
+fn parse_log_line(line: &str) -> Option<(ToolSpec, DateTime<Utc>)> {
+    let (timestamp, spec) = line.split_once('\t')?;
+    let used_at = DateTime::from_timestamp(timestamp.trim().parse().ok()?, 0)?;
+    let spec = spec.trim().parse().ok()?;
+    Some((spec, used_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_spec(s: &str) -> ToolSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn merge_log() {
+        let mut stats = UsageStats::default();
+        stats.merge_log(
+            "100\trojo-rbx/rojo@7.4.4\n\
+            300\trojo-rbx/rojo@7.4.4\n\
+            200\trojo-rbx/rojo@7.4.3\n\
+            not a valid line\n\
+            400\tlune-org/lune@0.8.9\n",
+        );
+
+        let usage = stats.get(&new_spec("rojo-rbx/rojo@7.4.4")).unwrap();
+        assert_eq!(usage.invocations, 2);
+        assert_eq!(usage.last_used.timestamp(), 300);
+
+        let rojo: ToolId = "rojo-rbx/rojo".parse().unwrap();
+        let usage = stats.get_for_id(&rojo).unwrap();
+        assert_eq!(usage.invocations, 3);
+        assert_eq!(usage.last_used.timestamp(), 300);
+
+        let selene: ToolId = "kampfkarren/selene".parse().unwrap();
+        assert_eq!(stats.get_for_id(&selene), None);
+        assert_eq!(stats.tools().count(), 3);
+    }
+}
@@ -0,0 +1,194 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    descriptor::Descriptor,
+    sources::{Artifact, ArtifactFormat, ArtifactProvider},
+    tool::ToolSpec,
+    warnings::RokitWarnings,
+};
+
+#[derive(Debug, Error)]
+pub enum SelectionFixtureError {
+    #[error("invalid selection fixture: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("unknown platform '{platform}' in selection case for '{tool}'")]
+    UnknownPlatform { tool: ToolSpec, platform: String },
+}
+
+/**
+    A single case in a [`SelectionFixture`] - the names of all assets in a real
+    release of a tool, and the asset that should be selected for each platform.
+
+    An empty expected asset name means that no asset should be selected.
+*/
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SelectionCase {
+    tool: ToolSpec,
+    assets: Vec<String>,
+    expected: BTreeMap<String, String>,
+}
+
+/**
+    A set of artifact selection cases, parsed from TOML such as:
+
+    ```toml
+    [[case]]
+    tool = "rojo-rbx/rojo@7.4.4"
+    assets = ["rojo-7.4.4-linux-x86_64.zip", "rojo-7.4.4-windows-x86_64.zip"]
+
+    [case.expected]
+    linux-x86_64 = "rojo-7.4.4-linux-x86_64.zip"
+    windows-aarch64 = "rojo-7.4.4-windows-x86_64.zip"
+    macos-aarch64 = ""
+    ```
+
+    Platforms are detected the same way as they are in asset names, so any
+    name that Rokit understands, such as `linux-x86_64` or `win64`, may be used.
+
+    Running a fixture selects an asset for each platform in each case, the same
+    way that Rokit does when installing tools, making it easy to turn any
+    reported mis-selection into a permanent regression test.
+*/
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SelectionFixture {
+    #[serde(rename = "case", default)]
+    cases: Vec<SelectionCase>,
+}
+
+impl SelectionFixture {
+    /**
+        Runs all of the cases in this fixture, returning
+        the outcome for each platform in each case, in order.
+    */
+    #[must_use]
+    pub fn run(&self) -> Vec<SelectionOutcome> {
+        let mut outcomes = Vec::new();
+        for case in &self.cases {
+            let artifacts = case
+                .assets
+                .iter()
+                .map(|name| Artifact {
+                    provider: ArtifactProvider::GitHub,
+                    format: ArtifactFormat::from_path_or_url(name),
+                    id: None,
+                    url: None,
+                    name: Some(name.clone()),
+                    size: None,
+                    digest: None,
+                    tool_spec: case.tool.clone(),
+                })
+                .collect::<Vec<_>>();
+            for (platform, expected) in &case.expected {
+                // NOTE: Platforms are checked when the fixture is parsed
+                let Some(system) = Descriptor::detect(platform) else {
+                    continue;
+                };
+                let actual =
+                    Artifact::find_most_compatible_with(&artifacts, &system, &RokitWarnings::new())
+                        .and_then(|artifact| artifact.name);
+                outcomes.push(SelectionOutcome {
+                    tool: case.tool.clone(),
+                    platform: platform.clone(),
+                    expected: Some(expected.clone()).filter(|e| !e.is_empty()),
+                    actual,
+                });
+            }
+        }
+        outcomes
+    }
+}
+
+impl FromStr for SelectionFixture {
+    type Err = SelectionFixtureError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fixture = toml::from_str::<Self>(s)?;
+        for case in &fixture.cases {
+            for platform in case.expected.keys() {
+                if Descriptor::detect(platform).is_none() {
+                    return Err(SelectionFixtureError::UnknownPlatform {
+                        tool: case.tool.clone(),
+                        platform: platform.clone(),
+                    });
+                }
+            }
+        }
+        Ok(fixture)
+    }
+}
+
+/**
+    The outcome of selecting an asset for a single platform in a [`SelectionFixture`].
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectionOutcome {
+    pub tool: ToolSpec,
+    pub platform: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl SelectionOutcome {
+    /**
+        Checks if the selected asset was the expected one.
+    */
+    #[must_use]
+    pub fn is_match(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+impl fmt::Display for SelectionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expected = self.expected.as_deref().unwrap_or("nothing");
+        let actual = self.actual.as_deref().unwrap_or("nothing");
+        if self.is_match() {
+            write!(f, "{} on {} selected {actual}", self.tool, self.platform)
+        } else {
+            write!(
+                f,
+                "{} on {} selected {actual}, expected {expected}",
+                self.tool, self.platform
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("fixtures/artifact_selection.toml");
+
+    #[test]
+    fn artifact_selection_regressions() {
+        let fixture = FIXTURE.parse::<SelectionFixture>().unwrap();
+        let outcomes = fixture.run();
+        assert!(!outcomes.is_empty());
+
+        let mismatches = outcomes
+            .iter()
+            .filter(|outcome| !outcome.is_match())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert!(mismatches.is_empty(), "{mismatches:#?}");
+    }
+
+    #[test]
+    fn unknown_platforms_rejected() {
+        let fixture = "
+            [[case]]
+            tool = \"author/tool@1.0.0\"
+            assets = [\"tool-1.0.0-linux-x86_64.zip\"]
+            expected = { amiga = \"tool-1.0.0-linux-x86_64.zip\" }
+        ";
+        assert!(matches!(
+            fixture.parse::<SelectionFixture>(),
+            Err(SelectionFixtureError::UnknownPlatform { .. })
+        ));
+    }
+}
@@ -5,7 +5,8 @@ use std::{
 
 use async_once_cell::OnceCell as AsyncOnceCell;
 use once_cell::sync::OnceCell;
-use tokio::{fs::read, task::spawn_blocking};
+
+use crate::executor::{fs::read, spawn_blocking};
 
 static CURRENT_DIR: AsyncOnceCell<PathBuf> = AsyncOnceCell::new();
 static CURRENT_EXE: AsyncOnceCell<PathBuf> = AsyncOnceCell::new();
@@ -1,12 +1,9 @@
-use std::path::PathBuf;
+use std::{io::ErrorKind, path::PathBuf};
 
 use futures::{stream::FuturesUnordered, StreamExt};
-use tokio::{
-    fs::{read_to_string, write},
-    io::ErrorKind,
-};
 
 use crate::{
+    executor::fs::{read_to_string, write},
     result::{RokitError, RokitResult},
     storage::Home,
 };
@@ -18,12 +15,12 @@ const ENV_SHELL_SCRIPT: &str = include_str!("./env.sh");
 
 pub async fn add_to_path(home: &Home) -> RokitResult<bool> {
     // Find our binaries dir and try to format it as "$HOME/.rokit/bin"
-    let bin_dir = home.path().join("bin");
+    let bin_dir = home.root_path().join("bin");
     let bin_dir_str = bin_dir.to_str().ok_or(RokitError::InvalidUtf8)?;
     let bin_dir_in_home = replace_home_path_with_var(bin_dir_str);
 
     // Do the same for the shell script path - "$HOME/.rokit/env"
-    let file_path = home.path().join(ENV_SHELL_FILE_PATH);
+    let file_path = home.root_path().join(ENV_SHELL_FILE_PATH);
     let file_path_str = file_path.to_str().ok_or(RokitError::InvalidUtf8)?;
     let file_path_in_home = replace_home_path_with_var(file_path_str);
 
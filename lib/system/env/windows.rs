@@ -1,9 +1,9 @@
 use std::path::Path;
 
-use tokio::task::spawn_blocking;
 use winreg::{enums::HKEY_CURRENT_USER, RegKey};
 
 use crate::{
+    executor::spawn_blocking,
     result::{RokitError, RokitResult},
     storage::Home,
     util::path::simplify_path,
@@ -12,7 +12,7 @@ use crate::{
 pub async fn add_to_path(home: &Home) -> RokitResult<bool> {
     // NOTE: Calls to canonicalize may use blocking filesystem
     // operations, so we spawn a task where that's acceptable.
-    let dir = home.path().join("bin");
+    let dir = home.root_path().join("bin");
     let task = spawn_blocking(move || {
         let dir = dir.canonicalize()?;
 
@@ -1,9 +1,17 @@
+mod artifact_selection;
 mod current;
 mod env;
 mod process;
+mod roblox;
+
+#[cfg(feature = "tokio")]
 mod runner;
 
+pub use self::artifact_selection::{SelectionFixture, SelectionFixtureError, SelectionOutcome};
 pub use self::current::{current_dir, current_exe, current_exe_contents, current_exe_name};
 pub use self::env::{add_to_path, exists_in_path};
 pub use self::process::{Launcher as ProcessLauncher, Parent as ProcessParent};
-pub use self::runner::run_interruptible;
+pub use self::roblox::roblox_plugins_dir;
+
+#[cfg(feature = "tokio")]
+pub use self::runner::{run_interruptible, run_interruptible_with_env};
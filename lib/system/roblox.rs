@@ -0,0 +1,28 @@
+use std::{env::var_os, path::PathBuf};
+
+const PLUGINS_DIR_ENV_VAR: &str = "ROKIT_ROBLOX_PLUGINS_DIR";
+
+/**
+    Gets the path to the Roblox Studio plugins directory for the current user.
+
+    - Windows: `%LOCALAPPDATA%\Roblox\Plugins`
+    - macOS: `~/Documents/Roblox/Plugins`
+
+    Roblox Studio does not officially support other operating systems, so
+    for these, the directory must be given using the `ROKIT_ROBLOX_PLUGINS_DIR`
+    environment variable - which may also be used to override the default.
+*/
+#[must_use]
+pub fn roblox_plugins_dir() -> Option<PathBuf> {
+    if let Some(dir) = var_os(PLUGINS_DIR_ENV_VAR).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let base = if cfg!(windows) {
+        dirs::data_local_dir()?
+    } else if cfg!(target_os = "macos") {
+        dirs::document_dir()?
+    } else {
+        return None;
+    };
+    Some(base.join("Roblox").join("Plugins"))
+}
@@ -67,6 +67,33 @@ where
     C: AsRef<OsStr>,
     A: IntoIterator<Item = S>,
     S: AsRef<OsStr>,
+{
+    run_interruptible_with_env(command, args, Vec::<(&OsStr, &OsStr)>::new()).await
+}
+
+/**
+    Runs the given command with the given arguments and additional
+    environment variables, and returns its exit code.
+
+    See [`run_interruptible`] for more information.
+
+    # Errors
+
+    - If signal listeners could not be created
+    - If the given command could not be spawned
+*/
+pub async fn run_interruptible_with_env<C, A, S, E, K, V>(
+    command: C,
+    args: A,
+    envs: E,
+) -> IoResult<i32>
+where
+    C: AsRef<OsStr>,
+    A: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+    E: IntoIterator<Item = (K, V)>,
+    K: AsRef<OsStr>,
+    V: AsRef<OsStr>,
 {
     let signal_handle = spawn_signal_listener_task()?;
     let signal_aborter = signal_handle.abort_handle();
@@ -86,11 +113,16 @@ where
     let mut child = {
         #[cfg(unix)]
         {
-            command.args(args).kill_on_drop(true).spawn()?
+            command.args(args).envs(envs).kill_on_drop(true).spawn()?
         }
         #[cfg(windows)]
         {
-            command.args(args).group().kill_on_drop(true).spawn()?
+            command
+                .args(args)
+                .envs(envs)
+                .group()
+                .kill_on_drop(true)
+                .spawn()?
         }
     };
 
@@ -0,0 +1,214 @@
+use std::{fmt, str::FromStr};
+
+use semver::Version;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+const STABLE_CHANNEL_NAME: &str = "stable";
+const ANY_PRERELEASE_CHANNEL_NAME: &str = "prerelease";
+
+/**
+    Error type representing the possible errors that can occur when parsing a `ReleaseChannel`.
+*/
+#[derive(Debug, Error)]
+pub enum ReleaseChannelParseError {
+    #[error("channel is empty")]
+    Empty,
+    #[error("channel '{0}' is invalid - channels may only contain letters and digits")]
+    Invalid(String),
+}
+
+/**
+    A release channel, which is the update track that a tool follows.
+
+    The stable channel only contains releases without a prerelease
+    version, such as `1.2.3`. Any other channel, such as `beta`,
+    additionally contains prereleases where the first prerelease
+    identifier matches its name, such as `1.2.3-beta.4` - meaning
+    that a tool in the `beta` channel will update to newer betas,
+    and to newer stable releases, but never to an `alpha` or `rc`.
+
+    The special `prerelease` channel contains all releases, including
+    any prerelease, for tools that have opted into prereleases in general.
+
+    Channel names are not case sensitive, and are stored in lowercase.
+*/
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    DeserializeFromStr,
+    SerializeDisplay,
+)]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    AnyPrerelease,
+    Prerelease(String),
+}
+
+impl ReleaseChannel {
+    /**
+        Gets the name of this channel, such as `stable` or `beta`.
+    */
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Stable => STABLE_CHANNEL_NAME,
+            Self::AnyPrerelease => ANY_PRERELEASE_CHANNEL_NAME,
+            Self::Prerelease(name) => name,
+        }
+    }
+
+    /**
+        Checks if this is the stable channel.
+    */
+    #[must_use]
+    pub fn is_stable(&self) -> bool {
+        matches!(self, Self::Stable)
+    }
+
+    /**
+        Gets the channel that the given version belongs to - the stable
+        channel for regular versions, and the channel named by the first
+        prerelease identifier for prereleases, such as `beta` for `1.2.3-beta.4`.
+
+        Prereleases that do not start with a valid, non-numeric
+        channel name belong to the `prerelease` channel instead.
+    */
+    #[must_use]
+    pub fn of(version: &Version) -> Self {
+        if version.pre.is_empty() {
+            return Self::Stable;
+        }
+        let first = version.pre.as_str().split('.').next().unwrap_or_default();
+        match first.parse() {
+            Ok(Self::Prerelease(name)) if !name.chars().all(|c| c.is_ascii_digit()) => {
+                Self::Prerelease(name)
+            }
+            _ => Self::AnyPrerelease,
+        }
+    }
+
+    /**
+        Checks if the given version belongs to this channel.
+
+        See [`ReleaseChannel`] for more information.
+    */
+    #[must_use]
+    pub fn contains(&self, version: &Version) -> bool {
+        if version.pre.is_empty() {
+            return true;
+        }
+        match self {
+            Self::Stable => false,
+            Self::AnyPrerelease => true,
+            Self::Prerelease(name) => version
+                .pre
+                .as_str()
+                .split('.')
+                .next()
+                .is_some_and(|first| first.eq_ignore_ascii_case(name)),
+        }
+    }
+}
+
+impl FromStr for ReleaseChannel {
+    type Err = ReleaseChannelParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ReleaseChannelParseError::Empty);
+        }
+        if !s.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(ReleaseChannelParseError::Invalid(s.to_string()));
+        }
+        if s.eq_ignore_ascii_case(STABLE_CHANNEL_NAME) {
+            Ok(Self::Stable)
+        } else if s.eq_ignore_ascii_case(ANY_PRERELEASE_CHANNEL_NAME) {
+            Ok(Self::AnyPrerelease)
+        } else {
+            Ok(Self::Prerelease(s.to_ascii_lowercase()))
+        }
+    }
+}
+
+impl fmt::Display for ReleaseChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "stable".parse::<ReleaseChannel>().unwrap(),
+            ReleaseChannel::Stable
+        );
+        assert_eq!(
+            "Beta".parse::<ReleaseChannel>().unwrap(),
+            ReleaseChannel::Prerelease(String::from("beta"))
+        );
+        assert!("".parse::<ReleaseChannel>().is_err());
+        assert!("be-ta".parse::<ReleaseChannel>().is_err());
+    }
+
+    #[test]
+    fn stable_contains() {
+        let channel = ReleaseChannel::Stable;
+        assert!(channel.contains(&version("1.2.3")));
+        assert!(!channel.contains(&version("1.2.3-beta.1")));
+    }
+
+    #[test]
+    fn prerelease_contains() {
+        let channel: ReleaseChannel = "beta".parse().unwrap();
+        assert!(channel.contains(&version("1.2.3")));
+        assert!(channel.contains(&version("1.2.3-beta")));
+        assert!(channel.contains(&version("1.2.3-beta.4")));
+        assert!(!channel.contains(&version("1.2.3-alpha.1")));
+        assert!(!channel.contains(&version("1.2.3-betamax.1")));
+    }
+
+    #[test]
+    fn any_prerelease_contains() {
+        let channel: ReleaseChannel = "prerelease".parse().unwrap();
+        assert_eq!(channel, ReleaseChannel::AnyPrerelease);
+        assert!(channel.contains(&version("1.2.3")));
+        assert!(channel.contains(&version("1.2.3-beta.4")));
+        assert!(channel.contains(&version("1.2.3-rc")));
+    }
+
+    #[test]
+    fn of_version() {
+        assert_eq!(
+            ReleaseChannel::of(&version("1.2.3")),
+            ReleaseChannel::Stable
+        );
+        assert_eq!(
+            ReleaseChannel::of(&version("1.2.3-beta.4")),
+            ReleaseChannel::Prerelease(String::from("beta"))
+        );
+        assert_eq!(
+            ReleaseChannel::of(&version("1.2.3-0.1")),
+            ReleaseChannel::AnyPrerelease
+        );
+        assert_eq!(
+            ReleaseChannel::of(&version("1.2.3-rc-1")),
+            ReleaseChannel::AnyPrerelease
+        );
+    }
+}
@@ -6,7 +6,14 @@ use thiserror::Error;
 
 use crate::{sources::ArtifactProvider, util::str::CaseInsensitiveString};
 
-use super::{util::is_invalid_identifier, ToolAlias, ToolSpec};
+use super::{
+    util::{is_invalid_host, is_invalid_identifier},
+    ToolAlias, ToolSpec,
+};
+
+const DEFAULT_GITHUB_HOST: &str = "github.com";
+const CRATES_AUTHOR: &str = "crates.io";
+pub(crate) const NPM_AUTHOR: &str = "npmjs.com";
 
 /**
     Error type representing the possible errors that can occur when parsing a `ToolId`.
@@ -19,6 +26,12 @@ pub enum ToolIdParseError {
     MissingSeparator,
     #[error("artifact provider '{0}' is invalid")]
     InvalidProvider(String),
+    #[error("host '{0}' is invalid")]
+    InvalidHost(String),
+    #[error("artifact provider '{0}' does not support custom hosts")]
+    HostNotSupported(ArtifactProvider),
+    #[error("artifact provider '{0}' requires a host, such as '{0}:example.com/author/name'")]
+    HostRequired(ArtifactProvider),
     #[error("author '{0}' is empty or invalid")]
     InvalidAuthor(String),
     #[error("name '{0}' is empty or invalid")]
@@ -33,12 +46,34 @@ pub enum ToolIdParseError {
     See [`CaseInsensitiveString`] for more information.
 
     Also includes the provider of the artifact, which by default is `GitHub`.
+    Identifiers from different providers are never equal, even if their
+    author and name match, and non-default providers are kept when
+    displayed, so that trust granted to a tool never carries over to
+    a tool with the same name from somewhere else.
+
+    Tools hosted on a GitHub Enterprise Server may also include the host
+    name of the server, such as `github.mycorp.com/author/name` - these
+    are distinct from tools with the same author and name on github.com.
+    Tools from custom providers always include the host that their provider
+    was registered for, such as `custom:artifacts.mycorp.com/author/name`.
+
+    Crates on crates.io have no author, and are given by name only, such
+    as `crates:stylua` - their author is always `crates.io` internally.
+
+    Tools from plain git repositories always need the host of the repository,
+    such as `git:codeberg.org/owner/repo`, same as tools from custom providers.
+
+    Packages on the npm registry are given by name, such as `npm:esbuild`,
+    or by scope and name for scoped packages, such as `npm:biomejs/biome`
+    for the `@biomejs/biome` package - unscoped packages have the author
+    `npmjs.com` internally, which is never a valid scope.
 
     Used to uniquely identify a tool, but not its version.
 */
 #[derive(Debug, Clone, PartialEq, Eq, Hash, DeserializeFromStr, SerializeDisplay)]
 pub struct ToolId {
     pub(crate) provider: ArtifactProvider,
+    pub(crate) host: Option<CaseInsensitiveString>,
     pub(crate) author: CaseInsensitiveString,
     pub(crate) name: CaseInsensitiveString,
 }
@@ -49,6 +84,16 @@ impl ToolId {
         self.provider
     }
 
+    /**
+        Gets the custom host of this tool, such as `github.mycorp.com`.
+
+        Returns `None` for tools hosted on the default host of their provider.
+    */
+    #[must_use]
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_ref().map(CaseInsensitiveString::original_str)
+    }
+
     #[must_use]
     pub fn author(&self) -> &str {
         self.author.original_str()
@@ -68,6 +113,27 @@ impl ToolId {
     pub fn into_alias(self) -> ToolAlias {
         ToolAlias::from(self)
     }
+
+    /**
+        Creates a new id with the same provider and host as this one,
+        but with the author and name from a full name such as `author/name`.
+
+        Returns `None` if the full name is not a valid author and name.
+    */
+    #[cfg(feature = "github")]
+    pub(crate) fn with_full_name(&self, full_name: &str) -> Option<Self> {
+        let (author, name) = full_name.split_once('/')?;
+        let (author, name) = (author.trim(), name.trim());
+        if is_invalid_identifier(author) || is_invalid_identifier(name) {
+            return None;
+        }
+        Some(Self {
+            provider: self.provider,
+            host: self.host.clone(),
+            author: CaseInsensitiveString::new(author),
+            name: CaseInsensitiveString::new(name),
+        })
+    }
 }
 
 impl Ord for ToolId {
@@ -75,6 +141,8 @@ impl Ord for ToolId {
         self.author
             .cmp(&other.author)
             .then_with(|| self.name.cmp(&other.name))
+            .then_with(|| self.provider.cmp(&other.provider))
+            .then_with(|| self.host.cmp(&other.host))
     }
 }
 
@@ -100,10 +168,63 @@ impl FromStr for ToolId {
             }
         };
 
+        // Crates have no author, and crate names never contain separators
+        if provider == ArtifactProvider::Crates {
+            let name = after_provider.trim();
+            if is_invalid_identifier(name) {
+                return Err(ToolIdParseError::InvalidName(name.to_string()));
+            }
+            return Ok(Self {
+                provider,
+                host: None,
+                author: CaseInsensitiveString::new(CRATES_AUTHOR),
+                name: CaseInsensitiveString::new(name),
+            });
+        }
+
+        // Packages on npm may be unscoped, and then have no author
+        if provider == ArtifactProvider::Npm && !after_provider.contains('/') {
+            let name = after_provider.trim();
+            if is_invalid_identifier(name) {
+                return Err(ToolIdParseError::InvalidName(name.to_string()));
+            }
+            return Ok(Self {
+                provider,
+                host: None,
+                author: CaseInsensitiveString::new(NPM_AUTHOR),
+                name: CaseInsensitiveString::new(name),
+            });
+        }
+
         let Some((before, after)) = after_provider.split_once('/') else {
             return Err(ToolIdParseError::MissingSeparator);
         };
 
+        // Ids with two separators start with a custom host, such as `github.mycorp.com/a/b`
+        let (host, before, after) = match after.split_once('/') {
+            None => (None, before, after),
+            Some((author, name)) => {
+                let host = before.trim();
+                if is_invalid_host(host) {
+                    return Err(ToolIdParseError::InvalidHost(host.to_string()));
+                }
+                if !matches!(
+                    provider,
+                    ArtifactProvider::GitHub | ArtifactProvider::Custom | ArtifactProvider::Git
+                ) {
+                    return Err(ToolIdParseError::HostNotSupported(provider));
+                }
+                let host = Some(host).filter(|h| {
+                    provider != ArtifactProvider::GitHub
+                        || !h.eq_ignore_ascii_case(DEFAULT_GITHUB_HOST)
+                });
+                (host, author, name)
+            }
+        };
+        if matches!(provider, ArtifactProvider::Custom | ArtifactProvider::Git) && host.is_none() {
+            return Err(ToolIdParseError::HostRequired(provider));
+        }
+
         let before = before.trim();
         let after = after.trim();
 
@@ -116,6 +237,7 @@ impl FromStr for ToolId {
 
         Ok(Self {
             provider,
+            host: host.map(CaseInsensitiveString::new),
             author: CaseInsensitiveString::new(before),
             name: CaseInsensitiveString::new(after),
         })
@@ -124,6 +246,17 @@ impl FromStr for ToolId {
 
 impl fmt::Display for ToolId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.provider != ArtifactProvider::default() {
+            write!(f, "{}:", self.provider)?;
+        }
+        if self.provider == ArtifactProvider::Crates
+            || (self.provider == ArtifactProvider::Npm && self.author.original_str() == NPM_AUTHOR)
+        {
+            return write!(f, "{}", self.name.original_str());
+        }
+        if let Some(host) = &self.host {
+            write!(f, "{}/", host.original_str())?;
+        }
         write!(
             f,
             "{}/{}",
@@ -140,6 +273,7 @@ mod tests {
     fn new_id_with_provider(provider: ArtifactProvider, author: &str, name: &str) -> ToolId {
         ToolId {
             provider,
+            host: None,
             author: CaseInsensitiveString::new(author),
             name: CaseInsensitiveString::new(name),
         }
@@ -188,11 +322,16 @@ mod tests {
     fn parse_valid_provider() {
         // Known provider strings should parse ok
         assert!("github:a/b".parse::<ToolId>().is_ok());
+        assert!("gitlab:a/b".parse::<ToolId>().is_ok());
         // The parsed ToolId should match the input
         assert_eq!(
             "github:a/b".parse::<ToolId>().unwrap(),
             new_id_with_provider(ArtifactProvider::GitHub, "a", "b")
         );
+        assert_eq!(
+            "gitlab:a/b".parse::<ToolId>().unwrap(),
+            new_id_with_provider(ArtifactProvider::GitLab, "a", "b")
+        );
     }
 
     #[test]
@@ -270,4 +409,144 @@ mod tests {
         assert_eq!(map.get(&new_id("Author", "Name")), Some(&2));
         assert_eq!(map.get(&new_id("123ABC456", "78DE90")), Some(&3));
     }
+
+    #[test]
+    fn near_miss_ids_are_distinct() {
+        // Ids that only look similar must never be treated as the same tool
+        let id = new_id("rojo-rbx", "rojo");
+        assert_ne!(id, new_id("evil", "rojo"));
+        assert_ne!(id, new_id("rojo-rbx", "rojo2"));
+        assert_ne!(id, new_id("rojo-rbx-", "rojo"));
+        assert_ne!(id, new_id("rojo", "rojo-rbx"));
+        // But the same tool with a different casing or explicit provider is equal
+        assert_eq!(id, "Rojo-Rbx/Rojo".parse::<ToolId>().unwrap());
+        assert_eq!(id, "github:rojo-rbx/rojo".parse::<ToolId>().unwrap());
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        // Displaying and parsing an id should give back the same id
+        let id = new_id("rojo-rbx", "rojo");
+        assert_eq!(id.to_string(), "rojo-rbx/rojo");
+        assert_eq!(id.to_string().parse::<ToolId>().unwrap(), id);
+        // Non-default providers must be kept when displayed
+        let id = new_id_with_provider(ArtifactProvider::GitLab, "rojo-rbx", "rojo");
+        assert_eq!(id.to_string(), "gitlab:rojo-rbx/rojo");
+        assert_eq!(id.to_string().parse::<ToolId>().unwrap(), id);
+        assert_ne!(id, new_id("rojo-rbx", "rojo"));
+    }
+
+    #[test]
+    fn parse_valid_host() {
+        // Ids may start with a custom host for GitHub Enterprise Server
+        let id = "github.mycorp.com/team/tool".parse::<ToolId>().unwrap();
+        assert_eq!(id.host(), Some("github.mycorp.com"));
+        assert_eq!(id.author(), "team");
+        assert_eq!(id.name(), "tool");
+        assert_eq!(id.to_string(), "github.mycorp.com/team/tool");
+        assert_eq!(id.to_string().parse::<ToolId>().unwrap(), id);
+        // Tools on different hosts are never equal
+        assert_ne!(id, new_id("team", "tool"));
+        assert_ne!(id, "github.other.com/team/tool".parse::<ToolId>().unwrap());
+        // The default host is the same as no host at all
+        assert_eq!(
+            "github.com/team/tool".parse::<ToolId>().unwrap(),
+            new_id("team", "tool")
+        );
+    }
+
+    #[test]
+    fn parse_invalid_host() {
+        // Hosts must look like domain names
+        assert!("mycorp/team/tool".parse::<ToolId>().is_err());
+        assert!("my corp.com/team/tool".parse::<ToolId>().is_err());
+        // Hosts are only supported for GitHub and custom providers
+        assert!(matches!(
+            "gitlab:gitlab.mycorp.com/team/tool".parse::<ToolId>(),
+            Err(ToolIdParseError::HostNotSupported(ArtifactProvider::GitLab))
+        ));
+    }
+
+    #[test]
+    fn parse_custom_provider() {
+        // Custom providers are identified by the host they were registered for
+        let id = "custom:artifacts.mycorp.com/team/tool"
+            .parse::<ToolId>()
+            .unwrap();
+        assert_eq!(id.provider(), ArtifactProvider::Custom);
+        assert_eq!(id.host(), Some("artifacts.mycorp.com"));
+        assert_eq!(id.to_string(), "custom:artifacts.mycorp.com/team/tool");
+        assert_eq!(id.to_string().parse::<ToolId>().unwrap(), id);
+        // The default GitHub host is not special for custom providers
+        let id = "custom:github.com/team/tool".parse::<ToolId>().unwrap();
+        assert_eq!(id.host(), Some("github.com"));
+        // Custom providers always need a host
+        assert!(matches!(
+            "custom:team/tool".parse::<ToolId>(),
+            Err(ToolIdParseError::HostRequired(ArtifactProvider::Custom))
+        ));
+    }
+
+    #[test]
+    fn parse_git_provider() {
+        let id = "git:codeberg.org/owner/tool".parse::<ToolId>().unwrap();
+        assert_eq!(id.provider(), ArtifactProvider::Git);
+        assert_eq!(id.host(), Some("codeberg.org"));
+        assert_eq!(id.author(), "owner");
+        assert_eq!(id.name(), "tool");
+        assert_eq!(id.to_string(), "git:codeberg.org/owner/tool");
+        assert_eq!(id.to_string().parse::<ToolId>().unwrap(), id);
+        // Git repositories always need a host
+        assert!(matches!(
+            "git:owner/tool".parse::<ToolId>(),
+            Err(ToolIdParseError::HostRequired(ArtifactProvider::Git))
+        ));
+    }
+
+    #[test]
+    fn parse_crates_provider() {
+        // Crates are given by name only, and keep that form when displayed
+        let id = "crates:stylua".parse::<ToolId>().unwrap();
+        assert_eq!(id.provider(), ArtifactProvider::Crates);
+        assert_eq!(id.name(), "stylua");
+        assert_eq!(id.host(), None);
+        assert_eq!(id.to_string(), "crates:stylua");
+        assert_eq!(id.to_string().parse::<ToolId>().unwrap(), id);
+        // Crates never have an author or a host
+        assert!("crates:author/stylua".parse::<ToolId>().is_err());
+        assert!("crates:".parse::<ToolId>().is_err());
+    }
+
+    #[test]
+    fn parse_npm_provider() {
+        // Unscoped packages are given by name only, and keep that form when displayed
+        let id = "npm:esbuild".parse::<ToolId>().unwrap();
+        assert_eq!(id.provider(), ArtifactProvider::Npm);
+        assert_eq!(id.name(), "esbuild");
+        assert_eq!(id.to_string(), "npm:esbuild");
+        assert_eq!(id.to_string().parse::<ToolId>().unwrap(), id);
+        // Scoped packages use their scope as the author
+        let id = "npm:biomejs/biome".parse::<ToolId>().unwrap();
+        assert_eq!(id.author(), "biomejs");
+        assert_eq!(id.name(), "biome");
+        assert_eq!(id.to_string(), "npm:biomejs/biome");
+        // Packages never have a host
+        assert!("npm:registry.example.com/biomejs/biome"
+            .parse::<ToolId>()
+            .is_err());
+        assert!("npm:".parse::<ToolId>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "github")]
+    fn with_full_name() {
+        // Moved tools keep their provider and host
+        let id = "github.mycorp.com/team/tool".parse::<ToolId>().unwrap();
+        let moved = id.with_full_name("new-team/new-tool").unwrap();
+        assert_eq!(moved.to_string(), "github.mycorp.com/new-team/new-tool");
+        // Full names must be exactly an author and a name
+        assert!(id.with_full_name("tool").is_none());
+        assert!(id.with_full_name("a/b/c").is_none());
+        assert!(id.with_full_name("/tool").is_none());
+    }
 }
@@ -1,8 +1,16 @@
 mod alias;
+mod channel;
 mod id;
+mod resolution;
 mod spec;
 mod util;
 
 pub use self::alias::{ToolAlias, ToolAliasParseError};
+pub use self::channel::{ReleaseChannel, ReleaseChannelParseError};
 pub use self::id::{ToolId, ToolIdParseError};
+pub use self::resolution::{ResolutionStrategy, ResolutionStrategyParseError};
 pub use self::spec::{ToolSpec, ToolSpecParseError};
+
+#[cfg(feature = "runtime")]
+pub(crate) use self::id::NPM_AUTHOR;
+pub(crate) use self::util::is_invalid_host;
@@ -0,0 +1,111 @@
+use std::{fmt, str::FromStr};
+
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+const LATEST_RELEASE_NAME: &str = "latest";
+const NEWEST_TAG_NAME: &str = "newest-tag";
+
+/**
+    Error type representing the possible errors that can occur when parsing a `ResolutionStrategy`.
+*/
+#[derive(Debug, Error)]
+#[error("resolution '{0}' is invalid - expected 'latest' or 'newest-tag'")]
+pub struct ResolutionStrategyParseError(String);
+
+/**
+    How the latest version of a tool is chosen, given using
+    the `resolution` option for a tool in a Rokit manifest.
+
+    By default, the release that the provider marks as the latest is
+    used, such as the "latest release" on GitHub. Some repositories mark
+    a long-term support release as the latest, even though newer tags
+    exist - those tools may use the `newest-tag` strategy instead, which
+    always chooses the release with the highest version.
+
+    Providers without a latest release marker, such as crates.io,
+    always choose the highest version, regardless of the strategy.
+*/
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    DeserializeFromStr,
+    SerializeDisplay,
+)]
+pub enum ResolutionStrategy {
+    #[default]
+    LatestRelease,
+    NewestTag,
+}
+
+impl ResolutionStrategy {
+    /**
+        Gets the name of this strategy, as used in manifests.
+    */
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LatestRelease => LATEST_RELEASE_NAME,
+            Self::NewestTag => NEWEST_TAG_NAME,
+        }
+    }
+
+    /**
+        Checks if this is the default strategy, using the latest release marker.
+    */
+    #[must_use]
+    pub fn is_latest_release(self) -> bool {
+        matches!(self, Self::LatestRelease)
+    }
+}
+
+impl FromStr for ResolutionStrategy {
+    type Err = ResolutionStrategyParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case(LATEST_RELEASE_NAME) {
+            Ok(Self::LatestRelease)
+        } else if s.eq_ignore_ascii_case(NEWEST_TAG_NAME) {
+            Ok(Self::NewestTag)
+        } else {
+            Err(ResolutionStrategyParseError(s.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for ResolutionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        for strategy in [
+            ResolutionStrategy::LatestRelease,
+            ResolutionStrategy::NewestTag,
+        ] {
+            assert_eq!(
+                strategy.to_string().parse::<ResolutionStrategy>().unwrap(),
+                strategy
+            );
+        }
+        assert_eq!(
+            " Newest-Tag ".parse::<ResolutionStrategy>().unwrap(),
+            ResolutionStrategy::NewestTag
+        );
+        assert!("newest".parse::<ResolutionStrategy>().is_err());
+        assert!(ResolutionStrategy::default().is_latest_release());
+    }
+}
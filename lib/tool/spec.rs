@@ -1,6 +1,6 @@
 use std::{fmt, str::FromStr};
 
-use semver::{Version, VersionReq};
+use semver::{BuildMetadata, Version, VersionReq};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use thiserror::Error;
 
@@ -21,6 +21,8 @@ pub enum ToolSpecParseError {
     IdParseError(#[from] ToolIdParseError),
     #[error("version '{0}' is invalid")]
     InvalidVersion(String),
+    #[error("tag '{0}' is invalid - tags may only contain letters, digits, hyphens, and dots")]
+    InvalidTag(String),
     #[error(transparent)]
     VersionParseError(#[from] semver::Error),
     #[error(
@@ -31,11 +33,18 @@ pub enum ToolSpecParseError {
     VersionParseErrorSuspectedVersionReq(String),
 }
 
+const TAG_PREFIX: &str = "tag:";
+const TAG_BUILD_PREFIX: &str = "tag.";
+
 /**
     A tool specification, which includes the author, name, and version of a tool.
 
     This is an extension of [`ToolId`] used to uniquely identify
     a *specific version requirement* of a given tool.
+
+    Tools with releases that are not tagged using semver, such as `2024-05-01`
+    or `build-1234`, may instead be specified using an exact release tag, such
+    as `owner/repo@tag:build-1234` - see [`ToolSpec::with_tag`] for details.
 */
 #[derive(
     Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, DeserializeFromStr, SerializeDisplay,
@@ -71,10 +80,51 @@ impl ToolSpec {
         &self.version
     }
 
+    /**
+        Gets the exact release tag of this tool specification,
+        if it was created using [`ToolSpec::with_tag`].
+    */
+    #[must_use]
+    pub fn tag(&self) -> Option<&str> {
+        let is_placeholder = self.version.major == 0
+            && self.version.minor == 0
+            && self.version.patch == 0
+            && self.version.pre.is_empty();
+        if is_placeholder {
+            self.version.build.as_str().strip_prefix(TAG_BUILD_PREFIX)
+        } else {
+            None
+        }
+    }
+
     #[must_use]
     pub fn matches_id(&self, id: &ToolId) -> bool {
         self.id == *id
     }
+
+    /**
+        Creates a new tool specification for an exact release tag,
+        which is resolved as-is instead of being parsed as a version.
+
+        The tag is stored as build metadata on a placeholder version, such as
+        `0.0.0+tag.build-1234`, so that different tags of the same tool are
+        always distinct, and can be installed side by side like versions.
+
+        # Errors
+
+        - If the tag is empty, or contains characters other than
+          ASCII letters, digits, hyphens, and non-consecutive dots.
+    */
+    pub fn with_tag(id: ToolId, tag: impl AsRef<str>) -> Result<Self, ToolSpecParseError> {
+        let tag = tag.as_ref().trim();
+        let build = BuildMetadata::new(&format!("{TAG_BUILD_PREFIX}{tag}"))
+            .ok()
+            .filter(|_| !tag.is_empty())
+            .ok_or_else(|| ToolSpecParseError::InvalidTag(tag.to_string()))?;
+        let mut version = Version::new(0, 0, 0);
+        version.build = build;
+        Ok(ToolSpec { id, version })
+    }
 }
 
 impl FromStr for ToolSpec {
@@ -93,6 +143,10 @@ impl FromStr for ToolSpec {
 
         let id = before.parse::<ToolId>()?;
 
+        if let Some(tag) = after.strip_prefix(TAG_PREFIX) {
+            return Self::with_tag(id, tag);
+        }
+
         if is_invalid_identifier(after) {
             return Err(ToolSpecParseError::InvalidVersion(after.to_string()));
         }
@@ -115,7 +169,10 @@ impl FromStr for ToolSpec {
 
 impl fmt::Display for ToolSpec {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}@{}", self.id, self.version)
+        match self.tag() {
+            Some(tag) => write!(f, "{}@{TAG_PREFIX}{tag}", self.id),
+            None => write!(f, "{}@{}", self.id, self.version),
+        }
     }
 }
 
@@ -191,4 +248,57 @@ mod tests {
         assert!("a/b@c@d".parse::<ToolSpec>().is_err());
         assert!("a/b@c@d@e".parse::<ToolSpec>().is_err());
     }
+
+    #[test]
+    fn parse_valid_host() {
+        // Specs may start with a custom host for GitHub Enterprise Server
+        let spec = "github.mycorp.com/team/tool@1.0.0"
+            .parse::<ToolSpec>()
+            .unwrap();
+        assert_eq!(spec.id().host(), Some("github.mycorp.com"));
+        assert_eq!(spec.to_string(), "github.mycorp.com/team/tool@1.0.0");
+        assert_ne!(spec, new_spec("team", "tool", "1.0.0"));
+    }
+
+    #[test]
+    fn parse_valid_crate() {
+        // Specs for crates have no author, only a crate name
+        let spec = "crates:stylua@0.20.0".parse::<ToolSpec>().unwrap();
+        assert_eq!(spec.provider(), ArtifactProvider::Crates);
+        assert_eq!(spec.name(), "stylua");
+        assert_eq!(spec.to_string(), "crates:stylua@0.20.0");
+    }
+
+    #[test]
+    fn parse_valid_tag() {
+        // Exact tags are kept as-is, and are not parsed as versions
+        let spec = "author/name@tag:build-1234".parse::<ToolSpec>().unwrap();
+        assert_eq!(spec.tag(), Some("build-1234"));
+        assert_eq!(spec.to_string(), "author/name@tag:build-1234");
+        assert_eq!(spec.to_string().parse::<ToolSpec>().unwrap(), spec);
+        // Different tags are different specs
+        let other = "author/name@tag:2024-05-01".parse::<ToolSpec>().unwrap();
+        assert_eq!(other.tag(), Some("2024-05-01"));
+        assert_ne!(spec, other);
+        // Regular versions have no tag
+        assert_eq!(new_spec("author", "name", "1.2.3").tag(), None);
+        assert_eq!(new_spec("author", "name", "0.0.0+build").tag(), None);
+    }
+
+    #[test]
+    fn parse_invalid_tag() {
+        // Tags must be non-empty and contain only supported characters
+        assert!(matches!(
+            "author/name@tag:".parse::<ToolSpec>(),
+            Err(ToolSpecParseError::InvalidTag(_))
+        ));
+        assert!(matches!(
+            "author/name@tag:build_1234".parse::<ToolSpec>(),
+            Err(ToolSpecParseError::InvalidTag(_))
+        ));
+        assert!(matches!(
+            "author/name@tag:a..b".parse::<ToolSpec>(),
+            Err(ToolSpecParseError::InvalidTag(_))
+        ));
+    }
 }
@@ -1,3 +1,11 @@
+pub fn is_invalid_host(s: &str) -> bool {
+    s.is_empty() // Must not be empty
+        || !s.contains('.') // Must be a domain name, not a single label
+        || s.starts_with(['.', '-'])
+        || s.ends_with(['.', '-'])
+        || !s.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
 pub fn is_invalid_identifier(s: &str) -> bool {
     s.is_empty() // Must not be empty
         || s.chars().all(char::is_whitespace) // Must contain some information
@@ -1,9 +1,55 @@
-use std::{env::consts::EXE_EXTENSION, path::Path, str::FromStr};
+use std::{
+    env::{consts::EXE_EXTENSION, var},
+    fs::File,
+    io::{ErrorKind, Write},
+    path::Path,
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
-use tokio::fs::{metadata, read_to_string, write};
-use tracing::{error, warn};
+use once_cell::sync::Lazy;
+use reflink_copy::reflink_or_copy;
+use tracing::{error, trace, warn};
 
-use crate::result::{RokitError, RokitResult};
+use crate::{
+    executor::{
+        fs::{metadata, read_to_string, remove_file, write},
+        spawn_blocking,
+    },
+    result::{RokitError, RokitResult},
+};
+
+static DURABLE_WRITES: Lazy<AtomicBool> = Lazy::new(|| {
+    let enabled = var("ROKIT_DURABLE_WRITES")
+        .is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes" | "on"));
+    AtomicBool::new(enabled)
+});
+
+/**
+    Checks if durable writes are enabled.
+
+    When enabled, manifests and caches are flushed to disk using `fsync`,
+    together with their parent directory, before a save is considered
+    complete. This is slower, but guarantees that saved data survives
+    a power loss, and is recommended for network filesystems and CI.
+
+    Durable writes are disabled by default, and may be enabled by
+    setting the `ROKIT_DURABLE_WRITES` environment variable to `1`.
+*/
+#[must_use]
+pub fn durable_writes() -> bool {
+    DURABLE_WRITES.load(Ordering::SeqCst)
+}
+
+/**
+    Enables or disables durable writes for the current process,
+    overriding the `ROKIT_DURABLE_WRITES` environment variable.
+
+    See [`durable_writes`] for more information.
+*/
+pub fn set_durable_writes(enabled: bool) {
+    DURABLE_WRITES.store(enabled, Ordering::SeqCst);
+}
 
 /**
     Loads the given type from the file at the given path.
@@ -24,13 +70,21 @@ where
         Err(e) => Err(e.into()),
         Ok(s) => match s.parse() {
             Ok(t) => Ok(t),
-            Err(e) => Err(e.into()),
+            Err(e) => match e.into() {
+                RokitError::ManifestParse(e) => {
+                    Err(RokitError::ManifestParse((*e).with_path(path).into()))
+                }
+                e => Err(e),
+            },
         },
     }
 }
 
 /**
     Saves the given data, stringified, to the file at the given path.
+
+    If durable writes are enabled, this will also `fsync` the
+    file and its parent directory, see [`durable_writes`].
 */
 pub(crate) async fn save_to_file<P, T>(path: P, data: T) -> RokitResult<()>
 where
@@ -38,7 +92,40 @@ where
     T: Clone + ToString,
 {
     let path = path.as_ref();
-    write(path, data.to_string()).await?;
+    if durable_writes() {
+        let file_path = path.to_path_buf();
+        let contents = data.to_string();
+        spawn_blocking(move || {
+            let mut file = File::create(file_path)?;
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .await??;
+        sync_parent_dir(path).await?;
+    } else {
+        write(path, data.to_string()).await?;
+    }
+    Ok(())
+}
+
+/**
+    Flushes the directory entry for the file at the given path to disk,
+    which is necessary for a newly created file to survive a power loss.
+*/
+#[cfg(unix)]
+pub(crate) async fn sync_parent_dir(path: impl AsRef<Path>) -> RokitResult<()> {
+    if let Some(parent) = path.as_ref().parent() {
+        let parent = parent.to_path_buf();
+        spawn_blocking(move || File::open(parent)?.sync_all()).await??;
+    }
+    Ok(())
+}
+
+// NOTE: Directories can not be opened as files on Windows, and
+// NTFS journals directory entries, so there is nothing to sync
+#[cfg(not(unix))]
+#[allow(clippy::unused_async)]
+pub(crate) async fn sync_parent_dir(_path: impl AsRef<Path>) -> RokitResult<()> {
     Ok(())
 }
 
@@ -83,11 +170,60 @@ pub async fn write_executable_file(
     Ok(())
 }
 
+/**
+    Copies the executable file at the given path to another
+    path, replacing any existing file, and adds executable
+    permissions to the copy.
+
+    On filesystems that support it, such as APFS, Btrfs, and XFS, the copy
+    is a copy-on-write clone (reflink), which is near-instant and uses no
+    additional disk space until either file is modified. On all other
+    filesystems, this falls back to a regular copy.
+*/
+pub async fn copy_executable_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> RokitResult<()> {
+    let from = from.as_ref().to_path_buf();
+    let to = to.as_ref().to_path_buf();
+
+    // NOTE: Clones can only be created as new files, and never
+    // replace existing ones, so remove any existing file first
+    match remove_file(&to).await {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            error!("Failed to replace executable at {to:?}:\n{e}");
+            return Err(e.into());
+        }
+        _ => {}
+    }
+
+    let path = to.clone();
+    let copied = spawn_blocking(move || reflink_or_copy(from, to)).await?;
+    match copied {
+        Ok(copied_bytes) => trace!(
+            ?path,
+            reflinked = copied_bytes.is_none(),
+            "copied executable"
+        ),
+        Err(e) => {
+            error!("Failed to copy executable to {path:?}:\n{e}");
+            return Err(e.into());
+        }
+    }
+
+    add_executable_permissions(&path).await?;
+
+    Ok(())
+}
+
+/**
+    Adds executable permissions to the file at the given path.
+
+    This does nothing on systems without executable permissions, such as Windows.
+*/
 #[cfg(unix)]
-async fn add_executable_permissions(path: impl AsRef<Path>) -> RokitResult<()> {
+pub async fn add_executable_permissions(path: impl AsRef<Path>) -> RokitResult<()> {
     use std::fs::Permissions;
     use std::os::unix::fs::PermissionsExt;
-    use tokio::fs::set_permissions;
+
+    use crate::executor::fs::set_permissions;
 
     let path = path.as_ref();
     if let Err(e) = set_permissions(path, Permissions::from_mode(0o755)).await {
@@ -99,6 +235,6 @@ async fn add_executable_permissions(path: impl AsRef<Path>) -> RokitResult<()> {
 }
 
 #[cfg(not(unix))]
-async fn add_executable_permissions(_path: impl AsRef<Path>) -> RokitResult<()> {
+pub async fn add_executable_permissions(_path: impl AsRef<Path>) -> RokitResult<()> {
     Ok(())
 }
@@ -0,0 +1,48 @@
+use std::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+/**
+    Computes the SHA-256 digest of the given contents,
+    formatted as a lowercase hexadecimal string.
+
+    For internal use only.
+*/
+pub(crate) fn sha256_hex(contents: impl AsRef<[u8]>) -> String {
+    hex(&Sha256::digest(contents.as_ref()))
+}
+
+/**
+    Formats the given bytes as a lowercase hexadecimal string.
+
+    For internal use only.
+*/
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_empty() {
+        assert_eq!(
+            sha256_hex([]),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn sha256_known() {
+        assert_eq!(
+            sha256_hex("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -1,3 +1,7 @@
+pub(crate) mod hash;
+pub(crate) mod str;
+
+#[cfg(feature = "runtime")]
 pub(crate) mod fs;
+#[cfg(feature = "runtime")]
 pub(crate) mod path;
-pub(crate) mod str;
@@ -9,6 +9,40 @@ pub(crate) const fn char_is_word_separator(c: char) -> bool {
     c.is_ascii_whitespace() || matches!(c, '-' | '_')
 }
 
+/**
+    Checks if the given string matches a wildcard pattern, ignoring case.
+
+    In patterns, `*` matches any sequence of characters, including an
+    empty one, and `?` matches exactly one character.
+
+    For internal use only.
+*/
+pub(crate) fn matches_wildcard(pattern: &str, s: &str) -> bool {
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+    let s = s.to_lowercase().chars().collect::<Vec<_>>();
+
+    // NOTE: Classic greedy matching with backtracking to the last
+    // star, which runs in linear time for typical asset patterns
+    let (mut p, mut c) = (0, 0);
+    let mut last_star = None;
+    while c < s.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == s[c]) {
+            p += 1;
+            c += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            last_star = Some((p, c));
+            p += 1;
+        } else if let Some((star_p, star_c)) = last_star {
+            p = star_p + 1;
+            c = star_c + 1;
+            last_star = Some((star_p, star_c + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
+
 /**
     A case-insensitive string wrapper.
 
@@ -85,3 +119,30 @@ impl fmt::Display for CaseInsensitiveString {
         self.original_str().fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_matches() {
+        assert!(matches_wildcard("*.deb", "tool-1.0.0-amd64.deb"));
+        assert!(matches_wildcard("*.MSI", "tool-1.0.0-x86_64.msi"));
+        assert!(matches_wildcard(
+            "tool-*-linux-*.zip",
+            "tool-1.0.0-linux-x86_64.zip"
+        ));
+        assert!(matches_wildcard("tool-?.zip", "tool-a.zip"));
+        assert!(matches_wildcard("*", ""));
+        assert!(matches_wildcard("tool.zip", "tool.zip"));
+    }
+
+    #[test]
+    fn wildcard_mismatches() {
+        assert!(!matches_wildcard("*.deb", "tool-1.0.0-linux.zip"));
+        assert!(!matches_wildcard("*.deb", "tool.deb.zip"));
+        assert!(!matches_wildcard("tool-?.zip", "tool-ab.zip"));
+        assert!(!matches_wildcard("tool", "tool.zip"));
+        assert!(!matches_wildcard("?", ""));
+    }
+}
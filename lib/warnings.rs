@@ -0,0 +1,190 @@
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    descriptor::Arch,
+    tool::{ToolAlias, ToolSpec},
+};
+
+/**
+    A non-fatal issue encountered by Rokit during an operation.
+
+    Warnings do not prevent an operation from succeeding, but
+    should generally be displayed to the user, since they may
+    indicate that something will not work as expected.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RokitWarning {
+    /**
+        No artifact was found for the current architecture,
+        and an artifact for another architecture was chosen,
+        which the current system should be able to emulate.
+    */
+    EmulatedArtifact {
+        spec: ToolSpec,
+        artifact_name: String,
+        current_arch: Arch,
+        artifact_arch: Arch,
+    },
+    /**
+        No artifact was found that is known to be compatible with
+        the current system, and a partially compatible artifact with
+        only a matching operating system was chosen as a fallback.
+    */
+    PartiallyCompatibleArtifact {
+        spec: ToolSpec,
+        artifact_name: String,
+    },
+    /**
+        A tool version that has been yanked is pinned in a manifest.
+    */
+    YankedVersion { spec: ToolSpec },
+    /**
+        The same tool alias is defined with different specifications in
+        several manifests, and the one in the nearest manifest shadows
+        the others - this is usually unintended, unless the tool in the
+        nearest manifest has been marked using `shadow = true`.
+    */
+    AliasCollision(Box<AliasCollision>),
+    /**
+        The release metadata for a tool could not be fetched, and the
+        artifact that was recorded when it was last installed was used
+        instead, after being verified against its recorded hash.
+    */
+    RecordedArtifact { spec: ToolSpec, reason: String },
+}
+
+/**
+    Details about a tool alias that is defined with different specifications
+    in several manifests - see [`RokitWarning::AliasCollision`].
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasCollision {
+    pub alias: ToolAlias,
+    pub spec: ToolSpec,
+    pub path: PathBuf,
+    pub shadowed_spec: ToolSpec,
+    pub shadowed_path: PathBuf,
+}
+
+impl fmt::Display for RokitWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmulatedArtifact {
+                spec,
+                artifact_name,
+                current_arch,
+                artifact_arch,
+            } => write!(
+                f,
+                "No {} artifact was found for {spec}, using {} artifact '{artifact_name}' \
+                which will run using emulation",
+                current_arch.as_str(),
+                artifact_arch.as_str(),
+            ),
+            Self::PartiallyCompatibleArtifact {
+                spec,
+                artifact_name,
+            } => write!(
+                f,
+                "No compatible artifact was found for {spec}, using '{artifact_name}' \
+                as a fallback which may not run on the current system",
+            ),
+            Self::YankedVersion { spec } => write!(
+                f,
+                "Tool {} is pinned to version {}, which has been yanked and is known to be broken",
+                spec.id(),
+                spec.version(),
+            ),
+            Self::AliasCollision(collision) => {
+                let AliasCollision {
+                    alias,
+                    spec,
+                    path,
+                    shadowed_spec,
+                    shadowed_path,
+                } = collision.as_ref();
+                write!(
+                    f,
+                    "Tool alias '{alias}' is {spec} in '{}', but {shadowed_spec} in '{}' - \
+                    using {spec}, set `shadow = true` for the tool in '{}' if this is intended",
+                    path.display(),
+                    shadowed_path.display(),
+                    path.display(),
+                )
+            }
+            Self::RecordedArtifact { spec, reason } => write!(
+                f,
+                "Release metadata for {spec} could not be fetched ({reason}), \
+                installing the artifact recorded when it was last installed",
+            ),
+        }
+    }
+}
+
+/**
+    A collection of warnings encountered during one or more operations.
+
+    Can be cheaply cloned while still referring to the same underlying
+    data, meaning it can be shared between concurrent operations.
+*/
+#[derive(Debug, Default, Clone)]
+pub struct RokitWarnings {
+    inner: Arc<Mutex<Vec<RokitWarning>>>,
+}
+
+impl RokitWarnings {
+    /**
+        Creates a new, **empty** collection of warnings.
+    */
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+        Adds a warning to this collection.
+
+        Warnings that have already been added will not be added again.
+    */
+    pub fn push(&self, warning: RokitWarning) {
+        let mut inner = self.inner.lock().expect("warnings lock was poisoned");
+        if !inner.contains(&warning) {
+            tracing::trace!(%warning, "emitted warning");
+            inner.push(warning);
+        }
+    }
+
+    /**
+        Checks if this collection contains no warnings.
+    */
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner
+            .lock()
+            .expect("warnings lock was poisoned")
+            .is_empty()
+    }
+
+    /**
+        Gets the number of warnings in this collection.
+    */
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.lock().expect("warnings lock was poisoned").len()
+    }
+
+    /**
+        Gets a copy of all warnings in this collection, in the order they were added.
+    */
+    #[must_use]
+    pub fn all(&self) -> Vec<RokitWarning> {
+        self.inner
+            .lock()
+            .expect("warnings lock was poisoned")
+            .clone()
+    }
+}
@@ -4,13 +4,17 @@ use console::style;
 
 use rokit::{
     discovery::discover_all_manifests,
-    manifests::RokitManifest,
+    manifests::{PolicyManifest, RokitManifest},
+    sources::{Artifact, ArtifactSource},
     storage::Home,
-    tool::{ToolAlias, ToolId},
+    tool::{ReleaseChannel, ToolAlias, ToolId},
+    warnings::{RokitWarning, RokitWarnings},
+    ResolveConstraints,
 };
 
 use crate::util::{
-    find_most_compatible_artifact, prompt_for_trust, CliProgressTracker, ToolIdOrSpec,
+    find_most_compatible_artifact, print_warnings, prompt_for_trust, resolve_headless_trust,
+    verify_artifact_hash, CliProgressTracker, ToolIdOrSpec,
 };
 
 /// Adds a new tool to Rokit and installs it.
@@ -18,8 +22,16 @@ use crate::util::{
 pub struct AddSubcommand {
     /// A tool identifier or specification describing where
     /// to get the tool, and optionally what version to install.
+    /// Partial versions such as `owner/repo@6` add the latest
+    /// matching version, and keep the tool within it on update.
+    /// May also be a direct HTTPS URL to an archive or binary.
     pub tool: ToolIdOrSpec,
+    /// The name that will be used to run the tool. Use different
+    /// names to add several major versions of the same tool.
+    #[clap(long = "alias", visible_alias = "as", value_name = "ALIAS")]
+    pub alias_flag: Option<ToolAlias>,
     /// The name that will be used to run the tool.
+    #[clap(conflicts_with = "alias_flag")]
     pub alias: Option<ToolAlias>,
     /// Add this tool globally instead of adding
     /// it to the nearest manifest file.
@@ -29,23 +41,35 @@ pub struct AddSubcommand {
     /// if it is already added or installed.
     #[clap(long)]
     pub force: bool,
+    /// Allow prereleases to be added, and to be updated to later on.
+    /// Tools added using an exact prerelease version, such as
+    /// `owner/repo@1.2.3-beta.1`, follow that prerelease channel.
+    #[clap(long)]
+    pub allow_prerelease: bool,
 }
 
 impl AddSubcommand {
     pub async fn run(self, home: &Home) -> Result<()> {
-        let id: ToolId = self.tool.clone().into();
-        let alias: ToolAlias = match self.alias.as_ref() {
-            Some(alias) => alias.clone(),
-            None => self.tool.clone().into(),
-        };
-
         let tool_cache = home.tool_cache();
         let tool_storage = home.tool_storage();
         let source = home.artifact_source().await?;
+        let policy = PolicyManifest::load_or_create(home.path()).await?;
+        let headless_trust = resolve_headless_trust(&policy)?;
+
+        // 0. Use the canonical casing of the tool id from its provider, so that
+        // the same tool is always written the same way, on any machine
+        let tool = canonicalize_tool(&source, self.tool).await;
+        let id: ToolId = tool.clone().into();
+        let alias: ToolAlias = match self.alias_flag.as_ref().or(self.alias.as_ref()) {
+            Some(alias) => alias.clone(),
+            None => tool.clone().into(),
+        };
 
         // 1. Check for trust, or prompt the user to trust the tool
         if !tool_cache.is_trusted(&id) {
-            if !self.force && !prompt_for_trust(id.clone()).await? {
+            if !self.force
+                && !prompt_for_trust(&source, tool_cache, headless_trust, id.clone()).await?
+            {
                 bail!("Tool is not trusted - operation was aborted");
             }
             let _ = tool_cache.add_trust(id.clone());
@@ -56,7 +80,8 @@ impl AddSubcommand {
         let manifest_path = if self.global {
             home.path().to_path_buf()
         } else {
-            let non_global_manifests = discover_all_manifests(true, true).await;
+            let non_global_manifests =
+                discover_all_manifests(&home.taps().await?, true, true).await;
             non_global_manifests
                 .first()
                 .map(|m| m.path.parent().unwrap().to_path_buf())
@@ -80,25 +105,73 @@ impl AddSubcommand {
             );
         }
 
-        // 3. If we only got an id without a specified version, we
-        // will fetch the latest non-prerelease release and use that
+        // 3. If we only got an id without a specified version, we will
+        // fetch the latest non-yanked release and use that - prereleases
+        // are only considered when the tool has explicitly opted into them
+        let channel = if self.allow_prerelease {
+            ReleaseChannel::AnyPrerelease
+        } else if let ToolIdOrSpec::Spec(spec) = &tool {
+            ReleaseChannel::of(spec.version())
+        } else {
+            ReleaseChannel::Stable
+        };
         let pt = CliProgressTracker::new_with_message("Fetching", 3);
-        let (spec, artifact) = match self.tool.clone() {
+        let warnings = RokitWarnings::new();
+        let (spec, artifact) = match tool.clone() {
             ToolIdOrSpec::Spec(spec) => {
+                if policy.is_yanked(&spec) {
+                    warnings.push(RokitWarning::YankedVersion { spec: spec.clone() });
+                }
                 let release_artifact = source.get_specific_release(&spec).await?;
-                let artifact = find_most_compatible_artifact(&release_artifact.artifacts, &id)?;
+                let artifact =
+                    find_most_compatible_artifact(&release_artifact.artifacts, &id, &warnings)?;
                 (spec, artifact)
             }
             ToolIdOrSpec::Id(id) => {
-                let release_artifact = source.get_latest_release(&id).await?;
-                let artifact = find_most_compatible_artifact(&release_artifact.artifacts, &id)?;
+                let release_artifact = source
+                    .get_latest_release_in_channel(&id, &channel, &policy.yanked_versions(&id))
+                    .await?;
+                let artifact =
+                    find_most_compatible_artifact(&release_artifact.artifacts, &id, &warnings)?;
+                (artifact.tool_spec.clone(), artifact)
+            }
+            ToolIdOrSpec::Req(id, req) => {
+                let constraints = ResolveConstraints::matching(req)
+                    .in_channel(channel.clone())
+                    .excluding(policy.yanked_versions(&id));
+                let release_artifact = source
+                    .get_latest_release_matching(&id, &constraints)
+                    .await?;
+                let artifact =
+                    find_most_compatible_artifact(&release_artifact.artifacts, &id, &warnings)?;
                 (artifact.tool_spec.clone(), artifact)
             }
+            ToolIdOrSpec::Url(url_source) => {
+                // NOTE: Direct URLs point at exactly one artifact, which the
+                // user chose, so there is nothing to pick from or to check
+                let spec = url_source.tool_spec();
+                let artifact = url_source.release_for(&spec).artifacts.remove(0);
+                (spec, artifact)
+            }
         };
         pt.task_completed();
 
-        // 4. Add the tool spec to the desired manifest file and save it
-        manifest.add_tool(&alias, &spec);
+        // 4. Add the tool spec to the desired manifest file and save it - tools
+        // added with a version requirement keep it, so that they stay within
+        // it when updated, even if another alias uses a newer major version
+        match &tool {
+            ToolIdOrSpec::Url(url_source) => {
+                manifest.add_tool_with_url(&alias, &spec, url_source.url())
+            }
+            ToolIdOrSpec::Req(_, req) if !channel.is_stable() => {
+                manifest.add_tool_with_channel(&alias, &spec, &channel, Some(req))
+            }
+            ToolIdOrSpec::Req(_, req) => manifest.add_tool_with_version_req(&alias, &spec, req),
+            _ if !channel.is_stable() => {
+                manifest.add_tool_with_channel(&alias, &spec, &channel, None)
+            }
+            _ => manifest.add_tool(&alias, &spec),
+        };
         manifest.save(manifest_path).await?;
 
         // 5. Download and install the tool
@@ -107,15 +180,18 @@ impl AddSubcommand {
                 .download_artifact_contents(&artifact)
                 .await
                 .with_context(|| format!("Failed to download contents for {spec}"))?;
+            verify_artifact_hash(tool_cache, &spec, &Artifact::compute_sha256(&contents))?;
             pt.task_completed();
             pt.update_message("Installing");
             let extracted = artifact
                 .extract_contents(contents)
                 .await
                 .with_context(|| format!("Failed to extract contents for {spec}"))?;
+            home.install_journal().record_installing(&spec).await?;
             tool_storage.replace_tool_contents(&spec, extracted).await?;
             pt.task_completed();
             let _ = tool_cache.add_installed(spec.clone());
+            home.install_journal().record_installed(&spec).await?;
         } else {
             pt.task_completed();
             pt.task_completed();
@@ -137,7 +213,27 @@ impl AddSubcommand {
             },
             pt.formatted_elapsed(),
         ));
+        print_warnings(&warnings);
 
         Ok(())
     }
 }
+
+/*
+    Replaces the id of the given tool with its canonical id, if it
+    could be fetched - this is best-effort, and any errors about the
+    tool not existing are instead reported when fetching its releases.
+*/
+async fn canonicalize_tool(source: &ArtifactSource, tool: ToolIdOrSpec) -> ToolIdOrSpec {
+    if matches!(tool, ToolIdOrSpec::Url(_)) {
+        return tool;
+    }
+    let id: ToolId = tool.clone().into();
+    match source.get_canonical_id(&id).await {
+        Ok(canonical_id) => tool.with_id(canonical_id),
+        Err(e) => {
+            tracing::debug!(%id, error = %e, "failed to fetch canonical tool id");
+            tool
+        }
+    }
+}
@@ -3,18 +3,33 @@ use clap::Parser;
 
 use console::style;
 use rokit::{
-    manifests::AuthManifest,
-    sources::{github::GithubProvider, ArtifactProvider},
+    manifests::{AuthManifest, PolicyManifest},
+    sources::{
+        github::GithubProvider,
+        gitlab::GitlabProvider,
+        s3::{S3Credentials, S3Provider, DEFAULT_REGION as DEFAULT_S3_REGION},
+        ArtifactProvider,
+    },
     storage::Home,
 };
 
 use crate::util::CliProgressTracker;
 
-/// Authenticate with an artifact provider, such as GitHub.
+const UNAUTHENTICATED_PROVIDERS: &str =
+    "direct URLs, registries, crates.io, npm, git repositories, and custom providers";
+
+/// Authenticate with an artifact provider, such as GitHub or GitLab.
+///
+/// Credentials for S3 buckets are given as a token in
+/// the format `ACCESS_KEY_ID:SECRET_ACCESS_KEY`.
 #[derive(Debug, Parser)]
 pub struct AuthenticateSubcommand {
     /// The artifact / tool provider to authenticate with.
     pub provider: ArtifactProvider,
+    /// The GitHub Enterprise Server host to authenticate with, such as `github.mycorp.com`.
+    /// Tokens for a host are only used for tools from that host.
+    #[clap(long)]
+    pub host: Option<String>,
     /// The token to use for authentication.
     /// Can be omitted when removing a token.
     #[clap(long)]
@@ -32,6 +47,32 @@ pub struct AuthenticateSubcommand {
 
 impl AuthenticateSubcommand {
     pub async fn run(self, home: &Home) -> Result<()> {
+        if matches!(
+            self.provider,
+            ArtifactProvider::Url
+                | ArtifactProvider::Registry
+                | ArtifactProvider::Custom
+                | ArtifactProvider::Crates
+                | ArtifactProvider::Npm
+                | ArtifactProvider::Git
+        ) {
+            bail!(
+                "Tools from {UNAUTHENTICATED_PROVIDERS} are never authenticated by Rokit.\
+                \nOnly GitHub, GitLab, and S3 credentials can be added."
+            );
+        }
+
+        let host = self.host.as_deref().map(str::trim);
+        if let Some(host) = host {
+            if self.provider != ArtifactProvider::GitHub {
+                bail!("Custom hosts are only supported for GitHub Enterprise Server.");
+            }
+            // Make sure the host is valid before saving anything for it
+            GithubProvider::new()?
+                .with_host(host)
+                .context("Invalid GitHub Enterprise Server host")?;
+        }
+
         let pt = CliProgressTracker::new_with_message(
             "Authenticating",
             if self.token.is_some() { 4 } else { 3 },
@@ -42,23 +83,33 @@ impl AuthenticateSubcommand {
             .context("Failed to load or create auth manifest")?;
         pt.task_completed();
 
-        let styled_provider = style(self.provider.display_name())
-            .bold()
-            .white()
-            .to_string();
+        let (provider_name, host_args) = match host {
+            Some(host) => (
+                format!("{} ({host})", self.provider.display_name()),
+                format!(" --host {host}"),
+            ),
+            None => (self.provider.display_name().to_string(), String::new()),
+        };
+        let styled_provider = style(provider_name).bold().white().to_string();
         let styled_add_command = style(format!(
-            "rokit authenticate {} --token YOUR_TOKEN_HERE",
+            "rokit authenticate {}{host_args} --token YOUR_TOKEN_HERE",
+            self.provider
+        ))
+        .bold()
+        .green()
+        .to_string();
+        let styled_remove_command = style(format!(
+            "rokit authenticate {}{host_args} --remove",
             self.provider
         ))
         .bold()
         .green()
         .to_string();
-        let styled_remove_command = style(format!("rokit authenticate {} --remove", self.provider))
-            .bold()
-            .green()
-            .to_string();
 
-        let exists = auth.has_token(self.provider);
+        let exists = match host {
+            Some(host) => auth.get_host_token(host).is_some(),
+            None => auth.has_token(self.provider),
+        };
         if self.remove {
             if !exists {
                 bail!(
@@ -74,16 +125,30 @@ impl AuthenticateSubcommand {
         }
 
         if self.remove {
-            let was_removed = auth.unset_token(self.provider);
+            let was_removed = match host {
+                Some(host) => auth.unset_host_token(host),
+                None => auth.unset_token(self.provider),
+            };
             assert!(was_removed, "token was not removed");
         } else if let Some(token) = self.token {
             let token = token.trim().to_string();
 
             pt.update_message("Verifying");
-            verify_token(self.provider, &token, self.skip_parse, self.skip_verify).await?;
+            verify_token(
+                home,
+                self.provider,
+                host,
+                &token,
+                self.skip_parse,
+                self.skip_verify,
+            )
+            .await?;
             pt.task_completed();
 
-            let had_token = auth.set_token(self.provider, token);
+            let had_token = match host {
+                Some(host) => auth.set_host_token(host, token),
+                None => auth.set_token(self.provider, token),
+            };
             assert!(!had_token, "token was overwritten");
         } else {
             bail!(
@@ -114,7 +179,9 @@ impl AuthenticateSubcommand {
 }
 
 async fn verify_token(
+    home: &Home,
     provider: ArtifactProvider,
+    host: Option<&str>,
     token: &str,
     skip_parse: bool,
     skip_verify: bool,
@@ -125,6 +192,16 @@ async fn verify_token(
             ArtifactProvider::GitHub => {
                 is_gh_classic_token(token) || is_gh_fine_grained_token(token)
             }
+            ArtifactProvider::GitLab => is_gl_personal_token(token),
+            ArtifactProvider::S3 => S3Credentials::from_token(token).is_some(),
+            ArtifactProvider::Url
+            | ArtifactProvider::Registry
+            | ArtifactProvider::Custom
+            | ArtifactProvider::Crates
+            | ArtifactProvider::Npm
+            | ArtifactProvider::Git => {
+                unreachable!("{UNAUTHENTICATED_PROVIDERS} are never authenticated")
+            }
         };
 
         if !validated {
@@ -134,6 +211,18 @@ async fn verify_token(
                     format!("{bullet} Starting with 'gh' followed by a lowercase letter and an underscore"),
                     format!("{bullet} Starting with 'github_pat_'"),
                 ],
+                ArtifactProvider::GitLab => vec![format!("{bullet} Starting with 'glpat-'")],
+                ArtifactProvider::S3 => vec![format!(
+                    "{bullet} An access key id and a secret access key, separated by a colon"
+                )],
+                ArtifactProvider::Url
+                | ArtifactProvider::Registry
+                | ArtifactProvider::Custom
+                | ArtifactProvider::Crates
+                | ArtifactProvider::Npm
+                | ArtifactProvider::Git => {
+                    unreachable!("{UNAUTHENTICATED_PROVIDERS} are never authenticated")
+                }
             };
 
             let styled_flag = style("--skip-parse").bold().green();
@@ -163,10 +252,49 @@ async fn verify_token(
     if !skip_verify {
         let verified = match provider {
             ArtifactProvider::GitHub => {
-                let client = GithubProvider::new_authenticated(token)?;
+                let mut client = GithubProvider::new_authenticated(token)?;
+                if let Some(host) = host {
+                    client = client.with_host(host)?;
+                }
                 let verify_res = client.verify_authentication().await;
                 verify_res.context("GitHub API returned an error during token verification")?
             }
+            ArtifactProvider::GitLab => {
+                let client = GitlabProvider::new_authenticated(token)?;
+                let verify_res = client.verify_authentication().await;
+                verify_res.context("GitLab API returned an error during token verification")?
+            }
+            ArtifactProvider::S3 => {
+                let policy = PolicyManifest::load_or_create(home.path())
+                    .await
+                    .context("Failed to load policy manifest")?;
+                let Some(bucket_url) = policy.s3_bucket_url() else {
+                    bail!(
+                        "No S3 bucket is configured to verify the credentials with.\
+                        \nRun `{}` first, or skip verification using the `{}` flag.",
+                        style("rokit config set s3_bucket YOUR_BUCKET_URL")
+                            .bold()
+                            .green(),
+                        style("--skip-verify").bold().green(),
+                    );
+                };
+                let region = policy
+                    .s3_region()
+                    .unwrap_or_else(|| DEFAULT_S3_REGION.to_string());
+                let credentials = S3Credentials::from_token(token)
+                    .context("S3 credentials must be given as ACCESS_KEY_ID:SECRET_ACCESS_KEY")?;
+                let client = S3Provider::new(&bucket_url, &region)?.with_credentials(credentials);
+                let verify_res = client.verify_authentication().await;
+                verify_res.context("S3 bucket returned an error during credential verification")?
+            }
+            ArtifactProvider::Url
+            | ArtifactProvider::Registry
+            | ArtifactProvider::Custom
+            | ArtifactProvider::Crates
+            | ArtifactProvider::Npm
+            | ArtifactProvider::Git => {
+                unreachable!("{UNAUTHENTICATED_PROVIDERS} are never authenticated")
+            }
         };
 
         if !verified {
@@ -202,3 +330,7 @@ fn is_gh_classic_token(token: &str) -> bool {
 fn is_gh_fine_grained_token(token: &str) -> bool {
     token.starts_with("github_pat_")
 }
+
+fn is_gl_personal_token(token: &str) -> bool {
+    token.starts_with("glpat-")
+}
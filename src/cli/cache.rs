@@ -0,0 +1,84 @@
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use console::style;
+
+use rokit::storage::Home;
+
+use crate::util::format_byte_size;
+
+/// Manages the cache of downloaded artifacts, which is used to
+/// install the same tool versions again without downloading them.
+#[derive(Debug, Parser)]
+pub struct CacheSubcommand {
+    #[clap(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommand {
+    Info(CacheInfoSubcommand),
+    Clear(CacheClearSubcommand),
+}
+
+impl CacheSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        match self.command {
+            CacheCommand::Info(cmd) => cmd.run(home).await,
+            CacheCommand::Clear(cmd) => cmd.run(home).await,
+        }
+    }
+}
+
+/// Shows where the artifact cache is stored, and how much space it uses.
+#[derive(Debug, Parser)]
+pub struct CacheInfoSubcommand {}
+
+impl CacheInfoSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let cache = home.shared_cache();
+        let usage = cache
+            .usage()
+            .await
+            .context("Failed to read the artifact cache")?;
+
+        let bullet = style("•").dim();
+        println!("{bullet} Path: {}", style(cache.path().display()).cyan());
+        println!("{bullet} Artifacts: {}", style(usage.artifacts).bold());
+        println!(
+            "{bullet} Size: {}",
+            style(format_byte_size(usage.size)).bold()
+        );
+
+        Ok(())
+    }
+}
+
+/// Removes all artifacts from the cache.
+///
+/// Installed tools are not affected, but tools that are
+/// installed again later will need to be downloaded again.
+#[derive(Debug, Parser)]
+pub struct CacheClearSubcommand {}
+
+impl CacheClearSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let removed = home
+            .shared_cache()
+            .clear()
+            .await
+            .context("Failed to clear the artifact cache")?;
+
+        if removed.artifacts == 0 {
+            println!("The artifact cache is already empty.");
+        } else {
+            println!(
+                "Removed {} cached artifact{}, freeing {}",
+                style(removed.artifacts).bold().magenta(),
+                if removed.artifacts == 1 { "" } else { "s" },
+                style(format_byte_size(removed.size)).bold(),
+            );
+        }
+
+        Ok(())
+    }
+}
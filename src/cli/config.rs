@@ -0,0 +1,252 @@
+use std::{env::var, fmt};
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use console::style;
+
+use rokit::{
+    manifests::{PolicyManifest, POLICY_MANIFEST_FILE_NAME, POLICY_SETTINGS},
+    storage::{Home, RETRIES_ENV_VAR, RETRY_BACKOFF_ENV_VAR, TEMP_DIR_ENV_VAR, TIMEOUT_ENV_VAR},
+};
+
+use crate::util::HEADLESS_TRUST_ENV_VAR;
+
+const PROFILE_ENV_VAR: &str = "ROKIT_PROFILE";
+const ROOT_ENV_VAR: &str = "ROKIT_ROOT";
+
+/// Shows or changes the configuration of Rokit.
+///
+/// Shows where each value comes from - a default, the user-level
+/// policy manifest, an environment variable, or a command line flag.
+#[derive(Debug, Parser)]
+pub struct ConfigSubcommand {
+    #[clap(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    List(ConfigListSubcommand),
+    Get(ConfigGetSubcommand),
+    Set(ConfigSetSubcommand),
+}
+
+impl ConfigSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        match self.command {
+            ConfigCommand::List(cmd) => cmd.run(home).await,
+            ConfigCommand::Get(cmd) => cmd.run(home).await,
+            ConfigCommand::Set(cmd) => cmd.run(home).await,
+        }
+    }
+}
+
+/// Lists the effective configuration, and the source of each value.
+#[derive(Debug, Parser)]
+pub struct ConfigListSubcommand {}
+
+impl ConfigListSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let entries = load_entries(home).await?;
+
+        let longest_key_len = entries.iter().map(|e| e.key.len()).max().unwrap_or(0);
+        let longest_value_len = entries
+            .iter()
+            .map(|e| e.value.as_deref().map_or(0, str::len))
+            .max()
+            .unwrap_or(0);
+
+        let bullet = style("•").dim();
+        for entry in entries {
+            let value = entry.value.as_deref().unwrap_or_default();
+            println!(
+                "{bullet} {}{} {}{} {}",
+                style(entry.key).bold(),
+                " ".repeat(longest_key_len - entry.key.len()),
+                value,
+                " ".repeat(longest_value_len - value.len()),
+                style(format!("({})", entry.source)).dim(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints the effective value of a single configuration key.
+#[derive(Debug, Parser)]
+pub struct ConfigGetSubcommand {
+    /// The configuration key to get, such as `headless_trust`.
+    pub key: String,
+    /// Also print where the value comes from.
+    #[clap(long)]
+    pub show_source: bool,
+}
+
+impl ConfigGetSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let entries = load_entries(home).await?;
+        let Some(entry) = entries.into_iter().find(|e| e.key == self.key) else {
+            bail!(
+                "Unknown configuration key '{}'.\
+                \nRun `{}` to see all keys.",
+                self.key,
+                style("rokit config list").bold().green(),
+            );
+        };
+        let Some(value) = entry.value else {
+            bail!("Configuration key '{}' is not set.", self.key);
+        };
+
+        if self.show_source {
+            println!("{value} {}", style(format!("({})", entry.source)).dim());
+        } else {
+            println!("{value}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Sets a user-level configuration key, in the policy manifest.
+///
+/// Values are checked before being saved, and comments
+/// and formatting in the policy manifest are kept.
+#[derive(Debug, Parser)]
+pub struct ConfigSetSubcommand {
+    /// The configuration key to set, such as `headless_trust`.
+    pub key: String,
+    /// The new value for the key.
+    pub value: String,
+}
+
+impl ConfigSetSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        if matches!(self.key.as_str(), "profile" | "root") {
+            bail!(
+                "Configuration key '{}' can not be set in the policy manifest.\
+                \nUse the `--profile` flag or the {PROFILE_ENV_VAR} and \
+                {ROOT_ENV_VAR} environment variables instead.",
+                self.key,
+            );
+        }
+
+        let mut policy = PolicyManifest::load_or_create(home.path())
+            .await
+            .context("Failed to load policy manifest")?;
+        policy
+            .set_setting(&self.key, &self.value)
+            .map_err(anyhow::Error::msg)
+            .with_context(|| format!("Failed to set configuration key '{}'", self.key))?;
+        policy.save(home.path()).await?;
+
+        let value = policy.get_setting(&self.key).unwrap_or(self.value);
+        println!(
+            "Set {} to {} in {}",
+            style(&self.key).bold(),
+            style(value).bold().cyan(),
+            POLICY_MANIFEST_FILE_NAME,
+        );
+
+        if self.key == "headless_trust" && env_value(HEADLESS_TRUST_ENV_VAR).is_some() {
+            println!(
+                "{}: The {HEADLESS_TRUST_ENV_VAR} environment variable is set, \
+                and takes precedence over this value.",
+                style("Note").bold().yellow(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigSource {
+    Default,
+    User,
+    Env(&'static str),
+    Flag,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => "default".fmt(f),
+            Self::User => write!(f, "user - {POLICY_MANIFEST_FILE_NAME}"),
+            Self::Env(name) => write!(f, "env - {name}"),
+            Self::Flag => "flag - --profile".fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ConfigEntry {
+    key: &'static str,
+    value: Option<String>,
+    source: ConfigSource,
+}
+
+async fn load_entries(home: &Home) -> Result<Vec<ConfigEntry>> {
+    let policy = PolicyManifest::load_or_create(home.path())
+        .await
+        .context("Failed to load policy manifest")?;
+
+    let mut entries = vec![profile_entry(home), root_entry(home)];
+    for key in POLICY_SETTINGS {
+        let env_var = match key {
+            "headless_trust" => Some(HEADLESS_TRUST_ENV_VAR),
+            "timeout_secs" => Some(TIMEOUT_ENV_VAR),
+            "retries" => Some(RETRIES_ENV_VAR),
+            "retry_backoff_ms" => Some(RETRY_BACKOFF_ENV_VAR),
+            "temp_dir" => Some(TEMP_DIR_ENV_VAR),
+            _ => None,
+        };
+        let env_override = env_var.and_then(|name| env_value(name).map(|v| (v, name)));
+        let (value, source) = if let Some((value, name)) = env_override {
+            (Some(value), ConfigSource::Env(name))
+        } else if let Some(value) = policy.get_setting(key) {
+            (Some(value), ConfigSource::User)
+        } else {
+            (PolicyManifest::default_setting(key), ConfigSource::Default)
+        };
+        entries.push(ConfigEntry { key, value, source });
+    }
+
+    Ok(entries)
+}
+
+fn profile_entry(home: &Home) -> ConfigEntry {
+    // NOTE: The --profile flag overrides the environment variable,
+    // so any profile that does not match the variable came from it
+    let env_profile = env_value(PROFILE_ENV_VAR).filter(|p| p != "default");
+    let source = match (home.profile(), env_profile.as_deref()) {
+        (Some(profile), Some(env)) if profile == env => ConfigSource::Env(PROFILE_ENV_VAR),
+        (Some(_), _) | (None, Some(_)) => ConfigSource::Flag,
+        (None, None) => ConfigSource::Default,
+    };
+    ConfigEntry {
+        key: "profile",
+        value: Some(home.profile().unwrap_or("default").to_string()),
+        source,
+    }
+}
+
+fn root_entry(home: &Home) -> ConfigEntry {
+    let source = if var(ROOT_ENV_VAR).is_ok() {
+        ConfigSource::Env(ROOT_ENV_VAR)
+    } else {
+        ConfigSource::Default
+    };
+    ConfigEntry {
+        key: "root",
+        value: Some(home.root_path().display().to_string()),
+        source,
+    }
+}
+
+fn env_value(name: &str) -> Option<String> {
+    var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
@@ -0,0 +1,224 @@
+use std::{collections::HashMap, fmt};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use console::style;
+use semver::Version;
+use serde::Serialize;
+use tokio::fs::try_exists;
+
+use rokit::{
+    discovery::discover_all_manifests,
+    manifests::InstallTarget,
+    storage::{Home, LinkStatus},
+    tool::{ToolAlias, ToolSpec},
+};
+
+use super::links::{link_alias, link_name};
+
+/// Compares the tools pinned in manifests with what is actually installed and linked.
+///
+/// Manifests pin exact versions of every tool, and act as the lockfile for a
+/// project - this reports any drift from them, such as tools that are not
+/// installed, tools installed at other versions than the pinned ones, and links
+/// that do not belong to any tool in the manifests for the current directory.
+///
+/// Exits with an error if any drift was found, which makes it useful in CI.
+#[derive(Debug, Parser)]
+pub struct DiffSubcommand {
+    /// Output the differences as JSON instead of human-readable text.
+    #[clap(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum Drift {
+    /// The tool is pinned in a manifest, but no version of it is installed.
+    MissingInstall { alias: ToolAlias, spec: ToolSpec },
+    /// The tool is pinned in a manifest, but only other versions of it are installed.
+    VersionMismatch {
+        alias: ToolAlias,
+        spec: ToolSpec,
+        installed: Vec<Version>,
+    },
+    /// The tool is installed, but there is no link for its alias.
+    MissingLink { alias: ToolAlias, spec: ToolSpec },
+    /// The link was written by another version of Rokit, or is corrupted.
+    OutdatedLink { alias: ToolAlias },
+    /// The link does not belong to any tool in the manifests.
+    ExtraLink { name: String },
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingInstall { alias, spec } => {
+                let alias = style(alias.to_string()).bold().cyan();
+                write!(f, "{alias} {spec} is not installed")
+            }
+            Self::VersionMismatch {
+                alias,
+                spec,
+                installed,
+            } => {
+                let alias = style(alias.to_string()).bold().cyan();
+                let installed = installed
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "{alias} {spec} is not installed, but {} is",
+                    style(installed).yellow()
+                )
+            }
+            Self::MissingLink { alias, spec } => {
+                let alias = style(alias.to_string()).bold().cyan();
+                write!(f, "{alias} {spec} is installed, but not linked")
+            }
+            Self::OutdatedLink { alias } => {
+                let alias = style(alias.to_string()).bold().cyan();
+                write!(f, "{alias} is linked, but the link is outdated or invalid")
+            }
+            Self::ExtraLink { name } => {
+                let name = style(name).bold().cyan();
+                write!(f, "{name} is linked, but is not in any manifest")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DiffReport {
+    tools: usize,
+    links: usize,
+    drift: Vec<Drift>,
+}
+
+impl DiffSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let tool_storage = home.tool_storage();
+        let tool_cache = home.tool_cache();
+        let taps = home.taps().await.unwrap_or_default();
+
+        // 1. Gather the pinned tool for every alias, using all known manifests
+        let manifests = discover_all_manifests(&taps, false, false).await;
+        let mut pinned_tools = HashMap::new();
+        for manifest in manifests.into_iter().rev() {
+            // NOTE: Manifests closer to the current directory take precedence
+            for (alias, spec) in manifest.tools {
+                let options = manifest.options.get(&alias).cloned().unwrap_or_default();
+                pinned_tools.insert(alias, (spec, options.installs, options.entrypoint));
+            }
+        }
+        let mut pinned_tools = pinned_tools.into_iter().collect::<Vec<_>>();
+        pinned_tools.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        // 2. Gather all links in the binary directory
+        let link_paths = tool_storage.all_link_paths().await?;
+        let links = link_paths
+            .iter()
+            .filter_map(|path| Some((link_alias(path)?, path.clone())))
+            .collect::<HashMap<_, _>>();
+
+        // 3. Compare pinned tools with installed tools and links
+        let mut drift = Vec::new();
+        for (alias, (spec, installs, entrypoint)) in &pinned_tools {
+            // NOTE: Plugins are installed into the Roblox plugins directory,
+            // and never have a link in the binary directory
+            if *installs == Some(InstallTarget::Plugin) {
+                if !tool_cache.is_plugin_installed(spec) {
+                    drift.push(Drift::MissingInstall {
+                        alias: alias.clone(),
+                        spec: spec.clone(),
+                    });
+                }
+                continue;
+            }
+
+            let is_installed = tool_cache.is_installed(spec)
+                && try_exists(tool_storage.entrypoint_path(spec, entrypoint.as_deref()))
+                    .await
+                    .unwrap_or_default();
+            if !is_installed {
+                let installed = tool_cache.all_installed_versions_for_id(spec.id());
+                drift.push(if installed.is_empty() {
+                    Drift::MissingInstall {
+                        alias: alias.clone(),
+                        spec: spec.clone(),
+                    }
+                } else {
+                    Drift::VersionMismatch {
+                        alias: alias.clone(),
+                        spec: spec.clone(),
+                        installed,
+                    }
+                });
+                continue;
+            }
+
+            match links.get(alias) {
+                None => drift.push(Drift::MissingLink {
+                    alias: alias.clone(),
+                    spec: spec.clone(),
+                }),
+                Some(path) => {
+                    if !matches!(tool_storage.check_link(path).await?, LinkStatus::Current) {
+                        drift.push(Drift::OutdatedLink {
+                            alias: alias.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        let mut extra_link_names = link_paths
+            .iter()
+            .filter(|path| {
+                !link_alias(path)
+                    .is_some_and(|alias| pinned_tools.iter().any(|(pinned, _)| pinned == &alias))
+            })
+            .map(|path| link_name(path))
+            .collect::<Vec<_>>();
+        extra_link_names.sort();
+        drift.extend(
+            extra_link_names
+                .into_iter()
+                .map(|name| Drift::ExtraLink { name }),
+        );
+
+        // 4. Report the drift, if any
+        let report = DiffReport {
+            tools: pinned_tools.len(),
+            links: link_paths.len(),
+            drift,
+        };
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else if report.drift.is_empty() {
+            println!(
+                "✓ All {} tools are installed and linked, with no drift.",
+                report.tools
+            );
+        } else {
+            let bullet = style("•").dim();
+            for drift in &report.drift {
+                println!("{bullet} {drift}");
+            }
+            println!();
+        }
+
+        if !report.drift.is_empty() {
+            let n = report.drift.len();
+            let s = if n == 1 { "" } else { "s" };
+            bail!(
+                "Found {n} difference{s} between manifests and installed tools.\
+                \nRun `{}` to install and link the pinned tools.",
+                style("rokit install").bold().green(),
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use std::{env::set_current_dir, ffi::OsString, path::PathBuf, process::exit};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use console::style;
+use tokio::fs::{canonicalize, metadata};
+
+use rokit::{
+    discovery::resolve_alias_for_dir,
+    storage::Home,
+    system::{current_dir, run_interruptible_with_env},
+    tool::ToolAlias,
+};
+
+/// Runs a tool from a project manifest, regardless of the current directory.
+///
+/// The tool is resolved the same way as when running it directly, but starting
+/// from the given manifest directory, and then runs inside of that directory.
+/// This is mostly useful for CI scripts that work with more than one project.
+#[derive(Debug, Parser)]
+pub struct ExecSubcommand {
+    /// The directory containing the project manifest, or the path to the
+    /// manifest file itself. Defaults to the current directory.
+    #[clap(long)]
+    pub manifest_dir: Option<PathBuf>,
+    /// The alias of the tool to run.
+    pub alias: ToolAlias,
+    /// The arguments to pass to the tool, given after `--`.
+    #[clap(last = true)]
+    pub args: Vec<String>,
+}
+
+impl ExecSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let dir = match self.manifest_dir {
+            Some(path) => resolve_manifest_dir(path).await?,
+            None => current_dir().await,
+        };
+
+        let Some(resolved) = resolve_alias_for_dir(home, &dir, &self.alias).await else {
+            bail!(
+                "Failed to find tool '{}' in any project manifest for '{}'.\
+                \nAdd the tool to the project using 'rokit add' before running it.",
+                self.alias,
+                dir.display()
+            );
+        };
+        let Some(spec) = resolved.spec else {
+            bail!(
+                "Tool '{}' is not managed by Rokit for '{}'.\
+                \nAdd the tool to the project using 'rokit add' before running it.",
+                self.alias,
+                dir.display()
+            );
+        };
+        if !resolved.is_installed {
+            bail!(
+                "Tool '{}' is not installed.\
+                \nRun `{}` in '{}' to install it.",
+                style(&spec).bold().magenta(),
+                style("rokit install").bold().green(),
+                dir.display()
+            );
+        }
+
+        // NOTE: Usage statistics are only informational, and
+        // must never prevent the tool itself from running
+        if let Err(e) = home.record_tool_usage(&spec).await {
+            tracing::debug!(%spec, error = %e, "failed to record tool usage");
+        }
+
+        let tool_dir = home.tool_storage().tool_dir(&spec);
+        let envs: Vec<(&str, OsString)> = vec![
+            ("ROKIT_TOOL_DIR", tool_dir.into()),
+            ("ROKIT_TOOL_VERSION", spec.version().to_string().into()),
+        ];
+
+        // Run the tool in the context of the project, same as
+        // if it had been run from within the manifest directory
+        set_current_dir(&dir)
+            .with_context(|| format!("Failed to change directory to '{}'", dir.display()))?;
+        let code = run_interruptible_with_env(&resolved.path, &self.args, envs)
+            .await
+            .with_context(|| format!("Failed to run '{}'", self.alias))?;
+        if code != 0 {
+            exit(code);
+        }
+
+        Ok(())
+    }
+}
+
+async fn resolve_manifest_dir(path: PathBuf) -> Result<PathBuf> {
+    let path = canonicalize(&path)
+        .await
+        .with_context(|| format!("Failed to find manifest directory '{}'", path.display()))?;
+    let meta = metadata(&path).await?;
+    if meta.is_file() {
+        let parent = path
+            .parent()
+            .context("Manifest file has no parent directory")?;
+        Ok(parent.to_path_buf())
+    } else {
+        Ok(path)
+    }
+}
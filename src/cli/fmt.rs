@@ -0,0 +1,73 @@
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use console::style;
+
+use rokit::{discovery::discover_all_manifests, manifests::RokitManifest, storage::Home};
+
+use crate::util::CliProgressTracker;
+
+/// Formats the nearest Rokit manifest, sorting tools and normalizing their specifications.
+#[derive(Debug, Parser)]
+pub struct FmtSubcommand {
+    /// Format the global manifest instead of the nearest manifest file.
+    #[clap(long)]
+    pub global: bool,
+    /// Check if the manifest is formatted without modifying it.
+    /// Exits with an error if the manifest is not formatted.
+    #[clap(long)]
+    pub check: bool,
+}
+
+impl FmtSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let manifest_path = if self.global {
+            home.path().to_path_buf()
+        } else {
+            let taps = home.taps().await?;
+            let non_global_manifests = discover_all_manifests(&taps, true, true).await;
+            non_global_manifests
+                .first()
+                .map(|m| m.path.parent().unwrap().to_path_buf())
+                .context(
+                    "No manifest was found for the current directory.\
+                    \nRun `rokit init` in your project root to create one.",
+                )?
+        };
+
+        let mut manifest = RokitManifest::load(&manifest_path).await?;
+        let changed = manifest.normalize();
+
+        if self.check {
+            if changed {
+                bail!(
+                    "The manifest at '{}' is not formatted.\
+                    \nRun `{}` to format it.",
+                    manifest_path.display(),
+                    style("rokit fmt").bold().green(),
+                );
+            }
+            println!("The manifest is already formatted.");
+            return Ok(());
+        }
+
+        // NOTE: We use a progress bar only to show the final message to the
+        // user below, to maintain consistent formatting with other commands.
+        let pt = CliProgressTracker::new_with_message("Formatting", 1);
+
+        if changed {
+            manifest.save(&manifest_path).await?;
+        }
+
+        pt.finish_with_message(format!(
+            "{} {}",
+            if changed {
+                "Formatted the manifest successfully!"
+            } else {
+                "The manifest is already formatted!"
+            },
+            pt.formatted_elapsed(),
+        ));
+
+        Ok(())
+    }
+}
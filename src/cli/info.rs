@@ -0,0 +1,140 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+use console::style;
+
+use rokit::{
+    discovery::discover_all_manifests,
+    manifests::ToolOptions,
+    sources::ReleaseNotes,
+    storage::Home,
+    tool::{ReleaseChannel, ToolId},
+    ResolveConstraints,
+};
+
+use crate::util::{format_release_summaries, print_release_changelogs, ToolAliasOrIdOrSpec};
+
+/// Shows information about a tool, and what changed since the installed version.
+///
+/// For tools that are pinned in a manifest, or installed, this lists the
+/// release notes for every release between the current version and the
+/// latest version, to help decide whether to run `rokit update`.
+#[derive(Debug, Parser)]
+pub struct InfoSubcommand {
+    /// The tool to show information about - can be an alias, id, or specification.
+    pub tool: ToolAliasOrIdOrSpec,
+    /// Display the full release notes, instead of
+    /// only a short summary of each release.
+    #[clap(long)]
+    pub changelog: bool,
+}
+
+impl InfoSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let source = home.artifact_source().await?;
+        let tool_cache = home.tool_cache();
+        let taps = home.taps().await.unwrap_or_default();
+
+        // 1. Find the tool, and the version of it that is currently used
+        let manifests = discover_all_manifests(&taps, false, false).await;
+        let pinned = manifests.iter().find_map(|manifest| {
+            // NOTE: Manifests closer to the current directory come first
+            manifest.tools.iter().find_map(|(alias, spec)| {
+                let is_match = match &self.tool {
+                    ToolAliasOrIdOrSpec::Alias(a) => a == alias,
+                    ToolAliasOrIdOrSpec::Id(id) => id == spec.id(),
+                    ToolAliasOrIdOrSpec::Spec(s) => s.id() == spec.id(),
+                };
+                is_match.then(|| {
+                    let options = manifest.options.get(alias).cloned().unwrap_or_default();
+                    (spec.clone(), options)
+                })
+            })
+        });
+        let (id, current, options): (ToolId, _, _) = match (&self.tool, pinned) {
+            (ToolAliasOrIdOrSpec::Spec(spec), pinned) => (
+                spec.id().clone(),
+                Some(spec.version().clone()),
+                pinned.map(|(_, options)| options).unwrap_or_default(),
+            ),
+            (_, Some((spec, options))) => {
+                (spec.id().clone(), Some(spec.version().clone()), options)
+            }
+            (ToolAliasOrIdOrSpec::Id(id), None) => {
+                let installed = tool_cache.all_installed_versions_for_id(id);
+                (
+                    id.clone(),
+                    installed.into_iter().max(),
+                    ToolOptions::default(),
+                )
+            }
+            (ToolAliasOrIdOrSpec::Alias(alias), None) => {
+                bail!(
+                    "No tool with the alias '{alias}' was found in any manifest.\
+                    \nUse the id of the tool instead, such as `author/{alias}`."
+                );
+            }
+        };
+
+        // 2. Fetch the latest release, the same way that `rokit update` would
+        let channel = options.channel.unwrap_or_else(|| match &current {
+            Some(version) if !version.pre.is_empty() => ReleaseChannel::AnyPrerelease,
+            _ => ReleaseChannel::Stable,
+        });
+        let constraints = ResolveConstraints {
+            version: options.version,
+            channel,
+            resolution: options.resolution.unwrap_or_default(),
+            ..ResolveConstraints::default()
+        };
+        let latest = source
+            .get_latest_release_matching(&id, &constraints)
+            .await?;
+
+        let bold_id = style(id.to_string()).bold().cyan();
+        println!("{bold_id}");
+        match &current {
+            Some(version) => {
+                let installed = if tool_cache.is_installed(&(id.clone(), version.clone()).into()) {
+                    style("installed").green()
+                } else {
+                    style("not installed").yellow()
+                };
+                println!("  Current  {} ({installed})", style(version).bold());
+            }
+            None => println!("  Current  {}", style("not installed").dim()),
+        }
+        println!("  Latest   {}", style(&latest.version).bold());
+
+        // 3. Gather the release notes for everything that changed since the
+        // current version - or only for the latest release, if there is none
+        let notes = match &current {
+            Some(version) if *version >= latest.version => {
+                println!("\n{bold_id} is up to date.");
+                return Ok(());
+            }
+            Some(version) => {
+                source
+                    .get_release_notes_between(&id, version, &latest.version)
+                    .await?
+            }
+            None => ReleaseNotes::from_release(&latest).into_iter().collect(),
+        };
+        if notes.is_empty() {
+            println!("\nNo release notes were found for {bold_id}.");
+            return Ok(());
+        }
+
+        // 4. Print the release notes, same as `rokit update` does
+        if self.changelog {
+            print_release_changelogs(&id.to_string(), &notes)?;
+        } else {
+            println!(
+                "\n📋 Release notes:\n\n{}\n\nRun with `{}` to view full release notes.",
+                format_release_summaries(&notes).join("\n"),
+                style("--changelog").bold().green(),
+            );
+        }
+
+        Ok(())
+    }
+}
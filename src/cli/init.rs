@@ -2,6 +2,8 @@ use anyhow::{bail, Context, Result};
 use clap::Parser;
 use console::style;
 
+use semver::Version;
+
 use rokit::{manifests::RokitManifest, storage::Home, system::current_dir};
 
 use crate::util::CliProgressTracker;
@@ -12,6 +14,13 @@ pub struct InitSubcommand {
     /// Overwrite an existing Rokit project in the current directory.
     #[clap(long, hide = true)]
     pub force: bool,
+    /// Pin the project to the current version of Rokit.
+    ///
+    /// Other versions of Rokit will download and run this version
+    /// instead when used in the project, so that the whole team
+    /// gets identical behavior.
+    #[clap(long)]
+    pub pin: bool,
 }
 
 impl InitSubcommand {
@@ -29,9 +38,13 @@ impl InitSubcommand {
             )
         }
 
-        let manifest = RokitManifest::load_or_create(&cwd)
+        let mut manifest = RokitManifest::load_or_create(&cwd)
             .await
             .context("Failed to create new Rokit manifest")?;
+        if self.pin {
+            let version = env!("CARGO_PKG_VERSION").parse::<Version>().unwrap();
+            manifest.set_rokit_version(Some(&version));
+        }
 
         // FUTURE: Maybe ask the user if they want to add some common tools here?
         // We could use `dialoguer` and its multi-select prompt for this - and we
@@ -1,16 +1,41 @@
-use std::collections::BTreeSet;
+use std::{
+    fmt,
+    io::{stderr, IsTerminal},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Result};
 use clap::Parser;
 
 use console::style;
-use futures::{stream::FuturesUnordered, TryStreamExt};
-use rokit::{discovery::discover_all_manifests, storage::Home};
+use dialoguer::theme::ColorfulTheme;
+use futures::future::BoxFuture;
+use rokit::{
+    manifests::{HeadlessTrust, PolicyManifest},
+    prompt::{DownloadPrompt, PromptError, Prompter, TrustPrompt, VersionPrompt},
+    result::RokitError,
+    session::{
+        InstallObserver, InstallPhase, InstallPolicy, InstallRestrictions, Session, STEPS_PER_TOOL,
+    },
+    storage::{offline_from_env, Home},
+    system::current_dir,
+    tool::{ToolId, ToolSpec},
+};
+use semver::Version;
+use tokio::time::Instant;
 
-use crate::util::{find_most_compatible_artifact, prompt_for_trust_specs, CliProgressTracker};
+use crate::util::{
+    format_byte_size, parse_byte_size, print_warnings, resolve_headless_trust, retrust_hint,
+    CliProgressTracker, CliPrompter, InstallReport, InstallReportTool, TrustPromptKind,
+};
 
 /// Adds a new tool using Rokit and installs it.
 #[derive(Debug, Parser)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct InstallSubcommand {
     /// Skip checking if tools have been trusted before.
     /// It is recommended to only use this on CI machines.
@@ -19,119 +44,310 @@ pub struct InstallSubcommand {
     /// Force install all tools, even if they are already installed.
     #[clap(long)]
     pub force: bool,
+    /// Never access the network, and only install tools from local caches.
+    /// Fails with a list of all tools that are not available locally.
+    /// May also be enabled by setting `ROKIT_OFFLINE=1`.
+    #[clap(long)]
+    pub offline: bool,
+    /// Fail if any tool version has never been installed before,
+    /// meaning its artifact has no recorded hash to verify against.
+    #[clap(long)]
+    pub locked: bool,
+    /// Write a machine-readable report of the installation to the given file.
+    /// Includes tools, versions, sources, hashes, and timings, in JSON format.
+    #[clap(long, value_name = "FILE")]
+    pub report: Option<PathBuf>,
+    /// Display how long each phase of installing each tool took.
+    #[clap(long)]
+    pub timings: bool,
+    /// Fail instead of downloading more than the given size, such as `200MB`.
+    #[clap(long, value_name = "SIZE", value_parser = parse_byte_size)]
+    pub max_download_size: Option<u64>,
 }
 
 impl InstallSubcommand {
     pub async fn run(self, home: &Home) -> Result<()> {
-        let force = self.force;
+        let cwd = current_dir().await;
+        self.run_in_dir(home, &cwd).await
+    }
+
+    /**
+        Installs all tools for the manifests found in the given directory,
+        instead of the current directory - used by `rokit new` for templates.
+    */
+    pub async fn run_in_dir(self, home: &Home, dir: &Path) -> Result<()> {
+        let start = Instant::now();
+
+        let policy_manifest = PolicyManifest::load_or_create(home.path()).await?;
+        let ui = CliInstallUi::new(resolve_headless_trust(&policy_manifest)?);
+        let offline = self.offline || offline_from_env();
+        let source = if offline {
+            home.offline_artifact_source().await?
+        } else {
+            home.artifact_source().await?
+        };
+        let session = Session::new(home, source, &ui).with_observer(&ui);
+        let policy = InstallPolicy {
+            force: self.force,
+            skip_trust_check: self.no_trust_check,
+            restrictions: InstallRestrictions {
+                offline,
+                locked: self.locked,
+            },
+            max_download_size: self.max_download_size,
+            ..InstallPolicy::default()
+        };
+        let summary = session
+            .install_all_in(dir, &policy)
+            .await
+            .map_err(explain_install_error)?;
+
+        // Display a nice message to the user
+        let pt = ui.progress();
+        let s = if summary.tools.len() == 1 { "" } else { "s" };
+        let downloaded = if summary.download_size > 0 {
+            format!(
+                ", up to {} downloaded",
+                format_byte_size(summary.download_size)
+            )
+        } else {
+            String::new()
+        };
+        pt.finish_with_message(format!(
+            "Installed and created link{s} for {} tool{s}{downloaded} {}",
+            style(summary.tools.len()).bold().magenta(),
+            pt.formatted_elapsed(),
+        ));
+        print_warnings(&summary.warnings);
+        if summary.installed_extras {
+            let share_dir = home.tool_storage().share_dir();
+            println!(
+                "Installed man pages and completions into '{}'.\
+                \nAdd '{}' to your {} to read the man pages.",
+                share_dir.display(),
+                share_dir.join("man").display(),
+                style("$MANPATH").bold(),
+            );
+        }
 
-        let source = home.artifact_source().await?;
-        let manifests = discover_all_manifests(false, false).await;
+        // Display timings and write the installation report, if requested
+        let report = InstallReport::new(
+            start.elapsed(),
+            summary
+                .tools
+                .iter()
+                .map(InstallReportTool::from_installed)
+                .collect(),
+        );
+        for tool in &report.tools {
+            tracing::debug!(
+                id = %tool.id,
+                version = %tool.version,
+                resolve = ?tool.timings.resolve,
+                download = ?tool.timings.download,
+                extract = ?tool.timings.extract,
+                link = ?tool.timings.link,
+                "install timings for tool"
+            );
+        }
+        if self.timings {
+            print_timings(&report.tools);
+        }
+        if let Some(report_path) = self.report {
+            report.write(report_path).await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn print_timings(tools: &[InstallReportTool]) {
+    // NOTE: Show the slowest tools first, since those are
+    // usually what someone looking at timings cares about
+    let mut tools = tools.iter().collect::<Vec<_>>();
+    tools.sort_by_key(|tool| std::cmp::Reverse(tool.timings.total()));
+
+    let width = tools
+        .iter()
+        .map(|tool| tool.id.to_string().len() + tool.version.to_string().len() + 1)
+        .max()
+        .unwrap_or_default();
+
+    println!("\n{}", style("Timings").bold());
+    for tool in tools {
+        let timings = tool.timings;
+        let spec = format!("{}@{}", tool.id, tool.version);
+        println!(
+            "  {spec:<width$}  {} {:>8.2?}  {} {:>8.2?}  {} {:>8.2?}  {} {:>8.2?}  {} {:>8.2?}",
+            style("resolve").dim(),
+            timings.resolve,
+            style("download").dim(),
+            timings.download,
+            style("extract").dim(),
+            timings.extract,
+            style("link").dim(),
+            timings.link,
+            style("total").dim(),
+            timings.total(),
+        );
+    }
+}
+
+/**
+    Adds instructions for the user to errors from installing tools, where possible.
+*/
+fn explain_install_error(err: RokitError) -> anyhow::Error {
+    match err {
+        RokitError::ArtifactHashChanged { ref spec, .. } => {
+            let hint = retrust_hint(spec);
+            anyhow::Error::from(err).context(hint)
+        }
+        RokitError::ArtifactChecksumMismatch { .. } => anyhow::Error::from(err).context(
+            "The release may have been changed, or the `sha256` option in the manifest is wrong.\
+            \nCheck the release, and update the checksum in the manifest if it is expected.",
+        ),
+        RokitError::ArtifactDigestMismatch { .. } => anyhow::Error::from(err).context(
+            "The download may have been corrupted, or the release was changed after publishing.\
+            \nTry installing again, and report the problem to the tool authors if it persists.",
+        ),
+        RokitError::DownloadTooLarge { size, max } => anyhow!(
+            "Installing would download {}, which is more than the maximum of {}.\
+            \nIncrease the maximum using `--max-download-size` to install anyway.",
+            format_byte_size(size),
+            format_byte_size(max),
+        ),
+        RokitError::Offline(_) => anyhow::Error::from(err).context(
+            "Run without `--offline` or `ROKIT_OFFLINE` set to download the missing tools.",
+        ),
+        err => err.into(),
+    }
+}
+
+/**
+    Displays the progress of installing tools as a progress bar,
+    and asks the user any questions using terminal prompts.
+*/
+struct CliInstallUi {
+    progress: OnceLock<CliProgressTracker>,
+    single: CliPrompter,
+    many: CliPrompter,
+    many_untrusted: AtomicBool,
+}
 
-        let tool_cache = home.tool_cache();
-        let tool_storage = home.tool_storage();
+impl CliInstallUi {
+    fn new(headless: HeadlessTrust) -> Self {
+        Self {
+            progress: OnceLock::new(),
+            single: CliPrompter::new(TrustPromptKind::Install, headless),
+            many: CliPrompter::new(TrustPromptKind::InstallMany, headless),
+            many_untrusted: AtomicBool::new(false),
+        }
+    }
 
-        // 1. Gather tool specifications from all known manifests
+    fn progress(&self) -> &CliProgressTracker {
+        self.progress_with_tasks(0)
+    }
 
-        let tools = manifests
-            .iter()
-            .flat_map(|manifest| manifest.tools.clone().into_iter())
-            .collect::<Vec<_>>();
+    fn progress_with_tasks(&self, num_tasks: usize) -> &CliProgressTracker {
+        self.progress.get_or_init(|| {
+            CliProgressTracker::new_with_message_and_subtasks(
+                "Resolving",
+                num_tasks,
+                STEPS_PER_TOOL,
+            )
+        })
+    }
 
-        // 2. Check for trust
+    fn prompter(&self) -> &CliPrompter {
+        if self.many_untrusted.load(Ordering::Relaxed) {
+            &self.many
+        } else {
+            &self.single
+        }
+    }
+}
 
-        // NOTE: Deduplicate tool aliases and specs since they may appear in several manifests
-        let tool_aliases = tools
-            .iter()
-            .map(|(alias, _)| alias.clone())
-            .collect::<BTreeSet<_>>();
-        let tool_specs = tools
-            .into_iter()
-            .map(|(_, spec)| spec)
-            .collect::<BTreeSet<_>>();
+impl fmt::Debug for CliInstallUi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CliInstallUi").finish_non_exhaustive()
+    }
+}
 
-        let tool_specs = if self.no_trust_check {
-            tool_specs
+impl InstallObserver for CliInstallUi {
+    fn untrusted_tools(&self, tool_ids: &[ToolId]) {
+        let many = tool_ids.len() > 1;
+        self.many_untrusted.store(many, Ordering::Relaxed);
+        if !stderr().is_terminal() {
+            return;
+        }
+        if many {
+            println!(
+                "Some tools are not yet trusted and need your approval.\
+                \nYou will be asked which of them to trust, and \
+                any tool you do not trust will not be installed."
+            );
         } else {
-            let (trusted_specs, untrusted_specs) = tool_specs
-                .into_iter()
-                .partition(|spec| tool_cache.is_trusted(spec.id()));
-            let newly_trusted_specs = prompt_for_trust_specs(untrusted_specs).await?;
-            for spec in &newly_trusted_specs {
-                let _ = tool_cache.add_trust(spec.id().clone());
+            println!("A tool is not yet trusted and needs your approval.");
+        }
+    }
+
+    fn phase_started(&self, phase: InstallPhase, num_tools: usize) {
+        match phase {
+            InstallPhase::Resolving => {
+                self.progress_with_tasks(num_tools);
             }
-            trusted_specs
-                .iter()
-                .chain(newly_trusted_specs.iter())
-                .cloned()
-                .collect::<BTreeSet<_>>()
-        };
+            InstallPhase::Installing => self.progress().update_message("Installing"),
+            InstallPhase::Linking => self.progress().update_message("Linking"),
+        }
+    }
 
-        // 3. Find artifacts, download and install them
-
-        let pt =
-            CliProgressTracker::new_with_message_and_subtasks("Installing", tool_specs.len(), 5);
-        let installed_specs = tool_specs
-            .into_iter()
-            .map(|tool_spec| async {
-                if tool_cache.is_installed(&tool_spec) && !force {
-                    pt.task_completed();
-                    // HACK: Force the async closure to take ownership
-                    // of tool_spec by returning it from the closure
-                    return anyhow::Ok(tool_spec);
-                }
-
-                let release_artifact = source.get_specific_release(&tool_spec).await?;
-                pt.subtask_completed();
-
-                let artifact =
-                    find_most_compatible_artifact(&release_artifact.artifacts, tool_spec.id())?;
-                pt.subtask_completed();
-
-                let contents = source
-                    .download_artifact_contents(&artifact)
-                    .await
-                    .with_context(|| format!("Failed to download contents for {tool_spec}"))?;
-                pt.subtask_completed();
-
-                let extracted = artifact
-                    .extract_contents(contents)
-                    .await
-                    .with_context(|| format!("Failed to extract contents for {tool_spec}"))?;
-                pt.subtask_completed();
-
-                tool_storage
-                    .replace_tool_contents(&tool_spec, extracted)
-                    .await?;
-                pt.subtask_completed();
-
-                let _ = tool_cache.add_installed(tool_spec.clone());
-                Ok(tool_spec)
-            })
-            .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
-            .await?;
-
-        // 4. Link all of the (possibly new) aliases, we do this even if the
-        // tool is already installed in case the link(s) have been corrupted
-        // and the user tries to re-install tools to fix it.
-
-        pt.update_message("Linking");
-        tool_aliases
-            .iter()
-            .map(|alias| tool_storage.create_tool_link(alias))
-            .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
-            .await?;
-
-        // 5. Finally, display a nice message to the user
-        let s = if installed_specs.len() == 1 { "" } else { "s" };
-        pt.finish_with_message(format!(
-            "Installed and created link{s} for {} tool{s} {}",
-            style(installed_specs.len()).bold().magenta(),
-            pt.formatted_elapsed(),
-        ));
+    fn step_completed(&self, _spec: &ToolSpec) {
+        self.progress().subtask_completed();
+    }
 
-        Ok(())
+    fn tool_completed(&self, _spec: &ToolSpec) {
+        self.progress().task_completed();
+    }
+}
+
+impl Prompter for CliInstallUi {
+    fn confirm_trust(&self, prompt: TrustPrompt) -> BoxFuture<'_, Result<bool, PromptError>> {
+        self.prompter().confirm_trust(prompt)
+    }
+
+    fn confirm_trust_all(
+        &self,
+        prompts: Vec<TrustPrompt>,
+    ) -> BoxFuture<'_, Result<Vec<bool>, PromptError>> {
+        self.prompter().confirm_trust_all(prompts)
+    }
+
+    fn pick_version(
+        &self,
+        prompt: VersionPrompt,
+    ) -> BoxFuture<'_, Result<Option<Version>, PromptError>> {
+        self.prompter().pick_version(prompt)
+    }
+
+    fn wants_provenance(&self) -> bool {
+        self.prompter().wants_provenance()
+    }
+
+    fn confirm_download(&self, prompt: DownloadPrompt) -> BoxFuture<'_, Result<bool, PromptError>> {
+        Box::pin(async move {
+            if !stderr().is_terminal() {
+                return Ok(true);
+            }
+            let confirmed = self.progress().suspend(|| {
+                dialoguer::Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt(format!(
+                        "Installing will download {}. Continue?",
+                        format_byte_size(prompt.size)
+                    ))
+                    .default(true)
+                    .interact_opt()
+            })?;
+            Ok(confirmed == Some(true))
+        })
     }
 }
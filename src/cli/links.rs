@@ -0,0 +1,220 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use console::style;
+use tokio::fs::try_exists;
+
+use rokit::{
+    discovery::discover_all_manifests,
+    storage::{Home, LinkStatus},
+    tool::{ToolAlias, ToolSpec},
+};
+
+use crate::util::CliProgressTracker;
+
+/// Inspects or manages tool links in the Rokit binary directory.
+#[derive(Debug, Parser)]
+pub struct LinksSubcommand {
+    #[clap(subcommand)]
+    pub command: LinksCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LinksCommand {
+    Check(LinksCheckSubcommand),
+}
+
+impl LinksSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        match self.command {
+            LinksCommand::Check(cmd) => cmd.run(home).await,
+        }
+    }
+}
+
+/// Checks that every link in the binary directory is a valid link
+/// for the current version of Rokit, and points to an installed tool.
+///
+/// This is a shallow check that only reads links and tool paths, and
+/// does not verify the contents of any installed tool binaries.
+#[derive(Debug, Parser)]
+pub struct LinksCheckSubcommand {
+    /// Repair outdated or invalid links, and remove stale links.
+    #[clap(long)]
+    pub fix: bool,
+}
+
+#[derive(Debug, Clone)]
+enum LinkProblem {
+    /// The link was written by another version of Rokit, or is corrupted.
+    Outdated(Option<String>),
+    /// The link points to a tool whose binary is not installed.
+    MissingTool(ToolSpec),
+    /// The link does not belong to any known or installed tool.
+    Stale,
+}
+
+impl LinksCheckSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let pt = CliProgressTracker::new_with_message("Checking", 2);
+
+        let tool_storage = home.tool_storage();
+        let tool_cache = home.tool_cache();
+        let taps = home.taps().await.unwrap_or_default();
+
+        // 1. Gather the expected tool for every alias, using all known manifests
+        let expected_tools = discover_all_manifests(&taps, false, false)
+            .await
+            .into_iter()
+            .rev() // Manifests closer to the current directory take precedence
+            .flat_map(|manifest| {
+                manifest.tools.into_iter().map(move |(alias, spec)| {
+                    let entrypoint = manifest
+                        .options
+                        .get(&alias)
+                        .and_then(|options| options.entrypoint.clone());
+                    (alias, (spec, entrypoint))
+                })
+            })
+            .collect::<HashMap<_, _>>();
+        let installed_tools = tool_cache.all_installed();
+        pt.task_completed();
+
+        // 2. Check every link in the binary directory
+        let mut problems = Vec::new();
+        let link_paths = tool_storage.all_link_paths().await?;
+        for path in &link_paths {
+            let Some(alias) = link_alias(path) else {
+                problems.push((path.clone(), LinkProblem::Stale));
+                continue;
+            };
+
+            if let Some((spec, entrypoint)) = expected_tools.get(&alias) {
+                let tool_path = tool_storage.entrypoint_path(spec, entrypoint.as_deref());
+                if !try_exists(&tool_path).await.unwrap_or_default() {
+                    problems.push((path.clone(), LinkProblem::MissingTool(spec.clone())));
+                    continue;
+                }
+            } else {
+                // NOTE: Links are shared between all projects, so a link that is not
+                // in any manifest we can see may still be used by some other project,
+                // and is only stale if there is no installed tool it could belong to
+                let is_installed = installed_tools
+                    .iter()
+                    .any(|spec| spec.id().name().eq_ignore_ascii_case(alias.name()));
+                if !is_installed {
+                    problems.push((path.clone(), LinkProblem::Stale));
+                    continue;
+                }
+            }
+
+            match tool_storage.check_link(path).await? {
+                LinkStatus::Current => {}
+                LinkStatus::Outdated(version) => {
+                    problems.push((path.clone(), LinkProblem::Outdated(Some(version))));
+                }
+                LinkStatus::Invalid => {
+                    problems.push((path.clone(), LinkProblem::Outdated(None)));
+                }
+            }
+        }
+        pt.task_completed();
+
+        if problems.is_empty() {
+            pt.finish_with_emoji_and_message(
+                "✓",
+                format!(
+                    "All {} links are healthy. {}",
+                    link_paths.len(),
+                    pt.formatted_elapsed()
+                ),
+            );
+            return Ok(());
+        }
+        pt.finish_with_emoji_and_message(
+            "✗",
+            format!(
+                "Found problems with {} out of {} links. {}",
+                problems.len(),
+                link_paths.len(),
+                pt.formatted_elapsed()
+            ),
+        );
+
+        // 3. Report, and fix any problems if desired
+        let bullet = style("•").dim();
+        let mut num_fixed = 0;
+        let mut num_unfixable = 0;
+        for (path, problem) in &problems {
+            let name = style(link_name(path)).bold().cyan();
+            let (message, fixed) = match problem {
+                LinkProblem::Outdated(version) => {
+                    let message = match version {
+                        Some(v) => format!("{name} was created by Rokit {v}"),
+                        None => format!("{name} is not a valid Rokit link"),
+                    };
+                    if self.fix {
+                        tool_storage.repair_link(path).await?;
+                    }
+                    (message, self.fix.then_some("repaired"))
+                }
+                LinkProblem::MissingTool(spec) => {
+                    num_unfixable += 1;
+                    (
+                        format!("{name} points to {spec}, which is not installed"),
+                        None,
+                    )
+                }
+                LinkProblem::Stale => {
+                    if self.fix {
+                        tool_storage.remove_link(path).await?;
+                    }
+                    (
+                        format!("{name} does not belong to any installed tool"),
+                        self.fix.then_some("removed"),
+                    )
+                }
+            };
+            match fixed {
+                Some(action) => {
+                    num_fixed += 1;
+                    println!("{bullet} {message} {}", style(format!("({action})")).dim());
+                }
+                None => println!("{bullet} {message}"),
+            }
+        }
+
+        if num_unfixable > 0 {
+            println!(
+                "\nRun `{}` to install missing tools.",
+                style("rokit install").bold().green()
+            );
+        }
+
+        if self.fix {
+            println!(
+                "\nFixed {num_fixed} out of {} problems with links.",
+                problems.len()
+            );
+            Ok(())
+        } else {
+            bail!(
+                "Found {} problems with links.\
+                \nRun `{}` to repair or remove broken links.",
+                problems.len(),
+                style("rokit links check --fix").bold().green()
+            )
+        }
+    }
+}
+
+pub(super) fn link_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+pub(super) fn link_alias(path: &Path) -> Option<ToolAlias> {
+    path.file_stem()?.to_str()?.parse().ok()
+}
@@ -4,6 +4,8 @@ use console::style;
 
 use rokit::{discovery::discover_all_manifests, storage::Home, system::current_dir, tool::ToolId};
 
+use crate::util::format_age;
+
 /// Lists all existing tools managed by Rokit.
 #[derive(Debug, Parser)]
 pub struct ListSubcommand {
@@ -49,7 +51,9 @@ fn list_versions_for_id(home: &Home, id: &ToolId) -> (String, Vec<String>) {
 // Lists versions for the current manifest, and the global manifest
 async fn list_versions(home: &Home) -> (String, Vec<String>) {
     let cwd = current_dir().await;
-    let manifests = discover_all_manifests(true, false).await;
+    let taps = home.taps().await.unwrap_or_default();
+    let manifests = discover_all_manifests(&taps, true, false).await;
+    let latest_versions = home.latest_versions();
 
     let bullet = style("•").dim();
     let arrow = style("→").dim();
@@ -76,8 +80,24 @@ async fn list_versions(home: &Home) -> (String, Vec<String>) {
 
         let mut lines = Vec::new();
         for (alias, spec) in sorted_tools {
+            // NOTE: Latest versions are only known from previous
+            // updates, so we also show how long ago they were checked
+            let latest_suffix = latest_versions
+                .get(spec.id())
+                .filter(|latest| &latest.version > spec.version())
+                .map_or_else(String::new, |latest| {
+                    format!(
+                        " {}",
+                        style(format!(
+                            "(latest: {}, checked {})",
+                            latest.version,
+                            format_age(latest.checked_at)
+                        ))
+                        .dim()
+                    )
+                });
             lines.push(format!(
-                "{bullet} {}{} {arrow} {} {}{at} {}",
+                "{bullet} {}{} {arrow} {} {}{at} {}{latest_suffix}",
                 style(alias.name()).bold().cyan(),
                 " ".repeat(longest_alias_len - alias.name().len()),
                 spec.id(),
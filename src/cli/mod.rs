@@ -6,42 +6,88 @@ use tracing::level_filters::LevelFilter;
 use rokit::storage::Home;
 use rokit::system::ProcessParent;
 
-use crate::util::init_tracing;
+use crate::util::{
+    init_colors, init_tracing, record_command_metrics, ColorChoice, VersionFormat, VersionInfo,
+};
 
 mod add;
 mod authenticate;
+mod cache;
+mod config;
+mod diff;
+mod exec;
+mod fmt;
+mod info;
 mod init;
 mod install;
+mod links;
 mod list;
+mod new;
+mod proxy;
+mod remove;
 mod self_install;
+#[cfg(feature = "self-update")]
 mod self_update;
+mod stats;
+mod status;
 mod system_info;
+mod tap;
+mod tree;
 mod trust;
+mod try_tool;
 mod update;
 
 use self::add::AddSubcommand;
 use self::authenticate::AuthenticateSubcommand;
+use self::cache::CacheSubcommand;
+use self::config::ConfigSubcommand;
+use self::diff::DiffSubcommand;
+use self::exec::ExecSubcommand;
+use self::fmt::FmtSubcommand;
+use self::info::InfoSubcommand;
 use self::init::InitSubcommand;
 use self::install::InstallSubcommand;
+use self::links::LinksSubcommand;
 use self::list::ListSubcommand;
+use self::new::NewSubcommand;
+use self::proxy::ProxySubcommand;
+use self::remove::RemoveSubcommand;
 use self::self_install::SelfInstallSubcommand;
+#[cfg(feature = "self-update")]
 use self::self_update::SelfUpdateSubcommand;
+use self::stats::StatsSubcommand;
+use self::status::StatusSubcommand;
 use self::system_info::SystemInfoSubcommand;
+use self::tap::TapSubcommand;
+use self::tree::TreeSubcommand;
 use self::trust::TrustSubcommand;
+use self::try_tool::TrySubcommand;
 use self::update::UpdateSubcommand;
 
 #[derive(Debug, Parser)]
-#[clap(author, version, about)]
+#[clap(author, version, about, disable_version_flag = true)]
 pub struct Cli {
     #[clap(subcommand)]
     pub subcommand: Option<Subcommand>,
+    /// Print version information.
+    #[clap(short = 'V', long)]
+    pub version: bool,
+    /// The format to print version information in - `json` also includes
+    /// the commit hash, build date, target triple, and enabled features.
+    #[clap(long, value_enum, requires = "version")]
+    pub format: Option<VersionFormat>,
     #[clap(flatten)]
     pub options: GlobalOptions,
 }
 
 impl Cli {
     pub async fn run(self) -> Result<()> {
-        // Enable the appropriate level of tracing / logging
+        if self.version {
+            return VersionInfo::current().print(self.format.unwrap_or_default());
+        }
+
+        // Enable colors if wanted, and the appropriate level of tracing / logging
+        init_colors(self.options.color);
         init_tracing(self.options.tracing_level_filter());
 
         // If we didn't get a subcommand, we should either print the help,
@@ -61,10 +107,15 @@ impl Cli {
 
         // Load Rokit data structures
         let start_home = Instant::now();
-        let home = Home::load_from_env().await.context(
+        let home = match self.options.profile.as_deref() {
+            Some(profile) => Home::load_from_env_with_profile(Some(profile)).await,
+            None => Home::load_from_env().await,
+        };
+        let home = home.context(
             "Failed to load Rokit home!\
             \nYour installation or environment may be corrupted.",
         )?;
+        home.preflight().await?;
         tracing::trace!(
             elapsed = ?start_home.elapsed(),
             "Rokit loaded"
@@ -74,21 +125,27 @@ impl Cli {
         // do not (!!!) use the question mark operator here, because
         // we want to save our data below even if the subcommand fails.
         let start_command = Instant::now();
+        let command_name = command.name();
         let result = command.run(&home).await;
+        let command_elapsed = start_command.elapsed();
         tracing::trace!(
-            elapsed = ?start_command.elapsed(),
+            elapsed = ?command_elapsed,
             success = result.is_ok(),
             "Rokit ran",
         );
 
-        // Save Rokit data structures to disk
+        // Record anonymous metrics about the subcommand, if opted in
+        record_command_metrics(&home, command_name, &result, command_elapsed).await;
+
+        // Save Rokit data structures to disk, if they changed
         let start_save = Instant::now();
-        home.save().await.context(
+        let saved = home.save_if_dirty().await.context(
             "Failed to save Rokit data!\
             \nChanges to trust, tools, and more may have been lost.",
         )?;
         tracing::trace!(
             elapsed = ?start_save.elapsed(),
+            saved,
             "Rokit saved"
         );
 
@@ -115,28 +172,91 @@ impl Cli {
 pub enum Subcommand {
     Add(AddSubcommand),
     Authenticate(AuthenticateSubcommand),
+    Cache(CacheSubcommand),
+    Config(ConfigSubcommand),
+    Diff(DiffSubcommand),
+    Exec(ExecSubcommand),
+    Fmt(FmtSubcommand),
+    Info(InfoSubcommand),
     Init(InitSubcommand),
     Install(InstallSubcommand),
+    Links(LinksSubcommand),
     List(ListSubcommand),
+    New(NewSubcommand),
+    Proxy(ProxySubcommand),
+    Remove(RemoveSubcommand),
     SelfInstall(SelfInstallSubcommand),
+    #[cfg(feature = "self-update")]
     SelfUpdate(SelfUpdateSubcommand),
+    Stats(StatsSubcommand),
+    Status(StatusSubcommand),
     SystemInfo(SystemInfoSubcommand),
+    Tap(TapSubcommand),
+    Tree(TreeSubcommand),
     Trust(TrustSubcommand),
+    Try(TrySubcommand),
     Update(UpdateSubcommand),
 }
 
 impl Subcommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Add(_) => "add",
+            Self::Authenticate(_) => "authenticate",
+            Self::Cache(_) => "cache",
+            Self::Config(_) => "config",
+            Self::Diff(_) => "diff",
+            Self::Exec(_) => "exec",
+            Self::Fmt(_) => "fmt",
+            Self::Info(_) => "info",
+            Self::Init(_) => "init",
+            Self::Install(_) => "install",
+            Self::Links(_) => "links",
+            Self::List(_) => "list",
+            Self::New(_) => "new",
+            Self::Proxy(_) => "proxy",
+            Self::Remove(_) => "remove",
+            Self::SelfInstall(_) => "self-install",
+            #[cfg(feature = "self-update")]
+            Self::SelfUpdate(_) => "self-update",
+            Self::Stats(_) => "stats",
+            Self::Status(_) => "status",
+            Self::SystemInfo(_) => "system-info",
+            Self::Tap(_) => "tap",
+            Self::Tree(_) => "tree",
+            Self::Trust(_) => "trust",
+            Self::Try(_) => "try",
+            Self::Update(_) => "update",
+        }
+    }
+
     pub async fn run(self, home: &Home) -> Result<()> {
         match self {
             Self::Add(cmd) => cmd.run(home).await,
             Self::Authenticate(cmd) => cmd.run(home).await,
+            Self::Cache(cmd) => cmd.run(home).await,
+            Self::Config(cmd) => cmd.run(home).await,
+            Self::Diff(cmd) => cmd.run(home).await,
+            Self::Exec(cmd) => cmd.run(home).await,
+            Self::Fmt(cmd) => cmd.run(home).await,
+            Self::Info(cmd) => cmd.run(home).await,
             Self::Init(cmd) => cmd.run(home).await,
             Self::Install(cmd) => cmd.run(home).await,
+            Self::Links(cmd) => cmd.run(home).await,
             Self::List(cmd) => cmd.run(home).await,
+            Self::New(cmd) => cmd.run(home).await,
+            Self::Proxy(cmd) => cmd.run(home).await,
+            Self::Remove(cmd) => cmd.run(home).await,
             Self::SelfInstall(cmd) => cmd.run(home).await,
+            #[cfg(feature = "self-update")]
             Self::SelfUpdate(cmd) => cmd.run(home).await,
+            Self::Stats(cmd) => cmd.run(home).await,
+            Self::Status(cmd) => cmd.run(home).await,
             Self::SystemInfo(cmd) => cmd.run(home).await,
+            Self::Tap(cmd) => cmd.run(home).await,
+            Self::Tree(cmd) => cmd.run(home).await,
             Self::Trust(cmd) => cmd.run(home).await,
+            Self::Try(cmd) => cmd.run(home).await,
             Self::Update(cmd) => cmd.run(home).await,
         }
     }
@@ -146,6 +266,14 @@ impl Subcommand {
 pub struct GlobalOptions {
     #[clap(short, long, action = ArgAction::Count)]
     pub verbose: u8,
+    /// The profile to use, with its own storage, trust, and authentication.
+    /// Overrides the `ROKIT_PROFILE` environment variable if set.
+    #[clap(long)]
+    pub profile: Option<String>,
+    /// When to use colors in output. Respects the `NO_COLOR`
+    /// and `CLICOLOR_FORCE` environment variables when `auto`.
+    #[clap(long, value_enum, default_value_t)]
+    pub color: ColorChoice,
 }
 
 impl GlobalOptions {
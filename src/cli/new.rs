@@ -0,0 +1,219 @@
+use std::{
+    io::{stderr, IsTerminal},
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use tokio::{
+    fs::{read_dir, remove_dir_all},
+    process::Command,
+    task::spawn_blocking,
+};
+
+use rokit::{
+    manifests::{RokitManifest, ROKIT_MANIFEST_FILE_NAME},
+    sources::unpack_repository_archive,
+    storage::Home,
+    system::current_dir,
+    tool::{ToolAlias, ToolId},
+};
+
+use crate::util::CliProgressTracker;
+
+use super::install::InstallSubcommand;
+
+/// Creates a new project from a template, and installs its tools.
+#[derive(Debug, Parser)]
+pub struct NewSubcommand {
+    /// The template to use, given as a GitHub repository, such
+    /// as `owner/repo`, optionally followed by `@<branch or tag>`.
+    pub template: String,
+    /// The directory to create the project in.
+    /// Defaults to the name of the template repository.
+    pub path: Option<PathBuf>,
+    /// Run the setup command of the template without asking for confirmation.
+    #[clap(long, short)]
+    pub yes: bool,
+    /// Do not run the setup command of the template.
+    #[clap(long, conflicts_with = "yes")]
+    pub no_setup: bool,
+}
+
+impl NewSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let (repo_id, reference) = parse_template(&self.template)?;
+        let dir = current_dir()
+            .await
+            .join(self.path.unwrap_or_else(|| PathBuf::from(repo_id.name())));
+
+        if !is_empty_dir(&dir).await {
+            bail!(
+                "Can not create a new project in '{}' since it is not empty.\
+                \nChoose a different directory by running `{}`.",
+                dir.display(),
+                style(format!("rokit new {} <path>", self.template))
+                    .bold()
+                    .green()
+            );
+        }
+
+        // 1. Download the template and unpack it into the project directory
+
+        let pt = CliProgressTracker::new_with_message("Downloading", 2);
+        let source = home.artifact_source().await?;
+        let archive = source
+            .download_repository_archive(&repo_id, reference.as_deref())
+            .await
+            .with_context(|| format!("Failed to download template '{}'", self.template))?;
+        pt.task_completed();
+
+        pt.update_message("Unpacking");
+        unpack_repository_archive(archive, &dir)
+            .await
+            .with_context(|| format!("Failed to unpack template '{}'", self.template))?;
+        pt.task_completed();
+
+        // NOTE: Anything without a manifest is not a template, and we
+        // should not leave random repository contents lying around
+        let Ok(manifest) = RokitManifest::load(&dir).await else {
+            let _ = remove_dir_all(&dir).await;
+            bail!(
+                "The repository '{}' is not a Rokit template.\
+                \nTemplates must contain a '{ROKIT_MANIFEST_FILE_NAME}' file at their root.",
+                self.template
+            );
+        };
+        pt.finish_with_message(format!(
+            "Created new project in '{}' {}",
+            dir.display(),
+            pt.formatted_elapsed()
+        ));
+
+        // 2. Install all tools for the project, same as `rokit install` would
+
+        InstallSubcommand {
+            no_trust_check: false,
+            force: false,
+            offline: false,
+            locked: false,
+            report: None,
+            timings: false,
+            max_download_size: None,
+        }
+        .run_in_dir(home, &dir)
+        .await?;
+
+        // 3. Run the setup command, if the template has one and the user agrees
+
+        if let Some(setup) = manifest.setup_command().filter(|_| !self.no_setup) {
+            run_setup_command(home, &manifest, &dir, &setup, self.yes).await?;
+        }
+
+        println!(
+            "\nYour new project is ready! Run `{}` to get started.",
+            style(format!("cd {}", dir.display())).bold().green()
+        );
+
+        Ok(())
+    }
+}
+
+fn parse_template(template: &str) -> Result<(ToolId, Option<String>)> {
+    let (repo, reference) = match template.split_once('@') {
+        Some((repo, reference)) => (repo, Some(reference.trim().to_string())),
+        None => (template, None),
+    };
+    if reference.as_ref().is_some_and(String::is_empty) {
+        bail!("The template '{template}' is missing a branch or tag after '@'");
+    }
+    let repo_id = repo
+        .parse::<ToolId>()
+        .with_context(|| format!("The template '{template}' is not a valid repository"))?;
+    Ok((repo_id, reference))
+}
+
+async fn is_empty_dir(dir: &Path) -> bool {
+    match read_dir(dir).await {
+        Ok(mut entries) => matches!(entries.next_entry().await, Ok(None)),
+        Err(_) => !dir.exists(),
+    }
+}
+
+/**
+    Runs the setup command of a template.
+
+    Since templates may be created by anyone, the setup command may only run
+    a tool from the template manifest that the user has trusted, and the user
+    must confirm running it, unless they have explicitly opted out of this.
+*/
+async fn run_setup_command(
+    home: &Home,
+    manifest: &RokitManifest,
+    dir: &Path,
+    setup: &[String],
+    yes: bool,
+) -> Result<()> {
+    let taps = home.taps().await?;
+    let command = setup.join(" ");
+    let (program, args) = setup.split_first().expect("setup command is never empty");
+
+    let spec = program
+        .parse::<ToolAlias>()
+        .ok()
+        .and_then(|alias| manifest.get_tool_with_taps(&alias, &taps))
+        .with_context(|| {
+            format!(
+                "The setup command `{command}` must run a tool from the template, \
+                but '{program}' is not a tool in its manifest."
+            )
+        })?;
+    if !home.tool_cache().is_trusted(spec.id()) || !home.tool_cache().is_installed(&spec) {
+        println!(
+            "Skipped setup command `{command}` since {} is not trusted.",
+            spec.id()
+        );
+        return Ok(());
+    }
+
+    let confirmed = if yes {
+        true
+    } else if stderr().is_terminal() {
+        let prompt = format!("Run setup command `{command}`?");
+        spawn_blocking(move || {
+            dialoguer::Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(prompt)
+                .default(true)
+                .interact_opt()
+        })
+        .await??
+        .unwrap_or_default()
+    } else {
+        false
+    };
+    if !confirmed {
+        println!(
+            "Skipped setup command - you can run it later using `{}`.",
+            style(&command).bold().green()
+        );
+        return Ok(());
+    }
+
+    let status = Command::new(home.tool_storage().tool_path(&spec))
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .await
+        .with_context(|| format!("Setup command `{command}` could not be run"))?;
+    if !status.success() {
+        bail!("Setup command `{command}` failed ({status})");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,230 @@
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use console::style;
+use tokio::{
+    fs::{create_dir_all, rename, try_exists, write, File},
+    io::{copy, AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    signal::ctrl_c,
+};
+
+use rokit::{sources::ArtifactSource, storage::Home, tool::ToolId};
+
+const PROXY_CACHE_DIR_NAME: &str = "proxy-cache";
+const MAX_REQUEST_HEAD_SIZE: usize = 8 * 1024;
+
+/// Runs or manages a caching proxy for tool artifacts.
+#[derive(Debug, Parser)]
+pub struct ProxySubcommand {
+    #[clap(subcommand)]
+    pub command: ProxyCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProxyCommand {
+    Serve(ProxyServeSubcommand),
+}
+
+impl ProxySubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        match self.command {
+            ProxyCommand::Serve(cmd) => cmd.run(home).await,
+        }
+    }
+}
+
+/// Serves tool artifacts over HTTP, caching them on disk as they are downloaded.
+///
+/// Other Rokit instances can use this proxy as a mirror for GitHub downloads
+/// using the base URL `http://<address>/github`, and will only download each
+/// artifact once. The proxy only listens on the local machine by default,
+/// use `--public` to serve other machines, for example on the local network.
+#[derive(Debug, Parser)]
+pub struct ProxyServeSubcommand {
+    /// The address to listen on.
+    #[clap(long, default_value = "127.0.0.1:8787")]
+    pub address: SocketAddr,
+    /// Allow listening on addresses other than the local machine.
+    ///
+    /// The proxy has no authentication, and anyone who can reach
+    /// the address will be able to download artifacts through it.
+    #[clap(long)]
+    pub public: bool,
+    /// Download artifacts using the stored authentication tokens.
+    ///
+    /// This lets anyone who can reach the proxy download artifacts that
+    /// only the stored tokens have access to, such as from private repositories.
+    #[clap(long)]
+    pub forward_credentials: bool,
+}
+
+impl ProxyServeSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        if !self.public && !self.address.ip().is_loopback() {
+            bail!(
+                "Refusing to listen on {}, which is reachable from other machines.\
+                \nThe proxy has no authentication, pass --public to serve it anyway.",
+                self.address
+            );
+        }
+
+        let source = if self.forward_credentials {
+            home.artifact_source().await?
+        } else {
+            home.anonymous_artifact_source().await?
+        };
+        let proxy = Arc::new(Proxy {
+            source,
+            cache_dir: home.path().join(PROXY_CACHE_DIR_NAME),
+        });
+
+        let listener = TcpListener::bind(self.address)
+            .await
+            .with_context(|| format!("Failed to listen on {}", self.address))?;
+        println!(
+            "Serving tool artifacts at {}\
+            \nPress Ctrl+C to stop.",
+            style(format!("http://{}/github", self.address))
+                .bold()
+                .cyan(),
+        );
+
+        loop {
+            let (stream, peer) = tokio::select! {
+                accepted = listener.accept() => accepted?,
+                _ = ctrl_c() => break,
+            };
+            let proxy = Arc::clone(&proxy);
+            tokio::spawn(async move {
+                if let Err(e) = proxy.handle_connection(stream).await {
+                    tracing::debug!(%peer, error = %e, "proxy connection failed");
+                }
+            });
+        }
+
+        Ok(())
+    }
+}
+
+struct Proxy {
+    source: ArtifactSource,
+    cache_dir: PathBuf,
+}
+
+impl Proxy {
+    async fn handle_connection(&self, mut stream: TcpStream) -> Result<()> {
+        let head = read_request_head(&mut stream).await?;
+        let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let path = parts.next().unwrap_or_default();
+
+        if method != "GET" && method != "HEAD" {
+            return respond(&mut stream, "405 Method Not Allowed").await;
+        }
+        let Some((tool_id, asset_id)) = parse_asset_path(path) else {
+            return respond(&mut stream, "404 Not Found").await;
+        };
+
+        match self.get_asset_path(&tool_id, &asset_id).await {
+            Ok(path) => {
+                // NOTE: Assets are streamed from disk, so that serving
+                // large artifacts never needs to keep them in memory
+                let mut file = File::open(&path).await?;
+                let length = file.metadata().await?.len();
+                respond_with_length(&mut stream, "200 OK", length).await?;
+                if method == "GET" {
+                    copy(&mut file, &mut stream).await?;
+                }
+                stream.flush().await?;
+                Ok(())
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch asset {asset_id} for {tool_id}: {e:?}");
+                respond(&mut stream, "502 Bad Gateway").await
+            }
+        }
+    }
+
+    async fn get_asset_path(&self, tool_id: &ToolId, asset_id: &str) -> Result<PathBuf> {
+        let path = self
+            .cache_dir
+            .join(tool_id.provider().as_str())
+            .join(tool_id.author().to_ascii_lowercase())
+            .join(tool_id.name().to_ascii_lowercase())
+            .join(asset_id);
+
+        if try_exists(&path).await.unwrap_or(false) {
+            tracing::debug!(%tool_id, asset_id, "serving cached asset");
+            return Ok(path);
+        }
+
+        tracing::info!("Downloading asset {asset_id} for {tool_id}");
+        let contents = self
+            .source
+            .download_asset_contents(tool_id, asset_id)
+            .await?;
+
+        // NOTE: Several clients may request the same asset at once, so we
+        // write to a temporary file first and then move it into place, to
+        // make sure that a partially written asset is never served
+        let dir = path.parent().expect("asset path has a parent");
+        create_dir_all(dir).await?;
+        let temp_path = dir.join(format!(".{asset_id}.{}.tmp", std::process::id()));
+        write(&temp_path, contents).await?;
+        rename(&temp_path, &path).await?;
+
+        Ok(path)
+    }
+}
+
+/**
+    Parses a request path in the same format as the GitHub API
+    uses for downloading release assets, prefixed by the provider:
+
+    `/github/repos/<owner>/<repo>/releases/assets/<id>`
+*/
+fn parse_asset_path(path: &str) -> Option<(ToolId, String)> {
+    let path = path.split('?').next()?;
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    match segments.as_slice() {
+        ["github", "repos", owner, repo, "releases", "assets", asset_id]
+            if asset_id.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            let tool_id = format!("{owner}/{repo}").parse().ok()?;
+            Some((tool_id, (*asset_id).to_string()))
+        }
+        _ => None,
+    }
+}
+
+async fn read_request_head(stream: &mut TcpStream) -> Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || head.len() + n > MAX_REQUEST_HEAD_SIZE {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+async fn respond(stream: &mut TcpStream, status: &str) -> Result<()> {
+    respond_with_length(stream, status, 0).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn respond_with_length(stream: &mut TcpStream, status: &str, length: u64) -> Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status}\r\n\
+        Content-Type: application/octet-stream\r\n\
+        Content-Length: {length}\r\n\
+        Connection: close\r\n\r\n"
+    );
+    stream.write_all(head.as_bytes()).await?;
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::{path::Path, process::Stdio, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use console::style;
+use tokio::{process::Command, time::timeout};
+
+use rokit::{
+    discovery::discover_all_manifests,
+    manifests::RokitManifest,
+    storage::Home,
+    tool::{ToolAlias, ToolSpec},
+};
+
+use crate::util::{CliProgressTracker, ToolAliasOrIdOrSpec};
+
+/// Removes a tool from Rokit, running its `pre-remove` command first, if any.
+///
+/// The `pre-remove` command of a tool only runs if the tool is trusted and
+/// installed, and lets tools clean up any integrations they registered
+/// with the system, such as Roblox Studio plugins or file associations.
+#[derive(Debug, Parser)]
+pub struct RemoveSubcommand {
+    /// The tool to remove - can be an alias, id, or specification.
+    pub tool: ToolAliasOrIdOrSpec,
+    /// Remove this tool globally instead of removing
+    /// it from the nearest manifest file.
+    #[clap(long)]
+    pub global: bool,
+    /// Remove the tool from the manifest, even
+    /// if its `pre-remove` command fails.
+    #[clap(long)]
+    pub force: bool,
+}
+
+impl RemoveSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let tool_cache = home.tool_cache();
+        let tool_storage = home.tool_storage();
+        let taps = home.taps().await?;
+
+        // 1. Load the desired manifest
+        let manifest_path = if self.global {
+            home.path().to_path_buf()
+        } else {
+            let non_global_manifests = discover_all_manifests(&taps, true, true).await;
+            non_global_manifests
+                .first()
+                .map(|m| m.path.parent().unwrap().to_path_buf())
+                .context(
+                    "No manifest was found for the current directory.\
+                    \nRun `rokit init` in your project root to create one.",
+                )?
+        };
+        let mut manifest = if self.global {
+            RokitManifest::load_or_create(&manifest_path).await?
+        } else {
+            RokitManifest::load(&manifest_path).await?
+        };
+
+        // 2. Find the alias and spec of the tool to remove
+        let (alias, spec) = match &self.tool {
+            ToolAliasOrIdOrSpec::Alias(alias) => {
+                let spec = manifest.get_tool_with_taps(alias, &taps);
+                (alias.clone(), spec)
+            }
+            tool => {
+                let search_id = match tool {
+                    ToolAliasOrIdOrSpec::Id(id) => id.clone(),
+                    ToolAliasOrIdOrSpec::Spec(spec) => spec.id().clone(),
+                    ToolAliasOrIdOrSpec::Alias(_) => unreachable!(),
+                };
+                let found = manifest
+                    .tool_specs_with_taps(&taps)
+                    .into_iter()
+                    .filter(|(_, spec)| spec.id() == &search_id)
+                    .collect::<Vec<_>>();
+                if found.len() > 1 {
+                    bail!(
+                        "Multiple tools with the id '{search_id}' have been added to this project.\
+                        \nPlease specify the tool by its alias instead."
+                    );
+                }
+                let Some((alias, spec)) = found.into_iter().next() else {
+                    bail!("No tool with the id '{search_id}' has been added to this project.");
+                };
+                (alias, Some(spec))
+            }
+        };
+        if !manifest.has_tool(&alias) {
+            bail!("No tool with the alias '{alias}' has been added to this project.");
+        }
+
+        // 3. Run the pre-remove command, if the tool has one, and can run it
+        let pt = CliProgressTracker::new_with_message("Removing", 2);
+        let options = manifest.get_tool_options(&alias);
+        let pre_remove = options.pre_remove;
+        if let (Some(pre_remove), Some(spec)) = (&pre_remove, &spec) {
+            if !tool_cache.is_trusted(spec.id()) {
+                pt.suspend(|| {
+                    eprintln!(
+                        "Skipping the pre-remove command for {}, since it is not trusted.",
+                        style(alias.to_string()).bold().cyan()
+                    );
+                });
+            } else if !tool_cache.is_installed(spec) {
+                pt.suspend(|| {
+                    eprintln!(
+                        "Skipping the pre-remove command for {}, since it is not installed.",
+                        style(alias.to_string()).bold().cyan()
+                    );
+                });
+            } else {
+                pt.update_message("Cleaning up");
+                let path = tool_storage.entrypoint_path(spec, options.entrypoint.as_deref());
+                let result = run_pre_remove(&path, &alias, spec, pre_remove).await;
+                match result {
+                    Err(e) if self.force => {
+                        pt.suspend(|| eprintln!("{e:#}\nRemoving the tool anyway."));
+                    }
+                    Err(e) => {
+                        return Err(e.context(format!(
+                            "The tool was not removed - run `{}` to remove it anyway.",
+                            style(format!("rokit remove --force {alias}"))
+                                .bold()
+                                .green()
+                        )));
+                    }
+                    Ok(()) => {}
+                }
+            }
+        }
+        pt.task_completed();
+
+        // 4. Remove the tool from the manifest and save it
+        manifest.remove_tool(&alias);
+        manifest.save(&manifest_path).await?;
+        pt.task_completed();
+
+        pt.finish_with_message(format!(
+            "Removed tool {} {}",
+            style(alias.to_string()).bold().cyan(),
+            pt.formatted_elapsed(),
+        ));
+
+        Ok(())
+    }
+}
+
+const PRE_REMOVE_TIMEOUT: Duration = Duration::from_mins(1);
+
+async fn run_pre_remove(
+    path: &Path,
+    alias: &ToolAlias,
+    spec: &ToolSpec,
+    pre_remove: &[String],
+) -> Result<()> {
+    let Some((program, args)) = pre_remove.split_first() else {
+        bail!("The pre-remove command for tool '{alias}' is empty");
+    };
+    if !program.eq_ignore_ascii_case(alias.name()) {
+        bail!(
+            "The pre-remove command for tool '{alias}' must run the tool itself, \
+            but tried to run '{program}'.\
+            \nChange the first item of the command to '{alias}' in your manifest."
+        );
+    }
+
+    let command = pre_remove.join(" ");
+    tracing::debug!(%spec, command, "running pre-remove command");
+    let output = Command::new(path)
+        .args(args)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = timeout(PRE_REMOVE_TIMEOUT, output)
+        .await
+        .with_context(|| format!("Pre-remove command `{command}` timed out"))?
+        .with_context(|| format!("Pre-remove command `{command}` could not be run"))?;
+
+    if !output.status.success() {
+        bail!(
+            "Pre-remove command `{command}` failed ({})\n\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
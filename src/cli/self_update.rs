@@ -1,20 +1,15 @@
-use std::io::{stdout, BufWriter};
-
-use anyhow::{bail, Context, Result};
+use anyhow::{Context, Result};
 use clap::Parser;
 use console::{style, Style};
 use dialoguer::{theme::ColorfulTheme, Confirm};
-use pulldown_cmark::{Options, Parser as MarkdownParser};
-use pulldown_cmark_mdcat::{
-    resources::FileResourceHandler, Environment, Settings, TerminalProgram, TerminalSize, Theme,
-};
-use syntect::parsing::SyntaxSet;
 
 use semver::Version;
 
-use rokit::{storage::Home, tool::ToolId};
+use rokit::{storage::Home, warnings::RokitWarnings};
 
-use crate::util::{find_most_compatible_artifact, CliProgressTracker};
+use crate::util::{
+    find_most_compatible_artifact, print_markdown, rokit_tool_id, CliProgressTracker,
+};
 
 /// Updates Rokit to the latest version.
 #[derive(Debug, Parser)]
@@ -26,16 +21,7 @@ pub struct SelfUpdateSubcommand {
 
 impl SelfUpdateSubcommand {
     pub async fn run(self, home: &Home) -> Result<()> {
-        let repo = env!("CARGO_PKG_REPOSITORY")
-            .trim_start_matches("https://github.com/")
-            .trim_end_matches(".git");
-        let Ok(tool_id) = repo.parse::<ToolId>() else {
-            bail!(
-                "Failed to parse manifest repository URL!\
-                \nThis is a bug in Rokit, please report it at:
-                \n{repo}"
-            );
-        };
+        let tool_id = rokit_tool_id()?;
 
         let pt = CliProgressTracker::new_with_message("Loading", 4);
         let source = home.artifact_source().await?;
@@ -70,8 +56,9 @@ impl SelfUpdateSubcommand {
         pt.task_completed();
         pt.update_message("Downloading");
 
-        let artifact = find_most_compatible_artifact(&release.artifacts, &tool_id)
-            .context("No compatible Rokit artifact was found (WAT???)")?;
+        let artifact =
+            find_most_compatible_artifact(&release.artifacts, &tool_id, &RokitWarnings::new())
+                .context("No compatible Rokit artifact was found (WAT???)")?;
         let artifact_contents = source
             .download_artifact_contents(&artifact)
             .await
@@ -119,30 +106,12 @@ impl SelfUpdateSubcommand {
 
             if to_show_changelog {
                 println!();
-                pulldown_cmark_mdcat::push_tty(
-                    &Settings {
-                        terminal_capabilities: TerminalProgram::detect().capabilities(),
-                        terminal_size: TerminalSize::detect()
-                            .context("Failed to detect terminal size")?,
-                        syntax_set: &SyntaxSet::load_defaults_newlines(),
-                        theme: Theme::default(),
-                    },
-                    &Environment::for_local_directory(&tempfile::tempdir()?.path())?,
-                    &FileResourceHandler::new(104_857_600), // TODO: Maybe make this be a DispatchingResourceHandler?
-                    &mut BufWriter::new(stdout()),
-                    MarkdownParser::new_ext(
-                        format!(
-                            "# Changelog - {} v{}\n{}",
-                            tool_id.name(),
-                            version_current,
-                            changelog
-                        )
-                        .as_str(),
-                        Options::ENABLE_FOOTNOTES
-                            | Options::ENABLE_TABLES
-                            | Options::ENABLE_STRIKETHROUGH,
-                    ),
-                )?;
+                print_markdown(format!(
+                    "# Changelog - {} v{}\n{}",
+                    tool_id.name(),
+                    version_current,
+                    changelog
+                ))?;
             }
         }
 
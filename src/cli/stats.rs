@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use console::style;
+
+use rokit::{
+    discovery::discover_all_manifests,
+    storage::{Home, ToolUsage},
+};
+
+use crate::util::format_age;
+
+/// Shows how often each tool has been run, and when it was last run.
+///
+/// Helps to find tools that are no longer used and can be removed.
+#[derive(Debug, Parser)]
+pub struct StatsSubcommand {
+    /// Show all tools that have ever been run, not just those in manifests.
+    #[clap(long)]
+    pub all: bool,
+}
+
+impl StatsSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let stats = home
+            .usage_stats()
+            .await
+            .context("Failed to load tool usage statistics")?;
+
+        let bullet = style("•").dim();
+
+        if self.all {
+            let tools = stats.tools().collect::<Vec<_>>();
+            if tools.is_empty() {
+                println!("📊 No tools have been run yet.");
+                return Ok(());
+            }
+
+            let longest_spec_len = tools
+                .iter()
+                .map(|(spec, _)| spec.to_string().len())
+                .max()
+                .unwrap_or(0);
+
+            println!("📊 Usage of all tools:\n");
+            for (spec, usage) in tools {
+                let spec = spec.to_string();
+                println!(
+                    "{bullet} {spec}{} {}",
+                    " ".repeat(longest_spec_len - spec.len()),
+                    format_usage(Some(*usage)),
+                );
+            }
+            return Ok(());
+        }
+
+        let taps = home.taps().await.unwrap_or_default();
+        let manifests = discover_all_manifests(&taps, true, false).await;
+
+        // NOTE: Tools are listed by alias, and the same alias may appear
+        // in several manifests, so we show each alias and tool id only once
+        let mut tools = manifests
+            .into_iter()
+            .flat_map(|manifest| manifest.tools.into_iter())
+            .map(|(alias, spec)| (alias.name().to_string(), spec.id().clone()))
+            .collect::<Vec<_>>();
+        tools.sort();
+        tools.dedup();
+
+        if tools.is_empty() {
+            println!("📊 No tools found.");
+            return Ok(());
+        }
+
+        let longest_alias_len = tools
+            .iter()
+            .map(|(alias, _)| alias.len())
+            .max()
+            .unwrap_or(0);
+
+        // Show the least used tools first, since those are what we
+        // want to highlight as potential candidates for removal
+        let mut rows = tools
+            .into_iter()
+            .map(|(alias, id)| {
+                let usage = stats.get_for_id(&id);
+                (alias, usage)
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|(_, usage)| usage.map(|u| (u.invocations, u.last_used)));
+
+        println!("📊 Usage of tools in manifests:\n");
+        for (alias, usage) in &rows {
+            println!(
+                "{bullet} {}{} {}",
+                style(alias).bold().cyan(),
+                " ".repeat(longest_alias_len - alias.len()),
+                format_usage(*usage),
+            );
+        }
+
+        let unused = rows.iter().filter(|(_, usage)| usage.is_none()).count();
+        if unused > 0 {
+            let s = if unused == 1 { "" } else { "s" };
+            println!(
+                "\n{} tool{s} {} never been run, and may no longer be needed.",
+                style(unused).bold().yellow(),
+                if unused == 1 { "has" } else { "have" },
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn format_usage(usage: Option<ToolUsage>) -> String {
+    let Some(usage) = usage else {
+        return style("never run").dim().to_string();
+    };
+    let s = if usage.invocations == 1 { "" } else { "s" };
+    format!(
+        "{} run{s} {}",
+        style(usage.invocations).bold(),
+        style(format!("(last {})", format_age(usage.last_used))).dim()
+    )
+}
@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use clap::Parser;
+use console::style;
+use futures::{stream::FuturesOrdered, StreamExt};
+use tokio::time::Instant;
+use url::Url;
+
+use rokit::{
+    sources::{ProviderAuthStatus, ProviderStatus, TapIndex},
+    storage::Home,
+};
+
+/// Checks if Rokit can reach the providers and taps it downloads tools from.
+///
+/// Shows reachability, authentication, and remaining rate limits for
+/// each provider, which helps to tell apart local problems, such as an
+/// expired token, from problems with the provider, such as an outage.
+#[derive(Debug, Parser)]
+pub struct StatusSubcommand {}
+
+impl StatusSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let source = &home.artifact_source().await?;
+        let taps = home.taps().await.unwrap_or_default();
+
+        let (providers, tap_results) = tokio::join!(source.get_provider_statuses(), async {
+            taps.tap_urls()
+                .into_iter()
+                .map(|url| async move {
+                    let start = Instant::now();
+                    let result = match url.parse::<Url>() {
+                        Ok(parsed) => TapIndex::fetch(source.http_client().as_ref(), &parsed)
+                            .await
+                            .map(|_| start.elapsed())
+                            .map_err(|e| e.to_string()),
+                        Err(e) => Err(e.to_string()),
+                    };
+                    (url, result)
+                })
+                .collect::<FuturesOrdered<_>>()
+                .collect::<Vec<_>>()
+                .await
+        });
+
+        let bullet = style("•").dim();
+        let mut unhealthy = 0;
+
+        println!("{}", style("Providers").bold());
+        for status in &providers {
+            if !status.is_healthy() {
+                unhealthy += 1;
+            }
+            println!("  {bullet} {}", format_provider_status(status));
+            if let Some(error) = &status.error {
+                println!("    {}", style(error).dim());
+            }
+        }
+
+        if !tap_results.is_empty() {
+            println!("\n{}", style("Taps").bold());
+            for (url, result) in &tap_results {
+                match result {
+                    Ok(latency) => println!("  {bullet} {url} {}", format_ok(*latency)),
+                    Err(e) => {
+                        unhealthy += 1;
+                        println!("  {bullet} {url} {}", style("unreachable").bold().red());
+                        println!("    {}", style(e).dim());
+                    }
+                }
+            }
+        }
+
+        if unhealthy > 0 {
+            let s = if unhealthy == 1 { "" } else { "s" };
+            bail!("Found problems with {unhealthy} provider{s} or tap{s}");
+        }
+
+        println!("\nAll providers and taps are working as expected.");
+        Ok(())
+    }
+}
+
+fn format_ok(latency: Duration) -> String {
+    format!(
+        "{} {}",
+        style("ok").bold().green(),
+        style(format!("({}ms)", latency.as_millis())).dim()
+    )
+}
+
+fn format_provider_status(status: &ProviderStatus) -> String {
+    let name = style(status.provider.display_name()).bold().cyan();
+    let Some(latency) = status.latency else {
+        return format!("{name} {}", style("unreachable").bold().red());
+    };
+
+    let reachability = if status.error.is_some() && status.auth.is_none() {
+        format!(
+            "{} {}",
+            style("error").bold().red(),
+            style(format!("({}ms)", latency.as_millis())).dim()
+        )
+    } else {
+        format_ok(latency)
+    };
+
+    let auth = match status.auth {
+        None => String::new(),
+        Some(ProviderAuthStatus::Anonymous) => format!(", {}", style("not authenticated").dim()),
+        Some(ProviderAuthStatus::Valid) => format!(", {}", style("authenticated").green()),
+        Some(ProviderAuthStatus::Invalid) => format!(
+            ", {} - run `{}` to fix",
+            style("invalid token").bold().red(),
+            style(format!("rokit authenticate {}", status.provider.as_str()))
+                .bold()
+                .green()
+        ),
+    };
+
+    let rate_limit = status.rate_limit.map_or_else(String::new, |limit| {
+        let remaining = if limit.is_exhausted() {
+            style(limit.remaining).bold().red()
+        } else {
+            style(limit.remaining).bold()
+        };
+        let resets = limit
+            .resets_at
+            .map(|at| (at - Utc::now()).num_minutes().max(0))
+            .map_or_else(String::new, |minutes| format!(", resets in {minutes} min"));
+        format!(
+            ", {remaining}/{} requests left{}",
+            limit.limit,
+            style(resets).dim()
+        )
+    });
+
+    format!("{name} {reachability}{auth}{rate_limit}")
+}
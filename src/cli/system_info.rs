@@ -1,27 +1,41 @@
 use std::{
     collections::HashMap,
     fmt::Write,
-    path::{Path, MAIN_SEPARATOR_STR},
+    path::{Path, PathBuf, MAIN_SEPARATOR_STR},
 };
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use console::style;
 use futures::{stream::FuturesOrdered, TryStreamExt};
-use tokio::{fs::read, task::spawn_blocking};
+use tokio::{
+    fs::{read, read_to_string},
+    task::spawn_blocking,
+};
 
 use rokit::{
     descriptor::Descriptor,
     storage::Home,
-    system::{current_dir, current_exe, exists_in_path},
+    system::{current_dir, current_exe, exists_in_path, SelectionFixture},
 };
 
 /// Prints out information about the current system and installed tools.
 #[derive(Debug, Parser)]
-pub struct SystemInfoSubcommand {}
+pub struct SystemInfoSubcommand {
+    /// Check artifact selection against the cases in a fixture file instead.
+    ///
+    /// Each case lists the assets in a release of a tool, and the asset that
+    /// should be selected for each platform. Fails if any selection differs.
+    #[clap(long, value_name = "FILE")]
+    pub selection_fixture: Option<PathBuf>,
+}
 
 impl SystemInfoSubcommand {
     pub async fn run(self, home: &Home) -> Result<()> {
+        if let Some(path) = self.selection_fixture {
+            return check_selection_fixture(&path).await;
+        }
+
         let cache = home.tool_cache();
         let storage = home.tool_storage();
 
@@ -70,6 +84,13 @@ impl SystemInfoSubcommand {
             "  {bullet} Rokit dir   {arrow} {}",
             style(display_path(home.path()))
         )?;
+        if let Some(profile) = home.profile() {
+            writeln!(
+                s,
+                "  {bullet} Profile     {arrow} {}",
+                style(profile).bold().cyan()
+            )?;
+        }
         writeln!(
             s,
             "  {bullet} Current dir {arrow} {}",
@@ -171,6 +192,34 @@ impl SystemInfoSubcommand {
     }
 }
 
+async fn check_selection_fixture(path: &Path) -> Result<()> {
+    let contents = read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read selection fixture at {}", path.display()))?;
+    let fixture = contents
+        .parse::<SelectionFixture>()
+        .with_context(|| format!("Failed to parse selection fixture at {}", path.display()))?;
+
+    let outcomes = fixture.run();
+    let mismatches = outcomes.iter().filter(|o| !o.is_match()).count();
+    for outcome in &outcomes {
+        if outcome.is_match() {
+            println!("{} {outcome}", style("✓").bold().green());
+        } else {
+            println!("{} {outcome}", style("✗").bold().red());
+        }
+    }
+
+    if mismatches > 0 {
+        bail!(
+            "{mismatches} of {} selections did not match the fixture",
+            outcomes.len()
+        );
+    }
+    println!("\nAll {} selections matched the fixture.", outcomes.len());
+    Ok(())
+}
+
 fn display_path(path: impl AsRef<Path>) -> String {
     let path = path.as_ref();
     if let Some(user_home) = dirs::home_dir() {
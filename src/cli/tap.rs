@@ -0,0 +1,134 @@
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use console::style;
+use url::Url;
+
+use rokit::{sources::TapIndex, storage::Home};
+
+use crate::util::CliProgressTracker;
+
+/// Manages taps - registries of short tool names, such as `rojo`.
+#[derive(Debug, Parser)]
+pub struct TapSubcommand {
+    #[clap(subcommand)]
+    pub command: TapCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TapCommand {
+    Add(TapAddSubcommand),
+    Remove(TapRemoveSubcommand),
+    List(TapListSubcommand),
+}
+
+impl TapSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        match self.command {
+            TapCommand::Add(cmd) => cmd.run(home).await,
+            TapCommand::Remove(cmd) => cmd.run(home).await,
+            TapCommand::List(cmd) => cmd.run(home).await,
+        }
+    }
+}
+
+/// Adds a tap, or fetches the latest contents of an existing tap.
+///
+/// A tap is a TOML file with a `[tools]` table, mapping short tool
+/// names to tool identifiers, such as `rojo = "rojo-rbx/rojo"`.
+#[derive(Debug, Parser)]
+pub struct TapAddSubcommand {
+    /// The URL of the tap index file.
+    pub url: Url,
+}
+
+impl TapAddSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let pt = CliProgressTracker::new_with_message("Fetching", 3);
+
+        let mut taps = home.taps().await.context("Failed to load taps manifest")?;
+        pt.task_completed();
+
+        let source = home.artifact_source().await?;
+        let index = TapIndex::fetch(source.http_client().as_ref(), &self.url)
+            .await
+            .with_context(|| format!("Failed to fetch tap from '{}'", self.url))?;
+        if index.is_empty() {
+            bail!(
+                "The tap at '{}' does not contain any tools.\
+                \nMake sure the URL points to a valid tap index file.",
+                self.url
+            );
+        }
+        pt.task_completed();
+
+        pt.update_message("Saving");
+        let is_new = taps.add_tap(self.url.as_str(), &index);
+        taps.save(home.path()).await?;
+
+        pt.finish_with_emoji_and_message(
+            "✓",
+            format!(
+                "{} tap {} with {} tool{}. {}",
+                if is_new { "Added" } else { "Updated" },
+                style(self.url.as_str()).bold().cyan(),
+                index.len(),
+                if index.len() == 1 { "" } else { "s" },
+                pt.formatted_elapsed()
+            ),
+        );
+
+        Ok(())
+    }
+}
+
+/// Removes a previously added tap.
+#[derive(Debug, Parser)]
+pub struct TapRemoveSubcommand {
+    /// The URL of the tap to remove.
+    pub url: Url,
+}
+
+impl TapRemoveSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let mut taps = home.taps().await.context("Failed to load taps manifest")?;
+        if !taps.remove_tap(self.url.as_str()) {
+            bail!(
+                "No tap with the URL '{}' has been added.\
+                \nRun `{}` to see all added taps.",
+                self.url,
+                style("rokit tap list").bold().green(),
+            );
+        }
+        taps.save(home.path()).await?;
+
+        println!("Removed tap {}", style(self.url.as_str()).bold().cyan());
+
+        Ok(())
+    }
+}
+
+/// Lists all added taps, in the order they are used to resolve tool names.
+#[derive(Debug, Parser)]
+pub struct TapListSubcommand {}
+
+impl TapListSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let taps = home.taps().await.context("Failed to load taps manifest")?;
+        let urls = taps.tap_urls();
+        if urls.is_empty() {
+            println!(
+                "No taps have been added.\
+                \nRun `{}` to add one.",
+                style("rokit tap add <url>").bold().green(),
+            );
+            return Ok(());
+        }
+
+        let bullet = style("•").dim();
+        for url in urls {
+            println!("{bullet} {url}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Result;
+use clap::Parser;
+use console::style;
+
+use rokit::{discovery::discover_all_manifests, storage::Home, system::current_dir};
+
+/// Shows every manifest that is discovered from the current directory,
+/// with the tools each one contains, and which of them are used.
+///
+/// Manifests are listed nearest first, and the nearest definition
+/// of a tool alias is used - any other definitions are shadowed.
+#[derive(Debug, Parser)]
+pub struct TreeSubcommand {}
+
+impl TreeSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let cwd = current_dir().await;
+        let taps = home.taps().await.unwrap_or_default();
+        let manifests = discover_all_manifests(&taps, false, false).await;
+        if manifests.is_empty() {
+            println!("🌳 No manifests found.");
+            return Ok(());
+        }
+
+        // NOTE: Manifests are ordered from nearest to furthest away,
+        // so the first manifest that defines an alias is the one used
+        let mut used_by = HashMap::new();
+        for (index, manifest) in manifests.iter().enumerate() {
+            for alias in manifest.tools.keys() {
+                used_by.entry(alias).or_insert(index);
+            }
+        }
+
+        let bullet = style("•").dim();
+        let arrow = style("→").dim();
+        let at = style("@").dim();
+
+        println!("🌳 Discovered manifests, nearest first:");
+        for (index, manifest) in manifests.iter().enumerate() {
+            println!(
+                "\n{}",
+                style(display_path(home, &cwd, &manifest.path)).bold()
+            );
+
+            let mut tools = manifest.tools.iter().collect::<Vec<_>>();
+            tools.sort_by(|(alias_a, _), (alias_b, _)| alias_a.name().cmp(alias_b.name()));
+            if tools.is_empty() {
+                println!("  {}", style("(no tools)").dim());
+                continue;
+            }
+
+            let longest_alias_len = tools
+                .iter()
+                .map(|(alias, _)| alias.name().len())
+                .max()
+                .unwrap_or(0);
+            for (alias, spec) in tools {
+                let padding = " ".repeat(longest_alias_len - alias.name().len());
+                let used_index = used_by.get(alias).copied().unwrap_or(index);
+                if used_index == index {
+                    println!(
+                        "  {bullet} {}{padding} {arrow} {}{at}{}",
+                        style(alias.name()).bold().cyan(),
+                        spec.id(),
+                        spec.version(),
+                    );
+                } else {
+                    let used_path = display_path(home, &cwd, &manifests[used_index].path);
+                    println!(
+                        "  {bullet} {}",
+                        style(format!(
+                            "{}{padding} → {spec} (shadowed by {used_path})",
+                            alias.name()
+                        ))
+                        .dim(),
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn display_path(home: &Home, cwd: &Path, path: &Path) -> String {
+    if let Ok(stripped) = path.strip_prefix(home.path()) {
+        format!("~/.rokit/{}", stripped.display())
+    } else if let Ok(stripped) = path.strip_prefix(cwd) {
+        format!("./{}", stripped.display())
+    } else {
+        path.display().to_string()
+    }
+}
@@ -1,76 +1,179 @@
-use anyhow::{bail, Result};
-use clap::Parser;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
 use console::style;
+use tokio::fs::{read_to_string, write};
 
-use rokit::{storage::Home, tool::ToolId};
+use rokit::{
+    storage::{Home, TrustList},
+    tool::ToolId,
+};
 
 use crate::util::CliProgressTracker;
 
 /// Mark the given tool(s) as being trusted.
 #[derive(Debug, Parser)]
+#[clap(args_conflicts_with_subcommands = true)]
 pub struct TrustSubcommand {
     /// The tool(s) to mark as trusted.
     pub tools: Vec<ToolId>,
+    /// Trust the tool(s) again, forgetting the artifact hashes recorded when
+    /// they were first installed. This allows installing a release that has
+    /// changed since then, such as one that was re-tagged by its author.
+    #[clap(long)]
+    pub retrust: bool,
+    #[clap(subcommand)]
+    pub command: Option<TrustCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TrustCommand {
+    Import(TrustImportSubcommand),
+    Export(TrustExportSubcommand),
 }
 
 impl TrustSubcommand {
     pub async fn run(self, home: &Home) -> Result<()> {
-        if self.tools.is_empty() {
-            bail!("Please provide at least one tool to trust.");
+        match self.command {
+            Some(TrustCommand::Import(cmd)) => cmd.run(home).await,
+            Some(TrustCommand::Export(cmd)) => cmd.run(home).await,
+            None => trust_tools(home, self.tools, self.retrust),
+        }
+    }
+}
+
+/// Marks all tools listed in a file as trusted.
+///
+/// The file may either contain one tool identifier per line, or be
+/// a TOML file with a `trusted` array of tool identifiers. Empty
+/// lines and lines starting with `#` are ignored in plain files.
+#[derive(Debug, Parser)]
+pub struct TrustImportSubcommand {
+    /// The file to import trusted tools from.
+    pub file: PathBuf,
+}
+
+impl TrustImportSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let contents = read_to_string(&self.file)
+            .await
+            .with_context(|| format!("Failed to read trust file at {}", self.file.display()))?;
+        let list = contents
+            .parse::<TrustList>()
+            .with_context(|| format!("Failed to parse trust file at {}", self.file.display()))?;
+        if list.is_empty() {
+            bail!(
+                "The trust file at {} does not contain any tools.",
+                self.file.display()
+            );
         }
+        trust_tools(home, list.tools(), false)
+    }
+}
+
+/// Exports all currently trusted tools to a file, or prints them.
+///
+/// Files ending in `.toml` are written as TOML, any other
+/// file is written with one tool identifier per line.
+#[derive(Debug, Parser)]
+pub struct TrustExportSubcommand {
+    /// The file to export trusted tools to.
+    /// Trusted tools are printed if no file is given.
+    pub file: Option<PathBuf>,
+}
+
+impl TrustExportSubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let list = TrustList::new(home.tool_cache().all_trusted());
 
-        // NOTE: We use a progress bar only to show the final message to the
-        // user below, to maintain consistent formatting with other commands.
-        let pt = CliProgressTracker::new_with_message("Trusting", 1);
-
-        let cache = home.tool_cache();
-        let (added_tools, existing_tools) = self
-            .tools
-            .into_iter()
-            .partition::<Vec<_>, _>(|tool| cache.add_trust(tool.clone()));
-
-        if added_tools.len() == 1 && existing_tools.is_empty() {
-            // Special case 1 with shorter output - a singular tool was added
-            pt.finish_with_message(format!(
-                "Tool {} is now trusted {}",
-                added_tools[0],
-                pt.formatted_elapsed(),
-            ));
-        } else if existing_tools.len() == 1 && added_tools.is_empty() {
-            // Special case 2 with shorter output - a singular tool was already trusted
-            pt.finish_with_message(format!(
-                "Tool {} was already trusted {}",
-                existing_tools[0],
-                pt.formatted_elapsed(),
-            ));
+        let Some(file) = self.file else {
+            print!("{list}");
+            return Ok(());
+        };
+
+        let is_toml = file.extension().is_some_and(|ext| ext == "toml");
+        let contents = if is_toml {
+            list.to_toml_string()
         } else {
-            // General case with multiple tools added and/or already trusted
-            let mut lines = Vec::new();
-            let list_bullet = style("•").dim();
-
-            if !added_tools.is_empty() {
-                lines.push(String::from("These tools are now trusted:"));
-                for tool in &added_tools {
-                    lines.push(format!("  {list_bullet} {tool}"));
-                }
-            }
+            list.to_string()
+        };
+        write(&file, contents)
+            .await
+            .with_context(|| format!("Failed to write trust file at {}", file.display()))?;
+
+        println!(
+            "Exported {} trusted tool{} to {}",
+            list.len(),
+            if list.len() == 1 { "" } else { "s" },
+            style(file.display()).bold().cyan()
+        );
+
+        Ok(())
+    }
+}
+
+fn trust_tools(home: &Home, tools: Vec<ToolId>, retrust: bool) -> Result<()> {
+    if tools.is_empty() {
+        bail!("Please provide at least one tool to trust.");
+    }
+    if retrust {
+        for tool in &tools {
+            let forgotten = home.tool_cache().forget_artifact_hashes(tool);
+            tracing::debug!(%tool, forgotten, "forgot artifact hashes for tool");
+        }
+    }
+
+    // NOTE: We use a progress bar only to show the final message to the
+    // user below, to maintain consistent formatting with other commands.
+    let pt = CliProgressTracker::new_with_message("Trusting", 1);
+
+    let cache = home.tool_cache();
+    let (added_tools, existing_tools) = tools
+        .into_iter()
+        .partition::<Vec<_>, _>(|tool| cache.add_trust(tool.clone()));
+
+    if added_tools.len() == 1 && existing_tools.is_empty() {
+        // Special case 1 with shorter output - a singular tool was added
+        pt.finish_with_message(format!(
+            "Tool {} is now trusted {}",
+            added_tools[0],
+            pt.formatted_elapsed(),
+        ));
+    } else if existing_tools.len() == 1 && added_tools.is_empty() {
+        // Special case 2 with shorter output - a singular tool was already trusted
+        pt.finish_with_message(format!(
+            "Tool {} was already trusted {}",
+            existing_tools[0],
+            pt.formatted_elapsed(),
+        ));
+    } else {
+        // General case with multiple tools added and/or already trusted
+        let mut lines = Vec::new();
+        let list_bullet = style("•").dim();
 
-            if !existing_tools.is_empty() {
-                lines.push(String::from("These tools were already trusted:"));
-                for tool in &existing_tools {
-                    lines.push(format!("  {list_bullet} {tool}"));
-                }
+        if !added_tools.is_empty() {
+            lines.push(String::from("These tools are now trusted:"));
+            for tool in &added_tools {
+                lines.push(format!("  {list_bullet} {tool}"));
             }
+        }
 
-            pt.finish_with_message(format!(
-                "Changed trust for {} tool{} {}\n\n{}",
-                added_tools.len(),
-                if added_tools.len() == 1 { "" } else { "s" },
-                pt.formatted_elapsed(),
-                lines.join("\n")
-            ));
+        if !existing_tools.is_empty() {
+            lines.push(String::from("These tools were already trusted:"));
+            for tool in &existing_tools {
+                lines.push(format!("  {list_bullet} {tool}"));
+            }
         }
 
-        Ok(())
+        pt.finish_with_message(format!(
+            "Changed trust for {} tool{} {}\n\n{}",
+            added_tools.len(),
+            if added_tools.len() == 1 { "" } else { "s" },
+            pt.formatted_elapsed(),
+            lines.join("\n")
+        ));
     }
+
+    Ok(())
 }
@@ -0,0 +1,149 @@
+use std::{
+    env::{join_paths, split_paths, var_os},
+    process::exit,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use console::style;
+
+use rokit::{
+    manifests::PolicyManifest,
+    storage::Home,
+    system::{current_dir, run_interruptible_with_env},
+    tool::{ToolAlias, ToolId},
+    warnings::{RokitWarning, RokitWarnings},
+    ResolveConstraints,
+};
+
+use crate::util::{
+    find_most_compatible_artifact, print_warnings, prompt_for_trust, resolve_headless_trust,
+    CliProgressTracker, ToolIdOrSpec,
+};
+
+/// Tries out a tool without installing it or adding it to any manifest.
+///
+/// The tool is downloaded into a temporary directory, which is added to
+/// the PATH while running the given command, and removed afterwards.
+#[derive(Debug, Parser)]
+pub struct TrySubcommand {
+    /// A tool identifier or specification describing where
+    /// to get the tool, and optionally what version to try.
+    pub tool: ToolIdOrSpec,
+    /// The name that will be used to run the tool.
+    #[clap(long)]
+    pub alias: Option<ToolAlias>,
+    /// The command to run, given after `--`, such as `-- rojo --version`.
+    #[clap(last = true, required = true)]
+    pub command: Vec<String>,
+}
+
+impl TrySubcommand {
+    pub async fn run(self, home: &Home) -> Result<()> {
+        let id: ToolId = self.tool.clone().into();
+        let alias: ToolAlias = match self.alias.as_ref() {
+            Some(alias) => alias.clone(),
+            None => self.tool.clone().into(),
+        };
+
+        let source = home.artifact_source().await?;
+        let policy = PolicyManifest::load_or_create(home.path()).await?;
+        let headless_trust = resolve_headless_trust(&policy)?;
+
+        // 1. Check for trust, or prompt the user to trust the tool - note that
+        // trusting it here only applies to this run, since nothing is installed
+        if !home.tool_cache().is_trusted(&id)
+            && !prompt_for_trust(&source, home.tool_cache(), headless_trust, id.clone()).await?
+        {
+            bail!("Tool is not trusted - operation was aborted");
+        }
+
+        // 2. Find the release to try, same as `rokit add` would
+        let pt = CliProgressTracker::new_with_message("Fetching", 3);
+        let warnings = RokitWarnings::new();
+        let release_artifact = match &self.tool {
+            ToolIdOrSpec::Spec(spec) => {
+                if policy.is_yanked(spec) {
+                    warnings.push(RokitWarning::YankedVersion { spec: spec.clone() });
+                }
+                source.get_specific_release(spec).await?
+            }
+            ToolIdOrSpec::Id(id) => {
+                source
+                    .get_latest_release_excluding(id, &policy.yanked_versions(id))
+                    .await?
+            }
+            ToolIdOrSpec::Req(id, req) => {
+                let constraints =
+                    ResolveConstraints::matching(req.clone()).excluding(policy.yanked_versions(id));
+                source.get_latest_release_matching(id, &constraints).await?
+            }
+            ToolIdOrSpec::Url(url_source) => url_source.release_for(&url_source.tool_spec()),
+        };
+        let artifact = match &self.tool {
+            ToolIdOrSpec::Url(_) => release_artifact.artifacts[0].clone(),
+            _ => find_most_compatible_artifact(&release_artifact.artifacts, &id, &warnings)?,
+        };
+        let spec = artifact.tool_spec.clone();
+        pt.task_completed();
+
+        // 3. Download the tool into a sandbox, instead of into tool storage
+        let contents = source
+            .download_artifact_contents(&artifact)
+            .await
+            .with_context(|| format!("Failed to download contents for {spec}"))?;
+        pt.task_completed();
+        pt.update_message("Extracting");
+        let extracted = artifact
+            .extract_contents(contents)
+            .await
+            .with_context(|| format!("Failed to extract contents for {spec}"))?;
+        let sandbox = home
+            .tool_storage()
+            .create_sandbox(&alias, extracted)
+            .await
+            .context("Failed to create a temporary directory for the tool")?;
+        pt.task_completed();
+        pt.finish_with_message(format!(
+            "Trying version {} of tool {} {}",
+            style(spec.version()).bold().yellow(),
+            style(spec.name()).bold().magenta(),
+            pt.formatted_elapsed(),
+        ));
+        print_warnings(&warnings);
+
+        // 4. Run the command with the sandbox first in the PATH, so that it
+        // takes precedence over any installed tool with the same alias
+        let existing_path = var_os("PATH").unwrap_or_default();
+        let path = join_paths(
+            std::iter::once(sandbox.path().to_path_buf()).chain(split_paths(&existing_path)),
+        )
+        .context("Failed to add the tool to the PATH")?;
+
+        let (program, args) = self.command.split_first().expect("command is required");
+        let cwd = current_dir().await;
+        let program_path = which::which_in(program, Some(&path), &cwd).with_context(|| {
+            format!("Failed to find '{program}' - is it the name of the tool you are trying?")
+        })?;
+
+        let envs = vec![
+            ("PATH", path),
+            ("ROKIT_TOOL_DIR", sandbox.path().as_os_str().to_owned()),
+            ("ROKIT_TOOL_VERSION", spec.version().to_string().into()),
+        ];
+        let code = run_interruptible_with_env(&program_path, args, envs)
+            .await
+            .with_context(|| format!("Failed to run '{program}'"))?;
+
+        // 5. Clean up the sandbox, and exit with the same code as the command,
+        // which must happen in this order since exiting skips any destructors
+        sandbox
+            .close()
+            .context("Failed to remove the temporary directory for the tool")?;
+        if code != 0 {
+            exit(code);
+        }
+
+        Ok(())
+    }
+}
@@ -1,16 +1,37 @@
+use std::{
+    collections::HashMap,
+    io::{stderr, IsTerminal},
+};
+
 use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::Parser;
 use console::style;
-use futures::{stream::FuturesUnordered, TryStreamExt};
+use dialoguer::theme::ColorfulTheme;
+use futures::{
+    stream::{FuturesOrdered, FuturesUnordered},
+    StreamExt, TryStreamExt,
+};
+use semver::Version;
 
-use rokit::{discovery::discover_all_manifests, manifests::RokitManifest, storage::Home};
+use rokit::{
+    discovery::discover_all_manifests,
+    manifests::{PolicyManifest, RokitManifest},
+    sources::{github::ReleaseFilter, Artifact, ArtifactProvider, ArtifactSource, ReleaseNotes},
+    storage::Home,
+    tool::{ToolAlias, ToolId, ToolSpec},
+    warnings::RokitWarnings,
+    ResolveConstraints,
+};
 
 use crate::util::{
-    find_most_compatible_artifact, CliProgressTracker, ToolAliasOrIdOrSpec, ToolIdOrSpec,
+    find_most_compatible_artifact, format_age, format_release_summaries, print_release_changelogs,
+    print_warnings, CliProgressTracker, ToolAliasOrIdOrSpec, ToolIdOrSpec,
 };
 
 /// Updates all tools, or specific tools, to the latest version.
 #[derive(Debug, Parser)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct UpdateSubcommand {
     /// The tools to update - can be aliases, ids, or specifications.
     /// Omit to update all tools.
@@ -21,16 +42,51 @@ pub struct UpdateSubcommand {
     /// Check for updates without actually updating the tools.
     #[clap(long)]
     pub check: bool,
+    /// Display the full release notes for updated tools,
+    /// instead of only a short summary of each release.
+    #[clap(long)]
+    pub changelog: bool,
+    /// Explain how the new version of each tool was chosen, such as
+    /// using the release marked as latest, or the newest release.
+    #[clap(long)]
+    pub explain: bool,
+    /// Consider draft releases on GitHub when updating tools.
+    /// Drafts are only visible when authenticated.
+    #[clap(long)]
+    pub include_drafts: bool,
+    /// Consider prereleases on GitHub when updating tools,
+    /// regardless of the release channel that each tool follows.
+    #[clap(long)]
+    pub include_prereleases: bool,
+    /// Skip releases on GitHub that were published before this date,
+    /// such as `2024-05-01` or `2024-05-01T12:00:00Z`.
+    #[clap(long, value_name = "DATE", value_parser = parse_date)]
+    pub published_after: Option<DateTime<Utc>>,
 }
 
 impl UpdateSubcommand {
+    fn release_filter(&self) -> ReleaseFilter {
+        ReleaseFilter {
+            include_drafts: self.include_drafts,
+            include_prereleases: self.include_prereleases,
+            published_after: self.published_after,
+        }
+    }
+
     pub async fn run(self, home: &Home) -> Result<()> {
         // 1. Load tool source and the desired manifest
-        let source = home.artifact_source().await?;
+        let release_filter = self.release_filter();
+        let is_default_filter = release_filter.is_default();
+        let source = home
+            .artifact_source()
+            .await?
+            .with_github_release_filter(release_filter);
+        let policy = PolicyManifest::load_or_create(home.path()).await?;
+        let taps = home.taps().await?;
         let manifest_path = if self.global {
             home.path().to_path_buf()
         } else {
-            let non_global_manifests = discover_all_manifests(true, true).await;
+            let non_global_manifests = discover_all_manifests(&taps, true, true).await;
             non_global_manifests
                 .first()
                 .map(|m| m.path.parent().unwrap().to_path_buf())
@@ -49,9 +105,14 @@ impl UpdateSubcommand {
         // 2. Try to convert aliases into ids using existing tools,
         // or fill with existing tools if no tools were provided
         let tools = if self.tools.is_empty() {
+            // NOTE: Tools from direct URLs have no releases, and tools pinned
+            // to an exact release tag have no versions, so neither are updated
             manifest
-                .tool_specs()
+                .tool_specs_with_taps(&taps)
                 .iter()
+                .filter(|(_, spec)| {
+                    spec.provider() != ArtifactProvider::Url && spec.tag().is_none()
+                })
                 .cloned()
                 .map(|(alias, spec)| (alias, ToolIdOrSpec::Id(spec.id().clone())))
                 .collect::<Vec<_>>()
@@ -72,7 +133,7 @@ impl UpdateSubcommand {
                             ToolAliasOrIdOrSpec::Alias(_) => unreachable!(),
                         };
                         let found = manifest
-                            .tool_specs()
+                            .tool_specs_with_taps(&taps)
                             .iter()
                             .filter_map(|(a, s)| {
                                 if s.id() == &search_id {
@@ -101,7 +162,7 @@ impl UpdateSubcommand {
                         ToolAliasOrIdOrSpec::Id(id) => Ok((alias, id.into())),
                         ToolAliasOrIdOrSpec::Spec(spec) => Ok((alias, spec.into())),
                         ToolAliasOrIdOrSpec::Alias(alias) => {
-                            let spec = manifest.get_tool(&alias).with_context(|| {
+                            let spec = manifest.get_tool_with_taps(&alias, &taps).with_context(|| {
                                 format!(
                                 "No tool with the alias '{alias}' has been added to this project.\
                                 \nYou can add the tool to the project using `{}`.",
@@ -116,53 +177,159 @@ impl UpdateSubcommand {
                 .collect::<Result<Vec<_>>>()?
         };
         let pt = CliProgressTracker::new_with_message_and_subtasks("Fetching", tools.len(), 3);
+        let warnings = RokitWarnings::new();
 
         // 3. Fetch the latest or desired versions of the tools
-        let tool_releases = tools
+        let check = self.check;
+        let explain = self.explain;
+        let latest_versions = home.latest_versions();
+        let mut tool_releases = tools
             .into_iter()
             .map(|(alias, tool)| async {
-                let (alias, id, artifacts) = match tool {
+                let asset_exclude = manifest.get_tool_options(&alias).asset_exclude;
+                let (alias, spec_new, cached_at, explanation) = match tool {
                     ToolIdOrSpec::Spec(spec) => {
-                        let artifacts =
+                        let release =
                             source.get_specific_release(&spec).await.with_context(|| {
                                 format!(
                                     "Failed to fetch release for '{spec}'!\
                                     \nMake sure the given tool version exists."
                                 )
                             })?;
-                        (alias, spec.id().clone(), artifacts)
+                        let artifact = find_most_compatible_artifact(
+                            &Artifact::filter_excluded(&release.artifacts, &asset_exclude),
+                            spec.id(),
+                            &warnings,
+                        )?;
+                        let explanation = String::from("the exact version given");
+                        (alias, artifact.tool_spec, None, explanation)
                     }
-                    ToolIdOrSpec::Id(id) => {
-                        let artifacts =
-                            source.get_latest_release(&id).await.with_context(|| {
-                                format!(
-                                    "Failed to fetch latest release for '{id}'!\
-                                    \nMake sure the given tool identifier exists."
-                                )
-                            })?;
-                        (alias, id, artifacts)
+                    tool => {
+                        // NOTE: Tools added side by side with a version requirement,
+                        // such as `owner/repo@6`, must stay within that requirement
+                        let options = manifest.get_tool_options(&alias);
+                        let (id, version_req) = match tool {
+                            ToolIdOrSpec::Req(id, req) => (id, Some(req)),
+                            tool => (ToolId::from(tool), options.version),
+                        };
+                        let channel = options.channel.unwrap_or_default();
+                        let resolution = options.resolution.unwrap_or_default();
+                        let constraints = ResolveConstraints {
+                            version: version_req.clone(),
+                            excluded: policy.yanked_versions(&id),
+                            channel: channel.clone(),
+                            resolution,
+                        };
+                        let result = source.get_latest_release_matching(&id, &constraints).await;
+                        let is_latest_stable = channel.is_stable()
+                            && version_req.is_none()
+                            && resolution.is_latest_release()
+                            && is_default_filter;
+                        match result {
+                            Ok(release) => {
+                                if is_latest_stable {
+                                    latest_versions.record(&id, &release.version);
+                                }
+                                let artifact = find_most_compatible_artifact(
+                                    &Artifact::filter_excluded(&release.artifacts, &asset_exclude),
+                                    &id,
+                                    &warnings,
+                                )?;
+                                let explanation = if explain {
+                                    explain_resolution(
+                                        &source,
+                                        &id,
+                                        &constraints,
+                                        &release.version,
+                                        is_default_filter,
+                                    )
+                                    .await
+                                } else {
+                                    String::new()
+                                };
+                                (alias, artifact.tool_spec, None, explanation)
+                            }
+                            Err(e) => {
+                                // NOTE: When only checking for updates, such as while offline,
+                                // a previously fetched latest version is better than nothing
+                                let cached = latest_versions
+                                    .get(&id)
+                                    .filter(|_| check && is_latest_stable);
+                                let Some(cached) = cached else {
+                                    return Err(anyhow::Error::from(e).context(format!(
+                                        "Failed to fetch latest release for '{id}'!\
+                                        \nMake sure the given tool identifier exists."
+                                    )));
+                                };
+                                tracing::debug!(%id, "using cached latest version: {e}");
+                                let spec = ToolSpec::from((id, cached.version));
+                                let explanation =
+                                    String::from("the latest version found when last online");
+                                (alias, spec, Some(cached.checked_at), explanation)
+                            }
+                        }
                     }
                 };
 
-                let artifact = find_most_compatible_artifact(&artifacts.artifacts, &id)?;
                 pt.subtask_completed();
 
-                Ok::<_, anyhow::Error>((alias, id, artifact))
+                Ok::<_, anyhow::Error>((alias, spec_new, cached_at, explanation))
             })
             .collect::<FuturesUnordered<_>>()
             .try_collect::<Vec<_>>()
             .await?;
 
-        // 4. Check if the --check flag was used, and if so, check for updates
+        if self.explain {
+            let mut explained = tool_releases.iter().collect::<Vec<_>>();
+            explained.sort_by(|(a, _, _, _), (b, _, _, _)| a.cmp(b));
+            pt.suspend(|| {
+                let bullet = style("•").dim();
+                println!("How the new version of each tool was chosen:\n");
+                for (alias, spec_new, _, explanation) in explained {
+                    println!(
+                        "{bullet} {} {} - {}",
+                        style(alias.to_string()).bold().cyan(),
+                        style(spec_new.version()).bold().yellow(),
+                        style(explanation).dim(),
+                    );
+                }
+                println!();
+            });
+        }
+
+        // 4. Check if any tools have moved, such as GitHub repositories that
+        // were renamed or transferred, and offer to use their new locations
+        let moved_tools = find_moved_tools(
+            &source,
+            &manifest,
+            tool_releases.iter().map(|(a, _, _, _)| a),
+        )
+        .await;
+        if confirm_moved_tools(&pt, &moved_tools, check)? {
+            let tool_cache = home.tool_cache();
+            for (alias, spec_new, _, _) in &mut tool_releases {
+                let Some((_, id_old, id_new)) = moved_tools.iter().find(|(a, _, _)| a == alias)
+                else {
+                    continue;
+                };
+                // NOTE: The tool is still the same tool, so any
+                // trust it was given also carries over to its new id
+                if tool_cache.is_trusted(id_old) {
+                    let _ = tool_cache.add_trust(id_new.clone());
+                }
+                *spec_new = id_new.clone().into_spec(spec_new.version().clone());
+            }
+        }
+
+        // 5. Check if the --check flag was used, and if so, check for updates
         let tools_changed = tool_releases
             .iter()
-            .filter_map(|(alias, _, artifact)| {
-                let spec_old = manifest.get_tool(alias).unwrap();
-                let spec_new = artifact.tool_spec.clone();
-                if spec_old == spec_new {
+            .filter_map(|(alias, spec_new, _, _)| {
+                let spec_old = manifest.get_tool_with_taps(alias, &taps).unwrap();
+                if &spec_old == spec_new {
                     None
                 } else {
-                    Some((alias.clone(), spec_old, spec_new))
+                    Some((alias.clone(), spec_old, spec_new.clone()))
                 }
             })
             .collect::<Vec<_>>();
@@ -170,11 +337,21 @@ impl UpdateSubcommand {
             let bullet = style("•").dim();
             let arrow = style("→").dim();
 
+            let cached_at = tool_releases
+                .iter()
+                .filter_map(|(alias, _, cached_at, _)| Some((alias.clone(), (*cached_at)?)))
+                .collect::<HashMap<_, _>>();
+
             let updated_tool_lines = tools_changed
                 .iter()
                 .map(|(alias, spec_old, spec_new)| {
+                    let cached_suffix = cached_at.get(alias).map_or_else(String::new, |at| {
+                        style(format!(" (offline, last checked {})", format_age(*at)))
+                            .dim()
+                            .to_string()
+                    });
                     format!(
-                        "{bullet} {} {} {arrow} {}",
+                        "{bullet} {} {} {arrow} {}{cached_suffix}",
                         style(alias.to_string()).bold().cyan(),
                         style(spec_old.version()).yellow(),
                         style(spec_new.version()).bold().yellow()
@@ -202,10 +379,11 @@ impl UpdateSubcommand {
                 ));
             }
             pt.subtask_completed();
+            print_warnings(&warnings);
             return Ok(());
         }
 
-        // 5. Modify the manifest with the desired new tools, save
+        // 6. Modify the manifest with the desired new tools, save
         pt.update_message("Modifying");
 
         for (alias, _, spec_new) in &tools_changed {
@@ -214,27 +392,24 @@ impl UpdateSubcommand {
         }
         manifest.save(&manifest_path).await?;
 
-        // 6. Finally, display a nice message to the user
-        let tools_changed = tool_releases
-            .iter()
-            .filter_map(|(alias, _, artifact)| {
-                let spec_old = manifest.get_tool(alias).unwrap();
-                let spec_new = artifact.tool_spec.clone();
-                if spec_old == spec_new {
-                    None
-                } else {
-                    Some((alias.clone(), spec_old, spec_new))
-                }
-            })
-            .collect::<Vec<_>>();
+        // 7. Display a nice message to the user - note that we use the
+        // changed tools from before modifying the manifest, since the
+        // manifest now contains the new tool specs and not the old ones
         let bullet = style("•").dim();
         let arrow = style("→").dim();
 
         let updated_tool_lines = tools_changed
             .iter()
             .map(|(alias, spec_old, spec_new)| {
+                let moved_suffix = if spec_old.id() == spec_new.id() {
+                    String::new()
+                } else {
+                    style(format!(" (moved to {})", spec_new.id()))
+                        .dim()
+                        .to_string()
+                };
                 format!(
-                    "{bullet} {} {} {arrow} {}",
+                    "{bullet} {} {} {arrow} {}{moved_suffix}",
                     style(alias.to_string()).bold().cyan(),
                     style(spec_old.version()).yellow(),
                     style(spec_new.version()).bold().yellow()
@@ -260,8 +435,224 @@ impl UpdateSubcommand {
             ));
         }
 
+        print_warnings(&warnings);
+
+        // 8. Fetch and display release notes between the old and new versions
+        let release_notes = fetch_release_notes(&source, &tools_changed).await;
+        if !release_notes.is_empty() {
+            if self.changelog {
+                print_release_notes_expanded(&release_notes)?;
+            } else {
+                print_release_notes_collapsed(&release_notes);
+            }
+        }
+
         // FUTURE: Install the newly updated tools automatically
 
         Ok(())
     }
 }
+
+fn parse_date(s: &str) -> Result<DateTime<Utc>, String> {
+    let s = s.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(s) {
+        return Ok(date.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc())
+        .ok_or_else(|| format!("'{s}' is not a valid date, such as 2024-05-01"))
+}
+
+type MovedTools = Vec<(ToolAlias, ToolId, ToolId)>;
+
+async fn find_moved_tools(
+    source: &ArtifactSource,
+    manifest: &RokitManifest,
+    aliases: impl Iterator<Item = &ToolAlias>,
+) -> MovedTools {
+    aliases
+        .filter_map(|alias| {
+            // NOTE: Tools specified using a short name from a tap are kept
+            // up to date by the tap, and never have their ids rewritten here
+            let id = manifest.get_tool(alias)?.id().clone();
+            Some((alias.clone(), id))
+        })
+        .map(|(alias, id)| async move {
+            // NOTE: Moved tools keep working for as long as the old location
+            // redirects, so failing to check should never make the update fail
+            match source.get_moved_id(&id).await {
+                Ok(moved) => moved.map(|moved| (alias, id, moved)),
+                Err(e) => {
+                    tracing::debug!(%id, "failed to check if tool has moved: {e}");
+                    None
+                }
+            }
+        })
+        .collect::<FuturesOrdered<_>>()
+        .filter_map(|moved| async move { moved })
+        .collect()
+        .await
+}
+
+fn confirm_moved_tools(
+    pt: &CliProgressTracker,
+    moved_tools: &MovedTools,
+    check: bool,
+) -> Result<bool> {
+    if moved_tools.is_empty() {
+        return Ok(false);
+    }
+
+    let bullet = style("•").dim();
+    let arrow = style("→").dim();
+    let moved_tool_lines = moved_tools
+        .iter()
+        .map(|(alias, id_old, id_new)| {
+            format!(
+                "{bullet} {} {} {arrow} {}",
+                style(alias.to_string()).bold().cyan(),
+                style(id_old).yellow(),
+                style(id_new).bold().yellow()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    pt.suspend(|| {
+        eprintln!(
+            "{} tool{} moved to a new location:\n\n{moved_tool_lines}\n",
+            style(moved_tools.len()).bold().magenta(),
+            if moved_tools.len() == 1 {
+                " has"
+            } else {
+                "s have"
+            },
+        );
+    });
+
+    if check || !stderr().is_terminal() {
+        pt.suspend(|| {
+            eprintln!(
+                "Moved tools keep working for now, but may stop working if their old\
+                \nlocations are ever reused. Run `{}` to use the new locations.\n",
+                style("rokit update").bold().green(),
+            );
+        });
+        return Ok(false);
+    }
+
+    let confirmed = pt.suspend(|| {
+        dialoguer::Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Update the manifest to use the new locations?")
+            .default(true)
+            .interact_opt()
+    })?;
+    Ok(confirmed == Some(true))
+}
+
+/*
+    Explains how the given version of a tool was chosen using the given
+    constraints - for tools using the latest release marker on GitHub,
+    this also mentions any newer release that the marker skipped over.
+*/
+async fn explain_resolution(
+    source: &ArtifactSource,
+    id: &ToolId,
+    constraints: &ResolveConstraints,
+    version: &Version,
+    is_default_filter: bool,
+) -> String {
+    if let Some(req) = &constraints.version {
+        return format!("the newest release matching the version requirement {req}");
+    }
+    if !constraints.channel.is_stable() {
+        return format!("the newest release in the {} channel", constraints.channel);
+    }
+    if !constraints.resolution.is_latest_release() {
+        return format!(
+            "the newest release, since the tool uses resolution = \"{}\"",
+            constraints.resolution
+        );
+    }
+    match id.provider() {
+        ArtifactProvider::GitHub if is_default_filter => {}
+        ArtifactProvider::GitHub => {
+            return String::from("the newest release allowed by the release filter")
+        }
+        ArtifactProvider::GitLab => return String::from("the most recently published release"),
+        _ => return String::from("the newest release"),
+    }
+
+    // NOTE: Some repositories mark an older, long-term support release as
+    // the latest, so we check if any newer release was skipped over - only
+    // recent releases are listed, which is plenty to find those, if any
+    let newer = source
+        .get_recent_releases_in_channel(id, &constraints.channel)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|release| release.version)
+        .find(|v| v > version && constraints.allows(v));
+    match newer {
+        Some(newer) => format!(
+            "the release marked as latest on GitHub, even though {newer} is newer\
+            \n  set resolution = \"newest-tag\" for the tool to update to newer releases"
+        ),
+        None => String::from("the release marked as latest on GitHub"),
+    }
+}
+
+type ToolReleaseNotes = Vec<(ToolAlias, Vec<ReleaseNotes>)>;
+
+async fn fetch_release_notes(
+    source: &ArtifactSource,
+    tools_changed: &[(ToolAlias, ToolSpec, ToolSpec)],
+) -> ToolReleaseNotes {
+    tools_changed
+        .iter()
+        .map(|(alias, spec_old, spec_new)| async move {
+            // NOTE: Release notes are purely informational, so failing
+            // to fetch them should never make the update itself fail
+            let notes = match source
+                .get_release_notes_between(spec_new.id(), spec_old.version(), spec_new.version())
+                .await
+            {
+                Ok(notes) => notes,
+                Err(e) => {
+                    tracing::debug!(id = %spec_new.id(), "failed to fetch release notes: {e}");
+                    return None;
+                }
+            };
+            if notes.is_empty() {
+                None
+            } else {
+                Some((alias.clone(), notes))
+            }
+        })
+        .collect::<FuturesOrdered<_>>()
+        .filter_map(|notes| async move { notes })
+        .collect()
+        .await
+}
+
+fn print_release_notes_collapsed(release_notes: &ToolReleaseNotes) {
+    let mut lines = Vec::new();
+    for (alias, notes) in release_notes {
+        lines.push(style(alias.to_string()).bold().cyan().to_string());
+        lines.extend(format_release_summaries(notes));
+    }
+
+    println!(
+        "\n📋 Release notes for the updated tools:\n\n{}\n\nRun with `{}` to view full release notes.",
+        lines.join("\n"),
+        style("--changelog").bold().green(),
+    );
+}
+
+fn print_release_notes_expanded(release_notes: &ToolReleaseNotes) -> Result<()> {
+    for (alias, notes) in release_notes {
+        print_release_changelogs(&alias.to_string(), notes)?;
+    }
+    Ok(())
+}
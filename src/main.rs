@@ -9,6 +9,7 @@ mod util;
 
 use self::cli::Cli;
 use self::runner::Runner;
+use self::util::{delegate_to_pinned_version, manifest_code_frame};
 
 #[tokio::main]
 async fn main() {
@@ -20,12 +21,20 @@ async fn main() {
           run that executable and pipe its output back
         - If it is not wrapping a tool executable, it will
           run a CLI interface for managing / installing tools
+
+        Before running the CLI, Rokit will also check if the current
+        project is pinned to a different version of Rokit, and if
+        so, delegate to that version instead of running the CLI.
     */
     let runner = Runner::new();
     let result = if runner.should_run() {
         runner.run().await
     } else {
-        Cli::parse().run().await
+        match delegate_to_pinned_version().await {
+            Ok(Some(code)) => exit(code),
+            Ok(None) => Cli::parse().run().await,
+            Err(e) => Err(e),
+        }
     };
 
     /*
@@ -37,7 +46,11 @@ async fn main() {
         respective `run` methods for the `Cli` and `Runner` structs.
     */
     if let Err(e) = result {
-        error!("{e:?}");
+        if let Some(frame) = manifest_code_frame(&e) {
+            error!("{e:?}\n\n{frame}");
+        } else {
+            error!("{e:?}");
+        }
         exit(1);
     }
 }
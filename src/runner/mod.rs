@@ -1,16 +1,16 @@
-use std::{env::args, process::exit, str::FromStr};
+use std::{env::args, ffi::OsString, process::exit, str::FromStr};
 
 use anyhow::{bail, Error, Result};
 use tracing::level_filters::LevelFilter;
 
 use rokit::{
-    discovery::{discover_non_rokit_tool, discover_tool_spec},
+    discovery::resolve_alias_for_dir,
     storage::Home,
-    system::{current_exe_name, run_interruptible},
+    system::{current_dir, current_exe_name, run_interruptible_with_env},
     tool::ToolAlias,
 };
 
-use crate::util::init_tracing;
+use crate::util::{init_colors, init_tracing, ColorChoice};
 
 mod info;
 
@@ -36,29 +36,43 @@ impl Runner {
         // Always log at INFO level when running a managed program
         // unless the user has explicitly set a different level
         // using the RUST_LOG environment variable.
+        init_colors(ColorChoice::Auto);
         init_tracing(LevelFilter::INFO);
 
         let alias = ToolAlias::from_str(&self.exe_name)?;
 
         let home = Home::load_from_env().await?;
-        let spec = discover_tool_spec(&alias, false, false).await;
+        let cwd = current_dir().await;
+        let resolved = resolve_alias_for_dir(&home, &cwd, &alias).await;
 
         let program_args = args().skip(1).collect::<Vec<_>>();
-        let program_path = match spec {
+        let mut program_envs = Vec::<(&str, OsString)>::new();
+        let program_path = match resolved {
             // TODO: Prompt for trust and install tool if not already installed
-            Some(spec) => home.tool_storage().tool_path(&spec),
+            Some(resolved) => {
+                if let Some(spec) = &resolved.spec {
+                    // Let tools know where they are stored, so that they can
+                    // find any resources that were bundled alongside them
+                    let tool_dir = home.tool_storage().tool_dir(spec);
+                    program_envs.push(("ROKIT_TOOL_DIR", tool_dir.into()));
+                    program_envs.push(("ROKIT_TOOL_VERSION", spec.version().to_string().into()));
+                    // NOTE: Usage statistics are only informational, and
+                    // must never prevent the tool itself from running
+                    if let Err(e) = home.record_tool_usage(spec).await {
+                        tracing::debug!(%spec, error = %e, "failed to record tool usage");
+                    }
+                }
+                resolved.path
+            }
             // FUTURE: Maybe we should add some kind of "fall-through" setting in
             // Rokit manifests instead of always falling through to non-rokit tools?
-            None => match discover_non_rokit_tool(&home, &alias).await {
-                Some(path) => path,
-                None => bail!(
-                    "Failed to find tool '{alias}' in any project manifest file.\
-                    \nAdd the tool to a project using 'rokit add' before running it."
-                ),
-            },
+            None => bail!(
+                "Failed to find tool '{alias}' in any project manifest file.\
+                \nAdd the tool to a project using 'rokit add' before running it."
+            ),
         };
 
-        let code = run_interruptible(&program_path, &program_args)
+        let code = run_interruptible_with_env(&program_path, &program_args, program_envs)
             .await
             .map_err(Error::from)
             .inspect_err(|e| inform_user_about_potential_fixes(&alias, e))?;
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use console::style;
+
+/**
+    Formats the age of the given time in a short, human-readable
+    way, such as `today`, `3 days ago`, or `2 years ago`.
+*/
+pub fn format_age(time: DateTime<Utc>) -> String {
+    let days = (Utc::now() - time).num_days().max(0);
+    let (amount, unit) = match days {
+        0 => return String::from("today"),
+        1..=30 => (days, "day"),
+        31..=364 => (days / 30, "month"),
+        _ => (days / 365, "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{} {unit}{plural} ago", style(amount).bold())
+}
@@ -1,42 +1,81 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use console::style;
 
 use rokit::{
     descriptor::{Arch, OS},
     sources::Artifact,
-    tool::ToolId,
+    storage::ToolCache,
+    tool::{ToolId, ToolSpec},
+    warnings::RokitWarnings,
 };
 
-pub fn find_most_compatible_artifact(artifacts: &[Artifact], tool_id: &ToolId) -> Result<Artifact> {
-    let mut artifact_opt = Artifact::sort_by_system_compatibility(artifacts)
-        .first()
-        .cloned();
+/**
+    Checks the hash of a downloaded artifact against the hash recorded when the
+    same tool version was first installed, recording it if this is the first install.
+
+    Fails with instructions for trusting the tool again if the hash has changed.
+*/
+pub fn verify_artifact_hash(tool_cache: &ToolCache, spec: &ToolSpec, sha256: &str) -> Result<()> {
+    tool_cache
+        .check_artifact_hash(spec, sha256)
+        .with_context(|| retrust_hint(spec))?;
+    Ok(())
+}
+
+/**
+    Creates instructions for trusting a tool again, after
+    its artifact hash has changed since it was first installed.
+*/
+pub fn retrust_hint(spec: &ToolSpec) -> String {
+    format!(
+        "Refusing to install {spec}, since its release has changed since it was first installed.\
+        \nThis may mean that the release was re-tagged, or that it has been tampered with.\
+        \nIf you are certain that the new release is safe, run `{}` and try again.",
+        style(format!("rokit trust --retrust {}", spec.id()))
+            .bold()
+            .green()
+    )
+}
+
+pub fn find_most_compatible_artifact(
+    artifacts: &[Artifact],
+    tool_id: &ToolId,
+    warnings: &RokitWarnings,
+) -> Result<Artifact> {
+    let artifact_opt = Artifact::find_most_compatible(artifacts, warnings);
 
     if artifact_opt.is_none() {
-        if let Some(artifact) = Artifact::find_partially_compatible_fallback(artifacts) {
-            tracing::debug!(
-                %tool_id,
-                name = %artifact.name.as_deref().unwrap_or("N/A"),
-                "found fallback artifact for tool",
-            );
-            artifact_opt.replace(artifact);
-        } else {
-            // If we failed to find an artifact compatible with the current system,
-            // we may be able to give additional information to Rokit's users, or tool
-            // maintainers who want to be Rokit-compatible, by examining the artifacts
-            let artifact_names = artifacts
-                .iter()
-                .filter_map(|artifact| artifact.name.as_deref())
-                .collect::<Vec<_>>();
-            tracing::debug!(
-                %tool_id,
-                missing_os_all = %artifact_names.iter().all(|s| OS::detect(s).is_none()),
-                missing_arch_all = %artifact_names.iter().all(|s| Arch::detect(s).is_none()),
-                "missing compatible artifact or fallback for tool"
-            );
-        }
+        // If we failed to find an artifact compatible with the current system,
+        // we may be able to give additional information to Rokit's users, or tool
+        // maintainers who want to be Rokit-compatible, by examining the artifacts
+        let artifact_names = artifacts
+            .iter()
+            .filter_map(|artifact| artifact.name.as_deref())
+            .collect::<Vec<_>>();
+        tracing::debug!(
+            %tool_id,
+            missing_os_all = %artifact_names.iter().all(|s| OS::detect(s).is_none()),
+            missing_arch_all = %artifact_names.iter().all(|s| Arch::detect(s).is_none()),
+            "missing compatible artifact or fallback for tool"
+        );
     }
 
     // If we did not find a compatible artifact, either directly
     // or through a fallback mechanism, this should be a hard error
-    artifact_opt.with_context(|| format!("No compatible artifact found for {tool_id}"))
+    let artifact =
+        artifact_opt.with_context(|| format!("No compatible artifact found for {tool_id}"))?;
+
+    // Installers are always chosen last, so finding one here means that
+    // there is nothing else we could possibly install for this system
+    if artifact.is_installer() {
+        bail!(
+            "Only installers were found for {tool_id}, such as '{}', which Rokit can not install.\
+            \nUse a different build of the tool, for example from another repository using the \
+            'platforms' option in your manifest, or ask the tool maintainers to also publish \
+            archives or plain binaries in their releases.",
+            artifact.name.as_deref().unwrap_or_default()
+        );
+    }
+
+    Ok(artifact)
 }
@@ -0,0 +1,86 @@
+use std::{
+    env::var_os,
+    io::{stderr, stdout, IsTerminal},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use clap::ValueEnum;
+
+static COLORS_ENABLED_STDERR: AtomicBool = AtomicBool::new(true);
+
+/**
+    When to use colors and other styling in output.
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Use colors if the output is a terminal that supports them.
+    #[default]
+    Auto,
+    /// Always use colors, even if the output is not a terminal.
+    Always,
+    /// Never use colors.
+    Never,
+}
+
+impl ColorChoice {
+    /**
+        Resolves the color choice for an output stream, taking the
+        `NO_COLOR` and `CLICOLOR_FORCE` environment variables into
+        account when set to `auto`, as well as dumb terminals.
+
+        See <https://no-color.org> and <https://bixense.com/clicolors>.
+    */
+    fn resolve(self, is_terminal: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                if env_is_set("NO_COLOR") {
+                    false
+                } else if env_is_set("CLICOLOR_FORCE") && !env_is("CLICOLOR_FORCE", "0") {
+                    true
+                } else {
+                    is_terminal && !is_dumb_terminal() && !env_is("CLICOLOR", "0")
+                }
+            }
+        }
+    }
+}
+
+/**
+    Initializes colors and styling for all output - prompts, progress
+    bars, errors, and anything else using `console` or `tracing`.
+
+    Must be called before anything is written to the terminal.
+*/
+pub fn init(choice: ColorChoice) {
+    let stdout_enabled = choice.resolve(stdout().is_terminal());
+    let stderr_enabled = choice.resolve(stderr().is_terminal());
+    console::set_colors_enabled(stdout_enabled);
+    console::set_colors_enabled_stderr(stderr_enabled);
+    COLORS_ENABLED_STDERR.store(stderr_enabled, Ordering::SeqCst);
+}
+
+/**
+    Checks if colors should be used for output written to stderr.
+*/
+pub fn colors_enabled_stderr() -> bool {
+    COLORS_ENABLED_STDERR.load(Ordering::SeqCst)
+}
+
+/**
+    Checks if the current terminal is a "dumb" terminal, which
+    can not move the cursor or redraw lines, and where animated
+    output such as progress bars should not be displayed.
+*/
+pub fn is_dumb_terminal() -> bool {
+    env_is("TERM", "dumb")
+}
+
+fn env_is_set(name: &str) -> bool {
+    var_os(name).is_some_and(|value| !value.is_empty())
+}
+
+fn env_is(name: &str, expected: &str) -> bool {
+    var_os(name).is_some_and(|value| value == expected)
+}
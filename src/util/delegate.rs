@@ -0,0 +1,173 @@
+use std::{
+    env::{args_os, var_os},
+    ffi::OsString,
+};
+
+use anyhow::{bail, Context, Result};
+use console::style;
+use semver::Version;
+
+use rokit::{
+    discovery::discover_rokit_version,
+    manifests::PolicyManifest,
+    sources::Artifact,
+    storage::Home,
+    system::run_interruptible_with_env,
+    tool::{ToolId, ToolSpec},
+    warnings::RokitWarnings,
+};
+
+use super::{find_most_compatible_artifact, prompt_for_trust, resolve_headless_trust};
+
+const DELEGATED_ENV_VAR: &str = "ROKIT_DELEGATED";
+
+/**
+    Gets the tool id for Rokit itself, using the repository URL in the package manifest.
+*/
+pub fn rokit_tool_id() -> Result<ToolId> {
+    let repo = env!("CARGO_PKG_REPOSITORY")
+        .trim_start_matches("https://github.com/")
+        .trim_end_matches(".git");
+    let Ok(tool_id) = repo.parse::<ToolId>() else {
+        bail!(
+            "Failed to parse manifest repository URL!\
+            \nThis is a bug in Rokit, please report it at:
+            \n{repo}"
+        );
+    };
+    Ok(tool_id)
+}
+
+/**
+    Delegates to the version of Rokit that the current project is pinned to, if
+    it differs from the currently running version, downloading it if necessary.
+
+    Returns the exit code of the pinned version if it was run, or `None` if
+    the currently running version of Rokit should continue as usual.
+*/
+pub async fn delegate_to_pinned_version() -> Result<Option<i32>> {
+    // NOTE: A pinned version that delegates to yet another version would loop
+    // forever, and self-install / self-update must always apply to the actual
+    // Rokit binary that is being run, so never delegate in those cases.
+    if var_os(DELEGATED_ENV_VAR).is_some() {
+        return Ok(None);
+    }
+    let args = args_os().skip(1).collect::<Vec<_>>();
+    let is_self_command = args
+        .iter()
+        .find(|arg| !arg.to_string_lossy().starts_with('-'))
+        .is_some_and(|arg| arg.to_string_lossy().starts_with("self-"));
+    if is_self_command {
+        return Ok(None);
+    }
+
+    let Some((manifest_path, version)) = discover_rokit_version().await else {
+        return Ok(None);
+    };
+    let version_current = env!("CARGO_PKG_VERSION").parse::<Version>().unwrap();
+    if version == version_current {
+        return Ok(None);
+    }
+
+    // NOTE: The CLI has not been parsed yet, since the pinned version may have
+    // different arguments, but the profile must still be respected if given
+    let home = match profile_from_args(&args) {
+        Some(profile) => Home::load_from_env_with_profile(Some(&profile)).await?,
+        None => Home::load_from_env().await?,
+    };
+    let spec = ToolSpec::from((rokit_tool_id()?, version));
+    let tool_cache = home.tool_cache();
+    let tool_storage = home.tool_storage();
+    let tool_path = tool_storage.tool_path(&spec);
+
+    // NOTE: Any project may pin a version of Rokit, and running it is the same as
+    // running any other tool from that project, so it must be trusted the same way
+    if !tool_cache.is_trusted(spec.id()) {
+        let source = home.artifact_source().await?;
+        let policy = PolicyManifest::load_or_create(home.path()).await?;
+        let headless_trust = resolve_headless_trust(&policy)?;
+        eprintln!(
+            "Rokit {} is pinned by {}",
+            style(spec.version()).bold().magenta(),
+            style(manifest_path.display()).dim(),
+        );
+        if !prompt_for_trust(&source, tool_cache, headless_trust, spec.id().clone()).await? {
+            bail!(
+                "Rokit {} is not trusted - operation was aborted\
+                \nRemove the pinned version from {} to use the current version.",
+                spec.version(),
+                manifest_path.display()
+            );
+        }
+        let _ = tool_cache.add_trust(spec.id().clone());
+    }
+
+    if !tool_path.exists() {
+        eprintln!(
+            "Downloading Rokit {}, pinned by {}",
+            style(spec.version()).bold().magenta(),
+            style(manifest_path.display()).dim(),
+        );
+
+        let source = home.artifact_source().await?;
+        let release = source
+            .get_specific_release(&spec)
+            .await
+            .with_context(|| format!("Failed to fetch pinned Rokit release '{spec}'"))?;
+        let artifact =
+            find_most_compatible_artifact(&release.artifacts, spec.id(), &RokitWarnings::new())?;
+        let contents = source
+            .download_artifact_contents(&artifact)
+            .await
+            .context("Failed to download pinned Rokit binary")?;
+
+        // NOTE: Same as for any other tool, the download must match the digest
+        // published alongside the release, and the hash recorded when it was
+        // first downloaded, since it will be run with the arguments given here
+        let sha256 = Artifact::compute_sha256(&contents);
+        if let Some(expected) = source.get_artifact_sha256(&artifact).await {
+            if expected != sha256 {
+                bail!(
+                    "Downloaded Rokit {} does not match its published digest!\
+                    \nExpected SHA-256 {expected}, got {sha256}",
+                    spec.version()
+                );
+            }
+        }
+        tool_cache.check_artifact_hash(&spec, &sha256)?;
+
+        let binary_contents = artifact
+            .extract_contents(contents)
+            .await
+            .context("Failed to extract pinned Rokit binary from archive")?;
+        tool_storage
+            .replace_tool_contents(&spec, binary_contents)
+            .await?;
+    }
+    home.save_if_dirty().await?;
+
+    // NOTE: Only the CLI is delegated - tools run through links always use the
+    // current version, since the pinned binary can not know which tool to run
+    let code = run_interruptible_with_env(&tool_path, &args, [(DELEGATED_ENV_VAR, "1")])
+        .await
+        .with_context(|| format!("Failed to run pinned Rokit version {}", spec.version()))?;
+
+    Ok(Some(code))
+}
+
+/**
+    Gets the profile given using `--profile` in the raw command line arguments, if any.
+*/
+fn profile_from_args(args: &[OsString]) -> Option<String> {
+    let mut args = args.iter().map(|arg| arg.to_string_lossy());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "--profile" {
+            return args.next().map(|profile| profile.to_string());
+        } else if let Some(profile) = arg.strip_prefix("--profile=") {
+            return Some(profile.to_string());
+        }
+    }
+    None
+}
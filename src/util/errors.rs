@@ -0,0 +1,22 @@
+use console::style;
+
+use rokit::{manifests::ManifestParseError, result::RokitError};
+
+/**
+    Renders a code frame for the given error, if it was
+    caused by a manifest file that is not valid TOML.
+*/
+pub fn manifest_code_frame(err: &anyhow::Error) -> Option<String> {
+    let parse_err = err.chain().find_map(|cause| {
+        if let Some(RokitError::ManifestParse(e)) = cause.downcast_ref::<RokitError>() {
+            Some(e.as_ref())
+        } else {
+            cause.downcast_ref::<ManifestParseError>()
+        }
+    })?;
+
+    // Highlight only the caret line, the rest of the frame should stay readable
+    let frame = parse_err.code_frame();
+    let (rest, caret) = frame.rsplit_once('\n')?;
+    Some(format!("{rest}\n{}", style(caret).red().bold()))
+}
@@ -1,8 +1,12 @@
 use std::str::FromStr;
 
+use semver::{Version, VersionReq};
 use serde_with::DeserializeFromStr;
 
-use rokit::tool::{ToolAlias, ToolId, ToolSpec};
+use rokit::{
+    sources::UrlSource,
+    tool::{ToolAlias, ToolId, ToolSpec},
+};
 
 use super::constants::get_known_tool;
 
@@ -10,6 +14,12 @@ use super::constants::get_known_tool;
     A tool identifier *or* specification, which includes
     the author, name, and *maybe* a version of a tool.
 
+    The version may also be a requirement, such as `owner/repo@6`,
+    which some commands use to pick the latest matching version.
+
+    Direct HTTPS URLs to an archive or raw binary are also accepted,
+    see [`UrlSource`] for how these are turned into tool ids.
+
     Can also be parsed from common Roblox tool aliases, meaning:
 
     - `rojo` will become `rojo-rbx/rojo`
@@ -20,16 +30,44 @@ use super::constants::get_known_tool;
 
     See [`ToolId`] and [`ToolSpec`] for more information.
 */
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, DeserializeFromStr)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, DeserializeFromStr)]
 pub enum ToolIdOrSpec {
     Id(ToolId),
     Spec(ToolSpec),
+    Req(ToolId, VersionReq),
+    Url(UrlSource),
+}
+
+impl ToolIdOrSpec {
+    /**
+        Replaces the tool id, keeping any version or version requirement.
+
+        Direct URLs are kept as they are, since their ids come from the URL itself.
+    */
+    pub fn with_id(self, id: ToolId) -> Self {
+        match self {
+            Self::Id(_) => Self::Id(id),
+            Self::Spec(spec) => Self::Spec(id.into_spec(spec.version().clone())),
+            Self::Req(_, req) => Self::Req(id, req),
+            Self::Url(source) => Self::Url(source),
+        }
+    }
 }
 
 impl FromStr for ToolIdOrSpec {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.contains('@') {
+        if s.starts_with("https://") || s.starts_with("http://") {
+            Ok(Self::Url(UrlSource::new(s.parse()?)?))
+        } else if let Some((id, version)) = s.split_once('@') {
+            // NOTE: Partial versions such as `6` or `6.1` are valid
+            // requirements, but should not be mistaken for full versions
+            let version = version.trim();
+            if version.parse::<Version>().is_err() {
+                if let Ok(req) = version.parse::<VersionReq>() {
+                    return Ok(Self::Req(id.trim().parse()?, req));
+                }
+            }
             Ok(Self::Spec(s.parse()?))
         } else if let Some(id) = get_known_tool(s) {
             Ok(Self::Id(id.clone()))
@@ -54,8 +92,9 @@ impl From<ToolSpec> for ToolIdOrSpec {
 impl From<ToolIdOrSpec> for ToolId {
     fn from(id_or_spec: ToolIdOrSpec) -> Self {
         match id_or_spec {
-            ToolIdOrSpec::Id(id) => id,
+            ToolIdOrSpec::Id(id) | ToolIdOrSpec::Req(id, _) => id,
             ToolIdOrSpec::Spec(spec) => spec.into(),
+            ToolIdOrSpec::Url(source) => source.tool_id().clone(),
         }
     }
 }
@@ -63,8 +102,9 @@ impl From<ToolIdOrSpec> for ToolId {
 impl From<ToolIdOrSpec> for ToolAlias {
     fn from(id_or_spec: ToolIdOrSpec) -> Self {
         let name = match id_or_spec {
-            ToolIdOrSpec::Id(id) => id.name().to_string(),
+            ToolIdOrSpec::Id(id) | ToolIdOrSpec::Req(id, _) => id.name().to_string(),
             ToolIdOrSpec::Spec(spec) => spec.name().to_string(),
+            ToolIdOrSpec::Url(source) => source.tool_id().name().to_string(),
         };
         Self::from_str(&name).expect("Derived alias is always valid")
     }
@@ -0,0 +1,31 @@
+use std::io::{stdout, BufWriter};
+
+use anyhow::{Context, Result};
+use pulldown_cmark::{Options, Parser as MarkdownParser};
+use pulldown_cmark_mdcat::{
+    resources::FileResourceHandler, Environment, Settings, TerminalProgram, TerminalSize, Theme,
+};
+use syntect::parsing::SyntaxSet;
+
+/**
+    Renders the given markdown contents to stdout, using
+    the capabilities of the current terminal where possible.
+*/
+pub fn print_markdown(contents: impl AsRef<str>) -> Result<()> {
+    pulldown_cmark_mdcat::push_tty(
+        &Settings {
+            terminal_capabilities: TerminalProgram::detect().capabilities(),
+            terminal_size: TerminalSize::detect().context("Failed to detect terminal size")?,
+            syntax_set: &SyntaxSet::load_defaults_newlines(),
+            theme: Theme::default(),
+        },
+        &Environment::for_local_directory(&tempfile::tempdir()?.path())?,
+        &FileResourceHandler::new(104_857_600), // TODO: Maybe make this be a DispatchingResourceHandler?
+        &mut BufWriter::new(stdout()),
+        MarkdownParser::new_ext(
+            contents.as_ref(),
+            Options::ENABLE_FOOTNOTES | Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+        ),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use chrono::{Duration as ChronoDuration, Utc};
+use tokio::time::timeout;
+use url::Url;
+
+use rokit::{
+    manifests::PolicyManifest,
+    result::RokitError,
+    sources::{HttpRequest, HttpResponse},
+    storage::{Home, MetricsRecord},
+};
+
+const POST_INTERVAL_HOURS: i64 = 24;
+const POST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/**
+    Records anonymous metrics for a single run of a command,
+    and posts them to the configured endpoint when it is time to.
+
+    Does nothing unless metrics are enabled in the policy manifest, and
+    never fails - metrics must never get in the way of running Rokit.
+*/
+pub async fn record_command_metrics(
+    home: &Home,
+    command: &str,
+    result: &anyhow::Result<()>,
+    duration: Duration,
+) {
+    let Ok(policy) = PolicyManifest::load(home.path()).await else {
+        return;
+    };
+    if !policy.metrics_enabled() {
+        return;
+    }
+
+    let record = MetricsRecord {
+        command: command.to_string(),
+        error_kind: result.as_ref().err().map(|e| error_kind(e).to_string()),
+        duration,
+    };
+    if let Err(e) = home.record_metrics(&record).await {
+        tracing::debug!(error = %e, "failed to record metrics");
+        return;
+    }
+
+    if let Some(endpoint) = policy.metrics_endpoint() {
+        let due = home.metrics_last_posted().await.map_or(true, |posted| {
+            Utc::now() - posted >= ChronoDuration::hours(POST_INTERVAL_HOURS)
+        });
+        if due {
+            if let Err(e) = post_metrics(home, endpoint).await {
+                tracing::debug!(error = %e, "failed to post metrics");
+            }
+        }
+    }
+}
+
+/*
+    Posts metrics using the same client as the artifact source, so that
+    the proxy, CA bundle, and offline mode from the policy manifest apply.
+*/
+async fn post_metrics(home: &Home, endpoint: Url) -> anyhow::Result<()> {
+    let body = home.metrics().await?.to_json()?;
+    let source = home.artifact_source().await?;
+    let request =
+        HttpRequest::post(endpoint.as_str(), body).header("content-type", "application/json");
+    timeout(POST_TIMEOUT, source.http_client().send(request))
+        .await?
+        .and_then(HttpResponse::error_for_status)
+        .map_err(RokitError::from)?;
+    home.set_metrics_last_posted().await?;
+    tracing::debug!("posted metrics");
+    Ok(())
+}
+
+fn error_kind(err: &anyhow::Error) -> &'static str {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<RokitError>())
+        .map_or("other", RokitError::kind)
+}
@@ -1,14 +1,38 @@
+mod age;
 mod alias_or_id_or_spec;
 mod artifacts;
+mod color;
 mod constants;
+mod delegate;
+mod errors;
 mod id_or_spec;
+mod markdown;
+mod metrics;
 mod progress;
 mod prompts;
+mod release_notes;
+mod report;
+mod size;
 mod tracing;
+mod version;
+mod warnings;
 
+pub use self::age::format_age;
 pub use self::alias_or_id_or_spec::ToolAliasOrIdOrSpec;
-pub use self::artifacts::find_most_compatible_artifact;
+pub use self::artifacts::{find_most_compatible_artifact, retrust_hint, verify_artifact_hash};
+pub use self::color::{init as init_colors, ColorChoice};
+pub use self::delegate::{delegate_to_pinned_version, rokit_tool_id};
+pub use self::errors::manifest_code_frame;
 pub use self::id_or_spec::ToolIdOrSpec;
+pub use self::markdown::print_markdown;
+pub use self::metrics::record_command_metrics;
 pub use self::progress::CliProgressTracker;
-pub use self::prompts::{prompt_for_trust, prompt_for_trust_specs};
+pub use self::prompts::{
+    prompt_for_trust, resolve_headless_trust, CliPrompter, TrustPromptKind, HEADLESS_TRUST_ENV_VAR,
+};
+pub use self::release_notes::{format_release_summaries, print_release_changelogs};
+pub use self::report::{InstallReport, InstallReportTool};
+pub use self::size::{format_byte_size, parse_byte_size};
 pub use self::tracing::init as init_tracing;
+pub use self::version::{VersionFormat, VersionInfo};
+pub use self::warnings::print_warnings;
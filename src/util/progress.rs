@@ -1,7 +1,9 @@
 use std::{fmt::Write, time::Duration};
 
 use console::style;
-use indicatif::{ProgressBar, ProgressState, ProgressStyle};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
+
+use super::color::is_dumb_terminal;
 
 const PROGRESS_BAR_CHARACTERS: &str = "█▉▊▋▌▍▎▏ ";
 const PROGRESS_BAR_TICKERS: &str = "⠙⠹⠸⠼⠴⠦⠧⠇⠏ ";
@@ -100,6 +102,14 @@ impl CliProgressTracker {
         self.inner.println(message.into());
     }
 
+    /**
+        Hides the progress bar while running the given function,
+        such as when prompting the user for input.
+    */
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.inner.suspend(f)
+    }
+
     /**
         Finishes the progress tracker with a final message.
 
@@ -115,11 +125,14 @@ impl CliProgressTracker {
         This will clear the progress bar and display the final message given.
     */
     pub fn finish_with_emoji_and_message(&self, emoji: &str, final_message: impl Into<String>) {
-        self.inner.println(format!(
-            "{} {}",
-            style(emoji).bold().green(),
-            final_message.into()
-        ));
+        let message = format!("{} {}", style(emoji).bold().green(), final_message.into());
+        // NOTE: Hidden progress bars do not print anything, so on
+        // dumb terminals we need to print the final message ourselves
+        if is_dumb_terminal() {
+            eprintln!("{message}");
+        } else {
+            self.inner.println(message);
+        }
         self.inner.finish_and_clear();
     }
 }
@@ -154,9 +167,16 @@ fn new_progress_bar(
         .with_style(new_progress_style(num_tasks, subtasks_per_task))
         .with_message(message.into());
 
-    pb.enable_steady_tick(Duration::from_millis(50));
     pb.set_length((num_tasks * subtasks_per_task) as u64);
-    pb.tick();
+
+    // Dumb terminals can not redraw lines, and would
+    // show every single tick as a new line of output
+    if is_dumb_terminal() {
+        pb.set_draw_target(ProgressDrawTarget::hidden());
+    } else {
+        pb.enable_steady_tick(Duration::from_millis(50));
+        pb.tick();
+    }
 
     pb
 }
@@ -1,79 +1,156 @@
 use std::{
-    collections::BTreeSet,
+    env::var,
     io::{stderr, IsTerminal},
 };
 
 use anyhow::{bail, Context, Result};
 use console::{style, Style};
 use dialoguer::theme::ColorfulTheme;
-use rokit::tool::{ToolId, ToolSpec};
+use futures::future::BoxFuture;
+use rokit::{
+    manifests::{HeadlessTrust, PolicyManifest},
+    prompt::{PromptError, Prompter, TrustPrompt, VersionPrompt},
+    sources::{ArtifactSource, ToolProvenance},
+    storage::ToolCache,
+    tool::ToolId,
+};
+use semver::Version;
 use tokio::task::spawn_blocking;
 
+use super::format_age;
+
+pub const HEADLESS_TRUST_ENV_VAR: &str = "ROKIT_HEADLESS_TRUST";
+
 #[derive(Debug, Clone, Copy)]
 pub enum TrustPromptKind {
     Install,
     InstallMany,
 }
 
-pub async fn prompt_for_trust(tool_id: ToolId) -> Result<bool> {
-    spawn_blocking(move || prompt_for_install_trust_inner(TrustPromptKind::Install, &tool_id))
-        .await?
+/**
+    Resolves what to do with untrusted tools when we can not prompt for trust.
+
+    The `ROKIT_HEADLESS_TRUST` environment variable takes
+    precedence over the setting in the policy manifest.
+*/
+pub fn resolve_headless_trust(policy: &PolicyManifest) -> Result<HeadlessTrust> {
+    match var(HEADLESS_TRUST_ENV_VAR) {
+        Ok(value) if !value.trim().is_empty() => value
+            .parse::<HeadlessTrust>()
+            .map_err(anyhow::Error::msg)
+            .with_context(|| format!("Invalid value for {HEADLESS_TRUST_ENV_VAR}")),
+        _ => Ok(policy.headless_trust()),
+    }
+}
+
+pub async fn prompt_for_trust(
+    source: &ArtifactSource,
+    tool_cache: &ToolCache,
+    headless: HeadlessTrust,
+    tool_id: ToolId,
+) -> Result<bool> {
+    let prompter = CliPrompter::new(TrustPromptKind::Install, headless);
+    let trusted = rokit::prompt::prompt_for_trust(&prompter, source, tool_cache, [tool_id]).await?;
+    Ok(!trusted.is_empty())
+}
+
+/**
+    Prompts for trust and versions in the terminal, or decides on trust
+    using the headless trust policy if the terminal is not interactive.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct CliPrompter {
+    kind: TrustPromptKind,
+    headless: HeadlessTrust,
+    interactive: bool,
+}
+
+impl CliPrompter {
+    pub fn new(kind: TrustPromptKind, headless: HeadlessTrust) -> Self {
+        Self {
+            kind,
+            headless,
+            interactive: stderr().is_terminal(),
+        }
+    }
 }
 
-pub async fn prompt_for_trust_specs(tool_specs: Vec<ToolSpec>) -> Result<Vec<ToolSpec>> {
-    spawn_blocking(move || {
-        if tool_specs.is_empty() {
-            Ok(Vec::new())
-        } else if tool_specs.len() == 1 {
-            println!("A tool is not yet trusted and needs your approval.");
-            let spec = tool_specs.first().unwrap();
-            if prompt_for_install_trust_inner(TrustPromptKind::Install, spec.id())? {
-                Ok(vec![spec.clone()])
-            } else {
-                Ok(Vec::new())
+impl Prompter for CliPrompter {
+    fn confirm_trust(&self, prompt: TrustPrompt) -> BoxFuture<'_, Result<bool, PromptError>> {
+        let this = *self;
+        Box::pin(async move {
+            if !this.interactive {
+                return Ok(decide_headless_trust(this.headless, &prompt)?);
             }
-        } else {
-            println!(
-                "Some tools are not yet trusted and need your approval.\
-                \nYou will be prompted for each tool individually, and \
-                any tool you do not trust will not be installed."
-            );
-            let ids_to_prompt_for = tool_specs
-                .iter()
-                .map(|spec| spec.id().clone())
-                .collect::<BTreeSet<_>>();
-
-            let mut newly_trusted_ids = Vec::new();
-            for id in ids_to_prompt_for {
-                if prompt_for_install_trust_inner(TrustPromptKind::InstallMany, &id)? {
-                    newly_trusted_ids.push(id);
-                }
+            let trusted =
+                spawn_blocking(move || prompt_for_trust_inner(this.kind, &prompt)).await??;
+            Ok(trusted)
+        })
+    }
+
+    fn confirm_trust_all(
+        &self,
+        prompts: Vec<TrustPrompt>,
+    ) -> BoxFuture<'_, Result<Vec<bool>, PromptError>> {
+        let this = *self;
+        Box::pin(async move {
+            if !this.interactive {
+                let answers = prompts
+                    .iter()
+                    .map(|prompt| decide_headless_trust(this.headless, prompt))
+                    .collect::<Result<Vec<_>>>()?;
+                return Ok(answers);
             }
+            let answers = spawn_blocking(move || prompt_for_trust_all_inner(&prompts)).await??;
+            Ok(answers)
+        })
+    }
 
-            let newly_trusted_specs = tool_specs
-                .into_iter()
-                .filter(|spec| newly_trusted_ids.contains(spec.id()))
-                .collect();
-            Ok(newly_trusted_specs)
-        }
-    })
-    .await?
+    fn pick_version(
+        &self,
+        prompt: VersionPrompt,
+    ) -> BoxFuture<'_, Result<Option<Version>, PromptError>> {
+        let this = *self;
+        Box::pin(async move {
+            if !this.interactive || prompt.versions.is_empty() {
+                return Ok(None);
+            }
+            let picked = spawn_blocking(move || {
+                let items = prompt.versions.iter().map(ToString::to_string);
+                dialoguer::Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt(format!("Pick a version of {}", prompt.id))
+                    .items(&items.collect::<Vec<_>>())
+                    .default(0)
+                    .interact_opt()
+                    .map(|index| index.map(|i| prompt.versions[i].clone()))
+            })
+            .await??;
+            Ok(picked)
+        })
+    }
+
+    fn wants_provenance(&self) -> bool {
+        self.interactive
+    }
 }
 
-fn prompt_for_install_trust_inner(kind: TrustPromptKind, tool_id: &ToolId) -> Result<bool> {
+fn prompt_for_trust_inner(kind: TrustPromptKind, prompt: &TrustPrompt) -> Result<bool> {
+    let tool_id = &prompt.id;
     let theme = ColorfulTheme {
         active_item_prefix: style("🔒 ".to_string()),
         prompt_style: Style::new(),
         ..Default::default()
     };
 
-    // If the terminal isn't interactive, tell the user that they
-    // need to open an interactive terminal to trust this tool.
-    if !stderr().is_terminal() {
-        bail!(
-            "The following tool has not been marked as trusted: {tool_id}\
-            \nRun `rokit add {tool_id}` to install and trust this tool.",
-        );
+    // Show what we know about the tool, to help the user make an informed decision
+    let bullet = style("•").dim();
+    if let Some(provenance) = &prompt.provenance {
+        for line in provenance_lines(provenance) {
+            println!("  {bullet} {line}");
+        }
+    }
+    if let Some(line) = lookalike_line(tool_id, &prompt.lookalikes) {
+        println!("  {bullet} {}", style(line).bold().yellow());
     }
 
     // Since the terminal is interactive, ask the user
@@ -91,3 +168,145 @@ fn prompt_for_install_trust_inner(kind: TrustPromptKind, tool_id: &ToolId) -> Re
 
     Ok(trusted)
 }
+
+/**
+    Asks the user which of several tools to trust using a single prompt,
+    after showing what we know about each of them.
+
+    No tools are selected by default, so that each tool must be explicitly
+    chosen - just like a single tool has no default answer to trust it.
+*/
+fn prompt_for_trust_all_inner(prompts: &[TrustPrompt]) -> Result<Vec<bool>> {
+    let theme = ColorfulTheme {
+        prompt_style: Style::new(),
+        ..Default::default()
+    };
+
+    let bullet = style("•").dim();
+    for prompt in prompts {
+        println!("{}", style(&prompt.id).bold());
+        if let Some(provenance) = &prompt.provenance {
+            for line in provenance_lines(provenance) {
+                println!("  {bullet} {line}");
+            }
+        }
+        if let Some(line) = lookalike_line(&prompt.id, &prompt.lookalikes) {
+            println!("  {bullet} {}", style(line).bold().yellow());
+        }
+    }
+
+    let items = prompts
+        .iter()
+        .map(|prompt| prompt.id.to_string())
+        .collect::<Vec<_>>();
+    let selected = dialoguer::MultiSelect::with_theme(&theme)
+        .with_prompt("Select the tools to trust (space to toggle, enter to confirm)")
+        .items(&items)
+        .interact_opt()?
+        .context("Exited without trusting tools")?;
+
+    Ok((0..prompts.len())
+        .map(|index| selected.contains(&index))
+        .collect())
+}
+
+/**
+    Decides if a tool should be trusted when the terminal is not
+    interactive, and we can not prompt the user for trust.
+
+    Any decision that is not a failure is logged, since
+    tools may end up being trusted without user approval.
+*/
+fn decide_headless_trust(headless: HeadlessTrust, prompt: &TrustPrompt) -> Result<bool> {
+    let tool_id = &prompt.id;
+    if let Some(line) = lookalike_line(tool_id, &prompt.lookalikes) {
+        tracing::warn!(%tool_id, "{line}");
+    }
+    match headless {
+        HeadlessTrust::Fail => bail!(
+            "The following tool has not been marked as trusted: {tool_id}\
+            \nRun `rokit add {tool_id}` to install and trust this tool.\
+            \nTo trust or skip untrusted tools in non-interactive environments, \
+            set {HEADLESS_TRUST_ENV_VAR} to 'trust' or 'skip'.",
+        ),
+        HeadlessTrust::Trust => {
+            tracing::warn!(
+                %tool_id,
+                policy = %headless,
+                "Trusting tool {tool_id} without approval, since Rokit can not prompt for trust"
+            );
+            Ok(true)
+        }
+        HeadlessTrust::Skip => {
+            tracing::warn!(
+                %tool_id,
+                policy = %headless,
+                "Skipping untrusted tool {tool_id}, since Rokit can not prompt for trust"
+            );
+            Ok(false)
+        }
+    }
+}
+
+/**
+    Creates a line warning that a tool has the same name as one or more
+    trusted tools from a different owner, which is a common sign of a
+    tool trying to impersonate another one - returns `None` if it does not.
+*/
+fn lookalike_line(tool_id: &ToolId, lookalikes: &[ToolId]) -> Option<String> {
+    if lookalikes.is_empty() {
+        return None;
+    }
+    let names = lookalikes
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!(
+        "{tool_id} has the same name as trusted {names}, but is from a different owner"
+    ))
+}
+
+fn provenance_lines(provenance: &ToolProvenance) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    let mut repo_parts = Vec::new();
+    if let Some(created_at) = provenance.created_at {
+        repo_parts.push(format!("created {}", format_age(created_at)));
+    }
+    if let Some(stars) = provenance.stars {
+        let plural = if stars == 1 { "" } else { "s" };
+        repo_parts.push(format!("{} star{plural}", style(stars).bold()));
+    }
+    if provenance.fork {
+        repo_parts.push(String::from("is a fork"));
+    }
+    if !repo_parts.is_empty() {
+        lines.push(format!("Repository {}", repo_parts.join(", ")));
+    }
+    if provenance.archived {
+        lines.push(
+            style("Repository is archived and no longer maintained")
+                .yellow()
+                .to_string(),
+        );
+    }
+
+    match provenance.latest_release_at {
+        Some(published_at) => lines.push(format!(
+            "Latest release published {}",
+            format_age(published_at)
+        )),
+        None if provenance.signed.is_none() => {
+            lines.push(style("Repository has no releases").yellow().to_string());
+        }
+        None => {}
+    }
+    match provenance.signed {
+        Some(true) => lines.push(style("Release artifacts are signed").green().to_string()),
+        Some(false) => lines.push(String::from("Release artifacts are not signed")),
+        None => {}
+    }
+
+    lines
+}
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use console::style;
+
+use rokit::sources::ReleaseNotes;
+
+use super::print_markdown;
+
+/**
+    Formats a bulleted, one-line summary for each of the given release notes.
+*/
+pub fn format_release_summaries(notes: &[ReleaseNotes]) -> Vec<String> {
+    let bullet = style("•").dim();
+    notes
+        .iter()
+        .map(|notes| {
+            format!(
+                "  {bullet} {} {}",
+                style(&notes.version).bold().yellow(),
+                style(notes.summary()).dim()
+            )
+        })
+        .collect()
+}
+
+/**
+    Prints the full changelog for each of the given release notes, as markdown.
+*/
+pub fn print_release_changelogs(name: &str, notes: &[ReleaseNotes]) -> Result<()> {
+    for notes in notes {
+        println!();
+        print_markdown(format!(
+            "# Changelog - {name} v{}\n{}",
+            notes.version, notes.changelog
+        ))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::{Context, Result};
+use semver::Version;
+use serde::Serialize;
+use tokio::fs::write;
+use url::Url;
+
+use rokit::{
+    session::{self, InstalledTool},
+    sources::ArtifactProvider,
+    tool::{ToolAlias, ToolId},
+};
+
+/**
+    A machine-readable summary of a single `rokit install` run.
+
+    Meant to be written to disk for CI artifacts and build provenance records.
+*/
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallReport {
+    pub rokit_version: &'static str,
+    pub elapsed_secs: f64,
+    pub tools: Vec<InstallReportTool>,
+}
+
+impl InstallReport {
+    pub fn new(elapsed: Duration, mut tools: Vec<InstallReportTool>) -> Self {
+        // NOTE: Tools are installed concurrently and finish in any
+        // order, sort them to keep reports stable between runs
+        tools.sort_by_key(|tool| tool.id.to_string());
+        Self {
+            rokit_version: env!("CARGO_PKG_VERSION"),
+            elapsed_secs: elapsed.as_secs_f64(),
+            tools,
+        }
+    }
+
+    pub async fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let contents = serde_json::to_string_pretty(self)?;
+        write(path, contents)
+            .await
+            .with_context(|| format!("Failed to write install report to {}", path.display()))
+    }
+}
+
+/**
+    The outcome of installing a single tool.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallReportStatus {
+    Installed,
+    AlreadyInstalled,
+}
+
+/**
+    Information about a single tool in an [`InstallReport`].
+*/
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallReportTool {
+    pub id: ToolId,
+    pub version: Version,
+    pub aliases: Vec<ToolAlias>,
+    pub status: InstallReportStatus,
+    pub source: Option<InstallReportSource>,
+    pub sha256: Option<String>,
+    pub timings: InstallTimings,
+}
+
+impl InstallReportTool {
+    pub fn from_installed(tool: &InstalledTool) -> Self {
+        let status = if tool.was_installed() {
+            InstallReportStatus::Installed
+        } else {
+            InstallReportStatus::AlreadyInstalled
+        };
+        Self {
+            id: tool.spec.id().clone(),
+            version: tool.spec.version().clone(),
+            aliases: tool.aliases.clone(),
+            status,
+            source: tool.artifact.as_ref().map(|artifact| InstallReportSource {
+                provider: artifact.provider,
+                artifact_name: artifact.name.clone(),
+                url: artifact.url.clone(),
+            }),
+            sha256: tool.sha256.clone(),
+            timings: tool.timings.into(),
+        }
+    }
+}
+
+/**
+    Time spent in each phase of installing a single tool.
+
+    Phases that were skipped, such as downloading and extracting
+    a tool that was already installed, will have a zero duration.
+*/
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct InstallTimings {
+    #[serde(rename = "resolve_secs", serialize_with = "serialize_secs")]
+    pub resolve: Duration,
+    #[serde(rename = "download_secs", serialize_with = "serialize_secs")]
+    pub download: Duration,
+    #[serde(rename = "extract_secs", serialize_with = "serialize_secs")]
+    pub extract: Duration,
+    #[serde(rename = "link_secs", serialize_with = "serialize_secs")]
+    pub link: Duration,
+}
+
+impl InstallTimings {
+    pub fn total(&self) -> Duration {
+        self.resolve + self.download + self.extract + self.link
+    }
+}
+
+impl From<session::InstallTimings> for InstallTimings {
+    fn from(timings: session::InstallTimings) -> Self {
+        Self {
+            resolve: timings.resolve,
+            download: timings.download,
+            extract: timings.extract,
+            link: timings.link,
+        }
+    }
+}
+
+/**
+    Where the artifact for a tool in an [`InstallReport`] was downloaded from.
+*/
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallReportSource {
+    #[serde(serialize_with = "serialize_provider")]
+    pub provider: ArtifactProvider,
+    pub artifact_name: Option<String>,
+    pub url: Option<Url>,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_provider<S: serde::Serializer>(
+    provider: &ArtifactProvider,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(provider.as_str())
+}
+
+fn serialize_secs<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
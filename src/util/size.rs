@@ -0,0 +1,51 @@
+const UNITS: [(&str, u64); 4] = [
+    ("GB", 1024 * 1024 * 1024),
+    ("MB", 1024 * 1024),
+    ("KB", 1024),
+    ("B", 1),
+];
+
+/**
+    Parses a human-readable size, such as `500MB`, `1.5GB`, or `1024`, into bytes.
+
+    Units are not case sensitive, and a number without a unit is in bytes.
+*/
+pub fn parse_byte_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let upper = s.to_ascii_uppercase();
+    let (number, multiplier) = UNITS
+        .iter()
+        .find_map(|(unit, multiplier)| {
+            let number = upper.strip_suffix(unit)?;
+            Some((number, *multiplier))
+        })
+        .unwrap_or((upper.as_str(), 1));
+
+    let number = number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+        .ok_or_else(|| format!("invalid size '{s}' - expected a size such as '500MB' or '1GB'"))?;
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    Ok((number * multiplier as f64) as u64)
+}
+
+/**
+    Formats a size in bytes in a short, human-readable way, such as `12.3 MB`.
+*/
+pub fn format_byte_size(bytes: u64) -> String {
+    for (unit, multiplier) in UNITS {
+        if bytes >= multiplier && multiplier > 1 {
+            #[allow(clippy::cast_precision_loss)]
+            let amount = bytes as f64 / multiplier as f64;
+            return format!("{amount:.1} {unit}");
+        }
+    }
+    format!("{bytes} B")
+}
@@ -3,6 +3,8 @@ use std::io::stderr;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::EnvFilter;
 
+use super::color::colors_enabled_stderr;
+
 #[cfg(debug_assertions)]
 const FMT_PRETTY: bool = true;
 
@@ -28,6 +30,7 @@ pub fn init(default_level_filter: LevelFilter) {
     tracing_subscriber::fmt()
         .with_env_filter(tracing_env_filter)
         .with_writer(stderr)
+        .with_ansi(colors_enabled_stderr())
         .with_target(FMT_PRETTY)
         .without_time()
         .init();
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/**
+    The format to print version information in, using `rokit --version`.
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VersionFormat {
+    /// The name and version of Rokit, such as `rokit 1.0.0`.
+    #[default]
+    Text,
+    /// All information about the build of Rokit, as JSON.
+    Json,
+}
+
+/**
+    Machine-readable information about the build of Rokit.
+
+    The fields of this struct are stable, and meant to be used by automation
+    to check which version of Rokit is installed, and what it supports.
+*/
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub commit: Option<&'static str>,
+    pub build_date: Option<&'static str>,
+    pub target: &'static str,
+    pub features: Vec<&'static str>,
+}
+
+impl VersionInfo {
+    pub fn current() -> Self {
+        let features = [
+            ("cli", cfg!(feature = "cli")),
+            ("runtime", cfg!(feature = "runtime")),
+            ("tokio", cfg!(feature = "tokio")),
+            ("reqwest-client", cfg!(feature = "reqwest-client")),
+            ("github", cfg!(feature = "github")),
+            ("gitlab", cfg!(feature = "gitlab")),
+            ("extract", cfg!(feature = "extract")),
+            ("zstd", cfg!(feature = "zstd")),
+            ("self-update", cfg!(feature = "self-update")),
+        ];
+        Self {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            commit: option_env!("ROKIT_BUILD_COMMIT"),
+            build_date: option_env!("ROKIT_BUILD_DATE"),
+            target: env!("ROKIT_BUILD_TARGET"),
+            features: features
+                .into_iter()
+                .filter_map(|(name, enabled)| enabled.then_some(name))
+                .collect(),
+        }
+    }
+
+    pub fn print(&self, format: VersionFormat) -> Result<()> {
+        match format {
+            VersionFormat::Text => println!("{} {}", self.name, self.version),
+            VersionFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+use rokit::warnings::RokitWarnings;
+
+/**
+    Displays all warnings in the given collection to the user.
+*/
+pub fn print_warnings(warnings: &RokitWarnings) {
+    for warning in warnings.all() {
+        tracing::warn!("{warning}");
+    }
+}